@@ -7,6 +7,7 @@ use crate::errors::AssertionError;
 use math::field::StarkField;
 use std::{
     cmp::{Ord, Ordering, PartialOrd},
+    collections::{btree_map::Entry, BTreeMap},
     fmt::{Display, Formatter},
 };
 
@@ -92,6 +93,89 @@ impl<B: StarkField> Assertion<B> {
         }
     }
 
+    // BATCH CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a set of assertions requiring that values in the execution trace are equal to the
+    /// values specified by the provided `(register, step, value)` entries.
+    ///
+    /// Entries can be provided in any order, and duplicate entries are ignored. Entries which
+    /// cover a register at all steps separated by the same stride are combined into periodic
+    /// assertions (if all values are the same) or sequence assertions (otherwise); the remaining
+    /// entries are converted into single assertions. The returned assertions are sorted in their
+    /// natural order, and do not overlap with each other.
+    ///
+    /// Returns an error if:
+    /// * `trace_length` is not a power of two;
+    /// * an entry refers to a register or a step outside of the execution trace;
+    /// * two entries assert different values for the same register and step.
+    pub fn from_table(
+        entries: &[(usize, usize, B)],
+        trace_width: usize,
+        trace_length: usize,
+    ) -> Result<Vec<Self>, AssertionError> {
+        if !trace_length.is_power_of_two() {
+            return Err(AssertionError::TraceLengthNotPowerOfTwo(trace_length));
+        }
+
+        // validate all entries and index their values by register and step
+        let mut registers = BTreeMap::<usize, BTreeMap<usize, B>>::new();
+        for &(register, step, value) in entries.iter() {
+            let assertion = Assertion::single(register, step, value);
+            assertion.validate_trace_width(trace_width)?;
+            assertion.validate_trace_length(trace_length)?;
+            match registers.entry(register).or_default().entry(step) {
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+                Entry::Occupied(entry) => {
+                    if *entry.get() != value {
+                        return Err(AssertionError::ConflictingValues(register, step));
+                    }
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        for (register, steps) in registers.into_iter() {
+            group_by_stride(register, steps, trace_length, &mut result);
+        }
+        result.sort();
+        Ok(result)
+    }
+
+    /// Returns a set of assertions requiring that values in the execution trace are equal to the
+    /// values specified by the provided columns, such that `values[i]` is asserted for register
+    /// `registers[i]` at step `steps[i]`.
+    ///
+    /// This is equivalent to calling [Assertion::from_table()] with entries assembled from the
+    /// columns.
+    ///
+    /// Returns an error if the columns have different lengths, or if any of the conditions
+    /// described for [Assertion::from_table()] is violated.
+    pub fn from_columns(
+        registers: &[usize],
+        steps: &[usize],
+        values: &[B],
+        trace_width: usize,
+        trace_length: usize,
+    ) -> Result<Vec<Self>, AssertionError> {
+        if registers.len() != steps.len() || registers.len() != values.len() {
+            return Err(AssertionError::ColumnLengthMismatch(
+                registers.len(),
+                steps.len(),
+                values.len(),
+            ));
+        }
+        let entries = registers
+            .iter()
+            .zip(steps.iter())
+            .zip(values.iter())
+            .map(|((&register, &step), &value)| (register, step, value))
+            .collect::<Vec<_>>();
+        Self::from_table(&entries, trace_width, trace_length)
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -139,36 +223,44 @@ impl<B: StarkField> Assertion<B> {
     /// Checks if this assertion overlaps with the provided assertion. Overlap is defined as
     /// asserting a value for the same step in the same register.
     pub fn overlaps_with(&self, other: &Assertion<B>) -> bool {
+        self.first_common_step(other).is_some()
+    }
+
+    /// Returns the first step at which both this assertion and the provided assertion assert a
+    /// value in the same register, or None if the assertions do not overlap.
+    ///
+    /// The step sets of the assertions are intersected exactly: a step is common to two
+    /// assertions with strides s1 and s2 and first steps f1 and f2 iff it is congruent to f1
+    /// modulo s1 and to f2 modulo s2; such steps exist iff f1 and f2 are congruent modulo
+    /// gcd(s1, s2). Periodic and sequence assertions are treated as repeating indefinitely; this
+    /// is exact for any trace length against which both assertions are valid.
+    pub fn first_common_step(&self, other: &Assertion<B>) -> Option<usize> {
         if self.register != other.register {
-            return false;
+            return None;
         }
-        if self.first_step == other.first_step {
-            return true;
-        }
-        if self.stride == other.stride {
-            return false;
-        }
-
-        // at this point we know that assertions are for the same register but they start
-        // on different steps and also have different strides
 
-        if self.first_step < other.first_step {
-            if self.is_single() {
-                return false;
+        match (self.is_single(), other.is_single()) {
+            (true, true) => {
+                if self.first_step == other.first_step {
+                    Some(self.first_step)
+                } else {
+                    None
+                }
             }
-            if other.is_single() || self.stride < other.stride {
-                (other.first_step - self.first_step) % self.stride == 0
-            } else {
-                false
-            }
-        } else {
-            if other.is_single() {
-                return false;
-            }
-            if self.is_single() || other.stride < self.stride {
-                (self.first_step - other.first_step) % other.stride == 0
-            } else {
-                false
+            (true, false) => other.find_step(self.first_step),
+            (false, true) => self.find_step(other.first_step),
+            (false, false) => {
+                let (f1, s1) = (self.first_step, self.stride);
+                let (f2, s2) = (other.first_step, other.stride);
+                let divisor = gcd(s1, s2);
+                if f1 % divisor != f2 % divisor {
+                    return None;
+                }
+                // the steps repeat with the period of lcm(s1, s2); thus, the first common step
+                // is among the first s2 / gcd(s1, s2) steps of this assertion
+                (0..(s2 / divisor))
+                    .map(|i| f1 + s1 * i)
+                    .find(|&step| other.find_step(step).is_some())
             }
         }
     }
@@ -264,6 +356,28 @@ impl<B: StarkField> Assertion<B> {
             self.values.len()
         }
     }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the `step` if this assertion asserts a value at this step, or None otherwise.
+    /// Periodic and sequence assertions are assumed to repeat indefinitely.
+    fn find_step(&self, step: usize) -> Option<usize> {
+        if step < self.first_step {
+            return None;
+        }
+        let offset = step - self.first_step;
+        let is_asserted = if self.is_single() {
+            offset == 0
+        } else {
+            offset % self.stride == 0
+        };
+        if is_asserted {
+            Some(step)
+        } else {
+            None
+        }
+    }
 }
 
 // OTHER TRAIT IMPLEMENTATIONS
@@ -312,6 +426,60 @@ impl<B: StarkField> Display for Assertion<B> {
 // HELPER FUNCTIONS
 // =================================================================================================
 
+/// Returns the greatest common divisor of `a` and `b`.
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Converts values asserted for a single register into assertions, and appends them to `result`.
+///
+/// Strides are tried from the smallest to the largest; for every stride, each offset at which
+/// the register is asserted at all steps of the trace separated by the stride yields a periodic
+/// or a sequence assertion. Values which do not form such groups yield single assertions.
+fn group_by_stride<B: StarkField>(
+    register: usize,
+    mut steps: BTreeMap<usize, B>,
+    trace_length: usize,
+    result: &mut Vec<Assertion<B>>,
+) {
+    let mut stride = MIN_STRIDE_LENGTH;
+    while stride < trace_length {
+        let num_values = trace_length / stride;
+        if steps.len() < num_values {
+            stride *= 2;
+            continue;
+        }
+
+        // count the asserted steps for every offset within the stride
+        let mut counts = BTreeMap::<usize, usize>::new();
+        for &step in steps.keys() {
+            *counts.entry(step % stride).or_default() += 1;
+        }
+
+        for (&first_step, _) in counts.iter().filter(|(_, &count)| count == num_values) {
+            let values = (0..num_values)
+                .map(|i| steps.remove(&(first_step + stride * i)).unwrap())
+                .collect::<Vec<_>>();
+            if values.iter().all(|&value| value == values[0]) {
+                result.push(Assertion::periodic(register, first_step, stride, values[0]));
+            } else {
+                result.push(Assertion::sequence(register, first_step, stride, values));
+            }
+        }
+
+        stride *= 2;
+    }
+
+    for (step, value) in steps.into_iter() {
+        result.push(Assertion::single(register, step, value));
+    }
+}
+
 fn validate_stride(stride: usize, first_step: usize, register: usize) {
     assert!(
         stride.is_power_of_two(),
@@ -361,3 +361,143 @@ fn assertion_overlap() {
     assert!(!a.overlaps_with(&b));
     assert!(!b.overlaps_with(&a));
 }
+
+#[test]
+fn assertion_first_common_step() {
+    let values = vec![BaseElement::ONE, BaseElement::ZERO];
+
+    // periodic assertions with different strides: steps 5, 13, 21, ... and 1, 5, 9, ...
+    let a = Assertion::periodic(0, 5, 8, BaseElement::ONE);
+    let b = Assertion::periodic(0, 1, 4, BaseElement::ONE);
+    assert_eq!(Some(5), a.first_common_step(&b));
+    assert_eq!(Some(5), b.first_common_step(&a));
+
+    // periodic and sequence assertions: steps 6, 14, 22, ... and 2, 18
+    let a = Assertion::periodic(0, 6, 8, BaseElement::ONE);
+    let b = Assertion::sequence(0, 2, 16, values.clone());
+    assert_eq!(None, a.first_common_step(&b));
+    assert_eq!(None, b.first_common_step(&a));
+
+    // sequence assertions with different strides: steps 3, 7, 11, 15 and 11, 27
+    let a = Assertion::sequence(0, 3, 4, vec![BaseElement::ONE; 4]);
+    let b = Assertion::sequence(0, 11, 16, values.clone());
+    assert_eq!(Some(11), a.first_common_step(&b));
+    assert_eq!(Some(11), b.first_common_step(&a));
+
+    // single and sequence assertions
+    let a = Assertion::sequence(0, 1, 8, values);
+    let b = Assertion::single(0, 9, BaseElement::ONE);
+    assert_eq!(Some(9), a.first_common_step(&b));
+    assert_eq!(Some(9), b.first_common_step(&a));
+    let b = Assertion::single(0, 5, BaseElement::ONE);
+    assert_eq!(None, a.first_common_step(&b));
+
+    // single and periodic assertions
+    let a = Assertion::periodic(0, 3, 4, BaseElement::ONE);
+    let b = Assertion::single(0, 27, BaseElement::ONE);
+    assert_eq!(Some(27), a.first_common_step(&b));
+    assert_eq!(Some(27), b.first_common_step(&a));
+
+    // different registers never overlap
+    let b = Assertion::periodic(1, 3, 4, BaseElement::ONE);
+    assert_eq!(None, a.first_common_step(&b));
+}
+
+// BATCH CONSTRUCTION
+// ================================================================================================
+
+#[test]
+fn assertions_from_table() {
+    let one = BaseElement::ONE;
+    let two = BaseElement::new(2);
+
+    // register 0 is asserted at every 4th step, register 1 is asserted at every other step
+    // starting with step 1, and register 2 is asserted at steps 3 and 5
+    let mut entries = Vec::new();
+    for step in (0..16).step_by(4) {
+        entries.push((0, step, one));
+    }
+    for (i, step) in (1..16).step_by(2).enumerate() {
+        entries.push((1, step, BaseElement::new(i as u128)));
+    }
+    entries.push((2, 5, two));
+    entries.push((2, 3, one));
+
+    // duplicate entries are ignored, and the order of entries does not matter
+    entries.push((0, 8, one));
+    entries.reverse();
+
+    let expected = vec![
+        Assertion::single(2, 3, one),
+        Assertion::single(2, 5, two),
+        Assertion::sequence(1, 1, 2, (0..8).map(BaseElement::new).collect()),
+        Assertion::periodic(0, 0, 4, one),
+    ];
+    let result = Assertion::from_table(&entries, 3, 16).unwrap();
+    assert_eq!(expected, result);
+
+    // the same assertions can be built from columns
+    let registers = entries.iter().map(|e| e.0).collect::<Vec<_>>();
+    let steps = entries.iter().map(|e| e.1).collect::<Vec<_>>();
+    let values = entries.iter().map(|e| e.2).collect::<Vec<_>>();
+    let result = Assertion::from_columns(&registers, &steps, &values, 3, 16).unwrap();
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn assertions_from_table_partial_strides() {
+    let one = BaseElement::ONE;
+
+    // steps 0, 2, 4, 6 do not cover the whole trace, and thus, are asserted individually; steps
+    // 1, 9 form a sequence with stride 8 once steps 3, 7, 11, 15 are grouped with stride 4
+    let mut entries = vec![(0, 0, one), (0, 2, one), (0, 4, one), (0, 6, one)];
+    for &step in [3, 7, 11, 15].iter() {
+        entries.push((0, step, one));
+    }
+    entries.push((0, 1, one));
+    entries.push((0, 9, BaseElement::ZERO));
+
+    let result = Assertion::from_table(&entries, 1, 16).unwrap();
+    let expected = vec![
+        Assertion::single(0, 0, one),
+        Assertion::single(0, 2, one),
+        Assertion::single(0, 4, one),
+        Assertion::single(0, 6, one),
+        Assertion::periodic(0, 3, 4, one),
+        Assertion::sequence(0, 1, 8, vec![one, BaseElement::ZERO]),
+    ];
+    assert_eq!(expected, result);
+
+    // none of the assertions overlap
+    for (i, a) in result.iter().enumerate() {
+        for b in result.iter().skip(i + 1) {
+            assert!(!a.overlaps_with(b));
+        }
+    }
+}
+
+#[test]
+fn assertions_from_table_errors() {
+    let one = BaseElement::ONE;
+
+    assert_eq!(
+        Err(AssertionError::TraceLengthNotPowerOfTwo(12)),
+        Assertion::from_table(&[(0, 0, one)], 1, 12)
+    );
+    assert_eq!(
+        Err(AssertionError::TraceWidthTooShort(2, 2)),
+        Assertion::from_table(&[(0, 0, one), (2, 0, one)], 2, 16)
+    );
+    assert_eq!(
+        Err(AssertionError::TraceLengthTooShort(32, 16)),
+        Assertion::from_table(&[(0, 16, one)], 1, 16)
+    );
+    assert_eq!(
+        Err(AssertionError::ConflictingValues(1, 3)),
+        Assertion::from_table(&[(1, 3, one), (1, 3, BaseElement::ZERO)], 2, 16)
+    );
+    assert_eq!(
+        Err(AssertionError::ColumnLengthMismatch(2, 1, 2)),
+        Assertion::from_columns(&[0, 1], &[0], &[one, one], 2, 16)
+    );
+}
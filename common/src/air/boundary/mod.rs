@@ -4,13 +4,13 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{Assertion, ConstraintDivisor};
-use crypto::RandomElementGenerator;
+use crate::ComputationContext;
 use math::{
     fft,
     field::{FieldElement, StarkField},
     polynom,
 };
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[cfg(test)]
 mod tests;
@@ -56,6 +56,13 @@ where
         }
     }
 
+    /// Creates a new boundary constraint group with the specified divisor in the computation
+    /// described by the `context`.
+    pub fn from_context(divisor: ConstraintDivisor<B>, context: &ComputationContext) -> Self {
+        let trace_poly_degree = context.trace_length() - 1;
+        Self::new(divisor, trace_poly_degree, context.composition_degree())
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -74,7 +81,8 @@ where
         self.degree_adjustment
     }
 
-    // Returns degree of the largest constraint polynomial in this group.
+    /// Returns degree of the largest constraint polynomial in this group; for an empty group,
+    /// this is zero.
     pub fn max_poly_degree(&self) -> usize {
         let mut poly_size = 0;
         for constraint in self.constraints.iter() {
@@ -82,25 +90,26 @@ where
                 poly_size = constraint.poly().len();
             }
         }
-        poly_size - 1
+        poly_size.saturating_sub(1)
     }
 
     // PUBLIC METHODS
     // --------------------------------------------------------------------------------------------
 
-    /// Creates a new boundary constraint from the specified assertion and adds it to the group.
-    pub fn add<R: RandomElementGenerator>(
+    /// Creates a new boundary constraint from the specified assertion and composition
+    /// coefficients, and adds it to the group.
+    pub fn add(
         &mut self,
         assertion: Assertion<B>,
         inv_g: B,
-        twiddle_map: &mut HashMap<usize, Vec<B>>,
-        coeff_prng: &mut R,
+        twiddle_map: &mut BTreeMap<usize, Vec<B>>,
+        coefficients: (E, E),
     ) {
         self.constraints.push(BoundaryConstraint::new(
             assertion,
             inv_g,
             twiddle_map,
-            coeff_prng,
+            coefficients,
         ));
     }
 
@@ -139,12 +148,13 @@ where
 {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Creates a new boundary constraint from the specified assertion.
-    pub fn new<R: RandomElementGenerator>(
+    /// Creates a new boundary constraint from the specified assertion and composition
+    /// coefficients.
+    pub fn new(
         assertion: Assertion<B>,
         inv_g: B,
-        twiddle_map: &mut HashMap<usize, Vec<B>>,
-        coeff_prng: &mut R,
+        twiddle_map: &mut BTreeMap<usize, Vec<B>>,
+        coefficients: (E, E),
     ) -> Self {
         // build a polynomial which evaluates to constraint values at asserted steps; for
         // single-value assertions we use the value as constant coefficient of degree 0
@@ -174,7 +184,7 @@ where
             register: assertion.register,
             poly,
             poly_offset,
-            cc: coeff_prng.draw_pair(),
+            cc: coefficients,
         }
     }
 
@@ -4,16 +4,16 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    super::tests::{build_prng, build_sequence_poly},
-    Assertion, BoundaryConstraint,
+    super::tests::{build_context, build_prng, build_sequence_poly},
+    Assertion, BoundaryConstraint, BoundaryConstraintGroup, ConstraintDivisor,
 };
 use crypto::{hash, DefaultRandomElementGenerator, RandomElementGenerator};
 use math::{
     field::{f128::BaseElement, FieldElement, StarkField},
     polynom,
-    utils::log2,
+    utils::log2_unchecked,
 };
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 // BOUNDARY CONSTRAINT TESTS
 // ================================================================================================
@@ -30,7 +30,7 @@ fn boundary_constraint_from_single_assertion() {
         assertion,
         inv_g,
         &mut twiddle_map,
-        &mut prng,
+        prng.draw_pair(),
     );
     assert_eq!(0, constraint.register());
     assert_eq!(vec![value], constraint.poly());
@@ -51,7 +51,7 @@ fn boundary_constraint_from_single_assertion() {
         assertion,
         inv_g,
         &mut twiddle_map,
-        &mut prng,
+        prng.draw_pair(),
     );
     assert_eq!(1, constraint.register());
     assert_eq!(vec![value], constraint.poly());
@@ -81,7 +81,7 @@ fn boundary_constraint_from_periodic_assertion() {
         assertion,
         inv_g,
         &mut twiddle_map,
-        &mut prng,
+        prng.draw_pair(),
     );
     assert_eq!(0, constraint.register());
     assert_eq!(vec![value], constraint.poly());
@@ -102,7 +102,7 @@ fn boundary_constraint_from_periodic_assertion() {
         assertion,
         inv_g,
         &mut twiddle_map,
-        &mut prng,
+        prng.draw_pair(),
     );
     assert_eq!(2, constraint.register());
     assert_eq!(vec![value], constraint.poly());
@@ -133,7 +133,7 @@ fn boundary_constraint_from_sequence_assertion() {
         assertion,
         inv_g,
         &mut twiddle_map,
-        &mut prng,
+        prng.draw_pair(),
     );
     assert_eq!(0, constraint.register());
     assert_eq!(constraint_poly, constraint.poly());
@@ -158,7 +158,7 @@ fn boundary_constraint_from_sequence_assertion() {
         assertion,
         inv_g,
         &mut twiddle_map,
-        &mut prng,
+        prng.draw_pair(),
     );
     assert_eq!(0, constraint.register());
     assert_eq!(constraint_poly, constraint.poly());
@@ -176,17 +176,53 @@ fn boundary_constraint_from_sequence_assertion() {
     );
 }
 
+// BOUNDARY CONSTRAINT GROUP TESTS
+// ================================================================================================
+
+#[test]
+fn boundary_constraint_group_from_context() {
+    let trace_length = 16;
+    let context = build_context(trace_length, 2);
+    let assertion = Assertion::single(1, 3, BaseElement::new(5));
+    let divisor = ConstraintDivisor::from_assertion(&assertion, &context);
+
+    // a group built from the context must adjust constraints to the composition degree
+    let mut group = BoundaryConstraintGroup::<BaseElement, BaseElement>::from_context(
+        divisor.clone(),
+        &context,
+    );
+    let expected = BoundaryConstraintGroup::<BaseElement, BaseElement>::new(
+        divisor,
+        trace_length - 1,
+        context.composition_degree(),
+    );
+    assert_eq!(expected.degree_adjustment(), group.degree_adjustment());
+    assert_eq!(
+        context.composition_degree(),
+        trace_length - 1 + group.degree_adjustment() as usize - group.divisor().degree()
+    );
+    assert_eq!(0, group.max_poly_degree());
+
+    let (inv_g, mut twiddle_map, mut prng) = build_constraint_params(trace_length);
+    let cc = prng.draw_pair();
+    group.add(assertion, inv_g, &mut twiddle_map, cc);
+    assert_eq!(1, group.constraints().len());
+    assert_eq!(&cc, group.constraints()[0].cc());
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 fn build_constraint_params(
     trace_length: usize,
 ) -> (
     BaseElement,
-    HashMap<usize, Vec<BaseElement>>,
+    BTreeMap<usize, Vec<BaseElement>>,
     DefaultRandomElementGenerator<hash::Blake3_256>,
 ) {
-    let inv_g = BaseElement::get_root_of_unity(log2(trace_length)).inv();
+    let inv_g = BaseElement::get_root_of_unity(log2_unchecked(trace_length))
+        .unwrap()
+        .inv();
     let prng = build_prng();
-    let twiddle_map = HashMap::<usize, Vec<BaseElement>>::new();
+    let twiddle_map = BTreeMap::<usize, Vec<BaseElement>>::new();
     (inv_g, twiddle_map, prng)
 }
@@ -14,7 +14,7 @@ use std::fmt::{Display, Formatter};
 /// For example (x^a - 1) * (x^b - 2) / (x - 3) can be represented as:
 ///   numerator: vec![(a, 1), (b, 2)]
 ///   exclude: vec![3]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ConstraintDivisor<B: StarkField> {
     pub(super) numerator: Vec<(usize, B)>,
     pub(super) exclude: Vec<B>,
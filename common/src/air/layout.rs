@@ -0,0 +1,105 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ConstraintDivisor, TransitionConstraintDegree, TransitionConstraintGroup};
+use crate::ComputationContext;
+use math::field::{FieldElement, StarkField};
+use std::collections::BTreeMap;
+
+// CONSTRAINT LAYOUT
+// ================================================================================================
+
+/// Constraint data derived from the context of a computation which depends neither on public
+/// inputs nor on composition coefficients.
+///
+/// This includes evaluation degrees of transition constraints, the assignment of transition
+/// constraints to groups together with the degree adjustment of each group, and the divisor of
+/// transition constraints. A layout is built on the first call to
+/// [ComputationContext::constraint_layout()] and is cached in the context afterwards. Boundary
+/// constraints are not part of the layout because they are built from assertions, which may
+/// depend on public inputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstraintLayout<B: StarkField> {
+    evaluation_degrees: Vec<usize>,
+    transition_groups: Vec<(TransitionConstraintDegree, u32)>,
+    group_indexes: Vec<usize>,
+    transition_divisor: ConstraintDivisor<B>,
+}
+
+impl<B: StarkField> ConstraintLayout<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a layout of constraints for the computation described by the `context`; groups
+    /// of transition constraints are sorted by evaluation degree in ascending order.
+    pub fn new(context: &ComputationContext) -> Self {
+        let trace_length = context.trace_length();
+        let evaluation_degrees = context
+            .transition_constraint_degrees()
+            .iter()
+            .map(|degree| degree.get_evaluation_degree(trace_length))
+            .collect::<Vec<_>>();
+
+        // assign each constraint to the group for its evaluation degree
+        let mut groups = BTreeMap::new();
+        for (degree, &evaluation_degree) in context
+            .transition_constraint_degrees()
+            .iter()
+            .zip(evaluation_degrees.iter())
+        {
+            groups.entry(evaluation_degree).or_insert_with(|| {
+                let group = TransitionConstraintGroup::<B>::from_context(degree.clone(), context);
+                (degree.clone(), group.degree_adjustment())
+            });
+        }
+        let group_indexes = evaluation_degrees
+            .iter()
+            .map(|degree| groups.range(..degree).count())
+            .collect();
+
+        ConstraintLayout {
+            evaluation_degrees,
+            transition_groups: groups.into_values().collect(),
+            group_indexes,
+            transition_divisor: ConstraintDivisor::from_transition(context),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns evaluation degrees of transition constraints in the order in which constraints
+    /// are listed in the context.
+    pub fn evaluation_degrees(&self) -> &[usize] {
+        &self.evaluation_degrees
+    }
+
+    /// Returns the number of transition constraint groups.
+    pub fn num_transition_groups(&self) -> usize {
+        self.transition_groups.len()
+    }
+
+    /// Returns the index of the group of each transition constraint in the order in which
+    /// constraints are listed in the context.
+    pub fn group_indexes(&self) -> &[usize] {
+        &self.group_indexes
+    }
+
+    /// Returns the divisor shared by all transition constraints.
+    pub fn transition_divisor(&self) -> &ConstraintDivisor<B> {
+        &self.transition_divisor
+    }
+
+    // PUBLIC METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns empty transition constraint groups with degrees and degree adjustments defined by
+    /// this layout.
+    pub fn new_transition_groups<E: FieldElement>(&self) -> Vec<TransitionConstraintGroup<E>> {
+        self.transition_groups
+            .iter()
+            .map(|(degree, adjustment)| TransitionConstraintGroup::new(degree.clone(), *adjustment))
+            .collect()
+    }
+}
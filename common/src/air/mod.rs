@@ -3,13 +3,15 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{ComputationContext, ProofOptions};
+use crate::{random::ConstraintCoefficients, ComputationContext, ProofOptions};
 use crypto::RandomElementGenerator;
 use math::{
     fft,
     field::{FieldElement, StarkField},
+    polynom,
 };
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
+use utils::Serializable;
 
 mod assertions;
 pub use assertions::Assertion;
@@ -23,6 +25,12 @@ pub use transition::{EvaluationFrame, TransitionConstraintDegree, TransitionCons
 mod divisor;
 pub use divisor::ConstraintDivisor;
 
+mod layout;
+pub use layout::ConstraintLayout;
+
+mod periodic;
+pub use periodic::PeriodicColumn;
+
 #[cfg(test)]
 mod tests;
 
@@ -44,7 +52,9 @@ pub struct TraceInfo {
 
 pub trait Air: Send + Sync {
     type BaseElement: StarkField;
-    type PublicInputs;
+    /// Public inputs of the computation; these must be serializable so that they can be sent to
+    /// the verifier together with a proof.
+    type PublicInputs: Serializable;
 
     // REQUIRED METHODS
     // --------------------------------------------------------------------------------------------
@@ -82,51 +92,96 @@ pub trait Air: Send + Sync {
     /// empty vector. For computations which rely on periodic columns this method should be
     /// overridden in the specialized implementation. Number of values for each periodic column
     /// must be a power of two.
+    ///
+    /// For columns with very long cycles, consider overriding get_periodic_columns() instead.
     fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
         Vec::new()
     }
 
-    /// Returns polynomial for all periodic columns. These polynomials are interpolated from
-    /// the values returned from the get_periodic_column_values() method.
-    fn get_periodic_column_polys(&self) -> Vec<Vec<Self::BaseElement>> {
-        // cache inverse twiddles for each cycle length so that we don't have to re-build them
-        // for columns with identical cycle lengths
-        let mut twiddle_map = HashMap::new();
-        // iterate over all periodic columns and convert column values into polynomials
+    /// Returns descriptions of all periodic columns used during computation. Unlike
+    /// get_periodic_column_values(), this allows values of a column to be supplied by a generator
+    /// function evaluated at each position of the cycle, and thus, the values do not need to be
+    /// held in memory. The default implementation of this method wraps the values returned from
+    /// the get_periodic_column_values() method. Cycle length of each column must be a power of
+    /// two.
+    fn get_periodic_columns(&self) -> Vec<PeriodicColumn<'_, Self::BaseElement>> {
         self.get_periodic_column_values()
             .into_iter()
-            .map(|mut column| {
-                let cycle_length = column.len();
-                assert!(
-                    cycle_length >= MIN_CYCLE_LENGTH,
-                    "number of values in a periodic column must be at least {}, but was {}",
-                    MIN_CYCLE_LENGTH,
-                    cycle_length
-                );
-                assert!(
-                    cycle_length.is_power_of_two(),
-                    "number of values in a periodic column must be a power of two, but was {}",
-                    cycle_length
-                );
-                assert!(cycle_length <= self.trace_length(),
-                    "number of values in a periodic column cannot exceed trace length {}, but was {}",
-                    self.trace_length(),
-                    cycle_length
-                );
-
-                // get twiddles for interpolation and interpolate values into a polynomial
-                let inv_twiddles = twiddle_map
-                    .entry(cycle_length)
-                    .or_insert_with(|| fft::get_inv_twiddles::<Self::BaseElement>(cycle_length));
-                fft::interpolate_poly(&mut column, &inv_twiddles);
-                column
-            })
+            .map(PeriodicColumn::from_values)
             .collect()
     }
 
+    /// Returns indexes of trace registers for which the prover must commit to a blinded version
+    /// of the register's values, in addition to committing to the full execution trace. Roots
+    /// of these commitments are included in the proof; the designated registers are still
+    /// committed to, opened, and evaluated out-of-domain as part of the execution trace, and the
+    /// proof does not attest that the additional commitments match the values of the registers.
+    /// The default implementation of this method returns an empty vector.
+    fn get_blinded_registers(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Returns the physical position of each trace register in rows of the trace commitment:
+    /// the i-th element of the returned vector is the column at which values of register i are
+    /// placed when rows of the extended execution trace are hashed and opened. This allows
+    /// optimizing the layout of committed rows without changing register indexes used in
+    /// assertions and transition constraints, which always refer to logical registers.
+    ///
+    /// The returned vector must be a permutation of register indexes of the main trace segment;
+    /// registers of the auxiliary segment are always committed to in the order of their indexes.
+    /// The default implementation of this method returns the identity mapping.
+    fn get_column_layout(&self) -> Vec<usize> {
+        (0..self.context().main_trace_width()).collect()
+    }
+
+    /// Sets random elements used to build the auxiliary segment of the execution trace.
+    ///
+    /// For computations which declare an auxiliary segment via
+    /// [with_aux_segment()](ComputationContext::with_aux_segment), both the prover and the
+    /// verifier call this method after the main segment of the trace has been committed to, and
+    /// before any constraints are built or evaluated; thus, transition constraints and assertions
+    /// may depend on these elements. The number of assertions must not depend on them. The
+    /// default implementation of this method ignores the elements.
+    fn set_aux_rand_elements(&mut self, _elements: &[Self::BaseElement]) {}
+
+    /// Returns polynomial for all periodic columns. These polynomials are interpolated from
+    /// the values of the columns returned from the get_periodic_columns() method.
+    ///
+    /// The polynomials are interpolated on the first call to this method and are cached in the
+    /// context of the computation; subsequent calls return the cached polynomials.
+    fn get_periodic_column_polys(&self) -> &[Vec<Self::BaseElement>] {
+        self.context().get_or_init_periodic_column_polys(|| {
+            build_periodic_column_polys(self.get_periodic_columns(), self.trace_length())
+        })
+    }
+
+    /// Evaluates all periodic columns at the specified point `x`, and saves the results into
+    /// `result`; values of the columns appear in `result` in the same order in which the
+    /// columns are returned from the get_periodic_columns() method.
+    ///
+    /// For a column with cycle length `c`, its polynomial is evaluated at x^(n / c), where n is
+    /// the length of the execution trace.
+    fn evaluate_periodic_columns_at<E>(&self, x: E, result: &mut Vec<E>)
+    where
+        E: FieldElement + From<Self::BaseElement>,
+    {
+        result.clear();
+        result.extend(self.get_periodic_column_polys().iter().map(|poly| {
+            let num_cycles = self.trace_length() / poly.len();
+            let x = x.exp((num_cycles as u32).into());
+            polynom::eval(poly, x)
+        }));
+    }
+
     /// Groups transition constraints together by their degree, and also assigns coefficients
     /// to each constraint. These coefficients will be used to compute random linear combination
     /// of transition constraints during constraint merging.
+    ///
+    /// Coefficients are drawn in the order in which constraints are listed in the context, and
+    /// the returned groups are sorted by evaluation degree in ascending order. Thus, the prover
+    /// and the verifier always end up with identical groups and coefficients. If coefficients
+    /// are drawn using [CoefficientScheme::Powers](crate::CoefficientScheme::Powers), α for each
+    /// group is drawn when the first constraint of the group is encountered.
     fn get_transition_constraints<E, R>(
         &self,
         mut coeff_prng: R,
@@ -135,33 +190,30 @@ pub trait Air: Send + Sync {
         E: FieldElement + From<Self::BaseElement>,
         R: RandomElementGenerator,
     {
-        let context = self.context();
-        // We want to make sure that once we divide constraint polynomials by the divisor,
-        // the degree of the resulting polynomial will be exactly equal to the composition_degree.
-        // For transition constraints, divisor degree = deg(trace). So, target degree for all
-        // transitions constraints is simply: deg(composition) + deg(trace)
-        let target_degree = context.composition_degree() + self.trace_poly_degree();
-
-        // iterate over all transition constraint degrees, and assign each constraint to the
-        // appropriate group based on degree
-        let mut groups = HashMap::new();
-        for (i, degree) in context.transition_constraint_degrees().iter().enumerate() {
-            let evaluation_degree = degree.get_evaluation_degree(self.trace_length());
-            let degree_adjustment = (target_degree - evaluation_degree) as u32;
-            let group = groups.entry(evaluation_degree).or_insert_with(|| {
-                TransitionConstraintGroup::new(degree.clone(), degree_adjustment)
-            });
-            group.add(i, coeff_prng.draw_pair());
+        // assignment of constraints to groups is taken from the layout cached in the context;
+        // groups in the layout are already ordered by evaluation degree
+        let layout = self.context().constraint_layout::<Self::BaseElement>();
+        let scheme = self.context().options().coefficient_scheme();
+        let mut groups = layout.new_transition_groups();
+        let mut coefficients = Vec::with_capacity(groups.len());
+        coefficients.resize_with(groups.len(), || None);
+        for (i, &group_idx) in layout.group_indexes().iter().enumerate() {
+            let group_coefficients = coefficients[group_idx]
+                .get_or_insert_with(|| ConstraintCoefficients::new(scheme, &mut coeff_prng));
+            groups[group_idx].add(i, group_coefficients.draw_pair(&mut coeff_prng));
         }
 
-        // convert from hash map into a vector and return
-        groups.into_iter().map(|e| e.1).collect()
+        groups
     }
 
     /// Convert assertions returned from get_assertions() method into boundary constraints,
     /// assign coefficients to each constraint, and group the constraints by denominator. The
     /// coefficients will be used to compute random linear combination of boundary constraints
     /// during constraint merging.
+    ///
+    /// The returned groups are sorted by degree adjustment; groups with the same degree
+    /// adjustment are sorted by stride and then by first step of their assertions. This order
+    /// does not depend on the order in which assertions are returned from get_assertions().
     fn get_boundary_constraints<E, R>(
         &self,
         mut coeff_prng: R,
@@ -172,7 +224,7 @@ pub trait Air: Send + Sync {
     {
         // group assertions by step - i.e.: assertions for the first step are grouped together,
         // assertions for the last step are grouped together etc.
-        let mut groups = HashMap::new();
+        let mut groups = BTreeMap::new();
 
         // compute inverse of the trace domain generator; this will be used for offset
         // computations when creating sequence constraints
@@ -183,7 +235,9 @@ pub trait Air: Send + Sync {
 
         // cache inverse twiddles for multi-value assertions in this map so that we don't have
         // to re-build them for assertions with identical strides
-        let mut twiddle_map = HashMap::new();
+        let mut twiddle_map = BTreeMap::new();
+
+        let scheme = self.context().options().coefficient_scheme();
 
         // get the assertions for this computation and make sure that they are all valid in
         // the context of this computation; also, sort the assertions in the deterministic order
@@ -195,20 +249,27 @@ pub trait Air: Send + Sync {
         // in ascending order
         for assertion in assertions.into_iter() {
             let key = (assertion.stride(), assertion.first_step());
-            let group = groups.entry(key).or_insert_with(|| {
-                BoundaryConstraintGroup::new(
-                    ConstraintDivisor::from_assertion(&assertion, self.context()),
-                    self.trace_poly_degree(),
-                    self.context().composition_degree(),
+            let (group, coefficients) = groups.entry(key).or_insert_with(|| {
+                (
+                    BoundaryConstraintGroup::from_context(
+                        ConstraintDivisor::from_assertion(&assertion, self.context()),
+                        self.context(),
+                    ),
+                    ConstraintCoefficients::new(scheme, &mut coeff_prng),
                 )
             });
 
             // add a new assertion constraint to the current group (last group in the list)
-            group.add(assertion, inv_g, &mut twiddle_map, &mut coeff_prng);
+            let cc = coefficients.draw_pair(&mut coeff_prng);
+            group.add(assertion, inv_g, &mut twiddle_map, cc);
         }
 
-        // make sure groups are sorted by adjustment degree
-        let mut groups = groups.into_iter().map(|e| e.1).collect::<Vec<_>>();
+        // make sure groups are sorted by adjustment degree; the sort is stable, and thus, groups
+        // with the same adjustment degree retain their (stride, first_step) order
+        let mut groups = groups
+            .into_iter()
+            .map(|(_, (group, _))| group)
+            .collect::<Vec<_>>();
         groups.sort_by_key(|c| c.degree_adjustment());
 
         groups
@@ -278,11 +339,61 @@ pub trait Air: Send + Sync {
     fn num_transition_constraints(&self) -> usize {
         self.context().transition_constraint_degrees().len()
     }
+
+    /// Returns the total number of transition and boundary constraints for an instance of the
+    /// computation described by this AIR.
+    fn num_constraints(&self) -> usize {
+        self.num_transition_constraints() + self.get_assertions().len()
+    }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Interpolates values of periodic columns into polynomials; the cycle length of each column
+/// must be a power of two greater than one, and cannot exceed trace length. Values of columns
+/// supplied by generator functions are computed one column at a time.
+fn build_periodic_column_polys<B: StarkField>(
+    columns: Vec<PeriodicColumn<B>>,
+    trace_length: usize,
+) -> Vec<Vec<B>> {
+    // cache inverse twiddles for each cycle length so that we don't have to re-build them
+    // for columns with identical cycle lengths
+    let mut twiddle_map = BTreeMap::new();
+    // iterate over all periodic columns and convert column values into polynomials
+    columns
+        .into_iter()
+        .map(|column| {
+            let cycle_length = column.cycle_length();
+            assert!(
+                cycle_length >= MIN_CYCLE_LENGTH,
+                "number of values in a periodic column must be at least {}, but was {}",
+                MIN_CYCLE_LENGTH,
+                cycle_length
+            );
+            assert!(
+                cycle_length.is_power_of_two(),
+                "number of values in a periodic column must be a power of two, but was {}",
+                cycle_length
+            );
+            assert!(
+                cycle_length <= trace_length,
+                "number of values in a periodic column cannot exceed trace length {}, but was {}",
+                trace_length,
+                cycle_length
+            );
+
+            // get twiddles for interpolation and interpolate values into a polynomial
+            let mut column = column.into_values();
+            let inv_twiddles = twiddle_map
+                .entry(cycle_length)
+                .or_insert_with(|| fft::get_inv_twiddles::<B>(cycle_length));
+            fft::interpolate_poly(&mut column, inv_twiddles);
+            column
+        })
+        .collect()
+}
+
 /// Makes sure the assertions are valid in the context of this computation and don't overlap with
 /// each other - i.e. no two assertions are placed against the same register and step combination.
 fn prepare_assertions<B: StarkField>(
@@ -306,12 +417,12 @@ fn prepare_assertions<B: StarkField>(
                 panic!("assertion {} is invalid: {}", assertion, err);
             });
         for a in result.iter().filter(|a| a.register == assertion.register) {
-            assert!(
-                !a.overlaps_with(&assertion),
-                "assertion {} overlaps with assertion {}",
-                assertion,
-                a
-            );
+            if let Some(step) = a.first_common_step(&assertion) {
+                panic!(
+                    "assertion {} overlaps with assertion {} at step {}",
+                    assertion, a, step
+                );
+            }
         }
 
         result.insert(assertion);
@@ -0,0 +1,127 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crypto::Hasher;
+use math::field::StarkField;
+use utils::uninit_vector;
+
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of values hashed together when computing a digest of a periodic column.
+const DIGEST_CHUNK_SIZE: usize = 64;
+
+/// Number of values computed together when a generator column is converted into a vector.
+const GENERATOR_CHUNK_SIZE: usize = 1024;
+
+// PERIODIC COLUMN
+// ================================================================================================
+
+/// Describes a column of values which repeat with a period of `cycle_length` steps.
+///
+/// Values of a column can be either listed explicitly, or supplied by a generator function
+/// which computes the value at a given position of the cycle. The latter allows AIRs with very
+/// long cycles (e.g., round schedules spanning 2^16 steps) to compute values on demand, rather
+/// than keeping all of them in memory.
+pub struct PeriodicColumn<'a, B: StarkField> {
+    cycle_length: usize,
+    source: ColumnSource<'a, B>,
+}
+
+enum ColumnSource<'a, B: StarkField> {
+    Values(Vec<B>),
+    Generator(Box<dyn Fn(usize) -> B + Send + Sync + 'a>),
+}
+
+impl<'a, B: StarkField> PeriodicColumn<'a, B> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+    /// Returns a column with the specified values; the length of the cycle is the number of
+    /// values.
+    pub fn from_values(values: Vec<B>) -> Self {
+        PeriodicColumn {
+            cycle_length: values.len(),
+            source: ColumnSource::Values(values),
+        }
+    }
+
+    /// Returns a column with the specified cycle length whose values are computed by the
+    /// `generator` function; the function is invoked with positions in the range
+    /// [0, `cycle_length`).
+    pub fn from_fn<F>(cycle_length: usize, generator: F) -> Self
+    where
+        F: Fn(usize) -> B + Send + Sync + 'a,
+    {
+        PeriodicColumn {
+            cycle_length,
+            source: ColumnSource::Generator(Box::new(generator)),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of steps after which values of this column repeat.
+    pub fn cycle_length(&self) -> usize {
+        self.cycle_length
+    }
+
+    /// Returns the value of this column at the specified step of the execution trace.
+    pub fn get(&self, step: usize) -> B {
+        let position = step % self.cycle_length;
+        match &self.source {
+            ColumnSource::Values(values) => values[position],
+            ColumnSource::Generator(generator) => generator(position),
+        }
+    }
+
+    /// Returns a digest of the values of this column for a single cycle; the digest commits to
+    /// the cycle length as well.
+    ///
+    /// Values are hashed in chunks, and thus, values supplied by a generator function are
+    /// computed without materializing the entire cycle in memory.
+    pub fn digest<H: Hasher>(&self) -> H::Digest {
+        let mut result = H::hash_elements(&[B::from(self.cycle_length as u64)]);
+        let mut chunk = [B::ZERO; DIGEST_CHUNK_SIZE];
+        for chunk_start in (0..self.cycle_length).step_by(DIGEST_CHUNK_SIZE) {
+            let chunk_size = DIGEST_CHUNK_SIZE.min(self.cycle_length - chunk_start);
+            self.read_values_into(chunk_start, &mut chunk[..chunk_size]);
+            result = H::merge(&[result, H::hash_elements(&chunk[..chunk_size])]);
+        }
+        result
+    }
+
+    /// Copies values of this column at positions [`start`, `start` + `target.len()`) into
+    /// `target`; positions wrap around the cycle.
+    pub fn read_values_into(&self, start: usize, target: &mut [B]) {
+        for (i, value) in target.iter_mut().enumerate() {
+            *value = self.get(start + i);
+        }
+    }
+
+    /// Returns all values of this column for a single cycle.
+    ///
+    /// If the values are supplied by a generator function, they are computed chunk by chunk
+    /// directly into the returned vector (in multiple threads when `concurrent` feature is
+    /// enabled); the vector can then be interpolated in place.
+    pub fn into_values(self) -> Vec<B> {
+        if let ColumnSource::Values(values) = self.source {
+            return values;
+        }
+
+        let mut result = uninit_vector(self.cycle_length);
+        #[cfg(not(feature = "concurrent"))]
+        let chunks = result.chunks_mut(GENERATOR_CHUNK_SIZE);
+        #[cfg(feature = "concurrent")]
+        let chunks = result.par_chunks_mut(GENERATOR_CHUNK_SIZE);
+        chunks
+            .enumerate()
+            .for_each(|(i, chunk)| self.read_values_into(i * GENERATOR_CHUNK_SIZE, chunk));
+        result
+    }
+}
@@ -4,15 +4,16 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    Air, Assertion, BoundaryConstraintGroup, ComputationContext, EvaluationFrame, ProofOptions,
-    TraceInfo, TransitionConstraintDegree,
+    Air, Assertion, BoundaryConstraintGroup, ComputationContext, ConstraintDivisor,
+    EvaluationFrame, PeriodicColumn, ProofOptions, TraceInfo, TransitionConstraintDegree,
+    TransitionConstraintGroup,
 };
-use crate::{FieldExtension, HashFunction};
+use crate::{CoefficientScheme, FieldExtension, HashFunction};
 use crypto::{hash, DefaultRandomElementGenerator, RandomElementGenerator};
 use math::{
     field::{f128::BaseElement, FieldElement, StarkField},
     polynom,
-    utils::{get_power_series, log2},
+    utils::{get_power_series_unchecked, log2_unchecked},
 };
 use rand::{seq::SliceRandom, thread_rng};
 use std::collections::HashMap;
@@ -41,6 +42,97 @@ fn get_periodic_column_polys() {
     assert_eq!(build_periodic_column_poly(&col2), column_polys[1]);
 }
 
+#[test]
+fn get_periodic_column_polys_cached() {
+    let col1 = vec![BaseElement::ONE, BaseElement::ZERO];
+    let air = MockAir::with_periodic_columns(vec![col1], 16);
+
+    // polynomials should be interpolated only once, and returned from the cache afterwards
+    let column_polys1 = air.get_periodic_column_polys();
+    let column_polys2 = air.get_periodic_column_polys();
+    assert!(core::ptr::eq(column_polys1, column_polys2));
+}
+
+#[test]
+fn evaluate_periodic_columns_at() {
+    let trace_length = 16;
+    let col1 = vec![BaseElement::new(3), BaseElement::new(5)];
+    let col2 = BaseElement::prng_vector([1; 32], 8);
+    let air = MockAir::with_periodic_columns(vec![col1.clone(), col2.clone()], trace_length);
+
+    // at the points of the trace domain, the columns should evaluate to the column values
+    let g = BaseElement::get_root_of_unity(log2_unchecked(trace_length)).unwrap();
+    let mut values = Vec::new();
+    for step in 0..trace_length {
+        air.evaluate_periodic_columns_at(g.exp((step as u64).into()), &mut values);
+        assert_eq!(vec![col1[step % 2], col2[step % 8]], values);
+    }
+}
+
+#[test]
+fn get_periodic_column_polys_lazy() {
+    let trace_length = 16;
+    let col1 = vec![BaseElement::new(3), BaseElement::new(5)];
+    let col2 = BaseElement::prng_vector([1; 32], 8);
+    let columns = vec![col1.clone(), col2.clone()];
+
+    // polynomials of columns supplied by generator functions are the same as polynomials of
+    // columns with explicit values
+    let air = MockAir::with_lazy_periodic_columns(columns.clone(), trace_length);
+    let column_polys = air.get_periodic_column_polys();
+    assert_eq!(2, column_polys.len());
+    assert_eq!(build_periodic_column_poly(&col1), column_polys[0]);
+    assert_eq!(build_periodic_column_poly(&col2), column_polys[1]);
+
+    let eager_air = MockAir::with_periodic_columns(columns, trace_length);
+    assert_eq!(eager_air.get_periodic_column_polys(), column_polys);
+}
+
+#[test]
+fn periodic_column_values() {
+    let values = BaseElement::prng_vector([1; 32], 4);
+    let column = PeriodicColumn::from_values(values.clone());
+    let lazy_column = PeriodicColumn::from_fn(4, |i| values[i]);
+    assert_eq!(4, column.cycle_length());
+    assert_eq!(4, lazy_column.cycle_length());
+
+    // values repeat every cycle_length steps
+    for step in 0..16 {
+        assert_eq!(values[step % 4], column.get(step));
+        assert_eq!(values[step % 4], lazy_column.get(step));
+    }
+    assert_eq!(values, column.into_values());
+    assert_eq!(values, lazy_column.into_values());
+}
+
+#[test]
+fn periodic_column_digest() {
+    // digests of columns spanning several chunks do not depend on how values are supplied
+    let values = BaseElement::prng_vector([1; 32], 256);
+    let column = PeriodicColumn::from_values(values.clone());
+    let lazy_column = PeriodicColumn::from_fn(256, |i| values[i]);
+    let digest = column.digest::<hash::Blake3_256>();
+    assert_eq!(digest, lazy_column.digest::<hash::Blake3_256>());
+
+    // changing a single value or the cycle length changes the digest
+    let lazy_column =
+        PeriodicColumn::from_fn(256, |i| values[i] + BaseElement::from((i == 200) as u8));
+    assert_ne!(digest, lazy_column.digest::<hash::Blake3_256>());
+    let short_column = PeriodicColumn::from_values(values[..128].to_vec());
+    assert_ne!(digest, short_column.digest::<hash::Blake3_256>());
+}
+
+#[test]
+#[should_panic(
+    expected = "number of values in a periodic column must be a power of two, but was 3"
+)]
+fn get_periodic_column_polys_lazy_not_power_of_two() {
+    let col1 = vec![BaseElement::ONE, BaseElement::ZERO, BaseElement::ONE];
+    let air = MockAir::with_lazy_periodic_columns(vec![col1], 16);
+    let column_polys = air.get_periodic_column_polys();
+    assert_eq!(0, column_polys.len());
+}
+
 #[test]
 #[should_panic(expected = "number of values in a periodic column must be at least 2, but was 1")]
 fn get_periodic_column_polys_num_values_too_small() {
@@ -64,7 +156,181 @@ fn get_periodic_column_polys_num_values_not_power_of_two() {
 // TRANSITION CONSTRAINTS
 // ================================================================================================
 
-// TODO
+#[test]
+fn get_transition_constraints() {
+    let trace_length = 16;
+    let degrees = vec![
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(1),
+        TransitionConstraintDegree::new(2),
+    ];
+    let air = MockAir::with_transition_constraint_degrees(degrees, trace_length);
+
+    // coefficients are drawn in the order in which constraints are listed
+    let mut prng = build_prng();
+    let expected_cc = (0..5)
+        .map(|_| prng.draw_pair::<BaseElement>())
+        .collect::<Vec<_>>();
+
+    // groups must be sorted by evaluation degree in ascending order
+    let groups = air.get_transition_constraints::<BaseElement, _>(build_prng());
+    let expected_degrees = vec![
+        trace_length - 1,
+        2 * (trace_length - 1),
+        3 * (trace_length - 1),
+    ];
+    let group_degrees = groups
+        .iter()
+        .map(|g| g.degree().get_evaluation_degree(trace_length))
+        .collect::<Vec<_>>();
+    assert_eq!(expected_degrees, group_degrees);
+
+    // each constraint must end up in the group of its degree, with the expected coefficients;
+    // at x = 1 the degree adjustment factor is 1, and thus, merging a unit evaluation of a
+    // single constraint yields the sum of its coefficients
+    let expected_groups = [2, 1, 2, 0, 1];
+    for (i, &expected_group) in expected_groups.iter().enumerate() {
+        let mut evaluations = vec![BaseElement::ZERO; 5];
+        evaluations[i] = BaseElement::ONE;
+        for (j, group) in groups.iter().enumerate() {
+            let result = group.merge_evaluations(&evaluations, BaseElement::ONE);
+            if j == expected_group {
+                assert_eq!(expected_cc[i].0 + expected_cc[i].1, result);
+            } else {
+                assert_eq!(BaseElement::ZERO, result);
+            }
+        }
+    }
+}
+
+#[test]
+fn constraint_layout() {
+    let trace_length = 16;
+    let degrees = vec![
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(1),
+        TransitionConstraintDegree::new(2),
+    ];
+    let air = MockAir::with_transition_constraint_degrees(degrees, trace_length);
+    let layout = air.context().constraint_layout::<BaseElement>();
+    assert_eq!(&[45, 30, 45, 15, 30], layout.evaluation_degrees());
+    assert_eq!(3, layout.num_transition_groups());
+    assert_eq!(&[2, 1, 2, 0, 1], layout.group_indexes());
+    assert_eq!(
+        &ConstraintDivisor::from_transition(air.context()),
+        layout.transition_divisor()
+    );
+
+    // the layout is cached in the context, and can be placed into the context of another
+    // instance of the same computation
+    let layout = air
+        .context()
+        .cached_constraint_layout::<BaseElement>()
+        .unwrap();
+    let other_air = MockAir::with_transition_constraint_degrees(
+        vec![TransitionConstraintDegree::new(1)],
+        trace_length,
+    );
+    assert!(other_air.context().set_constraint_layout(layout.clone()));
+    assert!(!other_air.context().set_constraint_layout(layout));
+    assert_eq!(
+        3,
+        other_air
+            .context()
+            .constraint_layout::<BaseElement>()
+            .num_transition_groups()
+    );
+}
+
+#[test]
+fn get_transition_constraints_with_coefficient_powers() {
+    let trace_length = 16;
+    let degrees = vec![
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(1),
+        TransitionConstraintDegree::new(2),
+    ];
+    let mut air = MockAir::with_transition_constraint_degrees(degrees.clone(), trace_length);
+    let options = air
+        .context
+        .options()
+        .clone()
+        .with_coefficient_scheme(CoefficientScheme::Powers);
+    air.context = ComputationContext::new(4, trace_length, degrees, options);
+
+    // a single element is drawn per group, in the order in which groups are first encountered;
+    // constraints within a group get consecutive pairs of powers of this element
+    let mut prng = build_prng();
+    let alphas = (0..3)
+        .map(|_| prng.draw::<BaseElement>())
+        .collect::<Vec<_>>();
+    let pair = |alpha: BaseElement, k: u128| (alpha.exp(2 * k + 1), alpha.exp(2 * k + 2));
+    let expected_cc = [
+        pair(alphas[0], 0),
+        pair(alphas[1], 0),
+        pair(alphas[0], 1),
+        pair(alphas[2], 0),
+        pair(alphas[1], 1),
+    ];
+
+    let groups = air.get_transition_constraints::<BaseElement, _>(build_prng());
+    let expected_groups = [2, 1, 2, 0, 1];
+    for (i, &expected_group) in expected_groups.iter().enumerate() {
+        let mut evaluations = vec![BaseElement::ZERO; 5];
+        evaluations[i] = BaseElement::ONE;
+        let result = groups[expected_group].merge_evaluations(&evaluations, BaseElement::ONE);
+        assert_eq!(expected_cc[i].0 + expected_cc[i].1, result);
+    }
+}
+
+#[test]
+fn transition_constraint_groups_in_isolation() {
+    let trace_length = 16;
+    let degrees = vec![
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(1),
+    ];
+    let air = MockAir::with_transition_constraint_degrees(degrees.clone(), trace_length);
+    let context = air.context();
+
+    // groups built directly from the context must be the same as groups built by the AIR
+    let mut prng = build_prng();
+    let mut expected = degrees
+        .iter()
+        .enumerate()
+        .map(|(i, degree)| {
+            let mut group = TransitionConstraintGroup::from_context(degree.clone(), context);
+            group.add(i, prng.draw_pair::<BaseElement>());
+            group
+        })
+        .collect::<Vec<_>>();
+    expected.reverse();
+
+    let groups = air.get_transition_constraints::<BaseElement, _>(build_prng());
+    assert_eq!(expected.len(), groups.len());
+    for (expected, group) in expected.iter().zip(groups.iter()) {
+        assert_eq!(expected.indexes(), group.indexes());
+        assert_eq!(expected.coefficients(), group.coefficients());
+        assert_eq!(expected.degree_adjustment(), group.degree_adjustment());
+    }
+
+    // once divided by the transition divisor, adjusted constraints have composition degree
+    let divisor = ConstraintDivisor::<BaseElement>::from_transition(context);
+    for group in groups.iter() {
+        let adjusted_degree =
+            group.degree().get_evaluation_degree(trace_length) + group.degree_adjustment() as usize;
+        assert_eq!(
+            context.composition_degree(),
+            adjusted_degree - divisor.degree()
+        );
+    }
+}
 
 // BOUNDARY CONSTRAINTS
 // ================================================================================================
@@ -93,7 +359,7 @@ fn get_boundary_constraints() {
     let trace_length = 16;
     let air = MockAir::with_assertions(assertions, trace_length);
     let no_poly_offset = (0, BaseElement::ONE);
-    let g = BaseElement::get_root_of_unity(log2(trace_length)); // trace domain generator
+    let g = BaseElement::get_root_of_unity(log2_unchecked(trace_length)).unwrap(); // trace domain generator
 
     // build coefficients for random liner combination; these will be derived for assertions
     // sorted first by stride, then by first step, and finally by register (similar to the order)
@@ -109,22 +375,11 @@ fn get_boundary_constraints() {
     expected_cc.insert(4, prng.draw_pair());
     expected_cc.insert(5, prng.draw_pair());
 
-    // get boundary constraints from AIR, and sort constraint groups so that the order
-    // is stable; the original order is just by degree_adjustment
+    // get boundary constraints from AIR; groups are expected to be sorted by degree adjustment
+    // first, and then by stride and first step of their assertions
     let prng = build_prng();
-    let mut groups: Vec<BoundaryConstraintGroup<BaseElement, BaseElement>> =
+    let groups: Vec<BoundaryConstraintGroup<BaseElement, BaseElement>> =
         air.get_boundary_constraints(prng);
-    groups.sort_by(|g1, g2| {
-        if g1.degree_adjustment() == g2.degree_adjustment() {
-            let n1 = &g1.divisor().numerator()[0].1;
-            let n2 = &g2.divisor().numerator()[0].1;
-            n1.as_int().partial_cmp(&n2.as_int()).unwrap()
-        } else {
-            g1.degree_adjustment()
-                .partial_cmp(&g2.degree_adjustment())
-                .unwrap()
-        }
-    });
     assert_eq!(5, groups.len());
 
     // group 0
@@ -218,6 +473,45 @@ fn get_boundary_constraints() {
     assert_eq!(expected_cc[&7], constraint.cc().clone());
 }
 
+#[test]
+fn get_boundary_constraints_order_independent() {
+    let values = vec![BaseElement::new(1), BaseElement::new(2)];
+    let mut assertions = vec![
+        Assertion::single(0, 0, BaseElement::new(3)),
+        Assertion::single(1, 14, BaseElement::new(5)),
+        Assertion::single(2, 7, BaseElement::new(9)),
+        Assertion::sequence(0, 1, 8, values.clone()),
+        Assertion::sequence(3, 2, 8, values.clone()),
+        Assertion::periodic(1, 3, 4, BaseElement::new(7)),
+        Assertion::periodic(2, 1, 4, BaseElement::new(11)),
+    ];
+    let trace_length = 16;
+
+    let air = MockAir::with_assertions(assertions.clone(), trace_length);
+    let expected: Vec<BoundaryConstraintGroup<BaseElement, BaseElement>> =
+        air.get_boundary_constraints(build_prng());
+
+    // shuffling assertions must not change constraint groups or their coefficients
+    for _ in 0..10 {
+        assertions.shuffle(&mut thread_rng());
+        let air = MockAir::with_assertions(assertions.clone(), trace_length);
+        let groups: Vec<BoundaryConstraintGroup<BaseElement, BaseElement>> =
+            air.get_boundary_constraints(build_prng());
+
+        assert_eq!(expected.len(), groups.len());
+        for (g1, g2) in expected.iter().zip(groups.iter()) {
+            assert_eq!(g1.degree_adjustment(), g2.degree_adjustment());
+            assert_eq!(g1.divisor().numerator(), g2.divisor().numerator());
+            assert_eq!(g1.constraints().len(), g2.constraints().len());
+            for (c1, c2) in g1.constraints().iter().zip(g2.constraints().iter()) {
+                assert_eq!(c1.register(), c2.register());
+                assert_eq!(c1.poly(), c2.poly());
+                assert_eq!(c1.cc(), c2.cc());
+            }
+        }
+    }
+}
+
 // PREPARE ASSERTIONS
 // ================================================================================================
 
@@ -264,7 +558,7 @@ fn prepare_assertions() {
 
 #[test]
 #[should_panic(
-    expected = "assertion (register=0, steps=[1, 9, ...], value=7) overlaps with assertion (register=0, step=9, value=5)"
+    expected = "assertion (register=0, steps=[1, 9, ...], value=7) overlaps with assertion (register=0, step=9, value=5) at step 9"
 )]
 fn prepare_assertions_with_overlap() {
     let assertions = vec![
@@ -302,6 +596,7 @@ struct MockAir {
     context: ComputationContext,
     assertions: Vec<Assertion<BaseElement>>,
     periodic_columns: Vec<Vec<BaseElement>>,
+    lazy_periodic_columns: bool,
 }
 
 impl MockAir {
@@ -321,6 +616,17 @@ impl MockAir {
         result
     }
 
+    /// Same as [MockAir::with_periodic_columns()], but values of the columns are supplied by
+    /// generator functions.
+    pub fn with_lazy_periodic_columns(
+        column_values: Vec<Vec<BaseElement>>,
+        trace_length: usize,
+    ) -> Self {
+        let mut result = Self::with_periodic_columns(column_values, trace_length);
+        result.lazy_periodic_columns = true;
+        result
+    }
+
     pub fn with_assertions(assertions: Vec<Assertion<BaseElement>>, trace_length: usize) -> Self {
         let mut result = Self::new(
             TraceInfo {
@@ -333,6 +639,23 @@ impl MockAir {
         result.assertions = assertions;
         result
     }
+
+    pub fn with_transition_constraint_degrees(
+        degrees: Vec<TransitionConstraintDegree>,
+        trace_length: usize,
+    ) -> Self {
+        let options = ProofOptions::new(32, 8, 0, HashFunction::Blake3_256, FieldExtension::None);
+        let mut result = Self::new(
+            TraceInfo {
+                length: trace_length,
+                meta: Vec::new(),
+            },
+            (),
+            options.clone(),
+        );
+        result.context = ComputationContext::new(4, trace_length, degrees, options);
+        result
+    }
 }
 
 impl Air for MockAir {
@@ -345,6 +668,7 @@ impl Air for MockAir {
             context,
             assertions: Vec::new(),
             periodic_columns: Vec::new(),
+            lazy_periodic_columns: false,
         }
     }
 
@@ -356,6 +680,20 @@ impl Air for MockAir {
         self.periodic_columns.clone()
     }
 
+    fn get_periodic_columns(&self) -> Vec<PeriodicColumn<'_, Self::BaseElement>> {
+        if !self.lazy_periodic_columns {
+            return self
+                .get_periodic_column_values()
+                .into_iter()
+                .map(PeriodicColumn::from_values)
+                .collect();
+        }
+        self.periodic_columns
+            .iter()
+            .map(|column| PeriodicColumn::from_fn(column.len(), move |i| column[i]))
+            .collect()
+    }
+
     fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
         self.assertions.clone()
     }
@@ -385,14 +723,14 @@ pub fn build_prng() -> DefaultRandomElementGenerator<hash::Blake3_256> {
 pub fn build_sequence_poly(values: &[BaseElement], trace_length: usize) -> Vec<BaseElement> {
     let cycle_length = trace_length / values.len();
     let domain_size = trace_length / cycle_length;
-    let g = BaseElement::get_root_of_unity(log2(domain_size));
-    let xs = get_power_series(g, domain_size);
+    let g = BaseElement::get_root_of_unity(log2_unchecked(domain_size)).unwrap();
+    let xs = get_power_series_unchecked(g, domain_size);
     polynom::interpolate(&xs, values, false)
 }
 
 pub fn build_periodic_column_poly(values: &[BaseElement]) -> Vec<BaseElement> {
     let domain_size = values.len();
-    let g = BaseElement::get_root_of_unity(log2(domain_size));
-    let xs = get_power_series(g, domain_size);
+    let g = BaseElement::get_root_of_unity(log2_unchecked(domain_size)).unwrap();
+    let xs = get_power_series_unchecked(g, domain_size);
     polynom::interpolate(&xs, values, false)
 }
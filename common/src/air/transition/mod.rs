@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::ComputationContext;
 use core::cmp;
 use math::field::FieldElement;
 
@@ -11,9 +12,16 @@ use math::field::FieldElement;
 
 const MIN_BLOWUP_FACTOR: usize = 2;
 
+#[cfg(test)]
+mod tests;
+
 // TRANSITION CONSTRAINT GROUP
 // ================================================================================================
 
+/// A group of transition constraints all having the same degree.
+///
+/// All transition constraints share the same divisor, which can be built via
+/// [ConstraintDivisor::from_transition()](super::ConstraintDivisor::from_transition).
 #[derive(Clone, Debug)]
 pub struct TransitionConstraintGroup<E: FieldElement> {
     degree: TransitionConstraintDegree,
@@ -33,11 +41,41 @@ impl<E: FieldElement> TransitionConstraintGroup<E> {
         }
     }
 
+    /// Creates a new transition constraint group to hold constraints of the specified degree in
+    /// the computation described by the `context`. The degree adjustment is chosen so that,
+    /// once divided by the transition divisor, adjusted constraint polynomials have degree equal
+    /// to the composition degree of the computation.
+    pub fn from_context(degree: TransitionConstraintDegree, context: &ComputationContext) -> Self {
+        // divisor degree for transition constraints = deg(trace); so, the target degree for all
+        // transition constraints is simply: deg(composition) + deg(trace)
+        let trace_length = context.trace_length();
+        let target_degree = context.composition_degree() + trace_length - 1;
+        let evaluation_degree = degree.get_evaluation_degree(trace_length);
+        let degree_adjustment = (target_degree - evaluation_degree) as u32;
+        Self::new(degree, degree_adjustment)
+    }
+
     /// Returns degree descriptor for all constraints in this constraint group.
     pub fn degree(&self) -> &TransitionConstraintDegree {
         &self.degree
     }
 
+    /// Returns a degree adjustment factor for all constraints in this group.
+    pub fn degree_adjustment(&self) -> u32 {
+        self.degree_adjustment
+    }
+
+    /// Returns indexes of constraints in this group in the order in which they were added.
+    pub fn indexes(&self) -> &[usize] {
+        &self.indexes
+    }
+
+    /// Returns composition coefficients for constraints in this group; coefficients are listed
+    /// in the same order as constraint indexes.
+    pub fn coefficients(&self) -> &[(E, E)] {
+        &self.coefficients
+    }
+
     /// Computes a linear combination of evaluations relevant to this constraint group.
     pub fn merge_evaluations<B>(&self, evaluations: &[B], x: B) -> E
     where
@@ -73,7 +111,13 @@ impl<E: FieldElement> TransitionConstraintGroup<E> {
 /// registers, and a periodic register with a period of 32 steps can be represented as:
 ///   base: 2
 ///   cycles: [32]
-#[derive(Clone, Debug)]
+///
+/// Selector flags defined via periodic columns (e.g., a column which is set to one on the last
+/// step of every 8-step cycle) are described by their cycle lengths in the same way. A periodic
+/// column with cycle length `c` contributes only (n / c) * (c - 1) to the degree of a constraint
+/// over a trace of length n, rather than n - 1 contributed by a trace register; this is taken
+/// into account when computing evaluation degree and minimum blowup factor for a constraint.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TransitionConstraintDegree {
     base: usize,
     cycles: Vec<usize>,
@@ -127,13 +171,18 @@ impl TransitionConstraintDegree {
         result
     }
 
-    /// Returns a minimum blowup factor needed to evaluate constraint of this degree. Is
-    /// guaranteed to be a power of two, greater than one.
-    pub fn min_blowup_factor(&self) -> usize {
-        cmp::max(
-            (self.base + self.cycles.len()).next_power_of_two(),
-            MIN_BLOWUP_FACTOR,
-        )
+    /// Returns a minimum blowup factor needed to evaluate constraint of this degree in the
+    /// context of execution trace of the specified length. Is guaranteed to be a power of two,
+    /// greater than one.
+    ///
+    /// The blowup factor is chosen so that the evaluation degree of the constraint is smaller
+    /// than the size of the constraint evaluation domain. Because periodic columns contribute
+    /// less than trace registers to the evaluation degree, this may be smaller than the blowup
+    /// factor implied by treating every periodic column as a trace register.
+    pub fn min_blowup_factor(&self, trace_length: usize) -> usize {
+        let evaluation_degree = self.get_evaluation_degree(trace_length);
+        let blowup_factor = (evaluation_degree + trace_length) / trace_length;
+        cmp::max(blowup_factor.next_power_of_two(), MIN_BLOWUP_FACTOR)
     }
 }
 
@@ -146,10 +195,28 @@ pub struct EvaluationFrame<E: FieldElement> {
 }
 
 impl<E: FieldElement> EvaluationFrame<E> {
+    /// Returns a new evaluation frame for an execution trace with the specified number of
+    /// registers; all values in the frame are initialized to zeros.
     pub fn new(num_registers: usize) -> Self {
         EvaluationFrame {
             current: E::zeroed_vector(num_registers),
             next: E::zeroed_vector(num_registers),
         }
     }
+
+    /// Returns the number of registers in this frame.
+    pub fn width(&self) -> usize {
+        self.current.len()
+    }
+
+    /// Prepares this frame to hold states of an execution trace with the specified number of
+    /// registers.
+    ///
+    /// Memory is re-allocated only if the frame does not have enough capacity, and existing
+    /// values are not zeroed out; thus, the caller is expected to overwrite both states before
+    /// the frame is read.
+    pub fn reuse(&mut self, num_registers: usize) {
+        self.current.resize(num_registers, E::ZERO);
+        self.next.resize(num_registers, E::ZERO);
+    }
 }
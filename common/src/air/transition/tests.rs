@@ -0,0 +1,94 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{EvaluationFrame, TransitionConstraintDegree};
+use math::field::{f128::BaseElement, FieldElement};
+
+// TRANSITION CONSTRAINT DEGREE
+// ================================================================================================
+
+#[test]
+fn get_evaluation_degree() {
+    let trace_length = 64;
+
+    let degree = TransitionConstraintDegree::new(2);
+    assert_eq!(2 * 63, degree.get_evaluation_degree(trace_length));
+
+    // periodic column with cycle length 8 contributes (64 / 8) * 7 = 56
+    let degree = TransitionConstraintDegree::with_cycles(2, vec![8]);
+    assert_eq!(2 * 63 + 56, degree.get_evaluation_degree(trace_length));
+
+    // two selector flags with cycle length 2 contribute (64 / 2) * 1 = 32 each
+    let degree = TransitionConstraintDegree::with_cycles(1, vec![2, 2]);
+    assert_eq!(63 + 2 * 32, degree.get_evaluation_degree(trace_length));
+
+    // a column which spans the entire trace contributes as much as a trace register
+    let degree = TransitionConstraintDegree::with_cycles(1, vec![64]);
+    assert_eq!(2 * 63, degree.get_evaluation_degree(trace_length));
+}
+
+#[test]
+fn min_blowup_factor() {
+    let trace_length = 64;
+
+    assert_eq!(
+        2,
+        TransitionConstraintDegree::new(1).min_blowup_factor(trace_length)
+    );
+    assert_eq!(
+        2,
+        TransitionConstraintDegree::new(2).min_blowup_factor(trace_length)
+    );
+    assert_eq!(
+        4,
+        TransitionConstraintDegree::new(3).min_blowup_factor(trace_length)
+    );
+    assert_eq!(
+        8,
+        TransitionConstraintDegree::new(5).min_blowup_factor(trace_length)
+    );
+
+    // degree 63 + 64 = 127 fits into a domain of size 128
+    let degree = TransitionConstraintDegree::with_cycles(1, vec![2, 2]);
+    assert_eq!(2, degree.min_blowup_factor(trace_length));
+
+    // degree 3 * 63 + 56 = 245 fits into a domain of size 256
+    let degree = TransitionConstraintDegree::with_cycles(3, vec![8]);
+    assert_eq!(4, degree.min_blowup_factor(trace_length));
+
+    // degree 3 * 63 + 2 * 56 = 301 requires a domain of size 512
+    let degree = TransitionConstraintDegree::with_cycles(3, vec![8, 8]);
+    assert_eq!(8, degree.min_blowup_factor(trace_length));
+
+    // a column which spans the entire trace is equivalent to a trace register
+    let degree = TransitionConstraintDegree::with_cycles(1, vec![64]);
+    assert_eq!(
+        TransitionConstraintDegree::new(2).min_blowup_factor(trace_length),
+        degree.min_blowup_factor(trace_length)
+    );
+}
+
+// EVALUATION FRAME
+// ================================================================================================
+
+#[test]
+fn evaluation_frame_reuse() {
+    let mut frame = EvaluationFrame::<BaseElement>::new(4);
+    assert_eq!(4, frame.width());
+    frame.current[0] = BaseElement::ONE;
+
+    // growing the frame retains existing values and pads the frame with zeros
+    frame.reuse(8);
+    assert_eq!(8, frame.width());
+    assert_eq!(8, frame.next.len());
+    assert_eq!(BaseElement::ONE, frame.current[0]);
+    assert_eq!(BaseElement::ZERO, frame.current[7]);
+
+    // shrinking the frame does not release memory
+    let capacity = frame.current.capacity();
+    frame.reuse(2);
+    assert_eq!(2, frame.width());
+    assert_eq!(capacity, frame.current.capacity());
+}
@@ -0,0 +1,240 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{CompositionCoefficients, ComputationContext, EvaluationFrame};
+use math::field::{FieldElement, StarkField};
+
+#[cfg(test)]
+mod tests;
+
+// DEEP COMPOSER
+// ================================================================================================
+
+/// Defines the DEEP composition polynomial shared by the prover and the verifier.
+///
+/// The DEEP composition polynomial is a random linear combination of the following terms:
+/// * T1_i(x) = (T_i(x) - T_i(z)) / (x - z) for every trace register i;
+/// * T2_i(x) = (T_i(x) - T_i(z * g)) / (x - z * g) for every trace register i which is not
+///   local, where g is the generator of the trace domain;
+/// * T3_i(x) = (T_i(x) - T_i(z')) / (x - z') for every trace register i, where z' is the
+///   conjugate of z (i.e., the image of z under the Frobenius automorphism of the extension
+///   field); these terms are included only when field extension is enabled, and are needed to
+///   prove that the trace is defined over the base field;
+/// * C(x) = (P(x) - P(z)) / (x - z), where P(x) is the constraint composition polynomial.
+///
+/// Extra polynomials committed to by the prover (see
+/// [with_extra_polys()](ComputationContext::with_extra_polys)) contribute T1 and T3 terms
+/// defined in the same way as for trace registers; these terms are added to the sum of trace
+/// terms.
+///
+/// The sum of trace terms has degree trace_length - 2; to bring it up to the degree of the
+/// composition polynomial, it is multiplied by (k1 + k2 * x^p), where p is the incremental
+/// degree returned by [incremental_degree()](DeepComposer::incremental_degree).
+///
+/// The prover applies these formulas to polynomial coefficients, while the verifier applies
+/// them to individual evaluations at the queried positions; keeping the out-of-domain points,
+/// coefficient assignment, and degree adjustment in one place ensures the two stay in sync.
+pub struct DeepComposer<E: FieldElement> {
+    cc: CompositionCoefficients<E>,
+    z: E,
+    next_z: E,
+    z_conjugate: Option<E>,
+    next_state_registers: Vec<usize>,
+    incremental_degree: usize,
+}
+
+impl<E: FieldElement> DeepComposer<E> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new DEEP composer for the computation described by the `context`, instantiated
+    /// for out-of-domain point `z` and composition coefficients `cc`.
+    pub fn new<B>(context: &ComputationContext, z: E, cc: CompositionCoefficients<E>) -> Self
+    where
+        B: StarkField,
+        E: From<B>,
+    {
+        debug_assert_eq!(
+            context.trace_width(),
+            cc.trace.len(),
+            "number of trace coefficients must be equal to trace width"
+        );
+        debug_assert_eq!(
+            context.num_extra_polys(),
+            cc.extra.len(),
+            "number of extra coefficients must be equal to the number of extra polynomials"
+        );
+
+        let next_z = z * E::from(context.get_trace_domain_generator::<B>());
+        let z_conjugate = if context.options().field_extension().is_none() {
+            None
+        } else {
+            Some(z.conjugate())
+        };
+
+        DeepComposer {
+            cc,
+            z,
+            next_z,
+            z_conjugate,
+            next_state_registers: context.next_state_registers().to_vec(),
+            incremental_degree: get_incremental_degree(context),
+        }
+    }
+
+    // ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns pseudo-random coefficients used in the DEEP composition.
+    pub fn coefficients(&self) -> &CompositionCoefficients<E> {
+        &self.cc
+    }
+
+    /// Returns the out-of-domain point z.
+    pub fn z(&self) -> E {
+        self.z
+    }
+
+    /// Returns the out-of-domain point z * g, where g is the generator of the trace domain; this
+    /// point defines the "next" computation state in relation to point z.
+    pub fn next_z(&self) -> E {
+        self.next_z
+    }
+
+    /// Returns the conjugate of the out-of-domain point z when field extension is enabled;
+    /// otherwise, returns None.
+    pub fn z_conjugate(&self) -> Option<E> {
+        self.z_conjugate
+    }
+
+    /// Returns indexes of registers for which the DEEP composition includes T2 terms; these are
+    /// the registers evaluated at z * g in the out-of-domain frame.
+    pub fn next_state_registers(&self) -> &[usize] {
+        &self.next_state_registers
+    }
+
+    /// Returns the degree by which the sum of trace terms must be raised to match the degree of
+    /// the DEEP composition polynomial.
+    pub fn incremental_degree(&self) -> usize {
+        self.incremental_degree
+    }
+
+    // TRACE COMPOSITION
+    // --------------------------------------------------------------------------------------------
+
+    /// Writes conjugates of the `trace_state` into the `result` vector when field extension is
+    /// enabled; otherwise, clears the `result` vector. Any values previously held by the vector
+    /// are discarded.
+    pub fn conjugate_trace_state_into(&self, trace_state: &[E], result: &mut Vec<E>) {
+        result.clear();
+        if self.z_conjugate.is_some() {
+            result.extend(trace_state.iter().map(|v| v.conjugate()));
+        }
+    }
+
+    /// Returns the value of the DEEP composition of trace registers at `x`, given the trace
+    /// `state` at `x`, the `ood_frame` of trace states at z and z * g, and conjugates of the
+    /// trace state at z as computed by
+    /// [conjugate_trace_state_into()](DeepComposer::conjugate_trace_state_into). Values of local
+    /// registers in the next state of the `ood_frame` are ignored. The result already includes
+    /// the degree adjustment.
+    pub fn compose_trace_state<B>(
+        &self,
+        state: &[B],
+        x: E,
+        ood_frame: &EvaluationFrame<E>,
+        ood_conjugates: &[E],
+    ) -> E
+    where
+        B: StarkField,
+        E: From<B>,
+    {
+        let trace_at_z1 = &ood_frame.current;
+        let trace_at_z2 = &ood_frame.next;
+
+        let mut composition = E::ZERO;
+        for (i, &value) in state.iter().enumerate() {
+            let value = E::from(value);
+            // compute T1(x) = (T(x) - T(z)) / (x - z)
+            let t1 = (value - trace_at_z1[i]) / (x - self.z);
+            composition += t1 * self.cc.trace[i].0;
+
+            // compute T3(x) = (T(x) - T(z_conjugate)) / (x - z_conjugate)
+            if let Some(z_conjugate) = self.z_conjugate {
+                let t3 = (value - ood_conjugates[i]) / (x - z_conjugate);
+                composition += t3 * self.cc.trace[i].2;
+            }
+        }
+
+        // compute T2(x) = (T(x) - T(z * g)) / (x - z * g) for registers which are not local
+        for &i in self.next_state_registers.iter() {
+            let t2 = (E::from(state[i]) - trace_at_z2[i]) / (x - self.next_z);
+            composition += t2 * self.cc.trace[i].1;
+        }
+
+        self.adjust_trace_degree(composition, x)
+    }
+
+    /// Returns the value of the DEEP composition of extra polynomials at `x`, given their
+    /// evaluations `state` at `x`, their evaluations `extra_at_z` at z, and conjugates of these
+    /// evaluations as computed by
+    /// [conjugate_trace_state_into()](DeepComposer::conjugate_trace_state_into). Similarly to
+    /// [compose_trace_state()](DeepComposer::compose_trace_state), the result already includes
+    /// the degree adjustment.
+    pub fn compose_extra_state<B>(
+        &self,
+        state: &[B],
+        x: E,
+        extra_at_z: &[E],
+        extra_conjugates: &[E],
+    ) -> E
+    where
+        B: StarkField,
+        E: From<B>,
+    {
+        let mut composition = E::ZERO;
+        for (i, &value) in state.iter().enumerate() {
+            let value = E::from(value);
+            // compute T1(x) = (T(x) - T(z)) / (x - z)
+            let t1 = (value - extra_at_z[i]) / (x - self.z);
+            composition += t1 * self.cc.extra[i].0;
+
+            // compute T3(x) = (T(x) - T(z_conjugate)) / (x - z_conjugate)
+            if let Some(z_conjugate) = self.z_conjugate {
+                let t3 = (value - extra_conjugates[i]) / (x - z_conjugate);
+                composition += t3 * self.cc.extra[i].1;
+            }
+        }
+
+        self.adjust_trace_degree(composition, x)
+    }
+
+    /// Raises the degree of the trace composition `value` at `x` to match the degree of the DEEP
+    /// composition polynomial by computing value * (k1 + k2 * x^p).
+    pub fn adjust_trace_degree(&self, value: E, x: E) -> E {
+        let xp = x.exp((self.incremental_degree as u32).into());
+        value * (self.cc.trace_degree.0 + xp * self.cc.trace_degree.1)
+    }
+
+    // CONSTRAINT COMPOSITION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the value of the DEEP quotient of the constraint composition polynomial at `x`
+    /// multiplied by its pseudo-random coefficient; `evaluation` and `evaluation_at_z` are values
+    /// of the constraint composition polynomial at `x` and z respectively.
+    pub fn compose_constraint_evaluation(&self, evaluation: E, evaluation_at_z: E, x: E) -> E {
+        // compute C(x) = (P(x) - P(z)) / (x - z)
+        let composition = (evaluation - evaluation_at_z) / (x - self.z);
+        composition * self.cc.constraints
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the degree by which the sum of trace terms (of degree trace_length - 2) needs to be
+/// raised to match the degree of the DEEP composition polynomial.
+fn get_incremental_degree(context: &ComputationContext) -> usize {
+    context.deep_composition_degree() - (context.trace_length() - 2)
+}
@@ -0,0 +1,228 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::DeepComposer;
+use crate::{
+    CompositionCoefficients, ComputationContext, EvaluationFrame, FieldExtension, HashFunction,
+    ProofOptions, TransitionConstraintDegree,
+};
+use math::{
+    field::{f128::BaseElement, CubeExtension, FieldElement, QuadExtension, StarkField},
+    polynom,
+    utils::{get_power_series_with_offset_unchecked, log2_unchecked},
+};
+
+const TRACE_WIDTH: usize = 2;
+const TRACE_LENGTH: usize = 8;
+
+// OUT-OF-DOMAIN POINTS
+// ================================================================================================
+
+#[test]
+fn deep_composer_ood_points() {
+    let context = build_context(FieldExtension::None);
+    let z = BaseElement::prng_vector([1; 32], 1)[0];
+    let composer = DeepComposer::new::<BaseElement>(&context, z, build_coefficients([2; 32]));
+
+    let g = BaseElement::get_root_of_unity(log2_unchecked(TRACE_LENGTH)).unwrap();
+    assert_eq!(z, composer.z());
+    assert_eq!(z * g, composer.next_z());
+    assert_eq!(None, composer.z_conjugate());
+    assert_eq!(
+        context.deep_composition_degree(),
+        composer.incremental_degree() + TRACE_LENGTH - 2
+    );
+
+    // when field extension is enabled, conjugate of z is used as an additional point
+    let context = build_context(FieldExtension::Quadratic);
+    let z = QuadExtension::<BaseElement>::prng_vector([1; 32], 1)[0];
+    let composer = DeepComposer::new::<BaseElement>(&context, z, build_coefficients([2; 32]));
+    assert_eq!(z * QuadExtension::from(g), composer.next_z());
+    assert_eq!(Some(z.conjugate()), composer.z_conjugate());
+
+    // with cubic extension, the conjugate of z is its image under the Frobenius automorphism;
+    // polynomials over the base field map values at z to values at the conjugate of z
+    let context = build_context(FieldExtension::Cubic);
+    let z = CubeExtension::<BaseElement>::prng_vector([1; 32], 1)[0];
+    let composer = DeepComposer::new::<BaseElement>(&context, z, build_coefficients([2; 32]));
+    let z_conjugate = z.exp(BaseElement::MODULUS);
+    assert_eq!(Some(z_conjugate), composer.z_conjugate());
+    let poly = BaseElement::prng_vector([3; 32], TRACE_LENGTH)
+        .into_iter()
+        .map(CubeExtension::from)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        polynom::eval(&poly, z).conjugate(),
+        polynom::eval(&poly, z_conjugate)
+    );
+}
+
+// DEEP COMPOSITION
+// ================================================================================================
+
+#[test]
+fn compose_trace_state_degree() {
+    let context = build_context(FieldExtension::None);
+    let z = BaseElement::prng_vector([1; 32], 1)[0];
+    let composer = DeepComposer::new::<BaseElement>(&context, z, build_coefficients([2; 32]));
+
+    // build random trace polynomials and evaluate them at the out-of-domain points
+    let trace_polys = (0..TRACE_WIDTH)
+        .map(|i| BaseElement::prng_vector([i as u8 + 3; 32], TRACE_LENGTH))
+        .collect::<Vec<_>>();
+    let ood_frame = EvaluationFrame {
+        current: trace_polys.iter().map(|p| polynom::eval(p, z)).collect(),
+        next: trace_polys
+            .iter()
+            .map(|p| polynom::eval(p, composer.next_z()))
+            .collect(),
+    };
+    let mut conjugates = Vec::new();
+    composer.conjugate_trace_state_into(&ood_frame.current, &mut conjugates);
+    assert!(conjugates.is_empty());
+
+    // compose trace states over a coset of the constraint evaluation domain
+    let xs = build_domain(&context);
+    let ys = xs
+        .iter()
+        .map(|&x| {
+            let state = trace_polys
+                .iter()
+                .map(|p| polynom::eval(p, x))
+                .collect::<Vec<_>>();
+            composer.compose_trace_state(&state, x, &ood_frame, &conjugates)
+        })
+        .collect::<Vec<_>>();
+
+    // the result must be a polynomial of DEEP composition degree
+    let poly = polynom::interpolate(&xs, &ys, true);
+    assert_eq!(context.deep_composition_degree(), polynom::degree_of(&poly));
+}
+
+#[test]
+fn compose_trace_state_local_registers() {
+    let context = build_context(FieldExtension::None).with_local_registers(&[1]);
+    let z = BaseElement::prng_vector([1; 32], 1)[0];
+    let composer = DeepComposer::new::<BaseElement>(&context, z, build_coefficients([2; 32]));
+    assert_eq!(&[0], composer.next_state_registers());
+
+    let state = BaseElement::prng_vector([3; 32], TRACE_WIDTH);
+    let x = BaseElement::prng_vector([4; 32], 1)[0];
+    let mut ood_frame = EvaluationFrame {
+        current: BaseElement::prng_vector([5; 32], TRACE_WIDTH),
+        next: BaseElement::prng_vector([6; 32], TRACE_WIDTH),
+    };
+
+    // the next-state value of the local register does not affect the composition
+    let expected = composer.compose_trace_state(&state, x, &ood_frame, &[]);
+    ood_frame.next[1] = BaseElement::ZERO;
+    assert_eq!(
+        expected,
+        composer.compose_trace_state(&state, x, &ood_frame, &[])
+    );
+
+    // the composition omits exactly the T2 term of the local register
+    let full_composer = DeepComposer::new::<BaseElement>(
+        &build_context(FieldExtension::None),
+        z,
+        build_coefficients([2; 32]),
+    );
+    let t2 = (state[1] - ood_frame.next[1]) / (x - composer.next_z());
+    let cc = composer.coefficients();
+    assert_eq!(
+        full_composer.compose_trace_state(&state, x, &ood_frame, &[]),
+        expected + composer.adjust_trace_degree(t2 * cc.trace[1].1, x)
+    );
+}
+
+#[test]
+fn compose_extra_state_degree() {
+    let context = build_context(FieldExtension::None).with_extra_polys(1);
+    let z = BaseElement::prng_vector([1; 32], 1)[0];
+    let mut cc = build_coefficients([2; 32]);
+    let extra_coefficients = BaseElement::prng_vector([3; 32], 2);
+    cc.extra = vec![(extra_coefficients[0], extra_coefficients[1])];
+    let composer = DeepComposer::new::<BaseElement>(&context, z, cc);
+
+    // build a random extra polynomial and compose its evaluations over a coset of the
+    // constraint evaluation domain
+    let extra_poly = BaseElement::prng_vector([4; 32], TRACE_LENGTH);
+    let extra_at_z = vec![polynom::eval(&extra_poly, z)];
+    let xs = build_domain(&context);
+    let ys = xs
+        .iter()
+        .map(|&x| {
+            let state = vec![polynom::eval(&extra_poly, x)];
+            composer.compose_extra_state(&state, x, &extra_at_z, &[])
+        })
+        .collect::<Vec<_>>();
+
+    // the result must be a polynomial of DEEP composition degree
+    let poly = polynom::interpolate(&xs, &ys, true);
+    assert_eq!(context.deep_composition_degree(), polynom::degree_of(&poly));
+
+    // a wrong evaluation at z breaks the degree bound
+    let wrong_at_z = vec![extra_at_z[0] + BaseElement::ONE];
+    let ys = xs
+        .iter()
+        .map(|&x| {
+            let state = vec![polynom::eval(&extra_poly, x)];
+            composer.compose_extra_state(&state, x, &wrong_at_z, &[])
+        })
+        .collect::<Vec<_>>();
+    let poly = polynom::interpolate(&xs, &ys, true);
+    assert!(context.deep_composition_degree() < polynom::degree_of(&poly));
+}
+
+#[test]
+fn compose_constraint_evaluation_degree() {
+    let context = build_context(FieldExtension::None);
+    let z = BaseElement::prng_vector([1; 32], 1)[0];
+    let composer = DeepComposer::new::<BaseElement>(&context, z, build_coefficients([2; 32]));
+
+    // build a random constraint composition polynomial and evaluate it over a coset of the
+    // constraint evaluation domain
+    let constraint_poly = BaseElement::prng_vector([3; 32], context.composition_degree() + 1);
+    let value_at_z = polynom::eval(&constraint_poly, z);
+    let xs = build_domain(&context);
+    let ys = xs
+        .iter()
+        .map(|&x| {
+            let evaluation = polynom::eval(&constraint_poly, x);
+            composer.compose_constraint_evaluation(evaluation, value_at_z, x)
+        })
+        .collect::<Vec<_>>();
+
+    // dividing out z must reduce the degree by one
+    let poly = polynom::interpolate(&xs, &ys, true);
+    assert_eq!(context.deep_composition_degree(), polynom::degree_of(&poly));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_context(field_extension: FieldExtension) -> ComputationContext {
+    let options = ProofOptions::new(8, 8, 0, HashFunction::Blake3_256, field_extension);
+    let t_degrees = vec![TransitionConstraintDegree::new(3)];
+    ComputationContext::new(TRACE_WIDTH, TRACE_LENGTH, t_degrees, options)
+}
+
+fn build_coefficients<E: FieldElement>(seed: [u8; 32]) -> CompositionCoefficients<E> {
+    let values = E::prng_vector(seed, TRACE_WIDTH * 3 + 3);
+    CompositionCoefficients {
+        trace: values[..TRACE_WIDTH * 3]
+            .chunks(3)
+            .map(|c| (c[0], c[1], c[2]))
+            .collect(),
+        trace_degree: (values[TRACE_WIDTH * 3], values[TRACE_WIDTH * 3 + 1]),
+        constraints: values[TRACE_WIDTH * 3 + 2],
+        extra: Vec::new(),
+    }
+}
+
+fn build_domain(context: &ComputationContext) -> Vec<BaseElement> {
+    let g = BaseElement::get_root_of_unity(log2_unchecked(context.ce_domain_size())).unwrap();
+    get_power_series_with_offset_unchecked(g, BaseElement::GENERATOR, context.ce_domain_size())
+}
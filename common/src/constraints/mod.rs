@@ -0,0 +1,88 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Air, EvaluationFrame, PublicCoin};
+use math::field::FieldElement;
+
+// CONSTRAINT EVALUATION
+// ================================================================================================
+
+/// Evaluates constraints of the `air` against the specified evaluation frame at point `x`, and
+/// returns a random linear combination of the results divided by the corresponding divisors.
+///
+/// When `ood_frame` contains evaluations of trace polynomials at an out-of-domain point z and
+/// at z * g (where g is the generator of the trace domain), and `x` = z, the result is equal to
+/// the evaluation of the prover's constraint composition polynomial at z. Coefficients of the
+/// random linear combination are drawn from the `coin`, which must be in the same state as the
+/// prover's public coin.
+///
+/// `periodic_values` and `t_evaluations` are used as buffers for values of periodic columns at
+/// `x` and for transition constraint evaluations respectively; their contents are overwritten.
+pub fn evaluate_constraints<A: Air, C: PublicCoin, E: FieldElement + From<A::BaseElement>>(
+    air: &A,
+    coin: &C,
+    ood_frame: &EvaluationFrame<E>,
+    x: E,
+    periodic_values: &mut Vec<E>,
+    t_evaluations: &mut Vec<E>,
+) -> E {
+    // ----- evaluate transition constraints ------------------------------------------------------
+
+    // initialize a buffer to hold transition constraint evaluations
+    t_evaluations.clear();
+    t_evaluations.resize(air.num_transition_constraints(), E::ZERO);
+
+    // compute values of periodic columns at x
+    air.evaluate_periodic_columns_at(x, periodic_values);
+
+    // evaluate transition constraints over OOD evaluation frame
+    air.evaluate_transition(ood_frame, periodic_values, t_evaluations);
+
+    // merge all constraint evaluations into a single value by computing their random linear
+    // combination using coefficients drawn from the public coin
+    let t_constraints = air.get_transition_constraints(coin.get_transition_coefficient_prng());
+    let t_evaluation = t_constraints.iter().fold(E::ZERO, |acc, group| {
+        acc + group.merge_evaluations(t_evaluations, x)
+    });
+
+    // get the divisor for transition constraints; divisors for all transition constraints are
+    // the same and have the form: (x^steps - 1) / (x - x_at_last_step)
+    let t_divisor = air
+        .context()
+        .constraint_layout::<A::BaseElement>()
+        .transition_divisor();
+
+    // divide out the evaluation of divisor at x
+    let z = t_divisor.evaluate_at(x);
+    let mut result = t_evaluation / z;
+
+    // ----- evaluate boundary constraints --------------------------------------------------------
+
+    // get boundary constraints grouped by common divisor from the AIR
+    let b_constraints = air.get_boundary_constraints(coin.get_boundary_coefficient_prng());
+
+    // iterate over boundary constraint groups (each group has a distinct divisor), evaluate
+    // constraints in each group and add them to the evaluations vector
+
+    // cache power of x here so that we only re-compute it when degree_adjustment changes
+    let mut degree_adjustment = b_constraints[0].degree_adjustment();
+    let mut xp = x.exp(degree_adjustment.into());
+
+    for group in b_constraints.iter() {
+        // if adjustment degree hasn't changed, no need to recompute `xp` - so just reuse the
+        // previous value; otherwise, compute new `xp`
+        if group.degree_adjustment() != degree_adjustment {
+            degree_adjustment = group.degree_adjustment();
+            xp = x.exp(degree_adjustment.into());
+        }
+        // evaluate all constraints in the group, and the divide out the value implied
+        // by the divisor
+        let evaluation = group.evaluate_at(&ood_frame.current, x, xp);
+        let z = group.divisor().evaluate_at(x);
+        result += evaluation / z;
+    }
+
+    result
+}
@@ -0,0 +1,373 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{utils, ProofOptions, TraceRowHashing};
+use core::{cmp, fmt};
+use math::{field::StarkField, utils::log2_unchecked};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Size of a hash digest in bytes; this is the same for all supported hash functions.
+const DIGEST_SIZE: usize = 32;
+
+/// Number of bytes hashed when two digests are merged into a parent Merkle tree node, or when a
+/// pseudo-random value is drawn from a seed.
+const NODE_INPUT_SIZE: usize = 2 * DIGEST_SIZE;
+
+/// Number of evaluations folded into a single value at each FRI layer.
+const FRI_FOLDING_FACTOR: usize = 4;
+
+/// Approximate number of multiplications needed to interpolate a degree 3 polynomial from four
+/// points (with inversions amortized across all queries) and to evaluate it at a single point.
+const FRI_FOLDING_MULTS: usize = 48;
+
+/// Approximate serialized size of proof context and of length prefixes of proof sections.
+const PROOF_OVERHEAD_SIZE: usize = 64;
+
+// VERIFICATION COST
+// ================================================================================================
+
+/// An estimate of the work performed by the verifier to verify a single proof.
+///
+/// The estimate is computed from proof options and trace dimensions alone, and thus can be
+/// obtained before a proof is generated. Values related to Merkle authentication paths are
+/// upper bounds: they assume that authentication paths for different queries share as few
+/// nodes as possible. Work which depends on the specifics of the AIR (i.e., evaluating
+/// constraints at the out-of-domain point, and drawing constraint composition coefficients) is
+/// not included; for most computations, this work is small compared to the per-query work.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationCost {
+    /// Number of hash function invocations, including hashing of Merkle tree leaves and nodes
+    /// as well as drawing pseudo-random values from the public coin.
+    pub num_hashes: usize,
+    /// Total number of bytes passed to the hash function.
+    pub hashed_bytes: usize,
+    /// Number of field multiplications; when field extension is enabled, these are
+    /// multiplications in the extension field.
+    pub num_field_mults: usize,
+    /// Number of field inversions (each division requires one inversion); when field extension
+    /// is enabled, these are inversions in the extension field.
+    pub num_field_inversions: usize,
+    /// Approximate size of the serialized proof in bytes.
+    pub proof_size: usize,
+}
+
+impl VerificationCost {
+    /// Returns an estimate of the verifier's work for a proof of a computation with the
+    /// specified trace dimensions generated with the specified `options` over base field `B`.
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a power of two.
+    pub fn estimate<B: StarkField>(
+        options: &ProofOptions,
+        trace_width: usize,
+        trace_length: usize,
+    ) -> Self {
+        Self::estimate_with_next_state::<B>(options, trace_width, trace_width, trace_length)
+    }
+
+    /// Same as [VerificationCost::estimate()], but only `num_next_state` registers are evaluated
+    /// at z * g in the out-of-domain frame; the remaining registers are local.
+    pub(crate) fn estimate_with_next_state<B: StarkField>(
+        options: &ProofOptions,
+        trace_width: usize,
+        num_next_state: usize,
+        trace_length: usize,
+    ) -> Self {
+        assert!(
+            trace_length.is_power_of_two(),
+            "trace length must be a power of 2, but was {}",
+            trace_length
+        );
+
+        let mut cost = VerificationCost {
+            num_hashes: 0,
+            hashed_bytes: 0,
+            num_field_mults: 0,
+            num_field_inversions: 0,
+            proof_size: PROOF_OVERHEAD_SIZE + B::ELEMENT_BYTES,
+        };
+
+        let num_queries = options.num_queries();
+        let num_fri_queries = options.num_fri_queries();
+        let lde_domain_size = trace_length * options.blowup_factor();
+        let lde_domain_depth = log2_unchecked(lde_domain_size) as usize;
+        let base_bytes = options.byte_encoding().element_bytes::<B>();
+        let element_bytes = base_bytes * options.field_extension().degree();
+
+        // trace commitment: each query opens a leaf containing one or more full rows of the
+        // extended execution trace, and several queries may fall into the same leaf
+        let rows_per_leaf = options.trace_leaf_batching();
+        let num_trace_leaves = lde_domain_size / rows_per_leaf;
+        cost.add_batch_opening(
+            cmp::min(num_queries, num_trace_leaves),
+            log2_unchecked(num_trace_leaves) as usize,
+            rows_per_leaf * trace_width * base_bytes,
+        );
+
+        // with chunked row hashing, each opened trace leaf is also the root of a Merkle tree over
+        // digests of row chunks; every chunk is hashed separately and the chunk digests are then
+        // merged into the leaf
+        if let TraceRowHashing::Chunked(chunk_width) = options.trace_row_hashing() {
+            let num_chunks = utils::get_num_row_chunks(trace_width, chunk_width as usize);
+            let padded_chunks = utils::get_padded_chunks_per_row(num_chunks);
+            let num_chunk_nodes = cmp::max(rows_per_leaf * padded_chunks, 2) - 1;
+            let num_opened_leaves = cmp::min(num_queries, num_trace_leaves);
+            cost.num_hashes += num_opened_leaves * (rows_per_leaf * num_chunks + num_chunk_nodes);
+            cost.num_hashes -= num_opened_leaves;
+            cost.hashed_bytes += num_opened_leaves * num_chunk_nodes * NODE_INPUT_SIZE;
+        }
+
+        // constraint commitment: evaluations are packed into leaves of two digests each, and
+        // several queries may fall into the same leaf; the number of evaluations per leaf is
+        // determined by the native size of field elements regardless of the byte encoding
+        let native_bytes = B::ELEMENT_BYTES * options.field_extension().degree();
+        let evaluations_per_leaf = utils::get_items_per_leaf(NODE_INPUT_SIZE, native_bytes);
+        let num_constraint_leaves = lde_domain_size / evaluations_per_leaf;
+        cost.add_batch_opening(
+            cmp::min(num_queries, num_constraint_leaves),
+            log2_unchecked(num_constraint_leaves) as usize,
+            evaluations_per_leaf * element_bytes,
+        );
+
+        // out-of-domain frame, as well as trace and constraint commitments
+        cost.proof_size += (trace_width + num_next_state) * element_bytes + 2 * DIGEST_SIZE;
+
+        // DEEP composition: for every query, each register contributes one quotient (two when
+        // field extension is enabled), each non-local register contributes an extra quotient for
+        // the next state, and the constraint polynomial contributes one
+        let quotients_per_register = if options.field_extension().is_none() {
+            1
+        } else {
+            2
+        };
+        let num_quotients = trace_width * quotients_per_register + num_next_state + 1;
+        cost.num_field_inversions += num_queries * num_quotients;
+        cost.num_field_mults += num_queries * (2 * num_quotients + 2 * lde_domain_depth);
+
+        // FRI layers: each FRI query opens a group of evaluations in every layer
+        let fri_options = options.to_fri_options::<B>();
+        let num_fri_layers = fri_options.num_fri_layers(lde_domain_size);
+        let mut domain_size = lde_domain_size;
+        for _ in 0..num_fri_layers {
+            let num_leaves = domain_size / FRI_FOLDING_FACTOR;
+            let num_layer_queries = cmp::min(num_fri_queries, num_leaves);
+            cost.add_batch_opening(
+                num_layer_queries,
+                log2_unchecked(num_leaves) as usize,
+                FRI_FOLDING_FACTOR * element_bytes,
+            );
+            cost.proof_size += DIGEST_SIZE;
+
+            // computing x coordinates, and interpolating and evaluating row polynomials
+            let x_mults = 2 * log2_unchecked(domain_size) as usize;
+            cost.num_field_mults += num_layer_queries * (x_mults + FRI_FOLDING_MULTS);
+            cost.num_field_inversions += 1;
+            domain_size = num_leaves;
+        }
+
+        // FRI remainder: the verifier interpolates a polynomial from a subset of the remainder
+        // and checks that it evaluates correctly at the remaining points
+        let remainder_length = fri_options.fri_remainder_length(lde_domain_size);
+        let remainder_degree = cmp::max(remainder_length / options.blowup_factor(), 1);
+        cost.proof_size += remainder_length * element_bytes;
+        cost.num_field_mults += 2 * remainder_degree * remainder_degree;
+        cost.num_field_mults += (remainder_length - remainder_degree) * remainder_degree;
+        cost.num_field_inversions += remainder_degree;
+
+        // public coin: out-of-domain point, DEEP composition coefficients, FRI folding
+        // coefficients, query seed with proof-of-work, and query positions
+        let num_draws = 1 + (3 * trace_width + 3) + num_fri_layers + num_fri_queries;
+        cost.num_hashes += num_draws + 2;
+        cost.hashed_bytes += (num_draws + 1) * NODE_INPUT_SIZE + num_fri_layers * DIGEST_SIZE;
+        cost.proof_size += 8;
+
+        cost
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the cost of verifying a batch Merkle proof for `num_queries` leaves of a tree with
+    /// the specified depth, where each leaf is built by hashing `leaf_size` bytes.
+    fn add_batch_opening(&mut self, num_queries: usize, depth: usize, leaf_size: usize) {
+        let num_nodes = get_max_batch_nodes(num_queries, depth);
+
+        self.num_hashes += num_queries + num_nodes;
+        self.hashed_bytes += num_queries * leaf_size + num_nodes * NODE_INPUT_SIZE;
+        self.proof_size += num_queries * leaf_size + num_nodes * DIGEST_SIZE;
+    }
+}
+
+impl fmt::Display for VerificationCost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} hashes ({} bytes hashed), {} field multiplications, {} field inversions; \
+            proof size is about {} bytes",
+            self.num_hashes,
+            self.hashed_bytes,
+            self.num_field_mults,
+            self.num_field_inversions,
+            self.proof_size
+        )
+    }
+}
+
+// PROVING COST
+// ================================================================================================
+
+/// A rough estimate of the work performed by the prover to generate a single proof.
+///
+/// Like [VerificationCost], the estimate is computed from proof options and trace dimensions
+/// alone. It accounts for extending the execution trace, committing to the extended trace and
+/// to constraint evaluations, building the DEEP composition polynomial, computing FRI layers,
+/// and grinding. Evaluating transition constraints is assumed to take one multiplication per
+/// register at every point of the constraint evaluation domain; actual cost of constraint
+/// evaluation depends on the AIR and can be much higher.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProvingCost {
+    /// Number of hash function invocations, including expected number of invocations needed to
+    /// find a proof-of-work nonce.
+    pub num_hashes: u64,
+    /// Total number of bytes passed to the hash function.
+    pub hashed_bytes: u64,
+    /// Number of base field multiplications; a multiplication in an extension field of degree
+    /// k is counted as k^2 base field multiplications, and inversions are assumed to be
+    /// batched and are counted as 3 multiplications each.
+    pub num_field_mults: u64,
+}
+
+impl ProvingCost {
+    /// Returns an estimate of the prover's work for a proof of a computation with the specified
+    /// trace dimensions and constraint evaluation (CE) blowup factor generated with the
+    /// specified `options` over base field `B`.
+    ///
+    /// # Panics
+    /// Panics if `trace_length` or `ce_blowup_factor` is not a power of two.
+    pub fn estimate<B: StarkField>(
+        options: &ProofOptions,
+        trace_width: usize,
+        trace_length: usize,
+        ce_blowup_factor: usize,
+    ) -> Self {
+        assert!(
+            trace_length.is_power_of_two(),
+            "trace length must be a power of 2, but was {}",
+            trace_length
+        );
+        assert!(
+            ce_blowup_factor.is_power_of_two(),
+            "CE blowup factor must be a power of 2, but was {}",
+            ce_blowup_factor
+        );
+
+        let mut cost = ProvingCost {
+            num_hashes: 0,
+            hashed_bytes: 0,
+            num_field_mults: 0,
+        };
+
+        let trace_width = trace_width as u64;
+        let trace_length = trace_length as u64;
+        let lde_domain_size = trace_length * options.blowup_factor() as u64;
+        let ce_domain_size = trace_length * ce_blowup_factor as u64;
+        let extension_degree = options.field_extension().degree() as u64;
+        let ext_mult = extension_degree * extension_degree;
+        let base_bytes = options.byte_encoding().element_bytes::<B>() as u64;
+        let element_bytes = base_bytes * extension_degree;
+
+        // trace extension: every register is interpolated over the trace domain and evaluated
+        // over the LDE domain
+        cost.num_field_mults +=
+            trace_width * (get_fft_mults(trace_length) + get_fft_mults(lde_domain_size));
+
+        // trace commitment: rows are hashed into leaves which are then merged into a tree
+        let rows_per_leaf = options.trace_leaf_batching() as u64;
+        cost.add_tree(
+            lde_domain_size / rows_per_leaf,
+            rows_per_leaf * trace_width * base_bytes,
+        );
+
+        // constraint evaluation and commitment: the constraint polynomial is interpolated over
+        // the CE domain and evaluated over the LDE domain
+        cost.num_field_mults += ce_domain_size * trace_width * ext_mult;
+        cost.num_field_mults +=
+            (get_fft_mults(ce_domain_size) + get_fft_mults(lde_domain_size)) * ext_mult;
+        let native_bytes = B::ELEMENT_BYTES * options.field_extension().degree();
+        let evaluations_per_leaf = utils::get_items_per_leaf(NODE_INPUT_SIZE, native_bytes) as u64;
+        cost.add_tree(
+            lde_domain_size / evaluations_per_leaf,
+            evaluations_per_leaf * element_bytes,
+        );
+
+        // DEEP composition: two quotients per register (four when field extension is enabled)
+        // and one for the constraint polynomial at every point of the LDE domain
+        let quotients_per_register = if options.field_extension().is_none() {
+            2
+        } else {
+            4
+        };
+        let num_quotients = trace_width * quotients_per_register + 1;
+        cost.num_field_mults += lde_domain_size * (2 * num_quotients + 6) * ext_mult;
+
+        // FRI layers: every layer is folded from the previous one and committed to
+        let fri_options = options.to_fri_options::<B>();
+        let num_fri_layers = fri_options.num_fri_layers(lde_domain_size as usize);
+        let mut domain_size = lde_domain_size;
+        for _ in 0..num_fri_layers {
+            let num_leaves = domain_size / FRI_FOLDING_FACTOR as u64;
+            cost.add_tree(num_leaves, FRI_FOLDING_FACTOR as u64 * element_bytes);
+            cost.num_field_mults += num_leaves * FRI_FOLDING_MULTS as u64 * ext_mult;
+            domain_size = num_leaves;
+        }
+
+        // grinding: a nonce is expected to be found after 2^grinding_factor attempts
+        let num_attempts = 1u64 << options.grinding_factor();
+        cost.num_hashes += num_attempts;
+        cost.hashed_bytes += num_attempts * (DIGEST_SIZE + 8) as u64;
+
+        cost
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the cost of building a Merkle tree with the specified number of leaves, where each
+    /// leaf is built by hashing `leaf_size` bytes.
+    fn add_tree(&mut self, num_leaves: u64, leaf_size: u64) {
+        let num_nodes = num_leaves.saturating_sub(1);
+        self.num_hashes += num_leaves + num_nodes;
+        self.hashed_bytes += num_leaves * leaf_size + num_nodes * NODE_INPUT_SIZE as u64;
+    }
+}
+
+impl fmt::Display for ProvingCost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} hashes ({} bytes hashed), {} field multiplications",
+            self.num_hashes, self.hashed_bytes, self.num_field_mults
+        )
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the largest number of internal nodes which need to be hashed (and the largest number
+/// of digests included in a proof) to authenticate `num_queries` leaves in a Merkle tree of the
+/// specified depth. A tree level with 2^i nodes cannot contribute more than 2^i nodes, nor more
+/// than one node per query.
+fn get_max_batch_nodes(num_queries: usize, depth: usize) -> usize {
+    (0..depth).map(|i| cmp::min(num_queries, 1 << i)).sum()
+}
+
+/// Returns the number of multiplications performed by a radix-2 FFT over a domain of the
+/// specified size.
+fn get_fft_mults(domain_size: u64) -> u64 {
+    domain_size / 2 * domain_size.trailing_zeros() as u64
+}
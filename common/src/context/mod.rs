@@ -3,8 +3,36 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{air::TransitionConstraintDegree, ProofOptions};
-use math::{field::StarkField, utils::log2};
+use crate::{
+    air::{ConstraintLayout, TransitionConstraintDegree},
+    errors::ParameterError,
+    limits, ProofOptions,
+};
+use core::{any::Any, cmp};
+use math::{
+    field::StarkField,
+    utils::{log2, log2_unchecked},
+};
+use once_cell::sync::OnceCell;
+use std::sync::Arc;
+
+mod cost;
+pub use cost::{ProvingCost, VerificationCost};
+
+mod report;
+pub use report::CeBlowupReport;
+
+mod search;
+pub use search::{CostModel, ParameterCandidate, ParameterSearch};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Smallest blowup factor required by any transition constraint.
+const MIN_CE_BLOWUP_FACTOR: usize = 2;
 
 // TYPES AND INTERFACES
 // ================================================================================================
@@ -16,6 +44,14 @@ pub struct ComputationContext {
     trace_length: usize,
     transition_constraint_degrees: Vec<TransitionConstraintDegree>,
     ce_blowup_factor: usize,
+    next_state_registers: Vec<usize>,
+    air_id: Option<[u8; 32]>,
+    num_extra_polys: usize,
+    aux_segment_width: usize,
+    num_aux_rand_elements: usize,
+    min_field_bits: u32,
+    periodic_column_polys: OnceCell<Arc<dyn Any + Send + Sync>>,
+    constraint_layout: OnceCell<Arc<dyn Any + Send + Sync>>,
 }
 
 // COMPUTATION CONTEXT
@@ -24,7 +60,7 @@ pub struct ComputationContext {
 impl ComputationContext {
     // CONSTANTS
     // --------------------------------------------------------------------------------------------
-    pub const MIN_TRACE_LENGTH: usize = 8;
+    pub const MIN_TRACE_LENGTH: usize = limits::MIN_TRACE_LENGTH;
 
     // CONSTRUCTORS
     // --------------------------------------------------------------------------------------------
@@ -40,6 +76,12 @@ impl ComputationContext {
             "trace_width must be greater than 0; was {}",
             trace_width
         );
+        assert!(
+            trace_width <= limits::MAX_TRACE_WIDTH,
+            "trace_width cannot be greater than {}; was {}",
+            limits::MAX_TRACE_WIDTH,
+            trace_width
+        );
         assert!(
             trace_length >= Self::MIN_TRACE_LENGTH,
             "trace_length must beat least {}; was {}",
@@ -60,8 +102,9 @@ impl ComputationContext {
         // the blowup factor of the highest degree constraint
         let mut ce_blowup_factor = 0;
         for degree in transition_constraint_degrees.iter() {
-            if degree.min_blowup_factor() > ce_blowup_factor {
-                ce_blowup_factor = degree.min_blowup_factor();
+            let min_blowup_factor = degree.min_blowup_factor(trace_length);
+            if min_blowup_factor > ce_blowup_factor {
+                ce_blowup_factor = min_blowup_factor;
             }
         }
 
@@ -78,7 +121,211 @@ impl ComputationContext {
             trace_length,
             transition_constraint_degrees,
             ce_blowup_factor,
+            next_state_registers: (0..trace_width).collect(),
+            air_id: None,
+            num_extra_polys: 0,
+            aux_segment_width: 0,
+            num_aux_rand_elements: 0,
+            min_field_bits: 0,
+            periodic_column_polys: OnceCell::new(),
+            constraint_layout: OnceCell::new(),
+        }
+    }
+
+    /// Returns this context with the specified registers marked as local.
+    ///
+    /// Values of local registers in the next state are not referenced by any transition
+    /// constraint; thus, their evaluations at z * g are omitted from the out-of-domain frame,
+    /// and the corresponding quotients are omitted from the DEEP composition polynomial. This
+    /// reduces proof size and the work of the prover and the verifier for wide traces in which
+    /// many registers are constrained only within a single state.
+    ///
+    /// When constraints are evaluated at the out-of-domain point, next-state values of local
+    /// registers are set to zero; thus, transition constraints must not read them. When compiled
+    /// in debug mode, the prover checks this during constraint evaluation, and panics if a
+    /// transition constraint reads a next-state value of a local register.
+    ///
+    /// # Panics
+    /// Panics if any of the registers is listed more than once or is not smaller than the trace
+    /// width.
+    pub fn with_local_registers(mut self, registers: &[usize]) -> Self {
+        let mut is_local = vec![false; self.trace_width];
+        for &register in registers.iter() {
+            assert!(
+                register < self.trace_width,
+                "local register {} is out of bounds for trace width {}",
+                register,
+                self.trace_width
+            );
+            assert!(
+                !is_local[register],
+                "register {} is marked as local more than once",
+                register
+            );
+            is_local[register] = true;
+        }
+        self.next_state_registers = (0..self.trace_width).filter(|&i| !is_local[i]).collect();
+        self
+    }
+
+    /// Returns this context with the specified identifier of the AIR (e.g., a hash of the
+    /// program executed by the computation).
+    ///
+    /// The identifier is included in the proof and is bound into the seed from which constraint
+    /// composition coefficients are drawn. The verifier rejects proofs which carry an identifier
+    /// different from the identifier of the AIR they are verified against; thus, a proof
+    /// generated for one AIR cannot be replayed against a different AIR which happens to share
+    /// trace dimensions and proof options.
+    pub fn with_air_id(mut self, air_id: [u8; 32]) -> Self {
+        self.air_id = Some(air_id);
+        self
+    }
+
+    /// Returns this context with the specified number of extra polynomials committed to by the
+    /// prover.
+    ///
+    /// Extra polynomials are defined by the application (e.g., a custom accumulator of a
+    /// protocol layered on top of the STARK) via their evaluations over the trace domain. They
+    /// are committed to after the execution trace, are opened at the out-of-domain point, and
+    /// are included into the DEEP composition polynomial; thus, their degree is checked by the
+    /// same FRI proof as the degree of the trace. Extra polynomials are not referenced by
+    /// transition or boundary constraints.
+    ///
+    /// # Panics
+    /// Panics if `num_extra_polys` is greater than the maximum trace width.
+    pub fn with_extra_polys(mut self, num_extra_polys: usize) -> Self {
+        assert!(
+            num_extra_polys <= limits::MAX_TRACE_WIDTH,
+            "number of extra polynomials cannot be greater than {}; was {}",
+            limits::MAX_TRACE_WIDTH,
+            num_extra_polys
+        );
+        self.num_extra_polys = num_extra_polys;
+        self
+    }
+
+    /// Returns this context with the last `width` registers of the trace forming an auxiliary
+    /// segment which is built using `num_rand_elements` random elements.
+    ///
+    /// The prover commits to registers of the main segment first, draws the random elements from
+    /// the public coin seeded with this commitment, and only then builds registers of the
+    /// auxiliary segment and commits to them separately. This allows transition constraints to
+    /// reference values derived from verifier randomness (e.g., running products of permutation
+    /// arguments). Random elements are drawn from the base field; they are passed to the AIR via
+    /// [Air::set_aux_rand_elements()](crate::Air::set_aux_rand_elements) before any constraint
+    /// is built or evaluated.
+    ///
+    /// # Panics
+    /// Panics if `width` is not smaller than the trace width, or if `num_rand_elements` is zero.
+    pub fn with_aux_segment(mut self, width: usize, num_rand_elements: usize) -> Self {
+        assert!(
+            width < self.trace_width,
+            "auxiliary segment must be narrower than the trace width {}; was {}",
+            self.trace_width,
+            width
+        );
+        assert!(
+            num_rand_elements > 0,
+            "auxiliary segment requires at least one random element"
+        );
+        self.aux_segment_width = width;
+        self.num_aux_rand_elements = num_rand_elements;
+        self
+    }
+
+    /// Returns this context with the specified smallest size (in bits) of the field from which
+    /// the out-of-domain point and composition coefficients are drawn.
+    ///
+    /// These values are drawn from the extension of the base field specified in proof options;
+    /// without an extension, they are drawn from the base field itself, and for small base fields
+    /// (e.g., 62-bit or 64-bit fields), this limits soundness of the proof. Setting this to a
+    /// value larger than the size of the base field makes [validate()](ComputationContext::validate)
+    /// fail for options which do not specify a large enough extension; thus, both the prover and
+    /// the verifier refuse such options. This is 0 by default, i.e., any extension is accepted.
+    pub fn with_min_field_bits(mut self, min_field_bits: u32) -> Self {
+        self.min_field_bits = min_field_bits;
+        self
+    }
+
+    // VALIDATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that the parameters of this context are consistent with the specified base field.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * the size of the LDE domain is not a power of two;
+    /// * the LDE domain does not fit into the two-adic subgroup of the field, or if it covers
+    ///   at least half of the field's multiplicative group;
+    /// * the number of queries (including FRI queries) exceeds half of the LDE domain size; in
+    ///   such a case, most queries would be drawn from the same positions and would not add to
+    ///   proof soundness;
+    /// * the grinding factor exceeds the size of the field (or its extension, if extension is
+    ///   enabled), as no proof can provide more security than that;
+    /// * the field from which the out-of-domain point is drawn is smaller than required via
+    ///   [with_min_field_bits()](ComputationContext::with_min_field_bits).
+    pub fn validate<B: StarkField>(&self) -> Result<(), ParameterError> {
+        let lde_domain_depth = log2(self.lde_domain_size())
+            .map_err(|_| ParameterError::LdeDomainSizeNotPowerOfTwo(self.lde_domain_size()))?;
+        if lde_domain_depth > B::max_two_adicity() {
+            return Err(ParameterError::LdeDomainTooLarge(
+                lde_domain_depth,
+                B::max_two_adicity(),
+            ));
+        }
+        if lde_domain_depth + 1 >= B::MODULUS_BITS {
+            return Err(ParameterError::LdeDomainExhaustsField(
+                lde_domain_depth,
+                B::MODULUS_BITS,
+            ));
         }
+
+        let num_queries = self.options.num_fri_queries();
+        if num_queries > self.lde_domain_size() / 2 {
+            return Err(ParameterError::TooManyQueries(
+                num_queries,
+                self.lde_domain_size(),
+            ));
+        }
+
+        let field_bits = B::MODULUS_BITS * self.options.field_extension() as u32;
+        if self.options.grinding_factor() > field_bits {
+            return Err(ParameterError::GrindingFactorTooLarge(
+                self.options.grinding_factor(),
+                field_bits,
+            ));
+        }
+        if field_bits < self.min_field_bits {
+            return Err(ParameterError::FieldTooSmall(
+                field_bits,
+                self.min_field_bits,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the constraint evaluation blowup factor implied by transition constraint
+    /// degrees does not exceed `max_ce_blowup_factor`.
+    ///
+    /// This can be used as a strict mode to make sure that changes to constraints do not make
+    /// constraint evaluation unexpectedly more expensive.
+    ///
+    /// # Errors
+    /// Returns an error identifying the first constraint which requires a larger blowup factor.
+    pub fn validate_ce_blowup_factor(
+        &self,
+        max_ce_blowup_factor: usize,
+    ) -> Result<(), ParameterError> {
+        if self.ce_blowup_factor <= max_ce_blowup_factor {
+            return Ok(());
+        }
+        let report = self.ce_blowup_report();
+        Err(ParameterError::CeBlowupFactorTooLarge(
+            report.driving_constraints[0],
+            self.ce_blowup_factor,
+            max_ce_blowup_factor,
+        ))
     }
 
     // TRACE INFO
@@ -92,6 +339,51 @@ impl ComputationContext {
         self.trace_length
     }
 
+    /// Returns indexes of registers, in ascending order, whose values in the next state may be
+    /// referenced by transition constraints; evaluations of these registers at z * g are
+    /// included in the out-of-domain frame. Unless some registers were marked as local via
+    /// [with_local_registers()](ComputationContext::with_local_registers), this includes all
+    /// registers.
+    pub fn next_state_registers(&self) -> &[usize] {
+        &self.next_state_registers
+    }
+
+    /// Returns the identifier of the AIR set via
+    /// [with_air_id()](ComputationContext::with_air_id), if any.
+    pub fn air_id(&self) -> Option<[u8; 32]> {
+        self.air_id
+    }
+
+    /// Returns the smallest size of the field for the out-of-domain point set via
+    /// [with_min_field_bits()](ComputationContext::with_min_field_bits); this is 0 by default.
+    pub fn min_field_bits(&self) -> u32 {
+        self.min_field_bits
+    }
+
+    /// Returns the number of extra polynomials set via
+    /// [with_extra_polys()](ComputationContext::with_extra_polys); this is 0 by default.
+    pub fn num_extra_polys(&self) -> usize {
+        self.num_extra_polys
+    }
+
+    /// Returns the number of registers in the auxiliary segment of the trace set via
+    /// [with_aux_segment()](ComputationContext::with_aux_segment); this is 0 by default.
+    pub fn aux_segment_width(&self) -> usize {
+        self.aux_segment_width
+    }
+
+    /// Returns the number of registers in the main segment of the trace; these are all registers
+    /// of the trace unless the trace has an auxiliary segment.
+    pub fn main_trace_width(&self) -> usize {
+        self.trace_width - self.aux_segment_width
+    }
+
+    /// Returns the number of random elements used to build the auxiliary segment of the trace;
+    /// this is 0 when the trace has no auxiliary segment.
+    pub fn num_aux_rand_elements(&self) -> usize {
+        self.num_aux_rand_elements
+    }
+
     // CONSTRAINT INFO
     // --------------------------------------------------------------------------------------------
 
@@ -111,6 +403,35 @@ impl ComputationContext {
         self.trace_length * self.ce_blowup_factor()
     }
 
+    /// Returns a report describing which transition constraints determine the constraint
+    /// evaluation blowup factor, and how much cheaper constraint evaluation would be if the
+    /// degree of these constraints was lowered.
+    pub fn ce_blowup_report(&self) -> CeBlowupReport {
+        let constraint_blowup_factors = self
+            .transition_constraint_degrees
+            .iter()
+            .map(|degree| degree.min_blowup_factor(self.trace_length))
+            .collect::<Vec<_>>();
+
+        let mut driving_constraints = Vec::new();
+        let mut next_ce_blowup_factor = MIN_CE_BLOWUP_FACTOR;
+        for (i, &blowup_factor) in constraint_blowup_factors.iter().enumerate() {
+            if blowup_factor == self.ce_blowup_factor {
+                driving_constraints.push(i);
+            } else if blowup_factor > next_ce_blowup_factor {
+                next_ce_blowup_factor = blowup_factor;
+            }
+        }
+
+        CeBlowupReport {
+            ce_blowup_factor: self.ce_blowup_factor,
+            constraint_blowup_factors,
+            driving_constraints,
+            next_ce_blowup_factor: cmp::min(next_ce_blowup_factor, self.ce_blowup_factor),
+            ce_domain_size: self.ce_domain_size(),
+        }
+    }
+
     pub fn transition_constraint_degrees(&self) -> &[TransitionConstraintDegree] {
         &self.transition_constraint_degrees
     }
@@ -123,6 +444,15 @@ impl ComputationContext {
         self.composition_degree() - 1
     }
 
+    /// Returns the number of coefficients which the FRI remainder polynomial is allowed to
+    /// have; this is the degree bound of the composition polynomial reduced by every FRI layer.
+    pub fn fri_remainder_degree_bound<B: StarkField>(&self) -> usize {
+        let lde_domain_size = self.lde_domain_size();
+        let fri_options = self.options.to_fri_options::<B>();
+        let remainder_length = fri_options.fri_remainder_length(lde_domain_size);
+        (self.composition_degree() + 1) / (lde_domain_size / remainder_length)
+    }
+
     // OTHER PROPERTIES
     // --------------------------------------------------------------------------------------------
 
@@ -134,17 +464,147 @@ impl ComputationContext {
         self.options.domain_offset()
     }
 
+    /// Returns an estimate of the work performed by the verifier to verify a proof of the
+    /// computation described by this context over base field `B`.
+    pub fn estimate_verification_cost<B: StarkField>(&self) -> VerificationCost {
+        VerificationCost::estimate_with_next_state::<B>(
+            &self.options,
+            self.trace_width,
+            self.next_state_registers.len(),
+            self.trace_length,
+        )
+    }
+
+    /// Returns an estimate of the peak amount of memory (in bytes) used by the prover to generate
+    /// a proof of the computation described by this context over base field `B`.
+    ///
+    /// The estimate accounts only for the largest buffers allocated by the prover: trace
+    /// polynomials, the extended trace and its Merkle tree, constraint evaluations and their
+    /// commitment, the DEEP composition polynomial, and FRI layers together with their Merkle
+    /// trees. All of these are assumed to be held in memory at the same time.
+    pub fn estimate_prover_memory<B: StarkField>(&self) -> usize {
+        const DIGEST_BYTES: usize = 32;
+        let base_bytes = B::ELEMENT_BYTES;
+        let ext_bytes = base_bytes * self.options.field_extension().degree();
+        let lde_domain_size = self.lde_domain_size();
+
+        // trace polynomials and the extended trace; a Merkle tree has as many internal nodes
+        // as it has leaves
+        let num_trace_leaves = lde_domain_size / self.options.trace_leaf_batching();
+        let trace = self.trace_width * (self.trace_length + lde_domain_size) * base_bytes
+            + 2 * num_trace_leaves * DIGEST_BYTES;
+
+        // transition and boundary constraint evaluations over the constraint evaluation domain,
+        // and the constraint polynomial evaluated over the LDE domain; evaluations are hashed
+        // one per leaf in the worst case
+        let constraints = 2 * self.ce_domain_size() * ext_bytes
+            + lde_domain_size * (ext_bytes + 2 * DIGEST_BYTES);
+
+        // DEEP composition polynomial and FRI layers; the first FRI layer holds the evaluations
+        // of the composition polynomial grouped into leaves of 4 elements, and every subsequent
+        // layer is 4 times smaller than the previous one
+        let composition = lde_domain_size * ext_bytes;
+        let fri = (lde_domain_size * ext_bytes + lde_domain_size / 2 * DIGEST_BYTES) * 4 / 3;
+
+        trace + constraints + composition + fri
+    }
+
+    // CACHED VALUES
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns polynomials of periodic columns for the computation described by this context;
+    /// the polynomials are built using `init` on the first call and are returned from the cache
+    /// on subsequent calls.
+    ///
+    /// The cache is not generic over the field, and thus, the polynomials are stored as a
+    /// type-erased value; this is the reason for the `B: 'static` requirement of StarkField.
+    ///
+    /// # Panics
+    /// Panics if the polynomials were previously cached for a different field.
+    pub(crate) fn get_or_init_periodic_column_polys<B, F>(&self, init: F) -> &[Vec<B>]
+    where
+        B: StarkField,
+        F: FnOnce() -> Vec<Vec<B>>,
+    {
+        self.periodic_column_polys
+            .get_or_init(|| Arc::new(init()))
+            .downcast_ref::<Vec<Vec<B>>>()
+            .expect("periodic column polynomials were cached for a different field")
+    }
+
+    /// Returns polynomials of periodic columns cached in this context, or None if the
+    /// polynomials have not been built yet or were cached for a different field.
+    pub fn cached_periodic_column_polys<B: StarkField>(&self) -> Option<Arc<Vec<Vec<B>>>> {
+        self.periodic_column_polys
+            .get()
+            .and_then(|polys| polys.clone().downcast::<Vec<Vec<B>>>().ok())
+    }
+
+    /// Places the specified periodic column polynomials into the cache of this context; the
+    /// polynomials will then be returned from
+    /// [Air::get_periodic_column_polys()](crate::Air::get_periodic_column_polys) without
+    /// interpolating the columns. Returns false if polynomials have already been cached.
+    ///
+    /// This allows polynomials built for one instance of an AIR to be re-used by other instances
+    /// of the same AIR; the caller is responsible for making sure that the polynomials were built
+    /// from the same periodic columns.
+    #[doc(hidden)]
+    pub fn set_periodic_column_polys<B: StarkField>(&self, polys: Arc<Vec<Vec<B>>>) -> bool {
+        self.periodic_column_polys.set(polys).is_ok()
+    }
+
+    /// Returns the layout of constraints for the computation described by this context; the
+    /// layout is built on the first call and is returned from the cache on subsequent calls.
+    ///
+    /// # Panics
+    /// Panics if the layout was previously cached for a different field.
+    pub fn constraint_layout<B: StarkField>(&self) -> &ConstraintLayout<B> {
+        self.constraint_layout
+            .get_or_init(|| Arc::new(ConstraintLayout::<B>::new(self)))
+            .downcast_ref::<ConstraintLayout<B>>()
+            .expect("constraint layout was cached for a different field")
+    }
+
+    /// Returns the layout of constraints cached in this context, or None if the layout has not
+    /// been built yet or was cached for a different field.
+    pub fn cached_constraint_layout<B: StarkField>(&self) -> Option<Arc<ConstraintLayout<B>>> {
+        self.constraint_layout
+            .get()
+            .and_then(|layout| layout.clone().downcast::<ConstraintLayout<B>>().ok())
+    }
+
+    /// Places the specified constraint layout into the cache of this context; returns false if a
+    /// layout has already been cached. The caller is responsible for making sure that the layout
+    /// was built for a context with the same trace length, constraint degrees, and options.
+    #[doc(hidden)]
+    pub fn set_constraint_layout<B: StarkField>(&self, layout: Arc<ConstraintLayout<B>>) -> bool {
+        self.constraint_layout.set(layout).is_ok()
+    }
+
     // UTILITY FUNCTIONS
     // --------------------------------------------------------------------------------------------
 
     /// Returns a generator of the trace domain in the specified STARK field.
+    ///
+    /// # Panics
+    /// Panics if the trace domain is too large for the specified field.
     pub fn get_trace_domain_generator<B: StarkField>(&self) -> B {
-        B::get_root_of_unity(log2(self.trace_length()))
+        B::get_root_of_unity(log2_unchecked(self.trace_length()))
+            .unwrap_or_else(|err| panic!("invalid trace length {}: {}", self.trace_length(), err))
     }
 
     /// Returns a generator of the LDE domain in the specified STARK field.
+    ///
+    /// # Panics
+    /// Panics if the LDE domain is too large for the specified field.
     pub fn get_lde_domain_generator<B: StarkField>(&self) -> B {
-        B::get_root_of_unity(log2(self.lde_domain_size()))
+        B::get_root_of_unity(log2_unchecked(self.lde_domain_size())).unwrap_or_else(|err| {
+            panic!(
+                "invalid LDE domain size {}: {}",
+                self.lde_domain_size(),
+                err
+            )
+        })
     }
 
     /// Returns g^step, where g is the generator of trace domain.
@@ -0,0 +1,61 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::fmt;
+
+// CONSTRAINT EVALUATION BLOWUP REPORT
+// ================================================================================================
+
+/// Describes how the constraint evaluation (CE) blowup factor of a computation was determined.
+///
+/// The CE blowup factor is the smallest power of two which allows evaluating the highest-degree
+/// transition constraint; the prover evaluates all transition constraints over a domain which is
+/// this many times larger than the execution trace. Thus, a single constraint of a high degree
+/// can make constraint evaluation for the entire computation several times more expensive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CeBlowupReport {
+    /// Blowup factor used for constraint evaluation.
+    pub ce_blowup_factor: usize,
+    /// Minimum blowup factor required by each transition constraint, in the order in which
+    /// transition constraint degrees were specified.
+    pub constraint_blowup_factors: Vec<usize>,
+    /// Indexes of transition constraints which require the CE blowup factor; lowering the
+    /// degree of these constraints is the only way to reduce the CE blowup factor.
+    pub driving_constraints: Vec<usize>,
+    /// Blowup factor which would be used if the driving constraints required the same blowup
+    /// factor as the most demanding of the remaining constraints.
+    pub next_ce_blowup_factor: usize,
+    /// Size of the domain over which transition constraints are evaluated.
+    pub ce_domain_size: usize,
+}
+
+impl CeBlowupReport {
+    /// Returns the number of constraint evaluations the prover performs per evaluation it would
+    /// perform if the driving constraints were lowered to the next CE blowup factor.
+    pub fn cost_ratio(&self) -> usize {
+        self.ce_blowup_factor / self.next_ce_blowup_factor
+    }
+}
+
+impl fmt::Display for CeBlowupReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "constraint evaluation blowup factor is {} (domain size {}), required by \
+            constraints {:?}",
+            self.ce_blowup_factor, self.ce_domain_size, self.driving_constraints
+        )?;
+        if self.cost_ratio() > 1 {
+            write!(
+                f,
+                "; without them, the blowup factor would be {} and constraint evaluation would \
+                be {} times cheaper",
+                self.next_ce_blowup_factor,
+                self.cost_ratio()
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ProvingCost, VerificationCost};
+use crate::{limits, proof::get_security_level, FieldExtension, HashFunction, ProofOptions};
+use core::cmp::Ordering;
+use math::field::StarkField;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Smallest blowup factor required by any transition constraint.
+const MIN_CE_BLOWUP_FACTOR: usize = 2;
+
+/// Smallest relative reduction in proving time for which a larger proof is worth it; smaller
+/// reductions are within the error of proving time estimates.
+const MIN_PROVING_TIME_REDUCTION: f64 = 0.01;
+
+/// Number of constraints used for security estimates; candidate options draw independent
+/// composition coefficients, and thus, the number of constraints does not affect security.
+const NUM_CONSTRAINTS: usize = 0;
+
+// COST MODEL
+// ================================================================================================
+
+/// Time (in nanoseconds) it takes to perform basic operations on the target machine; used to
+/// turn estimates of the work performed by the prover and by the verifier into time estimates.
+///
+/// The default values roughly correspond to BLAKE3 and a 128-bit field on a single core of a
+/// modern CPU.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CostModel {
+    /// Fixed cost of a single hash function invocation.
+    pub hash: f64,
+    /// Cost of passing a single byte to the hash function.
+    pub hashed_byte: f64,
+    /// Cost of a single field multiplication.
+    pub field_mult: f64,
+    /// Cost of a single field inversion.
+    pub field_inversion: f64,
+}
+
+impl CostModel {
+    /// Returns estimated time (in nanoseconds) it takes the verifier to perform the work
+    /// described by `cost`.
+    pub fn verification_time(&self, cost: &VerificationCost) -> f64 {
+        cost.num_hashes as f64 * self.hash
+            + cost.hashed_bytes as f64 * self.hashed_byte
+            + cost.num_field_mults as f64 * self.field_mult
+            + cost.num_field_inversions as f64 * self.field_inversion
+    }
+
+    /// Returns estimated time (in nanoseconds) it takes the prover to perform the work
+    /// described by `cost`.
+    pub fn proving_time(&self, cost: &ProvingCost) -> f64 {
+        cost.num_hashes as f64 * self.hash
+            + cost.hashed_bytes as f64 * self.hashed_byte
+            + cost.num_field_mults as f64 * self.field_mult
+    }
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        CostModel {
+            hash: 100.0,
+            hashed_byte: 1.0,
+            field_mult: 5.0,
+            field_inversion: 500.0,
+        }
+    }
+}
+
+// PARAMETER CANDIDATE
+// ================================================================================================
+
+/// Proof options found by [ParameterSearch] together with estimates of the resulting proof.
+#[derive(Clone)]
+pub struct ParameterCandidate {
+    /// Proof options to generate proofs with.
+    pub options: ProofOptions,
+    /// Conjectured security level (in bits) of the resulting proofs.
+    pub security_level: u32,
+    /// Approximate size of the resulting proofs in bytes.
+    pub proof_size: usize,
+    /// Estimated time (in nanoseconds) it takes to generate a proof.
+    pub proving_time: f64,
+    /// Estimated time (in nanoseconds) it takes to verify a proof.
+    pub verification_time: f64,
+}
+
+// PARAMETER SEARCH
+// ================================================================================================
+
+/// Searches the space of proof options for the ones which reach a target security level for a
+/// computation of the specified dimensions, and returns the Pareto frontier of proof size vs.
+/// proving time.
+///
+/// The search goes over blowup factors, grinding factors, and FRI remainder degrees; for every
+/// combination of these, the smallest number of queries which reaches the target security level
+/// is used, as additional queries can only make the proof larger and slower to generate. The
+/// FRI folding factor is fixed at 4, and thus, the FRI remainder degree is the only parameter
+/// which controls the number of FRI layers.
+///
+/// Proof sizes and proving times are estimated via [VerificationCost] and [ProvingCost], and
+/// are converted into time estimates via a [CostModel]. Since constraint evaluation is
+/// approximated, proving time estimates are most useful for comparing candidates with each
+/// other.
+#[derive(Clone, Debug)]
+pub struct ParameterSearch {
+    security_level: u32,
+    trace_width: usize,
+    trace_length: usize,
+    ce_blowup_factor: usize,
+    hash_fn: HashFunction,
+    field_extension: FieldExtension,
+    cost_model: CostModel,
+    max_verification_time: Option<f64>,
+    max_grinding_factor: u32,
+}
+
+impl ParameterSearch {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new search for proof options which reach the specified conjectured security
+    /// level (in bits) for a computation with the specified trace dimensions.
+    ///
+    /// By default, the CE blowup factor of the computation is assumed to be 2 (i.e., transition
+    /// constraints are of degree 2), proofs are generated with BLAKE3 and without field
+    /// extension, grinding factor is at most 20 bits, and verification time is not limited.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `trace_width` is zero or greater than [limits::MAX_TRACE_WIDTH].
+    /// * `trace_length` is smaller than [limits::MIN_TRACE_LENGTH] or is not a power of two.
+    pub fn new(security_level: u32, trace_width: usize, trace_length: usize) -> Self {
+        assert!(trace_width > 0, "trace width must be greater than 0");
+        assert!(
+            trace_width <= limits::MAX_TRACE_WIDTH,
+            "trace width cannot be greater than {}, but was {}",
+            limits::MAX_TRACE_WIDTH,
+            trace_width
+        );
+        assert!(
+            trace_length >= limits::MIN_TRACE_LENGTH,
+            "trace length must be at least {}, but was {}",
+            limits::MIN_TRACE_LENGTH,
+            trace_length
+        );
+        assert!(
+            trace_length.is_power_of_two(),
+            "trace length must be a power of 2, but was {}",
+            trace_length
+        );
+
+        ParameterSearch {
+            security_level,
+            trace_width,
+            trace_length,
+            ce_blowup_factor: MIN_CE_BLOWUP_FACTOR,
+            hash_fn: HashFunction::Blake3_256,
+            field_extension: FieldExtension::None,
+            cost_model: CostModel::default(),
+            max_verification_time: None,
+            max_grinding_factor: 20,
+        }
+    }
+
+    /// Returns this search with the CE blowup factor of the computation set to the specified
+    /// value; the blowup factor must be a power of two and at least 2.
+    pub fn with_ce_blowup_factor(mut self, ce_blowup_factor: usize) -> Self {
+        assert!(
+            ce_blowup_factor.is_power_of_two() && ce_blowup_factor >= MIN_CE_BLOWUP_FACTOR,
+            "CE blowup factor must be a power of 2 greater than 1, but was {}",
+            ce_blowup_factor
+        );
+        self.ce_blowup_factor = ce_blowup_factor;
+        self
+    }
+
+    /// Returns this search with proofs generated using the specified hash function.
+    pub fn with_hash_fn(mut self, hash_fn: HashFunction) -> Self {
+        self.hash_fn = hash_fn;
+        self
+    }
+
+    /// Returns this search with proofs generated using the specified field extension.
+    pub fn with_field_extension(mut self, field_extension: FieldExtension) -> Self {
+        self.field_extension = field_extension;
+        self
+    }
+
+    /// Returns this search with prover and verifier work converted into time using the
+    /// specified cost model.
+    pub fn with_cost_model(mut self, cost_model: CostModel) -> Self {
+        self.cost_model = cost_model;
+        self
+    }
+
+    /// Returns this search with candidates which take longer than `max_time` nanoseconds to
+    /// verify (according to the cost model) excluded from the results.
+    pub fn with_max_verification_time(mut self, max_time: f64) -> Self {
+        self.max_verification_time = Some(max_time);
+        self
+    }
+
+    /// Returns this search with the grinding factor limited to the specified number of bits;
+    /// the limit cannot be greater than [limits::MAX_GRINDING_FACTOR].
+    pub fn with_max_grinding_factor(mut self, max_grinding_factor: u32) -> Self {
+        assert!(
+            max_grinding_factor <= limits::MAX_GRINDING_FACTOR,
+            "grinding factor cannot be greater than {}",
+            limits::MAX_GRINDING_FACTOR
+        );
+        self.max_grinding_factor = max_grinding_factor;
+        self
+    }
+
+    // SEARCH
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the Pareto frontier of proof size vs. proving time for proofs over base field
+    /// `B` which reach the target security level.
+    ///
+    /// Candidates are sorted by proof size in ascending order; thus, every candidate results in
+    /// a faster prover than all candidates before it. Candidates which reduce proving time by
+    /// less than 1% as compared to a candidate with a smaller proof are omitted. The result is empty if the target
+    /// security level cannot be reached (e.g., because it exceeds the security provided by the
+    /// field or by the hash function).
+    pub fn run<B: StarkField>(&self) -> Vec<ParameterCandidate> {
+        let mut candidates = Vec::new();
+        for blowup_factor in self.get_blowup_factors::<B>() {
+            for grinding_factor in 0..=self.max_grinding_factor {
+                let options =
+                    match self.get_min_queries_options::<B>(blowup_factor, grinding_factor) {
+                        Some(options) => options,
+                        None => continue,
+                    };
+
+                // by default, the remainder has at most 256 elements; in addition, try all
+                // remainder degrees which can be requested explicitly
+                candidates.push(self.build_candidate::<B>(options.clone()));
+                let mut degree_bound = 1;
+                while degree_bound <= limits::MAX_FRI_REMAINDER_DEGREE + 1 {
+                    let options = options
+                        .clone()
+                        .with_fri_max_remainder_degree(degree_bound - 1);
+                    candidates.push(self.build_candidate::<B>(options));
+                    degree_bound *= 2;
+                }
+            }
+        }
+
+        if let Some(max_time) = self.max_verification_time {
+            candidates.retain(|c| c.verification_time <= max_time);
+        }
+
+        get_pareto_frontier(candidates)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns blowup factors which are large enough for the CE blowup factor of the
+    /// computation, and small enough for the LDE domain to fit into the field `B`.
+    fn get_blowup_factors<B: StarkField>(&self) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut blowup_factor = limits::MIN_BLOWUP_FACTOR;
+        while blowup_factor <= limits::MAX_BLOWUP_FACTOR {
+            if blowup_factor > self.ce_blowup_factor
+                && self.trace_length <= limits::max_trace_length::<B>(blowup_factor)
+            {
+                result.push(blowup_factor);
+            }
+            blowup_factor *= 2;
+        }
+        result
+    }
+
+    /// Returns proof options with the smallest number of queries which reaches the target
+    /// security level with the specified blowup and grinding factors, or None if the target
+    /// security level cannot be reached with these factors.
+    fn get_min_queries_options<B: StarkField>(
+        &self,
+        blowup_factor: usize,
+        grinding_factor: u32,
+    ) -> Option<ProofOptions> {
+        let lde_domain_depth = (self.trace_length * blowup_factor).trailing_zeros();
+        (limits::MIN_QUERIES..=limits::MAX_QUERIES)
+            .map(|num_queries| {
+                ProofOptions::new(
+                    num_queries,
+                    blowup_factor,
+                    grinding_factor,
+                    self.hash_fn,
+                    self.field_extension,
+                )
+            })
+            .find(|options| {
+                let security_level = get_security_level(
+                    options,
+                    self.ce_blowup_factor,
+                    grinding_factor,
+                    B::MODULUS_BITS,
+                    lde_domain_depth,
+                    NUM_CONSTRAINTS,
+                    true,
+                );
+                security_level >= self.security_level
+            })
+    }
+
+    /// Returns a candidate for the specified options with proof size and timing estimates.
+    fn build_candidate<B: StarkField>(&self, options: ProofOptions) -> ParameterCandidate {
+        let verification_cost =
+            VerificationCost::estimate::<B>(&options, self.trace_width, self.trace_length);
+        let proving_cost = ProvingCost::estimate::<B>(
+            &options,
+            self.trace_width,
+            self.trace_length,
+            self.ce_blowup_factor,
+        );
+        let lde_domain_depth = (self.trace_length * options.blowup_factor()).trailing_zeros();
+
+        ParameterCandidate {
+            security_level: get_security_level(
+                &options,
+                self.ce_blowup_factor,
+                options.grinding_factor(),
+                B::MODULUS_BITS,
+                lde_domain_depth,
+                NUM_CONSTRAINTS,
+                true,
+            ),
+            proof_size: verification_cost.proof_size,
+            proving_time: self.cost_model.proving_time(&proving_cost),
+            verification_time: self.cost_model.verification_time(&verification_cost),
+            options,
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns candidates which are not dominated by any other candidate sorted by proof size; a
+/// candidate is dominated if another candidate has a smaller proof size and a proving time
+/// which is larger by less than [MIN_PROVING_TIME_REDUCTION], or the same proof size and a
+/// smaller proving time.
+fn get_pareto_frontier(mut candidates: Vec<ParameterCandidate>) -> Vec<ParameterCandidate> {
+    candidates.sort_by(|a, b| {
+        a.proof_size.cmp(&b.proof_size).then_with(|| {
+            a.proving_time
+                .partial_cmp(&b.proving_time)
+                .unwrap_or(Ordering::Equal)
+        })
+    });
+
+    let mut result: Vec<ParameterCandidate> = Vec::new();
+    for candidate in candidates {
+        match result.last() {
+            Some(last)
+                if candidate.proving_time
+                    > last.proving_time * (1.0 - MIN_PROVING_TIME_REDUCTION) => {}
+            _ => result.push(candidate),
+        }
+    }
+    result
+}
@@ -0,0 +1,430 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ComputationContext, CostModel, ParameterSearch, ProvingCost, VerificationCost};
+use crate::{
+    errors::ParameterError, ExtensionVisitor, FieldExtension, HashFunction, ProofOptions,
+    TraceRowHashing, TransitionConstraintDegree,
+};
+use math::field::{f128, f62, ExtensionOf, FieldElement, StarkField};
+use std::sync::Arc;
+
+// VALIDATION
+// ================================================================================================
+
+#[test]
+fn validate() {
+    let context = build_context(1 << 10, 32, 8);
+    assert_eq!(Ok(()), context.validate::<f128::BaseElement>());
+    assert_eq!(Ok(()), context.validate::<f62::BaseElement>());
+
+    // LDE domain of size 2^38 fits into both fields
+    let context = build_context(1 << 35, 32, 8);
+    assert_eq!(Ok(()), context.validate::<f128::BaseElement>());
+    assert_eq!(Ok(()), context.validate::<f62::BaseElement>());
+}
+
+#[test]
+fn validate_lde_domain_too_large() {
+    // LDE domain of size 2^40 fits into f128 field, but not into f62 field
+    let context = build_context(1 << 37, 32, 8);
+    assert_eq!(Ok(()), context.validate::<f128::BaseElement>());
+    assert_eq!(
+        Err(ParameterError::LdeDomainTooLarge(40, 39)),
+        context.validate::<f62::BaseElement>()
+    );
+
+    let context = build_context(1 << 38, 32, 8);
+    assert_eq!(
+        Err(ParameterError::LdeDomainTooLarge(41, 40)),
+        context.validate::<f128::BaseElement>()
+    );
+}
+
+#[test]
+fn validate_too_many_queries() {
+    // LDE domain size is 32, so at most 16 queries are allowed
+    let context = build_context(8, 16, 4);
+    assert_eq!(Ok(()), context.validate::<f128::BaseElement>());
+
+    let context = build_context(8, 17, 4);
+    assert_eq!(
+        Err(ParameterError::TooManyQueries(17, 32)),
+        context.validate::<f128::BaseElement>()
+    );
+
+    // FRI queries are drawn from the same domain, and thus, are subject to the same limit
+    let options = build_options(8, 4).with_num_fri_queries(17);
+    let t_degrees = vec![TransitionConstraintDegree::new(2)];
+    let context = ComputationContext::new(4, 8, t_degrees, options);
+    assert_eq!(
+        Err(ParameterError::TooManyQueries(17, 32)),
+        context.validate::<f128::BaseElement>()
+    );
+}
+
+#[test]
+fn validate_min_field_bits() {
+    // the 62-bit field without an extension is refused when at least 100 bits are required
+    let context = build_context(8, 16, 4).with_min_field_bits(100);
+    assert_eq!(100, context.min_field_bits());
+    assert_eq!(
+        Err(ParameterError::FieldTooSmall(
+            f62::BaseElement::MODULUS_BITS,
+            100
+        )),
+        context.validate::<f62::BaseElement>()
+    );
+    assert_eq!(Ok(()), context.validate::<f128::BaseElement>());
+
+    // the quadratic extension of the 62-bit field is large enough
+    let options = ProofOptions::new(
+        16,
+        4,
+        0,
+        HashFunction::Blake3_256,
+        FieldExtension::Quadratic,
+    );
+    let t_degrees = vec![TransitionConstraintDegree::new(2)];
+    let context = ComputationContext::new(4, 8, t_degrees, options).with_min_field_bits(100);
+    assert_eq!(Ok(()), context.validate::<f62::BaseElement>());
+}
+
+// CE BLOWUP REPORT
+// ================================================================================================
+
+#[test]
+fn ce_blowup_report() {
+    let trace_length = 64;
+    let t_degrees = vec![
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::with_cycles(3, vec![8, 8]),
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(5),
+    ];
+    let context = ComputationContext::new(4, trace_length, t_degrees, build_options(32, 16));
+
+    let report = context.ce_blowup_report();
+    assert_eq!(8, report.ce_blowup_factor);
+    assert_eq!(vec![2, 8, 4, 8], report.constraint_blowup_factors);
+    assert_eq!(vec![1, 3], report.driving_constraints);
+    assert_eq!(4, report.next_ce_blowup_factor);
+    assert_eq!(512, report.ce_domain_size);
+    assert_eq!(2, report.cost_ratio());
+
+    // when all constraints require the same blowup factor, there is nothing to gain
+    let context = build_context(trace_length, 32, 8);
+    let report = context.ce_blowup_report();
+    assert_eq!(vec![0], report.driving_constraints);
+    assert_eq!(2, report.next_ce_blowup_factor);
+    assert_eq!(1, report.cost_ratio());
+}
+
+#[test]
+fn validate_ce_blowup_factor() {
+    let t_degrees = vec![
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::new(3),
+    ];
+    let context = ComputationContext::new(4, 64, t_degrees, build_options(32, 16));
+    assert_eq!(Ok(()), context.validate_ce_blowup_factor(4));
+    assert_eq!(Ok(()), context.validate_ce_blowup_factor(8));
+    assert_eq!(
+        Err(ParameterError::CeBlowupFactorTooLarge(1, 4, 2)),
+        context.validate_ce_blowup_factor(2)
+    );
+}
+
+// VERIFICATION COST
+// ================================================================================================
+
+#[test]
+fn estimate_verification_cost() {
+    // LDE domain of size 64 is smaller than FRI remainder, so there are no FRI layers
+    let context = build_context(8, 2, 8);
+    let cost = context.estimate_verification_cost::<f128::BaseElement>();
+    assert_eq!(42, cost.num_hashes);
+    assert_eq!(2624, cost.hashed_bytes);
+    assert_eq!(636, cost.num_field_mults);
+    assert_eq!(26, cost.num_field_inversions);
+    assert_eq!(2136, cost.proof_size);
+    assert_eq!(
+        cost,
+        VerificationCost::estimate::<f128::BaseElement>(context.options(), 4, 8)
+    );
+}
+
+#[test]
+fn estimate_verification_cost_scaling() {
+    let base = build_context(1 << 12, 32, 8).estimate_verification_cost::<f128::BaseElement>();
+
+    // more queries require more work and result in larger proofs
+    let cost = build_context(1 << 12, 48, 8).estimate_verification_cost::<f128::BaseElement>();
+    assert!(cost.num_hashes > base.num_hashes);
+    assert!(cost.num_field_mults > base.num_field_mults);
+    assert!(cost.proof_size > base.proof_size);
+
+    // longer traces require longer authentication paths and more FRI layers
+    let cost = build_context(1 << 16, 32, 8).estimate_verification_cost::<f128::BaseElement>();
+    assert!(cost.num_hashes > base.num_hashes);
+    assert!(cost.proof_size > base.proof_size);
+
+    // field extension adds DEEP quotients and doubles the size of composition values
+    let options = ProofOptions::new(
+        32,
+        8,
+        0,
+        HashFunction::Blake3_256,
+        FieldExtension::Quadratic,
+    );
+    let cost = VerificationCost::estimate::<f128::BaseElement>(&options, 4, 1 << 12);
+    assert!(cost.num_field_inversions > base.num_field_inversions);
+    assert!(cost.proof_size > base.proof_size);
+    // batching trace rows into leaves shortens trace authentication paths
+    let options = build_options(32, 8).with_trace_leaf_batching(4);
+    let cost = VerificationCost::estimate::<f128::BaseElement>(&options, 4, 1 << 12);
+    assert!(cost.num_hashes < base.num_hashes);
+    assert_eq!(base.num_field_mults, cost.num_field_mults);
+
+    // chunked row hashing hashes every chunk of an opened row, but does not change proof size
+    let options = build_options(32, 8).with_trace_row_hashing(TraceRowHashing::Chunked(1));
+    let cost = VerificationCost::estimate::<f128::BaseElement>(&options, 4, 1 << 12);
+    assert!(cost.num_hashes > base.num_hashes);
+    assert!(cost.hashed_bytes > base.hashed_bytes);
+    assert_eq!(base.proof_size, cost.proof_size);
+
+    // extra FRI queries open FRI layers only, so they add less work than regular queries
+    let options = build_options(32, 8).with_num_fri_queries(48);
+    let fri_cost = VerificationCost::estimate::<f128::BaseElement>(&options, 4, 1 << 12);
+    let cost = build_context(1 << 12, 48, 8).estimate_verification_cost::<f128::BaseElement>();
+    assert!(fri_cost.num_hashes > base.num_hashes);
+    assert!(fri_cost.num_hashes < cost.num_hashes);
+    assert!(fri_cost.proof_size < cost.proof_size);
+    assert_eq!(base.num_field_inversions, fri_cost.num_field_inversions);
+}
+
+#[test]
+fn estimate_prover_memory() {
+    // trace: 4 * (8 + 64) * 16 + 2 * 64 * 32 = 8704; constraints: 2 * 16 * 16 + 64 * (16 + 64)
+    // = 5632; composition: 64 * 16 = 1024; FRI: (64 * 16 + 32 * 32) * 4 / 3 = 2730
+    let context = build_context(8, 32, 8);
+    assert_eq!(16, context.ce_domain_size());
+    assert_eq!(18090, context.estimate_prover_memory::<f128::BaseElement>());
+
+    // memory grows with the length of the trace and with the size of field elements
+    let base = build_context(1 << 12, 32, 8).estimate_prover_memory::<f128::BaseElement>();
+    let longer = build_context(1 << 13, 32, 8).estimate_prover_memory::<f128::BaseElement>();
+    assert!(longer > base * 3 / 2);
+    let smaller = build_context(1 << 12, 32, 8).estimate_prover_memory::<f62::BaseElement>();
+    assert!(smaller < base);
+
+    // field extension doubles the size of constraint and composition values
+    let options = ProofOptions::new(
+        32,
+        8,
+        0,
+        HashFunction::Blake3_256,
+        FieldExtension::Quadratic,
+    );
+    let t_degrees = vec![TransitionConstraintDegree::new(2)];
+    let context = ComputationContext::new(4, 1 << 12, t_degrees, options);
+    assert!(context.estimate_prover_memory::<f128::BaseElement>() > base);
+}
+
+#[test]
+fn estimate_proving_cost() {
+    let base = ProvingCost::estimate::<f128::BaseElement>(&build_options(32, 8), 4, 1 << 12, 2);
+
+    // larger blowup factors make every step after trace interpolation more expensive
+    let cost = ProvingCost::estimate::<f128::BaseElement>(&build_options(32, 16), 4, 1 << 12, 2);
+    assert!(cost.num_hashes > base.num_hashes);
+    assert!(cost.num_field_mults > base.num_field_mults);
+
+    // the number of queries does not affect the work of the prover
+    let cost = ProvingCost::estimate::<f128::BaseElement>(&build_options(48, 8), 4, 1 << 12, 2);
+    assert_eq!(base, cost);
+
+    // every bit of grinding doubles the expected number of proof-of-work attempts
+    let options = ProofOptions::new(32, 8, 16, HashFunction::Blake3_256, FieldExtension::None);
+    let cost = ProvingCost::estimate::<f128::BaseElement>(&options, 4, 1 << 12, 2);
+    assert_eq!(base.num_hashes + (1 << 16) - 1, cost.num_hashes);
+    assert_eq!(base.num_field_mults, cost.num_field_mults);
+}
+
+// PARAMETER SEARCH
+// ================================================================================================
+
+#[test]
+fn search_parameters() {
+    let search = ParameterSearch::new(100, 8, 1 << 16).with_ce_blowup_factor(4);
+    let frontier = search.run::<f128::BaseElement>();
+    assert!(!frontier.is_empty());
+
+    // every candidate reaches the target security level, and is faster to prove but yields a
+    // larger proof than the candidate before it
+    for candidate in frontier.iter() {
+        assert!(candidate.security_level >= 100);
+        assert!(candidate.options.blowup_factor() > 4);
+        assert!(candidate.options.grinding_factor() <= 20);
+    }
+    for pair in frontier.windows(2) {
+        assert!(pair[0].proof_size < pair[1].proof_size);
+        assert!(pair[0].proving_time > pair[1].proving_time);
+    }
+
+    // the smallest proofs require the largest blowup factor, and the fastest prover uses the
+    // smallest blowup factor allowed by the constraint degree
+    assert_eq!(256, frontier[0].options.blowup_factor());
+    assert_eq!(8, frontier.last().unwrap().options.blowup_factor());
+
+    // limiting verification time excludes candidates with large FRI remainders
+    let max_time = frontier
+        .iter()
+        .map(|c| c.verification_time)
+        .fold(0.0, f64::max)
+        / 2.0;
+    let frontier = search
+        .with_max_verification_time(max_time)
+        .run::<f128::BaseElement>();
+    assert!(!frontier.is_empty());
+    assert!(frontier.iter().all(|c| c.verification_time <= max_time));
+}
+
+#[test]
+fn search_parameters_unreachable() {
+    // conjectured security cannot exceed security provided by the hash function
+    let frontier = ParameterSearch::new(129, 8, 1 << 10).run::<f128::BaseElement>();
+    assert!(frontier.is_empty());
+
+    // a 62-bit field cannot provide 100 bits of security without field extension
+    let search = ParameterSearch::new(100, 8, 1 << 10);
+    assert!(search.run::<f62::BaseElement>().is_empty());
+    let frontier = search
+        .with_field_extension(FieldExtension::Quadratic)
+        .run::<f62::BaseElement>();
+    assert!(!frontier.is_empty());
+
+    // a more expensive hash function shifts the frontier towards fewer hashes
+    let model = CostModel {
+        hash: 1000.0,
+        ..CostModel::default()
+    };
+    let frontier = ParameterSearch::new(96, 8, 1 << 10)
+        .with_cost_model(model)
+        .run::<f128::BaseElement>();
+    assert!(!frontier.is_empty());
+}
+
+// LOCAL REGISTERS
+// ================================================================================================
+
+#[test]
+fn local_registers() {
+    let context = build_context(8, 2, 8);
+    assert_eq!(&[0, 1, 2, 3], context.next_state_registers());
+
+    let local_context = build_context(8, 2, 8).with_local_registers(&[3, 1]);
+    assert_eq!(&[0, 2], local_context.next_state_registers());
+
+    // local registers are omitted from the OOD frame and from the DEEP composition
+    let base = context.estimate_verification_cost::<f128::BaseElement>();
+    let cost = local_context.estimate_verification_cost::<f128::BaseElement>();
+    assert_eq!(base.proof_size - 2 * 16, cost.proof_size);
+    assert_eq!(base.num_field_inversions - 2 * 2, cost.num_field_inversions);
+    assert_eq!(base.num_hashes, cost.num_hashes);
+}
+
+#[test]
+#[should_panic(expected = "local register 4 is out of bounds for trace width 4")]
+fn local_registers_out_of_bounds() {
+    let _ = build_context(8, 2, 8).with_local_registers(&[4]);
+}
+
+#[test]
+#[should_panic(expected = "register 1 is marked as local more than once")]
+fn local_registers_duplicated() {
+    let _ = build_context(8, 2, 8).with_local_registers(&[1, 2, 1]);
+}
+
+// CACHED VALUES
+// ================================================================================================
+
+#[test]
+fn set_periodic_column_polys() {
+    let context = build_context(8, 2, 8);
+    assert!(context
+        .cached_periodic_column_polys::<f128::BaseElement>()
+        .is_none());
+
+    let polys = Arc::new(vec![vec![f128::BaseElement::ONE, f128::BaseElement::ZERO]]);
+    assert!(context.set_periodic_column_polys(polys.clone()));
+    let cached = context.cached_periodic_column_polys::<f128::BaseElement>();
+    assert!(Arc::ptr_eq(&polys, &cached.unwrap()));
+
+    // polynomials are not replaced once cached, and are not returned for a different field
+    assert!(!context.set_periodic_column_polys(Arc::new(Vec::<Vec<f128::BaseElement>>::new())));
+    assert_eq!(
+        polys.as_slice(),
+        context.get_or_init_periodic_column_polys::<f128::BaseElement, _>(|| unreachable!())
+    );
+    assert!(context
+        .cached_periodic_column_polys::<f62::BaseElement>()
+        .is_none());
+}
+
+// FIELD EXTENSION DISPATCH
+// ================================================================================================
+
+#[test]
+fn field_extension_visit() {
+    for extension in [
+        FieldExtension::None,
+        FieldExtension::Quadratic,
+        FieldExtension::Cubic,
+        FieldExtension::Quartic,
+    ] {
+        let (degree, element_bytes) = extension.visit::<f62::BaseElement, _>(FieldShape);
+        assert_eq!(extension.degree(), degree);
+        assert_eq!(f62::BaseElement::ELEMENT_BYTES * degree, element_bytes);
+
+        let (degree, element_bytes) = extension.visit::<f128::BaseElement, _>(FieldShape);
+        assert_eq!(extension.degree(), degree);
+        assert_eq!(f128::BaseElement::ELEMENT_BYTES * degree, element_bytes);
+    }
+}
+
+/// Reports the degree and the element size of the field selected by a field extension.
+struct FieldShape;
+
+impl<B: StarkField> ExtensionVisitor<B> for FieldShape {
+    type Output = (usize, usize);
+
+    fn visit<E: ExtensionOf<B>>(self) -> Self::Output {
+        (E::DEGREE, E::ELEMENT_BYTES)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_context(
+    trace_length: usize,
+    num_queries: usize,
+    blowup_factor: usize,
+) -> ComputationContext {
+    let options = build_options(num_queries, blowup_factor);
+    let t_degrees = vec![TransitionConstraintDegree::new(2)];
+    ComputationContext::new(4, trace_length, t_degrees, options)
+}
+
+fn build_options(num_queries: usize, blowup_factor: usize) -> ProofOptions {
+    ProofOptions::new(
+        num_queries,
+        blowup_factor,
+        0,
+        HashFunction::Blake3_256,
+        FieldExtension::None,
+    )
+}
@@ -0,0 +1,68 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{errors::EncodingError, proof::StarkProof};
+use std::str::FromStr;
+use utils::{Deserializable, Serializable};
+
+#[cfg(test)]
+mod tests;
+
+// TEXT ENCODING
+// ================================================================================================
+
+/// Defines hex and base64 text encodings for proofs and related values.
+///
+/// Values are first serialized into bytes using the same binary format in which proofs are
+/// written to files (i.e., via [Serializable]), and the bytes are then encoded as text. Thus,
+/// hex encoding of a 32-byte digest is the usual 64-character hex string, and hex-decoding a
+/// proof yields the same proof as reading it from a file would.
+///
+/// This trait is implemented for all types which implement [Serializable] and [Deserializable];
+/// this includes [StarkProof], commitment digests (`[u8; 32]`), field elements, and public
+/// inputs of computations which implement these traits.
+pub trait TextEncoding: Serializable + Deserializable {
+    /// Returns a lowercase hex encoding of this value.
+    fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    /// Decodes a value from a hex string; both lowercase and uppercase digits are accepted.
+    fn from_hex(source: &str) -> Result<Self, EncodingError> {
+        let bytes =
+            hex::decode(source.trim()).map_err(|err| EncodingError::InvalidHex(err.to_string()))?;
+        from_bytes(&bytes)
+    }
+
+    /// Returns a base64 encoding (with the standard alphabet and padding) of this value.
+    fn to_base64(&self) -> String {
+        base64::encode(self.to_bytes())
+    }
+
+    /// Decodes a value from a base64 string with the standard alphabet and padding.
+    fn from_base64(source: &str) -> Result<Self, EncodingError> {
+        let bytes = base64::decode(source.trim())
+            .map_err(|err| EncodingError::InvalidBase64(err.to_string()))?;
+        from_bytes(&bytes)
+    }
+}
+
+impl<T: Serializable + Deserializable> TextEncoding for T {}
+
+/// Parses a proof from its hex encoding.
+impl FromStr for StarkProof {
+    type Err = EncodingError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        StarkProof::from_hex(source)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn from_bytes<T: Deserializable>(bytes: &[u8]) -> Result<T, EncodingError> {
+    T::read_from_bytes(bytes).map_err(|err| EncodingError::DeserializationFailed(err.to_string()))
+}
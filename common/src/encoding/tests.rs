@@ -0,0 +1,60 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::TextEncoding;
+use crate::{errors::EncodingError, proof::OodEvaluationFrame};
+
+#[test]
+fn digest_hex_encoding() {
+    let mut digest = [0u8; 32];
+    digest[0] = 0xab;
+    digest[31] = 0x01;
+
+    // digests are encoded as plain hex strings without any length prefix
+    let encoded = digest.to_hex();
+    assert_eq!(64, encoded.len());
+    assert!(encoded.starts_with("ab00"));
+    assert!(encoded.ends_with("0001"));
+    assert_eq!(digest, <[u8; 32]>::from_hex(&encoded).unwrap());
+    assert_eq!(
+        digest,
+        <[u8; 32]>::from_hex(&encoded.to_uppercase()).unwrap()
+    );
+}
+
+#[test]
+fn base64_encoding() {
+    let frame = OodEvaluationFrame {
+        trace_at_z1: vec![1, 2, 3],
+        trace_at_z2: vec![4, 5, 6, 7],
+        extra_at_z: vec![],
+    };
+
+    let decoded = OodEvaluationFrame::from_base64(&frame.to_base64()).unwrap();
+    assert_eq!(frame.trace_at_z1, decoded.trace_at_z1);
+    assert_eq!(frame.trace_at_z2, decoded.trace_at_z2);
+
+    let decoded = OodEvaluationFrame::from_hex(&frame.to_hex()).unwrap();
+    assert_eq!(frame.trace_at_z1, decoded.trace_at_z1);
+    assert_eq!(frame.trace_at_z2, decoded.trace_at_z2);
+}
+
+#[test]
+fn invalid_encodings() {
+    assert!(matches!(
+        <[u8; 32]>::from_hex("xyz"),
+        Err(EncodingError::InvalidHex(_))
+    ));
+    assert!(matches!(
+        <[u8; 32]>::from_base64("not base64!"),
+        Err(EncodingError::InvalidBase64(_))
+    ));
+
+    // valid encodings of too few bytes cannot be deserialized
+    assert!(matches!(
+        <[u8; 32]>::from_hex("abcd"),
+        Err(EncodingError::DeserializationFailed(_))
+    ));
+}
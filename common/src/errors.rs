@@ -5,14 +5,78 @@
 
 use displaydoc::Display;
 use thiserror::Error;
+use utils::DeserializationError;
 
 /// Represents an error thrown by the prover during an execution of the protocol
 #[derive(Debug, Display, Error)]
 pub enum ProverError {
-    /// A transition constraint was not satisfied at a certain step {0}
-    UnsatisfiedTransitionConstraintError(usize),
+    /// Transition constraint {0} did not evaluate to ZERO at step {1}
+    UnsatisfiedTransitionConstraintError(usize, usize),
+    /// Trace does not satisfy assertion against register {0} at step {1}
+    UnsatisfiedAssertion(usize, usize),
     /// The constraint polynomial's components do not all have the same degree, expected {0} but found {1}
     MismatchedConstraintPolynomialDegree(usize, usize),
+    /// Execution trace of length {0} is too long for the base field; for the chosen blowup factor, trace length cannot exceed 2^{1}
+    TraceTooLongForField(usize, u32),
+    /// Proof parameters are not valid for the base field: {0}
+    InvalidParameters(ParameterError),
+    /// Computation designates {0} blinded registers, but no trace blinder was provided
+    TraceBlinderNotProvided(usize),
+    /// Proof generation is estimated to require {0} bytes of memory, but the budget is {1} bytes
+    MemoryBudgetExceeded(usize, usize),
+    /// Proof generation was aborted because the deadline passed after {0}
+    DeadlineExceeded(ProvingPhase),
+    /// Committed execution trace is not compatible with the computation: {0} does not match
+    CommittedTraceMismatch(&'static str),
+    /// Computation declares {0} extra polynomials, but {1} were provided
+    ExtraPolyCountMismatch(usize, usize),
+    /// Extra polynomials are defined by {0} evaluations each, but the execution trace has {1} steps
+    ExtraPolyLengthMismatch(usize, usize),
+    /// Computation declares an auxiliary trace segment of {0} registers, but no segment builder was provided
+    AuxSegmentBuilderNotProvided(usize),
+    /// Computation declares an auxiliary trace segment of {0} registers, but {1} registers were built
+    AuxSegmentWidthMismatch(usize, usize),
+}
+
+/// Phases of proof generation after which the prover checks whether its deadline has passed
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum ProvingPhase {
+    /// extending the execution trace
+    TraceExtension,
+    /// committing to the extended execution trace
+    TraceCommitment,
+    /// evaluating constraints
+    ConstraintEvaluation,
+    /// committing to constraint evaluations
+    ConstraintCommitment,
+    /// building the DEEP composition polynomial
+    DeepComposition,
+    /// computing FRI layers
+    FriCommitment,
+    /// determining query positions
+    QueryGeneration,
+}
+
+/// Represents an error encountered while adopting externally-owned columnar buffers as an
+/// execution trace
+#[derive(Debug, Display, Error, PartialEq)]
+pub enum TraceBufferError {
+    /// execution trace must consist of at least one register
+    NoRegisters,
+    /// execution trace cannot consist of more than {0} registers, but was {1}
+    TooManyRegisters(usize, usize),
+    /// execution trace must be at least {0} steps long, but was {1}
+    TraceTooShort(usize, usize),
+    /// execution trace length must be a power of two, but was {0}
+    TraceLengthNotPowerOfTwo(usize),
+    /// register {0} contains {1} values, but the first register contains {2}
+    RegisterLengthMismatch(usize, usize, usize),
+    /// words of {0} bytes aligned to {1} bytes cannot back field elements of {2} bytes aligned to {3} bytes
+    IncompatibleWordLayout(usize, usize, usize, usize),
+    /// field elements are not stored in canonical representation and cannot be backed by raw words
+    NonCanonicalRepresentation,
+    /// value in register {0} at step {1} is not a valid field element
+    InvalidValue(usize, usize),
 }
 
 /// Represents an error thrown by the verifier during an execution of the protocol
@@ -28,12 +92,36 @@ pub enum VerifierError {
     ConstraintQueryDoesNotMatchCommitment,
     /// Constraint query deserialization failed
     ConstraintQueryDeserializationFailed,
+    /// Extra polynomial query did not match the commitment
+    ExtraQueryDoesNotMatchCommitment,
+    /// Extra polynomial query deserialization failed
+    ExtraQueryDeserializationFailed,
+    /// Auxiliary trace segment query did not match the commitment
+    AuxSegmentQueryDoesNotMatchCommitment,
+    /// Auxiliary trace segment query deserialization failed
+    AuxSegmentQueryDeserializationFailed,
     /// Query seed proof-of-work verification failed
     QuerySeedProofOfWorkVerificationFailed,
     /// Out-of-domain frame deserialization failed
     OodFrameDeserializationFailed,
+    /// Out-of-domain frame contains base field elements, but proof options specify a field extension
+    OodFrameNotInExtensionField,
     /// Computation context deserialization failed
     ComputationContextDeserializationFailed,
+    /// Proof version {0} is not supported by this verifier
+    UnsupportedProofVersion(u8),
+    /// Proof parameters are not valid for the base field: {0}
+    InvalidParameters(ParameterError),
+    /// Challenges were requested in a field with {0}-byte elements, but the proof was generated in a field with {1}-byte elements
+    ChallengeFieldMismatch(usize, usize),
+    /// Column layout of the computation is not a permutation of {0} trace registers
+    InvalidColumnLayout(usize),
+    /// Proof contains {0} auxiliary commitments, but the computation designates {1} blinded registers
+    AuxCommitmentCountMismatch(usize, usize),
+    /// Proof is internally inconsistent: {0}
+    InconsistentProof(#[from] ProofShapeError),
+    /// Trace commitment rows were not hashed in chunks
+    TraceRowsNotChunked,
 }
 
 /// Represents an error thrown during evaluation
@@ -47,4 +135,98 @@ pub enum AssertionError {
     TraceLengthTooShort(usize, usize),
     /// expected trace length to be exactly {0}, but was {1}
     TraceLengthNotExact(usize, usize),
+    /// different values were asserted for register {0} at step {1}
+    ConflictingValues(usize, usize),
+    /// expected columns of equal length, but got {0} registers, {1} steps, and {2} values
+    ColumnLengthMismatch(usize, usize, usize),
+}
+
+/// Represents an error caused by proof parameters which are inconsistent with the base field or
+/// with limits imposed by the user
+#[derive(Debug, Display, Error, PartialEq)]
+pub enum ParameterError {
+    /// LDE domain size of {0} is not a power of two
+    LdeDomainSizeNotPowerOfTwo(usize),
+    /// LDE domain of size 2^{0} does not fit into the two-adic subgroup of size 2^{1}
+    LdeDomainTooLarge(u32, u32),
+    /// LDE domain of size 2^{0} exhausts the multiplicative group of a {1}-bit field
+    LdeDomainExhaustsField(u32, u32),
+    /// number of queries ({0}) cannot exceed half of the LDE domain size ({1})
+    TooManyQueries(usize, usize),
+    /// grinding factor of {0} bits cannot exceed the field size of {1} bits
+    GrindingFactorTooLarge(u32, u32),
+    /// out-of-domain point is drawn from a field of {0} bits, but at least {1} bits are required
+    FieldTooSmall(u32, u32),
+    /// transition constraint {0} requires CE blowup factor of {1}, but at most {2} is allowed
+    CeBlowupFactorTooLarge(usize, usize, usize),
+}
+
+/// Represents an inconsistency between size fields carried in a proof, or between these fields
+/// and the parameters of the computation the proof is verified against
+#[derive(Debug, Display, Error, PartialEq)]
+pub enum ProofShapeError {
+    /// LDE domain depth of {0} is outside of the valid range [{1}, {2}]
+    LdeDomainDepthOutOfRange(u8, u32, u32),
+    /// proof declares CE blowup factor of {0}, but the computation requires {1}
+    CeBlowupFactorMismatch(usize, usize),
+    /// proof declares {0} constraints, but the computation has {1} constraints
+    NumConstraintsMismatch(usize, usize),
+    /// proof declares a field modulus different from the modulus of the base field
+    FieldModulusMismatch,
+    /// proof was generated for an AIR with a different identifier
+    AirIdMismatch,
+    /// proof {0} extra polynomials, but the computation declares {1} extra polynomials
+    ExtraPolysMismatch(&'static str, usize),
+    /// proof {0} an auxiliary trace segment, but the computation declares an auxiliary segment of {1} registers
+    AuxSegmentMismatch(&'static str, usize),
+    /// proof contains {0} FRI layer commitments, but {1} are expected
+    FriCommitmentCountMismatch(usize, usize),
+    /// proof contains queries against {0} FRI layers, but {1} are expected
+    FriLayerCountMismatch(usize, usize),
+    /// FRI layer {0} declares commitment depth of {1}, but {2} is expected
+    FriLayerDepthMismatch(usize, u8, u8),
+    /// FRI remainder contains {0} bytes, but {1} are expected
+    FriRemainderSizeMismatch(usize, usize),
+    /// proof declares proof-of-work difficulty of {0} bits, but the grinding factor is {1} bits
+    PowDifficultyTooLow(u8, u32),
+}
+
+/// Represents an error encountered while parsing a proof from untrusted bytes
+#[derive(Debug, Display, Error, PartialEq)]
+pub enum ProofParsingError {
+    /// proof contains {0} bytes, but at most {1} bytes are accepted
+    ProofTooLarge(usize, usize),
+    /// {0} contain {1} entries, but at most {2} are accepted
+    TooManyQueries(&'static str, usize, usize),
+    /// proof contains {0} FRI layers, but at most {1} are accepted
+    TooManyFriLayers(usize, usize),
+    /// FRI remainder contains {0} bytes, but at most {1} bytes are accepted
+    RemainderTooLarge(usize, usize),
+    /// LDE domain of size 2^{0} cannot be addressed on this platform
+    LdeDomainTooLarge(u8),
+    /// parsing the proof requires more than {0} bytes of memory
+    AllocationLimitExceeded(usize),
+    /// proof is malformed: {0}
+    MalformedProof(#[from] DeserializationError),
+}
+
+impl From<ProofParsingError> for DeserializationError {
+    fn from(error: ProofParsingError) -> Self {
+        match error {
+            ProofParsingError::MalformedProof(error) => error,
+            error => DeserializationError::InvalidValue(error.to_string()),
+        }
+    }
+}
+
+/// Represents an error encountered while decoding a value from its text encoding
+#[cfg(feature = "encoding")]
+#[derive(Debug, Display, Error, PartialEq)]
+pub enum EncodingError {
+    /// invalid hex string: {0}
+    InvalidHex(String),
+    /// invalid base64 string: {0}
+    InvalidBase64(String),
+    /// decoded bytes could not be deserialized: {0}
+    DeserializationFailed(String),
 }
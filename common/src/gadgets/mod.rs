@@ -0,0 +1,318 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Reusable transition constraint gadgets.
+//!
+//! A gadget describes a set of auxiliary trace registers together with transition constraints
+//! over these registers. Gadgets are used from [Air::evaluate_transition()](crate::Air) by
+//! passing them values from the evaluation frame and a slice of the result into which their
+//! constraint evaluations are written; [constraint_degrees()](RangeCheck::constraint_degrees)
+//! methods of gadgets return degrees of these constraints in the same order, so that they can
+//! be appended to the degrees of other constraints of the AIR. Trace generation code can use
+//! witness methods of gadgets (e.g., [RangeCheck::get_bits()]) to fill auxiliary registers.
+//!
+//! Transition constraints are not enforced at the last step of the trace; values which must be
+//! constrained at the last step should be also checked via assertions.
+//!
+//! Values are represented as field elements via the signed integer encoding defined in the
+//! math crate (see [encode_signed()](math::field::encode_signed)).
+
+use crate::TransitionConstraintDegree;
+use math::field::{encode_signed, FieldElement, FixedPoint, IntegerRange, StarkField};
+
+mod words;
+pub use words::{BitwiseOp, ShiftOp, WordAdd, WordBits, WordBitwise, WordShift, WordSize};
+
+#[cfg(test)]
+mod tests;
+
+// RANGE CHECK
+// ================================================================================================
+
+/// Constrains a value to a range of 2^n consecutive integers.
+///
+/// For a range [min, min + 2^n - 1], the gadget uses n binary registers b_0, ..., b_{n - 1}
+/// holding the bits of value - min in little-endian order, and emits n + 1 constraints:
+/// * b_i^2 - b_i = 0 for every bit register;
+/// * value - min - sum(b_i * 2^i) = 0.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RangeCheck {
+    range: IntegerRange,
+    num_bits: u32,
+}
+
+impl RangeCheck {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a range check for signed integers which can be represented with `num_bits` bits
+    /// in two's complement form, i.e., for the range [-2^(num_bits - 1), 2^(num_bits - 1) - 1].
+    ///
+    /// # Panics
+    /// Panics if `num_bits` is zero or greater than 127.
+    pub fn signed(num_bits: u32) -> Self {
+        assert!(
+            num_bits > 0 && num_bits <= 127,
+            "number of bits must be between 1 and 127, but was {}",
+            num_bits
+        );
+        RangeCheck {
+            range: IntegerRange::signed(num_bits),
+            num_bits,
+        }
+    }
+
+    /// Returns a range check for unsigned integers which can be represented with `num_bits` bits,
+    /// i.e., for the range [0, 2^num_bits - 1].
+    ///
+    /// # Panics
+    /// Panics if `num_bits` is zero or greater than 127.
+    pub fn unsigned(num_bits: u32) -> Self {
+        RangeCheck {
+            range: IntegerRange::unsigned(num_bits),
+            num_bits,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the range of integers enforced by this gadget.
+    pub fn range(&self) -> IntegerRange {
+        self.range
+    }
+
+    /// Returns the number of auxiliary registers used by this gadget.
+    pub fn num_registers(&self) -> usize {
+        self.num_bits as usize
+    }
+
+    /// Returns the number of constraints emitted by this gadget.
+    pub fn num_constraints(&self) -> usize {
+        self.num_bits as usize + 1
+    }
+
+    /// Returns degrees of constraints emitted by this gadget.
+    pub fn constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        let mut result = vec![TransitionConstraintDegree::new(2); self.num_bits as usize];
+        result.push(TransitionConstraintDegree::new(1));
+        result
+    }
+
+    /// Returns true if this gadget is sound in field `B`, i.e., if all integers in the range can
+    /// be encoded as elements of `B` and decoded back, and the sum of bit registers cannot wrap
+    /// around the field modulus.
+    pub fn fits_in<B: StarkField>(&self) -> bool {
+        self.range.fits_in::<B>() && self.num_bits < B::MODULUS_BITS
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates constraints of this gadget for the specified `value` and bit registers, and
+    /// writes the results into the first [num_constraints()](Self::num_constraints) slots of
+    /// `result`.
+    pub fn evaluate<E: FieldElement>(&self, value: E, bits: &[E], result: &mut [E]) {
+        debug_assert_eq!(
+            self.num_registers(),
+            bits.len(),
+            "invalid number of bit registers"
+        );
+        let n = self.num_bits as usize;
+        enforce_binary(bits, &mut result[..n]);
+        result[n] = value - encode_signed::<E>(self.range.min()) - combine_bits(bits);
+    }
+
+    // WITNESS GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns values of bit registers for the specified `value`.
+    ///
+    /// # Panics
+    /// Panics if `value` is not in the range enforced by this gadget.
+    pub fn get_bits<B: StarkField>(&self, value: i128) -> Vec<B> {
+        assert!(
+            self.range.contains(value),
+            "value {} is outside of the range [{}, {}]",
+            value,
+            self.range.min(),
+            self.range.max()
+        );
+        let offset = value.wrapping_sub(self.range.min()) as u128;
+        get_bits(offset, self.num_bits)
+    }
+}
+
+// FIXED-POINT MULTIPLICATION
+// ================================================================================================
+
+/// Constrains a value to be the product of two values in a fixed-point format.
+///
+/// For integers a and b representing values in a format with scale s, the product c is the
+/// integer such that a * b = c * s + r where 0 <= r < s (i.e., the product rounded towards
+/// negative infinity). Let n be the number of bits needed to represent s - 1; the gadget uses n
+/// binary registers r_0, ..., r_{n - 1} holding the bits of r, and emits constraints:
+/// * b_i^2 - b_i = 0 for every bit register;
+/// * a * b - c * s - sum(r_i * 2^i) = 0.
+///
+/// When the scale is not a power of two, another n binary registers u_0, ..., u_{n - 1} holding
+/// the bits of s - 1 - r are used to enforce that r < s; these add n + 1 constraints:
+/// * u_i^2 - u_i = 0 for every bit register;
+/// * s - 1 - sum(r_i * 2^i) - sum(u_i * 2^i) = 0.
+///
+/// The gadget does not constrain the range of c; the AIR should constrain it separately (e.g.,
+/// via [RangeCheck]) so that a * b - c * s - r cannot wrap around the field modulus (see
+/// [FixedPointMul::fits_in()]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FixedPointMul {
+    format: FixedPoint,
+    num_bits: u32,
+}
+
+impl FixedPointMul {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a multiplication gadget for values in the specified fixed-point `format`.
+    pub fn new(format: FixedPoint) -> Self {
+        let num_bits = 64 - (format.scale() - 1).leading_zeros();
+        FixedPointMul { format, num_bits }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the fixed-point format of the values multiplied by this gadget.
+    pub fn format(&self) -> FixedPoint {
+        self.format
+    }
+
+    /// Returns the number of auxiliary registers used by this gadget.
+    pub fn num_registers(&self) -> usize {
+        if self.has_upper_bound() {
+            2 * self.num_bits as usize
+        } else {
+            self.num_bits as usize
+        }
+    }
+
+    /// Returns the number of constraints emitted by this gadget.
+    pub fn num_constraints(&self) -> usize {
+        if self.has_upper_bound() {
+            2 * self.num_bits as usize + 2
+        } else {
+            self.num_bits as usize + 1
+        }
+    }
+
+    /// Returns degrees of constraints emitted by this gadget.
+    pub fn constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        let n = self.num_bits as usize;
+        let mut result = vec![TransitionConstraintDegree::new(2); n + 1];
+        if self.has_upper_bound() {
+            result.resize(2 * n + 1, TransitionConstraintDegree::new(2));
+            result.push(TransitionConstraintDegree::new(1));
+        }
+        result
+    }
+
+    /// Returns true if this gadget is sound in field `B` for factors from ranges `a` and `b`, and
+    /// products from range `c`, i.e., if a * b - c * s - r cannot wrap around the field modulus.
+    pub fn fits_in<B: StarkField>(
+        &self,
+        a: &IntegerRange,
+        b: &IntegerRange,
+        c: &IntegerRange,
+    ) -> bool {
+        let scale = IntegerRange::new(self.format.scale() as i128, self.format.scale() as i128);
+        let remainder = IntegerRange::new(0, self.format.scale() as i128 - 1);
+        let difference = a.checked_mul(b).and_then(|product| {
+            let rounded = c.checked_mul(&scale)?.checked_add(&remainder)?;
+            product.checked_sub(&rounded)
+        });
+        matches!(difference, Some(difference) if difference.fits_in::<B>())
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates constraints of this gadget for factors `a` and `b`, product `c`, and the
+    /// specified bit registers, and writes the results into the first
+    /// [num_constraints()](Self::num_constraints) slots of `result`.
+    pub fn evaluate<E: FieldElement>(&self, a: E, b: E, c: E, bits: &[E], result: &mut [E]) {
+        debug_assert_eq!(
+            self.num_registers(),
+            bits.len(),
+            "invalid number of bit registers"
+        );
+        let n = self.num_bits as usize;
+        let scale = E::from(self.format.scale());
+
+        let (r_bits, u_bits) = bits.split_at(n);
+        let remainder = combine_bits(r_bits);
+        enforce_binary(r_bits, &mut result[..n]);
+        result[n] = a * b - c * scale - remainder;
+
+        if self.has_upper_bound() {
+            enforce_binary(u_bits, &mut result[n + 1..2 * n + 1]);
+            result[2 * n + 1] = scale - E::ONE - remainder - combine_bits(u_bits);
+        }
+    }
+
+    // WITNESS GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the integer representing the product of values represented by `a` and `b`,
+    /// together with values of bit registers for this product.
+    ///
+    /// # Panics
+    /// Panics if a * b does not fit into i128.
+    pub fn get_witness<B: StarkField>(&self, a: i128, b: i128) -> (i128, Vec<B>) {
+        let (product, remainder) = self
+            .format
+            .checked_mul(a, b)
+            .unwrap_or_else(|| panic!("product of {} and {} does not fit into i128", a, b));
+
+        let mut bits = get_bits(remainder as u128, self.num_bits);
+        if self.has_upper_bound() {
+            let bound = self.format.scale() - 1 - remainder;
+            bits.append(&mut get_bits(bound as u128, self.num_bits));
+        }
+        (product, bits)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns true if the remainder needs to be constrained from above separately; this is not
+    /// needed when the scale is a power of two, since n bits cannot represent values above s - 1.
+    fn has_upper_bound(&self) -> bool {
+        !self.format.scale().is_power_of_two()
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Evaluates b^2 - b for every value in `bits`.
+fn enforce_binary<E: FieldElement>(bits: &[E], result: &mut [E]) {
+    for (result, &bit) in result.iter_mut().zip(bits) {
+        *result = bit.square() - bit;
+    }
+}
+
+/// Computes sum(bits[i] * 2^i).
+fn combine_bits<E: FieldElement>(bits: &[E]) -> E {
+    bits.iter()
+        .rev()
+        .fold(E::ZERO, |acc, &bit| acc.double() + bit)
+}
+
+/// Returns `num_bits` least significant bits of `value` in little-endian order.
+fn get_bits<B: StarkField>(value: u128, num_bits: u32) -> Vec<B> {
+    (0..num_bits)
+        .map(|i| B::from(((value >> i) & 1) as u8))
+        .collect()
+}
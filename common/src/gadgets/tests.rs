@@ -0,0 +1,177 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{FixedPointMul, RangeCheck};
+use math::field::{
+    encode_signed, f128, f31, f64::BaseElement, FieldElement, FixedPoint, IntegerRange,
+    QuadExtension,
+};
+
+// RANGE CHECK
+// ================================================================================================
+
+#[test]
+fn range_check_signed() {
+    let gadget = RangeCheck::signed(8);
+    assert_eq!(IntegerRange::new(-128, 127), gadget.range());
+    assert_eq!(8, gadget.num_registers());
+    assert_eq!(9, gadget.num_constraints());
+    assert_eq!(9, gadget.constraint_degrees().len());
+
+    for &value in [-128, -1, 0, 1, 127].iter() {
+        let bits = gadget.get_bits::<BaseElement>(value);
+        let result = evaluate_range_check(&gadget, encode_signed(value), &bits);
+        assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+    }
+
+    // a value which does not match the bits
+    let bits = gadget.get_bits::<BaseElement>(5);
+    let result = evaluate_range_check(&gadget, encode_signed(6), &bits);
+    assert_ne!(BaseElement::ZERO, result[8]);
+
+    // non-binary bits
+    let mut bits = gadget.get_bits::<BaseElement>(5);
+    bits[0] = BaseElement::from(3u8);
+    bits[1] = -BaseElement::ONE;
+    let result = evaluate_range_check(&gadget, encode_signed(5), &bits);
+    assert_eq!(BaseElement::ZERO, result[8]);
+    assert_ne!(BaseElement::ZERO, result[0]);
+    assert_ne!(BaseElement::ZERO, result[1]);
+}
+
+#[test]
+fn range_check_unsigned() {
+    let gadget = RangeCheck::unsigned(4);
+    assert_eq!(IntegerRange::new(0, 15), gadget.range());
+
+    let bits = gadget.get_bits::<BaseElement>(11);
+    let expected = [1u8, 1, 0, 1]
+        .iter()
+        .map(|&b| BaseElement::from(b))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, bits);
+
+    let result = evaluate_range_check(&gadget, BaseElement::from(11u8), &bits);
+    assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+
+    // constraints can be evaluated over extension fields
+    let bits = bits.iter().map(|&b| b.into()).collect::<Vec<_>>();
+    let mut result = vec![QuadExtension::<BaseElement>::ZERO; gadget.num_constraints()];
+    gadget.evaluate(QuadExtension::from(11u8), &bits, &mut result);
+    assert!(result.iter().all(|&v| v == QuadExtension::ZERO));
+}
+
+#[test]
+#[should_panic(expected = "value 16 is outside of the range [0, 15]")]
+fn range_check_value_out_of_range() {
+    RangeCheck::unsigned(4).get_bits::<BaseElement>(16);
+}
+
+#[test]
+fn range_check_fits_in() {
+    assert!(RangeCheck::signed(30).fits_in::<f31::BaseElement>());
+    assert!(!RangeCheck::unsigned(30).fits_in::<f31::BaseElement>());
+    assert!(RangeCheck::unsigned(62).fits_in::<BaseElement>());
+    assert!(!RangeCheck::unsigned(64).fits_in::<BaseElement>());
+    assert!(!RangeCheck::signed(64).fits_in::<BaseElement>());
+    assert!(RangeCheck::signed(127).fits_in::<f128::BaseElement>());
+}
+
+// FIXED-POINT MULTIPLICATION
+// ================================================================================================
+
+#[test]
+fn fixed_point_mul_decimal() {
+    let format = FixedPoint::with_decimals(2);
+    let gadget = FixedPointMul::new(format);
+
+    // 99 needs 7 bits, and since 100 is not a power of two, the remainder is bounded from above
+    assert_eq!(14, gadget.num_registers());
+    assert_eq!(16, gadget.num_constraints());
+    assert_eq!(16, gadget.constraint_degrees().len());
+
+    for &(a, b) in [(150, 225), (-150, 225), (-150, -225), (0, 7), (99, 1)].iter() {
+        let (c, bits) = gadget.get_witness::<BaseElement>(a, b);
+        assert_eq!(format.checked_mul(a, b).unwrap().0, c);
+        let result = evaluate_fixed_point_mul(&gadget, a, b, c, &bits);
+        assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+    }
+
+    // an incorrectly rounded product cannot be proven: 0.10 * 0.11 = 0.011 which is rounded
+    // down to 0.01; claiming that the product is 0 requires a remainder of 110, which does not
+    // satisfy the upper bound constraint
+    let mut bits = super::get_bits::<BaseElement>(110, 7);
+    bits.append(&mut super::get_bits(0, 7));
+    let result = evaluate_fixed_point_mul(&gadget, 10, 11, 0, &bits);
+    assert!(result[..15].iter().all(|&v| v == BaseElement::ZERO));
+    assert_ne!(BaseElement::ZERO, result[15]);
+}
+
+#[test]
+fn fixed_point_mul_binary() {
+    let format = FixedPoint::with_fractional_bits(8);
+    let gadget = FixedPointMul::new(format);
+
+    // remainders are always smaller than 2^8, and thus no upper bound is needed
+    assert_eq!(8, gadget.num_registers());
+    assert_eq!(9, gadget.num_constraints());
+    assert_eq!(9, gadget.constraint_degrees().len());
+
+    let a = format.to_raw(1.5);
+    let b = format.to_raw(-0.75);
+    let (c, bits) = gadget.get_witness::<BaseElement>(a, b);
+    assert_eq!(format.to_raw(-1.125), c);
+    let result = evaluate_fixed_point_mul(&gadget, a, b, c, &bits);
+    assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+
+    // an incorrect product
+    let result = evaluate_fixed_point_mul(&gadget, a, b, c + 1, &bits);
+    assert_ne!(BaseElement::ZERO, result[8]);
+}
+
+#[test]
+fn fixed_point_mul_fits_in() {
+    let gadget = FixedPointMul::new(FixedPoint::with_decimals(6));
+    let value = IntegerRange::signed(24);
+    assert!(gadget.fits_in::<BaseElement>(&value, &value, &value));
+    assert!(!gadget.fits_in::<f31::BaseElement>(&value, &value, &value));
+
+    // products of 32-bit values fit into the 64-bit field, but products of 40-bit values do not
+    let value = IntegerRange::signed(32);
+    assert!(gadget.fits_in::<BaseElement>(&value, &value, &value));
+    let value = IntegerRange::signed(40);
+    assert!(!gadget.fits_in::<BaseElement>(&value, &value, &value));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn evaluate_range_check(
+    gadget: &RangeCheck,
+    value: BaseElement,
+    bits: &[BaseElement],
+) -> Vec<BaseElement> {
+    let mut result = vec![BaseElement::ZERO; gadget.num_constraints()];
+    gadget.evaluate(value, bits, &mut result);
+    result
+}
+
+fn evaluate_fixed_point_mul(
+    gadget: &FixedPointMul,
+    a: i128,
+    b: i128,
+    c: i128,
+    bits: &[BaseElement],
+) -> Vec<BaseElement> {
+    let mut result = vec![BaseElement::ZERO; gadget.num_constraints()];
+    gadget.evaluate(
+        encode_signed(a),
+        encode_signed(b),
+        encode_signed(c),
+        bits,
+        &mut result,
+    );
+    result
+}
@@ -0,0 +1,474 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Gadgets for operations over 32-bit and 64-bit words.
+//!
+//! Bitwise operations, rotations, and shifts are expressed over bit registers of their operands:
+//! a word is decomposed into bits via [WordBits], and the result of an operation is bound to
+//! these bits by a single constraint, without additional registers. Thus, an AIR which applies
+//! several operations to the same word needs to decompose this word only once.
+
+use super::{combine_bits, enforce_binary, get_bits, RangeCheck};
+use crate::TransitionConstraintDegree;
+use math::field::{FieldElement, StarkField};
+
+#[cfg(test)]
+mod tests;
+
+// WORD SIZE
+// ================================================================================================
+
+/// Size of words operated on by word gadgets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WordSize {
+    U32,
+    U64,
+}
+
+impl WordSize {
+    /// Returns the number of bits in a word of this size.
+    pub fn num_bits(&self) -> u32 {
+        match self {
+            WordSize::U32 => 32,
+            WordSize::U64 => 64,
+        }
+    }
+
+    /// Returns the largest value of a word of this size.
+    pub fn max_value(&self) -> u64 {
+        u64::MAX >> (64 - self.num_bits())
+    }
+}
+
+// WORD DECOMPOSITION
+// ================================================================================================
+
+/// Constrains bit registers b_0, ..., b_{n - 1} to hold the bits of an n-bit word in
+/// little-endian order; this also constrains the word to the range [0, 2^n - 1].
+///
+/// The gadget emits n + 1 constraints:
+/// * b_i^2 - b_i = 0 for every bit register;
+/// * value - sum(b_i * 2^i) = 0.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WordBits {
+    size: WordSize,
+    range_check: RangeCheck,
+}
+
+impl WordBits {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a decomposition gadget for words of the specified size.
+    pub fn new(size: WordSize) -> Self {
+        WordBits {
+            size,
+            range_check: RangeCheck::unsigned(size.num_bits()),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the size of words decomposed by this gadget.
+    pub fn size(&self) -> WordSize {
+        self.size
+    }
+
+    /// Returns the number of auxiliary registers used by this gadget.
+    pub fn num_registers(&self) -> usize {
+        self.range_check.num_registers()
+    }
+
+    /// Returns the number of constraints emitted by this gadget.
+    pub fn num_constraints(&self) -> usize {
+        self.range_check.num_constraints()
+    }
+
+    /// Returns degrees of constraints emitted by this gadget.
+    pub fn constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        self.range_check.constraint_degrees()
+    }
+
+    /// Returns true if this gadget is sound in field `B`, i.e., if all words of this size are
+    /// smaller than the field modulus.
+    pub fn fits_in<B: StarkField>(&self) -> bool {
+        self.size.num_bits() < B::MODULUS_BITS
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates constraints of this gadget for the specified `value` and bit registers, and
+    /// writes the results into the first [num_constraints()](Self::num_constraints) slots of
+    /// `result`.
+    pub fn evaluate<E: FieldElement>(&self, value: E, bits: &[E], result: &mut [E]) {
+        self.range_check.evaluate(value, bits, result);
+    }
+
+    // WITNESS GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns values of bit registers for the specified `value`.
+    ///
+    /// # Panics
+    /// Panics if `value` does not fit into a word of this size.
+    pub fn get_bits<B: StarkField>(&self, value: u64) -> Vec<B> {
+        self.range_check.get_bits(value as i128)
+    }
+}
+
+// WORD ADDITION
+// ================================================================================================
+
+/// Constrains a word to be the sum of k words modulo 2^n.
+///
+/// For addends a_0, ..., a_{k - 1}, the sum c is the word such that sum(a_j) = c + carry * 2^n.
+/// Let m be the number of bits needed to represent k - 1; the gadget uses m binary registers
+/// carry_0, ..., carry_{m - 1} holding the bits of the carry, and emits m + 1 constraints:
+/// * carry_i^2 - carry_i = 0 for every carry register;
+/// * sum(a_j) - c - 2^n * sum(carry_i * 2^i) = 0.
+///
+/// The gadget does not constrain the range of c; the AIR should decompose c via [WordBits]
+/// (which is usually needed anyway when c is an operand of a bitwise operation).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WordAdd {
+    size: WordSize,
+    num_addends: usize,
+    num_carry_bits: u32,
+}
+
+impl WordAdd {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an addition gadget for `num_addends` words of the specified size.
+    ///
+    /// # Panics
+    /// Panics if `num_addends` is smaller than 2.
+    pub fn new(size: WordSize, num_addends: usize) -> Self {
+        assert!(
+            num_addends >= 2,
+            "number of addends must be at least 2, but was {}",
+            num_addends
+        );
+        let num_carry_bits = usize::BITS - (num_addends - 1).leading_zeros();
+        WordAdd {
+            size,
+            num_addends,
+            num_carry_bits,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the size of words added by this gadget.
+    pub fn size(&self) -> WordSize {
+        self.size
+    }
+
+    /// Returns the number of words added by this gadget.
+    pub fn num_addends(&self) -> usize {
+        self.num_addends
+    }
+
+    /// Returns the number of auxiliary registers used by this gadget.
+    pub fn num_registers(&self) -> usize {
+        self.num_carry_bits as usize
+    }
+
+    /// Returns the number of constraints emitted by this gadget.
+    pub fn num_constraints(&self) -> usize {
+        self.num_carry_bits as usize + 1
+    }
+
+    /// Returns degrees of constraints emitted by this gadget.
+    pub fn constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        let mut result = vec![TransitionConstraintDegree::new(2); self.num_carry_bits as usize];
+        result.push(TransitionConstraintDegree::new(1));
+        result
+    }
+
+    /// Returns true if this gadget is sound in field `B`, i.e., if c + carry * 2^n cannot wrap
+    /// around the field modulus.
+    pub fn fits_in<B: StarkField>(&self) -> bool {
+        self.size.num_bits() + self.num_carry_bits < B::MODULUS_BITS
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates constraints of this gadget for the specified `addends`, `sum`, and carry
+    /// registers, and writes the results into the first
+    /// [num_constraints()](Self::num_constraints) slots of `result`.
+    pub fn evaluate<E: FieldElement>(&self, addends: &[E], sum: E, carry: &[E], result: &mut [E]) {
+        debug_assert_eq!(self.num_addends, addends.len(), "invalid number of addends");
+        debug_assert_eq!(
+            self.num_registers(),
+            carry.len(),
+            "invalid number of carry registers"
+        );
+        let m = self.num_carry_bits as usize;
+        let base = E::from(1u128 << self.size.num_bits());
+        let total = addends.iter().fold(E::ZERO, |acc, &a| acc + a);
+
+        enforce_binary(carry, &mut result[..m]);
+        result[m] = total - sum - base * combine_bits(carry);
+    }
+
+    // WITNESS GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the sum of `addends` modulo 2^n, together with values of carry registers.
+    ///
+    /// # Panics
+    /// Panics if the number of addends is not the one specified for this gadget, or if any of
+    /// the addends does not fit into a word of this size.
+    pub fn get_witness<B: StarkField>(&self, addends: &[u64]) -> (u64, Vec<B>) {
+        assert_eq!(
+            self.num_addends,
+            addends.len(),
+            "expected {} addends, but received {}",
+            self.num_addends,
+            addends.len()
+        );
+        let total = addends.iter().fold(0u128, |acc, &a| {
+            assert!(
+                a <= self.size.max_value(),
+                "addend {} does not fit into a {}-bit word",
+                a,
+                self.size.num_bits()
+            );
+            acc + a as u128
+        });
+
+        let sum = (total & self.size.max_value() as u128) as u64;
+        let carry = total >> self.size.num_bits();
+        (sum, get_bits(carry, self.num_carry_bits))
+    }
+}
+
+// BITWISE OPERATIONS
+// ================================================================================================
+
+/// Bitwise operations supported by [WordBitwise] gadget.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    And,
+    Or,
+    Xor,
+}
+
+/// Constrains a word to be the result of a bitwise operation over two words.
+///
+/// For operands a and b with bit registers a_i and b_i, the gadget emits a single constraint
+/// c - sum(f(a_i, b_i) * 2^i) = 0, where f is:
+/// * a_i * b_i for AND;
+/// * a_i + b_i - a_i * b_i for OR;
+/// * a_i + b_i - 2 * a_i * b_i for XOR.
+///
+/// The gadget does not use auxiliary registers; bit registers of the operands must be
+/// constrained via [WordBits]. The result c does not need to be decomposed, since it is fully
+/// determined by the bits of the operands.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WordBitwise {
+    size: WordSize,
+    op: BitwiseOp,
+}
+
+impl WordBitwise {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a gadget for the specified bitwise operation over words of the specified size.
+    pub fn new(size: WordSize, op: BitwiseOp) -> Self {
+        WordBitwise { size, op }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the size of words operated on by this gadget.
+    pub fn size(&self) -> WordSize {
+        self.size
+    }
+
+    /// Returns the operation enforced by this gadget.
+    pub fn op(&self) -> BitwiseOp {
+        self.op
+    }
+
+    /// Returns the number of constraints emitted by this gadget.
+    pub fn num_constraints(&self) -> usize {
+        1
+    }
+
+    /// Returns degrees of constraints emitted by this gadget.
+    pub fn constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        vec![TransitionConstraintDegree::new(2)]
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates the constraint of this gadget for bit registers of the operands and the
+    /// `result` word, and writes the result into the first slot of `result`.
+    pub fn evaluate<E: FieldElement>(&self, a_bits: &[E], b_bits: &[E], c: E, result: &mut [E]) {
+        let n = self.size.num_bits() as usize;
+        debug_assert_eq!(n, a_bits.len(), "invalid number of bit registers");
+        debug_assert_eq!(n, b_bits.len(), "invalid number of bit registers");
+
+        let combined = a_bits
+            .iter()
+            .zip(b_bits)
+            .rev()
+            .fold(E::ZERO, |acc, (&a, &b)| {
+                let ab = a * b;
+                let bit = match self.op {
+                    BitwiseOp::And => ab,
+                    BitwiseOp::Or => a + b - ab,
+                    BitwiseOp::Xor => a + b - ab.double(),
+                };
+                acc.double() + bit
+            });
+        result[0] = c - combined;
+    }
+
+    // WITNESS GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the result of the operation enforced by this gadget for words `a` and `b`.
+    pub fn apply(&self, a: u64, b: u64) -> u64 {
+        match self.op {
+            BitwiseOp::And => a & b,
+            BitwiseOp::Or => a | b,
+            BitwiseOp::Xor => a ^ b,
+        }
+    }
+}
+
+// ROTATIONS AND SHIFTS
+// ================================================================================================
+
+/// Bit permutations supported by [WordShift] gadget.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ShiftOp {
+    RotateLeft(u32),
+    RotateRight(u32),
+    ShiftLeft(u32),
+    ShiftRight(u32),
+}
+
+/// Constrains a word to be the result of a rotation or a shift of another word.
+///
+/// For an operand a with bit registers a_i, the gadget emits a single linear constraint
+/// c - sum(a_{s(i)} * 2^i) = 0, where s maps every bit of the result to the bit of the operand
+/// it is taken from (bits which are shifted in are zeros).
+///
+/// The gadget does not use auxiliary registers; bit registers of the operand must be
+/// constrained via [WordBits].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WordShift {
+    size: WordSize,
+    op: ShiftOp,
+}
+
+impl WordShift {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a gadget for the specified rotation or shift of words of the specified size.
+    ///
+    /// # Panics
+    /// Panics if the rotation or shift amount is not smaller than the number of bits in a word.
+    pub fn new(size: WordSize, op: ShiftOp) -> Self {
+        let amount = match op {
+            ShiftOp::RotateLeft(r)
+            | ShiftOp::RotateRight(r)
+            | ShiftOp::ShiftLeft(r)
+            | ShiftOp::ShiftRight(r) => r,
+        };
+        assert!(
+            amount < size.num_bits(),
+            "shift amount must be smaller than {}, but was {}",
+            size.num_bits(),
+            amount
+        );
+        WordShift { size, op }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the size of words operated on by this gadget.
+    pub fn size(&self) -> WordSize {
+        self.size
+    }
+
+    /// Returns the operation enforced by this gadget.
+    pub fn op(&self) -> ShiftOp {
+        self.op
+    }
+
+    /// Returns the number of constraints emitted by this gadget.
+    pub fn num_constraints(&self) -> usize {
+        1
+    }
+
+    /// Returns degrees of constraints emitted by this gadget.
+    pub fn constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        vec![TransitionConstraintDegree::new(1)]
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates the constraint of this gadget for bit registers of the operand and the `result`
+    /// word, and writes the result into the first slot of `result`.
+    pub fn evaluate<E: FieldElement>(&self, a_bits: &[E], c: E, result: &mut [E]) {
+        let n = self.size.num_bits();
+        debug_assert_eq!(n as usize, a_bits.len(), "invalid number of bit registers");
+
+        let combined = (0..n)
+            .rev()
+            .fold(E::ZERO, |acc, i| match self.source_bit(i) {
+                Some(j) => acc.double() + a_bits[j as usize],
+                None => acc.double(),
+            });
+        result[0] = c - combined;
+    }
+
+    // WITNESS GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the result of the operation enforced by this gadget for word `a`.
+    pub fn apply(&self, a: u64) -> u64 {
+        let n = self.size.num_bits();
+        let mask = self.size.max_value();
+        match self.op {
+            ShiftOp::RotateLeft(r) if r > 0 => ((a << r) | (a >> (n - r))) & mask,
+            ShiftOp::RotateRight(r) if r > 0 => ((a >> r) | (a << (n - r))) & mask,
+            ShiftOp::ShiftLeft(r) => (a << r) & mask,
+            ShiftOp::ShiftRight(r) => a >> r,
+            _ => a,
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the index of the operand bit which is moved into bit `i` of the result, or None
+    /// if a zero is shifted into this bit.
+    fn source_bit(&self, i: u32) -> Option<u32> {
+        let n = self.size.num_bits();
+        match self.op {
+            ShiftOp::RotateLeft(r) => Some((i + n - r) % n),
+            ShiftOp::RotateRight(r) => Some((i + r) % n),
+            ShiftOp::ShiftLeft(r) => i.checked_sub(r),
+            ShiftOp::ShiftRight(r) => Some(i + r).filter(|&j| j < n),
+        }
+    }
+}
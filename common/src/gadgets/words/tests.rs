@@ -0,0 +1,135 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{BitwiseOp, ShiftOp, WordAdd, WordBits, WordBitwise, WordShift, WordSize};
+use math::field::{f128, f31, f64::BaseElement, FieldElement};
+
+// WORD DECOMPOSITION
+// ================================================================================================
+
+#[test]
+fn word_bits() {
+    let gadget = WordBits::new(WordSize::U32);
+    assert_eq!(32, gadget.num_registers());
+    assert_eq!(33, gadget.num_constraints());
+    assert_eq!(33, gadget.constraint_degrees().len());
+
+    let value = 0xdead_beef_u64;
+    let bits = gadget.get_bits::<BaseElement>(value);
+    let mut result = vec![BaseElement::ZERO; gadget.num_constraints()];
+    gadget.evaluate(BaseElement::from(value), &bits, &mut result);
+    assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+
+    gadget.evaluate(BaseElement::from(value + 1), &bits, &mut result);
+    assert_ne!(BaseElement::ZERO, result[32]);
+
+    assert!(gadget.fits_in::<BaseElement>());
+    assert!(!gadget.fits_in::<f31::BaseElement>());
+    assert!(!WordBits::new(WordSize::U64).fits_in::<BaseElement>());
+    assert!(WordBits::new(WordSize::U64).fits_in::<f128::BaseElement>());
+}
+
+// WORD ADDITION
+// ================================================================================================
+
+#[test]
+fn word_add() {
+    // three addends need two carry bits
+    let gadget = WordAdd::new(WordSize::U32, 3);
+    assert_eq!(2, gadget.num_registers());
+    assert_eq!(3, gadget.num_constraints());
+    assert_eq!(3, gadget.constraint_degrees().len());
+
+    let addends = [0xffff_ffff_u64, 0xffff_fffe, 7];
+    let (sum, carry) = gadget.get_witness::<BaseElement>(&addends);
+    assert_eq!(
+        0xffff_ffff_u32.wrapping_add(0xffff_fffe).wrapping_add(7) as u64,
+        sum
+    );
+    assert_eq!(vec![BaseElement::ZERO, BaseElement::ONE], carry);
+
+    let addends = addends
+        .iter()
+        .map(|&a| BaseElement::from(a))
+        .collect::<Vec<_>>();
+    let mut result = vec![BaseElement::ZERO; gadget.num_constraints()];
+    gadget.evaluate(&addends, BaseElement::from(sum), &carry, &mut result);
+    assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+
+    // an incorrect sum
+    gadget.evaluate(&addends, BaseElement::from(sum + 1), &carry, &mut result);
+    assert_ne!(BaseElement::ZERO, result[2]);
+
+    assert!(gadget.fits_in::<BaseElement>());
+    assert!(!WordAdd::new(WordSize::U64, 2).fits_in::<BaseElement>());
+    assert!(WordAdd::new(WordSize::U64, 5).fits_in::<f128::BaseElement>());
+}
+
+// BITWISE OPERATIONS
+// ================================================================================================
+
+#[test]
+fn word_bitwise() {
+    let bits = WordBits::new(WordSize::U64);
+    let a = 0x0123_4567_89ab_cdef_u64;
+    let b = 0xf0f0_0ff0_1234_ffff_u64;
+    let a_bits = bits.get_bits::<BaseElement>(a);
+    let b_bits = bits.get_bits::<BaseElement>(b);
+
+    for &(op, expected) in [
+        (BitwiseOp::And, a & b),
+        (BitwiseOp::Or, a | b),
+        (BitwiseOp::Xor, a ^ b),
+    ]
+    .iter()
+    {
+        let gadget = WordBitwise::new(WordSize::U64, op);
+        assert_eq!(expected, gadget.apply(a, b));
+
+        let mut result = [BaseElement::ONE];
+        gadget.evaluate(&a_bits, &b_bits, BaseElement::from(expected), &mut result);
+        assert_eq!(BaseElement::ZERO, result[0]);
+
+        gadget.evaluate(
+            &a_bits,
+            &b_bits,
+            BaseElement::from(expected ^ 1),
+            &mut result,
+        );
+        assert_ne!(BaseElement::ZERO, result[0]);
+    }
+}
+
+// ROTATIONS AND SHIFTS
+// ================================================================================================
+
+#[test]
+fn word_shift() {
+    let a = 0x8765_4321_u64;
+    let a_bits = WordBits::new(WordSize::U32).get_bits::<BaseElement>(a);
+
+    for &(op, expected) in [
+        (ShiftOp::RotateLeft(7), (a as u32).rotate_left(7)),
+        (ShiftOp::RotateRight(13), (a as u32).rotate_right(13)),
+        (ShiftOp::RotateRight(0), a as u32),
+        (ShiftOp::ShiftLeft(3), (a as u32) << 3),
+        (ShiftOp::ShiftRight(10), (a as u32) >> 10),
+    ]
+    .iter()
+    {
+        let gadget = WordShift::new(WordSize::U32, op);
+        assert_eq!(expected as u64, gadget.apply(a));
+
+        let mut result = [BaseElement::ONE];
+        gadget.evaluate(&a_bits, BaseElement::from(expected), &mut result);
+        assert_eq!(BaseElement::ZERO, result[0], "failed for {:?}", op);
+    }
+}
+
+#[test]
+#[should_panic(expected = "shift amount must be smaller than 32, but was 32")]
+fn word_shift_invalid_amount() {
+    WordShift::new(WordSize::U32, ShiftOp::RotateLeft(32));
+}
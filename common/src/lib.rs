@@ -4,20 +4,51 @@
 // LICENSE file in the root directory of this source tree.
 
 pub mod errors;
+pub mod gadgets;
+pub mod limits;
 pub mod proof;
 pub mod utils;
 
+pub use ::utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+#[cfg(feature = "encoding")]
+mod encoding;
+#[cfg(feature = "encoding")]
+pub use encoding::TextEncoding;
+
 mod context;
-pub use context::ComputationContext;
+pub use context::{
+    CeBlowupReport, ComputationContext, CostModel, ParameterCandidate, ParameterSearch,
+    ProvingCost, VerificationCost,
+};
 
 mod options;
-pub use options::{FieldExtension, HashFunction, ProofOptions};
+pub use math::field::ByteEncoding;
+pub use options::{
+    CoefficientScheme, ExtensionVisitor, FieldExtension, HashFunction, ProofOptions,
+    TraceRowHashing,
+};
 
 mod air;
 pub use air::{
     Air, Assertion, BoundaryConstraint, BoundaryConstraintGroup, ConstraintDivisor,
-    EvaluationFrame, TraceInfo, TransitionConstraintDegree, TransitionConstraintGroup,
+    ConstraintLayout, EvaluationFrame, PeriodicColumn, TraceInfo, TransitionConstraintDegree,
+    TransitionConstraintGroup,
 };
 
+mod public_inputs;
+pub use public_inputs::HashedPublicInputs;
+
+mod pow;
+pub use pow::{HashProofOfWork, NoProofOfWork, ProofOfWork};
+
 mod random;
-pub use random::{CompositionCoefficients, PublicCoin};
+pub use random::{build_constraint_seed, build_fri_seed, CompositionCoefficients, PublicCoin};
+
+mod composition;
+pub use composition::DeepComposer;
+
+mod constraints;
+pub use constraints::evaluate_constraints;
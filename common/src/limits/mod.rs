@@ -0,0 +1,93 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Limits on the parameters of computations and proofs.
+//!
+//! These are the same limits which are enforced by constructors of [ProofOptions] and
+//! [ComputationContext], by the prover, and by the parser of proofs used by the verifier. They can
+//! be used to validate user input (e.g., proof parameters supplied via a command line or an RPC
+//! call) before it is passed to the crate.
+//!
+//! [ProofOptions]: crate::ProofOptions
+//! [ComputationContext]: crate::ComputationContext
+
+use math::field::StarkField;
+
+#[cfg(test)]
+mod tests;
+
+// PROOF OPTIONS
+// ================================================================================================
+
+/// Smallest number of queries which can be specified in proof options.
+pub const MIN_QUERIES: usize = 1;
+
+/// Largest number of queries which can be specified in proof options.
+pub const MAX_QUERIES: usize = 128;
+
+/// Largest number of FRI queries which can be specified in proof options; FRI queries in excess
+/// of the number of queries open FRI layers only.
+pub const MAX_FRI_QUERIES: usize = 255;
+
+/// Smallest blowup factor which can be specified in proof options.
+pub const MIN_BLOWUP_FACTOR: usize = 4;
+
+/// Largest blowup factor which can be specified in proof options.
+pub const MAX_BLOWUP_FACTOR: usize = 256;
+
+/// Largest grinding factor (in bits) which can be specified in proof options.
+pub const MAX_GRINDING_FACTOR: u32 = 32;
+
+/// Largest number of trace rows which can be hashed into a single leaf of the trace commitment.
+pub const MAX_TRACE_LEAF_BATCHING: usize = 16;
+
+/// Largest degree of the FRI remainder polynomial which can be specified in proof options.
+pub const MAX_FRI_REMAINDER_DEGREE: usize = 4095;
+
+// COMPUTATIONS
+// ================================================================================================
+
+/// Smallest number of steps in an execution trace.
+pub const MIN_TRACE_LENGTH: usize = 8;
+
+/// Largest number of registers in an execution trace; this bounds the size of a single trace row
+/// and of the out-of-domain evaluation frame included in a proof.
+pub const MAX_TRACE_WIDTH: usize = 1 << 16;
+
+/// Returns the largest number of steps in an execution trace which can be proven in base field
+/// `B` with the specified blowup factor.
+///
+/// The LDE domain of the trace must fit into the two-adic subgroup of the field; thus, the trace
+/// length cannot exceed 2^(two-adicity) / blowup_factor.
+///
+/// # Panics
+/// Panics if `blowup_factor` is not a power of two.
+pub fn max_trace_length<B: StarkField>(blowup_factor: usize) -> usize {
+    assert!(
+        blowup_factor.is_power_of_two(),
+        "blowup_factor must be a power of 2"
+    );
+    let max_trace_depth = B::max_two_adicity().saturating_sub(blowup_factor.trailing_zeros());
+    1 << max_trace_depth.min(usize::BITS - 1)
+}
+
+// PROOFS
+// ================================================================================================
+
+/// Largest size (in bytes) of a serialized proof accepted by the verifier by default (64 MiB).
+pub const MAX_PROOF_SIZE: usize = 1 << 26;
+
+/// Largest number of FRI layers in a proof accepted by the verifier by default; folding an LDE
+/// domain of 2^64 elements by a factor of 4 takes at most 32 layers.
+pub const MAX_FRI_LAYERS: usize = 32;
+
+/// Largest size (in bytes) of the FRI remainder in a proof accepted by the verifier by default
+/// (32 MiB); this accommodates remainders of the largest degree supported by FRI at the largest
+/// blowup factor in a 256-bit extension field.
+pub const MAX_FRI_REMAINDER_SIZE: usize = 1 << 25;
+
+/// Largest amount of memory (in bytes) which the verifier allocates by default while parsing a
+/// proof (128 MiB).
+pub const MAX_PARSING_ALLOCATION: usize = 1 << 27;
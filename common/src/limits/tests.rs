@@ -0,0 +1,116 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::*;
+use crate::{
+    proof::ParsingLimits, ComputationContext, Deserializable, FieldExtension, HashFunction,
+    ProofOptions, Serializable, TransitionConstraintDegree,
+};
+use math::field::{f128, f31, f64};
+
+// PROOF OPTIONS
+// ================================================================================================
+
+#[test]
+fn proof_options_accept_limits() {
+    let options = ProofOptions::new(
+        MAX_QUERIES,
+        MAX_BLOWUP_FACTOR,
+        MAX_GRINDING_FACTOR,
+        HashFunction::Blake3_256,
+        FieldExtension::None,
+    )
+    .with_num_fri_queries(MAX_FRI_QUERIES)
+    .with_trace_leaf_batching(MAX_TRACE_LEAF_BATCHING)
+    .with_fri_max_remainder_degree(MAX_FRI_REMAINDER_DEGREE);
+
+    // options at the limits survive a serialization round trip
+    let bytes = options.to_bytes();
+    let parsed = ProofOptions::read_from_bytes(&bytes).unwrap();
+    assert_eq!(bytes, parsed.to_bytes());
+
+    let options = ProofOptions::new(
+        MIN_QUERIES,
+        MIN_BLOWUP_FACTOR,
+        0,
+        HashFunction::Blake3_256,
+        FieldExtension::None,
+    );
+    let bytes = options.to_bytes();
+    let parsed = ProofOptions::read_from_bytes(&bytes).unwrap();
+    assert_eq!(bytes, parsed.to_bytes());
+}
+
+#[test]
+#[should_panic(expected = "num_queries cannot be greater than 128")]
+fn proof_options_too_many_queries() {
+    ProofOptions::new(
+        MAX_QUERIES + 1,
+        8,
+        0,
+        HashFunction::Blake3_256,
+        FieldExtension::None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "blowup_factor cannot be greater than 256")]
+fn proof_options_blowup_factor_too_large() {
+    ProofOptions::new(
+        32,
+        MAX_BLOWUP_FACTOR * 2,
+        0,
+        HashFunction::Blake3_256,
+        FieldExtension::None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "grinding factor cannot be greater than 32")]
+fn proof_options_grinding_factor_too_large() {
+    ProofOptions::new(
+        32,
+        8,
+        MAX_GRINDING_FACTOR + 1,
+        HashFunction::Blake3_256,
+        FieldExtension::None,
+    );
+}
+
+// COMPUTATIONS
+// ================================================================================================
+
+#[test]
+fn max_trace_length_per_field() {
+    // the LDE domain must fit into the two-adic subgroup of the field
+    assert_eq!(1 << 30, max_trace_length::<f64::BaseElement>(4));
+    assert_eq!(1 << 29, max_trace_length::<f64::BaseElement>(8));
+    assert_eq!(1 << 37, max_trace_length::<f128::BaseElement>(8));
+    assert_eq!(
+        1 << 19,
+        max_trace_length::<f31::BaseElement>(MAX_BLOWUP_FACTOR)
+    );
+}
+
+#[test]
+#[should_panic(expected = "trace_width cannot be greater than 65536")]
+fn computation_context_trace_too_wide() {
+    let options = ProofOptions::new(32, 8, 0, HashFunction::Blake3_256, FieldExtension::None);
+    let degrees = vec![TransitionConstraintDegree::new(1)];
+    ComputationContext::new(MAX_TRACE_WIDTH + 1, MIN_TRACE_LENGTH, degrees, options);
+}
+
+// PROOFS
+// ================================================================================================
+
+#[test]
+fn default_parsing_limits() {
+    let limits = ParsingLimits::default();
+    assert_eq!(MAX_PROOF_SIZE, limits.max_proof_size());
+    assert_eq!(MAX_FRI_QUERIES, limits.max_queries());
+    assert_eq!(MAX_FRI_LAYERS, limits.max_fri_layers());
+    assert_eq!(MAX_FRI_REMAINDER_SIZE, limits.max_remainder_size());
+    assert_eq!(MAX_PARSING_ALLOCATION, limits.max_allocation());
+}
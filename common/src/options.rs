@@ -3,35 +3,91 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::limits::{
+    MAX_BLOWUP_FACTOR, MAX_FRI_QUERIES, MAX_FRI_REMAINDER_DEGREE, MAX_GRINDING_FACTOR, MAX_QUERIES,
+    MAX_TRACE_LEAF_BATCHING, MIN_BLOWUP_FACTOR, MIN_QUERIES,
+};
+use crate::proof::{LEGACY_PROOF_VERSION, PROOF_VERSION};
 use fri::FriOptions;
-use math::field::StarkField;
-use serde::{Deserialize, Serialize};
+use math::field::{
+    ByteEncoding, CubeExtension, ExtensionOf, QuadExtension, QuartExtension, StarkField,
+};
+use std::time::Duration;
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 // TYPES AND INTERFACES
 // ================================================================================================
 
+/// Code which is generic over the field in which a proof is generated or verified.
+///
+/// The field is specified at runtime via proof options; [FieldExtension::visit()] maps it to the
+/// corresponding extension of base field `B` and calls [visit()](ExtensionVisitor::visit)
+/// instantiated for that extension.
+pub trait ExtensionVisitor<B: StarkField> {
+    type Output;
+
+    /// Runs the generic code in field `E`.
+    fn visit<E: ExtensionOf<B>>(self) -> Self::Output;
+}
+
 #[repr(u8)]
-#[derive(Copy, Clone, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum FieldExtension {
     None = 1,
     Quadratic = 2,
+    Cubic = 3,
+    Quartic = 4,
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum HashFunction {
     Blake3_256 = 1,
     Sha3_256 = 2,
 }
 
-// TODO: validate field values on de-serialization
-#[derive(Clone, Serialize, Deserialize)]
+/// Defines how coefficients for the random linear combination of constraints are drawn from the
+/// public coin.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CoefficientScheme {
+    /// Two independent random elements are drawn for every constraint.
+    Independent = 1,
+    /// A single random element α is drawn for every constraint group, and coefficients for the
+    /// constraints in the group are set to consecutive powers of α (i.e., α, α^2, α^3 etc.).
+    Powers = 2,
+}
+
+/// Defines how rows of the extended execution trace are hashed into leaves of the trace
+/// commitment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraceRowHashing {
+    /// All values of the rows in a leaf are hashed together with a single invocation of the
+    /// hash function.
+    Flat,
+    /// Every row is split into chunks of the specified number of columns (the last chunk of a
+    /// row may be narrower), and every chunk is hashed separately; the leaf is the root of a
+    /// Merkle tree built from the chunk digests of all rows in the leaf. This makes it possible
+    /// to open individual chunks of committed rows.
+    Chunked(u8),
+}
+
+#[derive(Clone)]
 pub struct ProofOptions {
     num_queries: u8,
+    num_fri_queries: u8,
     blowup_factor: u8, // stored as power of 2
     grinding_factor: u8,
+    grinding_budget: Option<u32>, // stored in milliseconds
     hash_fn: HashFunction,
+    transcript_hash_fn: HashFunction,
     field_extension: FieldExtension,
+    trace_leaf_batching: u8, // stored as power of 2
+    compact_trace_queries: bool,
+    fri_max_remainder_degree: Option<u16>,
+    coefficient_scheme: CoefficientScheme,
+    trace_row_hashing: TraceRowHashing,
+    byte_encoding: ByteEncoding,
 }
 
 // PROOF OPTIONS IMPLEMENTATION
@@ -52,33 +108,228 @@ impl ProofOptions {
         hash_fn: HashFunction,
         field_extension: FieldExtension,
     ) -> ProofOptions {
-        assert!(num_queries > 0, "num_queries must be greater than 0");
-        assert!(num_queries <= 128, "num_queries cannot be greater than 128");
+        assert!(
+            num_queries >= MIN_QUERIES,
+            "num_queries cannot be smaller than {}",
+            MIN_QUERIES
+        );
+        assert!(
+            num_queries <= MAX_QUERIES,
+            "num_queries cannot be greater than {}",
+            MAX_QUERIES
+        );
 
         assert!(
             blowup_factor.is_power_of_two(),
             "blowup_factor must be a power of 2"
         );
-        assert!(blowup_factor >= 4, "blowup_factor cannot be smaller than 4");
         assert!(
-            blowup_factor <= 256,
-            "blowup_factor cannot be greater than 256"
+            blowup_factor >= MIN_BLOWUP_FACTOR,
+            "blowup_factor cannot be smaller than {}",
+            MIN_BLOWUP_FACTOR
+        );
+        assert!(
+            blowup_factor <= MAX_BLOWUP_FACTOR,
+            "blowup_factor cannot be greater than {}",
+            MAX_BLOWUP_FACTOR
         );
 
         assert!(
-            grinding_factor <= 32,
-            "grinding factor cannot be greater than 32"
+            grinding_factor <= MAX_GRINDING_FACTOR,
+            "grinding factor cannot be greater than {}",
+            MAX_GRINDING_FACTOR
         );
 
         ProofOptions {
             num_queries: num_queries as u8,
+            num_fri_queries: num_queries as u8,
             blowup_factor: blowup_factor.trailing_zeros() as u8,
             grinding_factor: grinding_factor as u8,
+            grinding_budget: None,
             hash_fn,
+            transcript_hash_fn: hash_fn,
             field_extension,
+            trace_leaf_batching: 0,
+            compact_trace_queries: false,
+            fri_max_remainder_degree: None,
+            coefficient_scheme: CoefficientScheme::Independent,
+            trace_row_hashing: TraceRowHashing::Flat,
+            byte_encoding: ByteEncoding::Native,
         }
     }
 
+    /// Returns these proof options with the trace leaf batching factor set to the specified
+    /// value; the batching factor must be a power of two between 1 and 16.
+    ///
+    /// By default, every row of the extended execution trace is hashed into its own leaf of the
+    /// trace commitment. With a batching factor of k, k adjacent rows are hashed into a single
+    /// leaf. This reduces the depth of the trace commitment tree by log2(k), and thus shortens
+    /// Merkle authentication paths included in a proof; but each query then opens all k rows
+    /// of a leaf.
+    pub fn with_trace_leaf_batching(mut self, batching_factor: usize) -> ProofOptions {
+        assert!(
+            batching_factor.is_power_of_two(),
+            "trace leaf batching factor must be a power of 2"
+        );
+        assert!(
+            batching_factor <= MAX_TRACE_LEAF_BATCHING,
+            "trace leaf batching factor cannot be greater than {}",
+            MAX_TRACE_LEAF_BATCHING
+        );
+        self.trace_leaf_batching = batching_factor.trailing_zeros() as u8;
+        self
+    }
+
+    /// Returns these proof options with the number of FRI queries set to the specified value;
+    /// the number of FRI queries must be an integer between num_queries and 255.
+    ///
+    /// By default, every query opens the trace and constraint commitments as well as all layers
+    /// of the FRI proof. FRI queries in excess of num_queries open FRI layers only; thus, they
+    /// increase soundness of the low-degree test without increasing the number of trace and
+    /// constraint openings included in a proof.
+    pub fn with_num_fri_queries(mut self, num_fri_queries: usize) -> ProofOptions {
+        assert!(
+            num_fri_queries >= self.num_queries(),
+            "num_fri_queries cannot be smaller than num_queries"
+        );
+        assert!(
+            num_fri_queries <= MAX_FRI_QUERIES,
+            "num_fri_queries cannot be greater than {}",
+            MAX_FRI_QUERIES
+        );
+        self.num_fri_queries = num_fri_queries as u8;
+        self
+    }
+
+    /// Returns these proof options with compact encoding of trace queries enabled.
+    ///
+    /// By default, every queried trace row is included in a proof as a sequence of serialized
+    /// field elements. With compact encoding, columns which hold the same value in all queried
+    /// rows are included in a proof only once. Queried rows belong to the extended execution
+    /// trace, so this applies to registers which are constant over the execution trace (e.g.,
+    /// unused or zero-padded registers in wide traces). Compact encoding has no effect on the
+    /// trace commitment, and thus, on proof soundness.
+    pub fn with_compact_trace_queries(mut self) -> ProofOptions {
+        self.compact_trace_queries = true;
+        self
+    }
+
+    /// Returns these proof options with FRI folding terminated as soon as the degree of the
+    /// folded polynomial does not exceed `max_degree`; `max_degree + 1` must be a power of two
+    /// not greater than 4096.
+    ///
+    /// By default, FRI folding continues until evaluations of the folded polynomial fit into a
+    /// remainder of at most 256 elements. A higher remainder degree reduces the number of FRI
+    /// layers, and thus, the number of commitments and authentication paths the verifier needs
+    /// to check, at the expense of a larger remainder included in a proof and more work to
+    /// check the degree of the remainder. This is advantageous when interpolating medium-degree
+    /// polynomials is cheap for the verifier, and the number of FRI layers matters more than
+    /// proof size.
+    pub fn with_fri_max_remainder_degree(mut self, max_degree: usize) -> ProofOptions {
+        assert!(
+            (max_degree + 1).is_power_of_two(),
+            "fri_max_remainder_degree plus one must be a power of 2"
+        );
+        assert!(
+            max_degree <= MAX_FRI_REMAINDER_DEGREE,
+            "fri_max_remainder_degree cannot be greater than {}",
+            MAX_FRI_REMAINDER_DEGREE
+        );
+        self.fri_max_remainder_degree = Some(max_degree as u16);
+        self
+    }
+
+    /// Returns these proof options with proof-of-work difficulty determined by a wall-clock
+    /// budget; the budget must be at least 1 millisecond, and at most u32::MAX milliseconds.
+    ///
+    /// By default, the prover searches for a nonce which satisfies the grinding factor. With a
+    /// grinding budget, the prover measures the rate at which it can check nonces, and selects
+    /// the largest difficulty for which a nonce is expected to be found within the budget; the
+    /// grinding factor then specifies the minimum difficulty. The selected difficulty is
+    /// recorded in a proof, and is used by the verifier to check proof-of-work and to compute
+    /// the security level of the proof. Since the difficulty depends on the speed of the
+    /// machine, proofs generated with a grinding budget are not deterministic.
+    pub fn with_grinding_budget(mut self, budget: Duration) -> ProofOptions {
+        let budget_ms = budget.as_millis();
+        assert!(budget_ms > 0, "grinding budget must be at least 1 ms");
+        assert!(
+            budget_ms <= u32::MAX as u128,
+            "grinding budget cannot be greater than {} ms",
+            u32::MAX
+        );
+        self.grinding_budget = Some(budget_ms as u32);
+        self
+    }
+
+    /// Returns these proof options with the hash function used for the Fiat-Shamir transcript
+    /// set to the specified function.
+    ///
+    /// By default, the same hash function is used for building commitments (Merkle trees over
+    /// the extended execution trace, constraint evaluations, and FRI layers) and for the
+    /// transcript (deriving seeds, drawing pseudo-random values, and proof-of-work). Setting a
+    /// different transcript hash function makes it possible to, for example, use a hash function
+    /// which is cheap to evaluate in a recursive circuit for the transcript, while using a hash
+    /// function which is fast on CPUs for the commitments.
+    pub fn with_transcript_hash_fn(mut self, hash_fn: HashFunction) -> ProofOptions {
+        self.transcript_hash_fn = hash_fn;
+        self
+    }
+
+    /// Returns these proof options with constraint composition coefficients drawn according to
+    /// the specified scheme.
+    ///
+    /// By default, two independent random coefficients are drawn for every constraint. With
+    /// [CoefficientScheme::Powers], a single random element is drawn per constraint group, and
+    /// its powers are used as coefficients. This reduces the number of elements drawn from the
+    /// public coin (which makes verifying a proof inside a recursive circuit cheaper), but
+    /// reduces soundness of constraint composition by up to log2 of twice the number of
+    /// constraints. Security level reported for a proof conservatively accounts for this loss
+    /// using the total number of constraints; for fields of 128 bits or more it is negligible.
+    pub fn with_coefficient_scheme(mut self, scheme: CoefficientScheme) -> ProofOptions {
+        self.coefficient_scheme = scheme;
+        self
+    }
+
+    /// Returns these proof options with rows of the extended execution trace hashed into the
+    /// trace commitment according to the specified strategy; for [TraceRowHashing::Chunked],
+    /// the number of columns in a chunk must be greater than zero.
+    ///
+    /// By default, all values of a leaf are hashed together. With chunked hashing, the trace
+    /// commitment can be opened at a subset of the columns of the queried rows: only the chunks
+    /// containing these columns and short authentication paths within the leaves are revealed
+    /// (see `CommittedTrace::open_chunks()` in the prover crate). This is useful when trace
+    /// values are consumed by parties which need only a few of the columns. STARK proofs still
+    /// open entire rows because all columns are needed to compute the DEEP composition, and
+    /// thus, chunked hashing requires more hashing for both, the prover and the verifier.
+    pub fn with_trace_row_hashing(mut self, row_hashing: TraceRowHashing) -> ProofOptions {
+        if let TraceRowHashing::Chunked(chunk_width) = row_hashing {
+            assert!(
+                chunk_width > 0,
+                "trace row chunk width must be greater than 0"
+            );
+        }
+        self.trace_row_hashing = row_hashing;
+        self
+    }
+
+    /// Returns these proof options with field elements and integers encoded into bytes according
+    /// to the specified encoding.
+    ///
+    /// By default, field elements are hashed into commitments and written into proofs in their
+    /// native little-endian representation. With [ByteEncoding::EvmWords], every coefficient of
+    /// a field element (e.g., of queried trace rows, constraint evaluations, out-of-domain
+    /// evaluations, FRI layers and the FRI remainder) is encoded as a 32-byte big-endian word,
+    /// the proof-of-work nonce is written into a proof as a 32-byte big-endian word, and
+    /// counters hashed together with seeds to draw pseudo-random values are encoded as
+    /// big-endian words as well. Commitments and seeds are 32-byte digests, and thus, are EVM
+    /// words already. This way, a verifier running on the EVM can hash values taken from
+    /// calldata and interpret digests as `uint256` values without reordering bytes, at the
+    /// expense of larger proofs.
+    pub fn with_byte_encoding(mut self, encoding: ByteEncoding) -> ProofOptions {
+        self.byte_encoding = encoding;
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -89,6 +340,13 @@ impl ProofOptions {
         self.num_queries as usize
     }
 
+    /// Returns number of queries against FRI layers of a STARK proof; the first num_queries of
+    /// these are also queries against the trace and constraint commitments. This is equal to
+    /// num_queries unless explicitly set otherwise.
+    pub fn num_fri_queries(&self) -> usize {
+        self.num_fri_queries as usize
+    }
+
     /// Returns trace blowup factor for a STARK proof (i.e. a factor by which the execution
     /// trace is extended). This directly impacts proof soundness as each query adds roughly
     /// log2(lde_domain_size / constraint_evaluation_domain_size) bits of security to a proof.
@@ -104,16 +362,34 @@ impl ProofOptions {
     /// commitment. Thus, higher grinding factor makes it more difficult to forge a STARK
     /// proof. However, setting grinding factor too high (e.g. higher than 20) will adversely
     /// affect prover time.
+    ///
+    /// When a grinding budget is set, this is the minimum difficulty of proof-of-work; the
+    /// difficulty achieved by the prover is recorded in the proof.
     pub fn grinding_factor(&self) -> u32 {
         self.grinding_factor as u32
     }
 
-    /// Returns a hash functions to be used during STARK proof construction. Security of a
-    /// STARK proof is bounded by collision resistance of the used hash function.
+    /// Returns the wall-clock budget for proof-of-work, or None if the difficulty of
+    /// proof-of-work is set by the grinding factor alone.
+    pub fn grinding_budget(&self) -> Option<Duration> {
+        self.grinding_budget
+            .map(|budget_ms| Duration::from_millis(budget_ms as u64))
+    }
+
+    /// Returns a hash function to be used for building commitments during STARK proof
+    /// construction. Security of a STARK proof is bounded by collision resistance of the used
+    /// hash function.
     pub fn hash_fn(&self) -> HashFunction {
         self.hash_fn
     }
 
+    /// Returns a hash function to be used for the Fiat-Shamir transcript during STARK proof
+    /// construction; this is the same as [hash_fn()](ProofOptions::hash_fn) unless explicitly
+    /// set otherwise.
+    pub fn transcript_hash_fn(&self) -> HashFunction {
+        self.transcript_hash_fn
+    }
+
     /// Returns a value indicating whether an extension field should be used for the composition
     /// polynomial. Using a field extension increases maximum security level of a proof, but
     /// also has non-negligible impact on prover performance.
@@ -121,6 +397,40 @@ impl ProofOptions {
         self.field_extension
     }
 
+    /// Returns the number of adjacent rows of the extended execution trace which are hashed
+    /// together into a single leaf of the trace commitment.
+    pub fn trace_leaf_batching(&self) -> usize {
+        1 << (self.trace_leaf_batching as usize)
+    }
+
+    /// Returns the strategy by which rows of the extended execution trace are hashed into leaves
+    /// of the trace commitment.
+    pub fn trace_row_hashing(&self) -> TraceRowHashing {
+        self.trace_row_hashing
+    }
+
+    /// Returns the encoding of field elements and integers which are hashed into commitments and
+    /// seeds, or written into a proof.
+    pub fn byte_encoding(&self) -> ByteEncoding {
+        self.byte_encoding
+    }
+
+    /// Returns `true` if queried trace rows are included in a proof using compact encoding.
+    pub fn compact_trace_queries(&self) -> bool {
+        self.compact_trace_queries
+    }
+
+    /// Returns the largest degree of the folded polynomial at which FRI folding is terminated, or
+    /// None if folding is terminated based on the default remainder size.
+    pub fn fri_max_remainder_degree(&self) -> Option<usize> {
+        self.fri_max_remainder_degree.map(|degree| degree as usize)
+    }
+
+    /// Returns the scheme by which constraint composition coefficients are drawn.
+    pub fn coefficient_scheme(&self) -> CoefficientScheme {
+        self.coefficient_scheme
+    }
+
     /// Returns the offset by which the low-degree extension domain is shifted in relation to the
     /// trace domain. Currently, this is hard-coded to the generator of the underlying base field.
     pub fn domain_offset<B: StarkField>(&self) -> B {
@@ -129,7 +439,12 @@ impl ProofOptions {
 
     /// Returns options for FRI protocol instantiated with parameters from this proof options.
     pub fn to_fri_options<B: StarkField>(&self) -> FriOptions<B> {
-        FriOptions::new(self.blowup_factor(), self.domain_offset())
+        let options = FriOptions::new(self.blowup_factor(), self.domain_offset())
+            .with_byte_encoding(self.byte_encoding);
+        match self.fri_max_remainder_degree() {
+            Some(max_degree) => options.with_max_remainder_degree(max_degree),
+            None => options,
+        }
     }
 }
 
@@ -141,4 +456,252 @@ impl FieldExtension {
     pub fn is_none(&self) -> bool {
         matches!(self, Self::None)
     }
+
+    /// Returns the degree of the field extension; this is 1 when field extension is not used.
+    pub fn degree(&self) -> usize {
+        match self {
+            Self::None => 1,
+            Self::Quadratic => 2,
+            Self::Cubic => 3,
+            Self::Quartic => 4,
+        }
+    }
+
+    /// Runs the `visitor` in the extension of base field `B` defined by this field extension;
+    /// when field extension is not used, the visitor runs in the base field. This is the only
+    /// place where field extensions are mapped to field types.
+    pub fn visit<B: StarkField, V: ExtensionVisitor<B>>(&self, visitor: V) -> V::Output {
+        match self {
+            Self::None => visitor.visit::<B>(),
+            Self::Quadratic => visitor.visit::<QuadExtension<B>>(),
+            Self::Cubic => visitor.visit::<CubeExtension<B>>(),
+            Self::Quartic => visitor.visit::<QuartExtension<B>>(),
+        }
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for FieldExtension {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for FieldExtension {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            1 => Ok(Self::None),
+            2 => Ok(Self::Quadratic),
+            3 => Ok(Self::Cubic),
+            4 => Ok(Self::Quartic),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "{} is not a valid field extension",
+                value
+            ))),
+        }
+    }
+}
+
+impl Serializable for HashFunction {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for HashFunction {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            1 => Ok(Self::Blake3_256),
+            2 => Ok(Self::Sha3_256),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "{} is not a valid hash function",
+                value
+            ))),
+        }
+    }
+}
+
+impl Serializable for CoefficientScheme {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for CoefficientScheme {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            1 => Ok(Self::Independent),
+            2 => Ok(Self::Powers),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "{} is not a valid coefficient scheme",
+                value
+            ))),
+        }
+    }
+}
+
+impl Serializable for TraceRowHashing {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            Self::Flat => target.write_u8(1),
+            Self::Chunked(chunk_width) => {
+                target.write_u8(2);
+                target.write_u8(*chunk_width);
+            }
+        }
+    }
+}
+
+impl Deserializable for TraceRowHashing {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            1 => Ok(Self::Flat),
+            2 => match source.read_u8()? {
+                0 => Err(invalid_option("trace row chunk width", 0)),
+                chunk_width => Ok(Self::Chunked(chunk_width)),
+            },
+            value => Err(DeserializationError::InvalidValue(format!(
+                "{} is not a valid trace row hashing strategy",
+                value
+            ))),
+        }
+    }
+}
+
+impl Serializable for ProofOptions {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.write_versioned(target, PROOF_VERSION);
+    }
+}
+
+impl Deserializable for ProofOptions {
+    /// Reads proof options from the `source`; returns an error if any of the options is outside
+    /// of the range allowed by the corresponding constructor or builder method.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Self::read_versioned(source, PROOF_VERSION)
+    }
+}
+
+impl ProofOptions {
+    /// Writes these options into the `target` in the layout of proofs of the specified
+    /// `version`; for [LEGACY_PROOF_VERSION], the byte encoding is omitted.
+    pub(crate) fn write_versioned<W: ByteWriter>(&self, target: &mut W, version: u8) {
+        target.write_u8(self.num_queries);
+        target.write_u8(self.num_fri_queries);
+        target.write_u8(self.blowup_factor);
+        target.write_u8(self.grinding_factor);
+        match self.grinding_budget {
+            Some(budget_ms) => {
+                target.write_u8(1);
+                target.write_u32(budget_ms);
+            }
+            None => target.write_u8(0),
+        }
+        target.write(&self.hash_fn);
+        target.write(&self.transcript_hash_fn);
+        target.write(&self.field_extension);
+        target.write_u8(self.trace_leaf_batching);
+        target.write(&self.compact_trace_queries);
+        match self.fri_max_remainder_degree {
+            Some(max_degree) => {
+                target.write_u8(1);
+                target.write_u16(max_degree);
+            }
+            None => target.write_u8(0),
+        }
+        target.write(&self.coefficient_scheme);
+        target.write(&self.trace_row_hashing);
+        if version != LEGACY_PROOF_VERSION {
+            target.write(&self.byte_encoding);
+        }
+    }
+
+    /// Reads proof options laid out as in proofs of the specified `version` from the `source`;
+    /// options of [LEGACY_PROOF_VERSION] proofs do not include the byte encoding, and always use
+    /// the native encoding.
+    pub(crate) fn read_versioned<R: ByteReader>(
+        source: &mut R,
+        version: u8,
+    ) -> Result<Self, DeserializationError> {
+        let num_queries = source.read_u8()?;
+        if !(MIN_QUERIES..=MAX_QUERIES).contains(&(num_queries as usize)) {
+            return Err(invalid_option("num_queries", num_queries));
+        }
+        let num_fri_queries = source.read_u8()?;
+        if num_fri_queries < num_queries {
+            return Err(invalid_option("num_fri_queries", num_fri_queries));
+        }
+        // blowup factor and trace leaf batching are stored as powers of two
+        let blowup_factor = source.read_u8()?;
+        let min_blowup_depth = MIN_BLOWUP_FACTOR.trailing_zeros();
+        let max_blowup_depth = MAX_BLOWUP_FACTOR.trailing_zeros();
+        if !(min_blowup_depth..=max_blowup_depth).contains(&(blowup_factor as u32)) {
+            return Err(invalid_option("blowup_factor", blowup_factor));
+        }
+        let grinding_factor = source.read_u8()?;
+        if grinding_factor as u32 > MAX_GRINDING_FACTOR {
+            return Err(invalid_option("grinding_factor", grinding_factor));
+        }
+        let grinding_budget = match source.read_u8()? {
+            0 => None,
+            1 => match source.read_u32()? {
+                0 => return Err(invalid_option("grinding_budget", 0)),
+                budget_ms => Some(budget_ms),
+            },
+            value => return Err(invalid_option("grinding_budget flag", value)),
+        };
+        let hash_fn = source.read()?;
+        let transcript_hash_fn = source.read()?;
+        let field_extension = source.read()?;
+        let trace_leaf_batching = source.read_u8()?;
+        if trace_leaf_batching as u32 > MAX_TRACE_LEAF_BATCHING.trailing_zeros() {
+            return Err(invalid_option("trace_leaf_batching", trace_leaf_batching));
+        }
+        let compact_trace_queries = source.read()?;
+        let fri_max_remainder_degree = match source.read_u8()? {
+            0 => None,
+            1 => {
+                let max_degree = source.read_u16()?;
+                if max_degree as usize > MAX_FRI_REMAINDER_DEGREE
+                    || !(max_degree + 1).is_power_of_two()
+                {
+                    return Err(invalid_option("fri_max_remainder_degree", max_degree));
+                }
+                Some(max_degree)
+            }
+            value => return Err(invalid_option("fri_max_remainder_degree flag", value)),
+        };
+        let coefficient_scheme = source.read()?;
+        let trace_row_hashing = source.read()?;
+        let byte_encoding = match version {
+            LEGACY_PROOF_VERSION => ByteEncoding::Native,
+            _ => source.read()?,
+        };
+
+        Ok(ProofOptions {
+            num_queries,
+            num_fri_queries,
+            blowup_factor,
+            grinding_factor,
+            grinding_budget,
+            hash_fn,
+            transcript_hash_fn,
+            field_extension,
+            trace_leaf_batching,
+            compact_trace_queries,
+            fri_max_remainder_degree,
+            coefficient_scheme,
+            trace_row_hashing,
+            byte_encoding,
+        })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn invalid_option<T: core::fmt::Display>(name: &str, value: T) -> DeserializationError {
+    DeserializationError::InvalidValue(format!("{} is not a valid value for {}", value, name))
 }
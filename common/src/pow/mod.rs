@@ -0,0 +1,185 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::limits::MAX_GRINDING_FACTOR;
+use crypto::HashFunction;
+use math::field::ByteEncoding;
+use std::convert::TryInto;
+
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+// PROOF OF WORK
+// ================================================================================================
+
+/// Defines how proof-of-work is applied to the query seed.
+///
+/// Before query positions are drawn, the prover must find a nonce which, combined with the seed
+/// derived from FRI layer commitments, satisfies the difficulty specified by the grinding factor;
+/// the final query seed is derived from the seed and the nonce. This makes it more expensive for
+/// a malicious prover to search for a favorable set of query positions.
+///
+/// The prover and the verifier must use the same scheme; a proof generated with one scheme will
+/// not verify with another. [HashProofOfWork] is the scheme used by default.
+pub trait ProofOfWork {
+    /// Returns a nonce which satisfies the difficulty specified by `grinding_factor` for the
+    /// `seed`, together with the query seed derived from the `seed` and the nonce; the nonce is
+    /// combined with the seed according to the `encoding`.
+    fn solve(
+        &self,
+        seed: &[u8; 32],
+        grinding_factor: u32,
+        hash_fn: HashFunction,
+        encoding: ByteEncoding,
+    ) -> (u64, [u8; 32]);
+
+    /// Returns the query seed derived from the `seed` and the `nonce` if the nonce satisfies the
+    /// difficulty specified by `grinding_factor`; otherwise, returns None.
+    fn verify(
+        &self,
+        seed: &[u8; 32],
+        nonce: u64,
+        grinding_factor: u32,
+        hash_fn: HashFunction,
+        encoding: ByteEncoding,
+    ) -> Option<[u8; 32]>;
+
+    /// Returns the largest grinding factor supported by this scheme; a grinding budget never
+    /// raises the grinding factor above this value.
+    fn max_grinding_factor(&self) -> u32 {
+        MAX_GRINDING_FACTOR
+    }
+}
+
+// HASH PROOF OF WORK
+// ================================================================================================
+
+/// Proof-of-work which requires hash(seed || nonce) to have at least `grinding_factor` trailing
+/// zero bits in its first 8 bytes interpreted as a little-endian integer, or, in EVM word
+/// encoding, in its last 8 bytes interpreted as a big-endian integer (i.e., in the low bits of
+/// the digest interpreted as a `uint256`); the resulting hash becomes the query seed.
+///
+/// When there are several valid nonces, the smallest one is always selected, and thus, proofs
+/// do not depend on the number of threads used to search for the nonce.
+pub struct HashProofOfWork;
+
+impl ProofOfWork for HashProofOfWork {
+    fn solve(
+        &self,
+        seed: &[u8; 32],
+        grinding_factor: u32,
+        hash_fn: HashFunction,
+        encoding: ByteEncoding,
+    ) -> (u64, [u8; 32]) {
+        let is_valid = |nonce: u64| {
+            self.verify(seed, nonce, grinding_factor, hash_fn, encoding)
+                .is_some()
+        };
+
+        #[cfg(not(feature = "concurrent"))]
+        let nonce = (1..u64::MAX)
+            .find(|&nonce| is_valid(nonce))
+            .expect("nonce not found");
+
+        #[cfg(feature = "concurrent")]
+        let nonce = (1..u64::MAX)
+            .into_par_iter()
+            .find_first(|&nonce| is_valid(nonce))
+            .expect("nonce not found");
+
+        (nonce, hash_with_nonce(seed, nonce, hash_fn, encoding))
+    }
+
+    fn verify(
+        &self,
+        seed: &[u8; 32],
+        nonce: u64,
+        grinding_factor: u32,
+        hash_fn: HashFunction,
+        encoding: ByteEncoding,
+    ) -> Option<[u8; 32]> {
+        let result = hash_with_nonce(seed, nonce, hash_fn, encoding);
+        let head = match encoding {
+            ByteEncoding::Native => u64::from_le_bytes(result[..8].try_into().unwrap()),
+            ByteEncoding::EvmWords => u64::from_be_bytes(result[24..].try_into().unwrap()),
+        };
+        if head.trailing_zeros() >= grinding_factor {
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+// NO PROOF OF WORK
+// ================================================================================================
+
+/// Disables proof-of-work: the nonce is always 0, and the seed derived from FRI layer
+/// commitments is used as the query seed directly.
+///
+/// This is meant for environments in which the prover is trusted. Since such proofs provide no
+/// additional security from grinding, the grinding factor must be set to 0: the prover panics
+/// otherwise, and the verifier rejects such proofs.
+pub struct NoProofOfWork;
+
+impl ProofOfWork for NoProofOfWork {
+    fn solve(
+        &self,
+        seed: &[u8; 32],
+        grinding_factor: u32,
+        _hash_fn: HashFunction,
+        _encoding: ByteEncoding,
+    ) -> (u64, [u8; 32]) {
+        assert_eq!(
+            0, grinding_factor,
+            "grinding factor must be 0 when proof-of-work is disabled"
+        );
+        (0, *seed)
+    }
+
+    fn verify(
+        &self,
+        seed: &[u8; 32],
+        nonce: u64,
+        grinding_factor: u32,
+        _hash_fn: HashFunction,
+        _encoding: ByteEncoding,
+    ) -> Option<[u8; 32]> {
+        if nonce == 0 && grinding_factor == 0 {
+            Some(*seed)
+        } else {
+            None
+        }
+    }
+
+    /// Returns 0 because no nonces are searched for; thus, a grinding budget has no effect on
+    /// proofs generated without proof-of-work.
+    fn max_grinding_factor(&self) -> u32 {
+        0
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Computes hash(seed || nonce), where the seed occupies the first 32 bytes of a 64-byte buffer,
+/// and the nonce encoded according to the `encoding` occupies the last 8 bytes.
+fn hash_with_nonce(
+    seed: &[u8; 32],
+    nonce: u64,
+    hash_fn: HashFunction,
+    encoding: ByteEncoding,
+) -> [u8; 32] {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(seed);
+    buf[56..].copy_from_slice(&encoding.encode_u64(nonce));
+
+    let mut result = [0u8; 32];
+    hash_fn(&buf, &mut result);
+    result
+}
@@ -0,0 +1,84 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{HashProofOfWork, NoProofOfWork, ProofOfWork};
+use crypto::{hash::Blake3_256, Hasher};
+use math::field::ByteEncoding;
+
+const NATIVE: ByteEncoding = ByteEncoding::Native;
+
+#[test]
+fn hash_pow_solve_verify() {
+    let hash_fn = Blake3_256::hash_fn();
+    let seed = [1; 32];
+    let (nonce, query_seed) = HashProofOfWork.solve(&seed, 8, hash_fn, NATIVE);
+    assert_ne!(seed, query_seed);
+    assert!(query_seed[0] == 0);
+    assert_eq!(
+        Some(query_seed),
+        HashProofOfWork.verify(&seed, nonce, 8, hash_fn, NATIVE)
+    );
+
+    // the smallest valid nonce is selected, so all smaller nonces must be invalid
+    for other_nonce in 1..nonce {
+        assert_eq!(
+            None,
+            HashProofOfWork.verify(&seed, other_nonce, 8, hash_fn, NATIVE)
+        );
+    }
+
+    // a nonce which is valid for one seed is not necessarily valid for another
+    let other_seed = [2; 32];
+    let (other_nonce, _) = HashProofOfWork.solve(&other_seed, 8, hash_fn, NATIVE);
+    assert_ne!(nonce, other_nonce);
+}
+
+#[test]
+fn hash_pow_solve_verify_evm_words() {
+    let hash_fn = Blake3_256::hash_fn();
+    let seed = [1; 32];
+    let encoding = ByteEncoding::EvmWords;
+    let (nonce, query_seed) = HashProofOfWork.solve(&seed, 8, hash_fn, encoding);
+    assert_eq!(
+        Some(query_seed),
+        HashProofOfWork.verify(&seed, nonce, 8, hash_fn, encoding)
+    );
+
+    // the nonce is hashed as a big-endian word following the seed, and the difficulty is
+    // checked against the low bits of the digest interpreted as a big-endian integer
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(&seed);
+    buf[56..].copy_from_slice(&nonce.to_be_bytes());
+    let mut expected_seed = [0u8; 32];
+    hash_fn(&buf, &mut expected_seed);
+    assert_eq!(expected_seed, query_seed);
+    assert_eq!(0, query_seed[31]);
+}
+
+#[test]
+fn no_pow_solve_verify() {
+    let hash_fn = Blake3_256::hash_fn();
+    let seed = [1; 32];
+    let (nonce, query_seed) = NoProofOfWork.solve(&seed, 0, hash_fn, NATIVE);
+    assert_eq!(0, nonce);
+    assert_eq!(seed, query_seed);
+    assert_eq!(
+        Some(seed),
+        NoProofOfWork.verify(&seed, 0, 0, hash_fn, NATIVE)
+    );
+
+    // proofs with non-zero nonce or with non-zero grinding factor are rejected
+    assert_eq!(None, NoProofOfWork.verify(&seed, 1, 0, hash_fn, NATIVE));
+    assert_eq!(None, NoProofOfWork.verify(&seed, 0, 8, hash_fn, NATIVE));
+
+    // a grinding budget cannot raise the grinding factor when proof-of-work is disabled
+    assert_eq!(0, NoProofOfWork.max_grinding_factor());
+}
+
+#[test]
+#[should_panic(expected = "grinding factor must be 0 when proof-of-work is disabled")]
+fn no_pow_solve_with_grinding() {
+    NoProofOfWork.solve(&[1; 32], 8, Blake3_256::hash_fn(), NATIVE);
+}
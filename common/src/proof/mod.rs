@@ -0,0 +1,614 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{CoefficientScheme, ProofOptions, TraceRowHashing};
+use crypto::{BatchMerkleProof, Hasher};
+use fri::FriProof;
+use math::{
+    errors::SerializationError,
+    field::{ByteEncoding, FieldElement, StarkField},
+    utils::log2_unchecked,
+};
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+mod parsing;
+use parsing::ProofParser;
+pub use parsing::{parse_proof, ParsingLimits};
+
+mod opening;
+pub use opening::TraceChunkOpening;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+const GRINDING_CONTRIBUTION_FLOOR: u32 = 80;
+
+/// Version of the proof layout produced by this revision of the prover. This value must be
+/// incremented whenever the serialized structure of [StarkProof] or the way it is verified
+/// changes so that verifiers can select the appropriate parsing and verification path.
+pub const PROOF_VERSION: u8 = 17;
+
+/// Version of the proof layout produced by the previous revision of the prover; proofs of this
+/// version can still be parsed and verified. Such proofs do not record the byte encoding in
+/// proof options, and field elements and the proof-of-work nonce in them are always encoded
+/// natively; otherwise, they are parsed and verified in the same way as proofs of the current
+/// version.
+pub const LEGACY_PROOF_VERSION: u8 = 16;
+
+// TYPES AND INTERFACES
+// ================================================================================================
+
+#[derive(Clone)]
+pub struct StarkProof {
+    pub context: Context,
+    pub commitments: Commitments,
+    pub trace_queries: Queries,
+    pub constraint_queries: Queries,
+    /// Queries against the commitment to extra polynomials; this is set only when the AIR
+    /// declares extra polynomials.
+    pub extra_queries: Option<Queries>,
+    /// Queries against the commitment to the auxiliary segment of the execution trace; this is
+    /// set only when the AIR declares an auxiliary segment.
+    pub aux_segment_queries: Option<Queries>,
+    pub ood_frame: OodEvaluationFrame,
+    pub fri_proof: FriProof,
+    pub pow_nonce: u64,
+    /// Grinding factor satisfied by the proof-of-work nonce; this is never smaller than the
+    /// grinding factor specified by proof options, and may be greater if proof options specify
+    /// a grinding budget.
+    pub pow_difficulty: u8,
+}
+
+// TODO: this should be replaced by ProofContext
+#[derive(Clone)]
+pub struct Context {
+    pub version: u8,
+    pub lde_domain_depth: u8,
+    pub ce_blowup_factor: u8,
+    /// Total number of transition and boundary constraints of the computation.
+    pub num_constraints: u32,
+    pub field_modulus_bytes: Vec<u8>,
+    pub options: ProofOptions,
+    /// Identifier of the AIR for which the proof was generated, if the AIR specifies one.
+    pub air_id: Option<[u8; 32]>,
+}
+
+#[derive(Clone)]
+pub struct Commitments {
+    pub trace_root: [u8; 32],
+    /// Roots of commitments to blinded versions of trace registers designated by the AIR, one
+    /// per register; openings of these commitments are not included in the proof.
+    pub aux_roots: Vec<[u8; 32]>,
+    /// Root of the commitment to extra polynomials, if the AIR declares any.
+    pub extra_root: Option<[u8; 32]>,
+    /// Root of the commitment to the auxiliary segment of the execution trace, if the AIR
+    /// declares one.
+    pub aux_segment_root: Option<[u8; 32]>,
+    pub constraint_root: [u8; 32],
+    pub fri_roots: Vec<[u8; 32]>,
+}
+
+#[derive(Clone)]
+pub struct Queries {
+    pub paths: Vec<Vec<[u8; 32]>>,
+    pub values: Vec<Vec<u8>>,
+    /// Queried values in compact encoding; when this is set, `values` is empty.
+    pub compact_values: Option<CompactValues>,
+}
+
+/// Compact encoding of queried values in which every column holding the same element in all
+/// values is encoded using a single element.
+#[derive(Clone)]
+pub struct CompactValues {
+    /// Number of encoded values.
+    pub num_values: u32,
+    /// Number of field elements in each value.
+    pub value_width: u32,
+    /// Bitmap with one bit per column; a set bit means that the column is constant.
+    pub constant_columns: Vec<u8>,
+    /// Serialized elements of constant columns, one element per column.
+    pub constants: Vec<u8>,
+    /// Serialized elements of all remaining columns, value by value.
+    pub elements: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OodEvaluationFrame {
+    pub trace_at_z1: Vec<u8>,
+    pub trace_at_z2: Vec<u8>,
+    /// Evaluations of extra polynomials at z; this is empty when the AIR declares no extra
+    /// polynomials.
+    pub extra_at_z: Vec<u8>,
+}
+
+// STARK PROOF IMPLEMENTATION
+// ================================================================================================
+impl StarkProof {
+    /// Returns version of the layout in which this proof was serialized.
+    pub fn version(&self) -> u8 {
+        self.context.version
+    }
+
+    /// Returns proof options which were used to generate this proof.
+    pub fn options(&self) -> &ProofOptions {
+        &self.context.options
+    }
+
+    /// Returns trace length for the computation described by this proof.
+    pub fn trace_length(&self) -> usize {
+        2usize.pow(self.context.lde_domain_depth as u32) / self.context.options.blowup_factor()
+    }
+
+    pub fn security_level(&self, optimistic: bool) -> u32 {
+        // the difficulty achieved by the prover is used because it may exceed the grinding factor
+        get_security_level(
+            &self.context.options,
+            self.context.ce_blowup_factor as usize,
+            self.pow_difficulty as u32,
+            get_num_modulus_bits(&self.context.field_modulus_bytes),
+            self.context.lde_domain_depth as u32,
+            self.context.num_constraints as usize,
+            optimistic,
+        )
+    }
+}
+
+// QUERY PROOFS IMPLEMENTATION
+// ================================================================================================
+
+impl Queries {
+    /// Returns a set of queries constructed from a batch Merkle proof and corresponding values;
+    /// values for each query can be provided as any type which can be borrowed as a slice of
+    /// field elements (e.g., a vector, or a slice into a larger buffer), and are encoded into
+    /// bytes according to the `encoding`.
+    pub fn new<E: FieldElement, V: AsRef<[E]>>(
+        merkle_proof: BatchMerkleProof,
+        values: &[V],
+        encoding: ByteEncoding,
+    ) -> Self {
+        // TODO: add debug check that values actually hash into the leaf nodes of the batch proof
+        Queries {
+            paths: merkle_proof.nodes,
+            values: values
+                .iter()
+                .map(|v| encoding.encode_elements(v.as_ref()).into_owned())
+                .collect(),
+            compact_values: None,
+        }
+    }
+
+    /// Returns these queries with values converted into compact encoding; values are assumed to
+    /// consist of elements of field `E` encoded according to the `encoding`, and to have the same
+    /// number of elements each.
+    ///
+    /// Columns in which all values hold the same element are included only once; all other
+    /// columns are included as serialized field elements for every value. For trace queries,
+    /// values are rows of the extended execution trace; thus, registers which are constant over
+    /// the execution trace (e.g., unused or zero-padded registers) remain constant at all queried
+    /// positions, while values of other registers (including binary registers) generally differ.
+    ///
+    /// # Panics
+    /// Panics if the values are already in compact encoding, or if values have different
+    /// lengths, or if the length of a value is not a multiple of the size of an encoded element.
+    pub fn compact<E: FieldElement>(self, encoding: ByteEncoding) -> Self {
+        let element_bytes = encoding.element_bytes::<E>();
+        assert!(
+            self.compact_values.is_none(),
+            "queries are already in compact encoding"
+        );
+        let value_bytes = self.values.first().map_or(0, |v| v.len());
+        assert!(
+            self.values.iter().all(|v| v.len() == value_bytes),
+            "all values must have the same length"
+        );
+        assert_eq!(
+            0,
+            value_bytes % element_bytes,
+            "value length must be a multiple of element size"
+        );
+        let value_width = value_bytes / element_bytes;
+
+        // determine which columns hold the same element in all values
+        let mut constant_columns = vec![0u8; value_width.div_ceil(8)];
+        let mut constants = Vec::new();
+        if let Some(first) = self.values.first() {
+            for (column, element) in first.chunks(element_bytes).enumerate() {
+                let range = column * element_bytes..(column + 1) * element_bytes;
+                if self.values.iter().all(|v| v[range.clone()] == *element) {
+                    constant_columns[column / 8] |= 1 << (column % 8);
+                    constants.extend_from_slice(element);
+                }
+            }
+        }
+
+        // write out elements of all other columns
+        let mut elements = Vec::new();
+        for value in self.values.iter() {
+            for (column, element) in value.chunks(element_bytes).enumerate() {
+                if !is_bit_set(&constant_columns, column) {
+                    elements.extend_from_slice(element);
+                }
+            }
+        }
+
+        Queries {
+            paths: self.paths,
+            values: Vec::new(),
+            compact_values: Some(CompactValues {
+                num_values: self.values.len() as u32,
+                value_width: value_width as u32,
+                constant_columns,
+                constants,
+                elements,
+            }),
+        }
+    }
+
+    /// Returns these queries with values converted from compact encoding back into elements of
+    /// field `E` encoded according to the `encoding`; this reverses [Queries::compact()]. Every
+    /// value is expected to consist of `value_width` elements.
+    ///
+    /// # Errors
+    /// Returns an error if the values are not in compact encoding, if the declared width of
+    /// values differs from `value_width`, or if the encoded data is not consistent with the
+    /// declared number and width of values.
+    pub fn expand<E: FieldElement>(
+        self,
+        value_width: usize,
+        encoding: ByteEncoding,
+    ) -> Result<Self, SerializationError> {
+        let element_bytes = encoding.element_bytes::<E>();
+        let compact = match self.compact_values {
+            Some(compact) if self.values.is_empty() => compact,
+            _ => return Err(SerializationError::FailedToReadElement(0)),
+        };
+        // the declared width determines the size of expanded values; thus, it must be checked
+        // before anything is allocated for them
+        let num_values = compact.num_values as usize;
+        if compact.value_width as usize != value_width || (value_width == 0 && num_values > 0) {
+            return Err(SerializationError::FailedToReadElement(0));
+        }
+        if compact.constant_columns.len() != value_width.div_ceil(8) {
+            return Err(SerializationError::DestinationTooSmall(
+                value_width.div_ceil(8),
+                compact.constant_columns.len(),
+            ));
+        }
+
+        // make sure encoded data contains exactly as many elements as needed
+        let num_constants = (0..value_width)
+            .filter(|&column| is_bit_set(&compact.constant_columns, column))
+            .count();
+        if compact.constants.len() != num_constants * element_bytes {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                compact.constants.len(),
+            ));
+        }
+        let num_elements = num_values * (value_width - num_constants);
+        if compact.elements.len() != num_elements * element_bytes {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                compact.elements.len(),
+            ));
+        }
+
+        let mut elements = compact.elements.chunks(element_bytes);
+        let mut values = Vec::with_capacity(num_values);
+        for _ in 0..num_values {
+            let mut value = Vec::with_capacity(value_width * element_bytes);
+            let mut constants = compact.constants.chunks(element_bytes);
+            for column in 0..value_width {
+                // the number of constants and elements was checked above
+                if is_bit_set(&compact.constant_columns, column) {
+                    value.extend_from_slice(constants.next().unwrap());
+                } else {
+                    value.extend_from_slice(elements.next().unwrap());
+                }
+            }
+            values.push(value);
+        }
+
+        Ok(Queries {
+            paths: self.paths,
+            values,
+            compact_values: None,
+        })
+    }
+
+    /// Convert a set of queries into a batch Merkle proof and corresponding values.
+    /// TODO: return values as a vector of field elements
+    pub fn into_batch<H: Hasher>(self, num_leaves: usize) -> (BatchMerkleProof, Vec<Vec<u8>>) {
+        let hash_fn = H::hash_fn();
+        self.build_batch(num_leaves, |value, leaf| hash_fn(value, leaf))
+    }
+
+    /// Same as [Queries::into_batch()], but the leaves are computed from the queried values
+    /// according to the leaf encoding of the trace commitment; `B` is the base field of the
+    /// computation, `trace_width` is the number of values in a trace row, and the values are
+    /// encoded according to the `encoding`.
+    pub fn into_trace_batch<B: StarkField, H: Hasher>(
+        self,
+        num_leaves: usize,
+        row_hashing: TraceRowHashing,
+        trace_width: usize,
+        encoding: ByteEncoding,
+    ) -> (BatchMerkleProof, Vec<Vec<u8>>) {
+        self.build_batch(num_leaves, |value, leaf| {
+            crate::utils::hash_trace_leaf::<B, H>(value, row_hashing, trace_width, encoding, leaf)
+        })
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    fn build_batch<F>(self, num_leaves: usize, leaf_fn: F) -> (BatchMerkleProof, Vec<Vec<u8>>)
+    where
+        F: Fn(&[u8], &mut [u8; 32]),
+    {
+        let mut hashed_values = vec![[0u8; 32]; self.values.len()];
+        for (trace_state, state_hash) in self.values.iter().zip(hashed_values.iter_mut()) {
+            leaf_fn(trace_state, state_hash);
+        }
+
+        let merkle_proof = BatchMerkleProof {
+            nodes: self.paths,
+            values: hashed_values,
+            depth: log2_unchecked(num_leaves) as u8,
+        };
+
+        (merkle_proof, self.values)
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for StarkProof {
+    /// Writes all sections of the proof one after another; the proof context is written first so
+    /// that the layout version can be checked before the rest of the proof is parsed.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.context.write_into(target);
+        self.commitments.write_into(target);
+        self.trace_queries.write_into(target);
+        self.constraint_queries.write_into(target);
+        match &self.extra_queries {
+            Some(extra_queries) => {
+                target.write_u8(1);
+                extra_queries.write_into(target);
+            }
+            None => target.write_u8(0),
+        }
+        match &self.aux_segment_queries {
+            Some(aux_segment_queries) => {
+                target.write_u8(1);
+                aux_segment_queries.write_into(target);
+            }
+            None => target.write_u8(0),
+        }
+        self.ood_frame.write_into(target);
+        self.fri_proof.write_into(target);
+        let encoding = self.context.options.byte_encoding();
+        encoding.write_u64(self.pow_nonce, target);
+        target.write_u8(self.pow_difficulty);
+    }
+}
+
+impl Deserializable for StarkProof {
+    /// Reads a proof subject to default [ParsingLimits]; to parse a proof with different limits,
+    /// use [parse_proof()].
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(ProofParser::new(source, &ParsingLimits::default()).read_proof()?)
+    }
+}
+
+impl Serializable for Context {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.version);
+        target.write_u8(self.lde_domain_depth);
+        target.write_u8(self.ce_blowup_factor);
+        target.write_u32(self.num_constraints);
+        target.write_u8(self.field_modulus_bytes.len() as u8);
+        target.write_u8_slice(&self.field_modulus_bytes);
+        self.options.write_versioned(target, self.version);
+        match &self.air_id {
+            Some(air_id) => {
+                target.write_u8(1);
+                target.write(air_id);
+            }
+            None => target.write_u8(0),
+        }
+    }
+}
+
+impl Deserializable for Context {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(ProofParser::new(source, &ParsingLimits::default()).read_context()?)
+    }
+}
+
+impl Serializable for Commitments {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write(&self.trace_root);
+        target.write_u8(self.aux_roots.len() as u8);
+        <[u8; 32]>::write_batch_into(&self.aux_roots, target);
+        match &self.extra_root {
+            Some(extra_root) => {
+                target.write_u8(1);
+                target.write(extra_root);
+            }
+            None => target.write_u8(0),
+        }
+        match &self.aux_segment_root {
+            Some(aux_segment_root) => {
+                target.write_u8(1);
+                target.write(aux_segment_root);
+            }
+            None => target.write_u8(0),
+        }
+        target.write(&self.constraint_root);
+        target.write_u8(self.fri_roots.len() as u8);
+        <[u8; 32]>::write_batch_into(&self.fri_roots, target);
+    }
+}
+
+impl Deserializable for Commitments {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(ProofParser::new(source, &ParsingLimits::default()).read_commitments()?)
+    }
+}
+
+impl Serializable for Queries {
+    /// Writes authentication paths followed by queried values; values are written either as is
+    /// or in compact encoding.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.paths.len() as u32);
+        for path in self.paths.iter() {
+            target.write_u8(path.len() as u8);
+            <[u8; 32]>::write_batch_into(path, target);
+        }
+        target.write_u32(self.values.len() as u32);
+        for value in self.values.iter() {
+            write_byte_vec(value, target);
+        }
+        match &self.compact_values {
+            Some(compact_values) => {
+                target.write_u8(1);
+                compact_values.write_into(target);
+            }
+            None => target.write_u8(0),
+        }
+    }
+}
+
+impl Deserializable for Queries {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(ProofParser::new(source, &ParsingLimits::default()).read_queries("queries")?)
+    }
+}
+
+impl Serializable for CompactValues {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.num_values);
+        target.write_u32(self.value_width);
+        write_byte_vec(&self.constant_columns, target);
+        write_byte_vec(&self.constants, target);
+        write_byte_vec(&self.elements, target);
+    }
+}
+
+impl Deserializable for CompactValues {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let limits = ParsingLimits::default();
+        Ok(ProofParser::new(source, &limits).read_compact_values("compact values")?)
+    }
+}
+
+impl Serializable for OodEvaluationFrame {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        write_byte_vec(&self.trace_at_z1, target);
+        write_byte_vec(&self.trace_at_z2, target);
+        write_byte_vec(&self.extra_at_z, target);
+    }
+}
+
+impl Deserializable for OodEvaluationFrame {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(ProofParser::new(source, &ParsingLimits::default()).read_ood_frame()?)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if the bit at the specified index is set in a bitmap packed starting from the
+/// least significant bit.
+fn is_bit_set(bitmap: &[u8], index: usize) -> bool {
+    bitmap[index / 8] & (1 << (index % 8)) != 0
+}
+
+/// Returns security level (in bits) of a proof generated with the specified options for a
+/// computation with the specified CE blowup factor; `pow_difficulty` is the number of bits of
+/// proof-of-work achieved by the prover, and the LDE domain has 2^lde_domain_depth elements.
+///
+/// `num_constraints` is the total number of constraints of the computation; it affects security
+/// only if composition coefficients are drawn according to [CoefficientScheme::Powers].
+pub(crate) fn get_security_level(
+    options: &ProofOptions,
+    ce_blowup_factor: usize,
+    pow_difficulty: u32,
+    field_modulus_bits: u32,
+    lde_domain_depth: u32,
+    num_constraints: usize,
+    optimistic: bool,
+) -> u32 {
+    // conjectured security requires half the queries as compared to proven security
+    let num_queries = if optimistic {
+        options.num_queries()
+    } else {
+        options.num_queries() / 2
+    };
+
+    let one_over_rho = (options.blowup_factor() / ce_blowup_factor) as u32;
+    let security_per_query = 31 - one_over_rho.leading_zeros(); // same as log2(one_over_rho)
+    let mut result = security_per_query * num_queries as u32;
+
+    // include grinding factor contributions only for proofs adequate security
+    if result >= GRINDING_CONTRIBUTION_FLOOR {
+        result += pow_difficulty;
+    }
+
+    // Provided by the collision resistance (CR) of the hash function we use
+    // TODO: make this dynamic based on the hash function used
+    let cr_security = 128;
+
+    // field_modulus_bits * field_extension_factor - log2(extended trace length)
+    let field_extension_factor = options.field_extension().degree() as u32;
+    let max_fri_security = field_modulus_bits * field_extension_factor - lde_domain_depth;
+
+    // when coefficients are powers of a single random element, random linear combination of
+    // constraints is a polynomial of degree 2 * num_constraints in this element; thus, by
+    // Schwartz-Zippel lemma, log2(2 * num_constraints) bits of security are lost
+    let max_composition_security = match options.coefficient_scheme() {
+        CoefficientScheme::Independent => field_modulus_bits * field_extension_factor,
+        CoefficientScheme::Powers => {
+            let num_coefficients = (2 * num_constraints).next_power_of_two();
+            field_modulus_bits * field_extension_factor - log2_unchecked(num_coefficients)
+        }
+    };
+
+    [
+        result,
+        max_fri_security,
+        max_composition_security,
+        cr_security,
+    ]
+    .iter()
+    .copied()
+    .min()
+    .unwrap()
+}
+
+/// Returns number of bits in the provided modulus; the modulus is assumed to be encoded in
+/// little-endian byte order
+fn get_num_modulus_bits(modulus_bytes: &[u8]) -> u32 {
+    let mut num_bits = modulus_bytes.len() as u32 * 8;
+    for &byte in modulus_bytes.iter().rev() {
+        if byte != 0 {
+            num_bits -= byte.leading_zeros();
+            return num_bits;
+        }
+        num_bits -= 8;
+    }
+
+    0
+}
+
+/// Writes a sequence of bytes prefixed with its length.
+fn write_byte_vec<W: ByteWriter>(bytes: &[u8], target: &mut W) {
+    target.write_u32(bytes.len() as u32);
+    target.write_u8_slice(bytes);
+}
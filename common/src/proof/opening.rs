@@ -0,0 +1,55 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ParsingLimits, ProofParser, Queries};
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// TRACE CHUNK OPENING
+// ================================================================================================
+
+/// An opening of a trace commitment at a subset of columns of the queried rows.
+///
+/// A trace commitment built with chunked row hashing (see [TraceRowHashing::Chunked]) can be
+/// opened at individual chunks of rows: only the values of the requested chunks are revealed,
+/// together with authentication paths from digests of these chunks to the leaves containing
+/// them, and from these leaves to the root of the commitment.
+///
+/// Leaves are opened in the order in which they first appear among the queried positions. Within
+/// a leaf, chunks are ordered by their index in the Merkle tree of chunk digests of the leaf:
+/// first by row, then by chunk index.
+///
+/// [TraceRowHashing::Chunked]: crate::TraceRowHashing::Chunked
+#[derive(Clone)]
+pub struct TraceChunkOpening {
+    /// Authentication paths from the commitment root to the opened leaves, and concatenated
+    /// values of the opened chunks of every leaf.
+    pub leaves: Queries,
+    /// Authentication paths from every opened leaf to the digests of its opened chunks.
+    pub chunk_paths: Vec<Vec<Vec<[u8; 32]>>>,
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for TraceChunkOpening {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.leaves.write_into(target);
+        target.write_u32(self.chunk_paths.len() as u32);
+        for paths in self.chunk_paths.iter() {
+            target.write_u32(paths.len() as u32);
+            for path in paths.iter() {
+                target.write_u8(path.len() as u8);
+                <[u8; 32]>::write_batch_into(path, target);
+            }
+        }
+    }
+}
+
+impl Deserializable for TraceChunkOpening {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let limits = ParsingLimits::default();
+        Ok(ProofParser::new(source, &limits).read_chunk_opening()?)
+    }
+}
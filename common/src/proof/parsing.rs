@@ -0,0 +1,478 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    Commitments, CompactValues, Context, OodEvaluationFrame, Queries, StarkProof, TraceChunkOpening,
+};
+use crate::{errors::ProofParsingError, limits, ProofOptions};
+use core::mem;
+use fri::{FriProof, FriProofLayer};
+use utils::{ByteReader, DeserializationError, SliceReader};
+
+// PARSING LIMITS
+// ================================================================================================
+
+/// Limits imposed on proofs parsed from untrusted bytes.
+///
+/// Every count read from a proof (number of queried values, authentication paths, FRI layers,
+/// etc.) is checked against these limits before anything is allocated for it, and all memory
+/// allocated while parsing a proof is charged against a single allocation budget. Thus, a hostile
+/// proof cannot cause the parser to allocate more memory than the budget allows, regardless of the
+/// sizes it declares. Default limits accept all proofs which can be generated by the prover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsingLimits {
+    max_proof_size: usize,
+    max_queries: usize,
+    max_fri_layers: usize,
+    max_remainder_size: usize,
+    max_allocation: usize,
+}
+
+impl ParsingLimits {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+    /// Returns default parsing limits, as defined in the [limits] module.
+    ///
+    /// The number of queried values in a single section of a proof is limited by
+    /// [limits::MAX_FRI_QUERIES], which is the largest number of FRI queries accepted by
+    /// [ProofOptions](crate::ProofOptions).
+    pub fn new() -> Self {
+        ParsingLimits {
+            max_proof_size: limits::MAX_PROOF_SIZE,
+            max_queries: limits::MAX_FRI_QUERIES,
+            max_fri_layers: limits::MAX_FRI_LAYERS,
+            max_remainder_size: limits::MAX_FRI_REMAINDER_SIZE,
+            max_allocation: limits::MAX_PARSING_ALLOCATION,
+        }
+    }
+
+    /// Returns these limits with the largest accepted size of a serialized proof (in bytes) set
+    /// to the specified value.
+    pub fn with_max_proof_size(mut self, max_proof_size: usize) -> Self {
+        self.max_proof_size = max_proof_size;
+        self
+    }
+
+    /// Returns these limits with the largest accepted number of queried values in a single
+    /// section of a proof (trace queries, constraint queries, or a FRI layer) set to the
+    /// specified value; the same limit applies to the number of authentication paths.
+    pub fn with_max_queries(mut self, max_queries: usize) -> Self {
+        self.max_queries = max_queries;
+        self
+    }
+
+    /// Returns these limits with the largest accepted number of FRI layers set to the specified
+    /// value.
+    pub fn with_max_fri_layers(mut self, max_fri_layers: usize) -> Self {
+        self.max_fri_layers = max_fri_layers;
+        self
+    }
+
+    /// Returns these limits with the largest accepted size of the FRI remainder (in bytes) set to
+    /// the specified value.
+    pub fn with_max_remainder_size(mut self, max_remainder_size: usize) -> Self {
+        self.max_remainder_size = max_remainder_size;
+        self
+    }
+
+    /// Returns these limits with the largest amount of memory (in bytes) which can be allocated
+    /// while parsing a proof set to the specified value.
+    pub fn with_max_allocation(mut self, max_allocation: usize) -> Self {
+        self.max_allocation = max_allocation;
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the largest accepted size of a serialized proof in bytes.
+    pub fn max_proof_size(&self) -> usize {
+        self.max_proof_size
+    }
+
+    /// Returns the largest accepted number of queried values in a single section of a proof.
+    pub fn max_queries(&self) -> usize {
+        self.max_queries
+    }
+
+    /// Returns the largest accepted number of FRI layers.
+    pub fn max_fri_layers(&self) -> usize {
+        self.max_fri_layers
+    }
+
+    /// Returns the largest accepted size of the FRI remainder in bytes.
+    pub fn max_remainder_size(&self) -> usize {
+        self.max_remainder_size
+    }
+
+    /// Returns the largest amount of memory in bytes which can be allocated while parsing a proof.
+    pub fn max_allocation(&self) -> usize {
+        self.max_allocation
+    }
+}
+
+impl Default for ParsingLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// PROOF PARSING
+// ================================================================================================
+
+/// Parses a proof from untrusted `bytes` subject to the specified `limits`.
+///
+/// # Errors
+/// Returns an error if the bytes do not encode a valid proof, if any of the sizes declared in the
+/// proof exceeds the corresponding limit, if parsing the proof would require allocating more
+/// memory than the limits allow, or if not all of the bytes were consumed.
+pub fn parse_proof(bytes: &[u8], limits: &ParsingLimits) -> Result<StarkProof, ProofParsingError> {
+    if bytes.len() > limits.max_proof_size {
+        return Err(ProofParsingError::ProofTooLarge(
+            bytes.len(),
+            limits.max_proof_size,
+        ));
+    }
+
+    let mut source = SliceReader::new(bytes);
+    let proof = ProofParser::new(&mut source, limits).read_proof()?;
+    if source.has_more_bytes() {
+        let num_bytes = bytes.len() - source.position();
+        return Err(DeserializationError::UnconsumedBytes(num_bytes).into());
+    }
+    Ok(proof)
+}
+
+// PROOF PARSER
+// ================================================================================================
+
+/// Reads sections of a proof from a byte source, checking all declared sizes against parsing
+/// limits and keeping track of the allocated memory.
+///
+/// The layout read by the parser must match the layout written by [Serializable] implementations
+/// of proof sections, including [FriProof].
+///
+/// [Serializable]: utils::Serializable
+pub(super) struct ProofParser<'a, R: ByteReader> {
+    source: &'a mut R,
+    limits: &'a ParsingLimits,
+    allocated: usize,
+}
+
+impl<'a, R: ByteReader> ProofParser<'a, R> {
+    /// Returns a new parser which reads from the specified `source`.
+    pub fn new(source: &'a mut R, limits: &'a ParsingLimits) -> Self {
+        ProofParser {
+            source,
+            limits,
+            allocated: 0,
+        }
+    }
+
+    // PROOF SECTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Reads all sections of a proof.
+    pub fn read_proof(&mut self) -> Result<StarkProof, ProofParsingError> {
+        let context = self.read_context()?;
+        let encoding = context.options.byte_encoding();
+        Ok(StarkProof {
+            context,
+            commitments: self.read_commitments()?,
+            trace_queries: self.read_queries("trace queries")?,
+            constraint_queries: self.read_queries("constraint queries")?,
+            extra_queries: match self.source.read_u8()? {
+                0 => None,
+                1 => Some(self.read_queries("extra queries")?),
+                value => return Err(invalid_flag("extra queries", value)),
+            },
+            aux_segment_queries: match self.source.read_u8()? {
+                0 => None,
+                1 => Some(self.read_queries("auxiliary segment queries")?),
+                value => return Err(invalid_flag("auxiliary segment queries", value)),
+            },
+            ood_frame: self.read_ood_frame()?,
+            fri_proof: self.read_fri_proof()?,
+            pow_nonce: encoding.read_u64(self.source)?,
+            pow_difficulty: self.read_pow_difficulty()?,
+        })
+    }
+
+    /// Reads proof context; the depth of the LDE domain must be small enough for the size of the
+    /// domain to be addressable on this platform.
+    pub fn read_context(&mut self) -> Result<Context, ProofParsingError> {
+        let version = self.source.read_u8()?;
+        let lde_domain_depth = self.source.read_u8()?;
+        if lde_domain_depth as u32 >= usize::BITS {
+            return Err(ProofParsingError::LdeDomainTooLarge(lde_domain_depth));
+        }
+        let ce_blowup_factor = self.source.read_u8()?;
+        let num_constraints = self.source.read_u32()?;
+        let num_modulus_bytes = self.source.read_u8()? as usize;
+        let field_modulus_bytes = self.read_bytes(num_modulus_bytes)?;
+        let options = ProofOptions::read_versioned(self.source, version)?;
+        let air_id = match self.source.read_u8()? {
+            0 => None,
+            1 => Some(self.source.read()?),
+            value => return Err(invalid_flag("AIR identifier", value)),
+        };
+        Ok(Context {
+            version,
+            lde_domain_depth,
+            ce_blowup_factor,
+            num_constraints,
+            field_modulus_bytes,
+            options,
+            air_id,
+        })
+    }
+
+    /// Reads commitment roots; a commitment is expected for every FRI layer and for the FRI
+    /// remainder.
+    pub fn read_commitments(&mut self) -> Result<Commitments, ProofParsingError> {
+        let trace_root = self.source.read()?;
+        let num_aux_roots = self.source.read_u8()? as usize;
+        let aux_roots = self.read_digests(num_aux_roots)?;
+        let extra_root = match self.source.read_u8()? {
+            0 => None,
+            1 => Some(self.source.read()?),
+            value => return Err(invalid_flag("extra root", value)),
+        };
+        let aux_segment_root = match self.source.read_u8()? {
+            0 => None,
+            1 => Some(self.source.read()?),
+            value => return Err(invalid_flag("auxiliary segment root", value)),
+        };
+        let constraint_root = self.source.read()?;
+        let num_fri_roots = self.source.read_u8()? as usize;
+        if num_fri_roots > self.limits.max_fri_layers + 1 {
+            return Err(ProofParsingError::TooManyFriLayers(
+                num_fri_roots,
+                self.limits.max_fri_layers + 1,
+            ));
+        }
+        let fri_roots = self.read_digests(num_fri_roots)?;
+        Ok(Commitments {
+            trace_root,
+            aux_roots,
+            extra_root,
+            aux_segment_root,
+            constraint_root,
+            fri_roots,
+        })
+    }
+
+    /// Reads authentication paths and values of queries from the specified `section` of a proof.
+    pub fn read_queries(&mut self, section: &'static str) -> Result<Queries, ProofParsingError> {
+        let num_paths = self.read_count(section)?;
+        self.allocate(num_paths, mem::size_of::<Vec<[u8; 32]>>())?;
+        let mut paths = Vec::with_capacity(num_paths);
+        for _ in 0..num_paths {
+            let path_len = self.source.read_u8()? as usize;
+            paths.push(self.read_digests(path_len)?);
+        }
+
+        let num_values = self.read_count(section)?;
+        self.allocate(num_values, mem::size_of::<Vec<u8>>())?;
+        let mut values = Vec::with_capacity(num_values);
+        for _ in 0..num_values {
+            let value_len = self.source.read_u32()? as usize;
+            values.push(self.read_bytes(value_len)?);
+        }
+
+        let compact_values = match self.source.read_u8()? {
+            0 => None,
+            1 => Some(self.read_compact_values(section)?),
+            value => return Err(invalid_flag("compact values", value)),
+        };
+        Ok(Queries {
+            paths,
+            values,
+            compact_values,
+        })
+    }
+
+    /// Reads an opening of a trace commitment at individual chunks of rows; the number of
+    /// authentication paths within a leaf is bounded only by the allocation budget because it
+    /// depends on the number of chunks in a row.
+    pub fn read_chunk_opening(&mut self) -> Result<TraceChunkOpening, ProofParsingError> {
+        let leaves = self.read_queries("chunk opening")?;
+        let num_leaves = self.read_count("chunk opening")?;
+        self.allocate(num_leaves, mem::size_of::<Vec<Vec<[u8; 32]>>>())?;
+        let mut chunk_paths = Vec::with_capacity(num_leaves);
+        for _ in 0..num_leaves {
+            let num_paths = self.source.read_u32()? as usize;
+            self.allocate(num_paths, mem::size_of::<Vec<[u8; 32]>>())?;
+            let mut paths = Vec::with_capacity(num_paths);
+            for _ in 0..num_paths {
+                let path_len = self.source.read_u8()? as usize;
+                paths.push(self.read_digests(path_len)?);
+            }
+            chunk_paths.push(paths);
+        }
+        Ok(TraceChunkOpening {
+            leaves,
+            chunk_paths,
+        })
+    }
+
+    /// Reads queried values in compact encoding from the specified `section` of a proof.
+    pub fn read_compact_values(
+        &mut self,
+        section: &'static str,
+    ) -> Result<CompactValues, ProofParsingError> {
+        let num_values = self.read_count(section)?;
+        let value_width = self.source.read_u32()?;
+        Ok(CompactValues {
+            num_values: num_values as u32,
+            value_width,
+            constant_columns: self.read_byte_vec()?,
+            constants: self.read_byte_vec()?,
+            elements: self.read_byte_vec()?,
+        })
+    }
+
+    /// Reads out-of-domain evaluation frame.
+    pub fn read_ood_frame(&mut self) -> Result<OodEvaluationFrame, ProofParsingError> {
+        Ok(OodEvaluationFrame {
+            trace_at_z1: self.read_byte_vec()?,
+            trace_at_z2: self.read_byte_vec()?,
+            extra_at_z: self.read_byte_vec()?,
+        })
+    }
+
+    /// Reads FRI proof.
+    pub fn read_fri_proof(&mut self) -> Result<FriProof, ProofParsingError> {
+        let num_layers = self.source.read_u8()? as usize;
+        if num_layers > self.limits.max_fri_layers {
+            return Err(ProofParsingError::TooManyFriLayers(
+                num_layers,
+                self.limits.max_fri_layers,
+            ));
+        }
+        self.allocate(num_layers, mem::size_of::<FriProofLayer>())?;
+        let mut layers = Vec::with_capacity(num_layers);
+        for _ in 0..num_layers {
+            layers.push(self.read_fri_layer()?);
+        }
+
+        let rem_values_len = self.source.read_u32()? as usize;
+        if rem_values_len > self.limits.max_remainder_size {
+            return Err(ProofParsingError::RemainderTooLarge(
+                rem_values_len,
+                self.limits.max_remainder_size,
+            ));
+        }
+        let rem_values = self.read_bytes(rem_values_len)?;
+        let partitioned = self.source.read()?;
+        Ok(FriProof {
+            layers,
+            rem_values,
+            partitioned,
+        })
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Reads queried values and authentication paths of a single FRI layer.
+    fn read_fri_layer(&mut self) -> Result<FriProofLayer, ProofParsingError> {
+        let num_values = self.read_count("FRI layer queries")?;
+        self.allocate(num_values, mem::size_of::<Vec<u8>>())?;
+        let mut values = Vec::with_capacity(num_values);
+        for _ in 0..num_values {
+            let value_len = self.source.read_u16()? as usize;
+            values.push(self.read_bytes(value_len)?);
+        }
+
+        let num_paths = self.read_count("FRI layer queries")?;
+        self.allocate(num_paths, mem::size_of::<Vec<[u8; 32]>>())?;
+        let mut paths = Vec::with_capacity(num_paths);
+        for _ in 0..num_paths {
+            let path_len = self.source.read_u8()? as usize;
+            paths.push(self.read_digests(path_len)?);
+        }
+
+        let depth = self.source.read_u8()?;
+        Ok(FriProofLayer {
+            values,
+            paths,
+            depth,
+        })
+    }
+
+    /// Reads the grinding factor satisfied by the proof-of-work nonce; the grinding factor cannot
+    /// be greater than 32.
+    fn read_pow_difficulty(&mut self) -> Result<u8, ProofParsingError> {
+        let pow_difficulty = self.source.read_u8()?;
+        if pow_difficulty > 32 {
+            return Err(ProofParsingError::MalformedProof(
+                DeserializationError::InvalidValue(format!(
+                    "{} is not a valid proof-of-work difficulty",
+                    pow_difficulty
+                )),
+            ));
+        }
+        Ok(pow_difficulty)
+    }
+
+    /// Reads the number of queried values (or paths) in the specified `section` of a proof, and
+    /// makes sure it does not exceed the limit on the number of queries.
+    fn read_count(&mut self, section: &'static str) -> Result<usize, ProofParsingError> {
+        let count = self.source.read_u32()? as usize;
+        if count > self.limits.max_queries {
+            return Err(ProofParsingError::TooManyQueries(
+                section,
+                count,
+                self.limits.max_queries,
+            ));
+        }
+        Ok(count)
+    }
+
+    /// Reads a sequence of bytes prefixed with its length.
+    fn read_byte_vec(&mut self) -> Result<Vec<u8>, ProofParsingError> {
+        let len = self.source.read_u32()? as usize;
+        self.read_bytes(len)
+    }
+
+    /// Reads `len` bytes from the source; the bytes are charged against the allocation budget
+    /// before they are read.
+    fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>, ProofParsingError> {
+        self.allocate(len, 1)?;
+        Ok(self.source.read_u8_vec(len)?)
+    }
+
+    /// Reads `num_digests` 32-byte digests from the source.
+    fn read_digests(&mut self, num_digests: usize) -> Result<Vec<[u8; 32]>, ProofParsingError> {
+        self.allocate(num_digests, 32)?;
+        let mut digests = Vec::with_capacity(num_digests);
+        for _ in 0..num_digests {
+            digests.push(self.source.read()?);
+        }
+        Ok(digests)
+    }
+
+    /// Charges `count` items of `item_size` bytes each against the allocation budget.
+    fn allocate(&mut self, count: usize, item_size: usize) -> Result<(), ProofParsingError> {
+        let max_allocation = self.limits.max_allocation;
+        self.allocated = count
+            .checked_mul(item_size)
+            .and_then(|size| self.allocated.checked_add(size))
+            .filter(|&allocated| allocated <= max_allocation)
+            .ok_or(ProofParsingError::AllocationLimitExceeded(max_allocation))?;
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn invalid_flag(name: &str, value: u8) -> ProofParsingError {
+    ProofParsingError::MalformedProof(DeserializationError::InvalidValue(format!(
+        "{} is not a valid {} flag",
+        value, name
+    )))
+}
@@ -0,0 +1,355 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    get_security_level, parse_proof, Commitments, Context, OodEvaluationFrame, ParsingLimits,
+    Queries, StarkProof,
+};
+use crate::{
+    errors::ProofParsingError, CoefficientScheme, FieldExtension, HashFunction, ProofOptions,
+};
+use ::utils::{Deserializable, DeserializationError, Serializable};
+use crypto::BatchMerkleProof;
+use fri::{FriProof, FriProofLayer};
+use math::field::{f128::BaseElement, ByteEncoding, FieldElement, StarkField};
+
+const NATIVE: ByteEncoding = ByteEncoding::Native;
+
+#[test]
+fn compact_queries() {
+    // the first and the third columns are constant, the second column is not
+    let values = vec![
+        vec![BaseElement::ZERO, BaseElement::new(5), BaseElement::new(9)],
+        vec![BaseElement::ZERO, BaseElement::ONE, BaseElement::new(9)],
+        vec![BaseElement::ZERO, BaseElement::new(7), BaseElement::new(9)],
+    ];
+    let queries = Queries::new::<BaseElement, _>(build_merkle_proof(), &values, NATIVE);
+    let expected = queries.values.clone();
+
+    let compacted = queries.compact::<BaseElement>(NATIVE);
+    assert!(compacted.values.is_empty());
+    let compact_values = compacted.compact_values.as_ref().unwrap();
+    assert_eq!(vec![0b101], compact_values.constant_columns);
+    assert_eq!(
+        BaseElement::elements_as_bytes(&[BaseElement::ZERO, BaseElement::new(9)]),
+        &compact_values.constants[..]
+    );
+    assert_eq!(
+        BaseElement::elements_as_bytes(&[
+            BaseElement::new(5),
+            BaseElement::ONE,
+            BaseElement::new(7)
+        ]),
+        &compact_values.elements[..]
+    );
+
+    let expanded = compacted.expand::<BaseElement>(3, NATIVE).unwrap();
+    assert!(expanded.compact_values.is_none());
+    assert_eq!(expected, expanded.values);
+}
+
+#[test]
+fn expand_malformed_queries() {
+    let values = vec![
+        vec![BaseElement::ONE, BaseElement::new(5)],
+        vec![BaseElement::ONE, BaseElement::new(6)],
+    ];
+    let queries = Queries::new::<BaseElement, _>(build_merkle_proof(), &values, NATIVE);
+
+    // queries which are not in compact encoding cannot be expanded
+    assert!(queries.clone().expand::<BaseElement>(2, NATIVE).is_err());
+
+    // encoded elements must be consistent with the number of values
+    let mut compacted = queries.clone().compact::<BaseElement>(NATIVE);
+    compacted.compact_values.as_mut().unwrap().num_values = 3;
+    assert!(compacted.expand::<BaseElement>(2, NATIVE).is_err());
+
+    // declared width of values must match the expected width
+    let compacted = queries.clone().compact::<BaseElement>(NATIVE);
+    assert!(compacted.clone().expand::<BaseElement>(3, NATIVE).is_err());
+    assert!(compacted.expand::<BaseElement>(2, NATIVE).is_ok());
+
+    // encoded constants must be consistent with the bitmap of constant columns
+    let mut compacted = queries.compact::<BaseElement>(NATIVE);
+    compacted.compact_values.as_mut().unwrap().constant_columns[0] = 0b11;
+    assert!(compacted.expand::<BaseElement>(2, NATIVE).is_err());
+}
+
+#[test]
+fn queries_serialization() {
+    let values = vec![
+        vec![BaseElement::ONE, BaseElement::new(5)],
+        vec![BaseElement::ONE, BaseElement::new(6)],
+    ];
+    let queries = Queries::new::<BaseElement, _>(build_merkle_proof(), &values, NATIVE);
+
+    for queries in [queries.clone(), queries.compact::<BaseElement>(NATIVE)] {
+        let bytes = queries.to_bytes();
+        let decoded = Queries::read_from_bytes(&bytes).unwrap();
+        assert_eq!(bytes, decoded.to_bytes());
+
+        // truncated and padded byte sequences must be rejected
+        assert!(Queries::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut padded = bytes.clone();
+        padded.push(0);
+        assert!(Queries::read_from_bytes(&padded).is_err());
+    }
+}
+
+// PROOF PARSING
+// ================================================================================================
+
+#[test]
+fn parse_proof_within_limits() {
+    let bytes = build_proof().to_bytes();
+    let proof = parse_proof(&bytes, &ParsingLimits::default()).unwrap();
+    assert_eq!(bytes, proof.to_bytes());
+    assert_eq!(
+        bytes,
+        StarkProof::read_from_bytes(&bytes).unwrap().to_bytes()
+    );
+
+    // truncated and padded byte sequences must be rejected
+    let result = parse_proof(&bytes[..bytes.len() - 1], &ParsingLimits::default());
+    let expected = ProofParsingError::MalformedProof(DeserializationError::UnexpectedEOF);
+    assert_eq!(Err(expected), result.map(|_| ()));
+    let mut padded = bytes;
+    padded.push(0);
+    let result = parse_proof(&padded, &ParsingLimits::default());
+    let expected = ProofParsingError::MalformedProof(DeserializationError::UnconsumedBytes(1));
+    assert_eq!(Err(expected), result.map(|_| ()));
+}
+
+#[test]
+fn parse_legacy_proof() {
+    // proofs of the legacy version do not record the byte encoding in proof options
+    let mut proof = build_proof();
+    let current_bytes = proof.to_bytes();
+    proof.context.version = super::LEGACY_PROOF_VERSION;
+    let bytes = proof.to_bytes();
+    assert_eq!(current_bytes.len() - 1, bytes.len());
+
+    let parsed = parse_proof(&bytes, &ParsingLimits::default()).unwrap();
+    assert_eq!(super::LEGACY_PROOF_VERSION, parsed.version());
+    assert_eq!(NATIVE, parsed.options().byte_encoding());
+    assert_eq!(bytes, parsed.to_bytes());
+}
+
+#[test]
+fn parse_proof_with_extra_polys() {
+    let mut proof = build_proof();
+    proof.commitments.extra_root = Some([7; 32]);
+    proof.extra_queries = Some(proof.constraint_queries.clone());
+    proof.ood_frame.extra_at_z = vec![8; 16];
+
+    let bytes = proof.to_bytes();
+    let parsed = parse_proof(&bytes, &ParsingLimits::default()).unwrap();
+    assert_eq!(Some([7; 32]), parsed.commitments.extra_root);
+    assert!(parsed.extra_queries.is_some());
+    assert_eq!(vec![8; 16], parsed.ood_frame.extra_at_z);
+    assert_eq!(bytes, parsed.to_bytes());
+
+    // flags of optional sections other than 0 or 1 are rejected
+    let mut bytes = proof.to_bytes();
+    let flag_index = proof.context.to_bytes().len() + 32 + 1;
+    assert_eq!(1, bytes[flag_index]);
+    bytes[flag_index] = 2;
+    let result = parse_proof(&bytes, &ParsingLimits::default()).map(|_| ());
+    assert!(matches!(
+        result,
+        Err(ProofParsingError::MalformedProof(
+            DeserializationError::InvalidValue(_)
+        ))
+    ));
+}
+
+#[test]
+fn parse_proof_with_aux_segment() {
+    let mut proof = build_proof();
+    proof.commitments.aux_segment_root = Some([9; 32]);
+    proof.aux_segment_queries = Some(proof.constraint_queries.clone());
+
+    let bytes = proof.to_bytes();
+    let parsed = parse_proof(&bytes, &ParsingLimits::default()).unwrap();
+    assert_eq!(Some([9; 32]), parsed.commitments.aux_segment_root);
+    assert!(parsed.aux_segment_queries.is_some());
+    assert!(parsed.extra_queries.is_none());
+    assert_eq!(bytes, parsed.to_bytes());
+
+    // the flag of the auxiliary segment root follows the flag of the extra root
+    let mut bytes = proof.to_bytes();
+    let flag_index = proof.context.to_bytes().len() + 32 + 1 + 1;
+    assert_eq!(1, bytes[flag_index]);
+    bytes[flag_index] = 2;
+    let result = parse_proof(&bytes, &ParsingLimits::default()).map(|_| ());
+    assert!(matches!(
+        result,
+        Err(ProofParsingError::MalformedProof(
+            DeserializationError::InvalidValue(_)
+        ))
+    ));
+}
+
+#[test]
+fn parse_proof_exceeding_limits() {
+    let bytes = build_proof().to_bytes();
+    let parse = |limits: ParsingLimits| parse_proof(&bytes, &limits).map(|_| ());
+
+    let limits = ParsingLimits::new().with_max_proof_size(bytes.len() - 1);
+    let expected = ProofParsingError::ProofTooLarge(bytes.len(), bytes.len() - 1);
+    assert_eq!(Err(expected), parse(limits));
+
+    let limits = ParsingLimits::new().with_max_queries(1);
+    let expected = ProofParsingError::TooManyQueries("trace queries", 2, 1);
+    assert_eq!(Err(expected), parse(limits));
+
+    // the proof contains 2 FRI layers and 3 FRI commitments
+    let limits = ParsingLimits::new().with_max_fri_layers(1);
+    let expected = ProofParsingError::TooManyFriLayers(3, 2);
+    assert_eq!(Err(expected), parse(limits));
+
+    let limits = ParsingLimits::new().with_max_remainder_size(63);
+    let expected = ProofParsingError::RemainderTooLarge(64, 63);
+    assert_eq!(Err(expected), parse(limits));
+
+    let limits = ParsingLimits::new().with_max_allocation(256);
+    let expected = ProofParsingError::AllocationLimitExceeded(256);
+    assert_eq!(Err(expected), parse(limits));
+
+    // the proof can be parsed when all limits are exactly met
+    let limits = ParsingLimits::new()
+        .with_max_proof_size(bytes.len())
+        .with_max_queries(2)
+        .with_max_fri_layers(2)
+        .with_max_remainder_size(64);
+    assert_eq!(Ok(()), parse(limits));
+}
+
+#[test]
+fn parse_hostile_proof() {
+    let proof = build_proof();
+    let context_bytes = proof.context.to_bytes();
+    let commitments_bytes = proof.commitments.to_bytes();
+
+    // a proof declaring 2^32 - 1 trace queries is rejected before anything is allocated for them
+    let mut bytes = [context_bytes.clone(), commitments_bytes.clone()].concat();
+    bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    let expected = ProofParsingError::TooManyQueries("trace queries", u32::MAX as usize, 255);
+    let result = parse_proof(&bytes, &ParsingLimits::default());
+    assert_eq!(Err(expected), result.map(|_| ()));
+
+    // a proof declaring the maximum number of maximum-length paths fails once the allocation
+    // budget is exhausted, rather than once the source runs out of bytes
+    let mut bytes = [context_bytes.clone(), commitments_bytes].concat();
+    bytes.extend_from_slice(&255u32.to_le_bytes());
+    bytes.extend(std::iter::repeat_n(255u8, 4096));
+    let limits = ParsingLimits::new().with_max_allocation(4096);
+    let expected = ProofParsingError::AllocationLimitExceeded(4096);
+    assert_eq!(Err(expected), parse_proof(&bytes, &limits).map(|_| ()));
+
+    // an LDE domain which cannot be addressed is rejected
+    let mut bytes = context_bytes;
+    bytes[1] = 64;
+    let expected = ProofParsingError::LdeDomainTooLarge(64);
+    let result = parse_proof(&bytes, &ParsingLimits::default());
+    assert_eq!(Err(expected), result.map(|_| ()));
+
+    // violations of limits are reported as invalid values by the deserializer
+    let result = StarkProof::read_from_bytes(&bytes).map(|_| ());
+    assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+
+    // proof-of-work difficulty cannot exceed the largest grinding factor
+    let mut bytes = proof.to_bytes();
+    *bytes.last_mut().unwrap() = 33;
+    let result = parse_proof(&bytes, &ParsingLimits::default()).map(|_| ());
+    assert!(matches!(
+        result,
+        Err(ProofParsingError::MalformedProof(
+            DeserializationError::InvalidValue(_)
+        ))
+    ));
+}
+
+#[test]
+fn security_level_with_coefficient_schemes() {
+    let independent = ProofOptions::new(64, 16, 0, HashFunction::Blake3_256, FieldExtension::None);
+    let powers = independent
+        .clone()
+        .with_coefficient_scheme(CoefficientScheme::Powers);
+    let security = |options: &ProofOptions, num_constraints| {
+        get_security_level(options, 2, 0, 128, 10, num_constraints, true)
+    };
+
+    // with few constraints, security is bounded by FRI in both schemes: 128 - 10 bits
+    assert_eq!(118, security(&independent, 3));
+    assert_eq!(118, security(&powers, 3));
+
+    // with many constraints, the powers scheme loses log2(2 * 4096) bits
+    assert_eq!(118, security(&independent, 4096));
+    assert_eq!(115, security(&powers, 4096));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_proof() -> StarkProof {
+    let options = ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, FieldExtension::None);
+    let values = vec![
+        vec![BaseElement::ONE, BaseElement::new(5)],
+        vec![BaseElement::ONE, BaseElement::new(6)],
+    ];
+    let fri_layer = FriProofLayer {
+        values: vec![vec![1; 64]],
+        paths: vec![vec![[2; 32]; 3]],
+        depth: 4,
+    };
+    StarkProof {
+        context: Context {
+            version: super::PROOF_VERSION,
+            lde_domain_depth: 10,
+            ce_blowup_factor: 2,
+            num_constraints: 3,
+            field_modulus_bytes: BaseElement::get_modulus_le_bytes(),
+            options,
+            air_id: None,
+        },
+        commitments: Commitments {
+            trace_root: [1; 32],
+            aux_roots: vec![],
+            extra_root: None,
+            aux_segment_root: None,
+            constraint_root: [2; 32],
+            fri_roots: vec![[3; 32]; 3],
+        },
+        trace_queries: Queries::new::<BaseElement, _>(build_merkle_proof(), &values, NATIVE),
+        constraint_queries: Queries::new::<BaseElement, _>(
+            build_merkle_proof(),
+            &values[..1],
+            NATIVE,
+        ),
+        extra_queries: None,
+        aux_segment_queries: None,
+        ood_frame: OodEvaluationFrame {
+            trace_at_z1: vec![4; 32],
+            trace_at_z2: vec![5; 32],
+            extra_at_z: vec![],
+        },
+        fri_proof: FriProof {
+            layers: vec![fri_layer.clone(), fri_layer],
+            rem_values: vec![6; 64],
+            partitioned: false,
+        },
+        pow_nonce: 42,
+        pow_difficulty: 0,
+    }
+}
+
+fn build_merkle_proof() -> BatchMerkleProof {
+    BatchMerkleProof {
+        values: vec![],
+        nodes: vec![vec![[1u8; 32]]],
+        depth: 2,
+    }
+}
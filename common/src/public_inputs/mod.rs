@@ -0,0 +1,98 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::HashFunction;
+use crypto::hash;
+use math::field::StarkField;
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Domain separator prepended to serialized public inputs before they are hashed.
+const DOMAIN_SEPARATOR: &[u8] = b"WINTERFELL_PUBLIC_INPUTS";
+
+// HASHED PUBLIC INPUTS
+// ================================================================================================
+
+/// A digest of public inputs of a computation.
+///
+/// Computations with large public inputs (e.g., lists of Merkle roots, or program hashes) can
+/// use this struct as [Air::PublicInputs](crate::Air::PublicInputs) to bind the proof to a
+/// 32-byte digest of the inputs rather than to the inputs themselves. The digest is computed by
+/// hashing the domain separator followed by the serialized inputs with the hash function
+/// specified in the proof options; thus, the verifier can recompute the digest from the full
+/// inputs, while the AIR needs to deal only with [to_elements()](HashedPublicInputs::to_elements)
+/// of the digest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HashedPublicInputs {
+    digest: [u8; 32],
+}
+
+impl HashedPublicInputs {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the digest of the specified `inputs` computed using the hash function `hash_fn`.
+    pub fn new<I: Serializable>(hash_fn: HashFunction, inputs: &I) -> Self {
+        let mut bytes = DOMAIN_SEPARATOR.to_vec();
+        inputs.write_into(&mut bytes);
+
+        let mut digest = [0u8; 32];
+        match hash_fn {
+            HashFunction::Blake3_256 => hash::blake3(&bytes, &mut digest),
+            HashFunction::Sha3_256 => hash::sha3(&bytes, &mut digest),
+        }
+        HashedPublicInputs { digest }
+    }
+
+    /// Returns public inputs consisting of a previously computed `digest`.
+    pub fn from_digest(digest: [u8; 32]) -> Self {
+        HashedPublicInputs { digest }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the digest of public inputs.
+    pub fn digest(&self) -> [u8; 32] {
+        self.digest
+    }
+
+    /// Returns the digest of public inputs encoded as a sequence of field elements.
+    ///
+    /// The digest is split into chunks of (MODULUS_BITS - 1) / 8 bytes, and each chunk is
+    /// interpreted as a little-endian integer; thus, every chunk maps to a distinct field element.
+    /// For example, for a 128-bit field, the digest is encoded into 3 elements.
+    pub fn to_elements<B: StarkField>(&self) -> Vec<B> {
+        let chunk_size = (B::MODULUS_BITS as usize - 1) / 8;
+        self.digest
+            .chunks(chunk_size)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(B::ZERO, |acc, &byte| acc * B::from(256u16) + B::from(byte))
+            })
+            .collect()
+    }
+}
+
+impl Serializable for HashedPublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(&self.digest);
+    }
+}
+
+impl Deserializable for HashedPublicInputs {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(HashedPublicInputs {
+            digest: source.read_u8_array()?,
+        })
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::HashedPublicInputs;
+use crate::HashFunction;
+use math::field::{f128::BaseElement, f62, FieldElement};
+use utils::{Deserializable, Serializable, SliceReader};
+
+#[test]
+fn hashed_public_inputs_digest() {
+    let mut inputs = [1u8; 96];
+    inputs[32..64].copy_from_slice(&[2u8; 32]);
+    let digest = HashedPublicInputs::new(HashFunction::Blake3_256, &inputs);
+
+    // the digest is deterministic, and depends on the inputs and the hash function
+    assert_eq!(
+        digest,
+        HashedPublicInputs::new(HashFunction::Blake3_256, &inputs)
+    );
+    assert_ne!(
+        digest,
+        HashedPublicInputs::new(HashFunction::Sha3_256, &inputs)
+    );
+
+    let mut other_inputs = inputs;
+    other_inputs[64] = 4;
+    assert_ne!(
+        digest,
+        HashedPublicInputs::new(HashFunction::Blake3_256, &other_inputs)
+    );
+
+    // inputs are hashed together with the domain separator
+    let mut plain_digest = [0u8; 32];
+    crypto::hash::blake3(&inputs, &mut plain_digest);
+    assert_ne!(plain_digest, digest.digest());
+}
+
+#[test]
+fn hashed_public_inputs_to_elements() {
+    let mut digest = [0u8; 32];
+    digest[0] = 1;
+    digest[15] = 2;
+    digest[31] = 255;
+    let inputs = HashedPublicInputs::from_digest(digest);
+
+    // for a 128-bit field, the digest is split into chunks of 15, 15, and 2 bytes
+    let elements = inputs.to_elements::<BaseElement>();
+    assert_eq!(
+        vec![
+            BaseElement::ONE,
+            BaseElement::new(2),
+            BaseElement::new(255 << 8)
+        ],
+        elements
+    );
+
+    // for a 62-bit field, the digest is split into chunks of 7 bytes
+    let elements = inputs.to_elements::<f62::BaseElement>();
+    assert_eq!(5, elements.len());
+    assert_eq!(f62::BaseElement::ONE, elements[0]);
+    assert_eq!(f62::BaseElement::new(2 << 8), elements[2]);
+    assert_eq!(f62::BaseElement::new(255 << 24), elements[4]);
+}
+
+#[test]
+fn hashed_public_inputs_serialization() {
+    let inputs = HashedPublicInputs::new(HashFunction::Sha3_256, &[7u8; 100]);
+    let bytes = inputs.to_bytes();
+    assert_eq!(inputs.digest().to_vec(), bytes);
+
+    let mut reader = SliceReader::new(&bytes);
+    assert_eq!(inputs, HashedPublicInputs::read_from(&mut reader).unwrap());
+}
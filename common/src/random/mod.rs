@@ -3,10 +3,10 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::ComputationContext;
+use crate::{CoefficientScheme, ComputationContext};
 use crypto::{Hasher, RandomElementGenerator};
-use math::field::FieldElement;
-use std::{convert::TryInto, mem::size_of};
+use math::field::{ByteEncoding, FieldElement};
+use std::convert::TryInto;
 
 #[cfg(test)]
 mod tests;
@@ -17,6 +17,7 @@ const TRANSITION_COEFF_OFFSET: u64 = 0;
 const BOUNDARY_COEFF_OFFSET: u64 = u32::MAX as u64;
 const DEEP_POINT_OFFSET: u64 = 0;
 const COMPOSITION_COEFF_OFFSET: u64 = 1024;
+const AUX_RAND_OFFSET: u64 = 1 << 48;
 
 // PUBLIC COIN
 // ================================================================================================
@@ -28,6 +29,7 @@ pub trait PublicCoin: fri::PublicCoin {
     // --------------------------------------------------------------------------------------------
 
     fn context(&self) -> &ComputationContext;
+    fn aux_segment_seed(&self) -> [u8; 32];
     fn constraint_seed(&self) -> [u8; 32];
     fn composition_seed(&self) -> [u8; 32];
     fn query_seed(&self) -> [u8; 32];
@@ -37,77 +39,154 @@ pub trait PublicCoin: fri::PublicCoin {
 
     /// Returns a PRNG for transition constraint coefficients.
     fn get_transition_coefficient_prng(&self) -> Self::RandomElementGenerator {
-        Self::RandomElementGenerator::new(self.constraint_seed(), TRANSITION_COEFF_OFFSET)
+        self.build_prng(self.constraint_seed(), TRANSITION_COEFF_OFFSET)
     }
 
     /// Returns a PRNG for boundary constraint coefficients.
     fn get_boundary_coefficient_prng(&self) -> Self::RandomElementGenerator {
-        Self::RandomElementGenerator::new(self.constraint_seed(), BOUNDARY_COEFF_OFFSET)
+        self.build_prng(self.constraint_seed(), BOUNDARY_COEFF_OFFSET)
+    }
+
+    /// Returns a PRNG seeded with the specified `seed` and `offset` which draws values according
+    /// to the byte encoding specified by proof options.
+    fn build_prng(&self, seed: [u8; 32], offset: u64) -> Self::RandomElementGenerator {
+        let encoding = self.context().options().byte_encoding();
+        Self::RandomElementGenerator::with_encoding(seed, offset, encoding)
     }
 
     // DRAW METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Draws random elements for building the auxiliary segment of the execution trace using
+    /// PRNG seeded with auxiliary segment seed; the number of elements is defined by the context
+    /// of the computation, and no elements are drawn if the trace has no auxiliary segment.
+    fn draw_aux_rand_elements<B: FieldElement>(&self) -> Vec<B> {
+        let num_elements = self.context().num_aux_rand_elements();
+        if num_elements == 0 {
+            return Vec::new();
+        }
+        let mut generator = self.build_prng(self.aux_segment_seed(), AUX_RAND_OFFSET);
+        (0..num_elements).map(|_| generator.draw()).collect()
+    }
+
     /// Draws a point from the entire field using PRNG seeded with composition seed.
     fn draw_deep_point<E: FieldElement>(&self) -> E {
-        let mut generator =
-            Self::RandomElementGenerator::new(self.composition_seed(), DEEP_POINT_OFFSET);
+        let mut generator = self.build_prng(self.composition_seed(), DEEP_POINT_OFFSET);
         generator.draw()
     }
 
     /// Draws coefficients for building composition polynomial using PRNG seeded with
     /// composition seed.
     fn draw_composition_coefficients<E: FieldElement>(&self) -> CompositionCoefficients<E> {
-        let generator =
-            Self::RandomElementGenerator::new(self.composition_seed(), COMPOSITION_COEFF_OFFSET);
-        CompositionCoefficients::new(generator, self.context().trace_width())
+        let generator = self.build_prng(self.composition_seed(), COMPOSITION_COEFF_OFFSET);
+        let context = self.context();
+        CompositionCoefficients::new(generator, context.trace_width(), context.num_extra_polys())
     }
 
     /// Draws a set of unique query positions using PRNG seeded with query seed. The positions
     /// are selected from the range [0, lde_domain_size).
     fn draw_query_positions(&self) -> Vec<usize> {
-        let hash_fn = Self::Hasher::hash_fn();
+        let mut result = Vec::with_capacity(self.context().options().num_queries());
+        self.draw_query_positions_into(&mut result);
+        result
+    }
+
+    /// Same as [draw_query_positions()](PublicCoin::draw_query_positions), but writes the
+    /// positions into the provided `result` vector; any values previously held by the vector are
+    /// discarded.
+    fn draw_query_positions_into(&self, result: &mut Vec<usize>) {
         let num_queries = self.context().options().num_queries();
+        draw_positions_into::<Self::Hasher>(self.query_seed(), self.context(), num_queries, result);
+    }
 
-        // determine how many bits are needed to represent valid indexes in the domain
-        let value_mask = self.context().lde_domain_size() - 1;
-        let value_offset = 32 - size_of::<usize>();
-
-        // initialize the seed for PRNG
-        let mut seed = [0u8; 64];
-        seed[..32].copy_from_slice(&self.query_seed());
-        let mut value_bytes = [0u8; 32];
-
-        // draw values from PRNG until we get as many unique values as specified by
-        // num_queries, but skipping values which are a multiple of blowup factor
-        let mut result = Vec::new();
-        for i in 0usize..1000 {
-            // update the seed with the new counter and hash the result
-            seed[56..].copy_from_slice(&i.to_le_bytes());
-            hash_fn(&seed, &mut value_bytes);
-
-            // read the required number of bits from the hashed value
-            let value =
-                usize::from_le_bytes(value_bytes[value_offset..].try_into().unwrap()) & value_mask;
-
-            if result.contains(&value) {
-                continue;
-            }
-            result.push(value);
-            if result.len() >= num_queries {
-                break;
-            }
-        }
+    /// Draws a set of unique positions for queries against FRI layers using PRNG seeded with
+    /// query seed. The number of positions is defined by the num_fri_queries option, and the
+    /// positions returned from [draw_query_positions()](PublicCoin::draw_query_positions) are
+    /// always a prefix of the returned positions.
+    fn draw_fri_query_positions(&self) -> Vec<usize> {
+        let mut result = Vec::with_capacity(self.context().options().num_fri_queries());
+        self.draw_fri_query_positions_into(&mut result);
+        result
+    }
 
-        assert!(
-            result.len() == num_queries,
-            "needed to generate {} query positions, but generated only {}",
-            num_queries,
-            result.len()
-        );
+    /// Same as [draw_fri_query_positions()](PublicCoin::draw_fri_query_positions), but writes
+    /// the positions into the provided `result` vector; any values previously held by the
+    /// vector are discarded.
+    fn draw_fri_query_positions_into(&self, result: &mut Vec<usize>) {
+        let num_queries = self.context().options().num_fri_queries();
+        draw_positions_into::<Self::Hasher>(self.query_seed(), self.context(), num_queries, result);
+    }
+}
 
-        result
+// SEED BUILDERS
+// ================================================================================================
+
+/// Returns the seed from which constraint composition coefficients are drawn. If the AIR
+/// specifies an identifier, or if the prover committed to blinded trace registers, to extra
+/// polynomials, or to the auxiliary segment of the trace, the AIR identifier and roots of these
+/// commitments are hashed together with the trace root; otherwise, the trace root is used as the
+/// seed directly.
+///
+/// Random elements for building the auxiliary segment are drawn from the seed built in the same
+/// way, but without the root of the auxiliary segment.
+pub fn build_constraint_seed<H: Hasher>(
+    trace_root: &[u8; 32],
+    aux_roots: &[[u8; 32]],
+    extra_root: Option<&[u8; 32]>,
+    aux_segment_root: Option<&[u8; 32]>,
+    air_id: Option<&[u8; 32]>,
+) -> [u8; 32] {
+    if aux_roots.is_empty()
+        && extra_root.is_none()
+        && aux_segment_root.is_none()
+        && air_id.is_none()
+    {
+        return *trace_root;
+    }
+
+    let mut data: Vec<u8> = Vec::with_capacity((aux_roots.len() + 4) * 32);
+    if let Some(air_id) = air_id {
+        data.extend_from_slice(air_id);
     }
+    data.extend_from_slice(trace_root);
+    for root in aux_roots.iter() {
+        data.extend_from_slice(root);
+    }
+    if let Some(extra_root) = extra_root {
+        data.extend_from_slice(extra_root);
+    }
+    if let Some(aux_segment_root) = aux_segment_root {
+        data.extend_from_slice(aux_segment_root);
+    }
+
+    let mut seed = [0u8; 32];
+    H::hash_fn()(&data, &mut seed);
+    seed
+}
+
+/// Returns the seed from which query positions are drawn (before proof-of-work is applied to
+/// it). The seed binds roots of all FRI layer commitments, as well as the FRI remainder and the
+/// bound on the number of coefficients of the remainder polynomial; thus, query positions depend
+/// on the contents of the remainder directly.
+///
+/// The remainder is expected to be encoded according to the `encoding`; the degree bound is
+/// encoded as an 8-byte integer in native encoding, and as a 32-byte word in EVM word encoding.
+pub fn build_fri_seed<H: Hasher>(
+    fri_roots: &[[u8; 32]],
+    fri_remainder: &[u8],
+    remainder_degree_bound: usize,
+    encoding: ByteEncoding,
+) -> [u8; 32] {
+    let mut data: Vec<u8> = Vec::with_capacity(fri_roots.len() * 32 + fri_remainder.len() + 32);
+    for root in fri_roots.iter() {
+        data.extend_from_slice(root);
+    }
+    data.extend_from_slice(fri_remainder);
+    encoding.write_u64(remainder_degree_bound as u64, &mut data);
+
+    let mut seed = [0u8; 32];
+    H::hash_fn()(&data, &mut seed);
+    seed
 }
 
 // COMPOSITION COEFFICIENTS
@@ -118,14 +197,116 @@ pub struct CompositionCoefficients<E: FieldElement> {
     pub trace: Vec<(E, E, E)>,
     pub trace_degree: (E, E),
     pub constraints: E,
+    pub extra: Vec<(E, E)>,
 }
 
 impl<E: FieldElement> CompositionCoefficients<E> {
-    pub fn new<R: RandomElementGenerator>(mut prng: R, trace_width: usize) -> Self {
+    /// Draws composition coefficients from the `prng`; coefficients for extra polynomials are
+    /// drawn last, and thus do not affect coefficients drawn for proofs without extra polynomials.
+    pub fn new<R: RandomElementGenerator>(
+        mut prng: R,
+        trace_width: usize,
+        num_extra_polys: usize,
+    ) -> Self {
         CompositionCoefficients {
             trace: (0..trace_width).map(|_| prng.draw_triple()).collect(),
             trace_degree: prng.draw_pair(),
             constraints: prng.draw(),
+            extra: (0..num_extra_polys).map(|_| prng.draw_pair()).collect(),
+        }
+    }
+}
+
+// CONSTRAINT COEFFICIENTS
+// ================================================================================================
+
+/// Draws coefficients for constraints of a single constraint group according to the specified
+/// coefficient scheme.
+///
+/// With [CoefficientScheme::Independent], every pair of coefficients is drawn from the PRNG
+/// directly. With [CoefficientScheme::Powers], a single element α is drawn from the PRNG when
+/// the group is created, and consecutive pairs of powers of α are returned afterwards: the first
+/// pair is (α, α^2), the second is (α^3, α^4) etc.
+pub(crate) struct ConstraintCoefficients<E: FieldElement> {
+    powers: Option<(E, E)>, // (α, last returned power of α)
+}
+
+impl<E: FieldElement> ConstraintCoefficients<E> {
+    /// Returns a new coefficient source for a constraint group; in the powers scheme, α for the
+    /// group is drawn from the `prng` here.
+    pub fn new<R: RandomElementGenerator>(scheme: CoefficientScheme, prng: &mut R) -> Self {
+        let powers = match scheme {
+            CoefficientScheme::Independent => None,
+            CoefficientScheme::Powers => Some((prng.draw(), E::ONE)),
+        };
+        ConstraintCoefficients { powers }
+    }
+
+    /// Returns the next pair of coefficients for a constraint in this group.
+    pub fn draw_pair<R: RandomElementGenerator>(&mut self, prng: &mut R) -> (E, E) {
+        match self.powers.as_mut() {
+            None => prng.draw_pair(),
+            Some((alpha, power)) => {
+                let c1 = *power * *alpha;
+                let c2 = c1 * *alpha;
+                *power = c2;
+                (c1, c2)
+            }
         }
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Draws `num_queries` unique positions from the LDE domain of the computation described by the
+/// `context` using PRNG seeded with `query_seed`, and writes them into the `result` vector.
+///
+/// Every position is read from the last 8 bytes of hash(seed || counter); in EVM word encoding,
+/// the counter is a big-endian word, and positions are read from the low bits of the digest
+/// interpreted as a big-endian integer.
+fn draw_positions_into<H: Hasher>(
+    query_seed: [u8; 32],
+    context: &ComputationContext,
+    num_queries: usize,
+    result: &mut Vec<usize>,
+) {
+    let hash_fn = H::hash_fn();
+    let encoding = context.options().byte_encoding();
+
+    // determine how many bits are needed to represent valid indexes in the domain
+    let value_mask = context.lde_domain_size() - 1;
+
+    // initialize the seed for PRNG
+    let mut seed = [0u8; 64];
+    seed[..32].copy_from_slice(&query_seed);
+    let mut value_bytes = [0u8; 32];
+
+    // draw values from PRNG until we get as many unique values as specified by
+    // num_queries, but skipping values which are a multiple of blowup factor
+    result.clear();
+    for i in 0usize..1000 {
+        // update the seed with the new counter and hash the result
+        seed[56..].copy_from_slice(&encoding.encode_u64(i as u64));
+        hash_fn(&seed, &mut value_bytes);
+
+        // read the required number of bits from the hashed value
+        let value =
+            encoding.decode_u64(value_bytes[24..].try_into().unwrap()) as usize & value_mask;
+
+        if result.contains(&value) {
+            continue;
+        }
+        result.push(value);
+        if result.len() >= num_queries {
+            break;
+        }
+    }
+
+    assert!(
+        result.len() == num_queries,
+        "needed to generate {} query positions, but generated only {}",
+        num_queries,
+        result.len()
+    );
+}
@@ -3,8 +3,12 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use super::{build_constraint_seed, build_fri_seed};
 use crypto::{hash, DefaultRandomElementGenerator, RandomElementGenerator};
-use math::field::f128::BaseElement;
+use math::field::{f128::BaseElement, ByteEncoding};
+use std::convert::TryInto;
+
+const NATIVE: ByteEncoding = ByteEncoding::Native;
 
 #[test]
 fn random_generator_draw() {
@@ -28,3 +32,139 @@ fn random_generator_draw() {
         BaseElement::new(209866678167327876517963759170433911820)
     );
 }
+
+#[test]
+fn random_generator_draw_evm_words() {
+    let mut generator = DefaultRandomElementGenerator::<hash::Blake3_256>::with_encoding(
+        [0; 32],
+        0,
+        ByteEncoding::EvmWords,
+    );
+
+    // elements are drawn from hash(seed || counter) with the counter encoded as a big-endian
+    // word, and are read from the low 16 bytes of the digest interpreted as a big-endian integer
+    let mut buf = [0u8; 64];
+    let mut digest = [0u8; 32];
+    for counter in 1..4u8 {
+        buf[63] = counter;
+        hash::blake3(&buf, &mut digest);
+        let value = u128::from_be_bytes(digest[16..].try_into().unwrap());
+        assert_eq!(BaseElement::new(value), generator.draw::<BaseElement>());
+    }
+}
+
+#[test]
+fn constraint_seed_with_aux_roots() {
+    type H = hash::Blake3_256;
+    let trace_root = [1; 32];
+
+    // without auxiliary commitments, the trace root is used as the seed
+    assert_eq!(
+        trace_root,
+        build_constraint_seed::<H>(&trace_root, &[], None, None, None)
+    );
+
+    // otherwise, the seed depends on every auxiliary root and on their order
+    let seed = build_constraint_seed::<H>(&trace_root, &[[2; 32], [3; 32]], None, None, None);
+    assert_ne!(trace_root, seed);
+    assert_ne!(
+        seed,
+        build_constraint_seed::<H>(&trace_root, &[[3; 32], [2; 32]], None, None, None)
+    );
+    assert_ne!(
+        seed,
+        build_constraint_seed::<H>(&trace_root, &[[2; 32]], None, None, None)
+    );
+}
+
+#[test]
+fn constraint_seed_with_air_id() {
+    type H = hash::Blake3_256;
+    let trace_root = [1; 32];
+
+    // the seed depends on the AIR identifier even without auxiliary commitments
+    let seed = build_constraint_seed::<H>(&trace_root, &[], None, None, Some(&[4; 32]));
+    assert_ne!(trace_root, seed);
+    assert_ne!(
+        seed,
+        build_constraint_seed::<H>(&trace_root, &[], None, None, Some(&[5; 32]))
+    );
+
+    // the identifier is bound together with auxiliary roots
+    let seed = build_constraint_seed::<H>(&trace_root, &[[2; 32]], None, None, Some(&[4; 32]));
+    assert_ne!(
+        seed,
+        build_constraint_seed::<H>(&trace_root, &[[2; 32]], None, None, None)
+    );
+}
+
+#[test]
+fn constraint_seed_with_extra_root() {
+    type H = hash::Blake3_256;
+    let trace_root = [1; 32];
+
+    // the seed depends on the root of extra polynomial commitment
+    let seed = build_constraint_seed::<H>(&trace_root, &[], Some(&[6; 32]), None, None);
+    assert_ne!(trace_root, seed);
+    assert_ne!(
+        seed,
+        build_constraint_seed::<H>(&trace_root, &[], Some(&[7; 32]), None, None)
+    );
+
+    // the extra root is bound together with auxiliary roots
+    let seed = build_constraint_seed::<H>(&trace_root, &[[2; 32]], Some(&[6; 32]), None, None);
+    assert_ne!(
+        seed,
+        build_constraint_seed::<H>(&trace_root, &[[2; 32]], None, None, None)
+    );
+}
+
+#[test]
+fn constraint_seed_with_aux_segment_root() {
+    type H = hash::Blake3_256;
+    let trace_root = [1; 32];
+
+    // the seed depends on the root of the auxiliary segment commitment, and thus, it differs
+    // from the seed from which random elements for the auxiliary segment are drawn
+    let aux_segment_seed = build_constraint_seed::<H>(&trace_root, &[], Some(&[6; 32]), None, None);
+    let seed = build_constraint_seed::<H>(&trace_root, &[], Some(&[6; 32]), Some(&[8; 32]), None);
+    assert_ne!(aux_segment_seed, seed);
+    assert_ne!(
+        seed,
+        build_constraint_seed::<H>(&trace_root, &[], Some(&[6; 32]), Some(&[9; 32]), None)
+    );
+    assert_ne!(
+        trace_root,
+        build_constraint_seed::<H>(&trace_root, &[], None, Some(&[8; 32]), None)
+    );
+}
+
+#[test]
+fn fri_seed_binds_remainder() {
+    type H = hash::Blake3_256;
+    let fri_roots = [[1; 32], [2; 32]];
+    let remainder = [5u8; 64];
+
+    // the seed depends on the remainder and on its degree bound, not only on the FRI roots
+    let seed = build_fri_seed::<H>(&fri_roots, &remainder, 4, NATIVE);
+    assert_ne!(
+        seed,
+        build_fri_seed::<H>(&fri_roots, &remainder[..32], 4, NATIVE)
+    );
+    assert_ne!(seed, build_fri_seed::<H>(&fri_roots, &[6u8; 64], 4, NATIVE));
+    assert_ne!(seed, build_fri_seed::<H>(&fri_roots, &remainder, 2, NATIVE));
+    assert_ne!(
+        seed,
+        build_fri_seed::<H>(&fri_roots[..1], &remainder, 4, NATIVE)
+    );
+
+    // in EVM word encoding, the degree bound is absorbed as a big-endian word
+    let mut data = [[1; 32], [2; 32], [5; 32], [5; 32], [0; 32]].concat();
+    data[159] = 4;
+    let mut expected_seed = [0u8; 32];
+    hash::blake3(&data, &mut expected_seed);
+    assert_eq!(
+        expected_seed,
+        build_fri_seed::<H>(&fri_roots, &remainder, 4, ByteEncoding::EvmWords)
+    );
+}
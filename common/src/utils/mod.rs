@@ -0,0 +1,259 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::TraceRowHashing;
+use core::{cmp, mem, ops::Range};
+use crypto::{HashFunction, Hasher};
+use math::field::{ByteEncoding, FieldElement, StarkField};
+
+#[cfg(test)]
+mod tests;
+
+// LEAF POSITIONS
+// ================================================================================================
+
+/// Returns the index of the commitment leaf which contains the item at the specified `position`,
+/// assuming that each leaf contains `items_per_leaf` consecutive items.
+pub fn get_leaf_index(position: usize, items_per_leaf: usize) -> usize {
+    position / items_per_leaf
+}
+
+/// Returns the index of the item at the specified `position` within the leaf containing it.
+pub fn get_leaf_offset(position: usize, items_per_leaf: usize) -> usize {
+    position % items_per_leaf
+}
+
+/// Returns positions of all items contained in the leaf at the specified index.
+pub fn get_leaf_range(leaf_index: usize, items_per_leaf: usize) -> Range<usize> {
+    let start = leaf_index * items_per_leaf;
+    start..start + items_per_leaf
+}
+
+/// Locates the item at the specified `position` among the opened `leaves` (e.g., as returned by
+/// [map_trace_positions_to_leaves()]). Returns the index of the containing leaf in `leaves`
+/// together with the offset of the item within this leaf, or None if the leaf containing the
+/// item was not opened.
+pub fn locate_in_leaves(
+    position: usize,
+    leaves: &[usize],
+    items_per_leaf: usize,
+) -> Option<(usize, usize)> {
+    let leaf_index = get_leaf_index(position, items_per_leaf);
+    leaves
+        .iter()
+        .position(|&leaf| leaf == leaf_index)
+        .map(|i| (i, get_leaf_offset(position, items_per_leaf)))
+}
+
+/// Returns the number of items of `item_bytes` bytes each which are committed together in a
+/// single leaf of `leaf_bytes` bytes. This is the largest power of two not exceeding the number
+/// of items which fit into the leaf, but never less than one.
+pub fn get_items_per_leaf(leaf_bytes: usize, item_bytes: usize) -> usize {
+    let result = cmp::max(leaf_bytes / item_bytes, 1);
+
+    // make sure we take the biggest power of 2 which is smaller than or equal to result;
+    // e.g. 2 -> 2, 3 -> 2, 4 -> 4
+    1 << (mem::size_of::<usize>() * 8 - result.leading_zeros() as usize - 1)
+}
+
+// CONSTRAINT COMMITMENTS
+// ================================================================================================
+
+/// Computes the number of evaluations which will be committed together in a single leaf. For
+/// example, if a node in the tree is 32 bytes, and our elements are 16 bytes each, we'll commit
+/// to 4 elements in a single leaf.
+pub fn evaluations_per_leaf<E: FieldElement, H: Hasher>() -> usize {
+    // compute how many elements would fit into two digests
+    let digest_size = mem::size_of::<H::Digest>();
+    get_items_per_leaf(2 * digest_size, E::ELEMENT_BYTES)
+}
+
+/// Maps positions in a trace commitment tree to positions in the constraint evaluation
+/// commitment tree.
+pub fn map_trace_to_constraint_positions(
+    trace_positions: &[usize],
+    evaluations_per_leaf: usize,
+) -> Vec<usize> {
+    let mut result = Vec::with_capacity(trace_positions.len());
+    map_trace_to_constraint_positions_into(trace_positions, evaluations_per_leaf, &mut result);
+    result
+}
+
+/// Same as [map_trace_to_constraint_positions()], but writes the positions into the provided
+/// `result` vector; any values previously held by the vector are discarded.
+pub fn map_trace_to_constraint_positions_into(
+    trace_positions: &[usize],
+    evaluations_per_leaf: usize,
+    result: &mut Vec<usize>,
+) {
+    map_positions_to_leaves_into(trace_positions, evaluations_per_leaf, result);
+}
+
+// TRACE COMMITMENTS
+// ================================================================================================
+
+/// Maximum number of bytes of trace rows which can be packed into a trace commitment leaf without
+/// hashing; this is the size of a digest.
+pub const MAX_PACKED_LEAF_BYTES: usize = 32;
+
+/// Computes a leaf of the trace commitment from the serialized trace rows in `leaf_bytes`, and
+/// writes it into `result`; `trace_width` is the number of values in every row, and the values
+/// are encoded according to the `encoding`.
+///
+/// With [TraceRowHashing::Flat], when elements of the base field are at most 8 bytes long, rows
+/// of a leaf which fit into a single digest are packed into the leaf directly: the leaf is
+/// `leaf_bytes` followed by zero bytes. Otherwise, the leaf is `hash(leaf_bytes)`. All leaves of
+/// a trace commitment encode the same number of bytes; thus, the zero padding is unambiguous.
+/// For narrow traces over small fields this removes one hash function invocation per leaf.
+///
+/// With [TraceRowHashing::Chunked], the leaf is the root of a Merkle tree built from digests of
+/// row chunks as described in [hash_trace_leaf_chunks()].
+pub fn hash_trace_leaf<B: StarkField, H: Hasher>(
+    leaf_bytes: &[u8],
+    row_hashing: TraceRowHashing,
+    trace_width: usize,
+    encoding: ByteEncoding,
+    result: &mut [u8; 32],
+) {
+    match row_hashing {
+        TraceRowHashing::Flat if is_packed_trace_leaf::<B>(leaf_bytes.len()) => {
+            result[..leaf_bytes.len()].copy_from_slice(leaf_bytes);
+            result[leaf_bytes.len()..].fill(0);
+        }
+        TraceRowHashing::Flat => H::hash_fn()(leaf_bytes, result),
+        TraceRowHashing::Chunked(chunk_width) => {
+            let row_bytes = trace_width * encoding.element_bytes::<B>();
+            let chunk_bytes = chunk_width as usize * encoding.element_bytes::<B>();
+            let digests = hash_trace_leaf_chunks::<H>(leaf_bytes, row_bytes, chunk_bytes);
+            *result = fold_merkle_root(digests, H::hash_fn());
+        }
+    }
+}
+
+/// Hashes every chunk of every row in `leaf_bytes` and returns the digests; these are the leaves
+/// of the Merkle tree whose root is the trace commitment leaf for chunked row hashing.
+///
+/// Rows are `row_bytes` long and are split into chunks of `chunk_bytes` bytes (the last chunk
+/// of a row may be shorter). Digests of each row are padded with zero digests to
+/// [get_padded_chunks_per_row()] digests, and the padded digests of all rows are placed one
+/// after another. Thus, the digest of chunk `j` of row `i` is at index
+/// `i * padded_chunks_per_row + j`.
+pub fn hash_trace_leaf_chunks<H: Hasher>(
+    leaf_bytes: &[u8],
+    row_bytes: usize,
+    chunk_bytes: usize,
+) -> Vec<[u8; 32]> {
+    let hash_fn = H::hash_fn();
+    let num_chunks = row_bytes.div_ceil(chunk_bytes);
+    let padded_chunks = get_padded_chunks_per_row(num_chunks);
+    let num_rows = leaf_bytes.len() / row_bytes;
+
+    let mut digests = vec![[0u8; 32]; cmp::max(num_rows * padded_chunks, 2)];
+    for (row, row_digests) in leaf_bytes
+        .chunks(row_bytes)
+        .zip(digests.chunks_mut(padded_chunks))
+    {
+        for (chunk, digest) in row.chunks(chunk_bytes).zip(row_digests.iter_mut()) {
+            hash_fn(chunk, digest);
+        }
+    }
+    digests
+}
+
+/// Returns the number of chunk digests per row in a trace commitment leaf for chunked row
+/// hashing; this is the number of chunks in a row rounded up to the next power of two.
+pub fn get_padded_chunks_per_row(num_chunks: usize) -> usize {
+    num_chunks.next_power_of_two()
+}
+
+/// Returns offsets of the rows of the leaf at index `leaf` which are located at any of the
+/// `positions`, in ascending order and without duplicates; each leaf contains `rows_per_leaf`
+/// consecutive rows.
+pub fn get_queried_rows(leaf: usize, rows_per_leaf: usize, positions: &[usize]) -> Vec<usize> {
+    let mut rows = positions
+        .iter()
+        .filter(|&&position| get_leaf_index(position, rows_per_leaf) == leaf)
+        .map(|&position| get_leaf_offset(position, rows_per_leaf))
+        .collect::<Vec<_>>();
+    rows.sort_unstable();
+    rows.dedup();
+    rows
+}
+
+/// Returns the number of chunks of `chunk_width` columns in a row of `trace_width` columns.
+pub fn get_num_row_chunks(trace_width: usize, chunk_width: usize) -> usize {
+    trace_width.div_ceil(chunk_width)
+}
+
+/// Returns true if trace rows serialized into `leaf_bytes` bytes are packed into a trace
+/// commitment leaf as is, rather than hashed.
+pub fn is_packed_trace_leaf<B: StarkField>(leaf_bytes: usize) -> bool {
+    B::ELEMENT_BYTES <= 8 && leaf_bytes <= MAX_PACKED_LEAF_BYTES
+}
+
+/// Maps positions in the LDE domain to indexes of the trace commitment leaves which contain
+/// rows at these positions; `rows_per_leaf` is the trace leaf batching factor.
+pub fn map_trace_positions_to_leaves(positions: &[usize], rows_per_leaf: usize) -> Vec<usize> {
+    let mut result = Vec::with_capacity(positions.len());
+    map_positions_to_leaves_into(positions, rows_per_leaf, &mut result);
+    result
+}
+
+/// Same as [map_trace_positions_to_leaves()], but writes the leaf indexes into the provided
+/// `result` vector; any values previously held by the vector are discarded.
+pub fn map_trace_positions_to_leaves_into(
+    positions: &[usize],
+    rows_per_leaf: usize,
+    result: &mut Vec<usize>,
+) {
+    map_positions_to_leaves_into(positions, rows_per_leaf, result);
+}
+
+/// Returns true if `layout` maps each of `trace_width` registers to a distinct column of a trace
+/// commitment row, i.e., if it is a permutation of [0, `trace_width`).
+pub fn is_valid_column_layout(layout: &[usize], trace_width: usize) -> bool {
+    let mut seen = vec![false; trace_width];
+    layout.len() == trace_width
+        && layout
+            .iter()
+            .all(|&column| column < trace_width && !mem::replace(&mut seen[column], true))
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Computes the root of a Merkle tree with the specified `leaves` in the same way as
+/// [MerkleTree](crypto::MerkleTree) does, but without keeping the internal nodes; the number of
+/// leaves must be a power of two greater than one.
+fn fold_merkle_root(mut nodes: Vec<[u8; 32]>, hash_fn: HashFunction) -> [u8; 32] {
+    let mut buf = [0u8; 64];
+    let mut n = nodes.len();
+    while n > 1 {
+        for i in 0..n / 2 {
+            buf[..32].copy_from_slice(&nodes[2 * i]);
+            buf[32..].copy_from_slice(&nodes[2 * i + 1]);
+            hash_fn(&buf, &mut nodes[i]);
+        }
+        n /= 2;
+    }
+    nodes[0]
+}
+
+/// Maps positions of individual items to indexes of the leaves containing them, assuming that
+/// each leaf contains `items_per_leaf` consecutive items; the resulting indexes are unique and
+/// are listed in the order in which they first appear.
+fn map_positions_to_leaves_into(
+    positions: &[usize],
+    items_per_leaf: usize,
+    result: &mut Vec<usize>,
+) {
+    result.clear();
+    for &position in positions.iter() {
+        let leaf = get_leaf_index(position, items_per_leaf);
+        if !result.contains(&leaf) {
+            result.push(leaf);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crypto::hash::Blake3_256;
+use math::field::{f128, f31, f62, QuadExtension};
+
+// LEAF POSITIONS
+// ================================================================================================
+
+#[test]
+fn get_leaf_index_and_offset() {
+    for &items_per_leaf in [1, 2, 4, 8].iter() {
+        for position in 0..64 {
+            let leaf_index = super::get_leaf_index(position, items_per_leaf);
+            let offset = super::get_leaf_offset(position, items_per_leaf);
+            assert!(offset < items_per_leaf);
+            assert_eq!(position, leaf_index * items_per_leaf + offset);
+            assert!(super::get_leaf_range(leaf_index, items_per_leaf).contains(&position));
+        }
+    }
+}
+
+#[test]
+fn get_leaf_range() {
+    assert_eq!(0..1, super::get_leaf_range(0, 1));
+    assert_eq!(5..6, super::get_leaf_range(5, 1));
+    assert_eq!(0..4, super::get_leaf_range(0, 4));
+    assert_eq!(12..16, super::get_leaf_range(3, 4));
+}
+
+#[test]
+fn locate_in_leaves() {
+    let positions = [9, 2, 11, 3, 30];
+    let leaves = super::map_trace_positions_to_leaves(&positions, 4);
+    assert_eq!(vec![2, 0, 7], leaves);
+
+    assert_eq!(Some((0, 1)), super::locate_in_leaves(9, &leaves, 4));
+    assert_eq!(Some((1, 2)), super::locate_in_leaves(2, &leaves, 4));
+    assert_eq!(Some((0, 3)), super::locate_in_leaves(11, &leaves, 4));
+    assert_eq!(Some((1, 3)), super::locate_in_leaves(3, &leaves, 4));
+    assert_eq!(Some((2, 2)), super::locate_in_leaves(30, &leaves, 4));
+
+    // positions which are not in any of the opened leaves
+    assert_eq!(None, super::locate_in_leaves(4, &leaves, 4));
+    assert_eq!(None, super::locate_in_leaves(64, &leaves, 4));
+}
+
+#[test]
+fn map_positions_to_leaves() {
+    let positions = [5, 1, 4, 7, 0];
+    assert_eq!(
+        positions.to_vec(),
+        super::map_trace_positions_to_leaves(&positions, 1)
+    );
+    assert_eq!(
+        vec![2, 0, 3],
+        super::map_trace_positions_to_leaves(&positions, 2)
+    );
+    assert_eq!(
+        vec![1, 0],
+        super::map_trace_to_constraint_positions(&positions, 4)
+    );
+
+    // previous contents of the result vector are discarded
+    let mut result = vec![10, 11, 12, 13];
+    super::map_trace_positions_to_leaves_into(&positions, 8, &mut result);
+    assert_eq!(vec![0], result);
+    super::map_trace_to_constraint_positions_into(&positions, 2, &mut result);
+    assert_eq!(vec![2, 0, 3], result);
+}
+
+#[test]
+fn get_items_per_leaf() {
+    assert_eq!(8, super::get_items_per_leaf(64, 8));
+    assert_eq!(4, super::get_items_per_leaf(64, 16));
+    assert_eq!(2, super::get_items_per_leaf(64, 24));
+    assert_eq!(2, super::get_items_per_leaf(64, 32));
+    assert_eq!(1, super::get_items_per_leaf(64, 48));
+    assert_eq!(1, super::get_items_per_leaf(64, 128));
+}
+
+#[test]
+fn evaluations_per_leaf() {
+    assert_eq!(
+        16,
+        super::evaluations_per_leaf::<f31::BaseElement, Blake3_256>()
+    );
+    assert_eq!(
+        8,
+        super::evaluations_per_leaf::<f62::BaseElement, Blake3_256>()
+    );
+    assert_eq!(
+        4,
+        super::evaluations_per_leaf::<f128::BaseElement, Blake3_256>()
+    );
+    assert_eq!(
+        2,
+        super::evaluations_per_leaf::<QuadExtension<f128::BaseElement>, Blake3_256>()
+    );
+}
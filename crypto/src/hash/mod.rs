@@ -12,7 +12,7 @@ use utils::AsBytes;
 // HASHER TRAIT
 // ================================================================================================
 
-pub trait Hasher {
+pub trait Hasher: Send + Sync {
     type Digest: Debug + Copy + AsRef<[u8]> + Default + Eq + PartialEq;
 
     fn merge(values: &[Self::Digest; 2]) -> Self::Digest;
@@ -5,7 +5,7 @@
 
 use crate::HashFunction;
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet},
     slice,
 };
 
@@ -144,6 +144,19 @@ impl MerkleTree {
 
     /// Checks whether the path for the specified index is valid.
     pub fn verify(root: &[u8; 32], index: usize, proof: &[[u8; 32]], hash: HashFunction) -> bool {
+        Self::compute_root(index, proof, hash) == *root
+    }
+
+    /// Computes the root to which the path for the specified index resolves.
+    ///
+    /// The first two elements of the `proof` are the leaf at the specified index and its
+    /// sibling, as returned by [MerkleTree::prove()]. Unlike [MerkleTree::verify()], this does
+    /// not compare the result against a known root, and thus, can be used to check the path
+    /// against a root obtained elsewhere.
+    ///
+    /// # Panics
+    /// Panics if the proof contains fewer than two elements.
+    pub fn compute_root(index: usize, proof: &[[u8; 32]], hash: HashFunction) -> [u8; 32] {
         let mut buf = [0u8; 64];
         let mut v = [0u8; 32];
 
@@ -165,7 +178,7 @@ impl MerkleTree {
             index >>= 1;
         }
 
-        v == *root
+        v
     }
 
     /// Checks whether the batch proof contains merkle paths for the of the specified indexes.
@@ -211,8 +224,8 @@ pub fn build_merkle_nodes(leaves: &[[u8; 32]], hash: HashFunction) -> Vec<[u8; 3
     nodes
 }
 
-fn map_indexes(indexes: &[usize], max_valid: usize) -> HashMap<usize, usize> {
-    let mut map = HashMap::new();
+fn map_indexes(indexes: &[usize], max_valid: usize) -> BTreeMap<usize, usize> {
+    let mut map = BTreeMap::new();
     for (i, index) in indexes.iter().cloned().enumerate() {
         map.insert(index, i);
         assert!(index <= max_valid, "invalid index {}", index);
@@ -4,10 +4,10 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::HashFunction;
-use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct BatchMerkleProof {
     pub values: Vec<[u8; 32]>,
     pub nodes: Vec<Vec<[u8; 32]>>,
@@ -85,7 +85,7 @@ impl BatchMerkleProof {
     /// Computes a node to which all Merkle paths aggregated in this proof resolve.
     pub fn get_root(&self, indexes: &[usize], hash: HashFunction) -> Option<[u8; 32]> {
         let mut buf = [0u8; 64];
-        let mut v = HashMap::new();
+        let mut v = BTreeMap::new();
 
         // replace odd indexes, offset, and sort in ascending order
         let offset = usize::pow(2, self.depth as u32);
@@ -207,6 +207,43 @@ impl BatchMerkleProof {
     }
 }
 
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for BatchMerkleProof {
+    /// Writes tree depth, leaf values, and authentication nodes; every list of nodes is prefixed
+    /// with its length.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.depth);
+        target.write_u32(self.values.len() as u32);
+        <[u8; 32]>::write_batch_into(&self.values, target);
+        target.write_u32(self.nodes.len() as u32);
+        for nodes in self.nodes.iter() {
+            target.write_u8(nodes.len() as u8);
+            <[u8; 32]>::write_batch_into(nodes, target);
+        }
+    }
+}
+
+impl Deserializable for BatchMerkleProof {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let depth = source.read_u8()?;
+        let num_values = source.read_u32()? as usize;
+        let values = <[u8; 32]>::read_batch_from(source, num_values)?;
+        let num_node_lists = source.read_u32()? as usize;
+        let mut nodes = Vec::new();
+        for _ in 0..num_node_lists {
+            let num_nodes = source.read_u8()? as usize;
+            nodes.push(<[u8; 32]>::read_batch_from(source, num_nodes)?);
+        }
+        Ok(BatchMerkleProof {
+            values,
+            nodes,
+            depth,
+        })
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
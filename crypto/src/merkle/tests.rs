@@ -7,6 +7,7 @@ use proptest::prelude::*;
 
 use super::*;
 use crate::hash;
+use utils::{Deserializable, Serializable};
 
 static LEAVES4: [[u8; 32]; 4] = [
     [
@@ -159,6 +160,27 @@ fn verify() {
     );
 }
 
+#[test]
+fn compute_root() {
+    let leaves = LEAVES8.to_vec();
+    let tree = MerkleTree::new(leaves, hash::blake3);
+    for index in 0..LEAVES8.len() {
+        let proof = tree.prove(index);
+        assert_eq!(
+            *tree.root(),
+            MerkleTree::compute_root(index, &proof, hash::blake3)
+        );
+    }
+
+    // a path for a different leaf resolves to a different root
+    let mut proof = tree.prove(3);
+    proof[0] = LEAVES8[4];
+    assert_ne!(
+        *tree.root(),
+        MerkleTree::compute_root(3, &proof, hash::blake3)
+    );
+}
+
 #[test]
 fn prove_batch() {
     let leaves = LEAVES8.to_vec();
@@ -268,6 +290,26 @@ fn verify_batch() {
     );
 }
 
+#[test]
+fn batch_proof_serialization() {
+    let leaves = LEAVES8.to_vec();
+    let tree = MerkleTree::new(leaves, hash::blake3);
+
+    let proof = tree.prove_batch(&[1, 3, 6]);
+    let bytes = proof.to_bytes();
+    let decoded = BatchMerkleProof::read_from_bytes(&bytes).unwrap();
+    assert_eq!(proof, decoded);
+    assert!(MerkleTree::verify_batch(
+        tree.root(),
+        &[1, 3, 6],
+        &decoded,
+        hash::blake3
+    ));
+
+    // truncated proofs cannot be deserialized
+    assert!(BatchMerkleProof::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
 proptest! {
     #[test]
     fn prove_n_verify(tree in random_blake3_merkle_tree(128),
@@ -5,7 +5,7 @@
 
 use crate::hash::Hasher;
 use core::slice;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
 
 mod proofs;
 pub use proofs::BatchMerkleProof;
@@ -197,8 +197,8 @@ pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
     nodes
 }
 
-fn map_indexes(indexes: &[usize], max_valid: usize) -> HashMap<usize, usize> {
-    let mut map = HashMap::new();
+fn map_indexes(indexes: &[usize], max_valid: usize) -> BTreeMap<usize, usize> {
+    let mut map = BTreeMap::new();
     for (i, index) in indexes.iter().cloned().enumerate() {
         map.insert(index, i);
         assert!(index <= max_valid, "invalid index {}", index);
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::Hasher;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BatchMerkleProof<H: Hasher> {
@@ -84,7 +84,7 @@ impl<H: Hasher> BatchMerkleProof<H> {
     /// Computes a node to which all Merkle paths aggregated in this proof resolve.
     pub fn get_root(&self, indexes: &[usize]) -> Option<H::Digest> {
         let mut buf = [H::Digest::default(); 2];
-        let mut v = HashMap::new();
+        let mut v = BTreeMap::new();
 
         // replace odd indexes, offset, and sort in ascending order
         let offset = usize::pow(2, self.depth as u32);
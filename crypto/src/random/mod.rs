@@ -4,17 +4,29 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::Hasher;
-use math::field::FieldElement;
+use math::field::{from_evm_word, from_evm_words, ByteEncoding, FieldElement, EVM_WORD_BYTES};
 use std::{convert::TryInto, marker::PhantomData};
 
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum size of field elements which can be drawn by the default generator.
+const MAX_ELEMENT_BYTES: usize = 128;
+
 // RANDOM FIELD ELEMENT GENERATOR TRAIT
 // ================================================================================================
 
-pub trait RandomElementGenerator {
+pub trait RandomElementGenerator: Sized {
     type Hasher: Hasher;
 
     /// Returns a new random element generator instantiated with the provided `seed` and `offset`.
-    fn new(seed: [u8; 32], offset: u64) -> Self;
+    fn new(seed: [u8; 32], offset: u64) -> Self {
+        Self::with_encoding(seed, offset, ByteEncoding::Native)
+    }
+
+    /// Returns a new random element generator instantiated with the provided `seed` and `offset`
+    /// which encodes its counter and decodes drawn elements according to the `encoding`.
+    fn with_encoding(seed: [u8; 32], offset: u64, encoding: ByteEncoding) -> Self;
 
     /// Generates the next pseudo-random field element.
     fn draw<E: FieldElement>(&mut self) -> E;
@@ -33,40 +45,85 @@ pub trait RandomElementGenerator {
 // DEFAULT GENERATOR
 // ================================================================================================
 
+/// Generator which draws elements from hash(seed || counter), where the seed occupies the first
+/// 32 bytes of a 64-byte buffer, and the counter occupies the last 8 bytes.
+///
+/// In native encoding, the counter is a little-endian integer, and elements are read from the
+/// first bytes of the digest. In EVM word encoding, the counter is a big-endian integer (and thus,
+/// the last 32 bytes of the buffer form an EVM word), and every coefficient of an element is
+/// drawn from a separate digest by reducing the digest, interpreted as a big-endian integer,
+/// modulo 2^(8 * n), where n is the number of bytes in an element of the base field.
 pub struct DefaultRandomElementGenerator<H: Hasher> {
     seed: [u8; 64],
+    encoding: ByteEncoding,
     _hasher: PhantomData<H>,
 }
 
 impl<H: Hasher> DefaultRandomElementGenerator<H> {
     /// Update the seed by incrementing the value in the last 8 bytes by 1.
     fn increment_counter(&mut self) {
-        let mut counter = u64::from_le_bytes(self.seed[56..].try_into().unwrap());
+        let mut counter = self
+            .encoding
+            .decode_u64(self.seed[56..].try_into().unwrap());
         counter += 1;
-        self.seed[56..].copy_from_slice(&counter.to_le_bytes());
+        self.seed[56..].copy_from_slice(&self.encoding.encode_u64(counter));
+    }
+
+    /// Draws an element as a sequence of EVM words, one word per coefficient of the element.
+    fn draw_evm_words<E: FieldElement>(&mut self) -> E {
+        let hash_fn = H::hash_fn();
+        let base_bytes = E::Base::ELEMENT_BYTES;
+        let mut words = vec![0u8; E::ELEMENT_BYTES / base_bytes * EVM_WORD_BYTES];
+        for word in words.chunks_exact_mut(EVM_WORD_BYTES) {
+            // keep only the low bytes of the digest, and retry until they encode a valid element
+            // of the base field
+            loop {
+                self.increment_counter();
+                hash_fn(&self.seed, word);
+                word[..EVM_WORD_BYTES - base_bytes].fill(0);
+                if from_evm_word::<E::Base>(word.as_ref().try_into().unwrap()).is_some() {
+                    break;
+                }
+            }
+        }
+        from_evm_words(&words).expect("words must encode a valid element")[0]
     }
 }
 
 impl<H: Hasher> RandomElementGenerator for DefaultRandomElementGenerator<H> {
     type Hasher = H;
 
-    fn new(seed: [u8; 32], offset: u64) -> Self {
+    fn with_encoding(seed: [u8; 32], offset: u64, encoding: ByteEncoding) -> Self {
         let mut generator = DefaultRandomElementGenerator {
             seed: [0u8; 64],
+            encoding,
             _hasher: PhantomData,
         };
         generator.seed[..32].copy_from_slice(&seed);
-        generator.seed[56..].copy_from_slice(&offset.to_le_bytes());
+        generator.seed[56..].copy_from_slice(&encoding.encode_u64(offset));
         generator
     }
 
     fn draw<E: FieldElement>(&mut self) -> E {
+        if let ByteEncoding::EvmWords = self.encoding {
+            return self.draw_evm_words();
+        }
+        assert!(
+            E::ELEMENT_BYTES <= MAX_ELEMENT_BYTES,
+            "cannot draw elements longer than {} bytes",
+            MAX_ELEMENT_BYTES
+        );
         let hash_fn = H::hash_fn();
-        let mut result = [0u8; 32];
+        let mut digest = [0u8; 32];
+        let mut result = [0u8; MAX_ELEMENT_BYTES];
         loop {
-            // updated the seed by incrementing its counter and then hash the result
-            self.increment_counter();
-            hash_fn(&self.seed, &mut result);
+            // updated the seed by incrementing its counter and then hash the result; elements
+            // which do not fit into a single digest are assembled from several digests
+            for chunk in result[..E::ELEMENT_BYTES].chunks_mut(digest.len()) {
+                self.increment_counter();
+                hash_fn(&self.seed, &mut digest);
+                chunk.copy_from_slice(&digest[..chunk.len()]);
+            }
 
             // take the first ELEMENT_BYTES from the hashed seed and check if they can be converted
             // into a valid field element; if the can, return; otherwise try again
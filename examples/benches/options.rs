@@ -0,0 +1,246 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion,
+};
+use prover::{
+    math::field::{f128, f31, f62, f64, FieldElement, StarkField},
+    Air, Assertion, ComputationContext, EvaluationFrame, ExecutionTrace, FieldExtension,
+    HashFunction, ProofOptions, Serializable, TraceInfo, TransitionConstraintDegree,
+};
+use std::time::{Duration, Instant};
+
+const TRACE_LENGTH: usize = 16_384;
+
+/// Compares proof generation time, proof verification time, and proof size for the same
+/// computation across all supported base fields, hash functions, and field extensions. Once all
+/// configurations are measured, the results are summarized in a single table.
+fn options(c: &mut Criterion) {
+    let mut group = c.benchmark_group("options");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    let mut results = Vec::new();
+    for &hash_fn in [HashFunction::Blake3_256, HashFunction::Sha3_256].iter() {
+        for &extension in [
+            FieldExtension::None,
+            FieldExtension::Quadratic,
+            FieldExtension::Cubic,
+            FieldExtension::Quartic,
+        ]
+        .iter()
+        {
+            let options = ProofOptions::new(32, 8, 0, hash_fn, extension);
+            results.push(bench_options::<f128::BaseElement>(
+                &mut group, "f128", &options,
+            ));
+            results.push(bench_options::<f62::BaseElement>(
+                &mut group, "f62", &options,
+            ));
+            results.push(bench_options::<f64::BaseElement>(
+                &mut group, "f64", &options,
+            ));
+            results.push(bench_options::<f31::BaseElement>(
+                &mut group, "f31", &options,
+            ));
+        }
+    }
+    group.finish();
+
+    println!(
+        "\n{:<6} {:<10} {:<10} {:>14} {:>14} {:>14}",
+        "field", "hash", "extension", "prover (ms)", "verifier (ms)", "proof (KB)"
+    );
+    for result in results.iter() {
+        let verifier_time = match &result.verifier_time {
+            Some(time) => format!("{:.3}", time.mean_millis()),
+            None => "failed".to_string(),
+        };
+        println!(
+            "{:<6} {:<10} {:<10} {:>14.1} {:>14} {:>14.1}",
+            result.field,
+            result.hash_fn,
+            result.extension,
+            result.prover_time.mean_millis(),
+            verifier_time,
+            result.proof_size as f64 / 1024.0
+        );
+    }
+}
+
+criterion_group!(options_group, options);
+criterion_main!(options_group);
+
+// BENCHMARK RUNNER
+// ================================================================================================
+
+struct BenchResult {
+    field: &'static str,
+    hash_fn: &'static str,
+    extension: &'static str,
+    prover_time: TotalTime,
+    /// None if the generated proof could not be verified.
+    verifier_time: Option<TotalTime>,
+    proof_size: usize,
+}
+
+/// Accumulates time reported to criterion across all iterations of a benchmark.
+#[derive(Default)]
+struct TotalTime {
+    elapsed: Duration,
+    iters: u64,
+}
+
+impl TotalTime {
+    fn mean_millis(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0 / self.iters as f64
+    }
+}
+
+fn bench_options<B: StarkField>(
+    group: &mut BenchmarkGroup<WallTime>,
+    field: &'static str,
+    options: &ProofOptions,
+) -> BenchResult {
+    let hash_fn = match options.hash_fn() {
+        HashFunction::Blake3_256 => "blake3",
+        HashFunction::Sha3_256 => "sha3",
+    };
+    let extension = match options.field_extension() {
+        FieldExtension::None => "none",
+        FieldExtension::Quadratic => "quadratic",
+        FieldExtension::Cubic => "cubic",
+        FieldExtension::Quartic => "quartic",
+    };
+    let id = format!("{}/{}/{}", field, hash_fn, extension);
+
+    // the trace is re-built for every proof, but trace generation is excluded from the timings
+    let result = build_trace::<B>(TRACE_LENGTH).get(1, TRACE_LENGTH - 1);
+    let prove = || {
+        let trace = build_trace::<B>(TRACE_LENGTH);
+        let now = Instant::now();
+        let proof = prover::prove::<FibAir<B>>(trace, result, options.clone()).unwrap();
+        (proof, now.elapsed())
+    };
+
+    let mut prover_time = TotalTime::default();
+    group.bench_function(BenchmarkId::new("prove", &id), |bench| {
+        bench.iter_custom(|iters| {
+            let elapsed = (0..iters).map(|_| prove().1).sum();
+            prover_time.elapsed += elapsed;
+            prover_time.iters += iters;
+            elapsed
+        });
+    });
+
+    let proof = prove().0;
+    let proof_size = proof.to_bytes().len();
+
+    // a proof which fails verification is reported in the summary instead of being benchmarked;
+    // this way, a single unsupported configuration does not hide results for all others
+    if let Err(err) = verifier::verify::<FibAir<B>>(proof.clone(), result) {
+        eprintln!("failed to verify proof for {}: {}", id, err);
+        return BenchResult {
+            field,
+            hash_fn,
+            extension,
+            prover_time,
+            verifier_time: None,
+            proof_size,
+        };
+    }
+
+    let mut verifier_time = TotalTime::default();
+    group.bench_function(BenchmarkId::new("verify", &id), |bench| {
+        bench.iter_custom(|iters| {
+            let elapsed = (0..iters)
+                .map(|_| {
+                    let proof = proof.clone();
+                    let now = Instant::now();
+                    verifier::verify::<FibAir<B>>(proof, result).unwrap();
+                    now.elapsed()
+                })
+                .sum();
+            verifier_time.elapsed += elapsed;
+            verifier_time.iters += iters;
+            elapsed
+        });
+    });
+
+    BenchResult {
+        field,
+        hash_fn,
+        extension,
+        prover_time,
+        verifier_time: Some(verifier_time),
+        proof_size,
+    }
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// A Fibonacci sequence AIR (2 terms per step) which can be instantiated for any base field.
+struct FibAir<B: StarkField> {
+    context: ComputationContext,
+    result: B,
+}
+
+impl<B: StarkField> Air for FibAir<B> {
+    type BaseElement = B;
+    type PublicInputs = B;
+
+    fn new(trace_info: TraceInfo, result: B, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+        ];
+        FibAir {
+            context: ComputationContext::new(2, trace_info.length, degrees, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<B>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<B>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, B::ONE),
+            Assertion::single(1, 0, B::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+fn build_trace<B: StarkField>(trace_length: usize) -> ExecutionTrace<B> {
+    let mut trace = ExecutionTrace::new(2, trace_length);
+    trace.fill(
+        |state| {
+            state[0] = B::ONE;
+            state[1] = B::ONE;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+    trace
+}
@@ -0,0 +1,215 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::{are_equal, is_binary, not};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ByteWriter, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions,
+    Serializable, TraceInfo, TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bits into which each value of the trajectory is decomposed; all values of the
+/// trajectory must fit into this many bits.
+pub const NUM_BITS: usize = 32;
+
+/// Register holding the current value of the trajectory.
+const VALUE: usize = 0;
+/// Register holding the inverse of (value - 1); this proves that the value is not 1.
+const INV: usize = 1;
+/// First of the registers holding binary decomposition of the value, least significant bit
+/// first; the least significant bit selects the branch taken at each step.
+const BITS: usize = 2;
+
+pub const TRACE_WIDTH: usize = BITS + NUM_BITS;
+
+// COLLATZ AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub start: u64,
+    pub num_steps: usize,
+}
+
+impl Serializable for PublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u64(self.start);
+        target.write_u64(self.num_steps as u64);
+    }
+}
+
+pub struct CollatzAir {
+    context: ComputationContext,
+    start: BaseElement,
+    num_steps: usize,
+}
+
+impl Air for CollatzAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        assert!(
+            pub_inputs.num_steps < trace_info.length,
+            "number of steps must be smaller than trace length"
+        );
+
+        // all constraints are enforced only on the steps of the trajectory; this is accomplished
+        // by multiplying them by a mask which spans the entire trace
+        let mut degrees = Vec::with_capacity(NUM_BITS + 3);
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            1,
+            vec![trace_info.length],
+        ));
+        for _ in 0..NUM_BITS + 2 {
+            degrees.push(TransitionConstraintDegree::with_cycles(
+                2,
+                vec![trace_info.length],
+            ));
+        }
+
+        // only the value register is read in the next state; the inverse and the bits are
+        // constrained within a single state, and thus, are local
+        let local_registers = (INV..TRACE_WIDTH).collect::<Vec<_>>();
+        let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options)
+            .with_local_registers(&local_registers);
+        CollatzAir {
+            context,
+            start: BaseElement::from(pub_inputs.start),
+            num_steps: pub_inputs.num_steps,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        let mut step_mask = vec![BaseElement::ZERO; self.trace_length()];
+        step_mask[..self.num_steps].fill(BaseElement::ONE);
+        vec![step_mask]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        // the trajectory must start with the public starting value and reach 1 after the
+        // specified number of steps
+        vec![
+            Assertion::single(VALUE, 0, self.start),
+            Assertion::single(VALUE, self.num_steps, BaseElement::ONE),
+        ]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        // expected state width is 2 + NUM_BITS field elements
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        let step_flag = periodic_values[0];
+        let value = current[VALUE];
+        let bits = &current[BITS..];
+
+        // the value must be equal to its binary decomposition, and every bit of the
+        // decomposition must be either 0 or 1; this also ensures that the value fits into
+        // NUM_BITS bits, and thus, the next step cannot overflow the field
+        result[0] = step_flag * are_equal(value, combine_bits(bits));
+        for (i, &bit) in bits.iter().enumerate() {
+            result[1 + i] = step_flag * is_binary(bit);
+        }
+
+        // the least significant bit is a selector for the branch taken at this step:
+        // next = 3 * value + 1 when the value is odd, and next = value / 2 otherwise; the
+        // result of the division is obtained by dropping the least significant bit
+        let is_odd = bits[0];
+        let odd_branch = E::from(3u8) * value + E::ONE;
+        let even_branch = combine_bits(&bits[1..]);
+        let expected = is_odd * odd_branch + not(is_odd) * even_branch;
+        result[NUM_BITS + 1] = step_flag * are_equal(next[VALUE], expected);
+
+        // the trajectory must not reach 1 before the last step; (value - 1) has an inverse
+        // only if the value is not 1
+        result[NUM_BITS + 2] = step_flag * are_equal((value - E::ONE) * current[INV], E::ONE);
+    }
+}
+
+// TRACE GENERATOR
+// ================================================================================================
+
+pub fn build_trace(start: u64, num_steps: usize) -> ExecutionTrace<BaseElement> {
+    let trace_length = get_trace_length(num_steps);
+    let mut trace = ExecutionTrace::new(TRACE_WIDTH, trace_length);
+
+    // record the trajectory up to the step preceding 1
+    let mut value = start;
+    let mut row = vec![BaseElement::ZERO; TRACE_WIDTH];
+    for step in 0..num_steps {
+        let element = BaseElement::from(value);
+        row[VALUE] = element;
+        row[INV] = (element - BaseElement::ONE).inv();
+        for (i, bit) in row[BITS..].iter_mut().enumerate() {
+            *bit = BaseElement::from((value >> i) & 1);
+        }
+        trace.update_row(step, &row);
+        value = apply_step(value);
+    }
+
+    // fill the remaining rows with random values, except for the value 1 which terminates the
+    // trajectory; the constraints are not enforced on these rows, and random values ensure that
+    // constraint degrees are stable regardless of how the trajectory is padded
+    let num_random_rows = trace_length - num_steps;
+    let random_values = BaseElement::prng_vector([5; 32], num_random_rows * TRACE_WIDTH);
+    for (i, row) in random_values.chunks(TRACE_WIDTH).enumerate() {
+        trace.update_row(num_steps + i, row);
+    }
+    trace.set(VALUE, num_steps, BaseElement::ONE);
+
+    trace
+}
+
+/// Returns the length of the execution trace needed to prove a trajectory with the specified
+/// number of steps; the trace must have a row for each value of the trajectory, including 1.
+pub fn get_trace_length(num_steps: usize) -> usize {
+    let trace_length = (num_steps + 1).next_power_of_two();
+    trace_length.max(ComputationContext::MIN_TRACE_LENGTH)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the next value of a Collatz trajectory.
+///
+/// # Panics
+/// Panics if the next value does not fit into [NUM_BITS] bits.
+pub fn apply_step(value: u64) -> u64 {
+    let next = if value & 1 == 0 {
+        value / 2
+    } else {
+        3 * value + 1
+    };
+    assert!(
+        next < 1 << NUM_BITS,
+        "trajectory value {} does not fit into {} bits",
+        next,
+        NUM_BITS
+    );
+    next
+}
+
+/// Combines binary decomposition of a value (least significant bit first) into the value.
+fn combine_bits<E: FieldElement>(bits: &[E]) -> E {
+    bits.iter()
+        .rev()
+        .fold(E::ZERO, |acc, &bit| acc * E::from(2u8) + bit)
+}
@@ -0,0 +1,116 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Example, ExampleOptions};
+use log::debug;
+use prover::{self, math::utils::log2_unchecked, ProofOptions, StarkProof};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{apply_step, build_trace, CollatzAir, PublicInputs};
+
+#[cfg(test)]
+mod tests;
+
+// COLLATZ EXAMPLE
+// ================================================================================================
+
+pub fn get_example(options: ExampleOptions, start: u64) -> Box<dyn Example> {
+    Box::new(CollatzExample::new(start, options.to_proof_options(28, 16)))
+}
+
+/// Proves that the Collatz trajectory of a public starting value reaches 1 after a given number
+/// of steps, and not earlier. Whether a step halves the value or computes 3 * value + 1 depends
+/// on the data: the least significant bit of the value is used as a selector between the two
+/// branches, and the steps after the trajectory reaches 1 are padding excluded by a mask.
+pub struct CollatzExample {
+    options: ProofOptions,
+    start: u64,
+    num_steps: usize,
+}
+
+impl CollatzExample {
+    pub fn new(start: u64, options: ProofOptions) -> CollatzExample {
+        assert!(start > 1, "starting value must be greater than 1");
+
+        // compute the length of the trajectory
+        let now = Instant::now();
+        let num_steps = get_trajectory_length(start);
+        debug!(
+            "Computed Collatz trajectory of {} in {} steps in {} ms",
+            start,
+            num_steps,
+            now.elapsed().as_millis()
+        );
+
+        CollatzExample {
+            options,
+            start,
+            num_steps,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for CollatzExample {
+    fn prove(&self) -> StarkProof {
+        debug!(
+            "Generating proof for Collatz trajectory of {} in {} steps\n\
+            ---------------------",
+            self.start, self.num_steps
+        );
+
+        // generate execution trace
+        let now = Instant::now();
+        let trace = build_trace(self.start, self.num_steps);
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            log2_unchecked(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            num_steps: self.num_steps,
+        };
+        prover::prove::<CollatzAir>(trace, pub_inputs, self.options.clone()).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            num_steps: self.num_steps,
+        };
+        verifier::verify::<CollatzAir>(proof, pub_inputs)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            num_steps: self.num_steps - 1,
+        };
+        verifier::verify::<CollatzAir>(proof, pub_inputs)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of steps it takes for the Collatz trajectory of `start` to reach 1.
+fn get_trajectory_length(start: u64) -> usize {
+    let mut value = start;
+    let mut num_steps = 0;
+    while value != 1 {
+        value = apply_step(value);
+        num_steps += 1;
+    }
+    num_steps
+}
@@ -0,0 +1,69 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::Example;
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    FieldExtension, HashFunction, ProofOptions,
+};
+
+#[test]
+fn collatz_test_basic_proof_verification() {
+    let collatz = Box::new(super::CollatzExample::new(27, build_options(false)));
+    crate::tests::test_basic_proof_verification(collatz);
+}
+
+#[test]
+fn collatz_test_basic_proof_verification_extension() {
+    let collatz = Box::new(super::CollatzExample::new(27, build_options(true)));
+    crate::tests::test_basic_proof_verification(collatz);
+}
+
+#[test]
+fn collatz_test_basic_proof_verification_fail() {
+    let collatz = Box::new(super::CollatzExample::new(27, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(collatz);
+}
+
+#[test]
+fn collatz_test_short_trajectory() {
+    // trajectory of 3 is 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1, which fits into the minimum
+    // trace length without padding
+    assert_eq!(7, super::get_trajectory_length(3));
+    assert_eq!(8, super::air::get_trace_length(7));
+    let collatz = Box::new(super::CollatzExample::new(3, build_options(false)));
+    crate::tests::test_basic_proof_verification(collatz);
+}
+
+#[test]
+fn collatz_test_local_registers_omitted_from_ood_frame() {
+    // only the value register is evaluated at z * g
+    let collatz = super::CollatzExample::new(27, build_options(false));
+    let proof = collatz.prove();
+    assert_eq!(
+        super::air::TRACE_WIDTH * BaseElement::ELEMENT_BYTES,
+        proof.ood_frame.trace_at_z1.len()
+    );
+    assert_eq!(
+        BaseElement::ELEMENT_BYTES,
+        proof.ood_frame.trace_at_z2.len()
+    );
+}
+
+#[test]
+fn collatz_test_trajectory_length() {
+    assert_eq!(1, super::get_trajectory_length(2));
+    assert_eq!(111, super::get_trajectory_length(27));
+    assert_eq!(128, super::air::get_trace_length(111));
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, extension)
+}
@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use super::super::utils::PublicInputs;
 use crate::utils::are_equal;
 use prover::{
     math::field::{f128::BaseElement, FieldElement},
@@ -17,16 +18,17 @@ const TRACE_WIDTH: usize = 2;
 
 pub struct FibAir {
     context: ComputationContext,
+    start: [BaseElement; 2],
     result: BaseElement,
 }
 
 impl Air for FibAir {
     type BaseElement = BaseElement;
-    type PublicInputs = BaseElement;
+    type PublicInputs = PublicInputs;
 
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseElement, options: ProofOptions) -> Self {
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
         let degrees = vec![
             TransitionConstraintDegree::new(1),
             TransitionConstraintDegree::new(1),
@@ -34,7 +36,8 @@ impl Air for FibAir {
         let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options);
         FibAir {
             context,
-            result: pub_inputs,
+            start: pub_inputs.start,
+            result: pub_inputs.result,
         }
     }
 
@@ -43,12 +46,12 @@ impl Air for FibAir {
     }
 
     fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
-        // a valid Fibonacci sequence should start with two ones and terminate with
-        // the expected result
+        // a valid Fibonacci sequence should start with the two public starting terms and
+        // terminate with the expected result
         let last_step = self.trace_length() - 1;
         vec![
-            Assertion::single(0, 0, Self::BaseElement::ONE),
-            Assertion::single(1, 0, Self::BaseElement::ONE),
+            Assertion::single(0, 0, self.start[0]),
+            Assertion::single(1, 0, self.start[1]),
             Assertion::single(1, last_step, self.result),
         ]
     }
@@ -75,7 +78,7 @@ impl Air for FibAir {
 
 // FIBONACCI TRACE BUILDER
 // ================================================================================================
-pub fn build_trace(sequence_length: usize) -> ExecutionTrace<BaseElement> {
+pub fn build_trace(sequence_length: usize, start: [BaseElement; 2]) -> ExecutionTrace<BaseElement> {
     assert!(
         sequence_length.is_power_of_two(),
         "sequence length must be a power of 2"
@@ -84,8 +87,8 @@ pub fn build_trace(sequence_length: usize) -> ExecutionTrace<BaseElement> {
     let mut trace = ExecutionTrace::new(TRACE_WIDTH, sequence_length / 2);
     trace.fill(
         |state| {
-            state[0] = BaseElement::ONE;
-            state[1] = BaseElement::ONE;
+            state[0] = start[0];
+            state[1] = start[1];
         },
         |_, state| {
             state[0] += state[1];
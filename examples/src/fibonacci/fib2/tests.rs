@@ -3,7 +3,12 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::super::utils::build_proof_options;
+use super::super::utils::{build_proof_options, compute_fib_term, PublicInputs};
+use crate::Example;
+use prover::{
+    math::field::f128::BaseElement,
+    testing::{check_air, rand::Rng, AirStrategy},
+};
 
 #[test]
 fn fib2_test_basic_proof_verification() {
@@ -17,8 +22,54 @@ fn fib2_test_basic_proof_verification_extension() {
     crate::tests::test_basic_proof_verification(fib);
 }
 
+#[test]
+fn fib2_test_proof_text_encoding() {
+    let fib = Box::new(super::FibExample::new(16, build_proof_options(false)));
+    crate::tests::test_proof_text_encoding(fib);
+}
+
 #[test]
 fn fib2_test_basic_proof_verification_fail() {
     let fib = Box::new(super::FibExample::new(16, build_proof_options(false)));
     crate::tests::test_basic_proof_verification_fail(fib);
 }
+
+#[test]
+fn fib2_test_custom_start_proof_verification() {
+    let start = [BaseElement::new(3), BaseElement::new(7)];
+    let options = build_proof_options(false);
+    let fib = Box::new(super::FibExample::with_start(16, start, options));
+    crate::tests::test_basic_proof_verification(fib);
+}
+
+#[test]
+fn fib2_test_custom_start_proof_verification_fail() {
+    // a proof generated for one pair of starting terms must not verify against another pair
+    let start = [BaseElement::new(3), BaseElement::new(7)];
+    let fib = super::FibExample::with_start(16, start, build_proof_options(false));
+    let proof = fib.prove();
+    let pub_inputs = PublicInputs {
+        start: [BaseElement::new(7), BaseElement::new(3)],
+        result: fib.result,
+    };
+    assert!(verifier::verify::<super::FibAir>(proof, pub_inputs).is_err());
+}
+
+#[test]
+fn fib2_test_check_air() {
+    let strategy = AirStrategy::<super::FibAir>::new(build_proof_options(false), |rng| {
+        let start = [
+            BaseElement::new(rng.gen_range(0..u64::MAX as u128)),
+            BaseElement::new(rng.gen_range(0..u64::MAX as u128)),
+        ];
+        let sequence_length = 1 << rng.gen_range(4..7);
+        let pub_inputs = PublicInputs {
+            start,
+            result: compute_fib_term(sequence_length, start),
+        };
+        (super::build_trace(sequence_length, start), pub_inputs)
+    })
+    .with_num_cases(4)
+    .with_seed(42);
+    check_air(&strategy);
+}
@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use super::super::utils::{compute_fib_term, PublicInputs};
 use crate::utils::are_equal;
 use prover::{
     math::field::{f128::BaseElement, FieldElement},
@@ -17,16 +18,17 @@ const TRACE_WIDTH: usize = 2;
 
 pub struct Fib8Air {
     context: ComputationContext,
+    start: [BaseElement; 2],
     result: BaseElement,
 }
 
 impl Air for Fib8Air {
     type BaseElement = BaseElement;
-    type PublicInputs = BaseElement;
+    type PublicInputs = PublicInputs;
 
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseElement, options: ProofOptions) -> Self {
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
         let degrees = vec![
             TransitionConstraintDegree::new(1),
             TransitionConstraintDegree::new(1),
@@ -34,7 +36,8 @@ impl Air for Fib8Air {
         let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options);
         Fib8Air {
             context,
-            result: pub_inputs,
+            start: pub_inputs.start,
+            result: pub_inputs.result,
         }
     }
 
@@ -43,12 +46,13 @@ impl Air for Fib8Air {
     }
 
     fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
-        // assert that the trace starts with 7th and 8th terms of Fibonacci sequence (the first
-        // 6 terms are not recorded in the trace), and ends with the expected result
+        // assert that the trace starts with 7th and 8th terms of Fibonacci sequence defined by
+        // the public starting terms (the first 6 terms are not recorded in the trace), and ends
+        // with the expected result
         let last_step = self.trace_length() - 1;
         vec![
-            Assertion::single(0, 0, BaseElement::new(13)),
-            Assertion::single(1, 0, BaseElement::new(21)),
+            Assertion::single(0, 0, compute_fib_term(7, self.start)),
+            Assertion::single(1, 0, compute_fib_term(8, self.start)),
             Assertion::single(1, last_step, self.result),
         ]
     }
@@ -83,15 +87,15 @@ impl Air for Fib8Air {
 // FIBONACCI TRACE BUILDER
 // ================================================================================================
 
-pub fn build_trace(length: usize) -> ExecutionTrace<BaseElement> {
+pub fn build_trace(length: usize, start: [BaseElement; 2]) -> ExecutionTrace<BaseElement> {
     assert!(
         length.is_power_of_two(),
         "sequence length must be a power of 2"
     );
 
     // initialize the trace with 7th and 8th terms of Fibonacci sequence (skipping the first 6)
-    let n0 = BaseElement::ONE;
-    let n1 = BaseElement::ONE;
+    let n0 = start[0];
+    let n1 = start[1];
     let n2 = n0 + n1;
     let n3 = n1 + n2;
     let n4 = n2 + n3;
@@ -3,14 +3,14 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::utils::compute_fib_term;
+use super::utils::{compute_fib_term, parse_start, PublicInputs, FIB_START};
 use crate::{Example, ExampleOptions};
 use log::debug;
 use prover::{
     self,
     math::{
         field::{f128::BaseElement, FieldElement},
-        utils::log2,
+        utils::log2_unchecked,
     },
     ProofOptions, StarkProof,
 };
@@ -26,9 +26,14 @@ mod tests;
 // FIBONACCI EXAMPLE
 // ================================================================================================
 
-pub fn get_example(options: ExampleOptions, sequence_length: usize) -> Box<dyn Example> {
-    Box::new(Fib8Example::new(
+pub fn get_example(
+    options: ExampleOptions,
+    sequence_length: usize,
+    start: &[u128],
+) -> Box<dyn Example> {
+    Box::new(Fib8Example::with_start(
         sequence_length,
+        parse_start(start, FIB_START),
         options.to_proof_options(28, 16),
     ))
 }
@@ -36,11 +41,20 @@ pub fn get_example(options: ExampleOptions, sequence_length: usize) -> Box<dyn E
 pub struct Fib8Example {
     options: ProofOptions,
     sequence_length: usize,
+    start: [BaseElement; 2],
     result: BaseElement,
 }
 
 impl Fib8Example {
     pub fn new(sequence_length: usize, options: ProofOptions) -> Fib8Example {
+        Self::with_start(sequence_length, FIB_START, options)
+    }
+
+    pub fn with_start(
+        sequence_length: usize,
+        start: [BaseElement; 2],
+        options: ProofOptions,
+    ) -> Fib8Example {
         assert!(
             sequence_length.is_power_of_two(),
             "sequence length must be a power of 2"
@@ -48,7 +62,7 @@ impl Fib8Example {
 
         // compute Fibonacci sequence
         let now = Instant::now();
-        let result = compute_fib_term(sequence_length);
+        let result = compute_fib_term(sequence_length, start);
         debug!(
             "Computed Fibonacci sequence up to {}th term in {} ms",
             sequence_length,
@@ -58,6 +72,7 @@ impl Fib8Example {
         Fib8Example {
             options,
             sequence_length,
+            start,
             result,
         }
     }
@@ -76,25 +91,37 @@ impl Example for Fib8Example {
 
         // generate execution trace
         let now = Instant::now();
-        let trace = build_trace(self.sequence_length);
+        let trace = build_trace(self.sequence_length, self.start);
         let trace_width = trace.width();
         let trace_length = trace.len();
         debug!(
             "Generated execution trace of {} registers and 2^{} steps in {} ms",
             trace_width,
-            log2(trace_length),
+            log2_unchecked(trace_length),
             now.elapsed().as_millis()
         );
 
         // generate the proof
-        prover::prove::<Fib8Air>(trace, self.result, self.options.clone()).unwrap()
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result,
+        };
+        prover::prove::<Fib8Air>(trace, pub_inputs, self.options.clone()).unwrap()
     }
 
     fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
-        verifier::verify::<Fib8Air>(proof, self.result)
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result,
+        };
+        verifier::verify::<Fib8Air>(proof, pub_inputs)
     }
 
     fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
-        verifier::verify::<Fib8Air>(proof, self.result + BaseElement::ONE)
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result + BaseElement::ONE,
+        };
+        verifier::verify::<Fib8Air>(proof, pub_inputs)
     }
 }
@@ -3,7 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::super::utils::build_proof_options;
+use super::super::utils::{build_proof_options, PublicInputs};
+use crate::Example;
+use prover::math::field::f128::BaseElement;
 
 #[test]
 fn fib8_test_basic_proof_verification() {
@@ -22,3 +24,24 @@ fn fib8_test_basic_proof_verification_fail() {
     let fib = Box::new(super::Fib8Example::new(64, build_proof_options(false)));
     crate::tests::test_basic_proof_verification_fail(fib);
 }
+
+#[test]
+fn fib8_test_custom_start_proof_verification() {
+    let start = [BaseElement::new(3), BaseElement::new(7)];
+    let options = build_proof_options(false);
+    let fib = Box::new(super::Fib8Example::with_start(64, start, options));
+    crate::tests::test_basic_proof_verification(fib);
+}
+
+#[test]
+fn fib8_test_custom_start_proof_verification_fail() {
+    // a proof generated for one pair of starting terms must not verify against another pair
+    let start = [BaseElement::new(3), BaseElement::new(7)];
+    let fib = super::Fib8Example::with_start(64, start, build_proof_options(false));
+    let proof = fib.prove();
+    let pub_inputs = PublicInputs {
+        start: [BaseElement::new(7), BaseElement::new(3)],
+        result: fib.result,
+    };
+    assert!(verifier::verify::<super::Fib8Air>(proof, pub_inputs).is_err());
+}
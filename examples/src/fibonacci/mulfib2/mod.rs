@@ -3,14 +3,14 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::utils::compute_mulfib_term;
+use super::utils::{compute_mulfib_term, parse_start, PublicInputs, MULFIB_START};
 use crate::{Example, ExampleOptions};
 use log::debug;
 use prover::{
     self,
     math::{
         field::{f128::BaseElement, FieldElement},
-        utils::log2,
+        utils::log2_unchecked,
     },
     ProofOptions, StarkProof,
 };
@@ -26,20 +26,34 @@ mod tests;
 // FIBONACCI EXAMPLE
 // ================================================================================================
 
-pub fn get_example(options: ExampleOptions, sequence_length: usize) -> Box<dyn Example> {
-    Box::new(MulFib2Example::new(
+pub fn get_example(
+    options: ExampleOptions,
+    sequence_length: usize,
+    start: &[u128],
+) -> Box<dyn Example> {
+    Box::new(MulFib2Example::with_start(
         sequence_length,
+        parse_start(start, MULFIB_START),
         options.to_proof_options(28, 16),
     ))
 }
 pub struct MulFib2Example {
     options: ProofOptions,
     sequence_length: usize,
+    start: [BaseElement; 2],
     result: BaseElement,
 }
 
 impl MulFib2Example {
     pub fn new(sequence_length: usize, options: ProofOptions) -> MulFib2Example {
+        Self::with_start(sequence_length, MULFIB_START, options)
+    }
+
+    pub fn with_start(
+        sequence_length: usize,
+        start: [BaseElement; 2],
+        options: ProofOptions,
+    ) -> MulFib2Example {
         assert!(
             sequence_length.is_power_of_two(),
             "sequence length must be a power of 2"
@@ -47,7 +61,8 @@ impl MulFib2Example {
 
         // compute Fibonacci sequence
         let now = Instant::now();
-        let result = compute_mulfib_term(sequence_length);
+        // the sequence terminates at the second to last term recorded in the trace
+        let result = compute_mulfib_term(sequence_length - 1, start);
         debug!(
             "Computed multiplicative Fibonacci sequence up to {}th term in {} ms",
             sequence_length,
@@ -57,6 +72,7 @@ impl MulFib2Example {
         MulFib2Example {
             options,
             sequence_length,
+            start,
             result,
         }
     }
@@ -76,25 +92,37 @@ impl Example for MulFib2Example {
 
         // generate execution trace
         let now = Instant::now();
-        let trace = build_trace(sequence_length);
+        let trace = build_trace(sequence_length, self.start);
         let trace_width = trace.width();
         let trace_length = trace.len();
         debug!(
             "Generated execution trace of {} registers and 2^{} steps in {} ms",
             trace_width,
-            log2(trace_length),
+            log2_unchecked(trace_length),
             now.elapsed().as_millis()
         );
 
         // generate the proof
-        prover::prove::<MulFib2Air>(trace, self.result, self.options.clone()).unwrap()
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result,
+        };
+        prover::prove::<MulFib2Air>(trace, pub_inputs, self.options.clone()).unwrap()
     }
 
     fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
-        verifier::verify::<MulFib2Air>(proof, self.result)
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result,
+        };
+        verifier::verify::<MulFib2Air>(proof, pub_inputs)
     }
 
     fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
-        verifier::verify::<MulFib2Air>(proof, self.result + BaseElement::ONE)
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result + BaseElement::ONE,
+        };
+        verifier::verify::<MulFib2Air>(proof, pub_inputs)
     }
 }
@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use super::super::utils::PublicInputs;
 use crate::utils::are_equal;
 use prover::{
     math::field::{f128::BaseElement, FieldElement},
@@ -17,16 +18,17 @@ const TRACE_WIDTH: usize = 8;
 
 pub struct MulFib8Air {
     context: ComputationContext,
+    start: [BaseElement; 2],
     result: BaseElement,
 }
 
 impl Air for MulFib8Air {
     type BaseElement = BaseElement;
-    type PublicInputs = BaseElement;
+    type PublicInputs = PublicInputs;
 
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseElement, options: ProofOptions) -> Self {
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
         let degrees = vec![
             TransitionConstraintDegree::new(2),
             TransitionConstraintDegree::new(2),
@@ -40,7 +42,8 @@ impl Air for MulFib8Air {
         let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options);
         MulFib8Air {
             context,
-            result: pub_inputs,
+            start: pub_inputs.start,
+            result: pub_inputs.result,
         }
     }
 
@@ -49,12 +52,12 @@ impl Air for MulFib8Air {
     }
 
     fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
-        // a valid multiplicative Fibonacci sequence should start with 1, 2 and terminate
-        // with the expected result
+        // a valid multiplicative Fibonacci sequence should start with the two public starting
+        // terms and terminate with the expected result
         let last_step = self.trace_length() - 1;
         vec![
-            Assertion::single(0, 0, BaseElement::new(1)),
-            Assertion::single(1, 0, BaseElement::new(2)),
+            Assertion::single(0, 0, self.start[0]),
+            Assertion::single(1, 0, self.start[1]),
             Assertion::single(6, last_step, self.result),
         ]
     }
@@ -94,14 +97,14 @@ impl Air for MulFib8Air {
 // FIBONACCI TRACE BUILDER
 // ================================================================================================
 
-pub fn build_trace(length: usize) -> ExecutionTrace<BaseElement> {
+pub fn build_trace(length: usize, start: [BaseElement; 2]) -> ExecutionTrace<BaseElement> {
     assert!(
         length.is_power_of_two(),
         "sequence length must be a power of 2"
     );
 
-    let mut reg0 = vec![BaseElement::new(1)];
-    let mut reg1 = vec![BaseElement::new(2)];
+    let mut reg0 = vec![start[0]];
+    let mut reg1 = vec![start[1]];
     let mut reg2 = vec![reg0[0] * reg1[0]];
     let mut reg3 = vec![reg1[0] * reg2[0]];
     let mut reg4 = vec![reg2[0] * reg3[0]];
@@ -3,14 +3,14 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::utils::compute_mulfib_term;
+use super::utils::{compute_mulfib_term, parse_start, PublicInputs, MULFIB_START};
 use crate::{Example, ExampleOptions};
 use log::debug;
 use prover::{
     self,
     math::{
         field::{f128::BaseElement, FieldElement},
-        utils::log2,
+        utils::log2_unchecked,
     },
     ProofOptions, StarkProof,
 };
@@ -26,9 +26,14 @@ mod tests;
 // FIBONACCI EXAMPLE
 // ================================================================================================
 
-pub fn get_example(options: ExampleOptions, sequence_length: usize) -> Box<dyn Example> {
-    Box::new(MulFib8Example::new(
+pub fn get_example(
+    options: ExampleOptions,
+    sequence_length: usize,
+    start: &[u128],
+) -> Box<dyn Example> {
+    Box::new(MulFib8Example::with_start(
         sequence_length,
+        parse_start(start, MULFIB_START),
         options.to_proof_options(28, 16),
     ))
 }
@@ -36,11 +41,20 @@ pub fn get_example(options: ExampleOptions, sequence_length: usize) -> Box<dyn E
 pub struct MulFib8Example {
     options: ProofOptions,
     sequence_length: usize,
+    start: [BaseElement; 2],
     result: BaseElement,
 }
 
 impl MulFib8Example {
     pub fn new(sequence_length: usize, options: ProofOptions) -> MulFib8Example {
+        Self::with_start(sequence_length, MULFIB_START, options)
+    }
+
+    pub fn with_start(
+        sequence_length: usize,
+        start: [BaseElement; 2],
+        options: ProofOptions,
+    ) -> MulFib8Example {
         assert!(
             sequence_length.is_power_of_two(),
             "sequence length must be a power of 2"
@@ -48,7 +62,8 @@ impl MulFib8Example {
 
         // compute Fibonacci sequence
         let now = Instant::now();
-        let result = compute_mulfib_term(sequence_length);
+        // the sequence terminates at the second to last term recorded in the trace
+        let result = compute_mulfib_term(sequence_length - 1, start);
         debug!(
             "Computed multiplicative Fibonacci sequence up to {}th term in {} ms",
             sequence_length,
@@ -58,6 +73,7 @@ impl MulFib8Example {
         MulFib8Example {
             options,
             sequence_length,
+            start,
             result,
         }
     }
@@ -77,25 +93,37 @@ impl Example for MulFib8Example {
 
         // generate execution trace
         let now = Instant::now();
-        let trace = build_trace(sequence_length);
+        let trace = build_trace(sequence_length, self.start);
         let trace_width = trace.width();
         let trace_length = trace.len();
         debug!(
             "Generated execution trace of {} registers and 2^{} steps in {} ms",
             trace_width,
-            log2(trace_length),
+            log2_unchecked(trace_length),
             now.elapsed().as_millis()
         );
 
         // generate the proof
-        prover::prove::<MulFib8Air>(trace, self.result, self.options.clone()).unwrap()
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result,
+        };
+        prover::prove::<MulFib8Air>(trace, pub_inputs, self.options.clone()).unwrap()
     }
 
     fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
-        verifier::verify::<MulFib8Air>(proof, self.result)
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result,
+        };
+        verifier::verify::<MulFib8Air>(proof, pub_inputs)
     }
 
     fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
-        verifier::verify::<MulFib8Air>(proof, self.result + BaseElement::ONE)
+        let pub_inputs = PublicInputs {
+            start: self.start,
+            result: self.result + BaseElement::ONE,
+        };
+        verifier::verify::<MulFib8Air>(proof, pub_inputs)
     }
 }
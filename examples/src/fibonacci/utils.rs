@@ -3,11 +3,40 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use prover::math::field::{f128::BaseElement, FieldElement};
+use prover::{math::field::f128::BaseElement, ByteWriter, Serializable};
 
-pub fn compute_fib_term(n: usize) -> BaseElement {
-    let mut t0 = BaseElement::ONE;
-    let mut t1 = BaseElement::ONE;
+// PUBLIC INPUTS
+// ================================================================================================
+
+/// Public inputs shared by all Fibonacci examples: the first two terms of the sequence and the
+/// expected value of the term at which the sequence terminates.
+#[derive(Clone)]
+pub struct PublicInputs {
+    pub start: [BaseElement; 2],
+    pub result: BaseElement,
+}
+
+impl Serializable for PublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        BaseElement::write_batch_into(&self.start, target);
+        target.write(&self.result);
+    }
+}
+
+/// Default starting terms of the additive Fibonacci sequence.
+pub const FIB_START: [BaseElement; 2] = [BaseElement::new(1), BaseElement::new(1)];
+
+/// Default starting terms of the multiplicative Fibonacci sequence.
+pub const MULFIB_START: [BaseElement; 2] = [BaseElement::new(1), BaseElement::new(2)];
+
+// SEQUENCE COMPUTATIONS
+// ================================================================================================
+
+/// Returns the n-th term of the Fibonacci sequence starting with the specified two terms.
+pub fn compute_fib_term(n: usize, start: [BaseElement; 2]) -> BaseElement {
+    // t0 holds the most recent term, and t1 holds the term preceding it
+    let mut t0 = start[1];
+    let mut t1 = start[0];
 
     for _ in 0..(n - 1) {
         t1 = t0 + t1;
@@ -17,9 +46,12 @@ pub fn compute_fib_term(n: usize) -> BaseElement {
     t1
 }
 
-pub fn compute_mulfib_term(n: usize) -> BaseElement {
-    let mut t0 = BaseElement::ONE;
-    let mut t1 = BaseElement::new(2);
+/// Returns the n-th term of the multiplicative Fibonacci sequence starting with the specified
+/// two terms.
+pub fn compute_mulfib_term(n: usize, start: [BaseElement; 2]) -> BaseElement {
+    // t0 holds the most recent term, and t1 holds the term preceding it
+    let mut t0 = start[1];
+    let mut t1 = start[0];
 
     for _ in 0..(n - 1) {
         t1 = t0 * t1;
@@ -29,6 +61,23 @@ pub fn compute_mulfib_term(n: usize) -> BaseElement {
     t1
 }
 
+/// Converts starting terms provided via command line into field elements; if no terms were
+/// provided, the `default` terms are used.
+pub fn parse_start(start: &[u128], default: [BaseElement; 2]) -> [BaseElement; 2] {
+    if start.is_empty() {
+        return default;
+    }
+    assert!(
+        start.len() == 2,
+        "expected exactly two starting terms, but received {}",
+        start.len()
+    );
+    [BaseElement::new(start[0]), BaseElement::new(start[1])]
+}
+
+// TEST HELPERS
+// ================================================================================================
+
 #[cfg(test)]
 pub fn build_proof_options(use_extension_field: bool) -> prover::ProofOptions {
     use prover::{FieldExtension, HashFunction, ProofOptions};
@@ -0,0 +1,366 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use std::{fs, path::PathBuf, process};
+use structopt::StructOpt;
+use verifier::{CoefficientScheme, Deserializable, HashFunction, Serializable, StarkProof};
+
+// PROOF INSPECTOR
+// ================================================================================================
+
+#[derive(StructOpt, Debug)]
+#[structopt(
+    name = "proof-inspect",
+    about = "Prints the structure of a serialized Winterfell proof"
+)]
+struct InspectOptions {
+    /// Path to a serialized proof
+    #[structopt(parse(from_os_str))]
+    proof: PathBuf,
+
+    /// Path to another proof; when provided, only the entries which differ between the two
+    /// proofs are printed
+    #[structopt(short = "d", long = "diff", parse(from_os_str))]
+    other: Option<PathBuf>,
+}
+
+fn main() {
+    let options = InspectOptions::from_args();
+
+    let report = build_report(&read_proof(&options.proof));
+    match options.other {
+        None => {
+            for (label, value) in report.iter() {
+                println!("{:<36} {}", label, value);
+            }
+        }
+        Some(other) => {
+            let other_report = build_report(&read_proof(&other));
+            let mut num_diffs = 0;
+            for (label, value, other_value) in diff_reports(&report, &other_report) {
+                println!("{:<36} {} | {}", label, value, other_value);
+                num_diffs += 1;
+            }
+            println!("{} entries differ", num_diffs);
+            if num_diffs > 0 {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+// REPORT BUILDER
+// ================================================================================================
+
+/// A list of (label, value) pairs describing a proof in the order in which they are printed.
+type Report = Vec<(String, String)>;
+
+/// A list of (label, value, other value) triplets for entries which differ between two reports.
+type Diff = Vec<(String, String, String)>;
+
+fn build_report(proof: &StarkProof) -> Report {
+    let mut report = Report::new();
+    let context = &proof.context;
+    let options = proof.options();
+
+    // context
+    let element_size = context.field_modulus_bytes.len();
+    let extension_factor = options.field_extension().degree();
+    add(&mut report, "context.version", proof.version());
+    add(
+        &mut report,
+        "context.field_modulus",
+        format!("0x{}", to_be_hex(&context.field_modulus_bytes)),
+    );
+    add(&mut report, "context.field_element_bytes", element_size);
+    add(&mut report, "context.trace_length", proof.trace_length());
+    add(&mut report, "context.trace_width", get_trace_width(proof));
+    add(
+        &mut report,
+        "context.num_constraints",
+        context.num_constraints,
+    );
+    add(
+        &mut report,
+        "context.lde_domain_size",
+        1usize << context.lde_domain_depth,
+    );
+    add(
+        &mut report,
+        "context.ce_blowup_factor",
+        context.ce_blowup_factor,
+    );
+    let air_id = match context.air_id {
+        Some(air_id) => hex::encode(air_id),
+        None => "none".to_string(),
+    };
+    add(&mut report, "context.air_id", air_id);
+
+    // proof options
+    add(&mut report, "options.num_queries", options.num_queries());
+    add(
+        &mut report,
+        "options.num_fri_queries",
+        options.num_fri_queries(),
+    );
+    add(
+        &mut report,
+        "options.blowup_factor",
+        options.blowup_factor(),
+    );
+    add(
+        &mut report,
+        "options.grinding_factor",
+        options.grinding_factor(),
+    );
+    let grinding_budget = options.grinding_budget().map_or_else(
+        || "none".to_string(),
+        |budget| format!("{} ms", budget.as_millis()),
+    );
+    add(&mut report, "options.grinding_budget", grinding_budget);
+    let hash_fn = match options.hash_fn() {
+        HashFunction::Blake3_256 => "blake3_256",
+        HashFunction::Sha3_256 => "sha3_256",
+    };
+    add(&mut report, "options.hash_fn", hash_fn);
+    let transcript_hash_fn = match options.transcript_hash_fn() {
+        HashFunction::Blake3_256 => "blake3_256",
+        HashFunction::Sha3_256 => "sha3_256",
+    };
+    add(
+        &mut report,
+        "options.transcript_hash_fn",
+        transcript_hash_fn,
+    );
+    add(&mut report, "options.field_extension", extension_factor);
+    add(
+        &mut report,
+        "options.compact_trace_queries",
+        options.compact_trace_queries(),
+    );
+    let fri_max_remainder_degree = match options.fri_max_remainder_degree() {
+        Some(max_degree) => max_degree.to_string(),
+        None => "default".to_string(),
+    };
+    add(
+        &mut report,
+        "options.fri_max_remainder_degree",
+        fri_max_remainder_degree,
+    );
+    let coefficient_scheme = match options.coefficient_scheme() {
+        CoefficientScheme::Independent => "independent",
+        CoefficientScheme::Powers => "powers",
+    };
+    add(
+        &mut report,
+        "options.coefficient_scheme",
+        coefficient_scheme,
+    );
+
+    // component sizes
+    add(&mut report, "size.total", serialized_size(proof));
+    add(&mut report, "size.context", serialized_size(&proof.context));
+    add(
+        &mut report,
+        "size.commitments",
+        serialized_size(&proof.commitments),
+    );
+    add(
+        &mut report,
+        "size.trace_queries",
+        serialized_size(&proof.trace_queries),
+    );
+    add(
+        &mut report,
+        "size.constraint_queries",
+        serialized_size(&proof.constraint_queries),
+    );
+    if let Some(extra_queries) = &proof.extra_queries {
+        add(
+            &mut report,
+            "size.extra_queries",
+            serialized_size(extra_queries),
+        );
+    }
+    add(
+        &mut report,
+        "size.ood_frame",
+        serialized_size(&proof.ood_frame),
+    );
+    add(
+        &mut report,
+        "size.fri_proof",
+        serialized_size(&proof.fri_proof),
+    );
+
+    // commitments
+    add(
+        &mut report,
+        "commitments.trace_root",
+        hex::encode(proof.commitments.trace_root),
+    );
+    for (i, root) in proof.commitments.aux_roots.iter().enumerate() {
+        add(
+            &mut report,
+            &format!("commitments.aux_root[{}]", i),
+            hex::encode(root),
+        );
+    }
+    if let Some(root) = &proof.commitments.extra_root {
+        add(&mut report, "commitments.extra_root", hex::encode(root));
+    }
+    add(
+        &mut report,
+        "commitments.constraint_root",
+        hex::encode(proof.commitments.constraint_root),
+    );
+    for (i, root) in proof.commitments.fri_roots.iter().enumerate() {
+        add(
+            &mut report,
+            &format!("commitments.fri_root[{}]", i),
+            hex::encode(root),
+        );
+    }
+
+    // queries
+    let num_trace_values = match &proof.trace_queries.compact_values {
+        Some(compact) => compact.num_values as usize,
+        None => proof.trace_queries.values.len(),
+    };
+    add(&mut report, "trace_queries.num_values", num_trace_values);
+    if let Some(compact) = &proof.trace_queries.compact_values {
+        let num_constant_columns = compact
+            .constant_columns
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum::<usize>();
+        add(
+            &mut report,
+            "trace_queries.num_constant_columns",
+            num_constant_columns,
+        );
+    }
+    add(
+        &mut report,
+        "trace_queries.num_path_nodes",
+        count_nodes(&proof.trace_queries.paths),
+    );
+    add(
+        &mut report,
+        "constraint_queries.num_values",
+        proof.constraint_queries.values.len(),
+    );
+    add(
+        &mut report,
+        "constraint_queries.num_path_nodes",
+        count_nodes(&proof.constraint_queries.paths),
+    );
+
+    // FRI layers
+    let fri_proof = &proof.fri_proof;
+    add(&mut report, "fri.num_layers", fri_proof.layers.len());
+    add(&mut report, "fri.partitioned", fri_proof.partitioned);
+    for (i, layer) in fri_proof.layers.iter().enumerate() {
+        let value_bytes = layer.values.first().map_or(0, |v| v.len());
+        add(
+            &mut report,
+            &format!("fri.layer[{}]", i),
+            format!(
+                "depth: {}, queries: {}, value bytes: {}, path nodes: {}",
+                layer.depth,
+                layer.values.len(),
+                value_bytes,
+                count_nodes(&layer.paths)
+            ),
+        );
+    }
+    add(
+        &mut report,
+        "fri.remainder_length",
+        fri_proof.rem_values.len() / (element_size * extension_factor),
+    );
+
+    // security
+    add(
+        &mut report,
+        "security.conjectured_bits",
+        proof.security_level(true),
+    );
+    add(
+        &mut report,
+        "security.proven_bits",
+        proof.security_level(false),
+    );
+    add(&mut report, "pow_nonce", proof.pow_nonce);
+    add(&mut report, "pow_difficulty", proof.pow_difficulty);
+
+    report
+}
+
+/// Returns (label, value, other value) triplets for all entries which differ between the two
+/// reports; entries missing from one of the reports are shown as "-".
+fn diff_reports(report: &[(String, String)], other: &[(String, String)]) -> Diff {
+    let lookup = |report: &[(String, String)], label: &str| {
+        report
+            .iter()
+            .find(|(l, _)| l == label)
+            .map_or_else(|| "-".to_string(), |(_, v)| v.clone())
+    };
+
+    let mut result = Diff::new();
+    for (label, value) in report.iter() {
+        let other_value = lookup(other, label);
+        if *value != other_value {
+            result.push((label.clone(), value.clone(), other_value));
+        }
+    }
+    for (label, other_value) in other.iter() {
+        if !report.iter().any(|(l, _)| l == label) {
+            result.push((label.clone(), "-".to_string(), other_value.clone()));
+        }
+    }
+    result
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn read_proof(path: &PathBuf) -> StarkProof {
+    let bytes = fs::read(path).unwrap_or_else(|err| {
+        eprintln!("failed to read {}: {}", path.display(), err);
+        process::exit(2);
+    });
+    StarkProof::read_from_bytes(&bytes).unwrap_or_else(|err| {
+        eprintln!("failed to parse {}: {}", path.display(), err);
+        process::exit(2);
+    })
+}
+
+fn add<T: ToString>(report: &mut Report, label: &str, value: T) {
+    report.push((label.to_string(), value.to_string()));
+}
+
+fn serialized_size<T: Serializable>(value: &T) -> String {
+    format!("{} bytes", value.to_bytes().len())
+}
+
+/// Infers the number of registers in the execution trace from the out-of-domain frame; the
+/// frame contains trace states in the extension field if field extension is enabled.
+fn get_trace_width(proof: &StarkProof) -> usize {
+    let element_size = proof.context.field_modulus_bytes.len();
+    let extension_factor = proof.options().field_extension().degree();
+    proof.ood_frame.trace_at_z1.len() / (element_size * extension_factor)
+}
+
+fn count_nodes(paths: &[Vec<[u8; 32]>]) -> usize {
+    paths.iter().map(|p| p.len()).sum()
+}
+
+/// Encodes little-endian bytes as a big-endian hex string.
+fn to_be_hex(bytes: &[u8]) -> String {
+    let mut bytes = bytes.to_vec();
+    bytes.reverse();
+    hex::encode(bytes)
+}
@@ -3,13 +3,20 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use prover::{FieldExtension, HashFunction, ProofOptions, StarkProof};
+use prover::{CoefficientScheme, FieldExtension, HashFunction, ProofOptions, StarkProof};
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 use verifier::VerifierError;
 
+pub mod collatz;
 pub mod fibonacci;
+pub mod life;
 pub mod merkle;
+pub mod polyeval;
 pub mod rescue;
+pub mod rollup;
+pub mod sha256;
+pub mod sort;
 pub mod utils;
 
 #[cfg(test)]
@@ -37,6 +44,10 @@ pub struct ExampleOptions {
     #[structopt(short = "q", long = "queries")]
     num_queries: Option<usize>,
 
+    /// Number of FRI queries to include in a proof; must not be smaller than number of queries
+    #[structopt(long = "fri_queries")]
+    num_fri_queries: Option<usize>,
+
     /// Blowup factor for low degree extension
     #[structopt(short = "b", long = "blowup")]
     blowup_factor: Option<usize>,
@@ -45,32 +56,100 @@ pub struct ExampleOptions {
     #[structopt(short = "g", long = "grinding", default_value = "16")]
     grinding_factor: u32,
 
+    /// Wall-clock budget for proof-of-work in milliseconds; when set, the grinding factor is the
+    /// minimum difficulty of proof-of-work
+    #[structopt(long = "grinding_budget")]
+    grinding_budget_ms: Option<u64>,
+
     /// Whether to use field extension for composition polynomial
     #[structopt(short = "e", long = "extension")]
     field_extension: bool,
+
+    /// Whether to use cubic field extension for composition polynomial; takes precedence over
+    /// the quadratic extension enabled via the --extension flag
+    #[structopt(long = "cubic")]
+    cubic_extension: bool,
+
+    /// Whether to use quartic field extension for composition polynomial; takes precedence over
+    /// the quadratic and cubic extensions
+    #[structopt(long = "quartic")]
+    quartic_extension: bool,
+
+    /// Whether to include queried trace rows in a proof using compact encoding
+    #[structopt(long = "compact")]
+    compact_trace_queries: bool,
+
+    /// Degree of the folded polynomial at which FRI folding terminates; plus one must be a power
+    /// of two
+    #[structopt(long = "fri_remainder_degree")]
+    fri_max_remainder_degree: Option<usize>,
+
+    /// Whether to use powers of a single random element per constraint group as constraint
+    /// composition coefficients
+    #[structopt(long = "coeff_powers")]
+    coefficient_powers: bool,
+
+    /// Hash function used to build commitments and to draw pseudo-random values
+    #[structopt(long = "hash", default_value = "blake3", possible_values = &["blake3", "sha3"])]
+    hash_fn: String,
+
+    /// Hash function used to draw pseudo-random values; defaults to the hash function used to
+    /// build commitments
+    #[structopt(long = "transcript_hash", possible_values = &["blake3", "sha3"])]
+    transcript_hash_fn: Option<String>,
+
+    /// File to which the generated proof should be written
+    #[structopt(short = "o", long = "output", parse(from_os_str))]
+    pub proof_file: Option<PathBuf>,
 }
 
 impl ExampleOptions {
+    /// Returns proof options specified via the command line; `q` and `b` are the number of
+    /// queries and the blowup factor to use when these are not specified explicitly.
     pub fn to_proof_options(&self, q: usize, b: usize) -> ProofOptions {
         let num_queries = self.num_queries.unwrap_or(q);
         let blowup_factor = self.blowup_factor.unwrap_or(b);
-        let field_extension = if self.field_extension {
+        let field_extension = if self.quartic_extension {
+            FieldExtension::Quartic
+        } else if self.cubic_extension {
+            FieldExtension::Cubic
+        } else if self.field_extension {
             FieldExtension::Quadratic
         } else {
             FieldExtension::None
         };
+        let hash_fn = parse_hash_fn(&self.hash_fn);
 
-        ProofOptions::new(
+        let mut options = ProofOptions::new(
             num_queries,
             blowup_factor,
             self.grinding_factor,
-            HashFunction::Blake3_256,
+            hash_fn,
             field_extension,
-        )
+        );
+        if let Some(budget_ms) = self.grinding_budget_ms {
+            options = options.with_grinding_budget(Duration::from_millis(budget_ms));
+        }
+        if let Some(transcript_hash_fn) = &self.transcript_hash_fn {
+            options = options.with_transcript_hash_fn(parse_hash_fn(transcript_hash_fn));
+        }
+        if self.compact_trace_queries {
+            options = options.with_compact_trace_queries();
+        }
+        if let Some(max_degree) = self.fri_max_remainder_degree {
+            options = options.with_fri_max_remainder_degree(max_degree);
+        }
+        if self.coefficient_powers {
+            options = options.with_coefficient_scheme(CoefficientScheme::Powers);
+        }
+        match self.num_fri_queries {
+            Some(num_fri_queries) => options.with_num_fri_queries(num_fri_queries),
+            None => options,
+        }
     }
 }
 
-#[derive(StructOpt, Debug)]
+#[derive(StructOpt, Clone, Debug)]
 //#[structopt(about = "available examples")]
 pub enum ExampleType {
     /// Compute a Fibonacci sequence using trace table with 2 registers
@@ -78,24 +157,57 @@ pub enum ExampleType {
         /// Length of Fibonacci sequence; must be a power of two
         #[structopt(short = "n", default_value = "1048576")]
         sequence_length: usize,
+        /// First two terms of the sequence; defaults to the standard starting terms
+        #[structopt(short = "s", long = "start", number_of_values = 2)]
+        start: Vec<u128>,
     },
     /// Compute a Fibonacci sequence using trace table with 8 registers
     Fib8 {
         /// Length of Fibonacci sequence; must be a power of two
         #[structopt(short = "n", default_value = "1048576")]
         sequence_length: usize,
+        /// First two terms of the sequence; defaults to the standard starting terms
+        #[structopt(short = "s", long = "start", number_of_values = 2)]
+        start: Vec<u128>,
     },
     /// Compute a multiplicative Fibonacci sequence using trace table with 2 registers
     Mulfib {
         /// Length of Fibonacci sequence; must be a power of two
         #[structopt(short = "n", default_value = "1048576")]
         sequence_length: usize,
+        /// First two terms of the sequence; defaults to the standard starting terms
+        #[structopt(short = "s", long = "start", number_of_values = 2)]
+        start: Vec<u128>,
     },
     /// Compute a multiplicative Fibonacci sequence using trace table with 8 registers
     Mulfib8 {
         /// Length of Fibonacci sequence; must be a power of two
         #[structopt(short = "n", default_value = "1048576")]
         sequence_length: usize,
+        /// First two terms of the sequence; defaults to the standard starting terms
+        #[structopt(short = "s", long = "start", number_of_values = 2)]
+        start: Vec<u128>,
+    },
+    /// Compute the length of a Collatz trajectory for a starting value
+    Collatz {
+        /// Starting value of the trajectory; must be greater than 1
+        #[structopt(short = "n", default_value = "27")]
+        start: u64,
+    },
+    /// Prove that a list is a sorted permutation of another list
+    Sort {
+        /// Number of values in the list
+        #[structopt(short = "n", default_value = "1024")]
+        list_length: usize,
+    },
+    /// Evaluate a secret polynomial at a set of public points
+    Polyeval {
+        /// Number of coefficients of the polynomial
+        #[structopt(short = "n", default_value = "1023")]
+        num_coefficients: usize,
+        /// Number of evaluation points
+        #[structopt(short = "p", default_value = "4")]
+        num_points: usize,
     },
     /// Compute a hash chain using Rescue hash function
     Rescue {
@@ -109,4 +221,38 @@ pub enum ExampleType {
         #[structopt(short = "n", default_value = "7")]
         tree_depth: usize,
     },
+    /// Apply a block of balance transfers to a Merkle-ized account state
+    Rollup {
+        /// Depth of the state tree; must be one less than a power of two
+        #[structopt(short = "d", default_value = "7")]
+        tree_depth: usize,
+        /// Number of transfers in the block; must be a power of two
+        #[structopt(short = "n", default_value = "16")]
+        num_transfers: usize,
+    },
+    /// Prove knowledge of a preimage for a SHA-256 digest
+    Sha256 {
+        /// Preimage of the digest; must be at most 55 bytes long
+        #[structopt(short = "m", default_value = "winterfell")]
+        message: String,
+    },
+    /// Run Conway's Game of Life on a square board for a number of generations
+    Life {
+        /// Number of cells on each side of the board
+        #[structopt(short = "s", default_value = "16")]
+        board_size: usize,
+        /// Number of generations; must be a power of two
+        #[structopt(short = "n", default_value = "32")]
+        num_steps: usize,
+    },
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn parse_hash_fn(hash_fn: &str) -> HashFunction {
+    match hash_fn {
+        "sha3" => HashFunction::Sha3_256,
+        _ => HashFunction::Blake3_256,
+    }
 }
@@ -0,0 +1,250 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::are_equal;
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ByteWriter, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions,
+    Serializable, TraceInfo, TransitionConstraintDegree,
+};
+
+// GAME OF LIFE AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub board_size: usize,
+    pub initial_board: Vec<bool>,
+    pub final_board: Vec<bool>,
+}
+
+impl Serializable for PublicInputs {
+    /// Writes board size followed by both boards; each board has board_size^2 cells, so the
+    /// number of cells is not written explicitly.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u64(self.board_size as u64);
+        bool::write_batch_into(&self.initial_board, target);
+        bool::write_batch_into(&self.final_board, target);
+    }
+}
+
+pub struct LifeAir {
+    context: ComputationContext,
+    board_size: usize,
+    num_steps: usize,
+    initial_board: Vec<bool>,
+    final_board: Vec<bool>,
+    born_coefficient: BaseElement,
+    survive_coefficient: BaseElement,
+}
+
+impl Air for LifeAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        let num_cells = pub_inputs.board_size * pub_inputs.board_size;
+        assert_eq!(num_cells, pub_inputs.initial_board.len());
+        assert_eq!(num_cells, pub_inputs.final_board.len());
+
+        // all constraints are enforced only on the steps which advance the board by one
+        // generation; this is accomplished by multiplying them by a mask which spans the
+        // entire trace
+        let mut degrees = Vec::with_capacity(num_cells * 3);
+        for _ in 0..num_cells {
+            degrees.push(TransitionConstraintDegree::with_cycles(
+                4,
+                vec![trace_info.length],
+            ));
+        }
+        for _ in 0..num_cells {
+            degrees.push(TransitionConstraintDegree::with_cycles(
+                4,
+                vec![trace_info.length],
+            ));
+        }
+        for _ in 0..num_cells {
+            degrees.push(TransitionConstraintDegree::with_cycles(
+                3,
+                vec![trace_info.length],
+            ));
+        }
+
+        let context = ComputationContext::new(num_cells * 3, trace_info.length, degrees, options);
+        LifeAir {
+            context,
+            board_size: pub_inputs.board_size,
+            num_steps: trace_info.length / 2,
+            initial_board: pub_inputs.initial_board,
+            final_board: pub_inputs.final_board,
+            born_coefficient: -BaseElement::new(720).inv(),
+            survive_coefficient: BaseElement::new(1440).inv(),
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        let mut step_mask = vec![BaseElement::ZERO; self.trace_length()];
+        step_mask[..self.num_steps].fill(BaseElement::ONE);
+        vec![step_mask]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        // the board must start in the initial state and end up in the final state after the
+        // specified number of steps
+        let mut result = Vec::with_capacity(self.initial_board.len() * 2);
+        for (cell, &alive) in self.initial_board.iter().enumerate() {
+            result.push(Assertion::single(cell, 0, to_element(alive)));
+        }
+        for (cell, &alive) in self.final_board.iter().enumerate() {
+            result.push(Assertion::single(cell, self.num_steps, to_element(alive)));
+        }
+        result
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        let num_cells = self.board_size * self.board_size;
+        // expected state width is 3 field elements per cell
+        debug_assert_eq!(num_cells * 3, current.len());
+        debug_assert_eq!(num_cells * 3, next.len());
+
+        let step_flag = periodic_values[0];
+        let born_coefficient = E::from(self.born_coefficient);
+        let survive_coefficient = E::from(self.survive_coefficient);
+
+        for cell in 0..num_cells {
+            let neighbors = get_neighbors(cell, self.board_size)
+                .iter()
+                .fold(E::ZERO, |acc, &i| acc + current[i]);
+            let alive = current[cell];
+            let h1 = current[num_cells + cell];
+            let h2 = current[num_cells * 2 + cell];
+
+            // a cell is alive in the next generation if it has exactly 3 live neighbors, or if
+            // it is alive and has exactly 2 live neighbors; with n being the number of live
+            // neighbors, this can be expressed using Lagrange basis polynomials over [0, 8]:
+            // next = L_3(n) + alive * L_2(n). The shared factors of these polynomials are
+            // computed in helper registers to reduce the degree of the constraint.
+            let born = born_coefficient * (neighbors - E::from(2u8));
+            let survive = survive_coefficient * alive * (neighbors - E::from(3u8));
+            result[cell] = step_flag * are_equal(next[cell], h1 * h2 * (born + survive));
+
+            // h1 = n * (n - 1) * (n - 4) * (n - 5)
+            // h2 = (n - 6) * (n - 7) * (n - 8)
+            result[num_cells + cell] = step_flag * are_equal(h1, compute_h1(neighbors));
+            result[num_cells * 2 + cell] = step_flag * are_equal(h2, compute_h2(neighbors));
+        }
+    }
+}
+
+// TRACE GENERATOR
+// ================================================================================================
+
+pub fn build_trace(
+    initial_board: &[bool],
+    board_size: usize,
+    num_steps: usize,
+) -> ExecutionTrace<BaseElement> {
+    assert!(
+        num_steps.is_power_of_two(),
+        "number of steps must be a power of 2"
+    );
+    let num_cells = board_size * board_size;
+    let trace_length = num_steps * 2;
+    let mut trace = ExecutionTrace::new(num_cells * 3, trace_length);
+
+    // record all generations of the board, starting with the initial one
+    let mut board = initial_board.to_vec();
+    let mut row = vec![BaseElement::ZERO; num_cells * 3];
+    for step in 0..=num_steps {
+        for cell in 0..num_cells {
+            let neighbors = BaseElement::from(count_live_neighbors(&board, cell, board_size));
+            row[cell] = to_element(board[cell]);
+            row[num_cells + cell] = compute_h1(neighbors);
+            row[num_cells * 2 + cell] = compute_h2(neighbors);
+        }
+        trace.update_row(step, &row);
+        board = apply_rules(&board, board_size);
+    }
+
+    // fill the remaining rows with random values; this ensures that constraint degrees are
+    // stable even when the board settles into a state which does not change
+    let num_random_rows = trace_length - num_steps - 1;
+    let random_values = BaseElement::prng_vector([3; 32], num_random_rows * num_cells * 3);
+    for (i, row) in random_values.chunks(num_cells * 3).enumerate() {
+        trace.update_row(num_steps + 1 + i, row);
+    }
+
+    trace
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Advances the board by one generation according to the rules of Conway's Game of Life. The
+/// board wraps around at the edges.
+pub fn apply_rules(board: &[bool], board_size: usize) -> Vec<bool> {
+    (0..board.len())
+        .map(|cell| {
+            let neighbors = count_live_neighbors(board, cell, board_size);
+            neighbors == 3 || (board[cell] && neighbors == 2)
+        })
+        .collect()
+}
+
+fn count_live_neighbors(board: &[bool], cell: usize, board_size: usize) -> u32 {
+    get_neighbors(cell, board_size)
+        .iter()
+        .filter(|&&i| board[i])
+        .count() as u32
+}
+
+/// Returns indexes of the 8 cells surrounding the specified cell on a board which wraps around
+/// at the edges; cells are assumed to be in row-major order.
+fn get_neighbors(cell: usize, board_size: usize) -> [usize; 8] {
+    let row = cell / board_size;
+    let col = cell % board_size;
+    let up = (row + board_size - 1) % board_size;
+    let down = (row + 1) % board_size;
+    let left = (col + board_size - 1) % board_size;
+    let right = (col + 1) % board_size;
+    [
+        up * board_size + left,
+        up * board_size + col,
+        up * board_size + right,
+        row * board_size + left,
+        row * board_size + right,
+        down * board_size + left,
+        down * board_size + col,
+        down * board_size + right,
+    ]
+}
+
+fn compute_h1<E: FieldElement>(n: E) -> E {
+    n * (n - E::ONE) * (n - E::from(4u8)) * (n - E::from(5u8))
+}
+
+fn compute_h2<E: FieldElement>(n: E) -> E {
+    (n - E::from(6u8)) * (n - E::from(7u8)) * (n - E::from(8u8))
+}
+
+fn to_element(alive: bool) -> BaseElement {
+    if alive {
+        BaseElement::ONE
+    } else {
+        BaseElement::ZERO
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Example, ExampleOptions};
+use log::debug;
+use prover::{
+    self,
+    math::{
+        field::{f128::BaseElement, FieldElement, StarkField},
+        utils::log2_unchecked,
+    },
+    ProofOptions, StarkProof,
+};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{apply_rules, build_trace, LifeAir, PublicInputs};
+
+#[cfg(test)]
+mod tests;
+
+// GAME OF LIFE EXAMPLE
+// ================================================================================================
+
+pub fn get_example(
+    options: ExampleOptions,
+    board_size: usize,
+    num_steps: usize,
+) -> Box<dyn Example> {
+    Box::new(LifeExample::new(
+        board_size,
+        num_steps,
+        options.to_proof_options(28, 64),
+    ))
+}
+
+/// Proves that running Conway's Game of Life for a given number of generations moves a square
+/// board from a public initial state to a public final state. The board wraps around at the
+/// edges. Every cell of the board occupies a separate register, so the neighbors of a cell are
+/// read from the same row of the execution trace.
+pub struct LifeExample {
+    options: ProofOptions,
+    board_size: usize,
+    num_steps: usize,
+    initial_board: Vec<bool>,
+    final_board: Vec<bool>,
+}
+
+impl LifeExample {
+    pub fn new(board_size: usize, num_steps: usize, options: ProofOptions) -> LifeExample {
+        assert!(board_size >= 3, "board size must be at least 3");
+        assert!(
+            num_steps.is_power_of_two(),
+            "number of steps must be a power of 2"
+        );
+
+        // seed the board with a pseudo-random pattern in which roughly a third of cells are
+        // alive, and run the game to get the final state of the board
+        let now = Instant::now();
+        let initial_board = BaseElement::prng_vector([1; 32], board_size * board_size)
+            .into_iter()
+            .map(|seed| seed.as_int() % 3 == 0)
+            .collect::<Vec<_>>();
+        let mut final_board = initial_board.clone();
+        for _ in 0..num_steps {
+            final_board = apply_rules(&final_board, board_size);
+        }
+        debug!(
+            "Computed {} generations of a {}x{} board in {} ms; live cells: {} -> {}",
+            num_steps,
+            board_size,
+            board_size,
+            now.elapsed().as_millis(),
+            count_live_cells(&initial_board),
+            count_live_cells(&final_board),
+        );
+
+        LifeExample {
+            options,
+            board_size,
+            num_steps,
+            initial_board,
+            final_board,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for LifeExample {
+    fn prove(&self) -> StarkProof {
+        // generate the execution trace
+        debug!(
+            "Generating proof for {} generations of a {}x{} board\n\
+            ---------------------",
+            self.num_steps, self.board_size, self.board_size
+        );
+        let now = Instant::now();
+        let trace = build_trace(&self.initial_board, self.board_size, self.num_steps);
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            log2_unchecked(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        let pub_inputs = PublicInputs {
+            board_size: self.board_size,
+            initial_board: self.initial_board.clone(),
+            final_board: self.final_board.clone(),
+        };
+        prover::prove::<LifeAir>(trace, pub_inputs, self.options.clone()).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            board_size: self.board_size,
+            initial_board: self.initial_board.clone(),
+            final_board: self.final_board.clone(),
+        };
+        verifier::verify::<LifeAir>(proof, pub_inputs)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let mut final_board = self.final_board.clone();
+        final_board[0] = !final_board[0];
+        let pub_inputs = PublicInputs {
+            board_size: self.board_size,
+            initial_board: self.initial_board.clone(),
+            final_board,
+        };
+        verifier::verify::<LifeAir>(proof, pub_inputs)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn count_live_cells(board: &[bool]) -> usize {
+    board.iter().filter(|&&alive| alive).count()
+}
@@ -0,0 +1,51 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use prover::{FieldExtension, HashFunction, ProofOptions};
+
+#[test]
+fn life_test_basic_proof_verification() {
+    let life = Box::new(super::LifeExample::new(6, 8, build_options(false)));
+    crate::tests::test_basic_proof_verification(life);
+}
+
+#[test]
+fn life_test_basic_proof_verification_extension() {
+    let life = Box::new(super::LifeExample::new(6, 8, build_options(true)));
+    crate::tests::test_basic_proof_verification(life);
+}
+
+#[test]
+fn life_test_basic_proof_verification_fail() {
+    let life = Box::new(super::LifeExample::new(6, 8, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(life);
+}
+
+#[test]
+fn life_test_blinker() {
+    // a blinker oscillates with period 2, so after an even number of steps the board must be
+    // back in its initial state
+    let mut board = vec![false; 25];
+    board[11] = true;
+    board[12] = true;
+    board[13] = true;
+
+    let next = super::apply_rules(&board, 5);
+    let mut expected = vec![false; 25];
+    expected[7] = true;
+    expected[12] = true;
+    expected[17] = true;
+    assert_eq!(expected, next);
+    assert_eq!(board, super::apply_rules(&next, 5));
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(32, 16, 0, HashFunction::Blake3_256, extension)
+}
@@ -4,10 +4,15 @@
 // LICENSE file in the root directory of this source tree.
 
 use log::debug;
+use std::fs;
 use std::io::Write;
 use std::time::Instant;
 use structopt::StructOpt;
-use winterfell::{fibonacci, merkle, rescue, ExampleOptions, ExampleType};
+use verifier::{Deserializable, Serializable, StarkProof};
+use winterfell::{
+    collatz, fibonacci, life, merkle, polyeval, rescue, rollup, sha256, sort, ExampleOptions,
+    ExampleType,
+};
 
 // EXAMPLE RUNNER
 // ================================================================================================
@@ -22,24 +27,44 @@ fn main() {
     // read command-line args
     let options = ExampleOptions::from_args();
 
+    let proof_file = options.proof_file.clone();
     debug!("============================================================");
 
     // instantiate and prepare the example
-    let example = match options.example {
-        ExampleType::Fib { sequence_length } => {
-            fibonacci::fib2::get_example(options, sequence_length)
-        }
-        ExampleType::Fib8 { sequence_length } => {
-            fibonacci::fib8::get_example(options, sequence_length)
-        }
-        ExampleType::Mulfib { sequence_length } => {
-            fibonacci::mulfib2::get_example(options, sequence_length)
-        }
-        ExampleType::Mulfib8 { sequence_length } => {
-            fibonacci::mulfib8::get_example(options, sequence_length)
-        }
+    let example = match options.example.clone() {
+        ExampleType::Fib {
+            sequence_length,
+            start,
+        } => fibonacci::fib2::get_example(options, sequence_length, &start),
+        ExampleType::Fib8 {
+            sequence_length,
+            start,
+        } => fibonacci::fib8::get_example(options, sequence_length, &start),
+        ExampleType::Mulfib {
+            sequence_length,
+            start,
+        } => fibonacci::mulfib2::get_example(options, sequence_length, &start),
+        ExampleType::Mulfib8 {
+            sequence_length,
+            start,
+        } => fibonacci::mulfib8::get_example(options, sequence_length, &start),
+        ExampleType::Collatz { start } => collatz::get_example(options, start),
+        ExampleType::Sort { list_length } => sort::get_example(options, list_length),
+        ExampleType::Polyeval {
+            num_coefficients,
+            num_points,
+        } => polyeval::get_example(options, num_coefficients, num_points),
         ExampleType::Rescue { chain_length } => rescue::get_example(options, chain_length),
         ExampleType::Merkle { tree_depth } => merkle::get_example(options, tree_depth),
+        ExampleType::Rollup {
+            tree_depth,
+            num_transfers,
+        } => rollup::get_example(options, tree_depth, num_transfers),
+        ExampleType::Sha256 { message } => sha256::get_example(options, message),
+        ExampleType::Life {
+            board_size,
+            num_steps,
+        } => life::get_example(options, board_size, num_steps),
     };
 
     // generate proof
@@ -49,13 +74,17 @@ fn main() {
         "---------------------\nProof generated in {} ms",
         now.elapsed().as_millis()
     );
-    let proof_bytes = bincode::serialize(&proof).unwrap();
+    let proof_bytes = proof.to_bytes();
     debug!("Proof size: {} KB", proof_bytes.len() / 1024);
     debug!("Proof security: {} bits", proof.security_level(true));
+    if let Some(proof_file) = proof_file {
+        fs::write(&proof_file, &proof_bytes).expect("failed to write proof file");
+        debug!("Proof written to {}", proof_file.display());
+    }
 
     // verify the proof
     debug!("---------------------");
-    let proof = bincode::deserialize(&proof_bytes).expect("proof deserialization failed");
+    let proof = StarkProof::read_from_bytes(&proof_bytes).expect("proof deserialization failed");
     let now = Instant::now();
     match example.verify(proof) {
         Ok(_) => debug!("Proof verified in {} ms", now.elapsed().as_millis()),
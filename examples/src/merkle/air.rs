@@ -13,8 +13,8 @@ use crate::utils::{
 };
 use prover::{
     math::field::{f128::BaseElement, FieldElement},
-    Air, Assertion, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions, TraceInfo,
-    TransitionConstraintDegree,
+    Air, Assertion, ByteWriter, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions,
+    Serializable, TraceInfo, TransitionConstraintDegree,
 };
 
 // CONSTANTS
@@ -29,6 +29,12 @@ pub struct PublicInputs {
     pub tree_root: [BaseElement; 2],
 }
 
+impl Serializable for PublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        BaseElement::write_batch_into(&self.tree_root, target);
+    }
+}
+
 pub struct MerkleAir {
     context: ComputationContext,
     tree_root: [BaseElement; 2],
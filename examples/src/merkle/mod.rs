@@ -13,7 +13,7 @@ use prover::{
     crypto::MerkleTree,
     math::{
         field::{f128::BaseElement, FieldElement, StarkField},
-        utils::log2,
+        utils::log2_unchecked,
     },
     ProofOptions, StarkProof,
 };
@@ -102,7 +102,7 @@ impl Example for MerkleExample {
         debug!(
             "Generated execution trace of {} registers and 2^{} steps in {} ms",
             trace.width(),
-            log2(trace_length),
+            log2_unchecked(trace_length),
             now.elapsed().as_millis()
         );
 
@@ -23,6 +23,13 @@ fn merkle_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(merkle);
 }
 
+#[test]
+fn merkle_test_proof_determinism() {
+    let options = ProofOptions::new(32, 16, 8, HashFunction::Sha3_256, FieldExtension::Quadratic);
+    let merkle = Box::new(super::MerkleExample::new(7, options));
+    crate::tests::test_proof_determinism(merkle);
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
@@ -0,0 +1,152 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::are_equal;
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ByteWriter, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions,
+    Serializable, TraceInfo, TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Register holding coefficients of the polynomial, starting with the highest-degree coefficient.
+const COEFF: usize = 0;
+/// First of the registers holding Horner accumulators, one register per evaluation point.
+const ACC: usize = 1;
+
+// POLYNOMIAL EVALUATION AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub points: Vec<BaseElement>,
+    pub values: Vec<BaseElement>,
+}
+
+impl Serializable for PublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u64(self.points.len() as u64);
+        BaseElement::write_batch_into(&self.points, target);
+        BaseElement::write_batch_into(&self.values, target);
+    }
+}
+
+pub struct PolyEvalAir {
+    context: ComputationContext,
+    points: Vec<BaseElement>,
+    values: Vec<BaseElement>,
+}
+
+impl Air for PolyEvalAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        assert!(
+            !pub_inputs.points.is_empty(),
+            "at least one evaluation point must be provided"
+        );
+        assert_eq!(
+            pub_inputs.points.len(),
+            pub_inputs.values.len(),
+            "number of values must be equal to the number of points"
+        );
+
+        let num_points = pub_inputs.points.len();
+        let degrees = vec![TransitionConstraintDegree::new(1); num_points];
+
+        // coefficients are read only in the current state, and thus, are local
+        let context =
+            ComputationContext::new(ACC + num_points, trace_info.length, degrees, options)
+                .with_local_registers(&[COEFF]);
+        PolyEvalAir {
+            context,
+            points: pub_inputs.points,
+            values: pub_inputs.values,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        // every accumulator starts at 0 and ends at the claimed evaluation of the polynomial
+        let last_step = self.trace_length() - 1;
+        let mut assertions = Vec::with_capacity(2 * self.points.len());
+        for (i, &value) in self.values.iter().enumerate() {
+            assertions.push(Assertion::single(ACC + i, 0, BaseElement::ZERO));
+            assertions.push(Assertion::single(ACC + i, last_step, value));
+        }
+        assertions
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        // expected state width is 1 + number of points field elements
+        debug_assert_eq!(ACC + self.points.len(), current.len());
+        debug_assert_eq!(ACC + self.points.len(), next.len());
+
+        // a single step of Horner's method for each evaluation point
+        let coeff = current[COEFF];
+        for (i, &z) in self.points.iter().enumerate() {
+            let expected = current[ACC + i] * E::from(z) + coeff;
+            result[i] = are_equal(next[ACC + i], expected);
+        }
+    }
+}
+
+// TRACE GENERATOR
+// ================================================================================================
+
+/// Builds an execution trace which evaluates the polynomial with the specified coefficients
+/// (lowest-degree coefficient first) at all `points`.
+pub fn build_trace(
+    coefficients: &[BaseElement],
+    points: &[BaseElement],
+) -> ExecutionTrace<BaseElement> {
+    let trace_length = get_trace_length(coefficients.len());
+    let trace_width = ACC + points.len();
+    let mut trace = ExecutionTrace::new(trace_width, trace_length);
+
+    // pad the coefficients with leading zeros so that the last coefficient is consumed at the
+    // step preceding the last step; leading zeros do not change the results of Horner's method
+    let mut row = vec![BaseElement::ZERO; trace_width];
+    let num_padding_rows = trace_length - 1 - coefficients.len();
+    for step in 0..num_padding_rows {
+        trace.update_row(step, &row);
+    }
+    for (step, &coeff) in coefficients.iter().rev().enumerate() {
+        row[COEFF] = coeff;
+        trace.update_row(num_padding_rows + step, &row);
+        for (acc, &z) in row[ACC..].iter_mut().zip(points.iter()) {
+            *acc = *acc * z + coeff;
+        }
+    }
+
+    // the coefficient in the last row is not consumed, and thus, is filled with a random value;
+    // this ensures that constraint degrees are stable regardless of the coefficients
+    row[COEFF] = BaseElement::prng_vector([3; 32], 1)[0];
+    trace.update_row(trace_length - 1, &row);
+
+    trace
+}
+
+/// Returns the length of the execution trace needed to evaluate a polynomial with the specified
+/// number of coefficients; each coefficient is consumed at a separate step, and the last step
+/// holds the results.
+pub fn get_trace_length(num_coefficients: usize) -> usize {
+    let trace_length = (num_coefficients + 1).next_power_of_two();
+    trace_length.max(ComputationContext::MIN_TRACE_LENGTH)
+}
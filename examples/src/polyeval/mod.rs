@@ -0,0 +1,130 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Example, ExampleOptions};
+use log::debug;
+use prover::{
+    self,
+    math::{
+        field::{f128::BaseElement, FieldElement},
+        polynom,
+        utils::log2_unchecked,
+    },
+    ProofOptions, StarkProof,
+};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{build_trace, PolyEvalAir, PublicInputs};
+
+#[cfg(test)]
+mod tests;
+
+// POLYNOMIAL EVALUATION EXAMPLE
+// ================================================================================================
+
+pub fn get_example(
+    options: ExampleOptions,
+    num_coefficients: usize,
+    num_points: usize,
+) -> Box<dyn Example> {
+    Box::new(PolyEvalExample::new(
+        num_coefficients,
+        num_points,
+        options.to_proof_options(28, 16),
+    ))
+}
+
+/// Proves that a polynomial with secret coefficients evaluates to public values at a set of
+/// public points. The coefficients are committed to as a column of the execution trace, and
+/// thus, the trace commitment in the proof serves as a commitment to the polynomial.
+pub struct PolyEvalExample {
+    options: ProofOptions,
+    coefficients: Vec<BaseElement>,
+    points: Vec<BaseElement>,
+    values: Vec<BaseElement>,
+}
+
+impl PolyEvalExample {
+    pub fn new(num_coefficients: usize, num_points: usize, options: ProofOptions) -> Self {
+        assert!(
+            num_coefficients > 0,
+            "polynomial must have at least one coefficient"
+        );
+        assert!(num_points > 0, "at least one evaluation point is required");
+
+        // generate pseudo-random coefficients and evaluation points
+        let coefficients = BaseElement::prng_vector([1; 32], num_coefficients);
+        let points = BaseElement::prng_vector([2; 32], num_points);
+
+        // evaluate the polynomial at all points
+        let now = Instant::now();
+        let values = polynom::eval_many(&coefficients, &points);
+        debug!(
+            "Evaluated a polynomial with {} coefficients at {} points in {} ms",
+            num_coefficients,
+            num_points,
+            now.elapsed().as_millis()
+        );
+
+        PolyEvalExample {
+            options,
+            coefficients,
+            points,
+            values,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for PolyEvalExample {
+    fn prove(&self) -> StarkProof {
+        debug!(
+            "Generating proof for evaluating a polynomial with {} coefficients at {} points\n\
+            ---------------------",
+            self.coefficients.len(),
+            self.points.len()
+        );
+
+        // generate execution trace
+        let now = Instant::now();
+        let trace = build_trace(&self.coefficients, &self.points);
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            log2_unchecked(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        let pub_inputs = PublicInputs {
+            points: self.points.clone(),
+            values: self.values.clone(),
+        };
+        prover::prove::<PolyEvalAir>(trace, pub_inputs, self.options.clone()).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            points: self.points.clone(),
+            values: self.values.clone(),
+        };
+        verifier::verify::<PolyEvalAir>(proof, pub_inputs)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let mut values = self.values.clone();
+        values[0] += BaseElement::ONE;
+        let pub_inputs = PublicInputs {
+            points: self.points.clone(),
+            values,
+        };
+        verifier::verify::<PolyEvalAir>(proof, pub_inputs)
+    }
+}
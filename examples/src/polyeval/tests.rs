@@ -0,0 +1,128 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use fri::{DefaultProverChannel, DefaultVerifierChannel, FriOptions, FriProver, PublicCoin};
+use prover::{
+    crypto::hash::Blake3_256,
+    math::{
+        field::{f128::BaseElement, FieldElement, StarkField},
+        polynom,
+        utils::{get_power_series_with_offset_unchecked, log2_unchecked},
+    },
+    FieldExtension, HashFunction, ProofOptions,
+};
+
+#[test]
+fn polyeval_test_basic_proof_verification() {
+    let polyeval = Box::new(super::PolyEvalExample::new(100, 4, build_options(false)));
+    crate::tests::test_basic_proof_verification(polyeval);
+}
+
+#[test]
+fn polyeval_test_basic_proof_verification_extension() {
+    let polyeval = Box::new(super::PolyEvalExample::new(100, 4, build_options(true)));
+    crate::tests::test_basic_proof_verification(polyeval);
+}
+
+#[test]
+fn polyeval_test_basic_proof_verification_fail() {
+    let polyeval = Box::new(super::PolyEvalExample::new(100, 4, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(polyeval);
+}
+
+#[test]
+fn polyeval_test_trace_length() {
+    // a polynomial with 7 coefficients fits into the minimum trace length without padding
+    assert_eq!(8, super::air::get_trace_length(7));
+    assert_eq!(16, super::air::get_trace_length(8));
+    let polyeval = Box::new(super::PolyEvalExample::new(7, 1, build_options(false)));
+    crate::tests::test_basic_proof_verification(polyeval);
+}
+
+#[test]
+fn polyeval_test_fri_opening() {
+    let num_coefficients = 64;
+    let blowup_factor = 8;
+    let offset = BaseElement::GENERATOR;
+    let coefficients = BaseElement::prng_vector([1; 32], num_coefficients);
+    let z = BaseElement::prng_vector([2; 32], 1)[0];
+    let value = polynom::eval(&coefficients, z);
+
+    // p(x) - p(z) is divisible by (x - z), and thus, the quotient is a polynomial of lower degree
+    // than p(x); the degree bound is set to the degree of p(x) because FRI verifier requires
+    // the bound plus one to be divisible by the folding factor
+    let max_degree = num_coefficients - 1;
+    let evaluations = build_quotient_evaluations(&coefficients, z, value, blowup_factor, offset);
+    assert!(prove_and_verify_degree(evaluations, max_degree, blowup_factor, offset).is_ok());
+
+    // the quotient for a wrong value is not a polynomial, and thus, fails the degree test
+    let wrong_value = value + BaseElement::ONE;
+    let evaluations =
+        build_quotient_evaluations(&coefficients, z, wrong_value, blowup_factor, offset);
+    assert!(prove_and_verify_degree(evaluations, max_degree, blowup_factor, offset).is_err());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, extension)
+}
+
+/// Evaluates (p(x) - value) / (x - z) over a coset of the domain which is `blowup_factor` times
+/// larger than the number of coefficients of p(x).
+fn build_quotient_evaluations(
+    coefficients: &[BaseElement],
+    z: BaseElement,
+    value: BaseElement,
+    blowup_factor: usize,
+    offset: BaseElement,
+) -> Vec<BaseElement> {
+    let domain = build_domain(coefficients.len() * blowup_factor, offset);
+    domain
+        .iter()
+        .map(|&x| (polynom::eval(coefficients, x) - value) / (x - z))
+        .collect()
+}
+
+/// Uses the standalone FRI prover and verifier to check that `evaluations` are evaluations of a
+/// polynomial of degree at most `max_degree`.
+fn prove_and_verify_degree(
+    evaluations: Vec<BaseElement>,
+    max_degree: usize,
+    blowup_factor: usize,
+    offset: BaseElement,
+) -> Result<(), fri::VerifierError> {
+    let domain_size = evaluations.len();
+    let options = FriOptions::new(blowup_factor, offset);
+    let domain = build_domain(domain_size, offset);
+
+    // commit to FRI layers and build the proof
+    let mut channel = DefaultProverChannel::<Blake3_256>::new(domain_size, 32);
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone(), &domain);
+    let positions = channel.draw_query_positions();
+    let proof = prover.build_proof(&positions);
+
+    // verify the proof against evaluations at the queried positions
+    let commitments = channel.fri_layer_commitments().to_vec();
+    let channel = DefaultVerifierChannel::<BaseElement, Blake3_256>::new(proof, commitments);
+    let context = fri::VerifierContext::new(domain_size, max_degree, 1, options)?;
+    let queried_evaluations = positions
+        .iter()
+        .map(|&p| evaluations[p])
+        .collect::<Vec<_>>();
+    fri::verify(&context, &channel, &queried_evaluations, &positions)
+}
+
+fn build_domain(domain_size: usize, offset: BaseElement) -> Vec<BaseElement> {
+    let g = BaseElement::get_root_of_unity(log2_unchecked(domain_size)).unwrap();
+    get_power_series_with_offset_unchecked(g, offset, domain_size)
+}
@@ -7,8 +7,8 @@ use super::rescue;
 use crate::utils::{are_equal, is_zero, not, EvaluationResult};
 use prover::{
     math::field::{f128::BaseElement, FieldElement},
-    Air, Assertion, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions, TraceInfo,
-    TransitionConstraintDegree,
+    Air, Assertion, ByteWriter, ComputationContext, EvaluationFrame, ExecutionTrace,
+    PeriodicColumn, ProofOptions, Serializable, TraceInfo, TransitionConstraintDegree,
 };
 
 // CONSTANTS
@@ -46,6 +46,13 @@ pub struct PublicInputs {
     pub result: [BaseElement; 2],
 }
 
+impl Serializable for PublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        BaseElement::write_batch_into(&self.seed, target);
+        BaseElement::write_batch_into(&self.result, target);
+    }
+}
+
 pub struct RescueAir {
     context: ComputationContext,
     seed: [BaseElement; 2],
@@ -77,9 +84,9 @@ impl Air for RescueAir {
         &self.context
     }
 
-    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
-        let mut result = vec![CYCLE_MASK.to_vec()];
-        result.append(&mut rescue::get_round_constants());
+    fn get_periodic_columns(&self) -> Vec<PeriodicColumn<'_, Self::BaseElement>> {
+        let mut result = vec![PeriodicColumn::from_values(CYCLE_MASK.to_vec())];
+        result.append(&mut rescue::get_round_constant_columns());
         result
     }
 
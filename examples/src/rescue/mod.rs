@@ -9,7 +9,7 @@ use prover::{
     self,
     math::{
         field::{f128::BaseElement, FieldElement},
-        utils::log2,
+        utils::log2_unchecked,
     },
     ProofOptions, StarkProof,
 };
@@ -85,7 +85,7 @@ impl Example for RescueExample {
         debug!(
             "Generated execution trace of {} registers and 2^{} steps in {} ms",
             trace.width(),
-            log2(trace_length),
+            log2_unchecked(trace_length),
             now.elapsed().as_millis()
         );
 
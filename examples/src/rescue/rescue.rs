@@ -4,7 +4,10 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::utils::{are_equal, EvaluationResult};
-use prover::math::field::{f128::BaseElement, FieldElement};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    PeriodicColumn,
+};
 
 /// The number of rounds is set to 14 to provide 128-bit security level.
 /// computed using algorithm 7 from https://eprint.iacr.org/2020/1143.pdf
@@ -85,21 +88,12 @@ pub fn enforce_round<E: FieldElement + From<BaseElement>>(
 // ROUND CONSTANTS
 // ================================================================================================
 
-/// Returns Rescue round constants arranged in column-major form.
-pub fn get_round_constants() -> Vec<Vec<BaseElement>> {
-    let mut constants = Vec::new();
-    for _ in 0..(STATE_WIDTH * 2) {
-        constants.push(vec![BaseElement::ZERO; CYCLE_LENGTH]);
-    }
-
-    #[allow(clippy::needless_range_loop)]
-    for i in 0..CYCLE_LENGTH {
-        for j in 0..(STATE_WIDTH * 2) {
-            constants[j][i] = ARK[i][j];
-        }
-    }
-
-    constants
+/// Returns Rescue round constants as periodic columns; values of the columns are read directly
+/// from the table of round constants, rather than being copied into column-major vectors.
+pub fn get_round_constant_columns<'a>() -> Vec<PeriodicColumn<'a, BaseElement>> {
+    (0..(STATE_WIDTH * 2))
+        .map(|j| PeriodicColumn::from_fn(CYCLE_LENGTH, move |i| ARK[i][j]))
+        .collect()
 }
 
 // HELPER FUNCTIONS
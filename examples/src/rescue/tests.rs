@@ -3,7 +3,13 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use prover::{FieldExtension, HashFunction, ProofOptions};
+use super::{PublicInputs, RescueAir};
+use crate::Example;
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    CoefficientScheme, FieldExtension, HashFunction, ProofOptions,
+};
+use verifier::VerifierContext;
 
 #[test]
 fn rescue_test_basic_proof_verification() {
@@ -23,6 +29,69 @@ fn rescue_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(rescue_eg);
 }
 
+#[test]
+fn rescue_test_basic_proof_verification_coefficient_powers() {
+    let options = build_options(false).with_coefficient_scheme(CoefficientScheme::Powers);
+    let rescue_eg = Box::new(super::RescueExample::new(128, options));
+    crate::tests::test_basic_proof_verification(rescue_eg);
+}
+
+#[test]
+fn rescue_test_basic_proof_verification_coefficient_powers_fail() {
+    let options = build_options(true).with_coefficient_scheme(CoefficientScheme::Powers);
+    let rescue_eg = Box::new(super::RescueExample::new(128, options));
+    crate::tests::test_basic_proof_verification_fail(rescue_eg);
+}
+
+#[test]
+fn rescue_test_proof_determinism() {
+    // grinding is enabled to make sure that proof-of-work nonce is deterministic as well
+    let options = ProofOptions::new(32, 16, 8, HashFunction::Blake3_256, FieldExtension::None);
+    let rescue_eg = Box::new(super::RescueExample::new(128, options));
+    crate::tests::test_proof_determinism(rescue_eg);
+}
+
+#[test]
+fn rescue_test_verifier_context() {
+    let mut context = VerifierContext::<RescueAir>::new();
+    assert!(context.is_empty());
+
+    // periodic column polynomials are built for the first proof and re-used afterwards
+    let rescue_eg = super::RescueExample::new(128, build_options(false));
+    let proof = rescue_eg.prove();
+    for _ in 0..3 {
+        let pub_inputs = PublicInputs {
+            seed: rescue_eg.seed,
+            result: rescue_eg.result,
+        };
+        verifier::verify_with_context(proof.clone(), pub_inputs, &mut context).unwrap();
+    }
+    assert!(!context.is_empty());
+    assert_eq!((2, 1), (context.num_hits(), context.num_misses()));
+
+    // cached data does not make the verifier accept a proof against wrong inputs
+    let pub_inputs = PublicInputs {
+        seed: rescue_eg.seed,
+        result: [rescue_eg.result[0], rescue_eg.result[1] + BaseElement::ONE],
+    };
+    let result = verifier::verify_with_context(proof, pub_inputs, &mut context);
+    assert!(result.is_err());
+    assert_eq!((3, 1), (context.num_hits(), context.num_misses()));
+
+    // a proof with a different trace length requires polynomials to be rebuilt; the same
+    // context can be used for proofs in the extension field
+    let rescue_eg = super::RescueExample::new(256, build_options(true));
+    let pub_inputs = PublicInputs {
+        seed: rescue_eg.seed,
+        result: rescue_eg.result,
+    };
+    verifier::verify_with_context(rescue_eg.prove(), pub_inputs, &mut context).unwrap();
+    assert_eq!((3, 2), (context.num_hits(), context.num_misses()));
+
+    context.clear();
+    assert!(context.is_empty());
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
@@ -0,0 +1,650 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::AccountUpdate;
+use crate::utils::{
+    are_equal, is_binary, is_zero, not,
+    rescue::{
+        self, CYCLE_LENGTH as HASH_CYCLE_LEN, NUM_ROUNDS as NUM_HASH_ROUNDS,
+        STATE_WIDTH as HASH_STATE_WIDTH,
+    },
+    EvaluationResult, TreeNode,
+};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, AuxSegmentBuilder, ByteWriter, ComputationContext, EvaluationFrame,
+    ExecutionTrace, ProofOptions, Serializable, TraceInfo, TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const MAIN_TRACE_WIDTH: usize = 24;
+const TRACE_WIDTH: usize = MAIN_TRACE_WIDTH + 1;
+
+/// Registers [0..6] hold the hash state for the Merkle path of the account before the update.
+const OLD_STATE: usize = 0;
+/// Registers [6..12] hold the hash state for the Merkle path of the account after the update.
+const NEW_STATE: usize = 6;
+/// Register 12 holds the bits of the updated account's index.
+const INDEX_BIT: usize = 12;
+/// Registers [13, 14] carry the state root expected at the start of the current update.
+const ROOT_CARRY: usize = 13;
+/// Register 15 holds the balance delta applied by the current update.
+const DELTA: usize = 15;
+/// Register 16 accumulates the index of the updated account from the index bits.
+const ACCOUNT: usize = 16;
+/// Register 17 holds the balance of the updated account before the update.
+const BALANCE: usize = 17;
+/// Registers [18..21] hold the account, the balance before the update, and the delta of an entry
+/// of the memory log: a copy of all updates sorted by account, and by time within an account.
+const SORTED_ACCOUNT: usize = 18;
+const SORTED_BALANCE: usize = 19;
+pub(super) const SORTED_DELTA: usize = 20;
+/// Register 21 is set to 1 on the last step of an update if the next entry of the memory log
+/// refers to the same account.
+const SAME_ACCOUNT: usize = 21;
+/// Register 22 holds the bits of the gap between accounts of the current and the next entries
+/// of the memory log, and register 23 accumulates the gap from these bits.
+const GAP_BIT: usize = 22;
+const GAP: usize = 23;
+/// Register 24 holds the running product of the permutation argument; this is the only register
+/// of the auxiliary trace segment.
+const PRODUCT: usize = MAIN_TRACE_WIDTH;
+
+// ROLLUP BLOCK AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub tree_depth: usize,
+    pub old_root: [BaseElement; 2],
+    pub new_root: [BaseElement; 2],
+}
+
+impl Serializable for PublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u64(self.tree_depth as u64);
+        BaseElement::write_batch_into(&self.old_root, target);
+        BaseElement::write_batch_into(&self.new_root, target);
+    }
+}
+
+pub struct RollupAir {
+    context: ComputationContext,
+    update_length: usize,
+    old_root: [BaseElement; 2],
+    new_root: [BaseElement; 2],
+    alpha: BaseElement,
+    beta: BaseElement,
+}
+
+impl Air for RollupAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        let update_length = get_update_length(pub_inputs.tree_depth);
+        let transfer_length = update_length * 2;
+
+        let hash_degree = TransitionConstraintDegree::with_cycles(5, vec![HASH_CYCLE_LEN]);
+        let mut degrees = vec![hash_degree; HASH_STATE_WIDTH * 2];
+        degrees.push(TransitionConstraintDegree::new(2));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            1,
+            vec![update_length],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            1,
+            vec![update_length],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            1,
+            vec![transfer_length],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            1,
+            vec![update_length],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            1,
+            vec![update_length],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            1,
+            vec![update_length],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            1,
+            vec![update_length],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            2,
+            vec![update_length],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            2,
+            vec![update_length],
+        ));
+
+        // memory log: the account index and balance of every update, the sorted copy of all
+        // updates, the range check of gaps between accounts, and the permutation argument
+        let log_degree = TransitionConstraintDegree::with_cycles(1, vec![update_length]);
+        let chain_degree = TransitionConstraintDegree::with_cycles(2, vec![update_length]);
+        degrees.extend(vec![log_degree.clone(); 6]);
+        degrees.push(TransitionConstraintDegree::new(2));
+        degrees.push(TransitionConstraintDegree::new(2));
+        degrees.push(log_degree);
+        degrees.extend(vec![chain_degree; 4]);
+
+        // the running product is built from random elements drawn after the main segment of the
+        // trace is committed
+        let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options)
+            .with_aux_segment(TRACE_WIDTH - MAIN_TRACE_WIDTH, 2);
+        RollupAir {
+            context,
+            update_length,
+            old_root: pub_inputs.old_root,
+            new_root: pub_inputs.new_root,
+            alpha: BaseElement::ZERO,
+            beta: BaseElement::ZERO,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn set_aux_rand_elements(&mut self, elements: &[Self::BaseElement]) {
+        self.alpha = elements[0];
+        self.beta = elements[1];
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        let mut result = vec![HASH_CYCLE_MASK.to_vec()];
+        result.append(&mut rescue::get_round_constants());
+        result.append(&mut build_update_masks(self.update_length));
+        result.push(build_index_powers(self.update_length));
+        result
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        // the first update must start from the old state root, and the last update must
+        // result in the new state root
+        let last_step = self.trace_length() - 1;
+        let mut result = vec![
+            Assertion::single(ROOT_CARRY, 0, self.old_root[0]),
+            Assertion::single(ROOT_CARRY + 1, 0, self.old_root[1]),
+            Assertion::single(NEW_STATE, last_step, self.new_root[0]),
+            Assertion::single(NEW_STATE + 1, last_step, self.new_root[1]),
+        ];
+
+        // leaf values are hashed as two-element messages; thus, the remainder of the rate
+        // must be ZERO at the start of every update
+        for &register in [OLD_STATE, NEW_STATE].iter() {
+            result.push(Assertion::periodic(
+                register + 2,
+                0,
+                self.update_length,
+                0u8.into(),
+            ));
+            result.push(Assertion::periodic(
+                register + 3,
+                0,
+                self.update_length,
+                0u8.into(),
+            ));
+        }
+
+        // hash capacity registers are reset to ZERO every 8 steps
+        for &register in [OLD_STATE, NEW_STATE].iter() {
+            result.push(Assertion::periodic(
+                register + 4,
+                0,
+                HASH_CYCLE_LEN,
+                0u8.into(),
+            ));
+            result.push(Assertion::periodic(
+                register + 5,
+                0,
+                HASH_CYCLE_LEN,
+                0u8.into(),
+            ));
+        }
+
+        // account index and the gap between accounts are accumulated from ZERO in every update
+        for &register in [ACCOUNT, GAP].iter() {
+            result.push(Assertion::periodic(
+                register,
+                0,
+                self.update_length,
+                0u8.into(),
+            ));
+        }
+
+        // the running product starts at ONE, and must return to ONE once all updates have been
+        // accounted for
+        result.push(Assertion::single(PRODUCT, 0, BaseElement::ONE));
+        result.push(Assertion::single(PRODUCT, last_step, BaseElement::ONE));
+
+        result
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        // expected state width is 25 field elements
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // split periodic values into hash mask, Rescue round constants, update masks, and
+        // powers of two for index bits
+        let hash_flag = periodic_values[0];
+        let ark = &periodic_values[1..(HASH_STATE_WIDTH * 2 + 1)];
+        let update_masks = &periodic_values[(HASH_STATE_WIDTH * 2 + 1)..];
+        let insert_flag = update_masks[0];
+        let update_start_flag = update_masks[1];
+        let root_flag = update_masks[2];
+        let update_end_flag = update_masks[3];
+        let transfer_flag = update_masks[4];
+        let index_power = update_masks[5];
+
+        // both Merkle paths are computed in the same way as in the Merkle example, except that
+        // the accumulated hash is carried into the next round only within a single update
+        let bit = next[INDEX_BIT];
+        for &offset in [OLD_STATE, NEW_STATE].iter() {
+            let current = &current[offset..(offset + HASH_STATE_WIDTH)];
+            let next = &next[offset..(offset + HASH_STATE_WIDTH)];
+            let result = &mut result[offset..(offset + HASH_STATE_WIDTH)];
+            enforce_merkle_step(result, current, next, ark, hash_flag, insert_flag, bit);
+        }
+
+        // values in the index bit register must be binary
+        result[12] = is_binary(current[INDEX_BIT]);
+
+        // the root carry stays the same within an update, and is replaced with the newly
+        // computed root on the last step of an update
+        for i in 0..2 {
+            let carry = current[ROOT_CARRY + i];
+            let expected = carry + update_end_flag * (current[NEW_STATE + i] - carry);
+            result[13 + i] = are_equal(next[ROOT_CARRY + i], expected);
+        }
+
+        // the delta stays the same within an update, and the second update of a transfer must
+        // have the delta opposite to the first update
+        result[15] = not(update_end_flag) * are_equal(next[DELTA], current[DELTA]);
+        result.agg_constraint(15, transfer_flag, next[DELTA] + current[DELTA]);
+
+        // the root of the old Merkle path must be equal to the root carried from the previous
+        // update; the old root is located in the next row relative to where root flag is set
+        result[16] = root_flag * are_equal(next[OLD_STATE], current[ROOT_CARRY]);
+        result[17] = root_flag * are_equal(next[OLD_STATE + 1], current[ROOT_CARRY + 1]);
+
+        // the new account balance must be equal to the old balance plus delta, and the rest of
+        // the account must remain unchanged
+        let new_balance = current[OLD_STATE] + current[DELTA];
+        result[18] = update_start_flag * are_equal(current[NEW_STATE], new_balance);
+        result[19] = update_start_flag * are_equal(current[NEW_STATE + 1], current[OLD_STATE + 1]);
+
+        // both Merkle paths must use the same sibling nodes
+        let not_bit = not(bit);
+        for i in 0..2 {
+            let left = are_equal(next[OLD_STATE + i], next[NEW_STATE + i]);
+            let right = are_equal(next[OLD_STATE + 2 + i], next[NEW_STATE + 2 + i]);
+            result[20 + i] = insert_flag * (bit * left + not_bit * right);
+        }
+
+        // the account index is accumulated from the index bits used in the Merkle paths, and
+        // the balance before the update is taken from the old value of the account
+        let not_end_flag = not(update_end_flag);
+        result[22] = not_end_flag * are_equal(next[ACCOUNT], current[ACCOUNT]) - index_power * bit;
+        result[23] = update_start_flag * are_equal(current[BALANCE], current[OLD_STATE]);
+        result[24] = not_end_flag * are_equal(next[BALANCE], current[BALANCE]);
+
+        // an entry of the memory log stays the same within an update
+        for (i, &register) in [SORTED_ACCOUNT, SORTED_BALANCE, SORTED_DELTA]
+            .iter()
+            .enumerate()
+        {
+            result[25 + i] = not_end_flag * are_equal(next[register], current[register]);
+        }
+
+        // the gap between accounts of adjacent entries of the memory log is accumulated from
+        // its bits in the same way as the account index
+        result[28] = is_binary(current[SAME_ACCOUNT]);
+        result[29] = is_binary(current[GAP_BIT]);
+        result[30] =
+            not_end_flag * are_equal(next[GAP], current[GAP]) - index_power * next[GAP_BIT];
+
+        // on the last step of an update, the next entry of the memory log must either refer to
+        // the same account and start from the balance left by the current entry, or refer to an
+        // account with a greater index; thus, the log is sorted by account, and the balance of
+        // every account is carried from one update of the account to the next
+        let same_flag = update_end_flag * current[SAME_ACCOUNT];
+        let gap_flag = update_end_flag * not(current[SAME_ACCOUNT]);
+        let new_balance = current[SORTED_BALANCE] + current[SORTED_DELTA];
+        result[31] = same_flag * are_equal(next[SORTED_ACCOUNT], current[SORTED_ACCOUNT]);
+        result[32] = same_flag * are_equal(next[SORTED_BALANCE], new_balance);
+        result[33] = gap_flag
+            * are_equal(
+                next[SORTED_ACCOUNT],
+                current[SORTED_ACCOUNT] + current[GAP] + E::ONE,
+            );
+
+        // permutation argument: once per update, the running product accumulates
+        // (alpha - u) / (alpha - s), where u and s are fingerprints of the update and of the
+        // entry of the memory log; the product over all updates is ONE only if the memory log
+        // is a permutation of the updates
+        let alpha = E::from(self.alpha);
+        let beta = E::from(self.beta);
+        let update = fingerprint(current[ACCOUNT], current[BALANCE], current[DELTA], beta);
+        let entry = fingerprint(
+            current[SORTED_ACCOUNT],
+            current[SORTED_BALANCE],
+            current[SORTED_DELTA],
+            beta,
+        );
+        result[34] = are_equal(
+            next[PRODUCT] * (E::ONE + root_flag * (alpha - entry - E::ONE)),
+            current[PRODUCT] * (E::ONE + root_flag * (alpha - update - E::ONE)),
+        );
+    }
+}
+
+/// Combines account index, balance, and delta into a single value using the random element
+/// `beta`.
+fn fingerprint<E: FieldElement>(account: E, balance: E, delta: E, beta: E) -> E {
+    account + beta * (balance + beta * delta)
+}
+
+/// Enforces a single step of Merkle path computation within the specified hash state.
+fn enforce_merkle_step<E: FieldElement + From<BaseElement>>(
+    result: &mut [E],
+    current: &[E],
+    next: &[E],
+    ark: &[E],
+    hash_flag: E,
+    insert_flag: E,
+    bit: E,
+) {
+    // when hash_flag = 1, constraints for Rescue round are enforced
+    rescue::enforce_round(result, current, next, ark, hash_flag);
+
+    // when insert_flag = 1, make sure accumulated hash is placed in the right place in the hash
+    // state for the next round of hashing: when index bit = 0 accumulated hash must go into
+    // registers [0, 1], and when index bit = 1, it must go into registers [2, 3]
+    let not_bit = not(bit);
+    result.agg_constraint(0, insert_flag, not_bit * are_equal(current[0], next[0]));
+    result.agg_constraint(1, insert_flag, not_bit * are_equal(current[1], next[1]));
+    result.agg_constraint(2, insert_flag, bit * are_equal(current[0], next[2]));
+    result.agg_constraint(3, insert_flag, bit * are_equal(current[1], next[3]));
+
+    // make sure capacity registers of the hash state are reset to zeros
+    result.agg_constraint(4, insert_flag, is_zero(next[4]));
+    result.agg_constraint(5, insert_flag, is_zero(next[5]));
+}
+
+// TRACE GENERATOR
+// ================================================================================================
+
+/// Builds the main segment of the execution trace for applying `updates` to the state tree with
+/// the specified root.
+///
+/// `memory_log` lists positions of the updates in the order in which they are recorded in the
+/// memory log; for a valid trace, this must be the order returned by [build_memory_log()].
+pub fn build_trace(
+    old_root: TreeNode,
+    updates: &[AccountUpdate],
+    memory_log: &[usize],
+    tree_depth: usize,
+) -> ExecutionTrace<BaseElement> {
+    assert_eq!(
+        updates.len(),
+        memory_log.len(),
+        "memory log must have an entry for every update"
+    );
+
+    // allocate memory to hold the trace table; each update is processed in a segment of the
+    // trace long enough to hash the leaf and all nodes of the Merkle path
+    let update_length = get_update_length(tree_depth);
+    let trace_length = updates.len() * update_length;
+    let mut trace = ExecutionTrace::new(MAIN_TRACE_WIDTH, trace_length);
+
+    trace.fill(
+        |state| {
+            // initialize first state of the computation
+            init_update_state(state, &updates[0], [old_root.0, old_root.1]);
+        },
+        |step, state| {
+            // execute the transition function for all steps
+            //
+            // For the first 7 steps of each 8-step cycle, compute a single round of Rescue hash in
+            // both hash states. On the 8th step, insert the next branch node into both states in
+            // the positions defined by the next bit of the leaf index. On the last step of an
+            // update, initialize the state for the next update.
+
+            let update = &updates[step / update_length];
+            let update_step = step % update_length;
+            let cycle_num = update_step / HASH_CYCLE_LEN;
+            let cycle_pos = update_step % HASH_CYCLE_LEN;
+
+            if cycle_pos < NUM_HASH_ROUNDS {
+                rescue::apply_round(&mut state[OLD_STATE..(OLD_STATE + HASH_STATE_WIDTH)], step);
+                rescue::apply_round(&mut state[NEW_STATE..(NEW_STATE + HASH_STATE_WIDTH)], step);
+            } else if update_step == update_length - 1 {
+                let root = [state[NEW_STATE], state[NEW_STATE + 1]];
+                init_update_state(state, &updates[step / update_length + 1], root);
+            } else {
+                // skip the first node of the path because it is computed as hash(value)
+                let node = update.path[cycle_num + 1];
+                let index_bit = BaseElement::new(((update.index >> cycle_num) & 1) as u128);
+                for &offset in [OLD_STATE, NEW_STATE].iter() {
+                    if index_bit == BaseElement::ZERO {
+                        // if index bit is zero, new branch node goes into registers [2, 3];
+                        // values in registers [0, 1] (the accumulated hash) remain unchanged
+                        state[offset + 2] = node.0;
+                        state[offset + 3] = node.1;
+                    } else {
+                        // if index bit is one, accumulated hash goes into registers [2, 3],
+                        // and new branch nodes goes into registers [0, 1]
+                        state[offset + 2] = state[offset];
+                        state[offset + 3] = state[offset + 1];
+                        state[offset] = node.0;
+                        state[offset + 1] = node.1;
+                    }
+                    // reset the capacity registers of the state to ZERO
+                    state[offset + 4] = BaseElement::ZERO;
+                    state[offset + 5] = BaseElement::ZERO;
+                }
+                state[INDEX_BIT] = index_bit;
+                state[ACCOUNT] += index_bit * BaseElement::new(1 << cycle_num);
+            }
+        },
+    );
+
+    // record the memory log next to the updates; the log is not a part of the state carried
+    // between steps, and thus, is written into the trace once the updates have been processed
+    for (i, &position) in memory_log.iter().enumerate() {
+        let entry = &updates[position];
+        let next_entry = memory_log.get(i + 1).map(|&position| &updates[position]);
+        let first_step = i * update_length;
+        let last_step = first_step + update_length - 1;
+
+        // adjacent entries for the same account are linked by balance; otherwise, the gap
+        // between accounts is decomposed into bits placed in the same steps as the index bits
+        let same_account = next_entry.map(|next| next.index) == Some(entry.index);
+        let gap = match next_entry {
+            Some(next) if !same_account => next.index.wrapping_sub(entry.index + 1),
+            _ => 0,
+        };
+
+        let mut gap_acc = BaseElement::ZERO;
+        for step in first_step..=last_step {
+            let update_step = step - first_step;
+            let cycle_pos = update_step % HASH_CYCLE_LEN;
+            if update_step >= HASH_CYCLE_LEN && cycle_pos == 0 {
+                let cycle_num = update_step / HASH_CYCLE_LEN - 1;
+                let gap_bit = BaseElement::new(((gap >> cycle_num) & 1) as u128);
+                gap_acc += gap_bit * BaseElement::new(1 << cycle_num);
+                trace.set(GAP_BIT, step, gap_bit);
+            }
+            trace.set(GAP, step, gap_acc);
+            trace.set(SORTED_ACCOUNT, step, BaseElement::new(entry.index as u128));
+            trace.set(SORTED_BALANCE, step, entry.old_value.0);
+            trace.set(SORTED_DELTA, step, entry.delta);
+        }
+        if same_account {
+            trace.set(SAME_ACCOUNT, last_step, FieldElement::ONE);
+        }
+    }
+
+    // set index bit, gap bit, and same account flag at the second step to one; this still
+    // results in a valid execution trace because actual bits are inserted into the trace after
+    // step 7, and the flag is read only on the last step of an update, but it ensures that
+    // there are no repeating patterns in these registers, and thus the degrees of constraints
+    // which depend on them are stable.
+    trace.set(INDEX_BIT, 1, FieldElement::ONE);
+    trace.set(GAP_BIT, 1, FieldElement::ONE);
+    trace.set(SAME_ACCOUNT, 1, FieldElement::ONE);
+
+    trace
+}
+
+/// Returns positions of `updates` sorted by account index; updates of the same account remain
+/// in the order in which they were applied.
+pub fn build_memory_log(updates: &[AccountUpdate]) -> Vec<usize> {
+    let mut memory_log = (0..updates.len()).collect::<Vec<_>>();
+    memory_log.sort_by_key(|&position| updates[position].index);
+    memory_log
+}
+
+/// Builds the auxiliary segment of the execution trace which holds the running product of the
+/// permutation argument between the updates and the memory log.
+pub struct ProductBuilder {
+    update_length: usize,
+}
+
+impl ProductBuilder {
+    pub fn new(tree_depth: usize) -> Self {
+        ProductBuilder {
+            update_length: get_update_length(tree_depth),
+        }
+    }
+}
+
+impl AuxSegmentBuilder<BaseElement> for ProductBuilder {
+    fn build_aux_segment(
+        &self,
+        main: &ExecutionTrace<BaseElement>,
+        elements: &[BaseElement],
+    ) -> ExecutionTrace<BaseElement> {
+        let (alpha, beta) = (elements[0], elements[1]);
+        let mut product = BaseElement::ONE;
+        let mut segment = ExecutionTrace::new(TRACE_WIDTH - MAIN_TRACE_WIDTH, main.len());
+        for step in 0..main.len() {
+            segment.set(0, step, product);
+            // the factor is accumulated one step before the last step of an update, because
+            // the last step of the trace is not covered by transition constraints
+            if step % self.update_length == self.update_length - 2 {
+                let update = fingerprint(
+                    main.get(ACCOUNT, step),
+                    main.get(BALANCE, step),
+                    main.get(DELTA, step),
+                    beta,
+                );
+                let entry = fingerprint(
+                    main.get(SORTED_ACCOUNT, step),
+                    main.get(SORTED_BALANCE, step),
+                    main.get(SORTED_DELTA, step),
+                    beta,
+                );
+                product *= (alpha - update) / (alpha - entry);
+            }
+        }
+        segment
+    }
+}
+
+/// Initializes hash states and registers for processing the specified update.
+fn init_update_state(state: &mut [BaseElement], update: &AccountUpdate, root: [BaseElement; 2]) {
+    state.fill(BaseElement::ZERO);
+    state[OLD_STATE] = update.old_value.0;
+    state[OLD_STATE + 1] = update.old_value.1;
+    state[NEW_STATE] = update.new_value.0;
+    state[NEW_STATE + 1] = update.new_value.1;
+    state[ROOT_CARRY] = root[0];
+    state[ROOT_CARRY + 1] = root[1];
+    state[DELTA] = update.delta;
+    state[BALANCE] = update.old_value.0;
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of steps needed to process a single account update in a tree of the
+/// specified depth.
+fn get_update_length(tree_depth: usize) -> usize {
+    assert!(
+        (tree_depth + 1).is_power_of_two(),
+        "tree depth must be one less than a power of 2, but was {}",
+        tree_depth
+    );
+    (tree_depth + 1) * HASH_CYCLE_LEN
+}
+
+/// Builds masks which identify specific steps of every update: node insertion steps, the first
+/// step, the step before the last, the last step, and the last step of the first update in a
+/// transfer.
+fn build_update_masks(update_length: usize) -> Vec<Vec<BaseElement>> {
+    let mut insert_mask = vec![BaseElement::ZERO; update_length];
+    for i in (NUM_HASH_ROUNDS..(update_length - 1)).step_by(HASH_CYCLE_LEN) {
+        insert_mask[i] = BaseElement::ONE;
+    }
+
+    let mut start_mask = vec![BaseElement::ZERO; update_length];
+    start_mask[0] = BaseElement::ONE;
+
+    let mut root_mask = vec![BaseElement::ZERO; update_length];
+    root_mask[update_length - 2] = BaseElement::ONE;
+
+    let mut end_mask = vec![BaseElement::ZERO; update_length];
+    end_mask[update_length - 1] = BaseElement::ONE;
+
+    let mut transfer_mask = vec![BaseElement::ZERO; update_length * 2];
+    transfer_mask[update_length - 1] = BaseElement::ONE;
+
+    vec![insert_mask, start_mask, root_mask, end_mask, transfer_mask]
+}
+
+/// Builds a column which holds the weight of the next index bit at every node insertion step,
+/// and ZERO at all other steps.
+fn build_index_powers(update_length: usize) -> Vec<BaseElement> {
+    let mut powers = vec![BaseElement::ZERO; update_length];
+    let mut power = BaseElement::ONE;
+    for i in (NUM_HASH_ROUNDS..(update_length - 1)).step_by(HASH_CYCLE_LEN) {
+        powers[i] = power;
+        power = power + power;
+    }
+    powers
+}
+
+// MASKS
+// ================================================================================================
+const HASH_CYCLE_MASK: [BaseElement; HASH_CYCLE_LEN] = [
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ZERO,
+];
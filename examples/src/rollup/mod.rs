@@ -0,0 +1,234 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    utils::{bytes_to_node, node_to_bytes, rescue, TreeNode},
+    Example, ExampleOptions,
+};
+use log::debug;
+use prover::{
+    self,
+    crypto::MerkleTree,
+    math::{
+        field::{f128::BaseElement, FieldElement, StarkField},
+        utils::log2_unchecked,
+    },
+    ProofOptions, StarkProof,
+};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{build_memory_log, build_trace, ProductBuilder, PublicInputs, RollupAir};
+
+#[cfg(test)]
+mod tests;
+
+// ROLLUP BLOCK EXAMPLE
+// ================================================================================================
+
+pub fn get_example(
+    options: ExampleOptions,
+    tree_depth: usize,
+    num_transfers: usize,
+) -> Box<dyn Example> {
+    Box::new(RollupExample::new(
+        tree_depth,
+        num_transfers,
+        options.to_proof_options(28, 64),
+    ))
+}
+
+/// Proves that a block of balance transfers moves a Merkle-ized account state from the old state
+/// root to the new state root. Each account is a (balance, owner) pair hashed into a leaf of a
+/// Merkle tree with Rescue hash function. A transfer is processed as two account updates: the
+/// first one subtracts the transferred amount from the sender's balance, and the second one adds
+/// the same amount to the receiver's balance.
+///
+/// Next to the updates, the execution trace holds a memory log: a copy of all updates sorted by
+/// account index. Constraints over the log require adjacent entries for the same account to
+/// carry the balance from one update to the next, and entries for different accounts to have
+/// strictly increasing indexes. A permutation argument over (account, balance, delta) triples
+/// binds the log to the updates; its running product lives in an auxiliary trace segment built
+/// from random elements drawn after the main segment is committed. The chain of state roots
+/// already orders all updates, so the log adds no soundness here; it gives a per-account view of
+/// the block on which further constraints (e.g., over net balance changes) can be placed.
+///
+/// This is a template rather than a production rollup: transfers are not authorized by account
+/// owners, and balances are not range-checked, so an overdrawn balance wraps around the field
+/// modulus.
+pub struct RollupExample {
+    options: ProofOptions,
+    tree_depth: usize,
+    old_root: TreeNode,
+    new_root: TreeNode,
+    updates: Vec<AccountUpdate>,
+}
+
+/// Describes a change to a single account in the state tree.
+pub struct AccountUpdate {
+    index: usize,
+    old_value: TreeNode,
+    new_value: TreeNode,
+    delta: BaseElement,
+    path: Vec<TreeNode>,
+}
+
+impl RollupExample {
+    pub fn new(tree_depth: usize, num_transfers: usize, options: ProofOptions) -> RollupExample {
+        assert!(
+            (tree_depth + 1).is_power_of_two(),
+            "tree depth must be one less than a power of 2"
+        );
+        assert!(
+            num_transfers.is_power_of_two(),
+            "number of transfers must be a power of 2"
+        );
+
+        // build the initial state of all accounts
+        let now = Instant::now();
+        let num_accounts = usize::pow(2, tree_depth as u32);
+        let owners = BaseElement::prng_vector([1; 32], num_accounts);
+        let mut accounts = owners
+            .into_iter()
+            .map(|owner| (BaseElement::from(1000u32), owner))
+            .collect::<Vec<_>>();
+        let mut tree = build_state_tree(&accounts);
+        let old_root = bytes_to_node(*tree.root());
+        debug!(
+            "Built state tree with {} accounts in {} ms",
+            num_accounts,
+            now.elapsed().as_millis(),
+        );
+
+        // apply transfers between pseudo-randomly selected accounts, and record Merkle paths
+        // of all updated accounts
+        let now = Instant::now();
+        let seeds = BaseElement::prng_vector([2; 32], num_transfers * 3);
+        let mut updates = Vec::with_capacity(num_transfers * 2);
+        for seed in seeds.chunks(3) {
+            let sender = (seed[0].as_int() % num_accounts as u128) as usize;
+            let offset = (seed[1].as_int() % (num_accounts as u128 - 1)) as usize + 1;
+            let receiver = (sender + offset) % num_accounts;
+            let amount = BaseElement::new(seed[2].as_int() % 100);
+
+            updates.push(update_account(&mut tree, &mut accounts, sender, -amount));
+            updates.push(update_account(&mut tree, &mut accounts, receiver, amount));
+        }
+        let new_root = bytes_to_node(*tree.root());
+        debug!(
+            "Applied {} transfers moving state root from {} to {} in {} ms",
+            num_transfers,
+            hex::encode(node_to_bytes(old_root)),
+            hex::encode(node_to_bytes(new_root)),
+            now.elapsed().as_millis(),
+        );
+
+        RollupExample {
+            options,
+            tree_depth,
+            old_root,
+            new_root,
+            updates,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for RollupExample {
+    fn prove(&self) -> StarkProof {
+        // generate the execution trace
+        debug!(
+            "Generating proof for applying {} transfers to a state tree of depth {}\n\
+            ---------------------",
+            self.updates.len() / 2,
+            self.tree_depth
+        );
+        let now = Instant::now();
+        let memory_log = build_memory_log(&self.updates);
+        let trace = build_trace(self.old_root, &self.updates, &memory_log, self.tree_depth);
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            log2_unchecked(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        let pub_inputs = PublicInputs {
+            tree_depth: self.tree_depth,
+            old_root: [self.old_root.0, self.old_root.1],
+            new_root: [self.new_root.0, self.new_root.1],
+        };
+        prover::Prover::<RollupAir>::new(self.options.clone())
+            .with_aux_segment(&ProductBuilder::new(self.tree_depth))
+            .prove(trace, pub_inputs)
+            .unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            tree_depth: self.tree_depth,
+            old_root: [self.old_root.0, self.old_root.1],
+            new_root: [self.new_root.0, self.new_root.1],
+        };
+        verifier::verify::<RollupAir>(proof, pub_inputs)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            tree_depth: self.tree_depth,
+            old_root: [self.old_root.0, self.old_root.1],
+            new_root: [self.new_root.1, self.new_root.0],
+        };
+        verifier::verify::<RollupAir>(proof, pub_inputs)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Applies `delta` to the balance of the account at the specified index, and returns a record
+/// of the update. The state tree is rebuilt to reflect the new value of the account.
+fn update_account(
+    tree: &mut MerkleTree,
+    accounts: &mut [TreeNode],
+    index: usize,
+    delta: BaseElement,
+) -> AccountUpdate {
+    let path = tree
+        .prove(index)
+        .into_iter()
+        .map(bytes_to_node)
+        .collect::<Vec<_>>();
+
+    let old_value = accounts[index];
+    let new_value = (old_value.0 + delta, old_value.1);
+    accounts[index] = new_value;
+    *tree = build_state_tree(accounts);
+
+    AccountUpdate {
+        index,
+        old_value,
+        new_value,
+        delta,
+        path,
+    }
+}
+
+fn build_state_tree(accounts: &[TreeNode]) -> MerkleTree {
+    let leaves = accounts
+        .iter()
+        .map(|&account| {
+            let mut leaf = [0; 32];
+            rescue::hash(&node_to_bytes(account), &mut leaf);
+            leaf
+        })
+        .collect();
+    MerkleTree::new(leaves, rescue::hash)
+}
@@ -0,0 +1,125 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    air::{build_memory_log, build_trace, ProductBuilder, PublicInputs, RollupAir, SORTED_DELTA},
+    RollupExample,
+};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, AuxSegmentBuilder, ExecutionTrace, FieldExtension, HashFunction, ProofOptions,
+    ProverError, TraceInfo,
+};
+
+#[test]
+fn rollup_test_basic_proof_verification() {
+    let rollup = Box::new(super::RollupExample::new(3, 2, build_options(false)));
+    crate::tests::test_basic_proof_verification(rollup);
+}
+
+#[test]
+fn rollup_test_basic_proof_verification_extension() {
+    let rollup = Box::new(super::RollupExample::new(3, 2, build_options(true)));
+    crate::tests::test_basic_proof_verification(rollup);
+}
+
+#[test]
+fn rollup_test_basic_proof_verification_fail() {
+    let rollup = Box::new(super::RollupExample::new(3, 2, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(rollup);
+}
+
+#[test]
+fn rollup_test_invalid_memory_logs() {
+    // with 8 transfers between 8 accounts, some accounts are updated more than once
+    let rollup = RollupExample::new(3, 8, build_options(false));
+    let memory_log = build_memory_log(&rollup.updates);
+    assert!(check_trace(&rollup, build_trace_for(&rollup, &memory_log)).is_ok());
+
+    // a log which is not sorted by account violates the range check of gaps between accounts
+    let mut unsorted_log = memory_log.clone();
+    unsorted_log.reverse();
+    let result = check_trace(&rollup, build_trace_for(&rollup, &unsorted_log));
+    assert!(matches!(
+        result,
+        Err(ProverError::UnsatisfiedTransitionConstraintError(33, _))
+    ));
+
+    // a log in which updates of the same account are out of order breaks the chain of balances
+    let i = (0..memory_log.len() - 1)
+        .find(|&i| {
+            let (first, second) = (
+                &rollup.updates[memory_log[i]],
+                &rollup.updates[memory_log[i + 1]],
+            );
+            first.index == second.index && first.delta + second.delta != BaseElement::ZERO
+        })
+        .expect("no account is updated twice");
+    let mut reordered_log = memory_log.clone();
+    reordered_log.swap(i, i + 1);
+    let result = check_trace(&rollup, build_trace_for(&rollup, &reordered_log));
+    assert!(matches!(
+        result,
+        Err(ProverError::UnsatisfiedTransitionConstraintError(32, _))
+    ));
+
+    // delta of the last entry of the log is not linked to any other entry, and thus, only the
+    // permutation argument detects that the log does not match the updates
+    let mut trace = build_trace_for(&rollup, &memory_log);
+    let update_length = trace.len() / rollup.updates.len();
+    let delta = trace.get(SORTED_DELTA, trace.len() - 1) + BaseElement::ONE;
+    for step in (trace.len() - update_length)..trace.len() {
+        trace.set(SORTED_DELTA, step, delta);
+    }
+    let result = check_trace(&rollup, trace);
+    assert!(matches!(
+        result,
+        Err(ProverError::UnsatisfiedAssertion(_, _))
+    ));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_trace_for(rollup: &RollupExample, memory_log: &[usize]) -> ExecutionTrace<BaseElement> {
+    build_trace(
+        rollup.old_root,
+        &rollup.updates,
+        memory_log,
+        rollup.tree_depth,
+    )
+}
+
+fn check_trace(
+    rollup: &RollupExample,
+    main: ExecutionTrace<BaseElement>,
+) -> Result<(), ProverError> {
+    let pub_inputs = PublicInputs {
+        tree_depth: rollup.tree_depth,
+        old_root: [rollup.old_root.0, rollup.old_root.1],
+        new_root: [rollup.new_root.0, rollup.new_root.1],
+    };
+    let trace_info = TraceInfo {
+        length: main.len(),
+        meta: vec![],
+    };
+    let mut air = RollupAir::new(trace_info, pub_inputs, build_options(false));
+
+    // any random elements which do not collide with fingerprints are suitable for checking
+    // the trace
+    let elements = [BaseElement::from(1234567u32), BaseElement::from(7654321u32)];
+    air.set_aux_rand_elements(&elements);
+    let aux = ProductBuilder::new(rollup.tree_depth).build_aux_segment(&main, &elements);
+    main.append_segment(aux).check(&air)
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(32, 16, 0, HashFunction::Blake3_256, extension)
+}
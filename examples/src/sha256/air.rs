@@ -0,0 +1,464 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::{are_equal, is_binary};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement, StarkField},
+    Air, Assertion, ByteWriter, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions,
+    Serializable, TraceInfo, TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bits in a SHA-256 word.
+const WORD_BITS: usize = 32;
+
+/// Number of rounds in SHA-256 compression function.
+const NUM_ROUNDS: usize = 64;
+
+/// Number of words in the message schedule window.
+const WINDOW_SIZE: usize = 16;
+
+/// Maximum length of a message which fits into a single 64-byte block after padding.
+pub const MAX_MESSAGE_LEN: usize = 55;
+
+// Registers [0..192) hold bit decompositions of working variables a, b, c, e, f, g; registers
+// 192 and 193 hold working variables d and h as single field elements since their bits are never
+// needed; registers [194..706) hold bit decompositions of the next 16 words of the message
+// schedule; the remaining registers hold carries for modular additions.
+const A: usize = 0;
+const B: usize = A + WORD_BITS;
+const C: usize = B + WORD_BITS;
+const E: usize = C + WORD_BITS;
+const F: usize = E + WORD_BITS;
+const G: usize = F + WORD_BITS;
+const D: usize = G + WORD_BITS;
+const H: usize = D + 1;
+const W: usize = H + 1;
+const W_CARRY: usize = W + WINDOW_SIZE * WORD_BITS;
+const A_CARRY: usize = W_CARRY + 2;
+const E_CARRY: usize = A_CARRY + 3;
+const TRACE_WIDTH: usize = E_CARRY + 3;
+
+/// The trace contains one row per round, followed by the row holding the final state; since
+/// trace length must be a power of two, the remaining rows are filled with random values.
+const TRACE_LENGTH: usize = NUM_ROUNDS * 2;
+
+// SHA-256 PREIMAGE AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub digest: [u8; 32],
+    pub message_len: usize,
+}
+
+impl Serializable for PublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write(&self.digest);
+        target.write_u64(self.message_len as u64);
+    }
+}
+
+pub struct Sha256Air {
+    context: ComputationContext,
+    digest: [u8; 32],
+    message_len: usize,
+}
+
+impl Air for Sha256Air {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        assert_eq!(TRACE_LENGTH, trace_info.length);
+        assert!(
+            pub_inputs.message_len <= MAX_MESSAGE_LEN,
+            "message length cannot exceed {} bytes, but was {}",
+            MAX_MESSAGE_LEN,
+            pub_inputs.message_len
+        );
+
+        // the order of degrees must match the order in which constraints are evaluated; all
+        // constraints, except for the binary constraints, are enforced only on round steps
+        let mut degrees = Vec::new();
+        for _ in get_bit_registers() {
+            degrees.push(TransitionConstraintDegree::new(2));
+        }
+        for _ in 0..(4 * WORD_BITS + 2 + (WINDOW_SIZE - 1) * WORD_BITS) {
+            degrees.push(TransitionConstraintDegree::with_cycles(
+                1,
+                vec![TRACE_LENGTH],
+            ));
+        }
+        for _ in 0..3 {
+            degrees.push(TransitionConstraintDegree::with_cycles(
+                3,
+                vec![TRACE_LENGTH],
+            ));
+        }
+
+        let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options);
+        Sha256Air {
+            context,
+            digest: pub_inputs.digest,
+            message_len: pub_inputs.message_len,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        let round_constants = ROUND_CONSTANTS
+            .iter()
+            .map(|&k| BaseElement::from(k))
+            .collect();
+
+        let mut round_mask = vec![BaseElement::ZERO; TRACE_LENGTH];
+        round_mask[..NUM_ROUNDS].fill(BaseElement::ONE);
+
+        vec![round_constants, round_mask]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        let mut result = Vec::new();
+
+        // the compression function must start with the initial hash value
+        assert_state(&mut result, 0, INITIAL_HASH);
+
+        // the padding of the message is fully defined by message length, and thus, all bytes
+        // of the message block following the message itself are public
+        let block = pad_message(&vec![0; self.message_len]);
+        for (i, &byte) in block.iter().enumerate().skip(self.message_len) {
+            // words are big-endian; so, the first byte of a word occupies its most significant bits
+            let first_bit = W + (i / 4) * WORD_BITS + (3 - i % 4) * 8;
+            for j in 0..8 {
+                result.push(Assertion::single(first_bit + j, 0, get_bit(byte as u32, j)));
+            }
+        }
+
+        // after the last round, adding the initial hash value to the state must yield the digest
+        let mut state = [0u32; 8];
+        for (i, word) in self.digest.chunks(4).enumerate() {
+            let digest_word = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+            state[i] = digest_word.wrapping_sub(INITIAL_HASH[i]);
+        }
+        assert_state(&mut result, NUM_ROUNDS, state);
+
+        result
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        // expected state width is 714 field elements
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        let round_constant = periodic_values[0];
+        let round_flag = periodic_values[1];
+        let mut i = 0;
+
+        // all registers, except for registers holding d and h, contain bits
+        for register in get_bit_registers() {
+            result[i] = is_binary(current[register]);
+            i += 1;
+        }
+
+        // working variables shift by one position with every round: b <- a, c <- b, d <- c,
+        // f <- e, g <- f, and h <- g
+        for &(from, to) in [(A, B), (B, C), (E, F), (F, G)].iter() {
+            for j in 0..WORD_BITS {
+                result[i] = round_flag * are_equal(next[to + j], current[from + j]);
+                i += 1;
+            }
+        }
+        result[i] = round_flag * are_equal(next[D], word(&current[C..]));
+        result[i + 1] = round_flag * are_equal(next[H], word(&current[G..]));
+        i += 2;
+
+        // the message schedule window shifts by one word with every round
+        for j in 0..((WINDOW_SIZE - 1) * WORD_BITS) {
+            result[i] = round_flag * are_equal(next[W + j], current[W + WORD_BITS + j]);
+            i += 1;
+        }
+
+        // the new word of the message schedule is computed as:
+        // w[16] = sigma1(w[14]) + w[9] + sigma0(w[1]) + w[0]
+        let w = |j: usize| &current[(W + j * WORD_BITS)..(W + (j + 1) * WORD_BITS)];
+        let w_new = word(&next[(W + (WINDOW_SIZE - 1) * WORD_BITS)..]);
+        let w_carry = current[W_CARRY] + current[W_CARRY + 1].double();
+        let w_sum = small_sigma1(w(14)) + word(w(9)) + small_sigma0(w(1)) + word(w(0));
+        result[i] = round_flag * are_equal(w_new + w_carry * two_pow_32(), w_sum);
+        i += 1;
+
+        // a round of compression function computes new values of a and e as:
+        // t1 = h + Sigma1(e) + ch(e, f, g) + k + w[0]
+        // t2 = Sigma0(a) + maj(a, b, c)
+        // a = t1 + t2
+        // e = d + t1
+        let a = &current[A..B];
+        let b = &current[B..C];
+        let c = &current[C..E];
+        let e = &current[E..F];
+        let f = &current[F..G];
+        let g = &current[G..D];
+        let t1 = current[H] + big_sigma1(e) + ch(e, f, g) + round_constant + word(w(0));
+        let t2 = big_sigma0(a) + maj(a, b, c);
+
+        let a_carry = carry(&current[A_CARRY..(A_CARRY + 3)]);
+        let a_new = word(&next[A..]) + a_carry * two_pow_32();
+        result[i] = round_flag * are_equal(a_new, t1 + t2);
+
+        let e_carry = carry(&current[E_CARRY..(E_CARRY + 3)]);
+        let e_new = word(&next[E..]) + e_carry * two_pow_32();
+        result[i + 1] = round_flag * are_equal(e_new, current[D] + t1);
+    }
+}
+
+// TRACE GENERATOR
+// ================================================================================================
+
+pub fn build_trace(message: &[u8]) -> ExecutionTrace<BaseElement> {
+    assert!(
+        message.len() <= MAX_MESSAGE_LEN,
+        "message length cannot exceed {} bytes, but was {}",
+        MAX_MESSAGE_LEN,
+        message.len()
+    );
+
+    // expand the message schedule far enough to cover the window at every round step, and
+    // at the step holding the final state
+    let block = pad_message(message);
+    let mut schedule = block
+        .chunks(4)
+        .map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]))
+        .collect::<Vec<_>>();
+    let mut schedule_carries = Vec::with_capacity(NUM_ROUNDS + 1);
+    for t in 0..=NUM_ROUNDS {
+        let sum = sigma1(schedule[t + 14]) as u64
+            + schedule[t + 9] as u64
+            + sigma0(schedule[t + 1]) as u64
+            + schedule[t] as u64;
+        schedule.push(sum as u32);
+        schedule_carries.push((sum >> 32) as u32);
+    }
+
+    let mut trace = ExecutionTrace::new(TRACE_WIDTH, TRACE_LENGTH);
+    let mut state = INITIAL_HASH;
+    let mut row = vec![BaseElement::ZERO; TRACE_WIDTH];
+    for t in 0..=NUM_ROUNDS {
+        let [a, b, c, d, e, f, g, h] = state;
+
+        // compute the round while keeping track of carries; the round constant is irrelevant
+        // for the step holding the final state since no round is applied at this step
+        let t1 = h as u64
+            + big_sigma1_u32(e) as u64
+            + ((e & f) ^ (!e & g)) as u64
+            + ROUND_CONSTANTS[t % NUM_ROUNDS] as u64
+            + schedule[t] as u64;
+        let t2 = big_sigma0_u32(a) as u64 + ((a & b) ^ (a & c) ^ (b & c)) as u64;
+        let a_sum = t1 + t2;
+        let e_sum = d as u64 + t1;
+
+        // record the current state of the computation
+        set_bits(&mut row[A..], a, WORD_BITS);
+        set_bits(&mut row[B..], b, WORD_BITS);
+        set_bits(&mut row[C..], c, WORD_BITS);
+        set_bits(&mut row[E..], e, WORD_BITS);
+        set_bits(&mut row[F..], f, WORD_BITS);
+        set_bits(&mut row[G..], g, WORD_BITS);
+        row[D] = BaseElement::from(d);
+        row[H] = BaseElement::from(h);
+        for j in 0..WINDOW_SIZE {
+            set_bits(&mut row[(W + j * WORD_BITS)..], schedule[t + j], WORD_BITS);
+        }
+        set_bits(&mut row[W_CARRY..], schedule_carries[t], 2);
+        set_bits(&mut row[A_CARRY..], (a_sum >> 32) as u32, 3);
+        set_bits(&mut row[E_CARRY..], (e_sum >> 32) as u32, 3);
+        trace.update_row(t, &row);
+
+        state = [a_sum as u32, a, b, c, e_sum as u32, e, f, g];
+    }
+
+    // fill the remaining rows with random values; this ensures that constraints which copy
+    // values between rows do not hold over the entire trace, and thus, have stable degrees
+    let num_random_rows = TRACE_LENGTH - NUM_ROUNDS - 1;
+    let random_values = BaseElement::prng_vector([7; 32], num_random_rows * TRACE_WIDTH);
+    for (t, values) in random_values.chunks(TRACE_WIDTH).enumerate() {
+        for register in get_bit_registers() {
+            row[register] = BaseElement::from((values[register].as_int() & 1) as u32);
+        }
+        row[D] = BaseElement::from(values[D].as_int() as u32);
+        row[H] = BaseElement::from(values[H].as_int() as u32);
+        trace.update_row(NUM_ROUNDS + 1 + t, &row);
+    }
+
+    trace
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Pads the message into a single 64-byte block as specified by SHA-256.
+fn pad_message(message: &[u8]) -> [u8; 64] {
+    let mut block = [0u8; 64];
+    block[..message.len()].copy_from_slice(message);
+    block[message.len()] = 0x80;
+    block[56..].copy_from_slice(&((message.len() as u64) * 8).to_be_bytes());
+    block
+}
+
+/// Returns register indexes of all registers which must contain binary values.
+fn get_bit_registers() -> impl Iterator<Item = usize> {
+    (A..D).chain(W..TRACE_WIDTH)
+}
+
+/// Adds assertions for all working variables at the specified step.
+fn assert_state(result: &mut Vec<Assertion<BaseElement>>, step: usize, state: [u32; 8]) {
+    let [a, b, c, d, e, f, g, h] = state;
+    for &(register, value) in [(A, a), (B, b), (C, c), (E, e), (F, f), (G, g)].iter() {
+        for j in 0..WORD_BITS {
+            result.push(Assertion::single(register + j, step, get_bit(value, j)));
+        }
+    }
+    result.push(Assertion::single(D, step, BaseElement::from(d)));
+    result.push(Assertion::single(H, step, BaseElement::from(h)));
+}
+
+fn get_bit(value: u32, index: usize) -> BaseElement {
+    BaseElement::from((value >> index) & 1)
+}
+
+fn set_bits(target: &mut [BaseElement], value: u32, num_bits: usize) {
+    for (j, bit) in target[..num_bits].iter_mut().enumerate() {
+        *bit = get_bit(value, j);
+    }
+}
+
+// NATIVE BIT OPERATIONS
+// ------------------------------------------------------------------------------------------------
+
+fn big_sigma0_u32(x: u32) -> u32 {
+    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+}
+
+fn big_sigma1_u32(x: u32) -> u32 {
+    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+}
+
+fn sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+fn sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+// CONSTRAINT EVALUATION HELPERS
+// ------------------------------------------------------------------------------------------------
+
+/// Composes a word from the first 32 bits in the provided slice.
+fn word<E: FieldElement>(bits: &[E]) -> E {
+    bits[..WORD_BITS]
+        .iter()
+        .rev()
+        .fold(E::ZERO, |acc, &bit| acc.double() + bit)
+}
+
+/// Composes a carry value from the provided bits.
+fn carry<E: FieldElement>(bits: &[E]) -> E {
+    bits.iter()
+        .rev()
+        .fold(E::ZERO, |acc, &bit| acc.double() + bit)
+}
+
+fn two_pow_32<E: FieldElement + From<BaseElement>>() -> E {
+    E::from(BaseElement::from(u32::MAX)) + E::ONE
+}
+
+fn xor<E: FieldElement>(a: E, b: E) -> E {
+    a + b - (a * b).double()
+}
+
+/// Computes a word in which every bit is an XOR of bits at the specified positions of `x`;
+/// positions are rotations to the right, unless `shift` is set, in which case the last position
+/// is a logical shift to the right.
+fn xor_rotations<E: FieldElement>(x: &[E], positions: [usize; 3], shift: bool) -> E {
+    let mut bits = [E::ZERO; WORD_BITS];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        let r0 = x[(i + positions[0]) % WORD_BITS];
+        let r1 = x[(i + positions[1]) % WORD_BITS];
+        let r2 = if !shift {
+            x[(i + positions[2]) % WORD_BITS]
+        } else if i + positions[2] < WORD_BITS {
+            x[i + positions[2]]
+        } else {
+            E::ZERO
+        };
+        *bit = xor(xor(r0, r1), r2);
+    }
+    word(&bits)
+}
+
+fn big_sigma0<E: FieldElement>(x: &[E]) -> E {
+    xor_rotations(x, [2, 13, 22], false)
+}
+
+fn big_sigma1<E: FieldElement>(x: &[E]) -> E {
+    xor_rotations(x, [6, 11, 25], false)
+}
+
+fn small_sigma0<E: FieldElement>(x: &[E]) -> E {
+    xor_rotations(x, [7, 18, 3], true)
+}
+
+fn small_sigma1<E: FieldElement>(x: &[E]) -> E {
+    xor_rotations(x, [17, 19, 10], true)
+}
+
+fn ch<E: FieldElement>(e: &[E], f: &[E], g: &[E]) -> E {
+    let mut bits = [E::ZERO; WORD_BITS];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        *bit = e[i] * f[i] + (E::ONE - e[i]) * g[i];
+    }
+    word(&bits)
+}
+
+fn maj<E: FieldElement>(a: &[E], b: &[E], c: &[E]) -> E {
+    let mut bits = [E::ZERO; WORD_BITS];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        let ab = a[i] * b[i];
+        *bit = ab + a[i] * c[i] + b[i] * c[i] - (ab * c[i]).double();
+    }
+    word(&bits)
+}
+
+// SHA-256 CONSTANTS
+// ================================================================================================
+
+const INITIAL_HASH: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const ROUND_CONSTANTS: [u32; NUM_ROUNDS] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
@@ -0,0 +1,110 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Example, ExampleOptions};
+use log::debug;
+use prover::{self, math::utils::log2_unchecked, ProofOptions, StarkProof};
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{build_trace, PublicInputs, Sha256Air, MAX_MESSAGE_LEN};
+
+#[cfg(test)]
+mod tests;
+
+// SHA-256 PREIMAGE EXAMPLE
+// ================================================================================================
+
+pub fn get_example(options: ExampleOptions, message: String) -> Box<dyn Example> {
+    Box::new(Sha256Example::new(
+        message.into_bytes(),
+        options.to_proof_options(28, 32),
+    ))
+}
+
+/// Proves knowledge of a message hashing to a public SHA-256 digest. The message must fit into a
+/// single SHA-256 block, and its length is public since it determines the padding of the block.
+pub struct Sha256Example {
+    options: ProofOptions,
+    message: Vec<u8>,
+    digest: [u8; 32],
+}
+
+impl Sha256Example {
+    pub fn new(message: Vec<u8>, options: ProofOptions) -> Sha256Example {
+        assert!(
+            message.len() <= MAX_MESSAGE_LEN,
+            "message length cannot exceed {} bytes, but was {}",
+            MAX_MESSAGE_LEN,
+            message.len()
+        );
+
+        // compute the digest using an external implementation of SHA-256
+        let now = Instant::now();
+        let mut digest = [0; 32];
+        digest.copy_from_slice(&Sha256::digest(&message));
+        debug!(
+            "Computed SHA-256 digest {} of a {}-byte message in {} ms",
+            hex::encode(digest),
+            message.len(),
+            now.elapsed().as_millis(),
+        );
+
+        Sha256Example {
+            options,
+            message,
+            digest,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for Sha256Example {
+    fn prove(&self) -> StarkProof {
+        // generate the execution trace
+        debug!(
+            "Generating proof for knowledge of a SHA-256 preimage\n\
+            ---------------------"
+        );
+        let now = Instant::now();
+        let trace = build_trace(&self.message);
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            log2_unchecked(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        let pub_inputs = PublicInputs {
+            digest: self.digest,
+            message_len: self.message.len(),
+        };
+        prover::prove::<Sha256Air>(trace, pub_inputs, self.options.clone()).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            digest: self.digest,
+            message_len: self.message.len(),
+        };
+        verifier::verify::<Sha256Air>(proof, pub_inputs)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let mut digest = self.digest;
+        digest[0] ^= 1;
+        let pub_inputs = PublicInputs {
+            digest,
+            message_len: self.message.len(),
+        };
+        verifier::verify::<Sha256Air>(proof, pub_inputs)
+    }
+}
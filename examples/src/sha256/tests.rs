@@ -0,0 +1,49 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use prover::{FieldExtension, HashFunction, ProofOptions};
+
+#[test]
+fn sha256_test_basic_proof_verification() {
+    let sha = Box::new(super::Sha256Example::new(
+        b"abc".to_vec(),
+        build_options(false),
+    ));
+    crate::tests::test_basic_proof_verification(sha);
+}
+
+#[test]
+fn sha256_test_basic_proof_verification_extension() {
+    let sha = Box::new(super::Sha256Example::new(
+        b"abc".to_vec(),
+        build_options(true),
+    ));
+    crate::tests::test_basic_proof_verification(sha);
+}
+
+#[test]
+fn sha256_test_basic_proof_verification_fail() {
+    let sha = Box::new(super::Sha256Example::new(
+        b"abc".to_vec(),
+        build_options(false),
+    ));
+    crate::tests::test_basic_proof_verification_fail(sha);
+}
+
+#[test]
+fn sha256_test_max_length_message() {
+    let message = vec![0xab; super::MAX_MESSAGE_LEN];
+    let sha = Box::new(super::Sha256Example::new(message, build_options(false)));
+    crate::tests::test_basic_proof_verification(sha);
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, extension)
+}
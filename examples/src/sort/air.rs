@@ -0,0 +1,222 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::{are_equal, is_binary};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, AuxSegmentBuilder, ByteWriter, ComputationContext, EvaluationFrame,
+    ExecutionTrace, ProofOptions, Serializable, TraceInfo, TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bits into which the difference between adjacent values of the output list is
+/// decomposed; all values of the lists must fit into this many bits.
+pub const NUM_BITS: usize = 32;
+
+/// Register holding the input list.
+const INPUT: usize = 0;
+/// Register holding the output list.
+const OUTPUT: usize = 1;
+/// First of the registers holding binary decomposition of the difference between the next and
+/// the current value of the output list, least significant bit first.
+const BITS: usize = 2;
+/// Register holding the running product of the permutation argument; this is the only register
+/// of the auxiliary trace segment.
+const PRODUCT: usize = BITS + NUM_BITS;
+
+pub const MAIN_TRACE_WIDTH: usize = PRODUCT;
+pub const TRACE_WIDTH: usize = PRODUCT + 1;
+
+// SORT AIR
+// ================================================================================================
+
+/// Public inputs of the sort computation: the smallest and the largest values of the lists.
+/// The lists themselves are committed to in the execution trace.
+pub struct PublicInputs {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Serializable for PublicInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.min);
+        target.write_u32(self.max);
+    }
+}
+
+pub struct SortAir {
+    context: ComputationContext,
+    min: BaseElement,
+    max: BaseElement,
+    alpha: BaseElement,
+}
+
+impl Air for SortAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        let mut degrees = vec![
+            TransitionConstraintDegree::new(2),
+            TransitionConstraintDegree::new(1),
+        ];
+        for _ in 0..NUM_BITS {
+            degrees.push(TransitionConstraintDegree::new(2));
+        }
+
+        // bits of the decomposition are constrained within a single state; the running product
+        // is built from a random element drawn after the main segment of the trace is committed
+        let local_registers = (BITS..PRODUCT).collect::<Vec<_>>();
+        let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options)
+            .with_local_registers(&local_registers)
+            .with_aux_segment(TRACE_WIDTH - MAIN_TRACE_WIDTH, 1);
+
+        SortAir {
+            context,
+            min: BaseElement::from(pub_inputs.min),
+            max: BaseElement::from(pub_inputs.max),
+            alpha: BaseElement::ZERO,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn set_aux_rand_elements(&mut self, elements: &[Self::BaseElement]) {
+        self.alpha = elements[0];
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        // transition constraints cover all but the last step; both lists are padded with the
+        // largest value, and thus, the last pair of values cancels out in the running product
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(INPUT, last_step, self.max),
+            Assertion::single(OUTPUT, 0, self.min),
+            Assertion::single(OUTPUT, last_step, self.max),
+            Assertion::single(PRODUCT, 0, BaseElement::ONE),
+            Assertion::single(PRODUCT, last_step, BaseElement::ONE),
+        ]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        // expected state width is 3 + NUM_BITS field elements
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        let alpha = E::from(self.alpha);
+
+        // permutation argument: the running product accumulates (alpha - x) / (alpha - y) for
+        // each pair of input and output values; the product over all steps is 1 only if the
+        // output list is a permutation of the input list
+        result[0] = are_equal(
+            next[PRODUCT] * (alpha - current[OUTPUT]),
+            current[PRODUCT] * (alpha - current[INPUT]),
+        );
+
+        // range check: the difference between adjacent values of the output list must be equal
+        // to its binary decomposition, and thus, must be a non-negative number of NUM_BITS bits
+        let bits = &current[BITS..PRODUCT];
+        result[1] = are_equal(next[OUTPUT] - current[OUTPUT], combine_bits(bits));
+        for (i, &bit) in bits.iter().enumerate() {
+            result[2 + i] = is_binary(bit);
+        }
+    }
+}
+
+// TRACE GENERATOR
+// ================================================================================================
+
+/// Builds the main segment of the execution trace for sorting the `input` list into the
+/// `output` list.
+///
+/// Both lists are padded with the largest value of the output list to the next power of two
+/// which leaves at least one row of padding.
+pub fn build_trace(input: &[u32], output: &[u32]) -> ExecutionTrace<BaseElement> {
+    assert_eq!(
+        input.len(),
+        output.len(),
+        "input and output lists must have the same length"
+    );
+    let trace_length = get_trace_length(input.len());
+    let max = *output.last().expect("lists must not be empty");
+    let mut trace = ExecutionTrace::new(MAIN_TRACE_WIDTH, trace_length);
+
+    // record both lists and decompositions of differences between adjacent output values
+    let mut row = vec![BaseElement::ZERO; MAIN_TRACE_WIDTH];
+    for step in 0..trace_length - 1 {
+        let x = input.get(step).copied().unwrap_or(max);
+        let y = output.get(step).copied().unwrap_or(max);
+        let next_y = output.get(step + 1).copied().unwrap_or(max);
+        row[INPUT] = BaseElement::from(x);
+        row[OUTPUT] = BaseElement::from(y);
+        let diff = next_y.wrapping_sub(y);
+        for (i, bit) in row[BITS..].iter_mut().enumerate() {
+            *bit = BaseElement::from((diff >> i) & 1);
+        }
+        trace.update_row(step, &row);
+    }
+
+    // the decomposition in the last row is not constrained, and thus, is filled with random
+    // values; this ensures that constraint degrees are stable regardless of the list values
+    let mut last_row = BaseElement::prng_vector([5; 32], MAIN_TRACE_WIDTH);
+    last_row[INPUT] = BaseElement::from(max);
+    last_row[OUTPUT] = BaseElement::from(max);
+    trace.update_row(trace_length - 1, &last_row);
+
+    trace
+}
+
+/// Builds the auxiliary segment of the execution trace which holds the running product of the
+/// permutation argument.
+pub struct ProductBuilder;
+
+impl AuxSegmentBuilder<BaseElement> for ProductBuilder {
+    fn build_aux_segment(
+        &self,
+        main: &ExecutionTrace<BaseElement>,
+        elements: &[BaseElement],
+    ) -> ExecutionTrace<BaseElement> {
+        let alpha = elements[0];
+        let mut product = BaseElement::ONE;
+        let mut segment = ExecutionTrace::new(TRACE_WIDTH - MAIN_TRACE_WIDTH, main.len());
+        for step in 0..main.len() {
+            segment.set(0, step, product);
+            let x = main.get(INPUT, step);
+            let y = main.get(OUTPUT, step);
+            product *= (alpha - x) / (alpha - y);
+        }
+        segment
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the length of the execution trace for lists of the specified length.
+pub fn get_trace_length(list_length: usize) -> usize {
+    (list_length + 1)
+        .next_power_of_two()
+        .max(ComputationContext::MIN_TRACE_LENGTH)
+}
+
+/// Combines binary decomposition of a value (least significant bit first) into the value.
+fn combine_bits<E: FieldElement>(bits: &[E]) -> E {
+    bits.iter()
+        .rev()
+        .fold(E::ZERO, |acc, &bit| acc * E::from(2u8) + bit)
+}
@@ -0,0 +1,124 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Example, ExampleOptions};
+use log::debug;
+use prover::{
+    self,
+    math::{
+        field::{f128::BaseElement, FieldElement, StarkField},
+        utils::log2_unchecked,
+    },
+    ProofOptions, StarkProof,
+};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{build_trace, ProductBuilder, PublicInputs, SortAir};
+
+#[cfg(test)]
+mod tests;
+
+// SORT EXAMPLE
+// ================================================================================================
+
+pub fn get_example(options: ExampleOptions, list_length: usize) -> Box<dyn Example> {
+    Box::new(SortExample::new(
+        list_length,
+        options.to_proof_options(28, 16),
+    ))
+}
+
+/// Proves that an output list is a sorted permutation of an input list. Both lists are committed
+/// to in the main segment of the execution trace, and only the smallest and the largest values
+/// of the lists are public. The permutation is checked with a running product argument which is
+/// recorded in an auxiliary trace segment, so that its challenge is drawn after both lists are
+/// committed to; sortedness is checked by decomposing the difference between adjacent values of
+/// the output list into bits.
+pub struct SortExample {
+    options: ProofOptions,
+    input: Vec<u32>,
+    output: Vec<u32>,
+}
+
+impl SortExample {
+    pub fn new(list_length: usize, options: ProofOptions) -> SortExample {
+        assert!(list_length > 0, "list length must be greater than zero");
+
+        // generate a pseudo-random input list and sort it
+        let now = Instant::now();
+        let input = BaseElement::prng_vector([7; 32], list_length)
+            .into_iter()
+            .map(|value| value.as_int() as u32)
+            .collect::<Vec<_>>();
+        let mut output = input.clone();
+        output.sort_unstable();
+        debug!(
+            "Sorted a list of {} values in {} ms",
+            list_length,
+            now.elapsed().as_millis()
+        );
+
+        SortExample {
+            options,
+            input,
+            output,
+        }
+    }
+
+    /// Returns public inputs of the computation: the smallest and the largest values of the
+    /// sorted list.
+    fn get_pub_inputs(&self) -> PublicInputs {
+        PublicInputs {
+            min: self.output[0],
+            max: self.output[self.output.len() - 1],
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for SortExample {
+    fn prove(&self) -> StarkProof {
+        debug!(
+            "Generating proof for sorting a list of {} values\n\
+            ---------------------",
+            self.input.len()
+        );
+
+        // generate execution trace
+        let now = Instant::now();
+        let trace = build_trace(&self.input, &self.output);
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            log2_unchecked(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof; the running product is built by the prover once the main segment
+        // of the trace is committed to
+        let pub_inputs = self.get_pub_inputs();
+        prover::Prover::<SortAir>::new(self.options.clone())
+            .with_aux_segment(&ProductBuilder)
+            .prove(trace, pub_inputs)
+            .unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        verifier::verify::<SortAir>(proof, self.get_pub_inputs())
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            max: self.get_pub_inputs().max + 1,
+            ..self.get_pub_inputs()
+        };
+        verifier::verify::<SortAir>(proof, pub_inputs)
+    }
+}
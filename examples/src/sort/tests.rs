@@ -0,0 +1,111 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::air::{build_trace, ProductBuilder, PublicInputs, SortAir};
+use prover::{
+    math::field::f128::BaseElement, Air, AuxSegmentBuilder, FieldExtension, HashFunction,
+    ProofOptions, ProverError, TraceInfo,
+};
+use verifier::VerifierError;
+
+#[test]
+fn sort_test_basic_proof_verification() {
+    let sort = Box::new(super::SortExample::new(64, build_options(false)));
+    crate::tests::test_basic_proof_verification(sort);
+}
+
+#[test]
+fn sort_test_basic_proof_verification_extension() {
+    let sort = Box::new(super::SortExample::new(64, build_options(true)));
+    crate::tests::test_basic_proof_verification(sort);
+}
+
+#[test]
+fn sort_test_basic_proof_verification_fail() {
+    let sort = Box::new(super::SortExample::new(50, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(sort);
+}
+
+#[test]
+fn sort_test_invalid_traces() {
+    let input = vec![5, 3, 8, 1, 9, 2, 7, 4];
+
+    // a sorted permutation of the input satisfies the AIR
+    let output = vec![1, 2, 3, 4, 5, 7, 8, 9];
+    assert!(check_trace(&input, &output).is_ok());
+
+    // a sorted list which is not a permutation of the input violates the permutation argument
+    let output = vec![1, 2, 3, 4, 5, 7, 8, 10];
+    assert!(check_trace(&input, &output).is_err());
+
+    // a permutation of the input which is not sorted violates the range check
+    let output = vec![1, 2, 3, 5, 4, 7, 8, 9];
+    assert!(check_trace(&input, &output).is_err());
+}
+
+#[test]
+fn sort_test_aux_segment_commitment() {
+    let input = vec![5, 3, 8, 1, 9, 2, 7, 4];
+    let output = vec![1, 2, 3, 4, 5, 7, 8, 9];
+    let build_pub_inputs = || PublicInputs { min: 1, max: 9 };
+    let options = build_options(false);
+
+    // the running product cannot be built without a builder for the auxiliary segment
+    let trace = build_trace(&input, &output);
+    let result = prover::prove::<SortAir>(trace.clone(), build_pub_inputs(), options.clone());
+    assert!(matches!(
+        result,
+        Err(ProverError::AuxSegmentBuilderNotProvided(1))
+    ));
+
+    // the proof commits to the auxiliary segment, and the challenge for the permutation argument
+    // is bound to this commitment
+    let proof = prover::Prover::<SortAir>::new(options)
+        .with_aux_segment(&ProductBuilder)
+        .prove(trace, build_pub_inputs())
+        .unwrap();
+    assert!(proof.commitments.aux_segment_root.is_some());
+    assert!(proof.aux_segment_queries.is_some());
+    assert!(verifier::verify::<SortAir>(proof.clone(), build_pub_inputs()).is_ok());
+
+    let mut tampered = proof;
+    tampered.commitments.aux_segment_root = Some([0; 32]);
+    let result = verifier::verify::<SortAir>(tampered, build_pub_inputs());
+    assert!(matches!(
+        result,
+        Err(VerifierError::AuxSegmentQueryDoesNotMatchCommitment)
+    ));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn check_trace(input: &[u32], output: &[u32]) -> Result<(), ProverError> {
+    let pub_inputs = PublicInputs {
+        min: output[0],
+        max: output[output.len() - 1],
+    };
+    let main = build_trace(input, output);
+    let trace_info = TraceInfo {
+        length: main.len(),
+        meta: vec![],
+    };
+    let mut air = SortAir::new(trace_info, pub_inputs, build_options(false));
+
+    // any challenge which is not one of the list values is suitable for checking the trace
+    let elements = [BaseElement::from(1234567u32)];
+    air.set_aux_rand_elements(&elements);
+    let aux = ProductBuilder.build_aux_segment(&main, &elements);
+    main.append_segment(aux).check(&air)
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, extension)
+}
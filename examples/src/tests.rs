@@ -3,7 +3,213 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::Example;
+use crate::{Example, ExampleOptions};
+use prover::{
+    math::field::{bls12_381, bn254, f128::BaseElement, f31, f62, f64, FieldElement, StarkField},
+    Air, Assertion, ByteWriter, CoefficientScheme, ComputationContext, EvaluationFrame,
+    ExecutionTrace, FieldExtension, HashFunction, HashedPublicInputs, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+use structopt::StructOpt;
+use verifier::{Serializable, StarkProof, TextEncoding};
+
+#[test]
+fn example_options_to_proof_options() {
+    // when proof parameters are not specified, defaults provided by the example are used
+    let options = ExampleOptions::from_iter(&["winterfell", "fib"]);
+    let proof_options = options.to_proof_options(28, 16);
+    assert_eq!(28, proof_options.num_queries());
+    assert_eq!(16, proof_options.blowup_factor());
+    assert_eq!(16, proof_options.grinding_factor());
+    assert_eq!(None, proof_options.grinding_budget());
+    assert!(matches!(proof_options.hash_fn(), HashFunction::Blake3_256));
+    assert!(matches!(
+        proof_options.transcript_hash_fn(),
+        HashFunction::Blake3_256
+    ));
+    assert!(proof_options.field_extension().is_none());
+    assert_eq!(
+        CoefficientScheme::Independent,
+        proof_options.coefficient_scheme()
+    );
+
+    // all parameters can be overridden from the command line
+    let options = ExampleOptions::from_iter(&[
+        "winterfell",
+        "-q",
+        "42",
+        "-b",
+        "8",
+        "-g",
+        "0",
+        "--grinding_budget",
+        "200",
+        "-e",
+        "--hash",
+        "sha3",
+        "--transcript_hash",
+        "blake3",
+        "--coeff_powers",
+        "fib",
+    ]);
+    let proof_options = options.to_proof_options(28, 16);
+    assert_eq!(42, proof_options.num_queries());
+    assert_eq!(8, proof_options.blowup_factor());
+    assert_eq!(0, proof_options.grinding_factor());
+    assert_eq!(
+        Some(std::time::Duration::from_millis(200)),
+        proof_options.grinding_budget()
+    );
+    assert!(matches!(proof_options.hash_fn(), HashFunction::Sha3_256));
+    assert!(matches!(
+        proof_options.transcript_hash_fn(),
+        HashFunction::Blake3_256
+    ));
+    assert!(matches!(
+        proof_options.field_extension(),
+        FieldExtension::Quadratic
+    ));
+    assert_eq!(
+        CoefficientScheme::Powers,
+        proof_options.coefficient_scheme()
+    );
+
+    // cubic extension takes precedence over quadratic extension
+    let options = ExampleOptions::from_iter(&["winterfell", "-e", "--cubic", "fib"]);
+    assert!(matches!(
+        options.to_proof_options(28, 16).field_extension(),
+        FieldExtension::Cubic
+    ));
+
+    // quartic extension takes precedence over both of them
+    let options = ExampleOptions::from_iter(&["winterfell", "-e", "--cubic", "--quartic", "fib"]);
+    assert!(matches!(
+        options.to_proof_options(28, 16).field_extension(),
+        FieldExtension::Quartic
+    ));
+
+    // unsupported hash functions are rejected
+    assert!(ExampleOptions::from_iter_safe(&["winterfell", "--hash", "md5", "fib"]).is_err());
+}
+
+#[test]
+fn hashed_public_inputs_proof_verification() {
+    // public inputs of several kilobytes are bound to the proof via their digest
+    let inputs = ProgramInputs {
+        roots: (0..128u8).map(|i| [i; 32]).collect(),
+    };
+    let options = ProofOptions::new(28, 8, 0, HashFunction::Sha3_256, FieldExtension::None);
+    let pub_inputs = HashedPublicInputs::new(options.hash_fn(), &inputs);
+
+    let trace = build_digest_trace(&pub_inputs.to_elements(), 16);
+    let proof = prover::prove::<DigestAir>(trace, pub_inputs, options).unwrap();
+    verifier::verify_with_hashed_inputs::<DigestAir, _>(proof.clone(), &inputs).unwrap();
+    verifier::verify::<DigestAir>(proof.clone(), pub_inputs).unwrap();
+
+    // changing a single byte of the full inputs changes the digest
+    let mut wrong_inputs = inputs;
+    wrong_inputs.roots[100][0] ^= 1;
+    let result = verifier::verify_with_hashed_inputs::<DigestAir, _>(proof, &wrong_inputs);
+    assert!(result.is_err());
+}
+
+#[test]
+fn air_id_proof_verification() {
+    let options = ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, FieldExtension::None);
+    let pub_inputs = HashedPublicInputs::from_digest([7; 32]);
+    let trace = build_digest_trace(&pub_inputs.to_elements(), 16);
+
+    // a proof generated for an AIR with an identifier is accepted only by that AIR
+    let proof = prover::prove::<TaggedDigestAir>(trace.clone(), pub_inputs, options.clone());
+    let proof = proof.unwrap();
+    assert_eq!(Some(TAGGED_AIR_ID), proof.context.air_id);
+    verifier::verify::<TaggedDigestAir>(proof.clone(), pub_inputs).unwrap();
+    let result = verifier::verify::<DigestAir>(proof.clone(), pub_inputs);
+    assert!(result.is_err());
+
+    // the identifier cannot be stripped from the proof or replaced
+    let mut stripped_proof = proof.clone();
+    stripped_proof.context.air_id = None;
+    assert!(verifier::verify::<DigestAir>(stripped_proof, pub_inputs).is_err());
+    let mut replaced_proof = proof;
+    replaced_proof.context.air_id = Some([0; 32]);
+    assert!(verifier::verify::<TaggedDigestAir>(replaced_proof, pub_inputs).is_err());
+
+    // a proof generated for an AIR without an identifier is not accepted by a tagged AIR which
+    // shares trace dimensions with it
+    let proof = prover::prove::<DigestAir>(trace, pub_inputs, options).unwrap();
+    let result = verifier::verify::<TaggedDigestAir>(proof, pub_inputs);
+    assert!(result.is_err());
+}
+
+#[test]
+fn small_field_proof_verification() {
+    check_small_field_proof::<f31::BaseElement>();
+    check_small_field_proof::<f62::BaseElement>();
+    check_small_field_proof::<f64::BaseElement>();
+
+    // with enough queries, only the cubic extension of 62-bit and 64-bit fields reaches 128-bit
+    // conjectured security
+    assert!(check_extension_security::<f62::BaseElement>(FieldExtension::Quadratic) < 128);
+    assert_eq!(
+        128,
+        check_extension_security::<f62::BaseElement>(FieldExtension::Cubic)
+    );
+    assert!(check_extension_security::<f64::BaseElement>(FieldExtension::Quadratic) < 128);
+    assert_eq!(
+        128,
+        check_extension_security::<f64::BaseElement>(FieldExtension::Cubic)
+    );
+
+    // the 31-bit field requires a quartic extension to reach 100-bit conjectured security
+    assert!(check_extension_security::<f31::BaseElement>(FieldExtension::Cubic) < 100);
+    assert!(check_extension_security::<f31::BaseElement>(FieldExtension::Quartic) >= 100);
+}
+
+#[test]
+fn bn254_proof_verification() {
+    check_small_field_proof::<bn254::BaseElement>();
+
+    // the field is large enough for proofs to reach 128-bit conjectured security without
+    // extensions
+    assert_eq!(
+        128,
+        check_extension_security::<bn254::BaseElement>(FieldExtension::None)
+    );
+}
+
+#[test]
+fn bls12_381_proof_verification() {
+    check_small_field_proof::<bls12_381::BaseElement>();
+    assert_eq!(
+        128,
+        check_extension_security::<bls12_381::BaseElement>(FieldExtension::None)
+    );
+}
+
+#[test]
+fn verification_report() {
+    type B = f64::BaseElement;
+    let trace = build_fib_trace::<B>(64);
+    let result = trace.get(1, 63);
+    let options = ProofOptions::new(28, 8, 0, HashFunction::Sha3_256, FieldExtension::Quadratic);
+    let proof = prover::prove::<FibAir<B>>(trace, result, options).unwrap();
+
+    // the report describes the accepted proof
+    let report = verifier::verify_with_report::<FibAir<B>>(proof.clone(), result).unwrap();
+    assert_eq!(proof.security_level(true), report.conjectured_security);
+    assert_eq!(proof.security_level(false), report.proven_security);
+    assert_eq!(HashFunction::Sha3_256, report.hash_fn);
+    assert_eq!(HashFunction::Sha3_256, report.transcript_hash_fn);
+    assert_eq!(B::get_modulus_le_bytes(), report.field_modulus);
+    assert_eq!(64, report.field_modulus_bits);
+    assert_eq!(FieldExtension::Quadratic, report.field_extension);
+    assert_eq!(64, report.trace_length);
+
+    // no report is returned for a rejected proof
+    let wrong_result = result + B::ONE;
+    assert!(verifier::verify_with_report::<FibAir<B>>(proof, wrong_result).is_err());
+}
 
 pub fn test_basic_proof_verification(e: Box<dyn Example>) {
     let proof = e.prove();
@@ -15,3 +221,238 @@ pub fn test_basic_proof_verification_fail(e: Box<dyn Example>) {
     let verified = e.verify_with_wrong_inputs(proof);
     assert!(verified.is_err());
 }
+
+pub fn test_proof_determinism(e: Box<dyn Example + Sync>) {
+    // proving the same statement twice must result in identical proofs
+    let proof = e.prove().to_bytes();
+    assert_eq!(proof, e.prove().to_bytes());
+
+    // proofs must not depend on the number of threads used to generate them
+    #[cfg(feature = "concurrent")]
+    for &num_threads in [1, 2, 4].iter() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        let other_proof = pool.install(|| e.prove().to_bytes());
+        assert_eq!(proof, other_proof);
+    }
+}
+
+pub fn test_proof_text_encoding(e: Box<dyn Example>) {
+    // proofs decoded from text must be identical to the original proofs and must verify
+    let proof = e.prove();
+    let proof_bytes = proof.to_bytes();
+
+    let decoded = proof.to_hex().parse::<StarkProof>().unwrap();
+    assert_eq!(proof_bytes, decoded.to_bytes());
+    assert!(e.verify(decoded).is_ok());
+
+    let decoded = StarkProof::from_base64(&proof.to_base64()).unwrap();
+    assert_eq!(proof_bytes, decoded.to_bytes());
+    assert!(e.verify(decoded).is_ok());
+}
+
+// HASHED PUBLIC INPUTS
+// ================================================================================================
+
+struct ProgramInputs {
+    roots: Vec<[u8; 32]>,
+}
+
+impl Serializable for ProgramInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u64(self.roots.len() as u64);
+        for root in self.roots.iter() {
+            root.write_into(target);
+        }
+    }
+}
+
+/// Every register starts with an element of the public inputs digest and is incremented by one
+/// at every step.
+struct DigestAir {
+    context: ComputationContext,
+    digest: Vec<BaseElement>,
+}
+
+impl Air for DigestAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = HashedPublicInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: HashedPublicInputs, options: ProofOptions) -> Self {
+        let digest = pub_inputs.to_elements();
+        let degrees = vec![TransitionConstraintDegree::new(1); digest.len()];
+        DigestAir {
+            context: ComputationContext::new(digest.len(), trace_info.length, degrees, options),
+            digest,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        for (i, value) in result.iter_mut().enumerate() {
+            *value = frame.next[i] - frame.current[i] - E::ONE;
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        self.digest
+            .iter()
+            .enumerate()
+            .map(|(register, &value)| Assertion::single(register, 0, value))
+            .collect()
+    }
+}
+
+/// Same computation as [DigestAir], but the context of the AIR carries an identifier.
+struct TaggedDigestAir(DigestAir);
+
+const TAGGED_AIR_ID: [u8; 32] = [42; 32];
+
+impl Air for TaggedDigestAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = HashedPublicInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: HashedPublicInputs, options: ProofOptions) -> Self {
+        let mut air = DigestAir::new(trace_info, pub_inputs, options);
+        air.context = air.context.with_air_id(TAGGED_AIR_ID);
+        TaggedDigestAir(air)
+    }
+
+    fn context(&self) -> &ComputationContext {
+        self.0.context()
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        self.0.get_assertions()
+    }
+}
+
+fn build_digest_trace(digest: &[BaseElement], length: usize) -> ExecutionTrace<BaseElement> {
+    let mut trace = ExecutionTrace::new(digest.len(), length);
+    trace.fill(
+        |state| state.copy_from_slice(digest),
+        |_, state| {
+            for value in state.iter_mut() {
+                *value += BaseElement::ONE;
+            }
+        },
+    );
+    trace
+}
+
+// SMALL FIELDS
+// ================================================================================================
+
+/// Proves and verifies a Fibonacci computation over base field `B` with and without a field
+/// extension, and makes sure the proofs are rejected for a wrong result.
+fn check_small_field_proof<B: StarkField>() {
+    let trace = build_fib_trace::<B>(64);
+    let result = trace.get(1, 63);
+
+    let extensions = [
+        FieldExtension::None,
+        FieldExtension::Quadratic,
+        FieldExtension::Cubic,
+        FieldExtension::Quartic,
+    ];
+    for &extension in extensions.iter() {
+        let options = ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, extension);
+        let proof = prover::prove::<FibAir<B>>(trace.clone(), result, options).unwrap();
+        verifier::verify::<FibAir<B>>(proof.clone(), result).unwrap();
+
+        let wrong_result = result + B::ONE;
+        assert!(verifier::verify::<FibAir<B>>(proof, wrong_result).is_err());
+    }
+}
+
+/// Proves and verifies a Fibonacci computation over the specified extension of base field `B`
+/// with enough queries for the security level to be limited by the size of the field, and returns
+/// the conjectured security level of the proof.
+fn check_extension_security<B: StarkField>(extension: FieldExtension) -> u32 {
+    let trace = build_fib_trace::<B>(64);
+    let result = trace.get(1, 63);
+
+    let options = ProofOptions::new(48, 16, 0, HashFunction::Blake3_256, extension);
+    let proof = prover::prove::<FibAir<B>>(trace, result, options).unwrap();
+    let security_level = proof.security_level(true);
+    verifier::verify::<FibAir<B>>(proof, result).unwrap();
+    security_level
+}
+
+/// A Fibonacci sequence AIR (2 terms per step) which can be instantiated for any base field.
+struct FibAir<B: StarkField> {
+    context: ComputationContext,
+    result: B,
+}
+
+impl<B: StarkField> Air for FibAir<B> {
+    type BaseElement = B;
+    type PublicInputs = B;
+
+    fn new(trace_info: TraceInfo, result: B, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1); 2];
+        FibAir {
+            context: ComputationContext::new(2, trace_info.length, degrees, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<B>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<B>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, B::ONE),
+            Assertion::single(1, 0, B::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+fn build_fib_trace<B: StarkField>(length: usize) -> ExecutionTrace<B> {
+    let mut trace = ExecutionTrace::new(2, length);
+    trace.fill(
+        |state| {
+            state[0] = B::ONE;
+            state[1] = B::ONE;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+    trace
+}
@@ -6,7 +6,7 @@
 use prover::{
     math::{
         field::{f128::BaseElement, FieldElement, StarkField},
-        utils::read_elements_into_vec,
+        utils::read_elements_into_vec_unchecked,
     },
     ExecutionTrace,
 };
@@ -68,7 +68,7 @@ pub fn node_to_bytes(node: TreeNode) -> [u8; 32] {
 }
 
 pub fn bytes_to_node(bytes: [u8; 32]) -> TreeNode {
-    let elements = read_elements_into_vec(&bytes).unwrap();
+    let elements = read_elements_into_vec_unchecked(&bytes);
     (elements[0], elements[1])
 }
 
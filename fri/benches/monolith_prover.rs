@@ -8,7 +8,7 @@ use crypto::hash::Blake3_256;
 use math::{
     fft,
     field::{f128::BaseElement, FieldElement, StarkField},
-    utils::{get_power_series_with_offset, log2},
+    utils::{get_power_series_with_offset_unchecked, log2_unchecked},
 };
 use std::time::Duration;
 use winter_fri::{DefaultProverChannel, FriOptions, FriProver};
@@ -25,8 +25,8 @@ pub fn build_layers(c: &mut Criterion) {
     let options = FriOptions::new(BLOWUP_FACTOR, DOMAIN_OFFSET);
 
     for &domain_size in &BATCH_SIZES {
-        let g = BaseElement::get_root_of_unity(log2(domain_size));
-        let domain = get_power_series_with_offset(g, BaseElement::GENERATOR, domain_size);
+        let g = BaseElement::get_root_of_unity(log2_unchecked(domain_size)).unwrap();
+        let domain = get_power_series_with_offset_unchecked(g, BaseElement::GENERATOR, domain_size);
         let evaluations = build_evaluations(domain_size);
 
         fri_group.bench_with_input(
@@ -6,7 +6,7 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use math::{
     field::{f128::BaseElement, FieldElement, StarkField},
-    utils::{get_power_series, log2},
+    utils::{get_power_series_unchecked, log2_unchecked},
 };
 use winter_fri::folding::quartic::{self, to_quartic_vec};
 
@@ -34,8 +34,8 @@ criterion_main!(quartic_group);
 // ================================================================================================
 
 fn build_coordinate_batches(batch_size: usize) -> (Vec<[BaseElement; 4]>, Vec<[BaseElement; 4]>) {
-    let r = BaseElement::get_root_of_unity(log2(batch_size));
-    let xs = to_quartic_vec(get_power_series(r, batch_size));
+    let r = BaseElement::get_root_of_unity(log2_unchecked(batch_size)).unwrap();
+    let xs = to_quartic_vec(get_power_series_unchecked(r, batch_size));
     let ys = to_quartic_vec(BaseElement::prng_vector([1; 32], batch_size));
     (xs, ys)
 }
@@ -5,7 +5,7 @@
 
 use super::FOLDING_FACTOR;
 use crypto::HashFunction;
-use math::field::{FieldElement, StarkField};
+use math::field::{ByteEncoding, FieldElement, StarkField};
 use rayon::prelude::*;
 use utils::uninit_vector;
 
@@ -82,16 +82,17 @@ pub fn to_quartic_vec<E: FieldElement>(vector: Vec<E>) -> Vec<[E; FOLDING_FACTOR
 pub fn hash_values<E: FieldElement>(
     values: &[[E; FOLDING_FACTOR]],
     hash: HashFunction,
+    encoding: ByteEncoding,
 ) -> Vec<[u8; 32]> {
     if values.len() <= MIN_CONCURRENT_DOMAIN {
-        super::hash_values(values, hash)
+        super::hash_values(values, hash, encoding)
     } else {
         let mut result: Vec<[u8; 32]> = uninit_vector(values.len());
         result
             .par_iter_mut()
             .zip(values.par_iter())
             .for_each(|(r, v)| {
-                hash(E::elements_as_bytes(v), r);
+                hash(&encoding.encode_elements(v), r);
             });
         result
     }
@@ -5,7 +5,7 @@
 
 use crypto::HashFunction;
 use math::{
-    field::{FieldElement, StarkField},
+    field::{ByteEncoding, FieldElement, StarkField},
     utils::batch_inversion,
 };
 use utils::{group_vector_elements, uninit_vector};
@@ -85,11 +85,16 @@ pub fn to_quartic_vec<E: FieldElement>(vector: Vec<E>) -> Vec<[E; 4]> {
     group_vector_elements::<E, 4>(vector)
 }
 
-/// Computes hashes for all quartic elements using the specified hash function.
-pub fn hash_values<E: FieldElement>(values: &[[E; 4]], hash: HashFunction) -> Vec<[u8; 32]> {
+/// Computes hashes for all quartic elements encoded according to the `encoding` using the
+/// specified hash function.
+pub fn hash_values<E: FieldElement>(
+    values: &[[E; 4]],
+    hash: HashFunction,
+    encoding: ByteEncoding,
+) -> Vec<[u8; 32]> {
     let mut result: Vec<[u8; 32]> = uninit_vector(values.len());
     for (r, v) in result.iter_mut().zip(values) {
-        hash(E::elements_as_bytes(v), r);
+        hash(&encoding.encode_elements(v), r);
     }
     result
 }
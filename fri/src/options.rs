@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use math::field::StarkField;
+use math::field::{ByteEncoding, StarkField};
 
 // CONSTANTS
 // ================================================================================================
@@ -12,6 +12,10 @@ use math::field::StarkField;
 pub const MAX_REMAINDER_LENGTH: usize = 256;
 pub const FOLDING_FACTOR: usize = 4;
 
+/// Largest degree bound (i.e., degree + 1) of the remainder polynomial which can be requested via
+/// [FriOptions::with_max_remainder_degree()].
+pub const MAX_REMAINDER_DEGREE_BOUND: usize = 4096;
+
 // FRI OPTIONS
 // ================================================================================================
 
@@ -21,6 +25,7 @@ pub struct FriOptions<B: StarkField> {
     folding_factor: usize,
     max_remainder_length: usize,
     blowup_factor: usize,
+    byte_encoding: ByteEncoding,
 }
 
 impl<B: StarkField> FriOptions<B> {
@@ -30,9 +35,40 @@ impl<B: StarkField> FriOptions<B> {
             folding_factor: FOLDING_FACTOR,
             max_remainder_length: MAX_REMAINDER_LENGTH,
             blowup_factor,
+            byte_encoding: ByteEncoding::Native,
         }
     }
 
+    /// Returns these options with evaluations encoded into bytes according to the specified
+    /// encoding; encoded evaluations are hashed into layer commitments and included in proofs.
+    pub fn with_byte_encoding(mut self, encoding: ByteEncoding) -> Self {
+        self.byte_encoding = encoding;
+        self
+    }
+
+    /// Returns these options with folding terminated as soon as the degree of the folded
+    /// polynomial does not exceed `max_degree`, rather than once evaluations of the folded
+    /// polynomial fit into a small remainder.
+    ///
+    /// A larger remainder results in fewer FRI layers, and thus, fewer commitments and
+    /// authentication paths for the verifier to check; but the remainder is included in a proof
+    /// in full, and the verifier needs to interpolate a polynomial of up to `max_degree` to check
+    /// its degree. `max_degree + 1` must be a power of two not greater than 4096.
+    pub fn with_max_remainder_degree(mut self, max_degree: usize) -> Self {
+        let degree_bound = max_degree + 1;
+        assert!(
+            degree_bound.is_power_of_two(),
+            "max remainder degree plus one must be a power of 2"
+        );
+        assert!(
+            degree_bound <= MAX_REMAINDER_DEGREE_BOUND,
+            "max remainder degree cannot be greater than {}",
+            MAX_REMAINDER_DEGREE_BOUND - 1
+        );
+        self.max_remainder_length = degree_bound * self.blowup_factor;
+        self
+    }
+
     pub fn domain_offset(&self) -> B {
         self.domain_offset
     }
@@ -45,10 +81,20 @@ impl<B: StarkField> FriOptions<B> {
         self.max_remainder_length
     }
 
+    /// Returns the largest degree of the folded polynomial at which folding is terminated.
+    pub fn max_remainder_degree(&self) -> usize {
+        (self.max_remainder_length / self.blowup_factor).max(1) - 1
+    }
+
     pub fn blowup_factor(&self) -> usize {
         self.blowup_factor
     }
 
+    /// Returns the encoding of evaluations hashed into layer commitments and included in proofs.
+    pub fn byte_encoding(&self) -> ByteEncoding {
+        self.byte_encoding
+    }
+
     pub fn num_fri_layers(&self, mut domain_size: usize) -> usize {
         let mut result = 0;
         while domain_size > self.max_remainder_length {
@@ -3,18 +3,89 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use serde::{Deserialize, Serialize};
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FriProofLayer {
     pub values: Vec<Vec<u8>>,
     pub paths: Vec<Vec<[u8; 32]>>,
     pub depth: u8,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FriProof {
     pub layers: Vec<FriProofLayer>,
     pub rem_values: Vec<u8>,
     pub partitioned: bool,
 }
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for FriProofLayer {
+    /// Writes queried values and their authentication paths; every value and every path is
+    /// prefixed with its length.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.values.len() as u32);
+        for value in self.values.iter() {
+            target.write_u16(value.len() as u16);
+            target.write_u8_slice(value);
+        }
+        target.write_u32(self.paths.len() as u32);
+        for path in self.paths.iter() {
+            target.write_u8(path.len() as u8);
+            <[u8; 32]>::write_batch_into(path, target);
+        }
+        target.write_u8(self.depth);
+    }
+}
+
+impl Deserializable for FriProofLayer {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_values = source.read_u32()? as usize;
+        let mut values = Vec::new();
+        for _ in 0..num_values {
+            let value_len = source.read_u16()? as usize;
+            values.push(source.read_u8_vec(value_len)?);
+        }
+        let num_paths = source.read_u32()? as usize;
+        let mut paths = Vec::new();
+        for _ in 0..num_paths {
+            let path_len = source.read_u8()? as usize;
+            paths.push(<[u8; 32]>::read_batch_from(source, path_len)?);
+        }
+        let depth = source.read_u8()?;
+        Ok(FriProofLayer {
+            values,
+            paths,
+            depth,
+        })
+    }
+}
+
+impl Serializable for FriProof {
+    /// Writes all layers of the proof followed by the remainder; both are prefixed with their
+    /// lengths.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.layers.len() as u8);
+        FriProofLayer::write_batch_into(&self.layers, target);
+        target.write_u32(self.rem_values.len() as u32);
+        target.write_u8_slice(&self.rem_values);
+        target.write(&self.partitioned);
+    }
+}
+
+impl Deserializable for FriProof {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_layers = source.read_u8()? as usize;
+        let layers = FriProofLayer::read_batch_from(source, num_layers)?;
+        let rem_values_len = source.read_u32()? as usize;
+        let rem_values = source.read_u8_vec(rem_values_len)?;
+        let partitioned = source.read()?;
+        Ok(FriProof {
+            layers,
+            rem_values,
+            partitioned,
+        })
+    }
+}
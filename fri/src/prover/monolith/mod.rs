@@ -88,6 +88,7 @@ where
         );
 
         let hash_fn = H::hash_fn();
+        let encoding = self.options.byte_encoding();
 
         // reduce the degree by 4 at each iteration until the remaining polynomial is small enough;
         // + 1 is for the remainder
@@ -97,7 +98,8 @@ where
             // rows of this matrix; we do this so that we could de-commit to 4 values with a sing
             // Merkle authentication path.
             let transposed_evaluations = quartic::transpose(&evaluations, 1);
-            let hashed_evaluations = quartic::hash_values(&transposed_evaluations, hash_fn);
+            let hashed_evaluations =
+                quartic::hash_values(&transposed_evaluations, hash_fn, encoding);
             let evaluation_tree = MerkleTree::new(hashed_evaluations, hash_fn);
             channel.commit_fri_layer(*evaluation_tree.root());
 
@@ -132,6 +134,7 @@ where
             !self.layers.is_empty(),
             "FRI layers have not been built yet"
         );
+        let encoding = self.options.byte_encoding();
         let mut positions = positions.to_vec();
         let mut domain_size = self.layers[0].evaluations.len() * FOLDING_FACTOR;
 
@@ -152,7 +155,7 @@ where
             layers.push(FriProofLayer {
                 values: queried_values
                     .into_iter()
-                    .map(|v| E::elements_as_bytes(&v).to_vec())
+                    .map(|v| encoding.encode_elements(&v).into_owned())
                     .collect(),
                 paths: proof.nodes,
                 depth: proof.depth,
@@ -161,6 +164,25 @@ where
         }
 
         // use the remaining polynomial values directly as proof
+        let remainder = self.remainder();
+
+        // clear layers so that another proof can be generated
+        self.reset();
+
+        FriProof {
+            layers,
+            rem_values: encoding.encode_elements(&remainder).into_owned(),
+            partitioned: false,
+        }
+    }
+
+    /// Returns evaluations of the remainder (the last FRI layer) in the order in which they are
+    /// included in the proof.
+    pub fn remainder(&self) -> Vec<E> {
+        assert!(
+            !self.layers.is_empty(),
+            "FRI layers have not been built yet"
+        );
         // TODO: write remainder to the proof in transposed form?
         let last_values = &self.layers[self.layers.len() - 1].evaluations;
         let n = last_values.len();
@@ -171,15 +193,7 @@ where
             remainder[i + n * 2] = last_values[i][2];
             remainder[i + n * 3] = last_values[i][3];
         }
-
-        // clear layers so that another proof can be generated
-        self.reset();
-
-        FriProof {
-            layers,
-            rem_values: E::elements_as_bytes(&remainder).to_vec(),
-            partitioned: false,
-        }
+        remainder
     }
 
     /// Returns number of FRI layers computed during the last execution of build_layers() method
@@ -7,8 +7,13 @@ use super::{
     super::tests::{build_evaluations, build_lde_domain, build_prover_channel, verify_proof},
     FriProver,
 };
-use crate::{FriOptions, PublicCoin};
-use math::field::{f128::BaseElement, StarkField};
+use crate::{
+    verifier, DefaultVerifierChannel, FriOptions, FriProof, PublicCoin, VerifierChannel,
+    VerifierContext, VerifierError,
+};
+use crypto::hash;
+use math::field::{f128::BaseElement, FieldElement, StarkField};
+use utils::{Deserializable, Serializable};
 
 #[test]
 fn sequential_fri_prove_verify() {
@@ -41,3 +46,204 @@ fn sequential_fri_prove_verify() {
     );
     assert!(result.is_ok(), "{:}", result.err().unwrap());
 }
+
+#[test]
+fn fri_proof_serialization() {
+    let trace_length = 1024;
+    let ce_blowup = 2;
+    let lde_blowup = 8;
+    let offset = BaseElement::GENERATOR;
+
+    let options = FriOptions::new(lde_blowup, offset);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup, ce_blowup);
+    let lde_domain = build_lde_domain(trace_length, lde_blowup, offset);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone(), &lde_domain);
+    let positions = channel.draw_query_positions();
+    let proof = prover.build_proof(&positions);
+
+    // a deserialized proof must be the same as the original and must still verify
+    let bytes = proof.to_bytes();
+    let decoded = FriProof::read_from_bytes(&bytes).unwrap();
+    assert_eq!(proof, decoded);
+
+    let commitments = channel.fri_layer_commitments().to_vec();
+    let max_degree = trace_length * ce_blowup - 1;
+    let result = verify_proof(
+        decoded,
+        commitments,
+        &evaluations,
+        max_degree,
+        &positions,
+        &options,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+    // truncated and padded proofs cannot be deserialized
+    assert!(FriProof::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    let mut padded = bytes;
+    padded.push(0);
+    assert!(FriProof::read_from_bytes(&padded).is_err());
+}
+
+#[test]
+fn sequential_fri_prove_verify_max_remainder_degree() {
+    let trace_length = 4096;
+    let ce_blowup = 2;
+    let lde_blowup = 8;
+    let offset = BaseElement::GENERATOR;
+
+    // terminating folding at a higher degree results in fewer layers and a larger remainder
+    let default_options = FriOptions::new(lde_blowup, offset);
+    let options = FriOptions::new(lde_blowup, offset).with_max_remainder_degree(511);
+    let lde_domain_size = trace_length * lde_blowup;
+    assert_eq!(511, options.max_remainder_degree());
+    assert!(
+        options.num_fri_layers(lde_domain_size) < default_options.num_fri_layers(lde_domain_size)
+    );
+    assert_eq!(2048, options.fri_remainder_length(lde_domain_size));
+
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup, ce_blowup);
+    let lde_domain = build_lde_domain(trace_length, lde_blowup, offset);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone(), &lde_domain);
+    let positions = channel.draw_query_positions();
+    let proof = prover.build_proof(&positions);
+
+    let commitments = channel.fri_layer_commitments().to_vec();
+    let max_degree = trace_length * ce_blowup - 1;
+    let result = verify_proof(
+        proof,
+        commitments,
+        &evaluations,
+        max_degree,
+        &positions,
+        &options,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+}
+
+#[test]
+fn sequential_fri_verify_partial_evaluations() {
+    let trace_length = 1024;
+    let ce_blowup = 2;
+    let lde_blowup = 8;
+    let offset = BaseElement::GENERATOR;
+
+    let options = FriOptions::new(lde_blowup, offset);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup, ce_blowup);
+    let lde_domain = build_lde_domain(trace_length, lde_blowup, offset);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone(), &lde_domain);
+    let positions = channel.draw_query_positions();
+    let proof = prover.build_proof(&positions);
+
+    // evaluations are provided only for the first half of the positions; the remaining
+    // positions are used to query FRI layers only
+    let commitments = channel.fri_layer_commitments().to_vec();
+    let verifier_channel =
+        DefaultVerifierChannel::<BaseElement, hash::Blake3_256>::new(proof, commitments);
+    let context = VerifierContext::new(
+        evaluations.len(),
+        trace_length * ce_blowup - 1,
+        verifier_channel.num_fri_partitions(),
+        options,
+    )
+    .unwrap();
+    let mut queried_evaluations = positions
+        .iter()
+        .take(positions.len() / 2)
+        .map(|&p| evaluations[p])
+        .collect::<Vec<_>>();
+    let result = verifier::verify(
+        &context,
+        &verifier_channel,
+        &queried_evaluations,
+        &positions,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+    // the provided evaluations must still be consistent with the first FRI layer
+    queried_evaluations[0] += BaseElement::ONE;
+    let result = verifier::verify(
+        &context,
+        &verifier_channel,
+        &queried_evaluations,
+        &positions,
+    );
+    assert!(matches!(
+        result,
+        Err(VerifierError::LayerValuesNotConsistent(0))
+    ));
+}
+
+#[test]
+fn fri_verify_reports_first_invalid_layer() {
+    let trace_length = 1024;
+    let ce_blowup = 2;
+    let lde_blowup = 8;
+    let offset = BaseElement::GENERATOR;
+
+    let options = FriOptions::new(lde_blowup, offset);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup, ce_blowup);
+    let lde_domain = build_lde_domain(trace_length, lde_blowup, offset);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone(), &lde_domain);
+    let positions = channel.draw_query_positions();
+    let mut proof = prover.build_proof(&positions);
+    assert!(proof.layers.len() > 2);
+
+    // corrupt values in two layers; layers may be authenticated in any order (e.g., in multiple
+    // threads), but the error must always be reported for the first corrupted layer
+    proof.layers[1].values[0][0] ^= 1;
+    proof.layers[2].values[0][0] ^= 1;
+    let commitments = channel.fri_layer_commitments().to_vec();
+    let verifier_channel =
+        DefaultVerifierChannel::<BaseElement, hash::Blake3_256>::new(proof, commitments);
+    let context = VerifierContext::new(
+        evaluations.len(),
+        trace_length * ce_blowup - 1,
+        verifier_channel.num_fri_partitions(),
+        options,
+    )
+    .unwrap();
+    let queried_evaluations = positions
+        .iter()
+        .map(|&p| evaluations[p])
+        .collect::<Vec<_>>();
+    let result = verifier::verify(
+        &context,
+        &verifier_channel,
+        &queried_evaluations,
+        &positions,
+    );
+    assert!(matches!(
+        result,
+        Err(VerifierError::LayerCommitmentMismatch(1))
+    ));
+}
+
+#[test]
+fn fri_verifier_context_rejects_invalid_domain_size() {
+    let options = FriOptions::new(8, BaseElement::GENERATOR);
+    let result = VerifierContext::new(1000, 63, 1, options.clone());
+    assert!(matches!(
+        result,
+        Err(VerifierError::InvalidDomainSize(1000, _))
+    ));
+
+    let domain_size = 1 << (BaseElement::max_two_adicity() + 1);
+    let result = VerifierContext::new(domain_size, 63, 1, options);
+    assert!(matches!(
+        result,
+        Err(VerifierError::InvalidDomainSize(size, _)) if size == domain_size
+    ));
+}
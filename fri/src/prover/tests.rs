@@ -11,7 +11,7 @@ use crypto::hash;
 use math::{
     fft,
     field::{f128::BaseElement, FieldElement, StarkField},
-    utils::{get_power_series_with_offset, log2},
+    utils::{get_power_series_with_offset_unchecked, log2_unchecked},
 };
 
 // TEST UTILS
@@ -30,8 +30,8 @@ pub fn build_lde_domain(
     offset: BaseElement,
 ) -> Vec<BaseElement> {
     let domain_size = trace_length * lde_blowup;
-    let g = BaseElement::get_root_of_unity(log2(domain_size));
-    get_power_series_with_offset(g, offset, domain_size)
+    let g = BaseElement::get_root_of_unity(log2_unchecked(domain_size)).unwrap();
+    get_power_series_with_offset_unchecked(g, offset, domain_size)
 }
 
 pub fn build_evaluations(
@@ -64,7 +64,7 @@ pub fn verify_proof(
         max_degree,
         channel.num_fri_partitions(),
         options.clone(),
-    );
+    )?;
     let queried_evaluations = positions
         .iter()
         .map(|&p| evaluations[p])
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use crypto::RandomElementGenerator;
-use math::field::FieldElement;
+use math::field::{ByteEncoding, FieldElement};
 
 pub trait PublicCoin {
     type RandomElementGenerator: RandomElementGenerator;
@@ -14,9 +14,16 @@ pub trait PublicCoin {
     /// evaluations during folding of the next FRI layer.
     fn draw_fri_alpha<E: FieldElement>(&self, layer_idx: usize) -> E {
         let seed = self.fri_layer_commitments()[layer_idx];
-        let mut generator = Self::RandomElementGenerator::new(seed, 0);
+        let mut generator =
+            Self::RandomElementGenerator::with_encoding(seed, 0, self.byte_encoding());
         generator.draw()
     }
 
     fn fri_layer_commitments(&self) -> &[[u8; 32]];
+
+    /// Returns the encoding according to which pseudo-random values are drawn, and values sent
+    /// by the prover are encoded; this is native encoding unless specified otherwise.
+    fn byte_encoding(&self) -> ByteEncoding {
+        ByteEncoding::Native
+    }
 }
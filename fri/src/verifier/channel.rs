@@ -4,10 +4,13 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{folding::quartic, FriProof, PublicCoin, VerifierError};
-use crypto::{BatchMerkleProof, DefaultRandomElementGenerator, Hasher, MerkleTree};
-use math::{field::FieldElement, utils::read_elements_into_vec};
+use crypto::{BatchMerkleProof, DefaultRandomElementGenerator, HashFunction, Hasher, MerkleTree};
+use math::field::{ByteEncoding, FieldElement};
 use std::{convert::TryInto, marker::PhantomData};
 
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
 type Bytes = Vec<u8>;
 
 // VERIFIER CHANNEL TRAIT
@@ -35,44 +38,81 @@ pub trait VerifierChannel<E: FieldElement>: PublicCoin {
         layer_idx: usize,
         positions: &[usize],
     ) -> Result<Vec<[E; 4]>, VerifierError> {
-        let hash_fn = Self::Hasher::hash_fn();
-        let layer_root = self.fri_layer_commitments()[layer_idx];
-        let layer_proof = &self.fri_layer_proofs()[layer_idx];
-        if !MerkleTree::verify_batch(&layer_root, &positions, &layer_proof, hash_fn) {
-            return Err(VerifierError::LayerCommitmentMismatch(layer_idx));
-        }
-
-        // convert query bytes into field elements of appropriate type
-        let mut queries = Vec::new();
-        for query_bytes in self.fri_layer_queries()[layer_idx].iter() {
-            let query: [E; 4] = read_elements_into_vec(query_bytes)
-                .map_err(|err| {
-                    VerifierError::LayerDeserializationError(layer_idx, err.to_string())
-                })?
-                .try_into()
-                .map_err(|_| {
-                    VerifierError::LayerDeserializationError(
-                        layer_idx,
-                        "failed to convert vec of elements to array of 4 element".to_string(),
-                    )
-                })?;
-            queries.push(query);
-        }
+        read_layer_queries(
+            layer_idx,
+            &self.fri_layer_commitments()[layer_idx],
+            &self.fri_layer_proofs()[layer_idx],
+            &self.fri_layer_queries()[layer_idx],
+            positions,
+            Self::Hasher::hash_fn(),
+            self.byte_encoding(),
+        )
+    }
 
-        Ok(queries)
+    /// Returns FRI query values from all FRI layers; values for a layer are read at the positions
+    /// specified for that layer by the `positions` slice. This also checks if the values are
+    /// valid against the FRI layer commitments sent by the prover.
+    ///
+    /// When `concurrent` feature is enabled, the layers are authenticated in multiple threads.
+    /// Regardless of the number of threads, if values of several layers are not valid, the error
+    /// for the layer with the smallest index is returned.
+    fn read_all_layer_queries(
+        &self,
+        positions: &[Vec<usize>],
+    ) -> Result<Vec<Vec<[E; 4]>>, VerifierError> {
+        let roots = self.fri_layer_commitments();
+        let proofs = self.fri_layer_proofs();
+        let queries = self.fri_layer_queries();
+        let hash_fn = Self::Hasher::hash_fn();
+        let encoding = self.byte_encoding();
+
+        #[cfg(not(feature = "concurrent"))]
+        let results = positions.iter().enumerate().map(|(layer_idx, positions)| {
+            read_layer_queries(
+                layer_idx,
+                &roots[layer_idx],
+                &proofs[layer_idx],
+                &queries[layer_idx],
+                positions,
+                hash_fn,
+                encoding,
+            )
+        });
+
+        #[cfg(feature = "concurrent")]
+        let results = positions
+            .par_iter()
+            .enumerate()
+            .map(|(layer_idx, positions)| {
+                read_layer_queries(
+                    layer_idx,
+                    &roots[layer_idx],
+                    &proofs[layer_idx],
+                    &queries[layer_idx],
+                    positions,
+                    hash_fn,
+                    encoding,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // collecting results in layer order returns the error of the first invalid layer
+        results.into_iter().collect()
     }
 
     /// Reads FRI remainder values (last FRI layer). This also checks that the remainder is
     /// valid against the commitment sent by the prover.
     fn read_remainder(&self) -> Result<Vec<E>, VerifierError> {
         let hash_fn = Self::Hasher::hash_fn();
+        let encoding = self.byte_encoding();
         // convert remainder bytes into field elements of appropriate type
-        let remainder = read_elements_into_vec(&self.fri_remainder())
+        let remainder = encoding
+            .decode_elements(self.fri_remainder())
             .map_err(|err| VerifierError::RemainderDeserializationError(err.to_string()))?;
 
         // build remainder Merkle tree
         let remainder_values = quartic::transpose(&remainder, 1);
-        let hashed_values = quartic::hash_values(&remainder_values, hash_fn);
+        let hashed_values = quartic::hash_values(&remainder_values, hash_fn, encoding);
         let remainder_tree = MerkleTree::new(hashed_values, hash_fn);
 
         // make sure the root of the tree matches the committed root of the last layer
@@ -111,7 +151,7 @@ pub trait VerifierChannel<E: FieldElement>: PublicCoin {
 
     fn num_fri_partitions(&self) -> usize {
         if self.fri_partitioned() {
-            self.fri_remainder().len() / E::ELEMENT_BYTES
+            self.fri_remainder().len() / self.byte_encoding().element_bytes::<E>()
         } else {
             1
         }
@@ -176,3 +216,40 @@ impl<E: FieldElement, H: Hasher> PublicCoin for DefaultVerifierChannel<E, H> {
         &self.commitments
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Checks that `layer_queries` at the specified `positions` are valid against the `layer_root`
+/// of the FRI layer at the specified index, and converts the queries into field elements.
+fn read_layer_queries<E: FieldElement>(
+    layer_idx: usize,
+    layer_root: &[u8; 32],
+    layer_proof: &BatchMerkleProof,
+    layer_queries: &[Bytes],
+    positions: &[usize],
+    hash_fn: HashFunction,
+    encoding: ByteEncoding,
+) -> Result<Vec<[E; 4]>, VerifierError> {
+    if !MerkleTree::verify_batch(layer_root, positions, layer_proof, hash_fn) {
+        return Err(VerifierError::LayerCommitmentMismatch(layer_idx));
+    }
+
+    // convert query bytes into field elements of appropriate type
+    let mut queries = Vec::new();
+    for query_bytes in layer_queries.iter() {
+        let query: [E; 4] = encoding
+            .decode_elements(query_bytes)
+            .map_err(|err| VerifierError::LayerDeserializationError(layer_idx, err.to_string()))?
+            .try_into()
+            .map_err(|_| {
+                VerifierError::LayerDeserializationError(
+                    layer_idx,
+                    "failed to convert vec of elements to array of 4 element".to_string(),
+                )
+            })?;
+        queries.push(query);
+    }
+
+    Ok(queries)
+}
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::FriOptions;
+use crate::{FriOptions, VerifierError};
 use math::{field::StarkField, utils::log2};
 
 pub struct VerifierContext<B: StarkField> {
@@ -15,20 +15,27 @@ pub struct VerifierContext<B: StarkField> {
 }
 
 impl<B: StarkField> VerifierContext<B> {
+    /// Returns a new verifier context for the evaluation domain of the specified size.
+    ///
+    /// Returns an error if `domain_size` is not a power of two, or if the domain does not fit
+    /// into the two-adic subgroup of the field.
     pub fn new(
         domain_size: usize,
         max_degree: usize,
         num_partitions: usize,
         options: FriOptions<B>,
-    ) -> Self {
-        let domain_generator = B::get_root_of_unity(log2(domain_size));
-        VerifierContext {
+    ) -> Result<Self, VerifierError> {
+        let domain_generator = log2(domain_size)
+            .map_err(|err| err.to_string())
+            .and_then(|depth| B::get_root_of_unity(depth).map_err(|err| err.to_string()))
+            .map_err(|err| VerifierError::InvalidDomainSize(domain_size, err))?;
+        Ok(VerifierContext {
             max_degree,
             domain_size,
             domain_generator,
             options,
             num_partitions,
-        }
+        })
     }
 
     pub fn max_degree(&self) -> usize {
@@ -7,6 +7,9 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum VerifierError {
+    #[error("FRI domain of size {0} is not valid: {1}")]
+    InvalidDomainSize(usize, String),
+
     #[error("FRI queries did not match the commitment at layer {0}")]
     LayerCommitmentMismatch(usize),
 
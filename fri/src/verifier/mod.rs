@@ -9,7 +9,6 @@ use math::{
     polynom,
     utils::get_power_series_with_offset,
 };
-use std::mem;
 
 mod context;
 pub use context::VerifierContext;
@@ -27,6 +26,9 @@ pub use errors::VerifierError;
 /// with degree <= context.max_degree() at x coordinates specified by the `positions` slice. The
 /// evaluation domain is defined by the combination of base field (specified by B type parameter)
 /// and context.domain_size() parameter.
+///
+/// There may be fewer evaluations than positions; in such a case, evaluations correspond to the
+/// first positions, and the remaining positions are used only to query the FRI layers.
 pub fn verify<B, E, C>(
     context: &VerifierContext<B>,
     channel: &C,
@@ -39,8 +41,8 @@ where
     C: VerifierChannel<E>,
 {
     assert!(
-        evaluations.len() == positions.len(),
-        "number of positions must match the number of evaluations"
+        evaluations.len() <= positions.len(),
+        "number of evaluations cannot exceed the number of positions"
     );
     let domain_size = context.domain_size();
     let domain_generator = context.domain_generator();
@@ -55,34 +57,50 @@ where
         domain_generator.exp((domain_size as u32 * 3 / 4).into()),
     ];
 
-    // 1 ----- verify the recursive components of the FRI proof -----------------------------------
-    let mut domain_generator = domain_generator;
-    let mut domain_size = domain_size;
-    let mut max_degree_plus_1 = context.max_degree() + 1;
-    let mut positions = positions.to_vec();
-    let mut evaluations = evaluations.to_vec();
-
+    // 1 ----- read queried values of all FRI layers ----------------------------------------------
+    // positions queried in each layer depend only on the positions queried in the previous
+    // layer; thus, they can be determined up front, and values of all layers can be read and
+    // authenticated against layer commitments independently of each other
+    let mut layer_positions = Vec::with_capacity(context.num_fri_layers() + 1);
+    let mut layer_indexes = Vec::with_capacity(context.num_fri_layers());
+    layer_positions.push(positions.to_vec());
+    let mut layer_domain_size = domain_size;
     for depth in 0..context.num_fri_layers() {
         // determine which evaluations were queried in the folded layer
-        let mut folded_positions =
-            utils::fold_positions(&positions, domain_size, context.folding_factor());
+        let folded_positions = utils::fold_positions(
+            &layer_positions[depth],
+            layer_domain_size,
+            context.folding_factor(),
+        );
         // determine where these evaluations are in the commitment Merkle tree
-        let position_indexes = utils::map_positions_to_indexes(
+        layer_indexes.push(utils::map_positions_to_indexes(
             &folded_positions,
-            domain_size,
+            layer_domain_size,
             context.folding_factor(),
             num_partitions,
-        );
-        // read query values from the specified indexes in the Merkle tree
-        let layer_values = channel.read_layer_queries(depth, &position_indexes)?;
+        ));
+        layer_positions.push(folded_positions);
+        layer_domain_size /= context.folding_factor();
+    }
+    let all_layer_values = channel.read_all_layer_queries(&layer_indexes)?;
+
+    // 2 ----- verify the recursive components of the FRI proof -----------------------------------
+    let mut domain_generator = domain_generator;
+    let mut domain_size = domain_size;
+    let mut max_degree_plus_1 = context.max_degree() + 1;
+    let mut evaluations = evaluations.to_vec();
+
+    for (depth, layer_values) in all_layer_values.iter().enumerate() {
+        let positions = &layer_positions[depth];
+        let folded_positions = &layer_positions[depth + 1];
         let query_values = get_query_values(
-            &layer_values,
-            &positions,
-            &folded_positions,
+            layer_values,
+            positions,
+            folded_positions,
             domain_size,
             context.folding_factor(),
         );
-        if evaluations != query_values {
+        if evaluations[..] != query_values[..evaluations.len()] {
             return Err(VerifierError::LayerValuesNotConsistent(depth));
         }
 
@@ -99,7 +117,7 @@ where
         }
 
         // interpolate x and y values into row polynomials
-        let row_polys = quartic::interpolate_batch(&xs, &layer_values);
+        let row_polys = quartic::interpolate_batch(&xs, layer_values);
 
         // calculate the pseudo-random value used for linear combination in layer folding
         let alpha = channel.draw_fri_alpha(depth);
@@ -112,14 +130,14 @@ where
         domain_generator = domain_generator.exp(4u32.into());
         max_degree_plus_1 /= 4;
         domain_size /= 4;
-        mem::swap(&mut positions, &mut folded_positions);
     }
 
-    // 2 ----- verify the remainder of the FRI proof ----------------------------------------------
+    // 3 ----- verify the remainder of the FRI proof ----------------------------------------------
 
     // read the remainder from the channel and make sure it matches with the columns
     // of the previous layer
     let remainder = channel.read_remainder()?;
+    let positions = &layer_positions[all_layer_values.len()];
     for (&position, evaluation) in positions.iter().zip(evaluations) {
         if remainder[position] != evaluation {
             return Err(VerifierError::RemainderValuesNotConsistent);
@@ -160,7 +178,8 @@ where
     }
 
     // pick a subset of points from the remainder and interpolate them into a polynomial
-    let domain = get_power_series_with_offset(domain_generator, B::GENERATOR, remainder.len());
+    let domain = get_power_series_with_offset(domain_generator, B::GENERATOR, remainder.len())
+        .map_err(|err| VerifierError::RemainderDeserializationError(err.to_string()))?;
     let mut xs = Vec::with_capacity(max_degree_plus_1);
     let mut ys = Vec::with_capacity(max_degree_plus_1);
     for &p in positions.iter().take(max_degree_plus_1) {
@@ -0,0 +1,355 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Compares the special-form reduction used by the f128 field against a prototype of Montgomery
+//! arithmetic over the same modulus. Both representations are run through the same FFT code so
+//! that only the cost of field operations differs.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::Rng;
+use std::time::Duration;
+use winter_math::{
+    field::{f128::BaseElement, FieldElement, StarkField},
+    utils::log2_unchecked,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const FFT_SIZE: usize = 1 << 20;
+
+// Field modulus = 2^128 - 45 * 2^40 + 1, split into 64-bit limbs
+const M: u128 = 340282366920938463463374557953744961537;
+const M0: u64 = M as u64;
+const M1: u64 = (M >> 64) as u64;
+
+// -M^(-1) mod 2^64
+const M_PRIME: u64 = neg_inv_mod_2_64(M0);
+
+// BENCHMARKS
+// ================================================================================================
+
+fn field_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("f128_montgomery");
+    let r2 = get_r2();
+    validate_prototype(r2);
+
+    let x = BaseElement::rand();
+    let y = BaseElement::rand();
+    let mx = MontElement::from_canonical(x, r2);
+    let my = MontElement::from_canonical(y, r2);
+
+    group.bench_function(BenchmarkId::new("mul", "special_form"), |bench| {
+        bench.iter(|| black_box(x) * black_box(y))
+    });
+    group.bench_function(BenchmarkId::new("mul", "montgomery"), |bench| {
+        bench.iter(|| black_box(mx).mul(black_box(my)))
+    });
+
+    group.bench_function(BenchmarkId::new("square", "special_form"), |bench| {
+        bench.iter(|| black_box(x).square())
+    });
+    group.bench_function(BenchmarkId::new("square", "montgomery"), |bench| {
+        bench.iter(|| black_box(mx).square())
+    });
+
+    group.bench_function(BenchmarkId::new("inv", "special_form"), |bench| {
+        bench.iter(|| black_box(x).inv())
+    });
+    group.bench_function(BenchmarkId::new("inv", "montgomery"), |bench| {
+        bench.iter(|| black_box(mx).inv(r2))
+    });
+
+    group.finish();
+}
+
+fn fft(c: &mut Criterion) {
+    let mut group = c.benchmark_group("f128_montgomery_fft");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(20));
+
+    let r2 = get_r2();
+    let values = BaseElement::prng_vector(rand::thread_rng().gen::<[u8; 32]>(), FFT_SIZE);
+    let twiddles = get_twiddles(FFT_SIZE);
+    let m_values = values
+        .iter()
+        .map(|&v| MontElement::from_canonical(v, r2))
+        .collect::<Vec<_>>();
+    let m_twiddles = twiddles
+        .iter()
+        .map(|&v| MontElement::from_canonical(v, r2))
+        .collect::<Vec<_>>();
+
+    group.bench_function(BenchmarkId::new("special_form", FFT_SIZE), |bench| {
+        bench.iter_with_large_drop(|| {
+            let mut result = values.clone();
+            fft_in_place(&mut result, &twiddles);
+            result
+        })
+    });
+    group.bench_function(BenchmarkId::new("montgomery", FFT_SIZE), |bench| {
+        bench.iter_with_large_drop(|| {
+            let mut result = m_values.clone();
+            fft_in_place(&mut result, &m_twiddles);
+            result
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(f128_montgomery_group, field_ops, fft);
+criterion_main!(f128_montgomery_group);
+
+// ARITHMETIC
+// ================================================================================================
+
+/// Operations needed by the FFT below; implemented for both representations.
+trait Arithmetic: Copy {
+    fn add(self, rhs: Self) -> Self;
+    fn sub(self, rhs: Self) -> Self;
+    fn mul(self, rhs: Self) -> Self;
+}
+
+impl Arithmetic for BaseElement {
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+}
+
+// MONTGOMERY PROTOTYPE
+// ================================================================================================
+
+/// Field element in Montgomery form, i.e. x * 2^128 mod M; internal values are in [0, M).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct MontElement(u128);
+
+impl MontElement {
+    fn from_canonical(value: BaseElement, r2: u128) -> Self {
+        MontElement(mont_mul(canonical(value), r2))
+    }
+
+    fn to_canonical(self) -> BaseElement {
+        BaseElement::new(redc(self.0 as u64, (self.0 >> 64) as u64, 0, 0))
+    }
+
+    #[inline(always)]
+    fn square(self) -> Self {
+        MontElement(mont_square(self.0))
+    }
+
+    /// Inverts the element via the canonical representation, which costs two Montgomery
+    /// multiplications on top of the canonical inversion.
+    fn inv(self, r2: u128) -> Self {
+        MontElement::from_canonical(self.to_canonical().inv(), r2)
+    }
+}
+
+impl Arithmetic for MontElement {
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        let z = M - rhs.0;
+        MontElement(if self.0 < z {
+            M - z + self.0
+        } else {
+            self.0 - z
+        })
+    }
+
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        MontElement(if self.0 < rhs.0 {
+            M - rhs.0 + self.0
+        } else {
+            self.0 - rhs.0
+        })
+    }
+
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self {
+        MontElement(mont_mul(self.0, rhs.0))
+    }
+}
+
+/// Computes a * b * 2^-128 mod M.
+#[inline(always)]
+fn mont_mul(a: u128, b: u128) -> u128 {
+    let (a0, a1) = (a as u64, (a >> 64) as u64);
+    let (b0, b1) = (b as u64, (b >> 64) as u64);
+
+    let p00 = (a0 as u128) * (b0 as u128);
+    let p01 = (a0 as u128) * (b1 as u128);
+    let p10 = (a1 as u128) * (b0 as u128);
+    let p11 = (a1 as u128) * (b1 as u128);
+
+    let t1 = (p00 >> 64) + (p01 as u64 as u128) + (p10 as u64 as u128);
+    let t2 = (t1 >> 64) + (p01 >> 64) + (p10 >> 64) + (p11 as u64 as u128);
+    let t3 = (t2 >> 64) + (p11 >> 64);
+    redc(p00 as u64, t1 as u64, t2 as u64, t3 as u64)
+}
+
+/// Computes a^2 * 2^-128 mod M; the cross product is computed only once.
+#[inline(always)]
+fn mont_square(a: u128) -> u128 {
+    let (a0, a1) = (a as u64, (a >> 64) as u64);
+
+    let p00 = (a0 as u128) * (a0 as u128);
+    let p01 = (a0 as u128) * (a1 as u128);
+    let p11 = (a1 as u128) * (a1 as u128);
+
+    let t1 = (p00 >> 64) + ((p01 as u64 as u128) << 1);
+    let t2 = (t1 >> 64) + ((p01 >> 64) << 1) + (p11 as u64 as u128);
+    let t3 = (t2 >> 64) + (p11 >> 64);
+    redc(p00 as u64, t1 as u64, t2 as u64, t3 as u64)
+}
+
+/// Computes t * 2^-128 mod M for a 256-bit value t < M^2 using two rounds of Montgomery
+/// reduction.
+#[inline(always)]
+fn redc(t0: u64, t1: u64, t2: u64, t3: u64) -> u128 {
+    // round 0: make t divisible by 2^64
+    let m = t0.wrapping_mul(M_PRIME);
+    let mm0 = (m as u128) * (M0 as u128);
+    let mm1 = (m as u128) * (M1 as u128);
+    let s = (t0 as u128) + (mm0 as u64 as u128);
+    let s = (t1 as u128) + (mm1 as u64 as u128) + (s >> 64) + (mm0 >> 64);
+    let t1 = s as u64;
+    let s = (t2 as u128) + (s >> 64) + (mm1 >> 64);
+    let t2 = s as u64;
+    let s = (t3 as u128) + (s >> 64);
+    let t3 = s as u64;
+    let mut overflow = (s >> 64) as u64;
+
+    // round 1: make t divisible by 2^128
+    let m = t1.wrapping_mul(M_PRIME);
+    let mm0 = (m as u128) * (M0 as u128);
+    let mm1 = (m as u128) * (M1 as u128);
+    let s = (t1 as u128) + (mm0 as u64 as u128);
+    let s = (t2 as u128) + (mm1 as u64 as u128) + (s >> 64) + (mm0 >> 64);
+    let t2 = s as u64;
+    let s = (t3 as u128) + (s >> 64) + (mm1 >> 64);
+    let t3 = s as u64;
+    overflow += (s >> 64) as u64;
+
+    // the result is smaller than 2M, so a single subtraction suffices
+    let result = ((t3 as u128) << 64) | (t2 as u128);
+    if overflow != 0 || result >= M {
+        result.wrapping_sub(M)
+    } else {
+        result
+    }
+}
+
+// FFT
+// ================================================================================================
+
+/// Evaluates a polynomial with coefficients `values` over the domain of the same size using an
+/// iterative radix-2 FFT; `twiddles` must contain g^i for i in [0, n / 2) for the generator g of
+/// the domain.
+fn fft_in_place<T: Arithmetic>(values: &mut [T], twiddles: &[T]) {
+    let n = values.len();
+    let log_n = log2_unchecked(n);
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let step = n / len;
+        for chunk in values.chunks_mut(len) {
+            for j in 0..half {
+                let u = chunk[j];
+                let v = chunk[j + half].mul(twiddles[j * step]);
+                chunk[j] = u.add(v);
+                chunk[j + half] = u.sub(v);
+            }
+        }
+        len *= 2;
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn canonical(value: BaseElement) -> u128 {
+    value.as_int()
+}
+
+/// Returns 2^256 mod M.
+fn get_r2() -> u128 {
+    let r = BaseElement::new(0u128.wrapping_sub(M)); // 2^128 mod M
+    canonical(r.square())
+}
+
+fn get_twiddles(n: usize) -> Vec<BaseElement> {
+    let g = BaseElement::get_root_of_unity(log2_unchecked(n)).unwrap();
+    let mut result = Vec::with_capacity(n / 2);
+    let mut w = BaseElement::ONE;
+    for _ in 0..n / 2 {
+        result.push(w);
+        w *= g;
+    }
+    result
+}
+
+/// Makes sure the prototype computes the same values as the field before it is benchmarked.
+fn validate_prototype(r2: u128) {
+    for _ in 0..1000 {
+        let x = BaseElement::rand();
+        let y = BaseElement::rand();
+        let mx = MontElement::from_canonical(x, r2);
+        let my = MontElement::from_canonical(y, r2);
+        assert_eq!(x, mx.to_canonical());
+        assert_eq!(x * y, mx.mul(my).to_canonical());
+        assert_eq!(x.square(), mx.square().to_canonical());
+        assert_eq!(x + y, mx.add(my).to_canonical());
+        assert_eq!(x - y, mx.sub(my).to_canonical());
+        assert_eq!(x.inv(), mx.inv(r2).to_canonical());
+    }
+
+    let values = BaseElement::prng_vector([1; 32], 64);
+    let mut expected = values.clone();
+    fft_in_place(&mut expected, &get_twiddles(64));
+    let mut actual = values
+        .iter()
+        .map(|&v| MontElement::from_canonical(v, r2))
+        .collect::<Vec<_>>();
+    let twiddles = get_twiddles(64)
+        .into_iter()
+        .map(|v| MontElement::from_canonical(v, r2))
+        .collect::<Vec<_>>();
+    fft_in_place(&mut actual, &twiddles);
+    let actual = actual
+        .into_iter()
+        .map(MontElement::to_canonical)
+        .collect::<Vec<_>>();
+    assert_eq!(expected, actual);
+}
+
+/// Computes -m^(-1) mod 2^64 for an odd m using Newton iterations.
+const fn neg_inv_mod_2_64(m: u64) -> u64 {
+    let mut inv = 1u64;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
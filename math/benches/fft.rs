@@ -8,10 +8,11 @@ use rand::Rng;
 use std::time::Duration;
 use winter_math::{
     fft,
-    field::{f128::BaseElement, FieldElement, QuadExtension, StarkField},
+    field::{f128::BaseElement, f31, FieldElement, QuadExtension, StarkField},
 };
 
 const SIZES: [usize; 3] = [262_144, 524_288, 1_048_576];
+const BATCH_WIDTHS: [usize; 3] = [16, 128, 512];
 
 fn fft_evaluate_poly(c: &mut Criterion) {
     let mut group = c.benchmark_group("fft_evaluate_poly");
@@ -62,6 +63,19 @@ fn fft_evaluate_poly(c: &mut Criterion) {
         });
     }
 
+    for &size in SIZES.iter() {
+        let p = f31::BaseElement::prng_vector(get_seed(), size / blowup_factor);
+        let twiddles = fft::get_twiddles::<f31::BaseElement>(size);
+        group.bench_function(BenchmarkId::new("f31", size), |bench| {
+            bench.iter_with_large_drop(|| {
+                let mut result = f31::BaseElement::zeroed_vector(size);
+                result[..p.len()].copy_from_slice(&p);
+                fft::evaluate_poly(&mut result, &twiddles);
+                result
+            });
+        });
+    }
+
     group.finish();
 }
 
@@ -99,6 +113,43 @@ fn fft_interpolate_poly(c: &mut Criterion) {
     group.finish();
 }
 
+fn fft_interpolate_poly_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fft_interpolate_poly_batch");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    // wide traces: many short columns interpolated over the same domain
+    let size = 8_192;
+    let inv_twiddles = fft::get_inv_twiddles::<BaseElement>(size);
+    for &num_columns in BATCH_WIDTHS.iter() {
+        let columns = (0..num_columns)
+            .map(|_| BaseElement::prng_vector(get_seed(), size))
+            .collect::<Vec<_>>();
+
+        group.bench_function(BenchmarkId::new("column_by_column", num_columns), |bench| {
+            bench.iter_batched_ref(
+                || columns.clone(),
+                |columns| {
+                    for column in columns.iter_mut() {
+                        fft::interpolate_poly(column, &inv_twiddles);
+                    }
+                },
+                BatchSize::LargeInput,
+            );
+        });
+
+        group.bench_function(BenchmarkId::new("batch", num_columns), |bench| {
+            bench.iter_batched_ref(
+                || columns.clone(),
+                |columns| fft::interpolate_poly_batch(columns, &inv_twiddles),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
 fn get_twiddles(c: &mut Criterion) {
     let mut group = c.benchmark_group("fft_get_twiddles");
     group.sample_size(10);
@@ -114,6 +165,7 @@ criterion_group!(
     fft_group,
     fft_evaluate_poly,
     fft_interpolate_poly,
+    fft_interpolate_poly_batch,
     get_twiddles
 );
 criterion_main!(fft_group);
@@ -8,7 +8,10 @@ use rand::Rng;
 use std::{convert::TryInto, time::Duration};
 use utils::AsBytes;
 use winter_math::{
-    field::{f128, f62, FieldElement, QuadExtension},
+    field::{
+        bls12_381, bn254, f128, f31, f62, f64, m31, FieldElement, QuadExtension, QuartExtension,
+        StarkField,
+    },
     utils::batch_inversion,
 };
 
@@ -100,6 +103,191 @@ pub fn f62_extension_ops(c: &mut Criterion) {
     });
 }
 
+pub fn f64_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("f64");
+
+    group.bench_function("add", |bench| {
+        let x = f64::BaseElement::rand();
+        let y = f64::BaseElement::rand();
+        bench.iter(|| black_box(x) + black_box(y))
+    });
+
+    group.bench_function("sub", |bench| {
+        let x = f64::BaseElement::rand();
+        let y = f64::BaseElement::rand();
+        bench.iter(|| black_box(x) - black_box(y))
+    });
+
+    group.bench_function("mul", |bench| {
+        let x = f64::BaseElement::rand();
+        let y = f64::BaseElement::rand();
+        bench.iter(|| black_box(x) * black_box(y))
+    });
+
+    group.bench_function("exp", |bench| {
+        let x = f64::BaseElement::rand();
+        let y = u64::from_le_bytes(f64::BaseElement::rand().as_bytes().try_into().unwrap());
+        bench.iter(|| f64::BaseElement::exp(black_box(x), black_box(y)))
+    });
+
+    group.bench_function("inv", |bench| {
+        let x = f64::BaseElement::rand();
+        bench.iter(|| f64::BaseElement::inv(black_box(x)))
+    });
+}
+
+pub fn f64_extension_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("f64_quad");
+
+    group.bench_function("mul", |bench| {
+        let x = QuadExtension::<f64::BaseElement>::rand();
+        let y = QuadExtension::<f64::BaseElement>::rand();
+        bench.iter(|| black_box(x) * black_box(y))
+    });
+}
+
+pub fn f31_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("f31");
+
+    group.bench_function("add", |bench| {
+        let x = f31::BaseElement::rand();
+        let y = f31::BaseElement::rand();
+        bench.iter(|| black_box(x) + black_box(y))
+    });
+
+    group.bench_function("sub", |bench| {
+        let x = f31::BaseElement::rand();
+        let y = f31::BaseElement::rand();
+        bench.iter(|| black_box(x) - black_box(y))
+    });
+
+    group.bench_function("mul", |bench| {
+        let x = f31::BaseElement::rand();
+        let y = f31::BaseElement::rand();
+        bench.iter(|| black_box(x) * black_box(y))
+    });
+
+    group.bench_function("exp", |bench| {
+        let x = f31::BaseElement::rand();
+        let y = rand::thread_rng().gen::<u64>();
+        bench.iter(|| f31::BaseElement::exp(black_box(x), black_box(y)))
+    });
+
+    group.bench_function("inv", |bench| {
+        let x = f31::BaseElement::rand();
+        bench.iter(|| f31::BaseElement::inv(black_box(x)))
+    });
+}
+
+pub fn m31_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("m31");
+
+    group.bench_function("add", |bench| {
+        let x = m31::BaseElement::rand();
+        let y = m31::BaseElement::rand();
+        bench.iter(|| black_box(x) + black_box(y))
+    });
+
+    group.bench_function("sub", |bench| {
+        let x = m31::BaseElement::rand();
+        let y = m31::BaseElement::rand();
+        bench.iter(|| black_box(x) - black_box(y))
+    });
+
+    group.bench_function("mul", |bench| {
+        let x = m31::BaseElement::rand();
+        let y = m31::BaseElement::rand();
+        bench.iter(|| black_box(x) * black_box(y))
+    });
+
+    group.bench_function("exp", |bench| {
+        let x = m31::BaseElement::rand();
+        let y = rand::thread_rng().gen::<u64>();
+        bench.iter(|| m31::BaseElement::exp(black_box(x), black_box(y)))
+    });
+
+    group.bench_function("inv", |bench| {
+        let x = m31::BaseElement::rand();
+        bench.iter(|| m31::BaseElement::inv(black_box(x)))
+    });
+}
+
+pub fn bn254_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bn254");
+
+    group.bench_function("add", |bench| {
+        let x = bn254::BaseElement::rand();
+        let y = bn254::BaseElement::rand();
+        bench.iter(|| black_box(x) + black_box(y))
+    });
+
+    group.bench_function("sub", |bench| {
+        let x = bn254::BaseElement::rand();
+        let y = bn254::BaseElement::rand();
+        bench.iter(|| black_box(x) - black_box(y))
+    });
+
+    group.bench_function("mul", |bench| {
+        let x = bn254::BaseElement::rand();
+        let y = bn254::BaseElement::rand();
+        bench.iter(|| black_box(x) * black_box(y))
+    });
+
+    group.bench_function("exp", |bench| {
+        let x = bn254::BaseElement::rand();
+        let y = bn254::BaseElement::rand().as_int();
+        bench.iter(|| bn254::BaseElement::exp(black_box(x), black_box(y)))
+    });
+
+    group.bench_function("inv", |bench| {
+        let x = bn254::BaseElement::rand();
+        bench.iter(|| bn254::BaseElement::inv(black_box(x)))
+    });
+}
+
+pub fn bls12_381_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bls12_381");
+
+    group.bench_function("add", |bench| {
+        let x = bls12_381::BaseElement::rand();
+        let y = bls12_381::BaseElement::rand();
+        bench.iter(|| black_box(x) + black_box(y))
+    });
+
+    group.bench_function("sub", |bench| {
+        let x = bls12_381::BaseElement::rand();
+        let y = bls12_381::BaseElement::rand();
+        bench.iter(|| black_box(x) - black_box(y))
+    });
+
+    group.bench_function("mul", |bench| {
+        let x = bls12_381::BaseElement::rand();
+        let y = bls12_381::BaseElement::rand();
+        bench.iter(|| black_box(x) * black_box(y))
+    });
+
+    group.bench_function("exp", |bench| {
+        let x = bls12_381::BaseElement::rand();
+        let y = bls12_381::BaseElement::rand().as_int();
+        bench.iter(|| bls12_381::BaseElement::exp(black_box(x), black_box(y)))
+    });
+
+    group.bench_function("inv", |bench| {
+        let x = bls12_381::BaseElement::rand();
+        bench.iter(|| bls12_381::BaseElement::inv(black_box(x)))
+    });
+}
+
+pub fn f31_extension_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("f31_quart");
+
+    group.bench_function("mul", |bench| {
+        let x = QuartExtension::<f31::BaseElement>::rand();
+        let y = QuartExtension::<f31::BaseElement>::rand();
+        bench.iter(|| black_box(x) * black_box(y))
+    });
+}
+
 pub fn batch_inv(c: &mut Criterion) {
     let mut group = c.benchmark_group("batch_inv");
     group.sample_size(10);
@@ -122,7 +310,14 @@ criterion_group!(
     f128_ops,
     f128_extension_ops,
     f62_ops,
-    f62_extension_ops
+    f62_extension_ops,
+    f64_ops,
+    f64_extension_ops,
+    f31_ops,
+    f31_extension_ops,
+    m31_ops,
+    bn254_ops,
+    bls12_381_ops
 );
 criterion_main!(field_group);
 
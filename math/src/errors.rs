@@ -34,3 +34,21 @@ pub enum ElementDecodingError {
     #[error("{0}")]
     UnknownError(String),
 }
+
+#[derive(Error, Debug, PartialEq)]
+pub enum RootOfUnityError {
+    #[error("cannot get root of unity for n = 0")]
+    ZeroOrder,
+
+    #[error("root of unity of order 2^{0} does not exist; order cannot exceed 2^{1}")]
+    OrderTooLarge(u32, u32),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum UtilsError {
+    #[error("{0} is not a power of two")]
+    NotPowerOfTwo(usize),
+
+    #[error("power series must contain at least one element")]
+    EmptyPowerSeries,
+}
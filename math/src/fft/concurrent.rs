@@ -5,7 +5,7 @@
 
 use crate::{
     field::{FieldElement, StarkField},
-    utils::log2,
+    utils::log2_unchecked,
 };
 use rayon::prelude::*;
 use utils::uninit_vector;
@@ -17,7 +17,7 @@ use utils::uninit_vector;
 /// `p` is updated with results of the evaluation.
 pub fn evaluate_poly<B, E>(p: &mut [E], twiddles: &[B])
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     split_radix_fft(p, twiddles);
@@ -38,7 +38,8 @@ where
     E: FieldElement + From<B>,
 {
     let domain_size = p.len() * blowup_factor;
-    let g = B::get_root_of_unity(log2(domain_size));
+    let g = B::get_root_of_unity(log2_unchecked(domain_size))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", domain_size, err));
     let mut result = uninit_vector(domain_size);
 
     result
@@ -56,6 +57,43 @@ where
     result
 }
 
+/// Evaluates a polynomial whose coefficients are stored in the first `values.len() /
+/// blowup_factor` elements of `values` over the domain of size `values.len()` shifted by the
+/// `domain_offset`; the evaluation is done in-place.
+pub fn evaluate_poly_in_place<B, E>(
+    values: &mut [E],
+    twiddles: &[B],
+    domain_offset: B,
+    blowup_factor: usize,
+) where
+    B: StarkField,
+    E: FieldElement + From<B>,
+{
+    let domain_size = values.len();
+    let g = B::get_root_of_unity(log2_unchecked(domain_size))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", domain_size, err));
+
+    // the first chunk holds polynomial coefficients, and thus, it is shifted in-place only after
+    // the coefficients have been copied into all other chunks; the offset of the first chunk is
+    // always equal to the domain offset
+    let poly_size = domain_size / blowup_factor;
+    let (p, chunks) = values.split_at_mut(poly_size);
+    chunks
+        .par_chunks_mut(poly_size)
+        .enumerate()
+        .for_each(|(i, chunk)| {
+            let idx = super::permute_index(blowup_factor, i + 1) as u64;
+            let offset = E::from(g.exp(idx.into()) * domain_offset);
+            clone_and_shift(p, chunk, offset);
+            split_radix_fft(chunk, twiddles);
+        });
+
+    shift_in_place(p, E::from(domain_offset));
+    split_radix_fft(p, twiddles);
+
+    permute(values);
+}
+
 // POLYNOMIAL INTERPOLATION
 // ================================================================================================
 
@@ -63,7 +101,7 @@ where
 /// is done in-place, meaning `values` are updated with polynomial coefficients.
 pub fn interpolate_poly<B, E>(v: &mut [E], inv_twiddles: &[B])
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     split_radix_fft(v, inv_twiddles);
@@ -78,7 +116,7 @@ where
 /// by `inv_twiddles` and offset by `domain_offset` factor.
 pub fn interpolate_poly_with_offset<B, E>(values: &mut [E], inv_twiddles: &[B], domain_offset: B)
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     split_radix_fft(values, inv_twiddles);
@@ -100,6 +138,24 @@ where
         });
 }
 
+/// Interpolates a polynomial from each of the provided `columns` in-place; columns are processed
+/// in parallel when there are enough of them to occupy all threads.
+pub fn interpolate_poly_batch<B, E>(columns: &mut [Vec<E>], inv_twiddles: &[B])
+where
+    B: FieldElement,
+    E: FieldElement + From<B>,
+{
+    if columns.len() >= rayon::current_num_threads() {
+        columns
+            .par_iter_mut()
+            .for_each(|column| super::serial::interpolate_poly(column, inv_twiddles));
+    } else {
+        for column in columns.iter_mut() {
+            super::interpolate_poly(column, inv_twiddles);
+        }
+    }
+}
+
 // PERMUTATIONS
 // ================================================================================================
 
@@ -131,7 +187,7 @@ pub fn permute<E: FieldElement>(v: &mut [E]) {
 
 /// In-place recursive FFT with permuted output.
 /// Adapted from: https://github.com/0xProject/OpenZKP/tree/master/algebra/primefield/src/fft
-pub(super) fn split_radix_fft<B: StarkField, E: FieldElement + From<B>>(
+pub(super) fn split_radix_fft<B: FieldElement, E: FieldElement + From<B>>(
     values: &mut [E],
     twiddles: &[B],
 ) {
@@ -140,7 +196,7 @@ pub(super) fn split_radix_fft<B: StarkField, E: FieldElement + From<B>>(
     let g = E::from(twiddles[twiddles.len() / 2]);
     debug_assert_eq!(g.exp((n as u32).into()), E::ONE);
 
-    let inner_len = 1_usize << (log2(n) / 2);
+    let inner_len = 1_usize << (log2_unchecked(n) / 2);
     let outer_len = n / inner_len;
     let stretch = outer_len / inner_len;
     debug_assert!(outer_len == inner_len || outer_len == 2 * inner_len);
@@ -239,3 +295,17 @@ fn clone_and_shift<E: FieldElement>(source: &[E], destination: &mut [E], offset:
             }
         });
 }
+
+fn shift_in_place<E: FieldElement>(values: &mut [E], offset: E) {
+    let batch_size = values.len() / rayon::current_num_threads().next_power_of_two();
+    values
+        .par_chunks_mut(batch_size)
+        .enumerate()
+        .for_each(|(i, batch)| {
+            let mut factor = offset.exp(((i * batch_size) as u64).into());
+            for value in batch.iter_mut() {
+                *value = *value * factor;
+                factor = factor * offset;
+            }
+        });
+}
@@ -3,6 +3,15 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+//! FFT-based evaluation and interpolation of polynomials over multiplicative subgroups of a
+//! base field and their cosets.
+//!
+//! Polynomials may have coefficients in an extension of the base field (e.g., composition
+//! polynomials in proofs which use a field extension): all functions of this module are generic
+//! over the type `E` of the coefficients, and accept twiddles from the base field `B` as long as
+//! `E` implements `From<B>`. Thus, the same twiddle tables are used for polynomials over the base
+//! field and over any of its extensions, and do not need to be converted.
+
 use crate::{
     field::{FieldElement, StarkField},
     utils,
@@ -32,7 +41,7 @@ pub const MIN_CONCURRENT_SIZE: usize = 1024;
 /// Otherwise, the evaluation is done in a single thread.
 pub fn evaluate_poly<B, E>(p: &mut [E], twiddles: &[B])
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     assert!(
@@ -61,6 +70,12 @@ where
 /// evaluated over domain specified by `twiddles`, expanded by the `blowup_factor`, and shifted
 /// by the `domain_offset`.
 ///
+/// That is, for n = `p.len() * blowup_factor` and a root of unity g of order n, the i-th element
+/// of the result is p(offset * g^i). The offset can be any element of the base field; e.g., LDEs
+/// can be built over cosets other than the one defined by the domain offset of proof options.
+/// Polynomials evaluated over a coset with a non-zero offset and no blowup can be recovered via
+/// [interpolate_poly_with_offset()].
+///
 /// When `concurrent` feature is enabled, the evaluation uses as many threads as are
 /// available in Rayon's global thread pool (usually as many threads as logical cores).
 /// Otherwise, the evaluation is done in a single thread.
@@ -109,6 +124,64 @@ where
     result
 }
 
+/// Evaluates a polynomial in the same way as [evaluate_poly_with_offset()], but does the
+/// evaluation in-place: coefficients of the polynomial are read from the first
+/// `values.len() / blowup_factor` elements of `values`, and `values` are then updated with
+/// evaluations of the polynomial over the domain of size `values.len()` shifted by the
+/// `domain_offset`. The remaining elements of `values` are overwritten, and thus, may contain
+/// arbitrary values when this function is called.
+///
+/// Unlike [evaluate_poly_with_offset()], this function does not allocate memory. Thus, a column
+/// can be extended in a single buffer of the size of the extended domain: the column is written
+/// into the start of the buffer, interpolated in-place, and then evaluated in-place.
+///
+/// The evaluation is done as a transposed FFT: the extended domain is split into
+/// `blowup_factor` cosets of the domain described by `twiddles`, each coset is evaluated via an
+/// FFT over a separate chunk of `values`, and the chunks are interleaved by a single permutation
+/// at the end. When `concurrent` feature is enabled, chunks are evaluated in parallel, and each
+/// chunk is evaluated using the four-step FFT over as many threads as are available in Rayon's
+/// global thread pool. Otherwise, the evaluation is done in a single thread.
+///
+/// # Panics
+/// Panics if:
+/// * Length of `values` or the `blowup_factor` is not a power of two.
+/// * The number of `twiddles` is not half of `values.len() / blowup_factor`.
+pub fn evaluate_poly_in_place<B, E>(
+    values: &mut [E],
+    twiddles: &[B],
+    domain_offset: B,
+    blowup_factor: usize,
+) where
+    B: StarkField,
+    E: FieldElement + From<B>,
+{
+    assert!(
+        values.len().is_power_of_two(),
+        "number of values must be a power of 2"
+    );
+    assert!(
+        blowup_factor.is_power_of_two(),
+        "blowup factor must be a power of 2"
+    );
+    let poly_size = values.len() / blowup_factor;
+    assert_eq!(
+        poly_size,
+        twiddles.len() * 2,
+        "invalid number of twiddles: expected {} but received {}",
+        poly_size / 2,
+        twiddles.len()
+    );
+
+    // when `concurrent` feature is enabled, run the concurrent version of the function; unless
+    // the polynomial is small, then don't bother with the concurrent version
+    if cfg!(feature = "concurrent") && poly_size >= MIN_CONCURRENT_SIZE {
+        #[cfg(feature = "concurrent")]
+        concurrent::evaluate_poly_in_place(values, twiddles, domain_offset, blowup_factor);
+    } else {
+        serial::evaluate_poly_in_place(values, twiddles, domain_offset, blowup_factor);
+    }
+}
+
 // POLYNOMIAL INTERPOLATION
 // ================================================================================================
 
@@ -120,7 +193,7 @@ where
 /// Otherwise, the interpolation is done in a single thread.
 pub fn interpolate_poly<B, E>(values: &mut [E], inv_twiddles: &[B])
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     debug_assert!(
@@ -149,12 +222,21 @@ where
 /// Uses FFT algorithm to interpolate a polynomial from provided `values` over the domain defined
 /// by `inv_twiddles` and offset by `domain_offset` factor.
 ///
+/// This is the inverse of [evaluate_poly_with_offset()] with the blowup factor of 1: the i-th
+/// element of `values` is expected to be the evaluation of the polynomial at offset * g^i, where
+/// g is a root of unity of order `values.len()`.
+///
 /// When `concurrent` feature is enabled, interpolation is done using as many threads as are
 /// available in Rayon's global thread pool (usually as many threads as logical cores).
 /// Otherwise, the interpolation is done in a single thread.
+///
+/// # Panics
+/// Panics if:
+/// * The number of `inv_twiddles` is not half of `values.len()`.
+/// * The `domain_offset` is zero.
 pub fn interpolate_poly_with_offset<B, E>(values: &mut [E], inv_twiddles: &[B], domain_offset: B)
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     debug_assert!(
@@ -169,6 +251,7 @@ where
         values.len() / 2,
         inv_twiddles.len()
     );
+    assert!(domain_offset != B::ZERO, "domain offset cannot be zero");
 
     // when `concurrent` feature is enabled, run the concurrent version of the function; unless
     // the polynomial is small, then don't bother with the concurrent version
@@ -180,6 +263,41 @@ where
     }
 }
 
+/// Uses FFT algorithm to interpolate a polynomial from each of the provided `columns`; the
+/// interpolation is done in-place, meaning every column is updated with coefficients of its
+/// polynomial. All columns must have the same length, and share the same `inv_twiddles`.
+///
+/// When `concurrent` feature is enabled and there are at least as many columns as there are
+/// threads in Rayon's global thread pool, each column is interpolated in a separate task using
+/// a single-threaded FFT; this way, parallelism scales with the number of columns rather than
+/// with their length. With fewer columns, the columns are interpolated one after another using
+/// all available threads for each column. Otherwise, the interpolation is done in a single
+/// thread.
+pub fn interpolate_poly_batch<B, E>(columns: &mut [Vec<E>], inv_twiddles: &[B])
+where
+    B: FieldElement,
+    E: FieldElement + From<B>,
+{
+    for column in columns.iter() {
+        assert_eq!(
+            column.len(),
+            inv_twiddles.len() * 2,
+            "invalid number of twiddles: expected {} but received {}",
+            column.len() / 2,
+            inv_twiddles.len()
+        );
+    }
+
+    if cfg!(feature = "concurrent") {
+        #[cfg(feature = "concurrent")]
+        concurrent::interpolate_poly_batch(columns, inv_twiddles);
+    } else {
+        for column in columns.iter_mut() {
+            serial::interpolate_poly(column, inv_twiddles);
+        }
+    }
+}
+
 // TWIDDLES
 // ================================================================================================
 
@@ -194,8 +312,9 @@ pub fn get_twiddles<B: StarkField>(domain_size: usize) -> Vec<B> {
         domain_size.is_power_of_two(),
         "domain size must be a power of 2"
     );
-    let root = B::get_root_of_unity(utils::log2(domain_size));
-    let mut twiddles = utils::get_power_series(root, domain_size / 2);
+    let root = B::get_root_of_unity(utils::log2_unchecked(domain_size))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", domain_size, err));
+    let mut twiddles = utils::get_power_series_unchecked(root, domain_size / 2);
     permute(&mut twiddles);
     twiddles
 }
@@ -211,9 +330,10 @@ pub fn get_inv_twiddles<B: StarkField>(domain_size: usize) -> Vec<B> {
         domain_size.is_power_of_two(),
         "domain size must be a power of 2"
     );
-    let root = B::get_root_of_unity(utils::log2(domain_size));
+    let root = B::get_root_of_unity(utils::log2_unchecked(domain_size))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", domain_size, err));
     let inv_root = root.exp((domain_size as u32 - 1).into());
-    let mut inv_twiddles = utils::get_power_series(inv_root, domain_size / 2);
+    let mut inv_twiddles = utils::get_power_series_unchecked(inv_root, domain_size / 2);
     permute(&mut inv_twiddles);
     inv_twiddles
 }
@@ -240,7 +360,7 @@ where
 // HELPER FUNCTIONS
 // ================================================================================================
 
-fn permute<E: FieldElement>(v: &mut [E]) {
+pub(crate) fn permute<E: FieldElement>(v: &mut [E]) {
     if cfg!(feature = "concurrent") && v.len() >= MIN_CONCURRENT_SIZE {
         #[cfg(feature = "concurrent")]
         concurrent::permute(v);
@@ -249,7 +369,7 @@ fn permute<E: FieldElement>(v: &mut [E]) {
     }
 }
 
-fn permute_index(size: usize, index: usize) -> usize {
+pub(crate) fn permute_index(size: usize, index: usize) -> usize {
     debug_assert!(index < size);
     if size == 1 {
         return 0;
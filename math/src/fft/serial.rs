@@ -5,7 +5,7 @@
 
 use crate::{
     field::{FieldElement, StarkField},
-    utils::log2,
+    utils::log2_unchecked,
 };
 use utils::uninit_vector;
 
@@ -20,7 +20,7 @@ const MAX_LOOP: usize = 256;
 /// `p` is updated with results of the evaluation.
 pub fn evaluate_poly<B, E>(p: &mut [E], twiddles: &[B])
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     fft_in_place(p, twiddles, 1, 1, 0);
@@ -41,7 +41,8 @@ where
     E: FieldElement + From<B>,
 {
     let domain_size = p.len() * blowup_factor;
-    let g = B::get_root_of_unity(log2(domain_size));
+    let g = B::get_root_of_unity(log2_unchecked(domain_size))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", domain_size, err));
     let mut result = uninit_vector(domain_size);
 
     result
@@ -63,6 +64,49 @@ where
     result
 }
 
+/// Evaluates a polynomial whose coefficients are stored in the first `values.len() /
+/// blowup_factor` elements of `values` over the domain of size `values.len()` shifted by the
+/// `domain_offset`; the evaluation is done in-place.
+pub fn evaluate_poly_in_place<B, E>(
+    values: &mut [E],
+    twiddles: &[B],
+    domain_offset: B,
+    blowup_factor: usize,
+) where
+    B: StarkField,
+    E: FieldElement + From<B>,
+{
+    let domain_size = values.len();
+    let g = B::get_root_of_unity(log2_unchecked(domain_size))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", domain_size, err));
+
+    // the first chunk holds polynomial coefficients, and thus, it is shifted in-place only after
+    // the coefficients have been copied into all other chunks; the offset of the first chunk is
+    // always equal to the domain offset
+    let poly_size = domain_size / blowup_factor;
+    let (p, chunks) = values.split_at_mut(poly_size);
+    for (i, chunk) in chunks.chunks_mut(poly_size).enumerate() {
+        let idx = super::permute_index(blowup_factor, i + 1) as u64;
+        let offset = E::from(g.exp(idx.into()) * domain_offset);
+        let mut factor = E::ONE;
+        for (d, c) in chunk.iter_mut().zip(p.iter()) {
+            *d = *c * factor;
+            factor *= offset;
+        }
+        fft_in_place(chunk, twiddles, 1, 1, 0);
+    }
+
+    let offset = E::from(domain_offset);
+    let mut factor = E::ONE;
+    for c in p.iter_mut() {
+        *c *= factor;
+        factor *= offset;
+    }
+    fft_in_place(p, twiddles, 1, 1, 0);
+
+    permute(values);
+}
+
 // POLYNOMIAL INTERPOLATION
 // ================================================================================================
 
@@ -70,7 +114,7 @@ where
 /// is done in-place, meaning `values` are updated with polynomial coefficients.
 pub fn interpolate_poly<B, E>(v: &mut [E], inv_twiddles: &[B])
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     fft_in_place(v, inv_twiddles, 1, 1, 0);
@@ -85,7 +129,7 @@ where
 /// by `inv_twiddles` and offset by `domain_offset` factor.
 pub fn interpolate_poly_with_offset<B, E>(values: &mut [E], inv_twiddles: &[B], domain_offset: B)
 where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     fft_in_place(values, inv_twiddles, 1, 1, 0);
@@ -124,7 +168,7 @@ pub(super) fn fft_in_place<B, E>(
     stride: usize,
     offset: usize,
 ) where
-    B: StarkField,
+    B: FieldElement,
     E: FieldElement + From<B>,
 {
     let size = values.len() / stride;
@@ -146,15 +190,17 @@ pub(super) fn fft_in_place<B, E>(
         butterfly(values, offset, stride);
     }
 
+    // since count never exceeds stride, the values in [offset, offset + count) and
+    // [offset + stride, offset + stride + count) ranges do not overlap, and butterflies for
+    // all of them can be computed in a single batch
     let last_offset = offset + size * stride;
     for (i, offset) in (offset..last_offset)
         .step_by(2 * stride)
         .enumerate()
         .skip(1)
     {
-        for j in offset..(offset + count) {
-            butterfly_twiddle(values, twiddles[i], j, stride);
-        }
+        let (lo, hi) = values[offset..].split_at_mut(stride);
+        E::batch_butterfly(&mut lo[..count], &mut hi[..count], E::from(twiddles[i]));
     }
 }
 
@@ -169,17 +215,3 @@ fn butterfly<E: FieldElement>(values: &mut [E], offset: usize, stride: usize) {
     values[i] = temp + values[j];
     values[j] = temp - values[j];
 }
-
-#[inline(always)]
-fn butterfly_twiddle<B, E>(values: &mut [E], twiddle: B, offset: usize, stride: usize)
-where
-    B: StarkField,
-    E: FieldElement + From<B>,
-{
-    let i = offset;
-    let j = offset + stride;
-    let temp = values[i];
-    values[j] *= E::from(twiddle);
-    values[i] = temp + values[j];
-    values[j] = temp - values[j];
-}
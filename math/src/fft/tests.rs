@@ -4,9 +4,9 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{
-    field::{f128::BaseElement, FieldElement, StarkField},
+    field::{f128::BaseElement, f31, FieldElement, QuadExtension, StarkField},
     polynom,
-    utils::{get_power_series, log2},
+    utils::{get_power_series_unchecked, log2_unchecked},
 };
 use utils::AsBytes;
 
@@ -44,6 +44,77 @@ fn fft_evaluate_poly_with_offset() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn fft_evaluate_poly_in_place() {
+    let offset = BaseElement::GENERATOR;
+    for &m in [8, super::MIN_CONCURRENT_SIZE * 2].iter() {
+        let p = build_random_element_vec(m);
+        let twiddles = super::get_twiddles::<BaseElement>(m);
+        for &blowup_factor in [1, 2, 8].iter() {
+            let expected = super::evaluate_poly_with_offset(&p, &twiddles, offset, blowup_factor);
+
+            // values past the coefficients do not affect the result
+            let mut values = build_random_element_vec(m * blowup_factor);
+            values[..m].copy_from_slice(&p);
+            super::evaluate_poly_in_place(&mut values, &twiddles, offset, blowup_factor);
+            assert_eq!(expected, values);
+        }
+    }
+
+    // extending a column in a single buffer yields the same result as separate interpolation
+    // and evaluation
+    let n = 16;
+    let column = build_random_element_vec(n);
+    let mut poly = column.clone();
+    super::interpolate_poly(&mut poly, &super::get_inv_twiddles::<BaseElement>(n));
+    let twiddles = super::get_twiddles::<BaseElement>(n);
+    let expected = super::evaluate_poly_with_offset(&poly, &twiddles, offset, 4);
+
+    let mut values = vec![BaseElement::ZERO; n * 4];
+    values[..n].copy_from_slice(&column);
+    super::interpolate_poly(&mut values[..n], &super::get_inv_twiddles::<BaseElement>(n));
+    super::evaluate_poly_in_place(&mut values, &twiddles, offset, 4);
+    assert_eq!(expected, values);
+}
+
+#[test]
+#[should_panic(expected = "invalid number of twiddles: expected 4 but received 8")]
+fn fft_evaluate_poly_in_place_wrong_twiddles() {
+    let mut values = vec![BaseElement::ZERO; 16];
+    let twiddles = super::get_twiddles::<BaseElement>(16);
+    super::evaluate_poly_in_place(&mut values, &twiddles, BaseElement::GENERATOR, 2);
+}
+
+#[test]
+fn fft_extension_poly_with_base_twiddles() {
+    type E = QuadExtension<BaseElement>;
+    let offset = BaseElement::GENERATOR;
+    let m = super::MIN_CONCURRENT_SIZE * 2;
+    let p = E::prng_vector(build_seed(), m);
+
+    // the polynomial is evaluated using twiddles from the base field
+    let twiddles = super::get_twiddles::<BaseElement>(m);
+    let evaluations = super::evaluate_poly_with_offset(&p, &twiddles, offset, 2);
+    let domain = build_domain(m * 2);
+    for (&x, &y) in domain.iter().zip(evaluations.iter()).step_by(97) {
+        assert_eq!(polynom::eval(&p, E::from(x * offset)), y);
+    }
+
+    let mut values = vec![E::ZERO; m * 2];
+    values[..m].copy_from_slice(&p);
+    super::evaluate_poly_in_place(&mut values, &twiddles, offset, 2);
+    assert_eq!(evaluations, values);
+
+    // and is interpolated back using inverse twiddles from the base field
+    let mut values = super::evaluate_poly_with_offset(&p, &twiddles, offset, 1);
+    super::interpolate_poly_with_offset(
+        &mut values,
+        &super::get_inv_twiddles::<BaseElement>(m),
+        offset,
+    );
+    assert_eq!(p, values);
+}
+
 // POLYNOMIAL INTERPOLATION
 // ================================================================================================
 
@@ -75,6 +146,60 @@ fn fft_interpolate_poly_with_offset() {
     assert_eq!(expected, ys);
 }
 
+#[test]
+fn fft_coset_round_trip() {
+    let n = 32;
+    let p = build_random_element_vec(n);
+    let twiddles = super::get_twiddles::<BaseElement>(n);
+    let inv_twiddles = super::get_inv_twiddles::<BaseElement>(n);
+    let domain = build_domain(n);
+
+    // cosets defined by arbitrary offsets, including the subgroup itself
+    for &offset in [
+        BaseElement::ONE,
+        BaseElement::from(7u32),
+        BaseElement::rand(),
+    ]
+    .iter()
+    {
+        let mut values = super::evaluate_poly_with_offset(&p, &twiddles, offset, 1);
+        let shifted_domain = domain.iter().map(|&x| x * offset).collect::<Vec<_>>();
+        assert_eq!(polynom::eval_many(&p, &shifted_domain), values);
+
+        super::interpolate_poly_with_offset(&mut values, &inv_twiddles, offset);
+        assert_eq!(p, values);
+    }
+}
+
+#[test]
+#[should_panic(expected = "domain offset cannot be zero")]
+fn fft_interpolate_poly_with_zero_offset() {
+    let mut values = build_random_element_vec(16);
+    let inv_twiddles = super::get_inv_twiddles::<BaseElement>(16);
+    super::interpolate_poly_with_offset(&mut values, &inv_twiddles, BaseElement::ZERO);
+}
+
+#[test]
+fn fft_interpolate_poly_batch() {
+    let n = super::MIN_CONCURRENT_SIZE * 2;
+    let domain = build_domain(n);
+    let inv_twiddles = super::get_inv_twiddles::<BaseElement>(n);
+
+    // use both fewer and more columns than threads in a typical thread pool
+    for &num_columns in [1, 3, 64].iter() {
+        let expected = (0..num_columns)
+            .map(|_| build_random_element_vec(n))
+            .collect::<Vec<_>>();
+        let mut columns = expected
+            .iter()
+            .map(|p| polynom::eval_many(p, &domain))
+            .collect::<Vec<_>>();
+
+        super::interpolate_poly_batch(&mut columns, &inv_twiddles);
+        assert_eq!(expected, columns);
+    }
+}
+
 // CORE ALGORITHMS
 // ================================================================================================
 
@@ -121,12 +246,31 @@ fn fft_in_place() {
     assert_eq!(expected, p);
 }
 
+#[test]
+fn fft_small_field() {
+    // the 31-bit field supports domains of up to 2^27 elements
+    type B31 = f31::BaseElement;
+    let n = super::MIN_CONCURRENT_SIZE * 2;
+    let mut p = B31::prng_vector(build_seed(), n);
+    let expected = p.clone();
+
+    let g = B31::get_root_of_unity(log2_unchecked(n)).unwrap();
+    let domain = get_power_series_unchecked(g, n);
+    let evaluations = polynom::eval_many(&p, &domain);
+
+    super::evaluate_poly(&mut p, &super::get_twiddles::<B31>(n));
+    assert_eq!(evaluations, p);
+
+    super::interpolate_poly(&mut p, &super::get_inv_twiddles::<B31>(n));
+    assert_eq!(expected, p);
+}
+
 #[test]
 fn fft_get_twiddles() {
     let n = super::MIN_CONCURRENT_SIZE * 2;
-    let g = BaseElement::get_root_of_unity(log2(n));
+    let g = BaseElement::get_root_of_unity(log2_unchecked(n)).unwrap();
 
-    let mut expected = get_power_series(g, n / 2);
+    let mut expected = get_power_series_unchecked(g, n / 2);
     super::permute(&mut expected);
 
     let twiddles = super::get_twiddles::<BaseElement>(n);
@@ -147,6 +291,6 @@ fn build_random_element_vec(size: usize) -> Vec<BaseElement> {
 }
 
 fn build_domain(size: usize) -> Vec<BaseElement> {
-    let g = BaseElement::get_root_of_unity(log2(size));
-    get_power_series(g, size)
+    let g = BaseElement::get_root_of_unity(log2_unchecked(size)).unwrap();
+    get_power_series_unchecked(g, size)
 }
@@ -0,0 +1,558 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::traits::{FieldElement, StarkField};
+use crate::errors::{ElementDecodingError, SerializationError};
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt::{Debug, Display, Formatter},
+    mem,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    slice,
+};
+use rand::prelude::*;
+use utils::{AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+pub use super::U256;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Field modulus = 52435875175126190479447740508185965837690552500527637822603658699938581184513
+/// (the order of the BLS12-381 elliptic curve subgroup), in little-endian 64-bit limbs.
+const M: [u64; 4] = [
+    0xffffffff00000001,
+    0x53bda402fffe5bfe,
+    0x3339d80809a1d805,
+    0x73eda753299d7d48,
+];
+
+/// -M^{-1} mod 2^64; this is used during Montgomery reduction.
+const U: u64 = 0xfffffffeffffffff;
+
+/// 2^512 mod M; this is used to bring results of Montgomery multiplication back into canonical
+/// representation.
+const R2: [u64; 4] = [
+    0xc999e990f3f29c6d,
+    0x2b6cedcb87925c23,
+    0x05d314967254398f,
+    0x0748d9d99f59ff11,
+];
+
+/// Number of bytes needed to represent field element
+const ELEMENT_BYTES: usize = 32;
+
+/// Mask which clears the top bit of the most significant limb; this is used to sample random
+/// 255-bit values.
+const TOP_LIMB_MASK: u64 = u64::MAX >> 1;
+
+// 2^32 root of unity
+const G: [u64; 4] = [
+    0x3829971f439f0d2b,
+    0xb63683508c2280b9,
+    0xd09b681922c813b4,
+    0x16a2a19edfe81f20,
+];
+
+// FIELD ELEMENT
+// ================================================================================================
+
+/// Base field element; internal values are stored in canonical representation as four 64-bit
+/// limbs in little-endian order, and are always in the range [0, M).
+///
+/// The field is the scalar field of the BLS12-381 elliptic curve; proofs over this field can be
+/// verified inside of pairing-based proof systems defined over this curve without emulating
+/// arithmetic of a different field. Multiplication is performed using Montgomery reduction, but
+/// values are converted back into canonical representation after every operation.
+///
+/// Since internal and canonical representations are the same, byte representations of elements
+/// returned by [FieldElement::elements_as_bytes()] can be compared and hashed directly.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BaseElement([u64; 4]);
+
+impl BaseElement {
+    /// Creates a new field element from the provided `value`.
+    pub const fn new(value: u64) -> BaseElement {
+        BaseElement([value, 0, 0, 0])
+    }
+}
+
+impl FieldElement for BaseElement {
+    type PositiveInteger = U256;
+    type Base = Self;
+
+    const ZERO: Self = BaseElement::new(0);
+    const ONE: Self = BaseElement::new(1);
+
+    const ELEMENT_BYTES: usize = ELEMENT_BYTES;
+
+    fn exp(self, power: Self::PositiveInteger) -> Self {
+        if power == U256::default() {
+            return Self::ONE;
+        } else if self == Self::ZERO {
+            return Self::ZERO;
+        }
+
+        // the computation is done in Montgomery representation so that every step requires a
+        // single Montgomery multiplication; the result is converted back at the end
+        let b = mont_mul(self.0, R2);
+        let mut r = b;
+        for i in (0..power.bits() - 1).rev() {
+            r = mont_mul(r, r);
+            if (power.0[(i / 64) as usize] >> (i % 64)) & 1 == 1 {
+                r = mont_mul(r, b);
+            }
+        }
+
+        BaseElement(mont_mul(r, [1, 0, 0, 0]))
+    }
+
+    fn inv(self) -> Self {
+        // by Fermat's little theorem, x^(M - 2) = x^{-1} for all non-zero x; for x = 0, this
+        // evaluates to 0
+        let mut power = M;
+        power[0] -= 2;
+        self.exp(U256(power))
+    }
+
+    fn conjugate(&self) -> Self {
+        BaseElement(self.0)
+    }
+
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        sample(rng)
+    }
+
+    fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::try_from(bytes).ok()
+    }
+
+    fn to_canonical_bytes(self) -> Vec<u8> {
+        U256(self.0).to_le_bytes().to_vec()
+    }
+
+    fn elements_into_bytes(elements: Vec<Self>) -> Vec<u8> {
+        let mut v = std::mem::ManuallyDrop::new(elements);
+        let p = v.as_mut_ptr();
+        let len = v.len() * Self::ELEMENT_BYTES;
+        let cap = v.capacity() * Self::ELEMENT_BYTES;
+        unsafe { Vec::from_raw_parts(p as *mut u8, len, cap) }
+    }
+
+    fn elements_as_bytes(elements: &[Self]) -> &[u8] {
+        // TODO: take endianness into account
+        let p = elements.as_ptr();
+        let len = elements.len() * Self::ELEMENT_BYTES;
+        unsafe { slice::from_raw_parts(p as *const u8, len) }
+    }
+
+    unsafe fn bytes_as_elements(bytes: &[u8]) -> Result<&[Self], SerializationError> {
+        if !bytes.len().is_multiple_of(Self::ELEMENT_BYTES) {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                bytes.len(),
+            ));
+        }
+
+        let p = bytes.as_ptr();
+        let len = bytes.len() / Self::ELEMENT_BYTES;
+
+        if !(p as usize).is_multiple_of(mem::align_of::<u64>()) {
+            return Err(SerializationError::InvalidMemoryAlignment);
+        }
+
+        Ok(slice::from_raw_parts(p as *const Self, len))
+    }
+
+    fn zeroed_vector(n: usize) -> Vec<Self> {
+        // this uses a specialized vector initialization code which requests zero-filled memory
+        // from the OS; unfortunately, this works only for built-in types and we can't use
+        // Self::ZERO here as much less efficient initialization procedure will be invoked.
+        // We also use u64 to make sure the memory is aligned correctly for our element size.
+        let result = vec![0u64; n * 4];
+
+        // translate a zero-filled vector of u64s into a vector of base field elements; every
+        // element occupies 4 consecutive u64s
+        let mut v = std::mem::ManuallyDrop::new(result);
+        let p = v.as_mut_ptr();
+        let len = v.len() / 4;
+        let cap = v.capacity() / 4;
+        unsafe { Vec::from_raw_parts(p as *mut Self, len, cap) }
+    }
+
+    fn prng_vector(seed: [u8; 32], n: usize) -> Vec<Self> {
+        let mut g = StdRng::from_seed(seed);
+        (0..n).map(|_| sample(&mut g)).collect()
+    }
+}
+
+impl StarkField for BaseElement {
+    /// sage: x = -0xd201000000010000
+    /// sage: MODULUS = x^4 - x^2 + 1
+    /// sage: GF(MODULUS).is_prime_field()
+    /// True
+    /// sage: GF(MODULUS).order()
+    /// 52435875175126190479447740508185965837690552500527637822603658699938581184513
+    const MODULUS: Self::PositiveInteger = U256(M);
+    const MODULUS_BITS: u32 = 255;
+
+    /// sage: GF(MODULUS).primitive_element()
+    /// 7
+    const GENERATOR: Self = BaseElement::new(7);
+
+    /// sage: is_odd((MODULUS - 1) / 2^32)
+    /// True
+    const TWO_ADICITY: u32 = 32;
+
+    /// sage: k = (MODULUS - 1) / 2^32
+    /// sage: GF(MODULUS).primitive_element()^k
+    /// 10238227357739495823651030575849232062558860180284477541189508159991286009131
+    const TWO_ADIC_ROOT_OF_UNITY: Self = BaseElement(G);
+
+    fn get_modulus_le_bytes() -> Vec<u8> {
+        Self::MODULUS.to_le_bytes().to_vec()
+    }
+
+    fn as_int(&self) -> Self::PositiveInteger {
+        U256(self.0)
+    }
+}
+
+impl Display for BaseElement {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "{}", U256(self.0))
+    }
+}
+
+// OVERLOADED OPERATORS
+// ================================================================================================
+
+impl Add for BaseElement {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(add(self.0, rhs.0))
+    }
+}
+
+impl AddAssign for BaseElement {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl Sub for BaseElement {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(sub(self.0, rhs.0))
+    }
+}
+
+impl SubAssign for BaseElement {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for BaseElement {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(mul(self.0, rhs.0))
+    }
+}
+
+impl MulAssign for BaseElement {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl Div for BaseElement {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl DivAssign for BaseElement {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
+impl Neg for BaseElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(sub([0; 4], self.0))
+    }
+}
+
+// TYPE CONVERSIONS
+// ================================================================================================
+
+impl From<U256> for BaseElement {
+    /// Converts a 256-bit value into a field element. If the value is greater than or equal to
+    /// the field modulus, modular reduction is silently performed.
+    fn from(value: U256) -> Self {
+        // 2^256 < 3M, and thus at most 2 subtractions are needed
+        let mut value = value;
+        while value >= U256(M) {
+            value = U256(sub_no_reduce(value.0, M).0);
+        }
+        BaseElement(value.0)
+    }
+}
+
+impl From<u128> for BaseElement {
+    /// Converts a 128-bit value into a field element.
+    fn from(value: u128) -> Self {
+        BaseElement(U256::from(value).0)
+    }
+}
+
+impl From<u64> for BaseElement {
+    /// Converts a 64-bit value into a field element.
+    fn from(value: u64) -> Self {
+        BaseElement::new(value)
+    }
+}
+
+impl From<u32> for BaseElement {
+    /// Converts a 32-bit value into a field element.
+    fn from(value: u32) -> Self {
+        BaseElement::new(value as u64)
+    }
+}
+
+impl From<u16> for BaseElement {
+    /// Converts a 16-bit value into a field element.
+    fn from(value: u16) -> Self {
+        BaseElement::new(value as u64)
+    }
+}
+
+impl From<u8> for BaseElement {
+    /// Converts an 8-bit value into a field element.
+    fn from(value: u8) -> Self {
+        BaseElement::new(value as u64)
+    }
+}
+
+impl From<[u8; 32]> for BaseElement {
+    /// Converts the value encoded in an array of 32 bytes into a field element. The bytes are
+    /// assumed to encode the element in the canonical representation in little-endian byte order.
+    /// If the value is greater than or equal to the field modulus, modular reduction is silently
+    /// performed.
+    fn from(bytes: [u8; 32]) -> Self {
+        BaseElement::from(U256::from_le_bytes(bytes))
+    }
+}
+
+impl TryFrom<&[u8]> for BaseElement {
+    type Error = ElementDecodingError;
+
+    /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
+    /// is not a valid field element. The bytes are assumed to encode the element in the canonical
+    /// representation in little-endian byte order.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() < ELEMENT_BYTES {
+            return Err(ElementDecodingError::NotEnoughBytes(
+                ELEMENT_BYTES,
+                bytes.len(),
+            ));
+        }
+        if bytes.len() > ELEMENT_BYTES {
+            return Err(ElementDecodingError::TooManyBytes(
+                ELEMENT_BYTES,
+                bytes.len(),
+            ));
+        }
+        let value = bytes
+            .try_into()
+            .map(U256::from_le_bytes)
+            .map_err(|error| ElementDecodingError::UnknownError(format!("{}", error)))?;
+        if value >= U256(M) {
+            return Err(ElementDecodingError::ValueTooLarger(format!("{}", value)));
+        }
+        Ok(BaseElement(value.0))
+    }
+}
+
+impl AsBytes for BaseElement {
+    fn as_bytes(&self) -> &[u8] {
+        // TODO: take endianness into account
+        let self_ptr: *const BaseElement = self;
+        unsafe { slice::from_raw_parts(self_ptr as *const u8, ELEMENT_BYTES) }
+    }
+}
+
+impl Serializable for BaseElement {
+    /// Writes the element in canonical representation in little-endian byte order.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(&U256(self.0).to_le_bytes());
+    }
+}
+
+impl Deserializable for BaseElement {
+    /// Reads an element encoded in canonical representation in little-endian byte order; returns
+    /// an error if the encoded value is not a valid field element.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let value = U256::from_le_bytes(source.read_u8_array()?);
+        if value >= U256(M) {
+            return Err(DeserializationError::InvalidValue(format!(
+                "value {} is greater than or equal to the field modulus",
+                value
+            )));
+        }
+        Ok(BaseElement(value.0))
+    }
+}
+
+// FINITE FIELD ARITHMETIC
+// ================================================================================================
+
+/// Computes (a + b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
+#[inline(always)]
+fn add(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+    // a + b < 2M < 2^256, and thus the sum cannot overflow 256 bits
+    let mut result = [0u64; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        let (s, c) = adc(a[i], b[i], carry);
+        result[i] = s;
+        carry = c;
+    }
+    normalize(result)
+}
+
+/// Computes (a - b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
+#[inline(always)]
+fn sub(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+    let (result, borrow) = sub_no_reduce(a, b);
+    if borrow {
+        // the difference wrapped around 2^256; adding M wraps it around again to a - b + M
+        let mut result = result;
+        let mut carry = 0;
+        for i in 0..4 {
+            let (s, c) = adc(result[i], M[i], carry);
+            result[i] = s;
+            carry = c;
+        }
+        result
+    } else {
+        result
+    }
+}
+
+/// Computes (a * b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
+///
+/// Montgomery multiplication of a and b yields a * b / 2^256; multiplying the result by
+/// 2^512 mod M in the same way removes the extra factor.
+#[inline(always)]
+fn mul(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+    mont_mul(mont_mul(a, b), R2)
+}
+
+/// Computes a * b / 2^256 reduced by M such that the output is in [0, M) range; a and b are
+/// assumed to be in [0, M).
+///
+/// This uses the coarsely integrated operand scanning (CIOS) method: a row of the product is
+/// accumulated and then reduced by a multiple of M which clears its lowest limb.
+#[inline(always)]
+fn mont_mul(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 6];
+    for &b_i in b.iter() {
+        // t = t + a * b_i
+        let mut carry = 0;
+        for j in 0..4 {
+            let (lo, hi) = mac(t[j], a[j], b_i, carry);
+            t[j] = lo;
+            carry = hi;
+        }
+        let (s, c) = t[4].overflowing_add(carry);
+        t[4] = s;
+        t[5] = c as u64;
+
+        // t = (t + m * M) / 2^64, where m is chosen such that the lowest limb becomes 0
+        let m = t[0].wrapping_mul(U);
+        let (_, mut carry) = mac(t[0], m, M[0], 0);
+        for j in 1..4 {
+            let (lo, hi) = mac(t[j], m, M[j], carry);
+            t[j - 1] = lo;
+            carry = hi;
+        }
+        let (s, c) = t[4].overflowing_add(carry);
+        t[3] = s;
+        t[4] = t[5] + c as u64;
+    }
+
+    // since a, b < M, the result is less than 2M < 2^256, and thus t[4] is always 0
+    normalize([t[0], t[1], t[2], t[3]])
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reduces any value in [0, 2M) range to [0, M) range
+#[inline(always)]
+fn normalize(value: [u64; 4]) -> [u64; 4] {
+    let (result, borrow) = sub_no_reduce(value, M);
+    if borrow {
+        value
+    } else {
+        result
+    }
+}
+
+/// Computes a - b over 256-bit values; returns the result modulo 2^256 and a flag indicating
+/// whether the subtraction underflowed.
+#[inline(always)]
+fn sub_no_reduce(a: [u64; 4], b: [u64; 4]) -> ([u64; 4], bool) {
+    let mut result = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        result[i] = d;
+        borrow = b1 | b2;
+    }
+    (result, borrow)
+}
+
+/// Computes a + b + carry; returns the low 64 bits of the result and the carry.
+#[inline(always)]
+const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = (a as u128) + (b as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Computes a + b * c + carry; returns the low and the high 64 bits of the result. The result
+/// always fits into 128 bits.
+#[inline(always)]
+const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = (a as u128) + (b as u128) * (c as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Samples a uniformly random field element by rejection sampling of 255-bit values.
+fn sample<R: Rng + ?Sized>(rng: &mut R) -> BaseElement {
+    loop {
+        let mut value: [u64; 4] = rng.gen();
+        value[3] &= TOP_LIMB_MASK;
+        if U256(value) < U256(M) {
+            return BaseElement(value);
+        }
+    }
+}
@@ -0,0 +1,372 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    AsBytes, BaseElement, Deserializable, DeserializationError, ElementDecodingError, FieldElement,
+    Serializable, SerializationError, StarkField, U256,
+};
+use num_bigint::BigUint;
+use proptest::prelude::*;
+use std::convert::TryFrom;
+
+// MANUAL TESTS
+// ================================================================================================
+
+#[test]
+fn add() {
+    // identity
+    let r = BaseElement::rand();
+    assert_eq!(r, r + BaseElement::ZERO);
+
+    // test addition within bounds
+    assert_eq!(
+        BaseElement::from(5u8),
+        BaseElement::from(2u8) + BaseElement::from(3u8)
+    );
+
+    // test overflow
+    let t = BaseElement::from(modulus_minus(1));
+    assert_eq!(BaseElement::ZERO, t + BaseElement::ONE);
+    assert_eq!(BaseElement::ONE, t + BaseElement::from(2u8));
+}
+
+#[test]
+fn sub() {
+    // identity
+    let r = BaseElement::rand();
+    assert_eq!(r, r - BaseElement::ZERO);
+
+    // test subtraction within bounds
+    assert_eq!(
+        BaseElement::from(2u8),
+        BaseElement::from(5u8) - BaseElement::from(3u8)
+    );
+
+    // test underflow
+    let expected = BaseElement::from(modulus_minus(2));
+    assert_eq!(expected, BaseElement::from(3u8) - BaseElement::from(5u8));
+}
+
+#[test]
+fn mul() {
+    // identity
+    let r = BaseElement::rand();
+    assert_eq!(BaseElement::ZERO, r * BaseElement::ZERO);
+    assert_eq!(r, r * BaseElement::ONE);
+
+    // test multiplication within bounds
+    assert_eq!(
+        BaseElement::from(15u8),
+        BaseElement::from(5u8) * BaseElement::from(3u8)
+    );
+
+    // test overflow
+    let t = BaseElement::from(modulus_minus(1));
+    assert_eq!(BaseElement::ONE, t * t);
+    assert_eq!(
+        BaseElement::from(modulus_minus(2)),
+        t * BaseElement::from(2u8)
+    );
+    assert_eq!(
+        BaseElement::from(modulus_minus(4)),
+        t * BaseElement::from(4u8)
+    );
+
+    // (M + 1) / 2 is the inverse of 2
+    let t = BaseElement::from(modulus_minus(1) >> 1) + BaseElement::ONE;
+    assert_eq!(BaseElement::ONE, t * BaseElement::from(2u8));
+}
+
+#[test]
+fn exp() {
+    let a = BaseElement::ZERO;
+    assert_eq!(a.exp(U256::from(0u32)), BaseElement::ONE);
+    assert_eq!(a.exp(U256::from(1u32)), BaseElement::ZERO);
+
+    let a = BaseElement::ONE;
+    assert_eq!(a.exp(U256::from(0u32)), BaseElement::ONE);
+    assert_eq!(a.exp(U256::from(1u32)), BaseElement::ONE);
+    assert_eq!(a.exp(U256::from(3u32)), BaseElement::ONE);
+
+    let a = BaseElement::rand();
+    assert_eq!(a.exp(U256::from(3u32)), a * a * a);
+
+    // by Fermat's little theorem, a^(M - 1) = 1 for all non-zero a
+    assert_eq!(BaseElement::ONE, a.exp(modulus_minus(1)));
+}
+
+#[test]
+fn inv() {
+    // identity
+    assert_eq!(BaseElement::ONE, BaseElement::inv(BaseElement::ONE));
+    assert_eq!(BaseElement::ZERO, BaseElement::inv(BaseElement::ZERO));
+}
+
+#[test]
+fn element_as_int() {
+    let v = U256([u64::MAX; 4]);
+    let e = BaseElement::from(v);
+    let expected = to_biguint(v) % to_biguint(BaseElement::MODULUS);
+    assert_eq!(expected, to_biguint(e.as_int()));
+}
+
+#[test]
+fn equals() {
+    let a = BaseElement::ONE;
+    let b = BaseElement::from(modulus_minus(1)) * BaseElement::from(modulus_minus(1));
+
+    // elements are equal, and since elements are stored in canonical representation, so are
+    // their internal representations
+    assert_eq!(a, b);
+    assert_eq!(a.as_int(), b.as_int());
+    assert_eq!(a.to_canonical_bytes(), b.to_canonical_bytes());
+    assert_eq!(a.as_bytes(), b.as_bytes());
+}
+
+#[test]
+fn mont_mul() {
+    // Montgomery multiplication by 2^256 mod M is the identity
+    let r = (BaseElement::from(U256([u64::MAX; 4])) + BaseElement::ONE).0;
+    for &v in [[0; 4], [1, 0, 0, 0], modulus_minus(1).0, super::R2].iter() {
+        assert_eq!(v, super::mont_mul(v, r));
+    }
+}
+
+// ROOTS OF UNITY
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn get_root_of_unity() {
+    let root_32 = BaseElement::get_root_of_unity(32).unwrap();
+    assert_eq!(BaseElement::TWO_ADIC_ROOT_OF_UNITY, root_32);
+    assert_eq!(BaseElement::ONE, root_32.exp(U256::from(1u32) << 32));
+    assert_ne!(BaseElement::ONE, root_32.exp(U256::from(1u32) << 31));
+
+    let root_31 = BaseElement::get_root_of_unity(31).unwrap();
+    let expected = root_32.exp(U256::from(2u32));
+    assert_eq!(expected, root_31);
+    assert_eq!(BaseElement::ONE, root_31.exp(U256::from(1u32) << 31));
+}
+
+// SERIALIZATION AND DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn from_u128() {
+    let v = u128::MAX;
+    let e = BaseElement::from(v);
+    assert_eq!(U256::from(v), e.as_int());
+}
+
+#[test]
+fn try_from_slice() {
+    let mut bytes = vec![0; 32];
+    bytes[0] = 1;
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert!(result.is_ok());
+    assert_eq!(U256::from(1u32), result.unwrap().as_int());
+
+    let result = BaseElement::try_from(&bytes[..31]);
+    assert_eq!(Err(ElementDecodingError::NotEnoughBytes(32, 31)), result);
+
+    let bytes = vec![1; 33];
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert_eq!(Err(ElementDecodingError::TooManyBytes(32, 33)), result);
+
+    let bytes = BaseElement::MODULUS.to_le_bytes();
+    let result = BaseElement::try_from(&bytes[..]);
+    assert_eq!(
+        Err(ElementDecodingError::ValueTooLarger(
+            "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+                .to_string()
+        )),
+        result
+    );
+}
+
+#[test]
+fn elements_into_bytes() {
+    let source = vec![
+        BaseElement::new(1),
+        BaseElement::new(2),
+        BaseElement::new(3),
+        BaseElement::new(4),
+    ];
+
+    let mut expected = vec![];
+    for element in source.iter() {
+        expected.extend_from_slice(&element.as_int().to_le_bytes());
+    }
+
+    assert_eq!(expected, BaseElement::elements_into_bytes(source));
+}
+
+#[test]
+fn elements_as_bytes() {
+    let source = vec![
+        BaseElement::new(1),
+        BaseElement::new(2),
+        BaseElement::new(3),
+        BaseElement::new(4),
+    ];
+
+    let mut expected = vec![];
+    for element in source.iter() {
+        expected.extend_from_slice(&element.as_int().to_le_bytes());
+    }
+
+    assert_eq!(expected, BaseElement::elements_as_bytes(&source));
+}
+
+#[test]
+fn bytes_as_elements() {
+    let elements = vec![
+        BaseElement::new(1),
+        BaseElement::new(2),
+        BaseElement::new(3),
+        BaseElement::new(4),
+    ];
+
+    let mut bytes = vec![];
+    for element in elements.iter() {
+        bytes.extend_from_slice(&element.as_int().to_le_bytes());
+    }
+    bytes.extend_from_slice(&BaseElement::new(5).as_int().to_le_bytes());
+
+    let result = unsafe { BaseElement::bytes_as_elements(&bytes[..128]) };
+    assert!(result.is_ok());
+    assert_eq!(elements, result.unwrap());
+
+    let result = unsafe { BaseElement::bytes_as_elements(&bytes[..129]) };
+    assert_eq!(
+        result,
+        Err(SerializationError::NotEnoughBytesForWholeElements(129))
+    );
+
+    let result = unsafe { BaseElement::bytes_as_elements(&bytes[1..129]) };
+    assert_eq!(result, Err(SerializationError::InvalidMemoryAlignment));
+}
+
+#[test]
+fn serialize_deserialize() {
+    let element = BaseElement::new(12345);
+    let bytes = element.to_bytes();
+    assert_eq!(U256::from(12345u32).to_le_bytes().to_vec(), bytes);
+    assert_eq!(Ok(element), BaseElement::read_from_bytes(&bytes));
+
+    let bytes = BaseElement::MODULUS.to_le_bytes();
+    assert!(matches!(
+        BaseElement::read_from_bytes(&bytes),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        BaseElement::read_from_bytes(&bytes[..31])
+    );
+}
+
+// INITIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn zeroed_vector() {
+    let result = BaseElement::zeroed_vector(4);
+    assert_eq!(4, result.len());
+    for element in result.into_iter() {
+        assert_eq!(BaseElement::ZERO, element);
+    }
+}
+
+#[test]
+fn prng_vector() {
+    let a = BaseElement::prng_vector([0; 32], 4);
+    assert_eq!(4, a.len());
+
+    let b = BaseElement::prng_vector([0; 32], 8);
+    assert_eq!(8, b.len());
+
+    for (&a, &b) in a.iter().zip(b.iter()) {
+        assert_eq!(a, b);
+    }
+
+    let c = BaseElement::prng_vector([1; 32], 4);
+    for (&a, &c) in a.iter().zip(c.iter()) {
+        assert_ne!(a, c);
+    }
+}
+
+// RANDOMIZED TESTS
+// ================================================================================================
+
+proptest! {
+
+    #[test]
+    fn add_proptest(a in any::<[u64; 4]>(), b in any::<[u64; 4]>()) {
+        let result = BaseElement::from(U256(a)) + BaseElement::from(U256(b));
+
+        let m = to_biguint(BaseElement::MODULUS);
+        let expected = (to_biguint(U256(a)) + to_biguint(U256(b))) % m;
+        prop_assert_eq!(expected, to_biguint(result.as_int()));
+    }
+
+    #[test]
+    fn sub_proptest(a in any::<[u64; 4]>(), b in any::<[u64; 4]>()) {
+        let result = BaseElement::from(U256(a)) - BaseElement::from(U256(b));
+
+        let m = to_biguint(BaseElement::MODULUS);
+        let a = to_biguint(U256(a)) % &m;
+        let b = to_biguint(U256(b)) % &m;
+        let expected = (a + &m - b) % m;
+        prop_assert_eq!(expected, to_biguint(result.as_int()));
+    }
+
+    #[test]
+    fn mul_proptest(a in any::<[u64; 4]>(), b in any::<[u64; 4]>()) {
+        let result = BaseElement::from(U256(a)) * BaseElement::from(U256(b));
+
+        let m = to_biguint(BaseElement::MODULUS);
+        let expected = (to_biguint(U256(a)) * to_biguint(U256(b))) % m;
+        prop_assert_eq!(expected, to_biguint(result.as_int()));
+    }
+
+    #[test]
+    fn exp_proptest(a in any::<[u64; 4]>(), b in any::<[u64; 4]>()) {
+        let result = BaseElement::from(U256(a)).exp(U256(b));
+
+        let m = to_biguint(BaseElement::MODULUS);
+        let expected = to_biguint(U256(a)).modpow(&to_biguint(U256(b)), &m);
+        prop_assert_eq!(expected, to_biguint(result.as_int()));
+    }
+
+    #[test]
+    fn inv_proptest(a in any::<[u64; 4]>()) {
+        let a = BaseElement::from(U256(a));
+        let b = a.inv();
+
+        let expected = if a == BaseElement::ZERO { BaseElement::ZERO } else { BaseElement::ONE };
+        prop_assert_eq!(expected, a * b);
+    }
+
+    #[test]
+    fn from_bytes_proptest(a in any::<[u64; 4]>()) {
+        let e = BaseElement::from(U256(a).to_le_bytes());
+
+        let m = to_biguint(BaseElement::MODULUS);
+        prop_assert_eq!(to_biguint(U256(a)) % m, to_biguint(e.as_int()));
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn to_biguint(value: U256) -> BigUint {
+    BigUint::from_bytes_le(&value.to_le_bytes())
+}
+
+fn modulus_minus(value: u64) -> U256 {
+    let mut result = BaseElement::MODULUS;
+    result.0[0] -= value;
+    result
+}
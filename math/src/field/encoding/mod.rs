@@ -0,0 +1,586 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Encodings of signed integers and fixed-point values as field elements.
+//!
+//! A signed integer v is encoded as v mod p, i.e., negative values are encoded as p - |v|. Field
+//! elements in the range [0, (p - 1) / 2] decode into non-negative integers, while the remaining
+//! elements decode into negative integers. Field arithmetic over encoded values matches integer
+//! arithmetic only as long as no intermediate value leaves the range [-(p - 1) / 2, (p - 1) / 2];
+//! [IntegerRange] can be used to track bounds of intermediate values and to check that they fit
+//! into a given field.
+//!
+//! A fixed-point value x with scale s is represented by the integer round(x * s), and this integer
+//! is then encoded as a signed integer.
+//!
+//! Field elements can also be encoded as 32-byte EVM words, i.e., as canonical integers written
+//! in big-endian byte order and padded with leading zeros; this is the layout in which EVM
+//! contracts receive `uint256` values in calldata. [ByteEncoding] selects between this encoding
+//! and the native encoding of field elements for data which is hashed or included in proofs.
+
+use super::{FieldElement, StarkField};
+use crate::{errors::SerializationError, utils::read_elements_into};
+use core::{cmp, convert::TryFrom};
+use std::borrow::Cow;
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+#[cfg(test)]
+mod tests;
+
+// SIGNED INTEGERS
+// ================================================================================================
+
+/// Encodes a signed integer as a field element; for extension fields, the integer is encoded as
+/// an element of the base field.
+///
+/// If the absolute value of `value` is not smaller than the field modulus, modular reduction is
+/// silently performed; such values cannot be decoded back.
+pub fn encode_signed<E: FieldElement>(value: i128) -> E {
+    let magnitude = E::from(value.unsigned_abs());
+    if value < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Decodes a signed integer from a field element; elements in the range [0, (p - 1) / 2] decode
+/// into non-negative integers, and all other elements decode into negative integers.
+///
+/// Returns None if the decoded integer does not fit into i128.
+pub fn decode_signed<B: StarkField>(element: B) -> Option<i128> {
+    if element.as_int() <= B::MODULUS >> 1u32 {
+        i128::try_from(get_magnitude(element)?).ok()
+    } else {
+        0i128.checked_sub_unsigned(get_magnitude(-element)?)
+    }
+}
+
+// INTEGER RANGE
+// ================================================================================================
+
+/// An inclusive range of signed integers.
+///
+/// Arithmetic methods of this type compute ranges of results of the corresponding integer
+/// operations, and [IntegerRange::fits_in()] checks whether field arithmetic over encoded values
+/// from this range matches integer arithmetic.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IntegerRange {
+    min: i128,
+    max: i128,
+}
+
+impl IntegerRange {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a range of integers from `min` to `max` inclusive.
+    ///
+    /// # Panics
+    /// Panics if `min` is greater than `max`.
+    pub fn new(min: i128, max: i128) -> Self {
+        assert!(
+            min <= max,
+            "range minimum {} cannot be greater than range maximum {}",
+            min,
+            max
+        );
+        IntegerRange { min, max }
+    }
+
+    /// Returns the range of signed integers which can be represented using `num_bits` bits in
+    /// two's complement form, i.e., [-2^(num_bits - 1), 2^(num_bits - 1) - 1].
+    ///
+    /// # Panics
+    /// Panics if `num_bits` is zero or greater than 128.
+    pub fn signed(num_bits: u32) -> Self {
+        assert!(
+            num_bits > 0 && num_bits <= 128,
+            "number of bits must be between 1 and 128, but was {}",
+            num_bits
+        );
+        let max = ((1u128 << (num_bits - 1)) - 1) as i128;
+        IntegerRange { min: -max - 1, max }
+    }
+
+    /// Returns the range of unsigned integers which can be represented using `num_bits` bits,
+    /// i.e., [0, 2^num_bits - 1].
+    ///
+    /// # Panics
+    /// Panics if `num_bits` is zero or greater than 127.
+    pub fn unsigned(num_bits: u32) -> Self {
+        assert!(
+            num_bits > 0 && num_bits <= 127,
+            "number of bits must be between 1 and 127, but was {}",
+            num_bits
+        );
+        IntegerRange {
+            min: 0,
+            max: ((1u128 << num_bits) - 1) as i128,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the smallest integer in this range.
+    pub fn min(&self) -> i128 {
+        self.min
+    }
+
+    /// Returns the largest integer in this range.
+    pub fn max(&self) -> i128 {
+        self.max
+    }
+
+    /// Returns true if `value` is in this range.
+    pub fn contains(&self, value: i128) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    /// Returns true if all integers in this range can be encoded as elements of field `B` and
+    /// decoded back, i.e., if absolute values of all integers in this range do not exceed
+    /// (p - 1) / 2.
+    ///
+    /// Field arithmetic over encoded values matches integer arithmetic as long as the ranges of
+    /// all intermediate results fit into the field.
+    pub fn fits_in<B: StarkField>(&self) -> bool {
+        let magnitude = cmp::max(self.min.unsigned_abs(), self.max.unsigned_abs());
+        fits_into_field::<B>(magnitude) && B::from(magnitude).as_int() <= B::MODULUS >> 1u32
+    }
+
+    // RANGE ARITHMETIC
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the range of sums of integers from this range and the `other` range, or None if
+    /// the bounds of the result do not fit into i128.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(IntegerRange {
+            min: self.min.checked_add(other.min)?,
+            max: self.max.checked_add(other.max)?,
+        })
+    }
+
+    /// Returns the range of differences of integers from this range and the `other` range, or
+    /// None if the bounds of the result do not fit into i128.
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        Some(IntegerRange {
+            min: self.min.checked_sub(other.max)?,
+            max: self.max.checked_sub(other.min)?,
+        })
+    }
+
+    /// Returns the range of products of integers from this range and the `other` range, or None
+    /// if the bounds of the result do not fit into i128.
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        let products = [
+            self.min.checked_mul(other.min)?,
+            self.min.checked_mul(other.max)?,
+            self.max.checked_mul(other.min)?,
+            self.max.checked_mul(other.max)?,
+        ];
+        Some(IntegerRange {
+            min: *products.iter().min().expect("products are not empty"),
+            max: *products.iter().max().expect("products are not empty"),
+        })
+    }
+}
+
+// FIXED-POINT VALUES
+// ================================================================================================
+
+/// A fixed-point format in which a value x is represented by the integer round(x * scale).
+///
+/// For example, prices with 6 decimal digits can use a scale of 10^6, while values with 16
+/// fractional bits use a scale of 2^16.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FixedPoint {
+    scale: u64,
+}
+
+impl FixedPoint {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a fixed-point format with the specified scale.
+    ///
+    /// # Panics
+    /// Panics if `scale` is zero.
+    pub fn new(scale: u64) -> Self {
+        assert!(scale > 0, "fixed-point scale must be greater than zero");
+        FixedPoint { scale }
+    }
+
+    /// Returns a fixed-point format with the specified number of fractional binary digits.
+    ///
+    /// # Panics
+    /// Panics if `num_bits` is greater than 63.
+    pub fn with_fractional_bits(num_bits: u32) -> Self {
+        assert!(
+            num_bits < 64,
+            "number of fractional bits cannot exceed 63, but was {}",
+            num_bits
+        );
+        FixedPoint {
+            scale: 1 << num_bits,
+        }
+    }
+
+    /// Returns a fixed-point format with the specified number of fractional decimal digits.
+    ///
+    /// # Panics
+    /// Panics if `num_digits` is greater than 19.
+    pub fn with_decimals(num_digits: u32) -> Self {
+        assert!(
+            num_digits <= 19,
+            "number of decimal digits cannot exceed 19, but was {}",
+            num_digits
+        );
+        FixedPoint {
+            scale: 10u64.pow(num_digits),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of units per whole number in this format.
+    pub fn scale(&self) -> u64 {
+        self.scale
+    }
+
+    // CONVERSIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the integer representing `value` in this format; the value is rounded to the
+    /// nearest representable value, and values outside of the i128 range are saturated.
+    pub fn to_raw(&self, value: f64) -> i128 {
+        (value * self.scale as f64).round() as i128
+    }
+
+    /// Returns the value represented by the integer `raw` in this format.
+    pub fn from_raw(&self, raw: i128) -> f64 {
+        raw as f64 / self.scale as f64
+    }
+
+    /// Encodes `value` as a field element.
+    pub fn encode<B: StarkField>(&self, value: f64) -> B {
+        encode_signed(self.to_raw(value))
+    }
+
+    /// Decodes a value from a field element, or returns None if the element does not decode into
+    /// an i128 integer.
+    pub fn decode<B: StarkField>(&self, element: B) -> Option<f64> {
+        decode_signed(element).map(|raw| self.from_raw(raw))
+    }
+
+    // ARITHMETIC
+    // --------------------------------------------------------------------------------------------
+
+    /// Multiplies integers `a` and `b` representing values in this format, and returns the
+    /// integer representing the product rounded towards negative infinity, together with the
+    /// remainder of the division of a * b by the scale.
+    ///
+    /// The result c and the remainder r are the unique integers such that a * b = c * scale + r
+    /// and 0 <= r < scale. Returns None if a * b does not fit into i128.
+    pub fn checked_mul(&self, a: i128, b: i128) -> Option<(i128, u64)> {
+        let product = a.checked_mul(b)?;
+        let scale = self.scale as i128;
+        Some((product.div_euclid(scale), product.rem_euclid(scale) as u64))
+    }
+
+    /// Returns the range of integers representing products of values from ranges `a` and `b`
+    /// in this format, or None if the bounds of the result do not fit into i128.
+    pub fn checked_mul_range(&self, a: &IntegerRange, b: &IntegerRange) -> Option<IntegerRange> {
+        let product = a.checked_mul(b)?;
+        let scale = self.scale as i128;
+        Some(IntegerRange::new(
+            product.min().div_euclid(scale),
+            product.max().div_euclid(scale),
+        ))
+    }
+}
+
+// EVM WORDS
+// ================================================================================================
+
+/// Number of bytes in an EVM word.
+pub const EVM_WORD_BYTES: usize = 32;
+
+/// Encodes a field element as a 32-byte EVM word, i.e., as the canonical integer value of the
+/// element in big-endian byte order, padded with leading zeros.
+///
+/// # Panics
+/// Panics if elements of field `B` do not fit into 32 bytes.
+pub fn to_evm_word<B: StarkField>(element: B) -> [u8; EVM_WORD_BYTES] {
+    let bytes = element.to_canonical_bytes();
+    assert!(
+        bytes.len() <= EVM_WORD_BYTES,
+        "field elements of {} bytes do not fit into an EVM word",
+        bytes.len()
+    );
+    let mut word = [0u8; EVM_WORD_BYTES];
+    for (target, &byte) in word.iter_mut().rev().zip(bytes.iter()) {
+        *target = byte;
+    }
+    word
+}
+
+/// Decodes a field element from a 32-byte EVM word; returns None if the integer encoded in the
+/// word is not smaller than the field modulus.
+pub fn from_evm_word<B: StarkField>(word: &[u8; EVM_WORD_BYTES]) -> Option<B> {
+    if B::ELEMENT_BYTES > EVM_WORD_BYTES {
+        return None;
+    }
+    let (padding, value) = word.split_at(EVM_WORD_BYTES - B::ELEMENT_BYTES);
+    if padding.iter().any(|&b| b != 0) {
+        return None;
+    }
+    let bytes = value.iter().rev().copied().collect::<Vec<_>>();
+    B::from_random_bytes(&bytes)
+}
+
+/// Encodes a list of field elements as a sequence of 32-byte EVM words; every coefficient of an
+/// element of an extension field is encoded as a separate word, starting with the first
+/// coefficient.
+///
+/// # Panics
+/// Panics if elements of the base field of `E` do not fit into 32 bytes.
+pub fn to_evm_words<E: FieldElement>(elements: &[E]) -> Vec<u8> {
+    let base_bytes = E::Base::ELEMENT_BYTES;
+    assert!(
+        base_bytes <= EVM_WORD_BYTES,
+        "field elements of {} bytes do not fit into an EVM word",
+        base_bytes
+    );
+    let num_words = elements.len() * (E::ELEMENT_BYTES / base_bytes);
+    let mut result = vec![0u8; num_words * EVM_WORD_BYTES];
+    let mut words = result.chunks_exact_mut(EVM_WORD_BYTES);
+    for &element in elements {
+        for (coefficient, word) in element
+            .to_canonical_bytes()
+            .chunks(base_bytes)
+            .zip(&mut words)
+        {
+            for (target, &byte) in word.iter_mut().rev().zip(coefficient.iter()) {
+                *target = byte;
+            }
+        }
+    }
+    result
+}
+
+/// Decodes a list of field elements from a sequence of 32-byte EVM words as encoded by
+/// [to_evm_words()]; returns None if the length of `bytes` does not divide into whole elements,
+/// or if any of the words does not encode a valid element of the base field.
+pub fn from_evm_words<E: FieldElement>(bytes: &[u8]) -> Option<Vec<E>> {
+    let element_bytes = get_evm_element_bytes::<E>();
+    let elements = bytes.chunks_exact(element_bytes);
+    if !elements.remainder().is_empty() {
+        return None;
+    }
+    elements.map(read_evm_element).collect()
+}
+
+// BYTE ENCODING
+// ================================================================================================
+
+/// Defines how field elements and integers are encoded into bytes which are hashed into
+/// commitments and seeds, or written into proofs.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ByteEncoding {
+    /// Field elements are encoded in their native in-memory representation, and integers are
+    /// encoded in little-endian byte order.
+    Native = 1,
+    /// Every coefficient of a field element is encoded as a 32-byte EVM word (see
+    /// [to_evm_words()]), and integers are encoded in big-endian byte order. Integers combined
+    /// with a 32-byte seed occupy the last 8 bytes of a 32-byte word following the seed; integers
+    /// written into proofs are written as full 32-byte words.
+    EvmWords = 2,
+}
+
+impl ByteEncoding {
+    /// Returns the number of bytes in an encoded element of field `E`.
+    pub fn element_bytes<E: FieldElement>(&self) -> usize {
+        match self {
+            Self::Native => E::ELEMENT_BYTES,
+            Self::EvmWords => get_evm_element_bytes::<E>(),
+        }
+    }
+
+    /// Encodes a list of field elements into bytes; in native encoding, the elements are
+    /// re-interpreted as bytes without copying.
+    pub fn encode_elements<'a, E: FieldElement>(&self, elements: &'a [E]) -> Cow<'a, [u8]> {
+        match self {
+            Self::Native => Cow::Borrowed(E::elements_as_bytes(elements)),
+            Self::EvmWords => Cow::Owned(to_evm_words(elements)),
+        }
+    }
+
+    /// Decodes a list of field elements from the `source` bytes into the `destination` slice,
+    /// and returns the number of decoded elements.
+    ///
+    /// # Errors
+    /// Returns an error if the `source` does not divide into whole elements, if the
+    /// `destination` is too small to hold all decoded elements, or if any of the encoded
+    /// elements is not a valid field element.
+    pub fn decode_elements_into<E: FieldElement>(
+        &self,
+        source: &[u8],
+        destination: &mut [E],
+    ) -> Result<usize, SerializationError> {
+        if let Self::Native = self {
+            return read_elements_into(source, destination);
+        }
+
+        let element_bytes = self.element_bytes::<E>();
+        if !source.len().is_multiple_of(element_bytes) {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                source.len(),
+            ));
+        }
+        let num_elements = source.len() / element_bytes;
+        if destination.len() < num_elements {
+            return Err(SerializationError::DestinationTooSmall(
+                num_elements,
+                destination.len(),
+            ));
+        }
+        for (i, bytes) in source.chunks_exact(element_bytes).enumerate() {
+            destination[i] = read_evm_element(bytes)
+                .ok_or(SerializationError::FailedToReadElement(i * element_bytes))?;
+        }
+        Ok(num_elements)
+    }
+
+    /// Decodes a list of field elements from the `source` bytes; returns an error for the same
+    /// reasons as [decode_elements_into()](ByteEncoding::decode_elements_into).
+    pub fn decode_elements<E: FieldElement>(
+        &self,
+        source: &[u8],
+    ) -> Result<Vec<E>, SerializationError> {
+        let element_bytes = self.element_bytes::<E>();
+        if !source.len().is_multiple_of(element_bytes) {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                source.len(),
+            ));
+        }
+        let mut result = vec![E::ZERO; source.len() / element_bytes];
+        self.decode_elements_into(source, &mut result)?;
+        Ok(result)
+    }
+
+    /// Encodes an integer which is combined with a 32-byte seed for hashing.
+    pub fn encode_u64(&self, value: u64) -> [u8; 8] {
+        match self {
+            Self::Native => value.to_le_bytes(),
+            Self::EvmWords => value.to_be_bytes(),
+        }
+    }
+
+    /// Decodes an integer from 8 bytes encoded via [encode_u64()](ByteEncoding::encode_u64).
+    pub fn decode_u64(&self, bytes: [u8; 8]) -> u64 {
+        match self {
+            Self::Native => u64::from_le_bytes(bytes),
+            Self::EvmWords => u64::from_be_bytes(bytes),
+        }
+    }
+
+    /// Writes an integer into a proof; in EVM word encoding the integer is written as a full
+    /// 32-byte word.
+    pub fn write_u64<W: ByteWriter>(&self, value: u64, target: &mut W) {
+        match self {
+            Self::Native => target.write_u64(value),
+            Self::EvmWords => {
+                target.write_u8_slice(&[0u8; EVM_WORD_BYTES - 8]);
+                target.write_u8_slice(&value.to_be_bytes());
+            }
+        }
+    }
+
+    /// Reads an integer written into a proof via [write_u64()](ByteEncoding::write_u64); in EVM
+    /// word encoding, an error is returned if the word encodes a value which does not fit into
+    /// 64 bits.
+    pub fn read_u64<R: ByteReader>(&self, source: &mut R) -> Result<u64, DeserializationError> {
+        match self {
+            Self::Native => source.read_u64(),
+            Self::EvmWords => {
+                let padding: [u8; EVM_WORD_BYTES - 8] = source.read_u8_array()?;
+                if padding.iter().any(|&b| b != 0) {
+                    return Err(DeserializationError::InvalidValue(
+                        "EVM word does not encode a 64-bit integer".to_string(),
+                    ));
+                }
+                Ok(u64::from_be_bytes(source.read_u8_array()?))
+            }
+        }
+    }
+}
+
+impl Serializable for ByteEncoding {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for ByteEncoding {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            1 => Ok(Self::Native),
+            2 => Ok(Self::EvmWords),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "{} is not a valid byte encoding",
+                value
+            ))),
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the canonical value of the element as u128, or None if the value does not fit into
+/// 128 bits.
+fn get_magnitude<B: StarkField>(element: B) -> Option<u128> {
+    let bytes = element.to_canonical_bytes();
+    let (low, high) = bytes.split_at(cmp::min(bytes.len(), 16));
+    if high.iter().any(|&b| b != 0) {
+        return None;
+    }
+    let mut value = [0u8; 16];
+    value[..low.len()].copy_from_slice(low);
+    Some(u128::from_le_bytes(value))
+}
+
+/// Returns the number of bytes in an element of field `E` encoded as a sequence of EVM words.
+fn get_evm_element_bytes<E: FieldElement>() -> usize {
+    E::ELEMENT_BYTES / E::Base::ELEMENT_BYTES * EVM_WORD_BYTES
+}
+
+/// Decodes a single element of field `E` from EVM words; the length of `bytes` is assumed to be
+/// the number of bytes in an encoded element.
+fn read_evm_element<E: FieldElement>(bytes: &[u8]) -> Option<E> {
+    let base_bytes = E::Base::ELEMENT_BYTES;
+    if base_bytes > EVM_WORD_BYTES {
+        return None;
+    }
+    let mut canonical_bytes = Vec::with_capacity(E::ELEMENT_BYTES);
+    for word in bytes.chunks_exact(EVM_WORD_BYTES) {
+        let (padding, value) = word.split_at(EVM_WORD_BYTES - base_bytes);
+        if padding.iter().any(|&b| b != 0) {
+            return None;
+        }
+        canonical_bytes.extend(value.iter().rev());
+    }
+    E::from_random_bytes(&canonical_bytes)
+}
+
+/// Returns true if `value` is smaller than the field modulus.
+fn fits_into_field<B: StarkField>(value: u128) -> bool {
+    // a value is smaller than the modulus if and only if converting it into a field element
+    // does not reduce it
+    get_magnitude(B::from(value)) == Some(value)
+}
@@ -0,0 +1,325 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    decode_signed, encode_signed, from_evm_word, from_evm_words, to_evm_word, to_evm_words,
+    ByteEncoding, FixedPoint, IntegerRange,
+};
+use crate::{
+    errors::SerializationError,
+    field::{bn254, f128, f31, f64, FieldElement, QuadExtension, StarkField},
+};
+use utils::{Deserializable, Serializable, SliceReader};
+
+// SIGNED INTEGERS
+// ================================================================================================
+
+#[test]
+fn encode_decode_signed() {
+    for &value in [0, 1, -1, 42, -42, i16::MAX as i128, i16::MIN as i128].iter() {
+        assert_eq!(
+            Some(value),
+            decode_signed(encode_signed::<f31::BaseElement>(value))
+        );
+        assert_eq!(
+            Some(value),
+            decode_signed(encode_signed::<f64::BaseElement>(value))
+        );
+        assert_eq!(
+            Some(value),
+            decode_signed(encode_signed::<bn254::BaseElement>(value))
+        );
+    }
+
+    for &value in [i32::MAX as i128, i32::MIN as i128].iter() {
+        assert_eq!(
+            Some(value),
+            decode_signed(encode_signed::<f64::BaseElement>(value))
+        );
+        assert_eq!(
+            Some(value),
+            decode_signed(encode_signed::<bn254::BaseElement>(value))
+        );
+    }
+
+    // negative values are encoded as p - |v|
+    assert_eq!(-f64::BaseElement::ONE, encode_signed(-1));
+    assert_eq!(
+        f64::BaseElement::ZERO - f64::BaseElement::from(5u8),
+        encode_signed(-5)
+    );
+
+    // the largest values which can be decoded are (p - 1) / 2 and -(p - 1) / 2
+    let half = (f64::BaseElement::MODULUS >> 1) as i128;
+    assert_eq!(
+        Some(half),
+        decode_signed(encode_signed::<f64::BaseElement>(half))
+    );
+    assert_eq!(
+        Some(-half),
+        decode_signed(encode_signed::<f64::BaseElement>(-half))
+    );
+    assert_eq!(
+        Some(-half),
+        decode_signed(encode_signed::<f64::BaseElement>(half + 1))
+    );
+
+    // extreme i128 values round-trip in fields with moduli larger than 2^128
+    for &value in [i128::MAX, i128::MIN].iter() {
+        assert_eq!(
+            Some(value),
+            decode_signed(encode_signed::<bn254::BaseElement>(value))
+        );
+    }
+
+    // values which do not fit into i128 cannot be decoded
+    let large = bn254::BaseElement::from(u128::MAX) * bn254::BaseElement::from(4u8);
+    assert_eq!(None, decode_signed(large));
+    assert_eq!(None, decode_signed(-large));
+    let large = encode_signed::<bn254::BaseElement>(i128::MAX) + bn254::BaseElement::ONE;
+    assert_eq!(None, decode_signed(large));
+    assert_eq!(Some(i128::MIN), decode_signed(-large));
+}
+
+// INTEGER RANGE
+// ================================================================================================
+
+#[test]
+fn integer_range_constructors() {
+    assert_eq!(IntegerRange::new(-128, 127), IntegerRange::signed(8));
+    assert_eq!(IntegerRange::new(-1, 0), IntegerRange::signed(1));
+    assert_eq!(
+        IntegerRange::new(i128::MIN, i128::MAX),
+        IntegerRange::signed(128)
+    );
+    assert_eq!(IntegerRange::new(0, 255), IntegerRange::unsigned(8));
+    assert_eq!(IntegerRange::new(0, i128::MAX), IntegerRange::unsigned(127));
+
+    let range = IntegerRange::signed(8);
+    assert!(range.contains(-128));
+    assert!(range.contains(127));
+    assert!(!range.contains(128));
+    assert!(!range.contains(-129));
+}
+
+#[test]
+#[should_panic(expected = "range minimum 2 cannot be greater than range maximum 1")]
+fn integer_range_invalid() {
+    IntegerRange::new(2, 1);
+}
+
+#[test]
+fn integer_range_arithmetic() {
+    let a = IntegerRange::new(-10, 5);
+    let b = IntegerRange::new(2, 3);
+    assert_eq!(Some(IntegerRange::new(-8, 8)), a.checked_add(&b));
+    assert_eq!(Some(IntegerRange::new(-13, 3)), a.checked_sub(&b));
+    assert_eq!(Some(IntegerRange::new(-30, 15)), a.checked_mul(&b));
+    assert_eq!(Some(IntegerRange::new(-50, 100)), a.checked_mul(&a));
+
+    let max = IntegerRange::signed(128);
+    assert_eq!(None, max.checked_add(&a));
+    assert_eq!(None, max.checked_sub(&a));
+    assert_eq!(None, max.checked_mul(&b));
+}
+
+#[test]
+fn integer_range_fits_in() {
+    // f31 modulus is 2^31 - 2^27 + 1, and thus (p - 1) / 2 = 2^30 - 2^26
+    let half = (f31::BaseElement::MODULUS >> 1) as i128;
+    assert!(IntegerRange::new(-half, half).fits_in::<f31::BaseElement>());
+    assert!(!IntegerRange::new(-half - 1, half).fits_in::<f31::BaseElement>());
+    assert!(!IntegerRange::new(-half, half + 1).fits_in::<f31::BaseElement>());
+    assert!(IntegerRange::signed(30).fits_in::<f31::BaseElement>());
+    assert!(!IntegerRange::signed(31).fits_in::<f31::BaseElement>());
+
+    // a product of two 32-bit values fits into a 64-bit field, but a product of three does not
+    let word = IntegerRange::signed(32);
+    let product = word.checked_mul(&word).unwrap();
+    assert!(product.fits_in::<f64::BaseElement>());
+    assert!(!product
+        .checked_mul(&word)
+        .unwrap()
+        .fits_in::<f64::BaseElement>());
+
+    // the full i128 range fits only into fields with moduli larger than 2^128
+    assert!(!IntegerRange::signed(128).fits_in::<f128::BaseElement>());
+    assert!(IntegerRange::signed(128).fits_in::<bn254::BaseElement>());
+}
+
+// FIXED-POINT VALUES
+// ================================================================================================
+
+#[test]
+fn fixed_point_encoding() {
+    let format = FixedPoint::with_decimals(6);
+    assert_eq!(1_000_000, format.scale());
+    assert_eq!(1_500_000, format.to_raw(1.5));
+    assert_eq!(-1_234_568, format.to_raw(-1.2345675));
+    assert_eq!(-1.5, format.from_raw(-1_500_000));
+
+    let element: f64::BaseElement = format.encode(-2.25);
+    assert_eq!(encode_signed::<f64::BaseElement>(-2_250_000), element);
+    assert_eq!(Some(-2.25), format.decode(element));
+
+    let format = FixedPoint::with_fractional_bits(16);
+    assert_eq!(65536, format.scale());
+    assert_eq!(
+        Some(0.5),
+        format.decode(format.encode::<f31::BaseElement>(0.5))
+    );
+}
+
+#[test]
+fn fixed_point_mul() {
+    let format = FixedPoint::with_decimals(2);
+
+    // 1.50 * 2.25 = 3.375, which is rounded down to 3.37
+    assert_eq!(Some((337, 50)), format.checked_mul(150, 225));
+    // -1.50 * 2.25 = -3.375, which is rounded down to -3.38
+    assert_eq!(Some((-338, 50)), format.checked_mul(-150, 225));
+    // products which do not fit into i128 are rejected
+    assert_eq!(None, format.checked_mul(i128::MAX, 2));
+
+    let a = IntegerRange::new(-150, 150);
+    let b = IntegerRange::new(0, 225);
+    assert_eq!(
+        Some(IntegerRange::new(-338, 337)),
+        format.checked_mul_range(&a, &b)
+    );
+}
+
+// EVM WORDS
+// ================================================================================================
+
+#[test]
+fn evm_word_layout() {
+    // values are right-aligned in big-endian byte order
+    let word = to_evm_word(f64::BaseElement::from(0x0102u32));
+    assert_eq!([0u8; 30], word[..30]);
+    assert_eq!([1, 2], word[30..]);
+
+    // modulus - 1 of the 128-bit field occupies the last 16 bytes of the word
+    let word = to_evm_word(-f128::BaseElement::ONE);
+    assert_eq!([0u8; 16], word[..16]);
+    assert_eq!((f128::BaseElement::MODULUS - 1).to_be_bytes(), word[16..]);
+}
+
+#[test]
+fn evm_word_round_trip() {
+    let values = f31::BaseElement::prng_vector([1; 32], 8);
+    assert_eq!(Some(values.clone()), from_evm_words(&to_evm_words(&values)));
+
+    let values = f128::BaseElement::prng_vector([2; 32], 8);
+    assert_eq!(Some(values.clone()), from_evm_words(&to_evm_words(&values)));
+
+    let values = bn254::BaseElement::prng_vector([3; 32], 8);
+    assert_eq!(Some(values.clone()), from_evm_words(&to_evm_words(&values)));
+}
+
+#[test]
+fn evm_word_invalid() {
+    // the modulus itself is not a valid element
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&f128::BaseElement::MODULUS.to_be_bytes());
+    assert_eq!(None, from_evm_word::<f128::BaseElement>(&word));
+
+    // non-zero padding is rejected
+    let mut word = to_evm_word(f31::BaseElement::ONE);
+    word[0] = 1;
+    assert_eq!(None, from_evm_word::<f31::BaseElement>(&word));
+
+    // partial words are rejected
+    assert_eq!(None, from_evm_words::<f31::BaseElement>(&[0u8; 33]));
+}
+
+#[test]
+fn evm_words_extension() {
+    type E = QuadExtension<f128::BaseElement>;
+
+    // every coefficient of an extension element occupies a separate word
+    let element = E::new(f128::BaseElement::from(1u32), f128::BaseElement::from(2u32));
+    let words = to_evm_words(&[element]);
+    assert_eq!(64, words.len());
+    assert_eq!(to_evm_word(f128::BaseElement::from(1u32)), words[..32]);
+    assert_eq!(to_evm_word(f128::BaseElement::from(2u32)), words[32..]);
+
+    let values = E::prng_vector([4; 32], 8);
+    assert_eq!(Some(values.clone()), from_evm_words(&to_evm_words(&values)));
+    assert_eq!(None, from_evm_words::<E>(&to_evm_words(&values)[..32]));
+}
+
+// BYTE ENCODING
+// ================================================================================================
+
+#[test]
+fn byte_encoding_elements() {
+    type E = QuadExtension<f128::BaseElement>;
+    let values = E::prng_vector([5; 32], 4);
+
+    let native = ByteEncoding::Native;
+    assert_eq!(32, native.element_bytes::<E>());
+    let bytes = native.encode_elements(&values);
+    assert_eq!(E::elements_as_bytes(&values), bytes.as_ref());
+    assert_eq!(values, native.decode_elements::<E>(&bytes).unwrap());
+
+    let evm = ByteEncoding::EvmWords;
+    assert_eq!(64, evm.element_bytes::<E>());
+    let bytes = evm.encode_elements(&values);
+    assert_eq!(to_evm_words(&values), bytes.as_ref());
+    assert_eq!(values, evm.decode_elements::<E>(&bytes).unwrap());
+
+    // invalid words are reported at the offset of the element which contains them
+    let mut bytes = bytes.into_owned();
+    bytes[64 + 32] = 1;
+    assert_eq!(
+        Err(SerializationError::FailedToReadElement(64)),
+        evm.decode_elements::<E>(&bytes)
+    );
+    assert_eq!(
+        Err(SerializationError::NotEnoughBytesForWholeElements(96)),
+        evm.decode_elements::<E>(&bytes[..96])
+    );
+    let mut destination = [E::ZERO; 1];
+    assert_eq!(
+        Err(SerializationError::DestinationTooSmall(2, 1)),
+        evm.decode_elements_into(&bytes[..128], &mut destination)
+    );
+}
+
+#[test]
+fn byte_encoding_integers() {
+    let value = 0x0102_0304_0506_0708u64;
+    assert_eq!(value.to_le_bytes(), ByteEncoding::Native.encode_u64(value));
+    assert_eq!(
+        value.to_be_bytes(),
+        ByteEncoding::EvmWords.encode_u64(value)
+    );
+    for encoding in [ByteEncoding::Native, ByteEncoding::EvmWords].iter() {
+        assert_eq!(value, encoding.decode_u64(encoding.encode_u64(value)));
+    }
+
+    // integers written in EVM word encoding occupy a full big-endian word
+    let mut bytes = Vec::new();
+    ByteEncoding::EvmWords.write_u64(value, &mut bytes);
+    assert_eq!([0u8; 24], bytes[..24]);
+    assert_eq!(value.to_be_bytes(), bytes[24..]);
+    let mut reader = SliceReader::new(&bytes);
+    assert_eq!(value, ByteEncoding::EvmWords.read_u64(&mut reader).unwrap());
+
+    bytes[0] = 1;
+    let mut reader = SliceReader::new(&bytes);
+    assert!(ByteEncoding::EvmWords.read_u64(&mut reader).is_err());
+
+    // encodings are serialized as a single byte
+    let mut bytes = Vec::new();
+    ByteEncoding::EvmWords.write_into(&mut bytes);
+    assert_eq!(vec![2], bytes);
+    assert_eq!(
+        ByteEncoding::EvmWords,
+        ByteEncoding::read_from_bytes(&bytes).unwrap()
+    );
+    assert!(ByteEncoding::read_from_bytes(&[3]).is_err());
+}
@@ -0,0 +1,565 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ExtensionOf, FieldElement, SerializationError, StarkField};
+use core::{
+    convert::TryFrom,
+    fmt::{Debug, Display, Formatter},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    slice,
+};
+use rand::Rng;
+use utils::{AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// CUBIC EXTENSION FIELD
+// ================================================================================================
+
+/// Represents an element in a cubic extension of the specified base field. The extension element
+/// is α + β * φ + γ * φ^2, where φ is a root of the polynomial x^3 - x - k, and α, β, and γ are
+/// base field elements. In other words, the extension field is F[X]/(X^3-X-k). The constant k is
+/// defined by the base field via [StarkField::CUBE_EXTENSION_TERM].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct CubeExtension<B: StarkField>(B, B, B);
+
+impl<B: StarkField> CubeExtension<B> {
+    /// Converts a vector of base elements into a vector of elements in a cubic extension field
+    /// by fusing three adjacent base elements together. The output vector is a third of the
+    /// length of the source vector.
+    fn base_to_cube_vector(source: Vec<B>) -> Vec<Self> {
+        debug_assert!(
+            source.len().is_multiple_of(3),
+            "source vector length must be divisible by three, but was {}",
+            source.len()
+        );
+        // capacity of the vector must be divisible by three as well; converting the vector into
+        // a boxed slice makes its capacity equal to its length
+        let source = source.into_boxed_slice().into_vec();
+        let mut v = std::mem::ManuallyDrop::new(source);
+        let p = v.as_mut_ptr();
+        let len = v.len() / 3;
+        let cap = v.capacity() / 3;
+        unsafe { Vec::from_raw_parts(p as *mut Self, len, cap) }
+    }
+}
+
+impl<B: StarkField> FieldElement for CubeExtension<B> {
+    type PositiveInteger = B::PositiveInteger;
+    type Base = B;
+
+    const ELEMENT_BYTES: usize = B::ELEMENT_BYTES * 3;
+    const ZERO: Self = Self(B::ZERO, B::ZERO, B::ZERO);
+    const ONE: Self = Self(B::ONE, B::ZERO, B::ZERO);
+
+    fn inv(self) -> Self {
+        if self == Self::ZERO {
+            return Self::ZERO;
+        }
+
+        // the inverse is the first column of the inverse of the matrix which describes
+        // multiplication by this element; it is computed from the cofactors of the first row
+        // of the matrix divided by the determinant of the matrix
+        let (a0, a1, a2) = (self.0, self.1, self.2);
+        let a02 = a0 + a2;
+        let c0 = a02 * a02 - (mul_by_term(a2) + a1) * a1;
+        let c1 = (mul_by_term(a2) + a1) * a2 - a1 * a02;
+        let c2 = a1 * a1 - a02 * a2;
+        let det = a0 * c0 + mul_by_term(a2 * c1 + a1 * c2);
+        let det_inv = det.inv();
+        Self(c0 * det_inv, c1 * det_inv, c2 * det_inv)
+    }
+
+    /// Returns the image of this element under the Frobenius automorphism (i.e., this element
+    /// raised to the power of the base field modulus). Unlike conjugation in quadratic
+    /// extensions, this requires a full exponentiation.
+    fn conjugate(&self) -> Self {
+        self.exp(B::MODULUS)
+    }
+
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self(
+            B::rand_with_rng(rng),
+            B::rand_with_rng(rng),
+            B::rand_with_rng(rng),
+        )
+    }
+
+    fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::try_from(&bytes[..Self::ELEMENT_BYTES]).ok()
+    }
+
+    fn to_canonical_bytes(self) -> Vec<u8> {
+        let mut result = self.0.to_canonical_bytes();
+        result.append(&mut self.1.to_canonical_bytes());
+        result.append(&mut self.2.to_canonical_bytes());
+        result
+    }
+
+    fn elements_into_bytes(elements: Vec<Self>) -> Vec<u8> {
+        let mut v = std::mem::ManuallyDrop::new(elements);
+        let p = v.as_mut_ptr();
+        let len = v.len() * Self::ELEMENT_BYTES;
+        let cap = v.capacity() * Self::ELEMENT_BYTES;
+        unsafe { Vec::from_raw_parts(p as *mut u8, len, cap) }
+    }
+
+    fn elements_as_bytes(elements: &[Self]) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(
+                elements.as_ptr() as *const u8,
+                elements.len() * Self::ELEMENT_BYTES,
+            )
+        }
+    }
+
+    unsafe fn bytes_as_elements(bytes: &[u8]) -> Result<&[Self], SerializationError> {
+        if !bytes.len().is_multiple_of(Self::ELEMENT_BYTES) {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                bytes.len(),
+            ));
+        }
+
+        let p = bytes.as_ptr();
+        let len = bytes.len() / Self::ELEMENT_BYTES;
+
+        // make sure the bytes are aligned on the boundary consistent with base element alignment
+        if !(p as usize).is_multiple_of(Self::Base::ELEMENT_BYTES) {
+            return Err(SerializationError::InvalidMemoryAlignment);
+        }
+
+        Ok(slice::from_raw_parts(p as *const Self, len))
+    }
+
+    fn zeroed_vector(n: usize) -> Vec<Self> {
+        // get three times the number of base elements, and re-interpret them as cube field
+        // elements
+        let result = B::zeroed_vector(n * 3);
+        Self::base_to_cube_vector(result)
+    }
+
+    fn prng_vector(seed: [u8; 32], n: usize) -> Vec<Self> {
+        // get three times the number of base elements, and re-interpret them as cube field
+        // elements
+        let result = B::prng_vector(seed, n * 3);
+        Self::base_to_cube_vector(result)
+    }
+}
+
+impl<B: StarkField> ExtensionOf<B> for CubeExtension<B> {
+    const DEGREE: usize = 3;
+}
+
+impl<B: StarkField> Display for CubeExtension<B> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "({}, {}, {})", self.0, self.1, self.2)
+    }
+}
+
+// OVERLOADED OPERATORS
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> Add for CubeExtension<B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl<B: StarkField> AddAssign for CubeExtension<B> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl<B: StarkField> Sub for CubeExtension<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+impl<B: StarkField> SubAssign for CubeExtension<B> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<B: StarkField> Mul for CubeExtension<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // multiply as polynomials in φ, and then reduce terms with φ^3 and φ^4 using
+        // φ^3 = φ + k and φ^4 = φ^2 + k * φ
+        let c0 = self.0 * rhs.0;
+        let c1 = self.0 * rhs.1 + self.1 * rhs.0;
+        let c2 = self.0 * rhs.2 + self.1 * rhs.1 + self.2 * rhs.0;
+        let c3 = self.1 * rhs.2 + self.2 * rhs.1;
+        let c4 = self.2 * rhs.2;
+        Self(c0 + mul_by_term(c3), c1 + c3 + mul_by_term(c4), c2 + c4)
+    }
+}
+
+impl<B: StarkField> MulAssign for CubeExtension<B> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl<B: StarkField> Div for CubeExtension<B> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl<B: StarkField> DivAssign for CubeExtension<B> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
+impl<B: StarkField> Neg for CubeExtension<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(B::ZERO - self.0, B::ZERO - self.1, B::ZERO - self.2)
+    }
+}
+
+// TYPE CONVERSIONS
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> From<B> for CubeExtension<B> {
+    fn from(e: B) -> Self {
+        Self(e, B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u128> for CubeExtension<B> {
+    fn from(value: u128) -> Self {
+        CubeExtension(B::from(value), B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u64> for CubeExtension<B> {
+    fn from(value: u64) -> Self {
+        CubeExtension(B::from(value), B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u32> for CubeExtension<B> {
+    fn from(value: u32) -> Self {
+        CubeExtension(B::from(value), B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u16> for CubeExtension<B> {
+    fn from(value: u16) -> Self {
+        CubeExtension(B::from(value), B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u8> for CubeExtension<B> {
+    fn from(value: u8) -> Self {
+        CubeExtension(B::from(value), B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> TryFrom<&[u8]> for CubeExtension<B> {
+    type Error = String;
+
+    /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
+    /// is not a valid field element. The bytes are assumed to be in little-endian byte order.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() < Self::ELEMENT_BYTES {
+            return Err(
+                "need more bytes in order to convert into extension field element".to_string(),
+            );
+        }
+        let mut values = bytes[..Self::ELEMENT_BYTES]
+            .chunks(B::ELEMENT_BYTES)
+            .map(|chunk| B::try_from(chunk).map_err(|_| "could not convert into field element"));
+        let value0 = values.next().unwrap()?;
+        let value1 = values.next().unwrap()?;
+        let value2 = values.next().unwrap()?;
+        Ok(Self(value0, value1, value2))
+    }
+}
+
+// SERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> AsBytes for CubeExtension<B> {
+    fn as_bytes(&self) -> &[u8] {
+        let self_ptr: *const Self = self;
+        unsafe { slice::from_raw_parts(self_ptr as *const u8, Self::ELEMENT_BYTES) }
+    }
+}
+
+impl<B: StarkField> Serializable for CubeExtension<B> {
+    /// Writes all three base field elements of the extension element one after another.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.0.write_into(target);
+        self.1.write_into(target);
+        self.2.write_into(target);
+    }
+}
+
+impl<B: StarkField> Deserializable for CubeExtension<B> {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let value0 = B::read_from(source)?;
+        let value1 = B::read_from(source)?;
+        let value2 = B::read_from(source)?;
+        Ok(Self(value0, value1, value2))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Multiplies `value` by the constant term of the polynomial defining the extension; the
+/// multiplication is skipped when the term is one.
+#[inline(always)]
+fn mul_by_term<B: StarkField>(value: B) -> B {
+    if B::CUBE_EXTENSION_TERM == B::ONE {
+        value
+    } else {
+        value * B::CUBE_EXTENSION_TERM
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AsBytes, CubeExtension, Deserializable, DeserializationError, FieldElement, Serializable,
+        StarkField,
+    };
+    use crate::field::{bn254, f128, f31, f62, f64::BaseElement, m31};
+
+    // BASIC ALGEBRA
+    // --------------------------------------------------------------------------------------------
+
+    #[test]
+    fn add() {
+        // identity
+        let r = CubeExtension::<BaseElement>::rand();
+        assert_eq!(r, r + CubeExtension::<BaseElement>::ZERO);
+
+        // test random values
+        let r1 = CubeExtension::<BaseElement>::rand();
+        let r2 = CubeExtension::<BaseElement>::rand();
+
+        let expected = CubeExtension(r1.0 + r2.0, r1.1 + r2.1, r1.2 + r2.2);
+        assert_eq!(expected, r1 + r2);
+    }
+
+    #[test]
+    fn sub() {
+        // identity
+        let r = CubeExtension::<BaseElement>::rand();
+        assert_eq!(r, r - CubeExtension::<BaseElement>::ZERO);
+
+        // test random values
+        let r1 = CubeExtension::<BaseElement>::rand();
+        let r2 = CubeExtension::<BaseElement>::rand();
+
+        let expected = CubeExtension(r1.0 - r2.0, r1.1 - r2.1, r1.2 - r2.2);
+        assert_eq!(expected, r1 - r2);
+    }
+
+    #[test]
+    fn mul() {
+        // identity
+        let r = CubeExtension::<BaseElement>::rand();
+        assert_eq!(
+            CubeExtension::<BaseElement>::ZERO,
+            r * CubeExtension::<BaseElement>::ZERO
+        );
+        assert_eq!(r, r * CubeExtension::<BaseElement>::ONE);
+
+        // φ is a root of x^3 - x - 1
+        let phi = CubeExtension(BaseElement::ZERO, BaseElement::ONE, BaseElement::ZERO);
+        let expected = CubeExtension(BaseElement::ONE, BaseElement::ONE, BaseElement::ZERO);
+        assert_eq!(expected, phi * phi * phi);
+
+        // multiplication is associative and distributive
+        let r1 = CubeExtension::<BaseElement>::rand();
+        let r2 = CubeExtension::<BaseElement>::rand();
+        let r3 = CubeExtension::<BaseElement>::rand();
+        assert_eq!((r1 * r2) * r3, r1 * (r2 * r3));
+        assert_eq!(r1 * (r2 + r3), r1 * r2 + r1 * r3);
+    }
+
+    #[test]
+    fn inv() {
+        // identity
+        assert_eq!(
+            CubeExtension::<BaseElement>::ONE,
+            CubeExtension::<BaseElement>::inv(CubeExtension::<BaseElement>::ONE)
+        );
+        assert_eq!(
+            CubeExtension::<BaseElement>::ZERO,
+            CubeExtension::<BaseElement>::inv(CubeExtension::<BaseElement>::ZERO)
+        );
+
+        // test random values
+        let x = CubeExtension::<BaseElement>::prng_vector(build_seed(), 1000);
+        for &value in x.iter() {
+            assert_eq!(CubeExtension::<BaseElement>::ONE, value * value.inv());
+        }
+    }
+
+    #[test]
+    fn conjugate() {
+        // the Frobenius automorphism fixes the base field and has order three
+        let a = CubeExtension::<BaseElement>::from(BaseElement::rand());
+        assert_eq!(a, a.conjugate());
+
+        let a = CubeExtension::<BaseElement>::rand();
+        let b = a.conjugate();
+        assert_ne!(a, b);
+        assert_eq!(a, b.conjugate().conjugate());
+
+        // it also respects field operations, and products of all conjugates are in the base field
+        let c = CubeExtension::<BaseElement>::rand();
+        assert_eq!(b * c.conjugate(), (a * c).conjugate());
+        let norm = a * b * b.conjugate();
+        assert_eq!(CubeExtension::from(norm.0), norm);
+    }
+
+    #[test]
+    fn non_unit_extension_term() {
+        // extensions of the 31-bit, 62-bit and BN254 fields are defined by x^3 - x - 2,
+        // extensions of the 128-bit field by x^3 - x - 7, and extensions of the Mersenne-31 field
+        // by x^3 - x - 3
+        check_extension_term::<f31::BaseElement>(2);
+        check_extension_term::<f62::BaseElement>(2);
+        check_extension_term::<bn254::BaseElement>(2);
+        check_extension_term::<m31::BaseElement>(3);
+        check_extension_term::<f128::BaseElement>(7);
+    }
+
+    // INITIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    #[test]
+    fn zeroed_vector() {
+        let result = CubeExtension::<BaseElement>::zeroed_vector(4);
+        assert_eq!(4, result.len());
+        for element in result.into_iter() {
+            assert_eq!(CubeExtension::<BaseElement>::ZERO, element);
+        }
+    }
+
+    #[test]
+    fn prng_vector() {
+        let a = CubeExtension::<BaseElement>::prng_vector([0; 32], 4);
+        assert_eq!(4, a.len());
+
+        let b = CubeExtension::<BaseElement>::prng_vector([0; 32], 8);
+        assert_eq!(8, b.len());
+
+        for (&a, &b) in a.iter().zip(b.iter()) {
+            assert_eq!(a, b);
+        }
+
+        let c = CubeExtension::<BaseElement>::prng_vector([1; 32], 4);
+        for (&a, &c) in a.iter().zip(c.iter()) {
+            assert_ne!(a, c);
+        }
+    }
+
+    // SERIALIZATION / DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    #[test]
+    fn serialize_deserialize() {
+        // extension elements are serialized as three consecutive base field elements
+        let element = CubeExtension(
+            BaseElement::new(1),
+            BaseElement::new(2),
+            BaseElement::new(3),
+        );
+        let mut expected = 1u64.to_le_bytes().to_vec();
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        assert_eq!(expected, element.to_bytes());
+        assert_eq!(Ok(element), CubeExtension::read_from_bytes(&expected));
+        assert_eq!(expected, element.as_bytes());
+        assert_eq!(
+            Some(element),
+            CubeExtension::<BaseElement>::from_random_bytes(&expected)
+        );
+
+        assert_eq!(
+            Err(DeserializationError::UnexpectedEOF),
+            CubeExtension::<BaseElement>::read_from_bytes(&expected[..23])
+        );
+    }
+
+    #[test]
+    fn elements_as_bytes() {
+        let source = vec![
+            CubeExtension(
+                BaseElement::new(1),
+                BaseElement::new(2),
+                BaseElement::new(3),
+            ),
+            CubeExtension(
+                BaseElement::new(4),
+                BaseElement::new(5),
+                BaseElement::new(6),
+            ),
+        ];
+
+        let mut expected = Vec::new();
+        for i in 1..7u64 {
+            expected.extend_from_slice(&i.to_le_bytes());
+        }
+
+        assert_eq!(
+            expected,
+            CubeExtension::<BaseElement>::elements_as_bytes(&source)
+        );
+        let elements = unsafe { CubeExtension::<BaseElement>::bytes_as_elements(&expected) };
+        assert_eq!(source, elements.unwrap());
+        assert_eq!(
+            expected,
+            CubeExtension::<BaseElement>::elements_into_bytes(source)
+        );
+    }
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn build_seed() -> [u8; 32] {
+        let mut result = [0; 32];
+        let seed = CubeExtension::<f128::BaseElement>::rand()
+            .as_bytes()
+            .to_vec();
+        result.copy_from_slice(&seed[..32]);
+        result
+    }
+
+    fn check_extension_term<B: StarkField>(term: u32) {
+        assert_eq!(B::from(term), B::CUBE_EXTENSION_TERM);
+        let phi = CubeExtension(B::ZERO, B::ONE, B::ZERO);
+        let expected = CubeExtension(B::from(term), B::ONE, B::ZERO);
+        assert_eq!(expected, phi * phi * phi);
+
+        let x = CubeExtension::<B>::prng_vector([1; 32], 100);
+        for &value in x.iter() {
+            assert_eq!(CubeExtension::<B>::ONE, value * value.inv());
+            assert_eq!(value, value.conjugate().conjugate().conjugate());
+        }
+    }
+}
@@ -6,5 +6,11 @@
 mod quadratic;
 pub use quadratic::QuadExtension;
 
-use super::{FieldElement, StarkField};
+mod cubic;
+pub use cubic::CubeExtension;
+
+mod quartic;
+pub use quartic::QuartExtension;
+
+use super::{ExtensionOf, FieldElement, StarkField};
 use crate::errors::SerializationError;
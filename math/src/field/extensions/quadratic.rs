@@ -3,26 +3,33 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{FieldElement, SerializationError, StarkField};
+use super::{ExtensionOf, FieldElement, SerializationError, StarkField};
 use core::{
     convert::TryFrom,
     fmt::{Debug, Display, Formatter},
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
     slice,
 };
-use utils::AsBytes;
+use rand::Rng;
+use utils::{AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 // QUADRATIC EXTENSION FIELD
 // ================================================================================================
 
 /// Represents an element in a quadratic extensions of the specified base field. The extension
-/// element is α + β * φ, where φ is a root of the polynomial x^2 - x - 1, and α and β are base
-/// field elements. In other words, the extension field is F[X]/(X^2-X-1).
+/// element is α + β * φ, where φ is a root of the polynomial x^2 - x - k, and α and β are base
+/// field elements. In other words, the extension field is F[X]/(X^2-X-k). The constant k is
+/// defined by the base field via [StarkField::QUAD_EXTENSION_TERM]; for most fields it is 1.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub struct QuadExtension<B: StarkField>(B, B);
 
 impl<B: StarkField> QuadExtension<B> {
+    /// Returns the extension element a + b * φ.
+    pub(crate) const fn new(a: B, b: B) -> Self {
+        Self(a, b)
+    }
+
     /// Converts a vector of base elements into a vector of elements in a quadratic extension
     /// field by fusing two adjacent base elements together. The output vector is half the length
     /// of the source vector.
@@ -53,7 +60,7 @@ impl<B: StarkField> FieldElement for QuadExtension<B> {
             return Self::ZERO;
         }
         #[allow(clippy::suspicious_operation_groupings)]
-        let denom = (self.0 * self.0) + (self.0 * self.1) - (self.1 * self.1);
+        let denom = (self.0 * self.0) + (self.0 * self.1) - mul_by_term(self.1 * self.1);
         let denom_inv = denom.inv();
         Self((self.0 + self.1) * denom_inv, self.1.neg() * denom_inv)
     }
@@ -62,8 +69,8 @@ impl<B: StarkField> FieldElement for QuadExtension<B> {
         Self(self.0 + self.1, B::ZERO - self.1)
     }
 
-    fn rand() -> Self {
-        Self(B::rand(), B::rand())
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self(B::rand_with_rng(rng), B::rand_with_rng(rng))
     }
 
     fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
@@ -124,6 +131,10 @@ impl<B: StarkField> FieldElement for QuadExtension<B> {
     }
 }
 
+impl<B: StarkField> ExtensionOf<B> for QuadExtension<B> {
+    const DEGREE: usize = 2;
+}
+
 impl<B: StarkField> Display for QuadExtension<B> {
     fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "({}, {})", self.0, self.1)
@@ -167,7 +178,7 @@ impl<B: StarkField> Mul for QuadExtension<B> {
     fn mul(self, rhs: Self) -> Self {
         let coef0_mul = self.0 * rhs.0;
         Self(
-            coef0_mul + self.1 * rhs.1,
+            coef0_mul + mul_by_term(self.1 * rhs.1),
             (self.0 + self.1) * (rhs.0 + rhs.1) - coef0_mul,
         )
     }
@@ -279,13 +290,46 @@ impl<B: StarkField> AsBytes for QuadExtension<B> {
     }
 }
 
+impl<B: StarkField> Serializable for QuadExtension<B> {
+    /// Writes both base field elements of the extension element one after another.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.0.write_into(target);
+        self.1.write_into(target);
+    }
+}
+
+impl<B: StarkField> Deserializable for QuadExtension<B> {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let value0 = B::read_from(source)?;
+        let value1 = B::read_from(source)?;
+        Ok(Self(value0, value1))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Multiplies `value` by the constant term of the polynomial defining the extension; the
+/// multiplication is skipped when the term is one.
+#[inline(always)]
+fn mul_by_term<B: StarkField>(value: B) -> B {
+    if B::QUAD_EXTENSION_TERM == B::ONE {
+        value
+    } else {
+        value * B::QUAD_EXTENSION_TERM
+    }
+}
+
 // TESTS
 // ================================================================================================
 
 #[cfg(test)]
 mod tests {
-    use super::{AsBytes, FieldElement, QuadExtension, SerializationError};
-    use crate::field::f128::BaseElement;
+    use super::{
+        AsBytes, Deserializable, DeserializationError, FieldElement, QuadExtension, Serializable,
+        SerializationError,
+    };
+    use crate::field::{f128::BaseElement, f31, f64};
 
     // BASIC ALGEBRA
     // --------------------------------------------------------------------------------------------
@@ -367,6 +411,44 @@ mod tests {
         assert_eq!(expected, b);
     }
 
+    #[test]
+    fn non_unit_extension_term() {
+        // the extension of the 64-bit field is defined by x^2 - x - 3
+        type E64 = QuadExtension<f64::BaseElement>;
+        let phi = QuadExtension(f64::BaseElement::ZERO, f64::BaseElement::ONE);
+        let expected = QuadExtension(f64::BaseElement::new(3), f64::BaseElement::ONE);
+        assert_eq!(expected, phi * phi);
+
+        // test random values
+        let r1 = E64::rand();
+        let r2 = E64::rand();
+        let expected = QuadExtension(
+            r1.0 * r2.0 + r1.1 * r2.1 * f64::BaseElement::new(3),
+            (r1.0 + r1.1) * (r2.0 + r2.1) - r1.0 * r2.0,
+        );
+        assert_eq!(expected, r1 * r2);
+
+        // products of elements and their conjugates are in the base field
+        let norm = r1 * r1.conjugate();
+        assert_eq!(f64::BaseElement::ZERO, norm.1);
+
+        let x = E64::prng_vector(build_seed(), 1000);
+        for &value in x.iter() {
+            assert_eq!(E64::ONE, value * value.inv());
+        }
+
+        // the extension of the 31-bit field is defined by x^2 - x - 8
+        type E31 = QuadExtension<f31::BaseElement>;
+        let phi = QuadExtension(f31::BaseElement::ZERO, f31::BaseElement::ONE);
+        let expected = QuadExtension(f31::BaseElement::new(8), f31::BaseElement::ONE);
+        assert_eq!(expected, phi * phi);
+
+        let x = E31::prng_vector(build_seed(), 1000);
+        for &value in x.iter() {
+            assert_eq!(E31::ONE, value * value.inv());
+        }
+    }
+
     // INITIALIZATION
     // --------------------------------------------------------------------------------------------
 
@@ -400,6 +482,21 @@ mod tests {
     // SERIALIZATION / DESERIALIZATION
     // --------------------------------------------------------------------------------------------
 
+    #[test]
+    fn serialize_deserialize() {
+        // extension elements are serialized as two consecutive base field elements
+        let element = QuadExtension(BaseElement::new(1), BaseElement::new(2));
+        let mut expected = 1u128.to_le_bytes().to_vec();
+        expected.extend_from_slice(&2u128.to_le_bytes());
+        assert_eq!(expected, element.to_bytes());
+        assert_eq!(Ok(element), QuadExtension::read_from_bytes(&expected));
+
+        assert_eq!(
+            Err(DeserializationError::UnexpectedEOF),
+            QuadExtension::<BaseElement>::read_from_bytes(&expected[..31])
+        );
+    }
+
     #[test]
     fn elements_into_bytes() {
         let source = vec![
@@ -0,0 +1,593 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ExtensionOf, FieldElement, SerializationError, StarkField};
+use core::{
+    convert::TryFrom,
+    fmt::{Debug, Display, Formatter},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    slice,
+};
+use rand::Rng;
+use utils::{AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// QUARTIC EXTENSION FIELD
+// ================================================================================================
+
+/// Represents an element in a quartic extension of the specified base field. The extension
+/// element is α + β * φ + γ * φ^2 + δ * φ^3, where φ is a root of the polynomial x^4 - k, and α,
+/// β, γ, and δ are base field elements. In other words, the extension field is F[X]/(X^4-k). The
+/// constant k is defined by the base field via [StarkField::QUART_EXTENSION_TERM].
+///
+/// Quartic extensions are primarily intended for small base fields (e.g., the 31-bit field),
+/// for which even a cubic extension does not provide enough bits of security.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct QuartExtension<B: StarkField>(B, B, B, B);
+
+impl<B: StarkField> QuartExtension<B> {
+    /// Converts a vector of base elements into a vector of elements in a quartic extension field
+    /// by fusing four adjacent base elements together. The output vector is a quarter of the
+    /// length of the source vector.
+    fn base_to_quart_vector(source: Vec<B>) -> Vec<Self> {
+        debug_assert!(
+            source.len().is_multiple_of(4),
+            "source vector length must be divisible by four, but was {}",
+            source.len()
+        );
+        // capacity of the vector must be divisible by four as well; converting the vector into
+        // a boxed slice makes its capacity equal to its length
+        let source = source.into_boxed_slice().into_vec();
+        let mut v = std::mem::ManuallyDrop::new(source);
+        let p = v.as_mut_ptr();
+        let len = v.len() / 4;
+        let cap = v.capacity() / 4;
+        unsafe { Vec::from_raw_parts(p as *mut Self, len, cap) }
+    }
+}
+
+impl<B: StarkField> FieldElement for QuartExtension<B> {
+    type PositiveInteger = B::PositiveInteger;
+    type Base = B;
+
+    const ELEMENT_BYTES: usize = B::ELEMENT_BYTES * 4;
+    const ZERO: Self = Self(B::ZERO, B::ZERO, B::ZERO, B::ZERO);
+    const ONE: Self = Self(B::ONE, B::ZERO, B::ZERO, B::ZERO);
+
+    fn inv(self) -> Self {
+        if self == Self::ZERO {
+            return Self::ZERO;
+        }
+
+        // the extension is viewed as a quadratic extension over F[u]/(u^2 - k), where u = φ^2;
+        // writing the element as a(u) + b(u) * φ, multiplying it by a(u) - b(u) * φ yields
+        // n(u) = a(u)^2 - u * b(u)^2 which lies in the intermediate field; n(u) is inverted
+        // there by multiplying it by its own conjugate
+        let (a0, a1, a2, a3) = (self.0, self.1, self.2, self.3);
+        let n0 = a0.square() + mul_by_term(a2.square() - (a1 * a3).double());
+        let n1 = (a0 * a2).double() - a1.square() - mul_by_term(a3.square());
+        let det_inv = (n0.square() - mul_by_term(n1.square())).inv();
+        let m0 = n0 * det_inv;
+        let m1 = -n1 * det_inv;
+
+        // the inverse is (a(u) - b(u) * φ) * n(u)^{-1}
+        Self(
+            a0 * m0 + mul_by_term(a2 * m1),
+            -(a1 * m0 + mul_by_term(a3 * m1)),
+            a0 * m1 + a2 * m0,
+            -(a1 * m1 + a3 * m0),
+        )
+    }
+
+    /// Returns the image of this element under the Frobenius automorphism (i.e., this element
+    /// raised to the power of the base field modulus). Unlike conjugation in quadratic
+    /// extensions, this requires a full exponentiation.
+    fn conjugate(&self) -> Self {
+        self.exp(B::MODULUS)
+    }
+
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self(
+            B::rand_with_rng(rng),
+            B::rand_with_rng(rng),
+            B::rand_with_rng(rng),
+            B::rand_with_rng(rng),
+        )
+    }
+
+    fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::try_from(&bytes[..Self::ELEMENT_BYTES]).ok()
+    }
+
+    fn to_canonical_bytes(self) -> Vec<u8> {
+        let mut result = self.0.to_canonical_bytes();
+        result.append(&mut self.1.to_canonical_bytes());
+        result.append(&mut self.2.to_canonical_bytes());
+        result.append(&mut self.3.to_canonical_bytes());
+        result
+    }
+
+    fn elements_into_bytes(elements: Vec<Self>) -> Vec<u8> {
+        let mut v = std::mem::ManuallyDrop::new(elements);
+        let p = v.as_mut_ptr();
+        let len = v.len() * Self::ELEMENT_BYTES;
+        let cap = v.capacity() * Self::ELEMENT_BYTES;
+        unsafe { Vec::from_raw_parts(p as *mut u8, len, cap) }
+    }
+
+    fn elements_as_bytes(elements: &[Self]) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(
+                elements.as_ptr() as *const u8,
+                elements.len() * Self::ELEMENT_BYTES,
+            )
+        }
+    }
+
+    unsafe fn bytes_as_elements(bytes: &[u8]) -> Result<&[Self], SerializationError> {
+        if !bytes.len().is_multiple_of(Self::ELEMENT_BYTES) {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                bytes.len(),
+            ));
+        }
+
+        let p = bytes.as_ptr();
+        let len = bytes.len() / Self::ELEMENT_BYTES;
+
+        // make sure the bytes are aligned on the boundary consistent with base element alignment
+        if !(p as usize).is_multiple_of(Self::Base::ELEMENT_BYTES) {
+            return Err(SerializationError::InvalidMemoryAlignment);
+        }
+
+        Ok(slice::from_raw_parts(p as *const Self, len))
+    }
+
+    fn zeroed_vector(n: usize) -> Vec<Self> {
+        // get four times the number of base elements, and re-interpret them as quartic field
+        // elements
+        let result = B::zeroed_vector(n * 4);
+        Self::base_to_quart_vector(result)
+    }
+
+    fn prng_vector(seed: [u8; 32], n: usize) -> Vec<Self> {
+        // get four times the number of base elements, and re-interpret them as quartic field
+        // elements
+        let result = B::prng_vector(seed, n * 4);
+        Self::base_to_quart_vector(result)
+    }
+}
+
+impl<B: StarkField> ExtensionOf<B> for QuartExtension<B> {
+    const DEGREE: usize = 4;
+}
+
+impl<B: StarkField> Display for QuartExtension<B> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "({}, {}, {}, {})", self.0, self.1, self.2, self.3)
+    }
+}
+
+// OVERLOADED OPERATORS
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> Add for QuartExtension<B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(
+            self.0 + rhs.0,
+            self.1 + rhs.1,
+            self.2 + rhs.2,
+            self.3 + rhs.3,
+        )
+    }
+}
+
+impl<B: StarkField> AddAssign for QuartExtension<B> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl<B: StarkField> Sub for QuartExtension<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(
+            self.0 - rhs.0,
+            self.1 - rhs.1,
+            self.2 - rhs.2,
+            self.3 - rhs.3,
+        )
+    }
+}
+
+impl<B: StarkField> SubAssign for QuartExtension<B> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<B: StarkField> Mul for QuartExtension<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // multiply as polynomials in φ, and then reduce terms with φ^4, φ^5, and φ^6 using
+        // φ^4 = k
+        let (a0, a1, a2, a3) = (self.0, self.1, self.2, self.3);
+        let (b0, b1, b2, b3) = (rhs.0, rhs.1, rhs.2, rhs.3);
+        Self(
+            a0 * b0 + mul_by_term(a1 * b3 + a2 * b2 + a3 * b1),
+            a0 * b1 + a1 * b0 + mul_by_term(a2 * b3 + a3 * b2),
+            a0 * b2 + a1 * b1 + a2 * b0 + mul_by_term(a3 * b3),
+            a0 * b3 + a1 * b2 + a2 * b1 + a3 * b0,
+        )
+    }
+}
+
+impl<B: StarkField> MulAssign for QuartExtension<B> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl<B: StarkField> Div for QuartExtension<B> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl<B: StarkField> DivAssign for QuartExtension<B> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
+impl<B: StarkField> Neg for QuartExtension<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(
+            B::ZERO - self.0,
+            B::ZERO - self.1,
+            B::ZERO - self.2,
+            B::ZERO - self.3,
+        )
+    }
+}
+
+// TYPE CONVERSIONS
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> From<B> for QuartExtension<B> {
+    fn from(e: B) -> Self {
+        Self(e, B::ZERO, B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u128> for QuartExtension<B> {
+    fn from(value: u128) -> Self {
+        QuartExtension(B::from(value), B::ZERO, B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u64> for QuartExtension<B> {
+    fn from(value: u64) -> Self {
+        QuartExtension(B::from(value), B::ZERO, B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u32> for QuartExtension<B> {
+    fn from(value: u32) -> Self {
+        QuartExtension(B::from(value), B::ZERO, B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u16> for QuartExtension<B> {
+    fn from(value: u16) -> Self {
+        QuartExtension(B::from(value), B::ZERO, B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> From<u8> for QuartExtension<B> {
+    fn from(value: u8) -> Self {
+        QuartExtension(B::from(value), B::ZERO, B::ZERO, B::ZERO)
+    }
+}
+
+impl<B: StarkField> TryFrom<&[u8]> for QuartExtension<B> {
+    type Error = String;
+
+    /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
+    /// is not a valid field element. The bytes are assumed to be in little-endian byte order.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() < Self::ELEMENT_BYTES {
+            return Err(
+                "need more bytes in order to convert into extension field element".to_string(),
+            );
+        }
+        let mut values = bytes[..Self::ELEMENT_BYTES]
+            .chunks(B::ELEMENT_BYTES)
+            .map(|chunk| B::try_from(chunk).map_err(|_| "could not convert into field element"));
+        let value0 = values.next().unwrap()?;
+        let value1 = values.next().unwrap()?;
+        let value2 = values.next().unwrap()?;
+        let value3 = values.next().unwrap()?;
+        Ok(Self(value0, value1, value2, value3))
+    }
+}
+
+// SERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> AsBytes for QuartExtension<B> {
+    fn as_bytes(&self) -> &[u8] {
+        let self_ptr: *const Self = self;
+        unsafe { slice::from_raw_parts(self_ptr as *const u8, Self::ELEMENT_BYTES) }
+    }
+}
+
+impl<B: StarkField> Serializable for QuartExtension<B> {
+    /// Writes all four base field elements of the extension element one after another.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.0.write_into(target);
+        self.1.write_into(target);
+        self.2.write_into(target);
+        self.3.write_into(target);
+    }
+}
+
+impl<B: StarkField> Deserializable for QuartExtension<B> {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let value0 = B::read_from(source)?;
+        let value1 = B::read_from(source)?;
+        let value2 = B::read_from(source)?;
+        let value3 = B::read_from(source)?;
+        Ok(Self(value0, value1, value2, value3))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Multiplies `value` by the constant term of the polynomial defining the extension.
+#[inline(always)]
+fn mul_by_term<B: StarkField>(value: B) -> B {
+    value * B::QUART_EXTENSION_TERM
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AsBytes, Deserializable, DeserializationError, FieldElement, QuartExtension, Serializable,
+        StarkField,
+    };
+    use crate::field::{f128, f31::BaseElement, f62, f64};
+
+    // BASIC ALGEBRA
+    // --------------------------------------------------------------------------------------------
+
+    #[test]
+    fn add() {
+        // identity
+        let r = QuartExtension::<BaseElement>::rand();
+        assert_eq!(r, r + QuartExtension::<BaseElement>::ZERO);
+
+        // test random values
+        let r1 = QuartExtension::<BaseElement>::rand();
+        let r2 = QuartExtension::<BaseElement>::rand();
+
+        let expected = QuartExtension(r1.0 + r2.0, r1.1 + r2.1, r1.2 + r2.2, r1.3 + r2.3);
+        assert_eq!(expected, r1 + r2);
+    }
+
+    #[test]
+    fn sub() {
+        // identity
+        let r = QuartExtension::<BaseElement>::rand();
+        assert_eq!(r, r - QuartExtension::<BaseElement>::ZERO);
+
+        // test random values
+        let r1 = QuartExtension::<BaseElement>::rand();
+        let r2 = QuartExtension::<BaseElement>::rand();
+
+        let expected = QuartExtension(r1.0 - r2.0, r1.1 - r2.1, r1.2 - r2.2, r1.3 - r2.3);
+        assert_eq!(expected, r1 - r2);
+    }
+
+    #[test]
+    fn mul() {
+        // identity
+        let r = QuartExtension::<BaseElement>::rand();
+        assert_eq!(
+            QuartExtension::<BaseElement>::ZERO,
+            r * QuartExtension::<BaseElement>::ZERO
+        );
+        assert_eq!(r, r * QuartExtension::<BaseElement>::ONE);
+
+        // multiplication is associative and distributive
+        let r1 = QuartExtension::<BaseElement>::rand();
+        let r2 = QuartExtension::<BaseElement>::rand();
+        let r3 = QuartExtension::<BaseElement>::rand();
+        assert_eq!((r1 * r2) * r3, r1 * (r2 * r3));
+        assert_eq!(r1 * (r2 + r3), r1 * r2 + r1 * r3);
+    }
+
+    #[test]
+    fn inv() {
+        // identity
+        assert_eq!(
+            QuartExtension::<BaseElement>::ONE,
+            QuartExtension::<BaseElement>::inv(QuartExtension::<BaseElement>::ONE)
+        );
+        assert_eq!(
+            QuartExtension::<BaseElement>::ZERO,
+            QuartExtension::<BaseElement>::inv(QuartExtension::<BaseElement>::ZERO)
+        );
+
+        // test random values
+        let x = QuartExtension::<BaseElement>::prng_vector(build_seed(), 1000);
+        for &value in x.iter() {
+            assert_eq!(QuartExtension::<BaseElement>::ONE, value * value.inv());
+        }
+    }
+
+    #[test]
+    fn conjugate() {
+        // the Frobenius automorphism fixes the base field and has order four
+        let a = QuartExtension::<BaseElement>::from(BaseElement::rand());
+        assert_eq!(a, a.conjugate());
+
+        let a = QuartExtension::<BaseElement>::rand();
+        let b = a.conjugate();
+        assert_ne!(a, b);
+        assert_ne!(a, b.conjugate());
+        assert_eq!(a, b.conjugate().conjugate().conjugate());
+
+        // it also respects field operations, and products of all conjugates are in the base field
+        let c = QuartExtension::<BaseElement>::rand();
+        assert_eq!(b * c.conjugate(), (a * c).conjugate());
+        let b2 = b.conjugate();
+        let norm = a * b * b2 * b2.conjugate();
+        assert_eq!(QuartExtension::from(norm.0), norm);
+    }
+
+    #[test]
+    fn extension_term() {
+        // φ^4 = k, and x^4 - k is irreducible for all available base fields
+        check_extension_term::<BaseElement>();
+        check_extension_term::<f62::BaseElement>();
+        check_extension_term::<f64::BaseElement>();
+        check_extension_term::<f128::BaseElement>();
+    }
+
+    // INITIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    #[test]
+    fn zeroed_vector() {
+        let result = QuartExtension::<BaseElement>::zeroed_vector(4);
+        assert_eq!(4, result.len());
+        for element in result.into_iter() {
+            assert_eq!(QuartExtension::<BaseElement>::ZERO, element);
+        }
+    }
+
+    #[test]
+    fn prng_vector() {
+        let a = QuartExtension::<BaseElement>::prng_vector([0; 32], 4);
+        assert_eq!(4, a.len());
+
+        let b = QuartExtension::<BaseElement>::prng_vector([0; 32], 8);
+        assert_eq!(8, b.len());
+
+        for (&a, &b) in a.iter().zip(b.iter()) {
+            assert_eq!(a, b);
+        }
+
+        let c = QuartExtension::<BaseElement>::prng_vector([1; 32], 4);
+        for (&a, &c) in a.iter().zip(c.iter()) {
+            assert_ne!(a, c);
+        }
+    }
+
+    // SERIALIZATION / DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    #[test]
+    fn serialize_deserialize() {
+        // extension elements are serialized as four consecutive base field elements
+        let element = QuartExtension(
+            BaseElement::new(1),
+            BaseElement::new(2),
+            BaseElement::new(3),
+            BaseElement::new(4),
+        );
+        let mut expected = Vec::new();
+        for i in 1..5u32 {
+            expected.extend_from_slice(&i.to_le_bytes());
+        }
+        assert_eq!(expected, element.to_bytes());
+        assert_eq!(Ok(element), QuartExtension::read_from_bytes(&expected));
+        assert_eq!(expected, element.as_bytes());
+        assert_eq!(
+            Some(element),
+            QuartExtension::<BaseElement>::from_random_bytes(&expected)
+        );
+
+        assert_eq!(
+            Err(DeserializationError::UnexpectedEOF),
+            QuartExtension::<BaseElement>::read_from_bytes(&expected[..15])
+        );
+    }
+
+    #[test]
+    fn elements_as_bytes() {
+        let source = vec![
+            QuartExtension(
+                BaseElement::new(1),
+                BaseElement::new(2),
+                BaseElement::new(3),
+                BaseElement::new(4),
+            ),
+            QuartExtension(
+                BaseElement::new(5),
+                BaseElement::new(6),
+                BaseElement::new(7),
+                BaseElement::new(8),
+            ),
+        ];
+
+        let mut expected = Vec::new();
+        for i in 1..9u32 {
+            expected.extend_from_slice(&i.to_le_bytes());
+        }
+
+        assert_eq!(
+            expected,
+            QuartExtension::<BaseElement>::elements_as_bytes(&source)
+        );
+        let elements = unsafe { QuartExtension::<BaseElement>::bytes_as_elements(&expected) };
+        assert_eq!(source, elements.unwrap());
+        assert_eq!(
+            expected,
+            QuartExtension::<BaseElement>::elements_into_bytes(source)
+        );
+    }
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn build_seed() -> [u8; 32] {
+        let mut result = [0; 32];
+        let seed = QuartExtension::<f64::BaseElement>::rand()
+            .as_bytes()
+            .to_vec();
+        result.copy_from_slice(&seed[..32]);
+        result
+    }
+
+    fn check_extension_term<B: StarkField>() {
+        // the term must be a quadratic non-residue for x^4 - k to be irreducible
+        let k = B::QUART_EXTENSION_TERM;
+        assert_ne!(B::ONE, k.exp(B::MODULUS >> 1));
+
+        let phi = QuartExtension(B::ZERO, B::ONE, B::ZERO, B::ZERO);
+        let expected = QuartExtension(k, B::ZERO, B::ZERO, B::ZERO);
+        assert_eq!(expected, phi * phi * phi * phi);
+
+        let x = QuartExtension::<B>::prng_vector([1; 32], 100);
+        for &value in x.iter() {
+            assert_eq!(QuartExtension::<B>::ONE, value * value.inv());
+            assert_eq!(value, value.conjugate().conjugate().conjugate().conjugate());
+        }
+    }
+}
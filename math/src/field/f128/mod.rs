@@ -13,8 +13,7 @@ use core::{
     slice,
 };
 use rand::{distributions::Uniform, prelude::*};
-use serde::{Deserialize, Serialize};
-use utils::AsBytes;
+use utils::{AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 #[cfg(test)]
 mod tests;
@@ -36,8 +35,12 @@ const ELEMENT_BYTES: usize = std::mem::size_of::<u128>();
 // FIELD ELEMENT
 // ================================================================================================
 
-// TODO: get rid of Serialize and Deserialize derives
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// Base field element; internal values are stored in canonical representation and are always
+/// in the range [0, M).
+///
+/// Montgomery form is not used: benchmarks against a prototype (`benches/f128_montgomery.rs`)
+/// show no FFT speedup, and elements are hashed in canonical form.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub struct BaseElement(u128);
 
 impl BaseElement {
@@ -66,10 +69,8 @@ impl FieldElement for BaseElement {
         BaseElement(self.0)
     }
 
-    fn rand() -> Self {
-        let range = Uniform::from(RANGE);
-        let mut g = thread_rng();
-        BaseElement(g.sample(range))
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        BaseElement(rng.sample(Uniform::from(RANGE)))
     }
 
     fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
@@ -157,6 +158,13 @@ impl StarkField for BaseElement {
     /// 23953097886125630542083529559205016746
     const TWO_ADIC_ROOT_OF_UNITY: Self = BaseElement(G);
 
+    /// sage: R.<x> = GF(MODULUS)[]
+    /// sage: (x^3 - x - 1).is_irreducible()
+    /// False
+    /// sage: (x^3 - x - 7).is_irreducible()
+    /// True
+    const CUBE_EXTENSION_TERM: Self = BaseElement(7);
+
     fn get_modulus_le_bytes() -> Vec<u8> {
         Self::MODULUS.to_le_bytes().to_vec()
     }
@@ -317,6 +325,28 @@ impl AsBytes for BaseElement {
     }
 }
 
+impl Serializable for BaseElement {
+    /// Writes the element in little-endian byte order.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(&self.0.to_le_bytes());
+    }
+}
+
+impl Deserializable for BaseElement {
+    /// Reads an element encoded in little-endian byte order; returns an error if the encoded
+    /// value is not a valid field element.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let value = u128::from_le_bytes(source.read_u8_array()?);
+        if value >= M {
+            return Err(DeserializationError::InvalidValue(format!(
+                "value {} is greater than or equal to the field modulus",
+                value
+            )));
+        }
+        Ok(BaseElement(value))
+    }
+}
+
 // FINITE FIELD ARITHMETIC
 // ================================================================================================
 
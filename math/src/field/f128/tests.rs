@@ -4,6 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::*;
+use crate::errors::RootOfUnityError;
 use num_bigint::BigUint;
 
 // BASIC ALGEBRA
@@ -121,19 +122,32 @@ fn conjugate() {
 
 #[test]
 fn get_root_of_unity() {
-    let root_40 = BaseElement::get_root_of_unity(40);
+    let root_40 = BaseElement::get_root_of_unity(40).unwrap();
     assert_eq!(
         BaseElement::from(23953097886125630542083529559205016746u128),
         root_40
     );
     assert_eq!(BaseElement::ONE, root_40.exp(u128::pow(2, 40)));
 
-    let root_39 = BaseElement::get_root_of_unity(39);
+    let root_39 = BaseElement::get_root_of_unity(39).unwrap();
     let expected = root_40.exp(2);
     assert_eq!(expected, root_39);
     assert_eq!(BaseElement::ONE, root_39.exp(u128::pow(2, 39)));
 }
 
+#[test]
+fn get_root_of_unity_invalid_order() {
+    assert_eq!(40, BaseElement::max_two_adicity());
+    assert_eq!(
+        Err(RootOfUnityError::ZeroOrder),
+        BaseElement::get_root_of_unity(0)
+    );
+    assert_eq!(
+        Err(RootOfUnityError::OrderTooLarge(41, 40)),
+        BaseElement::get_root_of_unity(41)
+    );
+}
+
 #[test]
 fn test_g_is_2_exp_40_root() {
     let g = BaseElement::TWO_ADIC_ROOT_OF_UNITY;
@@ -208,6 +222,24 @@ fn bytes_as_elements() {
     assert_eq!(result, Err(SerializationError::InvalidMemoryAlignment));
 }
 
+#[test]
+fn serialize_deserialize() {
+    let element = BaseElement::new(12345);
+    let bytes = element.to_bytes();
+    assert_eq!(12345u128.to_le_bytes().to_vec(), bytes);
+    assert_eq!(Ok(element), BaseElement::read_from_bytes(&bytes));
+
+    let bytes = M.to_le_bytes();
+    assert!(matches!(
+        BaseElement::read_from_bytes(&bytes),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        BaseElement::read_from_bytes(&bytes[..15])
+    );
+}
+
 // INITIALIZATION
 // ================================================================================================
 
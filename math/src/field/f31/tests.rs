@@ -0,0 +1,382 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    AsBytes, BaseElement, Deserializable, DeserializationError, ElementDecodingError, FieldElement,
+    Serializable, SerializationError, StarkField,
+};
+use num_bigint::BigUint;
+use proptest::prelude::*;
+use std::convert::TryFrom;
+
+// MANUAL TESTS
+// ================================================================================================
+
+#[test]
+fn add() {
+    // identity
+    let r = BaseElement::rand();
+    assert_eq!(r, r + BaseElement::ZERO);
+
+    // test addition within bounds
+    assert_eq!(
+        BaseElement::from(5u8),
+        BaseElement::from(2u8) + BaseElement::from(3u8)
+    );
+
+    // test overflow
+    let t = BaseElement::from(BaseElement::MODULUS - 1);
+    assert_eq!(BaseElement::ZERO, t + BaseElement::ONE);
+    assert_eq!(BaseElement::ONE, t + BaseElement::from(2u8));
+    assert_eq!(BaseElement::from(BaseElement::MODULUS - 2), t + t);
+}
+
+#[test]
+fn sub() {
+    // identity
+    let r = BaseElement::rand();
+    assert_eq!(r, r - BaseElement::ZERO);
+
+    // test subtraction within bounds
+    assert_eq!(
+        BaseElement::from(2u8),
+        BaseElement::from(5u8) - BaseElement::from(3u8)
+    );
+
+    // test underflow
+    let expected = BaseElement::from(BaseElement::MODULUS - 2);
+    assert_eq!(expected, BaseElement::from(3u8) - BaseElement::from(5u8));
+    let t = BaseElement::from(BaseElement::MODULUS - 1);
+    assert_eq!(BaseElement::ONE, BaseElement::ZERO - t);
+}
+
+#[test]
+fn mul() {
+    // identity
+    let r = BaseElement::rand();
+    assert_eq!(BaseElement::ZERO, r * BaseElement::ZERO);
+    assert_eq!(r, r * BaseElement::ONE);
+
+    // test multiplication within bounds
+    assert_eq!(
+        BaseElement::from(15u8),
+        BaseElement::from(5u8) * BaseElement::from(3u8)
+    );
+
+    // test overflow
+    let m = BaseElement::MODULUS;
+    let t = BaseElement::from(m - 1);
+    assert_eq!(BaseElement::ONE, t * t);
+    assert_eq!(BaseElement::from(m - 2), t * BaseElement::from(2u8));
+    assert_eq!(BaseElement::from(m - 4), t * BaseElement::from(4u8));
+
+    let t = m.div_ceil(2);
+    assert_eq!(
+        BaseElement::ONE,
+        BaseElement::from(t) * BaseElement::from(2u8)
+    );
+}
+
+#[test]
+fn exp() {
+    let a = BaseElement::ZERO;
+    assert_eq!(a.exp(0), BaseElement::ONE);
+    assert_eq!(a.exp(1), BaseElement::ZERO);
+
+    let a = BaseElement::ONE;
+    assert_eq!(a.exp(0), BaseElement::ONE);
+    assert_eq!(a.exp(1), BaseElement::ONE);
+    assert_eq!(a.exp(3), BaseElement::ONE);
+
+    let a = BaseElement::rand();
+    assert_eq!(a.exp(3), a * a * a);
+}
+
+#[test]
+fn inv() {
+    // identity
+    assert_eq!(BaseElement::ONE, BaseElement::inv(BaseElement::ONE));
+    assert_eq!(BaseElement::ZERO, BaseElement::inv(BaseElement::ZERO));
+}
+
+#[test]
+fn element_as_int() {
+    let v = u32::MAX;
+    let e = BaseElement::new(v);
+    assert_eq!((v % super::M) as u64, e.as_int());
+}
+
+#[test]
+fn equals() {
+    let a = BaseElement::ONE;
+    let b = BaseElement::new(super::M - 1) * BaseElement::new(super::M - 1);
+
+    // elements are equal, and since elements are stored in canonical representation, so are
+    // their internal representations
+    assert_eq!(a, b);
+    assert_eq!(a.as_int(), b.as_int());
+    assert_eq!(a.to_canonical_bytes(), b.to_canonical_bytes());
+    assert_eq!(a.as_bytes(), b.as_bytes());
+}
+
+#[test]
+fn mont_reduce() {
+    // values close to the bounds of the input range exercise both borrow and no-borrow paths
+    let m = super::M as u64;
+    let r_inv = BigUint::from(1u64 << 32).modpow(&BigUint::from(m - 2), &BigUint::from(m));
+    for &v in [
+        0,
+        1,
+        m,
+        m - 1,
+        (m - 1) * (m - 1),
+        (m << 32) - 1,
+        u32::MAX as u64,
+    ]
+    .iter()
+    {
+        let expected = (BigUint::from(v) * &r_inv) % BigUint::from(m);
+        let expected = expected.to_u64_digits().first().copied().unwrap_or(0);
+        assert_eq!(expected, super::mont_reduce(v) as u64);
+    }
+}
+
+// ROOTS OF UNITY
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn get_root_of_unity() {
+    let root_27 = BaseElement::get_root_of_unity(27).unwrap();
+    assert_eq!(BaseElement::TWO_ADIC_ROOT_OF_UNITY, root_27);
+    assert_eq!(BaseElement::ONE, root_27.exp(1u64 << 27));
+    assert_ne!(BaseElement::ONE, root_27.exp(1u64 << 26));
+
+    let root_26 = BaseElement::get_root_of_unity(26).unwrap();
+    let expected = root_27.exp(2);
+    assert_eq!(expected, root_26);
+    assert_eq!(BaseElement::ONE, root_26.exp(1u64 << 26));
+}
+
+// SERIALIZATION AND DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn from_u128() {
+    let v = u128::MAX;
+    let e = BaseElement::from(v);
+    assert_eq!((v % super::M as u128) as u64, e.as_int());
+}
+
+#[test]
+fn try_from_slice() {
+    let bytes = vec![1, 0, 0, 0];
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert!(result.is_ok());
+    assert_eq!(1, result.unwrap().as_int());
+
+    let bytes = vec![1, 0, 0];
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert_eq!(Err(ElementDecodingError::NotEnoughBytes(4, 3)), result);
+
+    let bytes = vec![1, 0, 0, 0, 0];
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert_eq!(Err(ElementDecodingError::TooManyBytes(4, 5)), result);
+
+    let bytes = vec![255, 255, 255, 255];
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert_eq!(
+        Err(ElementDecodingError::ValueTooLarger(
+            "4294967295".to_string()
+        )),
+        result
+    );
+}
+
+#[test]
+fn elements_into_bytes() {
+    let source = vec![
+        BaseElement::new(1),
+        BaseElement::new(2),
+        BaseElement::new(3),
+        BaseElement::new(4),
+    ];
+
+    let mut expected = vec![];
+    expected.extend_from_slice(&source[0].0.to_le_bytes());
+    expected.extend_from_slice(&source[1].0.to_le_bytes());
+    expected.extend_from_slice(&source[2].0.to_le_bytes());
+    expected.extend_from_slice(&source[3].0.to_le_bytes());
+
+    assert_eq!(expected, BaseElement::elements_into_bytes(source));
+}
+
+#[test]
+fn elements_as_bytes() {
+    let source = vec![
+        BaseElement::new(1),
+        BaseElement::new(2),
+        BaseElement::new(3),
+        BaseElement::new(4),
+    ];
+
+    let mut expected = vec![];
+    expected.extend_from_slice(&source[0].0.to_le_bytes());
+    expected.extend_from_slice(&source[1].0.to_le_bytes());
+    expected.extend_from_slice(&source[2].0.to_le_bytes());
+    expected.extend_from_slice(&source[3].0.to_le_bytes());
+
+    assert_eq!(expected, BaseElement::elements_as_bytes(&source));
+}
+
+#[test]
+fn bytes_as_elements() {
+    let elements = vec![
+        BaseElement::new(1),
+        BaseElement::new(2),
+        BaseElement::new(3),
+        BaseElement::new(4),
+    ];
+
+    let mut bytes = vec![];
+    bytes.extend_from_slice(&elements[0].0.to_le_bytes());
+    bytes.extend_from_slice(&elements[1].0.to_le_bytes());
+    bytes.extend_from_slice(&elements[2].0.to_le_bytes());
+    bytes.extend_from_slice(&elements[3].0.to_le_bytes());
+    bytes.extend_from_slice(&BaseElement::new(5).0.to_le_bytes());
+
+    let result = unsafe { BaseElement::bytes_as_elements(&bytes[..16]) };
+    assert!(result.is_ok());
+    assert_eq!(elements, result.unwrap());
+
+    let result = unsafe { BaseElement::bytes_as_elements(&bytes[..17]) };
+    assert_eq!(
+        result,
+        Err(SerializationError::NotEnoughBytesForWholeElements(17))
+    );
+
+    let result = unsafe { BaseElement::bytes_as_elements(&bytes[1..17]) };
+    assert_eq!(result, Err(SerializationError::InvalidMemoryAlignment));
+}
+
+#[test]
+fn serialize_deserialize() {
+    let element = BaseElement::new(12345);
+    let bytes = element.to_bytes();
+    assert_eq!(12345u32.to_le_bytes().to_vec(), bytes);
+    assert_eq!(Ok(element), BaseElement::read_from_bytes(&bytes));
+
+    let bytes = super::M.to_le_bytes();
+    assert!(matches!(
+        BaseElement::read_from_bytes(&bytes),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        BaseElement::read_from_bytes(&bytes[..3])
+    );
+}
+
+// INITIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn zeroed_vector() {
+    let result = BaseElement::zeroed_vector(4);
+    assert_eq!(4, result.len());
+    for element in result.into_iter() {
+        assert_eq!(BaseElement::ZERO, element);
+    }
+}
+
+#[test]
+fn prng_vector() {
+    let a = BaseElement::prng_vector([0; 32], 4);
+    assert_eq!(4, a.len());
+
+    let b = BaseElement::prng_vector([0; 32], 8);
+    assert_eq!(8, b.len());
+
+    for (&a, &b) in a.iter().zip(b.iter()) {
+        assert_eq!(a, b);
+    }
+
+    let c = BaseElement::prng_vector([1; 32], 4);
+    for (&a, &c) in a.iter().zip(c.iter()) {
+        assert_ne!(a, c);
+    }
+}
+
+// RANDOMIZED TESTS
+// ================================================================================================
+
+proptest! {
+
+    #[test]
+    fn add_proptest(a in any::<u64>(), b in any::<u64>()) {
+        let v1 = BaseElement::from(a);
+        let v2 = BaseElement::from(b);
+        let result = v1 + v2;
+
+        let m = super::M as u64;
+        let expected = (a % m + b % m) % m;
+        prop_assert_eq!(expected, result.as_int());
+    }
+
+    #[test]
+    fn sub_proptest(a in any::<u64>(), b in any::<u64>()) {
+        let v1 = BaseElement::from(a);
+        let v2 = BaseElement::from(b);
+        let result = v1 - v2;
+
+        let m = super::M as u64;
+        let a = a % m;
+        let b = b % m;
+        let expected = if a < b { m - b + a } else { a - b };
+
+        prop_assert_eq!(expected, result.as_int());
+    }
+
+    #[test]
+    fn mul_proptest(a in any::<u64>(), b in any::<u64>()) {
+        let v1 = BaseElement::from(a);
+        let v2 = BaseElement::from(b);
+        let result = v1 * v2;
+
+        let expected = (((a as u128) * (b as u128)) % super::M as u128) as u64;
+        prop_assert_eq!(expected, result.as_int());
+    }
+
+    #[test]
+    fn exp_proptest(a in any::<u64>(), b in any::<u64>()) {
+        let result = BaseElement::from(a).exp(b);
+
+        let b = BigUint::from(b);
+        let m = BigUint::from(super::M);
+        let expected = BigUint::from(a).modpow(&b, &m).to_u64_digits();
+        let expected = expected.first().copied().unwrap_or(0);
+        prop_assert_eq!(expected, result.as_int());
+    }
+
+    #[test]
+    fn inv_proptest(a in any::<u64>()) {
+        let a = BaseElement::from(a);
+        let b = a.inv();
+
+        let expected = if a == BaseElement::ZERO { BaseElement::ZERO } else { BaseElement::ONE };
+        prop_assert_eq!(expected, a * b);
+    }
+
+    #[test]
+    fn element_as_int_proptest(a in any::<u32>()) {
+        let e = BaseElement::new(a);
+        prop_assert_eq!((a % super::M) as u64, e.as_int());
+    }
+
+    #[test]
+    fn from_u128_proptest(v in any::<u128>()) {
+        let e = BaseElement::from(v);
+        assert_eq!((v % super::M as u128) as u64, e.as_int());
+    }
+}
@@ -13,7 +13,7 @@ use core::{
     slice,
 };
 use rand::{distributions::Uniform, prelude::*};
-use utils::AsBytes;
+use utils::{AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 #[cfg(test)]
 mod tests;
@@ -24,7 +24,7 @@ mod tests;
 /// Field modulus = 2^62 - 111 * 2^39 + 1
 const M: u64 = 4611624995532046337;
 
-/// 2^128 mod M; this is used for conversion of elements into Montgomery representation.
+/// 2^128 mod M; this is used for conversion of values into and out of Montgomery representation.
 const R2: u64 = 630444561284293700;
 
 /// 2^192 mod M; this is used during element inversion.
@@ -44,20 +44,21 @@ const RANGE: Range<u64> = Range { start: 0, end: M };
 // FIELD ELEMENT
 // ================================================================================================
 
-/// Base field element; internal values are stored in Montgomery representation and can be in
-/// the range [0; 2M)
-#[derive(Copy, Clone, Debug, Default)]
+/// Base field element; internal values are stored in canonical representation and are always
+/// in the range [0, M).
+///
+/// Multiplication and inversion are performed via Montgomery arithmetic, but their results are
+/// converted back into canonical representation. Thus, byte representations of elements returned
+/// by [FieldElement::elements_as_bytes()] are the same as their serialized representations, and
+/// elements can be included into proofs and hashed into commitments directly.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct BaseElement(u64);
 
 impl BaseElement {
-    /// Creates a new field element from the provided `value`; the value is converted into
-    /// Montgomery representation.
+    /// Creates a new field element from the provided `value`. If the value is greater than or
+    /// equal to the field modulus, modular reduction is silently performed.
     pub const fn new(value: u64) -> BaseElement {
-        // multiply the value with R2 to convert to Montgomery representation; this is OK because
-        // given the value of R2, the product of R2 and `value` is guaranteed to be in the range
-        // [0, 4M^2 - 4M + 1)
-        let z = mul(value, R2);
-        BaseElement(z)
+        BaseElement(value % M)
     }
 }
 
@@ -98,10 +99,8 @@ impl FieldElement for BaseElement {
         BaseElement(self.0)
     }
 
-    fn rand() -> Self {
-        let range = Uniform::from(RANGE);
-        let mut g = thread_rng();
-        BaseElement::new(g.sample(range))
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        BaseElement(rng.sample(Uniform::from(RANGE)))
     }
 
     fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
@@ -109,8 +108,7 @@ impl FieldElement for BaseElement {
     }
 
     fn to_canonical_bytes(self) -> Vec<u8> {
-        // convert from Montgomery representation into canonical representation
-        self.as_int().to_le_bytes().to_vec()
+        self.0.to_le_bytes().to_vec()
     }
 
     fn elements_into_bytes(elements: Vec<Self>) -> Vec<u8> {
@@ -163,7 +161,7 @@ impl FieldElement for BaseElement {
     fn prng_vector(seed: [u8; 32], n: usize) -> Vec<Self> {
         let range = Uniform::from(RANGE);
         let g = StdRng::from_seed(seed);
-        g.sample_iter(range).take(n).map(BaseElement::new).collect()
+        g.sample_iter(range).take(n).map(BaseElement).collect()
     }
 }
 
@@ -189,37 +187,28 @@ impl StarkField for BaseElement {
     /// 4421547261963328785
     const TWO_ADIC_ROOT_OF_UNITY: Self = BaseElement::new(G);
 
+    /// sage: R.<x> = GF(MODULUS)[]
+    /// sage: (x^3 - x - 1).is_irreducible()
+    /// False
+    /// sage: (x^3 - x - 2).is_irreducible()
+    /// True
+    const CUBE_EXTENSION_TERM: Self = BaseElement::new(2);
+
     fn get_modulus_le_bytes() -> Vec<u8> {
         Self::MODULUS.to_le_bytes().to_vec()
     }
 
     fn as_int(&self) -> Self::PositiveInteger {
-        // convert from Montgomery representation by multiplying by 1
-        let result = mul(self.0, 1);
-        // since the result of multiplication can be in [0, 2M), we need to normalize it
-        normalize(result)
+        self.0
     }
 }
 
 impl Display for BaseElement {
     fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-        write!(f, "{}", self.as_int())
-    }
-}
-
-// EQUALITY CHECKS
-// ================================================================================================
-
-impl PartialEq for BaseElement {
-    fn eq(&self, other: &Self) -> bool {
-        // since either of the elements can be in [0, 2M) range, we normalize them first to be
-        // in [0, M) range and then compare them.
-        normalize(self.0) == normalize(other.0)
+        write!(f, "{}", self.0)
     }
 }
 
-impl Eq for BaseElement {}
-
 // OVERLOADED OPERATORS
 // ================================================================================================
 
@@ -304,11 +293,11 @@ impl From<u128> for BaseElement {
         }
 
         // apply similar reduction as during multiplication; as output we get z = v * R^{-1} mod M,
-        // so we need to Montgomery-multiply it be R^3 to get z = v * R mod M
+        // so we need to Montgomery-multiply it by R^2 to get z = v mod M
         let q = (((v as u64) as u128) * U) as u64;
         let z = v + (q as u128) * (M as u128);
-        let z = mul((z >> 64) as u64, R3);
-        BaseElement(z)
+        let z = mont_mul((z >> 64) as u64, R2);
+        BaseElement(normalize(z))
     }
 }
 
@@ -323,21 +312,21 @@ impl From<u64> for BaseElement {
 impl From<u32> for BaseElement {
     /// Converts a 32-bit value into a filed element.
     fn from(value: u32) -> Self {
-        BaseElement::new(value as u64)
+        BaseElement(value as u64)
     }
 }
 
 impl From<u16> for BaseElement {
     /// Converts a 16-bit value into a filed element.
     fn from(value: u16) -> Self {
-        BaseElement::new(value as u64)
+        BaseElement(value as u64)
     }
 }
 
 impl From<u8> for BaseElement {
     /// Converts an 8-bit value into a filed element.
     fn from(value: u8) -> Self {
-        BaseElement::new(value as u64)
+        BaseElement(value as u64)
     }
 }
 
@@ -378,7 +367,7 @@ impl<'a> TryFrom<&'a [u8]> for BaseElement {
         if value >= M {
             return Err(ElementDecodingError::ValueTooLarger(format!("{}", value)));
         }
-        Ok(BaseElement::new(value))
+        Ok(BaseElement(value))
     }
 }
 
@@ -390,44 +379,87 @@ impl AsBytes for BaseElement {
     }
 }
 
+impl Serializable for BaseElement {
+    /// Writes the element in canonical representation in little-endian byte order.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(&self.0.to_le_bytes());
+    }
+}
+
+impl Deserializable for BaseElement {
+    /// Reads an element encoded in canonical representation in little-endian byte order; returns
+    /// an error if the encoded value is not a valid field element.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let value = u64::from_le_bytes(source.read_u8_array()?);
+        if value >= M {
+            return Err(DeserializationError::InvalidValue(format!(
+                "value {} is greater than or equal to the field modulus",
+                value
+            )));
+        }
+        Ok(BaseElement(value))
+    }
+}
+
 // FINITE FIELD ARITHMETIC
 // ================================================================================================
 
-/// Computes (a + b) reduced by M such that the output is in [0, 2M) range; a and b are assumed to
-/// be in [0, 2M).
+/// Computes (a + b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
 #[inline(always)]
 fn add(a: u64, b: u64) -> u64 {
-    let z = a + b;
-    let q = (z >> 62) * M;
-    z - q
+    normalize(a + b)
 }
 
-/// Computes (a - b) reduced by M such that the output is in [0, 2M) range; a and b are assumed to
-/// be in [0, 2M).
+/// Computes (a - b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
 #[inline(always)]
 fn sub(a: u64, b: u64) -> u64 {
     if a < b {
-        2 * M - b + a
+        M - b + a
     } else {
         a - b
     }
 }
 
-/// Computes (a * b) reduced by M such that the output is in [0, 2M) range; a and b are assumed to
-/// be in [0, 2M).
+/// Computes (a * b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
+///
+/// Montgomery multiplication of a and b yields a * b * R^{-1}; Montgomery-multiplying this by
+/// R^2 yields a * b.
+#[inline(always)]
+fn mul(a: u64, b: u64) -> u64 {
+    normalize(mont_mul(mont_mul(a, b), R2))
+}
+
+/// Computes y such that (x * y) % M = 1 except for when when x = 0; in such a case, 0 is returned;
+/// x is assumed to in [0, M) range, and the output will also be in [0, M) range.
+#[inline(always)]
+fn inv(x: u64) -> u64 {
+    // convert x into Montgomery representation, invert it, and convert the result back
+    let y = mont_inv(mont_mul(x, R2));
+    normalize(mont_mul(y, 1))
+}
+
+// MONTGOMERY ARITHMETIC
+// ================================================================================================
+
+/// Computes (a * b * R^{-1}) reduced by M such that the output is in [0, 2M) range; a and b are
+/// assumed to be in [0, 2M).
 #[inline(always)]
-const fn mul(a: u64, b: u64) -> u64 {
+const fn mont_mul(a: u64, b: u64) -> u64 {
     let z = (a as u128) * (b as u128);
     let q = (((z as u64) as u128) * U) as u64;
     let z = z + (q as u128) * (M as u128);
     (z >> 64) as u64
 }
 
-/// Computes y such that (x * y) % M = 1 except for when when x = 0; in such a case, 0 is returned;
-/// x is assumed to in [0, 2M) range, and the output will also be in [0, 2M) range.
+/// Computes y such that (x * y) % M = R^2 except for when when x = 0; in such a case, 0 is
+/// returned; i.e., inverts x in Montgomery representation. x is assumed to in [0, 2M) range, and
+/// the output will also be in [0, 2M) range.
 #[inline(always)]
 #[allow(clippy::many_single_char_names)]
-fn inv(x: u64) -> u64 {
+fn mont_inv(x: u64) -> u64 {
     if x == 0 {
         return 0;
     };
@@ -470,7 +502,7 @@ fn inv(x: u64) -> u64 {
         a -= M as u128;
     }
 
-    mul(a as u64, R3)
+    mont_mul(a as u64, R3)
 }
 
 // HELPER FUNCTIONS
@@ -478,7 +510,7 @@ fn inv(x: u64) -> u64 {
 
 /// Reduces any value in [0, 2M) range to [0, M) range
 #[inline(always)]
-fn normalize(value: u64) -> u64 {
+const fn normalize(value: u64) -> u64 {
     if value >= M {
         value - M
     } else {
@@ -4,7 +4,8 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    AsBytes, BaseElement, ElementDecodingError, FieldElement, SerializationError, StarkField,
+    AsBytes, BaseElement, Deserializable, DeserializationError, ElementDecodingError, FieldElement,
+    Serializable, SerializationError, StarkField,
 };
 use num_bigint::BigUint;
 use proptest::prelude::*;
@@ -109,14 +110,13 @@ fn equals() {
     let a = BaseElement::ONE;
     let b = BaseElement::new(super::M - 1) * BaseElement::new(super::M - 1);
 
-    // elements are equal
+    // elements are equal, and since elements are stored in canonical representation, so are
+    // their internal representations
     assert_eq!(a, b);
     assert_eq!(a.as_int(), b.as_int());
     assert_eq!(a.to_canonical_bytes(), b.to_canonical_bytes());
-
-    // but their internal representation is not
-    assert_ne!(a.0, b.0);
-    assert_ne!(a.as_bytes(), b.as_bytes());
+    assert_eq!(a.as_bytes(), b.as_bytes());
+    assert_eq!(a.to_canonical_bytes(), a.as_bytes());
 }
 
 // ROOTS OF UNITY
@@ -124,11 +124,11 @@ fn equals() {
 
 #[test]
 fn get_root_of_unity() {
-    let root_39 = BaseElement::get_root_of_unity(39);
+    let root_39 = BaseElement::get_root_of_unity(39).unwrap();
     assert_eq!(BaseElement::TWO_ADIC_ROOT_OF_UNITY, root_39);
     assert_eq!(BaseElement::ONE, root_39.exp(1u64 << 39));
 
-    let root_38 = BaseElement::get_root_of_unity(38);
+    let root_38 = BaseElement::get_root_of_unity(38).unwrap();
     let expected = root_39.exp(2);
     assert_eq!(expected, root_38);
     assert_eq!(BaseElement::ONE, root_38.exp(1u64 << 38));
@@ -235,6 +235,24 @@ fn bytes_as_elements() {
     assert_eq!(result, Err(SerializationError::InvalidMemoryAlignment));
 }
 
+#[test]
+fn serialize_deserialize() {
+    let element = BaseElement::new(12345);
+    let bytes = element.to_bytes();
+    assert_eq!(12345u64.to_le_bytes().to_vec(), bytes);
+    assert_eq!(Ok(element), BaseElement::read_from_bytes(&bytes));
+
+    let bytes = super::M.to_le_bytes();
+    assert!(matches!(
+        BaseElement::read_from_bytes(&bytes),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        BaseElement::read_from_bytes(&bytes[..7])
+    );
+}
+
 // INITIALIZATION
 // ------------------------------------------------------------------------------------------------
 
@@ -0,0 +1,151 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! FFT helpers for the Mersenne-31 field.
+//!
+//! The multiplicative group of the base field has order 2 * 3^2 * 7 * 11 * 31 * 151 * 331, and
+//! thus, it does not contain power-of-two subgroups needed for FFTs. The multiplicative group of
+//! the quadratic extension of the field has order M^2 - 1 = 2^32 * (2^30 - 1), and the root φ of
+//! x^2 - x - 1 which defines the extension generates its subgroup of order 2^32. This module
+//! builds FFT domains (and their multiplicative cosets) in this subgroup.
+//!
+//! Twiddles returned by this module can be passed to the generic [evaluate_poly()],
+//! [interpolate_poly()], and [interpolate_poly_with_offset()] functions of the [fft](crate::fft)
+//! module.
+//!
+//! [evaluate_poly()]: crate::fft::evaluate_poly
+//! [interpolate_poly()]: crate::fft::interpolate_poly
+//! [interpolate_poly_with_offset()]: crate::fft::interpolate_poly_with_offset
+
+use super::BaseElement;
+use crate::{
+    errors::RootOfUnityError,
+    fft,
+    field::{FieldElement, QuadExtension, StarkField},
+    utils::{get_power_series_unchecked, log2_unchecked},
+};
+use utils::uninit_vector;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Element of the quadratic extension of the Mersenne-31 field in which FFTs are computed.
+pub type ExtElement = QuadExtension<BaseElement>;
+
+/// Let M^2 - 1 = k * 2^n where k is odd; then, TWO_ADICITY is n.
+pub const TWO_ADICITY: u32 = 32;
+
+/// Root of x^2 - x - 1; since φ^(M + 1) = -1, this is a 2^32 root of unity.
+const TWO_ADIC_ROOT_OF_UNITY: ExtElement =
+    ExtElement::new(BaseElement::new(0), BaseElement::new(1));
+
+/// Default offset of FFT domain cosets; the field generator is not in the subgroup of order 2^32
+/// and thus, cosets shifted by it do not intersect the subgroup.
+pub const DOMAIN_OFFSET: ExtElement = ExtElement::new(BaseElement::GENERATOR, BaseElement::new(0));
+
+// ROOTS OF UNITY
+// ================================================================================================
+
+/// Returns a root of unity of order 2^n in the quadratic extension of the Mersenne-31 field.
+///
+/// # Errors
+/// Returns an error if `n` is zero or if it is greater than [TWO_ADICITY].
+pub fn get_root_of_unity(n: u32) -> Result<ExtElement, RootOfUnityError> {
+    if n == 0 {
+        return Err(RootOfUnityError::ZeroOrder);
+    }
+    if n > TWO_ADICITY {
+        return Err(RootOfUnityError::OrderTooLarge(n, TWO_ADICITY));
+    }
+    Ok(TWO_ADIC_ROOT_OF_UNITY.exp(1u64 << (TWO_ADICITY - n)))
+}
+
+// TWIDDLES
+// ================================================================================================
+
+/// Returns a set of twiddles for the specified domain size. These twiddles can then be used for
+/// FFT-based polynomial evaluation.
+pub fn get_twiddles(domain_size: usize) -> Vec<ExtElement> {
+    let root = get_domain_root(domain_size);
+    let mut twiddles = get_power_series_unchecked(root, domain_size / 2);
+    fft::permute(&mut twiddles);
+    twiddles
+}
+
+/// Returns a set of inverse twiddles for the specified domain size. These twiddles can then be
+/// used for FFT-based polynomial interpolation.
+pub fn get_inv_twiddles(domain_size: usize) -> Vec<ExtElement> {
+    let root = get_domain_root(domain_size);
+    let mut inv_twiddles = get_power_series_unchecked(root.inv(), domain_size / 2);
+    fft::permute(&mut inv_twiddles);
+    inv_twiddles
+}
+
+// POLYNOMIAL EVALUATION
+// ================================================================================================
+
+/// Evaluates polynomial `p` over the multiplicative coset of the FFT domain specified by
+/// `twiddles`, expanded by the `blowup_factor`, and shifted by the `domain_offset`; the result is
+/// returned in natural order of the domain.
+pub fn evaluate_poly_with_offset<E>(
+    p: &[E],
+    twiddles: &[ExtElement],
+    domain_offset: ExtElement,
+    blowup_factor: usize,
+) -> Vec<E>
+where
+    E: FieldElement + From<ExtElement>,
+{
+    assert!(
+        p.len().is_power_of_two(),
+        "number of coefficients must be a power of 2"
+    );
+    assert!(
+        blowup_factor.is_power_of_two(),
+        "blowup factor must be a power of 2"
+    );
+    assert_eq!(
+        p.len(),
+        twiddles.len() * 2,
+        "invalid number of twiddles: expected {} but received {}",
+        p.len() / 2,
+        twiddles.len()
+    );
+
+    let domain_size = p.len() * blowup_factor;
+    let g = get_domain_root(domain_size);
+    let mut result = uninit_vector(domain_size);
+
+    // each chunk holds evaluations over one coset of the FFT domain; cosets are processed in
+    // bit-reversed order, and evaluations within each coset are put back into bit-reversed order
+    // as well, so that the permutation at the end puts all evaluations into natural order
+    for (i, chunk) in result.chunks_mut(p.len()).enumerate() {
+        let idx = fft::permute_index(blowup_factor, i) as u64;
+        let offset = E::from(g.exp(idx) * domain_offset);
+        let mut factor = E::ONE;
+        for (d, &c) in chunk.iter_mut().zip(p.iter()) {
+            *d = c * factor;
+            factor *= offset;
+        }
+        fft::evaluate_poly(chunk, twiddles);
+        fft::permute(chunk);
+    }
+
+    fft::permute(&mut result);
+    result
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a generator of the FFT domain of the specified size.
+fn get_domain_root(domain_size: usize) -> ExtElement {
+    assert!(
+        domain_size.is_power_of_two(),
+        "domain size must be a power of 2"
+    );
+    get_root_of_unity(log2_unchecked(domain_size))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", domain_size, err))
+}
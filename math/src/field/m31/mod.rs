@@ -0,0 +1,462 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::traits::{FieldElement, StarkField};
+use crate::errors::{ElementDecodingError, SerializationError};
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt::{Debug, Display, Formatter},
+    mem,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Range, Sub, SubAssign},
+    slice,
+};
+use rand::{distributions::Uniform, prelude::*};
+use utils::{AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+#[cfg(feature = "simd")]
+use super::simd::{self, Reduction, SimdField};
+
+pub mod fft;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Field modulus = 2^31 - 1 (the Mersenne prime also known as M31)
+const M: u32 = 2147483647;
+
+/// Number of bytes needed to represent field element
+const ELEMENT_BYTES: usize = std::mem::size_of::<u32>();
+
+const RANGE: Range<u32> = Range { start: 0, end: M };
+
+// FIELD ELEMENT
+// ================================================================================================
+
+/// Base field element; internal values are stored in canonical representation and are always
+/// in the range [0, M).
+///
+/// Since 2^31 = 1 mod M, reduction needs only shifts, masks, and additions, which makes the
+/// arithmetic very fast on 32-bit targets (e.g., wasm32 or embedded platforms).
+///
+/// The multiplicative group of the field contains only a subgroup of order 2, and thus, this field
+/// cannot be used as a base field for proof generation. FFTs are instead computed in the quadratic
+/// extension of the field (see the [fft] module), and since M = 3 mod 4, x^4 - k is reducible for
+/// any k; quartic extensions of this field must not be used.
+///
+/// Since internal and canonical representations are the same, byte representations of elements
+/// returned by [FieldElement::elements_as_bytes()] can be compared and hashed directly.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct BaseElement(u32);
+
+impl BaseElement {
+    /// Creates a new field element from the provided `value`. If the value is greater than or
+    /// equal to the field modulus, modular reduction is silently performed.
+    pub const fn new(value: u32) -> BaseElement {
+        BaseElement(value % M)
+    }
+}
+
+impl FieldElement for BaseElement {
+    type PositiveInteger = u64;
+    type Base = Self;
+
+    const ZERO: Self = BaseElement::new(0);
+    const ONE: Self = BaseElement::new(1);
+
+    const ELEMENT_BYTES: usize = ELEMENT_BYTES;
+
+    fn exp(self, power: Self::PositiveInteger) -> Self {
+        let mut b = self;
+
+        if power == 0 {
+            return Self::ONE;
+        } else if b == Self::ZERO {
+            return Self::ZERO;
+        }
+
+        let mut r = if power & 1 == 1 { b } else { Self::ONE };
+        for i in 1..64 - power.leading_zeros() {
+            b = b.square();
+            if (power >> i) & 1 == 1 {
+                r *= b;
+            }
+        }
+
+        r
+    }
+
+    fn inv(self) -> Self {
+        // by Fermat's little theorem, x^(M - 2) = x^{-1} for all non-zero x; for x = 0, this
+        // evaluates to 0
+        self.exp(M as u64 - 2)
+    }
+
+    fn conjugate(&self) -> Self {
+        BaseElement(self.0)
+    }
+
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        BaseElement(rng.sample(Uniform::from(RANGE)))
+    }
+
+    fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::try_from(bytes).ok()
+    }
+
+    fn to_canonical_bytes(self) -> Vec<u8> {
+        self.0.to_le_bytes().to_vec()
+    }
+
+    fn elements_into_bytes(elements: Vec<Self>) -> Vec<u8> {
+        let mut v = std::mem::ManuallyDrop::new(elements);
+        let p = v.as_mut_ptr();
+        let len = v.len() * Self::ELEMENT_BYTES;
+        let cap = v.capacity() * Self::ELEMENT_BYTES;
+        unsafe { Vec::from_raw_parts(p as *mut u8, len, cap) }
+    }
+
+    fn elements_as_bytes(elements: &[Self]) -> &[u8] {
+        // TODO: take endianness into account
+        let p = elements.as_ptr();
+        let len = elements.len() * Self::ELEMENT_BYTES;
+        unsafe { slice::from_raw_parts(p as *const u8, len) }
+    }
+
+    unsafe fn bytes_as_elements(bytes: &[u8]) -> Result<&[Self], SerializationError> {
+        if !bytes.len().is_multiple_of(Self::ELEMENT_BYTES) {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                bytes.len(),
+            ));
+        }
+
+        let p = bytes.as_ptr();
+        let len = bytes.len() / Self::ELEMENT_BYTES;
+
+        if !(p as usize).is_multiple_of(mem::align_of::<u32>()) {
+            return Err(SerializationError::InvalidMemoryAlignment);
+        }
+
+        Ok(slice::from_raw_parts(p as *const Self, len))
+    }
+
+    fn zeroed_vector(n: usize) -> Vec<Self> {
+        // this uses a specialized vector initialization code which requests zero-filled memory
+        // from the OS; unfortunately, this works only for built-in types and we can't use
+        // Self::ZERO here as much less efficient initialization procedure will be invoked.
+        // We also use u32 to make sure the memory is aligned correctly for our element size.
+        let result = vec![0u32; n];
+
+        // translate a zero-filled vector of u32s into a vector of base field elements
+        let mut v = std::mem::ManuallyDrop::new(result);
+        let p = v.as_mut_ptr();
+        let len = v.len();
+        let cap = v.capacity();
+        unsafe { Vec::from_raw_parts(p as *mut Self, len, cap) }
+    }
+
+    fn prng_vector(seed: [u8; 32], n: usize) -> Vec<Self> {
+        let range = Uniform::from(RANGE);
+        let g = StdRng::from_seed(seed);
+        g.sample_iter(range).take(n).map(BaseElement).collect()
+    }
+
+    #[cfg(feature = "simd")]
+    fn batch_add(a: &mut [Self], b: &[Self]) {
+        simd::add_assign(a, b)
+    }
+
+    #[cfg(feature = "simd")]
+    fn batch_mul(a: &mut [Self], b: &[Self]) {
+        simd::mul_assign(a, b)
+    }
+
+    #[cfg(feature = "simd")]
+    fn batch_butterfly(lo: &mut [Self], hi: &mut [Self], twiddle: Self) {
+        simd::butterfly(lo, hi, twiddle)
+    }
+}
+
+impl StarkField for BaseElement {
+    /// sage: MODULUS = 2^31 - 1
+    /// sage: GF(MODULUS).is_prime_field()
+    /// True
+    /// sage: GF(MODULUS).order()
+    /// 2147483647
+    const MODULUS: Self::PositiveInteger = M as u64;
+    const MODULUS_BITS: u32 = 31;
+
+    /// sage: GF(MODULUS).primitive_element()
+    /// 7
+    const GENERATOR: Self = BaseElement::new(7);
+
+    /// sage: is_odd((MODULUS - 1) / 2)
+    /// True
+    const TWO_ADICITY: u32 = 1;
+
+    /// sage: k = (MODULUS - 1) / 2
+    /// sage: GF(MODULUS).primitive_element()^k
+    /// 2147483646
+    const TWO_ADIC_ROOT_OF_UNITY: Self = BaseElement::new(M - 1);
+
+    /// sage: R.<x> = GF(MODULUS)[]
+    /// sage: (x^3 - x - 2).is_irreducible()
+    /// False
+    /// sage: (x^3 - x - 3).is_irreducible()
+    /// True
+    const CUBE_EXTENSION_TERM: Self = BaseElement::new(3);
+
+    fn get_modulus_le_bytes() -> Vec<u8> {
+        Self::MODULUS.to_le_bytes().to_vec()
+    }
+
+    fn as_int(&self) -> Self::PositiveInteger {
+        self.0 as u64
+    }
+}
+
+impl Display for BaseElement {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// OVERLOADED OPERATORS
+// ================================================================================================
+
+impl Add for BaseElement {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(add(self.0, rhs.0))
+    }
+}
+
+impl AddAssign for BaseElement {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl Sub for BaseElement {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(sub(self.0, rhs.0))
+    }
+}
+
+impl SubAssign for BaseElement {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for BaseElement {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(mul(self.0, rhs.0))
+    }
+}
+
+impl MulAssign for BaseElement {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl Div for BaseElement {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl DivAssign for BaseElement {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
+impl Neg for BaseElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(sub(0, self.0))
+    }
+}
+
+// TYPE CONVERSIONS
+// ================================================================================================
+
+impl From<u128> for BaseElement {
+    /// Converts a 128-bit value into a filed element. If the value is greater than or equal to
+    /// the field modulus, modular reduction is silently preformed.
+    fn from(value: u128) -> Self {
+        BaseElement((value % M as u128) as u32)
+    }
+}
+
+impl From<u64> for BaseElement {
+    /// Converts a 64-bit value into a filed element. If the value is greater than or equal to
+    /// the field modulus, modular reduction is silently preformed.
+    fn from(value: u64) -> Self {
+        BaseElement((value % M as u64) as u32)
+    }
+}
+
+impl From<u32> for BaseElement {
+    /// Converts a 32-bit value into a filed element. If the value is greater than or equal to
+    /// the field modulus, modular reduction is silently preformed.
+    fn from(value: u32) -> Self {
+        BaseElement::new(value)
+    }
+}
+
+impl From<u16> for BaseElement {
+    /// Converts a 16-bit value into a filed element.
+    fn from(value: u16) -> Self {
+        BaseElement(value as u32)
+    }
+}
+
+impl From<u8> for BaseElement {
+    /// Converts an 8-bit value into a filed element.
+    fn from(value: u8) -> Self {
+        BaseElement(value as u32)
+    }
+}
+
+impl From<[u8; 4]> for BaseElement {
+    /// Converts the value encoded in an array of 4 bytes into a field element. The bytes are
+    /// assumed to encode the element in the canonical representation in little-endian byte order.
+    /// If the value is greater than or equal to the field modulus, modular reduction is silently
+    /// preformed.
+    fn from(bytes: [u8; 4]) -> Self {
+        let value = u32::from_le_bytes(bytes);
+        BaseElement::new(value)
+    }
+}
+
+impl TryFrom<&[u8]> for BaseElement {
+    type Error = ElementDecodingError;
+
+    /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
+    /// is not a valid field element. The bytes are assumed to encode the element in the canonical
+    /// representation in little-endian byte order.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() < ELEMENT_BYTES {
+            return Err(ElementDecodingError::NotEnoughBytes(
+                ELEMENT_BYTES,
+                bytes.len(),
+            ));
+        }
+        if bytes.len() > ELEMENT_BYTES {
+            return Err(ElementDecodingError::TooManyBytes(
+                ELEMENT_BYTES,
+                bytes.len(),
+            ));
+        }
+        let value = bytes
+            .try_into()
+            .map(u32::from_le_bytes)
+            .map_err(|error| ElementDecodingError::UnknownError(format!("{}", error)))?;
+        if value >= M {
+            return Err(ElementDecodingError::ValueTooLarger(format!("{}", value)));
+        }
+        Ok(BaseElement(value))
+    }
+}
+
+impl AsBytes for BaseElement {
+    fn as_bytes(&self) -> &[u8] {
+        // TODO: take endianness into account
+        let self_ptr: *const BaseElement = self;
+        unsafe { slice::from_raw_parts(self_ptr as *const u8, ELEMENT_BYTES) }
+    }
+}
+
+impl Serializable for BaseElement {
+    /// Writes the element in canonical representation in little-endian byte order.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(&self.0.to_le_bytes());
+    }
+}
+
+impl Deserializable for BaseElement {
+    /// Reads an element encoded in canonical representation in little-endian byte order; returns
+    /// an error if the encoded value is not a valid field element.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let value = u32::from_le_bytes(source.read_u8_array()?);
+        if value >= M {
+            return Err(DeserializationError::InvalidValue(format!(
+                "value {} is greater than or equal to the field modulus",
+                value
+            )));
+        }
+        Ok(BaseElement(value))
+    }
+}
+
+// SIMD BACKEND
+// ================================================================================================
+
+#[cfg(feature = "simd")]
+unsafe impl SimdField for BaseElement {
+    const M: u32 = M;
+    const REDUCTION: Reduction = Reduction::Mersenne;
+
+    fn add(a: u32, b: u32) -> u32 {
+        add(a, b)
+    }
+
+    fn sub(a: u32, b: u32) -> u32 {
+        sub(a, b)
+    }
+
+    fn mul(a: u32, b: u32) -> u32 {
+        mul(a, b)
+    }
+}
+
+// FINITE FIELD ARITHMETIC
+// ================================================================================================
+
+/// Computes (a + b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
+#[inline(always)]
+fn add(a: u32, b: u32) -> u32 {
+    // a + b < 2M < 2^32; if the sum is less than M, subtracting M wraps around to a value which
+    // is greater than the sum, and thus the minimum is always the reduced value
+    let z = a + b;
+    z.min(z.wrapping_sub(M))
+}
+
+/// Computes (a - b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
+#[inline(always)]
+fn sub(a: u32, b: u32) -> u32 {
+    // on underflow, the difference wraps around to a value greater than M, while adding M to it
+    // wraps around again to a - b + M; otherwise, adding M yields a value greater than a - b
+    let z = a.wrapping_sub(b);
+    z.min(z.wrapping_add(M))
+}
+
+/// Computes (a * b) reduced by M such that the output is in [0, M) range; a and b are assumed to
+/// be in [0, M).
+#[inline(always)]
+fn mul(a: u32, b: u32) -> u32 {
+    // since 2^31 = 1 mod M, the product is congruent to the sum of its low 31 bits and its
+    // remaining high bits; the sum is less than 2M, and thus a single subtraction reduces it
+    let z = a as u64 * b as u64;
+    let z = (z as u32 & M) + (z >> 31) as u32;
+    z.min(z.wrapping_sub(M))
+}
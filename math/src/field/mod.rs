@@ -4,10 +4,30 @@
 // LICENSE file in the root directory of this source tree.
 
 mod traits;
-pub use traits::{FieldElement, StarkField};
+pub use traits::{ExtensionOf, FieldElement, StarkField};
 
+mod u256;
+pub use u256::U256;
+
+pub mod bls12_381;
+pub mod bn254;
 pub mod f128;
+pub mod f31;
 pub mod f62;
+pub mod f64;
+pub mod m31;
 
 mod extensions;
-pub use extensions::QuadExtension;
+pub use extensions::{CubeExtension, QuadExtension, QuartExtension};
+
+mod profiled;
+pub use profiled::ProfiledElement;
+
+mod encoding;
+pub use encoding::{
+    decode_signed, encode_signed, from_evm_word, from_evm_words, to_evm_word, to_evm_words,
+    ByteEncoding, FixedPoint, IntegerRange, EVM_WORD_BYTES,
+};
+
+#[cfg(feature = "simd")]
+mod simd;
@@ -0,0 +1,395 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{FieldElement, StarkField};
+use crate::errors::SerializationError;
+use core::{
+    convert::TryFrom,
+    fmt::{Display, Formatter},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    slice,
+};
+use rand::Rng;
+use utils::{AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// PROFILED ELEMENT
+// ================================================================================================
+
+/// A field element which keeps track of the number of field operations used to compute it.
+///
+/// The number of operations of an element is the number of field operations in the expression
+/// used to compute it, where a sub-expression used several times is counted once for each use.
+/// Elements created from values or constants have no cost; exponentiation counts as the number
+/// of squarings and multiplications it requires.
+///
+/// Profiled elements are used by the prover to estimate costs of transition constraints when
+/// constraint profiling is enabled: since constraints are evaluated generically over any field
+/// element, they can be evaluated over profiled elements without any changes to the AIR.
+///
+/// Internally, an element is laid out as two base field elements: the value followed by the
+/// number of operations. Thus, when profiled elements are re-interpreted as raw bytes, the bytes
+/// include the numbers of operations, and profiled elements should not be used in commitments.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ProfiledElement<B: StarkField> {
+    value: B,
+    ops: B,
+}
+
+impl<B: StarkField> ProfiledElement<B> {
+    /// Returns a new element with the specified value and no cost.
+    pub fn new(value: B) -> Self {
+        ProfiledElement {
+            value,
+            ops: B::ZERO,
+        }
+    }
+
+    /// Returns the value of this element.
+    pub fn value(&self) -> B {
+        self.value
+    }
+
+    /// Returns the number of field operations used to compute this element.
+    pub fn ops(&self) -> u64 {
+        let bytes = self.ops.to_canonical_bytes();
+        let mut result = [0u8; 8];
+        result.copy_from_slice(&bytes[..8]);
+        u64::from_le_bytes(result)
+    }
+
+    fn with_ops(value: B, ops: B) -> Self {
+        ProfiledElement { value, ops }
+    }
+
+    fn add_ops(self, value: B, ops: u64) -> Self {
+        Self::with_ops(value, self.ops + B::from(ops))
+    }
+
+    fn combine(self, rhs: Self, value: B) -> Self {
+        Self::with_ops(value, self.ops + rhs.ops + B::ONE)
+    }
+}
+
+impl<B: StarkField> FieldElement for ProfiledElement<B> {
+    type PositiveInteger = B::PositiveInteger;
+    type Base = B;
+
+    const ELEMENT_BYTES: usize = B::ELEMENT_BYTES;
+    const ZERO: Self = Self {
+        value: B::ZERO,
+        ops: B::ZERO,
+    };
+    const ONE: Self = Self {
+        value: B::ONE,
+        ops: B::ZERO,
+    };
+
+    fn double(self) -> Self {
+        self.add_ops(self.value.double(), 1)
+    }
+
+    fn square(self) -> Self {
+        self.add_ops(self.value.square(), 1)
+    }
+
+    fn cube(self) -> Self {
+        self.add_ops(self.value.cube(), 2)
+    }
+
+    fn exp(self, power: Self::PositiveInteger) -> Self {
+        let int_zero = Self::PositiveInteger::from(0u32);
+        let int_one = Self::PositiveInteger::from(1u32);
+
+        // count squarings and multiplications performed by square-and-multiply algorithm
+        let mut ops = 0u64;
+        let mut p = power;
+        while p > int_zero {
+            if p & int_one == int_one {
+                ops += 1;
+            }
+            p >>= int_one;
+            ops += 1;
+        }
+
+        self.add_ops(self.value.exp(power), ops)
+    }
+
+    fn inv(self) -> Self {
+        self.add_ops(self.value.inv(), 1)
+    }
+
+    fn conjugate(&self) -> Self {
+        Self::with_ops(self.value.conjugate(), self.ops)
+    }
+
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(B::rand_with_rng(rng))
+    }
+
+    fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
+        B::from_random_bytes(bytes).map(Self::new)
+    }
+
+    fn to_canonical_bytes(self) -> Vec<u8> {
+        self.value.to_canonical_bytes()
+    }
+
+    fn elements_into_bytes(elements: Vec<Self>) -> Vec<u8> {
+        let values = elements.into_iter().map(|e| e.value).collect();
+        B::elements_into_bytes(values)
+    }
+
+    fn elements_as_bytes(elements: &[Self]) -> &[u8] {
+        // re-interpret profiled elements as pairs of base elements
+        let values =
+            unsafe { slice::from_raw_parts(elements.as_ptr() as *const B, elements.len() * 2) };
+        B::elements_as_bytes(values)
+    }
+
+    unsafe fn bytes_as_elements(bytes: &[u8]) -> Result<&[Self], SerializationError> {
+        let values = B::bytes_as_elements(bytes)?;
+        if values.len() % 2 != 0 {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                bytes.len(),
+            ));
+        }
+        Ok(slice::from_raw_parts(
+            values.as_ptr() as *const Self,
+            values.len() / 2,
+        ))
+    }
+
+    fn prng_vector(seed: [u8; 32], n: usize) -> Vec<Self> {
+        B::prng_vector(seed, n).into_iter().map(Self::new).collect()
+    }
+}
+
+impl<B: StarkField> PartialEq for ProfiledElement<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<B: StarkField> Eq for ProfiledElement<B> {}
+
+impl<B: StarkField> Display for ProfiledElement<B> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+// OVERLOADED OPERATORS
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> Add for ProfiledElement<B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.combine(rhs, self.value + rhs.value)
+    }
+}
+
+impl<B: StarkField> AddAssign for ProfiledElement<B> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl<B: StarkField> Sub for ProfiledElement<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.combine(rhs, self.value - rhs.value)
+    }
+}
+
+impl<B: StarkField> SubAssign for ProfiledElement<B> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<B: StarkField> Mul for ProfiledElement<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.combine(rhs, self.value * rhs.value)
+    }
+}
+
+impl<B: StarkField> MulAssign for ProfiledElement<B> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl<B: StarkField> Div for ProfiledElement<B> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self.combine(rhs, self.value / rhs.value)
+    }
+}
+
+impl<B: StarkField> DivAssign for ProfiledElement<B> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
+impl<B: StarkField> Neg for ProfiledElement<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.add_ops(-self.value, 1)
+    }
+}
+
+// TYPE CONVERSIONS
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> From<B> for ProfiledElement<B> {
+    fn from(value: B) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<B: StarkField> From<u128> for ProfiledElement<B> {
+    fn from(value: u128) -> Self {
+        Self::new(B::from(value))
+    }
+}
+
+impl<B: StarkField> From<u64> for ProfiledElement<B> {
+    fn from(value: u64) -> Self {
+        Self::new(B::from(value))
+    }
+}
+
+impl<B: StarkField> From<u32> for ProfiledElement<B> {
+    fn from(value: u32) -> Self {
+        Self::new(B::from(value))
+    }
+}
+
+impl<B: StarkField> From<u16> for ProfiledElement<B> {
+    fn from(value: u16) -> Self {
+        Self::new(B::from(value))
+    }
+}
+
+impl<B: StarkField> From<u8> for ProfiledElement<B> {
+    fn from(value: u8) -> Self {
+        Self::new(B::from(value))
+    }
+}
+
+impl<B: StarkField> TryFrom<&[u8]> for ProfiledElement<B> {
+    type Error = String;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        B::try_from(bytes)
+            .map(Self::new)
+            .map_err(|_| "could not convert into field element".to_string())
+    }
+}
+
+// SERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField> AsBytes for ProfiledElement<B> {
+    fn as_bytes(&self) -> &[u8] {
+        self.value.as_bytes()
+    }
+}
+
+impl<B: StarkField> Serializable for ProfiledElement<B> {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.value.write_into(target);
+    }
+}
+
+impl<B: StarkField> Deserializable for ProfiledElement<B> {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        B::read_from(source).map(Self::new)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldElement, ProfiledElement};
+    use crate::field::f128::BaseElement;
+
+    #[test]
+    fn count_operations() {
+        let a = ProfiledElement::new(BaseElement::from(3u8));
+        let b = ProfiledElement::new(BaseElement::from(5u8));
+        assert_eq!(0, a.ops());
+
+        // values are computed the same way as for the wrapped elements
+        let c = (a + b) * a - b / a;
+        let expected = (a.value() + b.value()) * a.value() - b.value() / a.value();
+        assert_eq!(expected, c.value());
+        assert_eq!(4, c.ops());
+
+        // shared sub-expressions are counted once for each use
+        let d = c * c;
+        assert_eq!(9, d.ops());
+
+        // constants have no cost
+        let e = -(a + ProfiledElement::ONE);
+        assert_eq!(2, e.ops());
+    }
+
+    #[test]
+    fn count_exp_operations() {
+        let a = ProfiledElement::new(BaseElement::from(3u8));
+        let b = a.square() + a;
+        assert_eq!(2, b.ops());
+
+        // 7 = 0b111 takes 3 squarings and 3 multiplications
+        let c = b.exp(7);
+        assert_eq!(b.value().exp(7), c.value());
+        assert_eq!(8, c.ops());
+    }
+
+    #[test]
+    fn elements_as_bytes_round_trip() {
+        let a = ProfiledElement::new(BaseElement::from(3u8));
+        let elements = [a, a * a];
+        let bytes = ProfiledElement::elements_as_bytes(&elements);
+        assert_eq!(4 * BaseElement::ELEMENT_BYTES, bytes.len());
+
+        // byte representation includes both, values and numbers of operations
+        let expected = BaseElement::elements_as_bytes(&[
+            a.value(),
+            BaseElement::ZERO,
+            (a * a).value(),
+            BaseElement::ONE,
+        ])
+        .to_vec();
+        assert_eq!(expected, bytes);
+
+        let result = unsafe { ProfiledElement::<BaseElement>::bytes_as_elements(bytes) }.unwrap();
+        assert_eq!(&elements[..], result);
+        assert_eq!(1, result[1].ops());
+
+        // bytes for a single base element do not make up a profiled element
+        let result = unsafe { ProfiledElement::<BaseElement>::bytes_as_elements(&bytes[..16]) };
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn equality_ignores_operations() {
+        let a = ProfiledElement::new(BaseElement::from(3u8));
+        let b = a + ProfiledElement::ZERO;
+        assert_eq!(1, b.ops());
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! AVX2 kernels which process 8 field elements per instruction.
+//!
+//! AVX2 can multiply only 32-bit words in even lanes into 64-bit products; thus, every vector
+//! multiplication computes products in even and odd lanes separately and recombines their
+//! reduced halves via blending.
+
+use super::{prepare_const, Reduction, SimdField};
+use core::arch::x86_64::*;
+
+/// Number of field elements in a single AVX2 register.
+const LANES: usize = 8;
+
+/// Blend mask which selects odd 32-bit lanes.
+const ODD_LANES: i32 = 0b1010_1010;
+
+// KERNELS
+// ================================================================================================
+
+/// Computes a[i] + b[i] for all full registers of the inputs, and returns the number of
+/// processed elements.
+#[target_feature(enable = "avx2")]
+pub unsafe fn add_assign<F: SimdField>(a: &mut [u32], b: &[u32]) -> usize {
+    let m = _mm256_set1_epi32(F::M as i32);
+    let mut n = 0;
+    for (a, b) in a.chunks_exact_mut(LANES).zip(b.chunks_exact(LANES)) {
+        let x = load(a);
+        let y = load(b);
+        store(a, add(x, y, m));
+        n += LANES;
+    }
+    n
+}
+
+/// Computes a[i] * b[i] for all full registers of the inputs, and returns the number of
+/// processed elements.
+#[target_feature(enable = "avx2")]
+pub unsafe fn mul_assign<F: SimdField>(a: &mut [u32], b: &[u32]) -> usize {
+    let m = _mm256_set1_epi32(F::M as i32);
+    let mut n = 0;
+    for (a, b) in a.chunks_exact_mut(LANES).zip(b.chunks_exact(LANES)) {
+        let x = load(a);
+        let y = load(b);
+        store(a, mul::<F>(x, y, m));
+        n += LANES;
+    }
+    n
+}
+
+/// Computes FFT butterflies for all full registers of the inputs, and returns the number of
+/// processed elements.
+#[target_feature(enable = "avx2")]
+pub unsafe fn butterfly<F: SimdField>(lo: &mut [u32], hi: &mut [u32], twiddle: u32) -> usize {
+    let m = _mm256_set1_epi32(F::M as i32);
+    let c = _mm256_set1_epi32(prepare_const::<F>(twiddle) as i32);
+    let mut n = 0;
+    for (lo, hi) in lo.chunks_exact_mut(LANES).zip(hi.chunks_exact_mut(LANES)) {
+        let x = load(lo);
+        let y = mul_by_const::<F>(load(hi), c, m);
+        store(lo, add(x, y, m));
+        store(hi, sub(x, y, m));
+        n += LANES;
+    }
+    n
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn load(values: &[u32]) -> __m256i {
+    debug_assert_eq!(values.len(), LANES);
+    _mm256_loadu_si256(values.as_ptr() as *const __m256i)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn store(values: &mut [u32], x: __m256i) {
+    debug_assert_eq!(values.len(), LANES);
+    _mm256_storeu_si256(values.as_mut_ptr() as *mut __m256i, x)
+}
+
+/// Computes (x + y) mod M in every lane; this is the same computation as the one performed by
+/// scalar field arithmetic: if x + y < M, subtracting M wraps around to a larger value.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn add(x: __m256i, y: __m256i, m: __m256i) -> __m256i {
+    let z = _mm256_add_epi32(x, y);
+    _mm256_min_epu32(z, _mm256_sub_epi32(z, m))
+}
+
+/// Computes (x - y) mod M in every lane.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn sub(x: __m256i, y: __m256i, m: __m256i) -> __m256i {
+    let z = _mm256_sub_epi32(x, y);
+    _mm256_min_epu32(z, _mm256_add_epi32(z, m))
+}
+
+/// Computes (x * y) mod M in every lane.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn mul<F: SimdField>(x: __m256i, y: __m256i, m: __m256i) -> __m256i {
+    match F::REDUCTION {
+        Reduction::Mersenne => mersenne_mul(x, y, m),
+        Reduction::Montgomery { u, r2 } => {
+            let u = _mm256_set1_epi32(u as i32);
+            let z = montgomery_mul(x, y, u, m);
+            montgomery_mul(z, _mm256_set1_epi32(r2 as i32), u, m)
+        }
+    }
+}
+
+/// Computes (x * c) mod M in every lane, where c was prepared via `prepare_const()`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn mul_by_const<F: SimdField>(x: __m256i, c: __m256i, m: __m256i) -> __m256i {
+    match F::REDUCTION {
+        Reduction::Mersenne => mersenne_mul(x, c, m),
+        Reduction::Montgomery { u, .. } => montgomery_mul(x, c, _mm256_set1_epi32(u as i32), m),
+    }
+}
+
+/// Computes 64-bit products of even lanes and odd lanes of x and y.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn wide_mul(x: __m256i, y: __m256i) -> (__m256i, __m256i) {
+    let even = _mm256_mul_epu32(x, y);
+    let odd = _mm256_mul_epu32(_mm256_srli_epi64::<32>(x), _mm256_srli_epi64::<32>(y));
+    (even, odd)
+}
+
+/// Computes (x * y) mod 2^31 - 1 in every lane; this mirrors scalar multiplication in the
+/// Mersenne-31 field.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn mersenne_mul(x: __m256i, y: __m256i, m: __m256i) -> __m256i {
+    let (even, odd) = wide_mul(x, y);
+
+    // low 32 bits of every product; for odd lanes these are moved into the high half of the
+    // 64-bit lane
+    let lo = _mm256_blend_epi32::<ODD_LANES>(even, _mm256_slli_epi64::<32>(odd));
+    // bits above the 31st bit of every product; since products are smaller than 2^62, shifting
+    // odd products left by one bit moves these bits into the high half of the 64-bit lane
+    let hi =
+        _mm256_blend_epi32::<ODD_LANES>(_mm256_srli_epi64::<31>(even), _mm256_slli_epi64::<1>(odd));
+
+    let z = _mm256_add_epi32(_mm256_and_si256(lo, m), hi);
+    _mm256_min_epu32(z, _mm256_sub_epi32(z, m))
+}
+
+/// Computes (x * y) / 2^32 mod M in every lane; this mirrors a multiplication followed by a
+/// scalar Montgomery reduction.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn montgomery_mul(x: __m256i, y: __m256i, u: __m256i, m: __m256i) -> __m256i {
+    let (p_even, p_odd) = wide_mul(x, y);
+
+    // t = (p mod 2^32) * U mod 2^32 is chosen such that p - t * M is divisible by 2^32; only
+    // low 32 bits of 64-bit lanes of t are used by the following multiplication
+    let q_even = _mm256_mul_epu32(_mm256_mul_epu32(p_even, u), m);
+    let q_odd = _mm256_mul_epu32(_mm256_mul_epu32(p_odd, u), m);
+
+    // low words of p and t * M are equal, and thus (p - t * M) / 2^32 is the difference of their
+    // high words; both high words are smaller than M
+    let p_hi = _mm256_blend_epi32::<ODD_LANES>(_mm256_srli_epi64::<32>(p_even), p_odd);
+    let q_hi = _mm256_blend_epi32::<ODD_LANES>(_mm256_srli_epi64::<32>(q_even), q_odd);
+    sub(p_hi, q_hi, m)
+}
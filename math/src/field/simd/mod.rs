@@ -0,0 +1,183 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! SIMD backend for arithmetic in 31-bit prime fields.
+//!
+//! Elements of [f31](super::f31) and [m31](super::m31) fields fit into 32-bit words, and thus a
+//! 256-bit AVX2 register holds 8 elements, while a 128-bit NEON register holds 4 elements.
+//! Functions of this module operate on slices of canonical field elements (viewed as `u32`
+//! words) one full register at a time; the remaining elements, as well as all elements on CPUs
+//! which do not support these instruction sets, are processed using scalar field arithmetic.
+//!
+//! On x86_64 targets, support for AVX2 is detected at runtime; NEON is always available on
+//! aarch64 targets.
+
+use core::slice;
+
+#[cfg(target_arch = "x86_64")]
+mod avx2;
+
+#[cfg(target_arch = "aarch64")]
+mod neon;
+
+#[cfg(test)]
+mod tests;
+
+// FIELD PARAMETERS
+// ================================================================================================
+
+/// Method used to reduce products of field elements.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Reduction {
+    /// The modulus is 2^31 - 1; since 2^31 = 1 mod M, a product is reduced by adding its low 31
+    /// bits to its remaining high bits.
+    Mersenne,
+    /// A product is reduced via Montgomery reduction; `u` is M^{-1} mod 2^32, and `r2` is
+    /// 2^64 mod M which is used to remove the extra 2^{-32} factor introduced by the reduction.
+    Montgomery { u: u32, r2: u32 },
+}
+
+/// A prime field with a modulus smaller than 2^31 which can be processed by this backend.
+///
+/// Scalar operations are used for elements which do not fill a full register; these operations
+/// are expected to accept and return canonical values in [0, M) range.
+///
+/// # Safety
+/// Elements of the field must be represented by a single `u32` word (i.e., the type must have
+/// the same memory layout as `u32`), and this word must hold the canonical value of the element.
+pub(crate) unsafe trait SimdField: Copy {
+    /// Field modulus.
+    const M: u32;
+
+    /// Method used to reduce products of field elements.
+    const REDUCTION: Reduction;
+
+    /// Computes (a + b) mod M.
+    fn add(a: u32, b: u32) -> u32;
+
+    /// Computes (a - b) mod M.
+    fn sub(a: u32, b: u32) -> u32;
+
+    /// Computes (a * b) mod M.
+    fn mul(a: u32, b: u32) -> u32;
+}
+
+// BATCH OPERATIONS
+// ================================================================================================
+
+/// Computes a[i] + b[i] for all i and stores the results in a.
+pub(crate) fn add_assign<F: SimdField>(a: &mut [F], b: &[F]) {
+    debug_assert_eq!(
+        a.len(),
+        b.len(),
+        "number of values must be the same for both operands"
+    );
+    let (a, b) = (as_words_mut(a), as_words(b));
+
+    #[cfg(target_arch = "x86_64")]
+    let n = if is_x86_feature_detected!("avx2") {
+        unsafe { avx2::add_assign::<F>(a, b) }
+    } else {
+        0
+    };
+
+    #[cfg(target_arch = "aarch64")]
+    let n = unsafe { neon::add_assign::<F>(a, b) };
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    let n = 0;
+
+    for (a, &b) in a[n..].iter_mut().zip(&b[n..]) {
+        *a = F::add(*a, b);
+    }
+}
+
+/// Computes a[i] * b[i] for all i and stores the results in a.
+pub(crate) fn mul_assign<F: SimdField>(a: &mut [F], b: &[F]) {
+    debug_assert_eq!(
+        a.len(),
+        b.len(),
+        "number of values must be the same for both operands"
+    );
+    let (a, b) = (as_words_mut(a), as_words(b));
+
+    #[cfg(target_arch = "x86_64")]
+    let n = if is_x86_feature_detected!("avx2") {
+        unsafe { avx2::mul_assign::<F>(a, b) }
+    } else {
+        0
+    };
+
+    #[cfg(target_arch = "aarch64")]
+    let n = unsafe { neon::mul_assign::<F>(a, b) };
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    let n = 0;
+
+    for (a, &b) in a[n..].iter_mut().zip(&b[n..]) {
+        *a = F::mul(*a, b);
+    }
+}
+
+/// Computes lo[i] + hi[i] * twiddle and lo[i] - hi[i] * twiddle for all i, and stores the
+/// results in lo and hi respectively.
+pub(crate) fn butterfly<F: SimdField>(lo: &mut [F], hi: &mut [F], twiddle: F) {
+    debug_assert_eq!(
+        lo.len(),
+        hi.len(),
+        "number of values must be the same for both halves"
+    );
+    let (lo, hi) = (as_words_mut(lo), as_words_mut(hi));
+    let twiddle = as_words(slice::from_ref(&twiddle))[0];
+
+    #[cfg(target_arch = "x86_64")]
+    let n = if is_x86_feature_detected!("avx2") {
+        unsafe { avx2::butterfly::<F>(lo, hi, twiddle) }
+    } else {
+        0
+    };
+
+    #[cfg(target_arch = "aarch64")]
+    let n = unsafe { neon::butterfly::<F>(lo, hi, twiddle) };
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    let n = 0;
+
+    for (lo, hi) in lo[n..].iter_mut().zip(hi[n..].iter_mut()) {
+        let t = F::mul(*hi, twiddle);
+        *hi = F::sub(*lo, t);
+        *lo = F::add(*lo, t);
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Re-interprets a slice of field elements as a slice of their canonical values.
+#[inline(always)]
+fn as_words<F: SimdField>(elements: &[F]) -> &[u32] {
+    // this is safe because SimdField guarantees that F has the same layout as u32
+    unsafe { slice::from_raw_parts(elements.as_ptr() as *const u32, elements.len()) }
+}
+
+/// Re-interprets a mutable slice of field elements as a mutable slice of their canonical values;
+/// since all operations of this module return canonical values, the elements remain valid.
+#[inline(always)]
+fn as_words_mut<F: SimdField>(elements: &mut [F]) -> &mut [u32] {
+    // this is safe because SimdField guarantees that F has the same layout as u32
+    unsafe { slice::from_raw_parts_mut(elements.as_mut_ptr() as *mut u32, elements.len()) }
+}
+
+/// Converts a constant multiplier into the form expected by `mul_by_const()` functions of vector
+/// kernels: for Montgomery reduction this is c * 2^32 mod M, so that a single reduction of the
+/// product yields the result; otherwise, the constant is returned unchanged.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+#[inline(always)]
+fn prepare_const<F: SimdField>(c: u32) -> u32 {
+    match F::REDUCTION {
+        Reduction::Mersenne => c,
+        Reduction::Montgomery { .. } => (((c as u64) << 32) % F::M as u64) as u32,
+    }
+}
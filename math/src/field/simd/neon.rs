@@ -0,0 +1,140 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! NEON kernels which process 4 field elements per instruction.
+//!
+//! 64-bit products are computed separately for the low and the high halves of a register, and
+//! their reduced 32-bit words are recombined via narrowing instructions.
+
+use super::{prepare_const, Reduction, SimdField};
+use core::arch::aarch64::*;
+
+/// Number of field elements in a single NEON register.
+const LANES: usize = 4;
+
+// KERNELS
+// ================================================================================================
+
+/// Computes a[i] + b[i] for all full registers of the inputs, and returns the number of
+/// processed elements.
+pub unsafe fn add_assign<F: SimdField>(a: &mut [u32], b: &[u32]) -> usize {
+    let m = vdupq_n_u32(F::M);
+    let mut n = 0;
+    for (a, b) in a.chunks_exact_mut(LANES).zip(b.chunks_exact(LANES)) {
+        let x = vld1q_u32(a.as_ptr());
+        let y = vld1q_u32(b.as_ptr());
+        vst1q_u32(a.as_mut_ptr(), add(x, y, m));
+        n += LANES;
+    }
+    n
+}
+
+/// Computes a[i] * b[i] for all full registers of the inputs, and returns the number of
+/// processed elements.
+pub unsafe fn mul_assign<F: SimdField>(a: &mut [u32], b: &[u32]) -> usize {
+    let m = vdupq_n_u32(F::M);
+    let mut n = 0;
+    for (a, b) in a.chunks_exact_mut(LANES).zip(b.chunks_exact(LANES)) {
+        let x = vld1q_u32(a.as_ptr());
+        let y = vld1q_u32(b.as_ptr());
+        vst1q_u32(a.as_mut_ptr(), mul::<F>(x, y, m));
+        n += LANES;
+    }
+    n
+}
+
+/// Computes FFT butterflies for all full registers of the inputs, and returns the number of
+/// processed elements.
+pub unsafe fn butterfly<F: SimdField>(lo: &mut [u32], hi: &mut [u32], twiddle: u32) -> usize {
+    let m = vdupq_n_u32(F::M);
+    let c = vdupq_n_u32(prepare_const::<F>(twiddle));
+    let mut n = 0;
+    for (lo, hi) in lo.chunks_exact_mut(LANES).zip(hi.chunks_exact_mut(LANES)) {
+        let x = vld1q_u32(lo.as_ptr());
+        let y = mul_by_const::<F>(vld1q_u32(hi.as_ptr()), c, m);
+        vst1q_u32(lo.as_mut_ptr(), add(x, y, m));
+        vst1q_u32(hi.as_mut_ptr(), sub(x, y, m));
+        n += LANES;
+    }
+    n
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Computes (x + y) mod M in every lane.
+#[inline(always)]
+unsafe fn add(x: uint32x4_t, y: uint32x4_t, m: uint32x4_t) -> uint32x4_t {
+    let z = vaddq_u32(x, y);
+    vminq_u32(z, vsubq_u32(z, m))
+}
+
+/// Computes (x - y) mod M in every lane.
+#[inline(always)]
+unsafe fn sub(x: uint32x4_t, y: uint32x4_t, m: uint32x4_t) -> uint32x4_t {
+    let z = vsubq_u32(x, y);
+    vminq_u32(z, vaddq_u32(z, m))
+}
+
+/// Computes (x * y) mod M in every lane.
+#[inline(always)]
+unsafe fn mul<F: SimdField>(x: uint32x4_t, y: uint32x4_t, m: uint32x4_t) -> uint32x4_t {
+    match F::REDUCTION {
+        Reduction::Mersenne => mersenne_mul(x, y, m),
+        Reduction::Montgomery { u, r2 } => {
+            let u = vdupq_n_u32(u);
+            let z = montgomery_mul(x, y, u, m);
+            montgomery_mul(z, vdupq_n_u32(r2), u, m)
+        }
+    }
+}
+
+/// Computes (x * c) mod M in every lane, where c was prepared via `prepare_const()`.
+#[inline(always)]
+unsafe fn mul_by_const<F: SimdField>(x: uint32x4_t, c: uint32x4_t, m: uint32x4_t) -> uint32x4_t {
+    match F::REDUCTION {
+        Reduction::Mersenne => mersenne_mul(x, c, m),
+        Reduction::Montgomery { u, .. } => montgomery_mul(x, c, vdupq_n_u32(u), m),
+    }
+}
+
+/// Computes 64-bit products of the low halves and the high halves of x and y.
+#[inline(always)]
+unsafe fn wide_mul(x: uint32x4_t, y: uint32x4_t) -> (uint64x2_t, uint64x2_t) {
+    let lo = vmull_u32(vget_low_u32(x), vget_low_u32(y));
+    let hi = vmull_high_u32(x, y);
+    (lo, hi)
+}
+
+/// Computes (x * y) mod 2^31 - 1 in every lane; this mirrors scalar multiplication in the
+/// Mersenne-31 field.
+#[inline(always)]
+unsafe fn mersenne_mul(x: uint32x4_t, y: uint32x4_t, m: uint32x4_t) -> uint32x4_t {
+    let (p_lo, p_hi) = wide_mul(x, y);
+
+    // low 32 bits and bits above the 31st bit of every product
+    let lo = vcombine_u32(vmovn_u64(p_lo), vmovn_u64(p_hi));
+    let hi = vcombine_u32(vshrn_n_u64::<31>(p_lo), vshrn_n_u64::<31>(p_hi));
+
+    let z = vaddq_u32(vandq_u32(lo, m), hi);
+    vminq_u32(z, vsubq_u32(z, m))
+}
+
+/// Computes (x * y) / 2^32 mod M in every lane; this mirrors a multiplication followed by a
+/// scalar Montgomery reduction.
+#[inline(always)]
+unsafe fn montgomery_mul(x: uint32x4_t, y: uint32x4_t, u: uint32x4_t, m: uint32x4_t) -> uint32x4_t {
+    let (p_lo, p_hi) = wide_mul(x, y);
+
+    // t = (p mod 2^32) * U mod 2^32 is chosen such that p - t * M is divisible by 2^32
+    let t = vmulq_u32(vcombine_u32(vmovn_u64(p_lo), vmovn_u64(p_hi)), u);
+    let (q_lo, q_hi) = wide_mul(t, m);
+
+    // low words of p and t * M are equal, and thus (p - t * M) / 2^32 is the difference of their
+    // high words; both high words are smaller than M
+    let p = vcombine_u32(vshrn_n_u64::<32>(p_lo), vshrn_n_u64::<32>(p_hi));
+    let q = vcombine_u32(vshrn_n_u64::<32>(q_lo), vshrn_n_u64::<32>(q_hi));
+    sub(p, q, m)
+}
@@ -0,0 +1,102 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{add_assign, butterfly, mul_assign, SimdField};
+use crate::field::{f31, m31, StarkField};
+
+/// Number of elements in test vectors; this is not a multiple of the number of lanes so that
+/// both vector and scalar code paths are exercised.
+const NUM_VALUES: usize = 67;
+
+// BATCH OPERATIONS
+// ================================================================================================
+
+#[test]
+fn batch_add() {
+    check_add::<f31::BaseElement>();
+    check_add::<m31::BaseElement>();
+}
+
+#[test]
+fn batch_mul() {
+    check_mul::<f31::BaseElement>();
+    check_mul::<m31::BaseElement>();
+}
+
+#[test]
+fn batch_butterfly() {
+    check_butterfly::<f31::BaseElement>();
+    check_butterfly::<m31::BaseElement>();
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn check_add<E: StarkField + SimdField>() {
+    let a = build_values::<E>(1);
+    let b = build_values::<E>(2);
+
+    let mut result = a.clone();
+    add_assign(&mut result, &b);
+    let expected = a.iter().zip(&b).map(|(&a, &b)| a + b).collect::<Vec<_>>();
+    assert_eq!(expected, result);
+
+    // the same values added to themselves in reverse order
+    let mut result = a.clone();
+    let b = a.iter().rev().copied().collect::<Vec<_>>();
+    add_assign(&mut result, &b);
+    let expected = a.iter().zip(&b).map(|(&a, &b)| a + b).collect::<Vec<_>>();
+    assert_eq!(expected, result);
+}
+
+fn check_mul<E: StarkField + SimdField>() {
+    let a = build_values::<E>(3);
+    let b = build_values::<E>(4);
+
+    let mut result = a.clone();
+    mul_assign(&mut result, &b);
+    let expected = a.iter().zip(&b).map(|(&a, &b)| a * b).collect::<Vec<_>>();
+    assert_eq!(expected, result);
+
+    let mut result = a.clone();
+    let b = a.iter().rev().copied().collect::<Vec<_>>();
+    mul_assign(&mut result, &b);
+    let expected = a.iter().zip(&b).map(|(&a, &b)| a * b).collect::<Vec<_>>();
+    assert_eq!(expected, result);
+}
+
+fn check_butterfly<E: StarkField + SimdField>() {
+    let lo = build_values::<E>(5);
+    let hi = build_values::<E>(6);
+
+    for &twiddle in build_values::<E>(7).iter().take(8) {
+        let mut result_lo = lo.clone();
+        let mut result_hi = hi.clone();
+        butterfly(&mut result_lo, &mut result_hi, twiddle);
+
+        for i in 0..NUM_VALUES {
+            assert_eq!(lo[i] + hi[i] * twiddle, result_lo[i]);
+            assert_eq!(lo[i] - hi[i] * twiddle, result_hi[i]);
+        }
+    }
+}
+
+/// Builds a vector of pseudo-random values which starts with the largest field elements and
+/// small values, for which reductions are most likely to be incorrect.
+fn build_values<E: StarkField>(seed: u8) -> Vec<E> {
+    let mut values = E::prng_vector([seed; 32], NUM_VALUES);
+    let m_minus_one = E::ZERO - E::ONE;
+    let edge_values = [
+        m_minus_one,
+        E::ZERO,
+        E::ONE,
+        m_minus_one - E::ONE,
+        E::from(2u8),
+        m_minus_one,
+    ];
+    let offset = seed as usize % 2;
+    values[offset..offset + edge_values.len()].copy_from_slice(&edge_values);
+    values
+}
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::errors::SerializationError;
+use crate::errors::{RootOfUnityError, SerializationError};
 use core::{
     convert::TryFrom,
     fmt::{Debug, Display},
@@ -12,13 +12,16 @@ use core::{
         SubAssign,
     },
 };
-use utils::AsBytes;
+use rand::Rng;
+use utils::{AsBytes, Deserializable, Serializable};
 
 // FIELD ELEMENT
 // ================================================================================================
 
 pub trait FieldElement:
     AsBytes
+    + Serializable
+    + Deserializable
     + Copy
     + Clone
     + Debug
@@ -121,8 +124,18 @@ pub trait FieldElement:
     // RANDOMNESS
     // --------------------------------------------------------------------------------------------
 
-    /// Returns a cryptographically-secure random element drawn uniformly from the entire field.
-    fn rand() -> Self;
+    /// Returns a random element drawn uniformly from the entire field using the provided random
+    /// number generator.
+    fn rand_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self;
+
+    /// Returns a cryptographically-secure random element drawn uniformly from the entire field
+    /// using the thread-local random number generator seeded by the operating system.
+    ///
+    /// This function is available only when `std-rand` feature is enabled.
+    #[cfg(feature = "std-rand")]
+    fn rand() -> Self {
+        Self::rand_with_rng(&mut rand::thread_rng())
+    }
 
     /// Returns a field element if the set of bytes forms a valid field element, otherwise returns
     /// None. The element is expected to be in canonical representation. This function is primarily
@@ -170,12 +183,59 @@ pub trait FieldElement:
     /// Returns a vector of n pseudo-random elements drawn uniformly from the entire
     /// field based on the provided seed.
     fn prng_vector(seed: [u8; 32], n: usize) -> Vec<Self>;
+
+    // BATCH OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes a[i] + b[i] for all i and stores the results in a; specialized implementations
+    /// of this function may process several elements per instruction.
+    ///
+    /// Both slices are expected to have the same length.
+    fn batch_add(a: &mut [Self], b: &[Self]) {
+        debug_assert_eq!(
+            a.len(),
+            b.len(),
+            "number of values must be the same for both operands"
+        );
+        a.iter_mut().zip(b).for_each(|(a, &b)| *a += b);
+    }
+
+    /// Computes a[i] * b[i] for all i and stores the results in a; specialized implementations
+    /// of this function may process several elements per instruction.
+    ///
+    /// Both slices are expected to have the same length.
+    fn batch_mul(a: &mut [Self], b: &[Self]) {
+        debug_assert_eq!(
+            a.len(),
+            b.len(),
+            "number of values must be the same for both operands"
+        );
+        a.iter_mut().zip(b).for_each(|(a, &b)| *a *= b);
+    }
+
+    /// Computes FFT butterflies lo[i] + hi[i] * twiddle and lo[i] - hi[i] * twiddle for all i,
+    /// and stores the results in lo and hi respectively; specialized implementations of this
+    /// function may process several elements per instruction.
+    ///
+    /// Both slices are expected to have the same length.
+    fn batch_butterfly(lo: &mut [Self], hi: &mut [Self], twiddle: Self) {
+        debug_assert_eq!(
+            lo.len(),
+            hi.len(),
+            "number of values must be the same for both halves"
+        );
+        for (lo, hi) in lo.iter_mut().zip(hi.iter_mut()) {
+            let t = *hi * twiddle;
+            *hi = *lo - t;
+            *lo += t;
+        }
+    }
 }
 
 // STARK FIELD
 // ================================================================================================
 
-pub trait StarkField: FieldElement + AsBytes {
+pub trait StarkField: FieldElement + AsBytes + 'static {
     /// Prime modulus of the field. Must be of the form k * 2^n + 1 (a Proth prime).
     /// This ensures that the field has high 2-adicity.
     const MODULUS: Self::PositiveInteger;
@@ -193,17 +253,40 @@ pub trait StarkField: FieldElement + AsBytes {
     /// computed as Self::GENERATOR^k.
     const TWO_ADIC_ROOT_OF_UNITY: Self;
 
-    /// Returns the root of unity of order 2^n. Panics if the root of unity for
-    /// the specified order does not exist in this field.
-    fn get_root_of_unity(n: u32) -> Self {
-        assert!(n != 0, "cannot get root of unity for n = 0");
-        assert!(
-            n <= Self::TWO_ADICITY,
-            "order cannot exceed 2^{}",
-            Self::TWO_ADICITY
-        );
+    /// Constant term k of the polynomial x^2 - x - k which defines quadratic extensions of this
+    /// field; the polynomial must be irreducible over the field. Defaults to one.
+    const QUAD_EXTENSION_TERM: Self = Self::ONE;
+
+    /// Constant term k of the polynomial x^3 - x - k which defines cubic extensions of this
+    /// field; the polynomial must be irreducible over the field. Defaults to one.
+    const CUBE_EXTENSION_TERM: Self = Self::ONE;
+
+    /// Constant term k of the polynomial x^4 - k which defines quartic extensions of this
+    /// field; k must be a quadratic non-residue for the polynomial to be irreducible over the
+    /// field (given that MODULUS = 1 mod 4). Defaults to the field generator which is never a
+    /// quadratic residue.
+    const QUART_EXTENSION_TERM: Self = Self::GENERATOR;
+
+    /// Returns the largest n such that a root of unity of order 2^n exists in this field;
+    /// this is the same as Self::TWO_ADICITY.
+    fn max_two_adicity() -> u32 {
+        Self::TWO_ADICITY
+    }
+
+    /// Returns the root of unity of order 2^n.
+    ///
+    /// # Errors
+    /// Returns an error if `n` is zero, or if `n` is greater than the two-adicity of this field
+    /// (i.e., the root of unity for the specified order does not exist in this field).
+    fn get_root_of_unity(n: u32) -> Result<Self, RootOfUnityError> {
+        if n == 0 {
+            return Err(RootOfUnityError::ZeroOrder);
+        }
+        if n > Self::TWO_ADICITY {
+            return Err(RootOfUnityError::OrderTooLarge(n, Self::TWO_ADICITY));
+        }
         let power = Self::PositiveInteger::from(1u32) << (Self::TWO_ADICITY - n);
-        Self::TWO_ADIC_ROOT_OF_UNITY.exp(power)
+        Ok(Self::TWO_ADIC_ROOT_OF_UNITY.exp(power))
     }
 
     /// Returns byte representation of the field modulus in little-endian byte order.
@@ -212,3 +295,21 @@ pub trait StarkField: FieldElement + AsBytes {
     /// Returns a canonical integer representation of the field element.
     fn as_int(&self) -> Self::PositiveInteger;
 }
+
+// EXTENSION FIELD
+// ================================================================================================
+
+/// Defines a field which extends the base field `B`; every base field is also considered to be
+/// an extension of itself of degree one.
+///
+/// Code which works in an extension field (e.g., the prover and the verifier) should be generic
+/// over this trait rather than over specific extensions, so that the same code supports
+/// extensions of any degree.
+pub trait ExtensionOf<B: StarkField>: FieldElement + From<B> + 'static {
+    /// Degree of this field over the base field.
+    const DEGREE: usize;
+}
+
+impl<B: StarkField> ExtensionOf<B> for B {
+    const DEGREE: usize = 1;
+}
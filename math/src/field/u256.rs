@@ -0,0 +1,181 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    ops::{BitAnd, Shl, Shr, ShrAssign},
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Largest power of 10 which fits into 64 bits; used when formatting values in decimal.
+const DECIMAL_BASE: u128 = 10_000_000_000_000_000_000;
+
+// 256-BIT INTEGER
+// ================================================================================================
+
+/// A 256-bit unsigned integer stored as four 64-bit limbs in little-endian order.
+///
+/// This type implements only the operations needed to use it as an integer representation of
+/// elements of 256-bit fields (e.g., as an exponent), and is not intended for general arithmetic.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct U256(pub [u64; 4]);
+
+impl U256 {
+    /// Returns the value encoded in the provided bytes in little-endian byte order.
+    pub fn from_le_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+            let mut limb_bytes = [0u8; 8];
+            limb_bytes.copy_from_slice(chunk);
+            *limb = u64::from_le_bytes(limb_bytes);
+        }
+        U256(limbs)
+    }
+
+    /// Returns the little-endian byte representation of this value.
+    pub fn to_le_bytes(self) -> [u8; 32] {
+        let mut result = [0u8; 32];
+        for (chunk, limb) in result.chunks_mut(8).zip(self.0.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        result
+    }
+
+    /// Returns the number of bits needed to represent this value.
+    pub fn bits(&self) -> u32 {
+        for (i, &limb) in self.0.iter().enumerate().rev() {
+            if limb != 0 {
+                return i as u32 * 64 + 64 - limb.leading_zeros();
+            }
+        }
+        0
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // limbs are compared starting with the most significant one
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl Display for U256 {
+    /// Formats the value in decimal.
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        // split the value into base 10^19 digits by repeated long division
+        let mut limbs = self.0;
+        let mut digits = Vec::new();
+        loop {
+            let mut remainder = 0u128;
+            for limb in limbs.iter_mut().rev() {
+                let value = (remainder << 64) | *limb as u128;
+                *limb = (value / DECIMAL_BASE) as u64;
+                remainder = value % DECIMAL_BASE;
+            }
+            digits.push(remainder as u64);
+            if limbs == [0; 4] {
+                break;
+            }
+        }
+
+        let mut digits = digits.iter().rev();
+        write!(f, "{}", digits.next().unwrap())?;
+        for digit in digits {
+            write!(f, "{:019}", digit)?;
+        }
+        Ok(())
+    }
+}
+
+// OPERATORS
+// ================================================================================================
+
+impl Shl<u32> for U256 {
+    type Output = Self;
+
+    fn shl(self, rhs: u32) -> Self {
+        let limb_shift = (rhs / 64) as usize;
+        let bit_shift = rhs % 64;
+        let mut result = [0u64; 4];
+        for (i, limb) in result.iter_mut().enumerate().skip(limb_shift) {
+            *limb = self.0[i - limb_shift] << bit_shift;
+            if bit_shift > 0 && i > limb_shift {
+                *limb |= self.0[i - limb_shift - 1] >> (64 - bit_shift);
+            }
+        }
+        U256(result)
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = Self;
+
+    fn shr(self, rhs: u32) -> Self {
+        let limb_shift = (rhs / 64) as usize;
+        let bit_shift = rhs % 64;
+        let mut result = [0u64; 4];
+        let num_limbs = 4usize.saturating_sub(limb_shift);
+        for (i, limb) in result.iter_mut().enumerate().take(num_limbs) {
+            *limb = self.0[i + limb_shift] >> bit_shift;
+            if bit_shift > 0 && i + limb_shift < 3 {
+                *limb |= self.0[i + limb_shift + 1] << (64 - bit_shift);
+            }
+        }
+        U256(result)
+    }
+}
+
+impl ShrAssign for U256 {
+    fn shr_assign(&mut self, rhs: Self) {
+        *self = if rhs < U256::from(256u32) {
+            *self >> rhs.0[0] as u32
+        } else {
+            U256::default()
+        };
+    }
+}
+
+impl BitAnd for U256 {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        U256([
+            self.0[0] & rhs.0[0],
+            self.0[1] & rhs.0[1],
+            self.0[2] & rhs.0[2],
+            self.0[3] & rhs.0[3],
+        ])
+    }
+}
+
+// TYPE CONVERSIONS
+// ================================================================================================
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        U256([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        U256([value, 0, 0, 0])
+    }
+}
+
+impl From<u32> for U256 {
+    fn from(value: u32) -> Self {
+        U256([value as u64, 0, 0, 0])
+    }
+}
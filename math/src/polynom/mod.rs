@@ -3,12 +3,24 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{field::FieldElement, utils as crate_utils};
+use crate::{
+    field::{FieldElement, StarkField},
+    utils as crate_utils,
+};
 use std::mem;
 
+mod subproduct;
+
 #[cfg(test)]
 mod tests;
 
+// CONSTANTS
+// ================================================================================================
+
+/// Polynomials are evaluated via subproduct trees only when both the number of coefficients and
+/// the number of coordinates are at least this large.
+const MIN_SUBPRODUCT_TREE_SIZE: usize = 1024;
+
 // POLYNOMIAL EVALUATION
 // ================================================================================================
 
@@ -25,12 +37,82 @@ where
 }
 
 /// Evaluates polynomial `p` at all coordinates in `xs` slice.
+///
+/// When both the polynomial and the number of coordinates are large, the evaluation uses a
+/// subproduct tree, and requires O(n log^2 n) operations where n is the larger of the two; the
+/// tree uses FFT-based multiplication only when the base field of `E` has a large enough
+/// multiplicative subgroup. Otherwise, the polynomial is evaluated at every coordinate
+/// separately using Horner's method.
 pub fn eval_many<B, E>(p: &[B], xs: &[E]) -> Vec<E>
 where
     B: FieldElement,
+    E: FieldElement + From<B> + From<E::Base>,
+{
+    if p.len() < MIN_SUBPRODUCT_TREE_SIZE || xs.len() < MIN_SUBPRODUCT_TREE_SIZE {
+        return xs.iter().map(|x| eval(p, *x)).collect();
+    }
+    let p = p.iter().map(|&c| E::from(c)).collect::<Vec<_>>();
+    subproduct::eval_many(&p, xs)
+}
+
+/// Evaluates a polynomial at coordinate `x` directly from its `evaluations` over a coset of a
+/// multiplicative subgroup, without interpolating the polynomial first.
+///
+/// The evaluations must be in natural order, i.e., evaluations[i] must be the value of the
+/// polynomial at domain_offset * g^i, where g is the generator of the subgroup of size
+/// evaluations.len(); the polynomial is assumed to have degree smaller than the size of the
+/// subgroup. The evaluation uses the barycentric formula:
+///
+/// p(x) = (x^n - s^n) / (n * s^n) * sum(y_i * x_i / (x - x_i))
+///
+/// where s is the domain offset and x_i = s * g^i. This requires O(n) operations, as opposed to
+/// O(n log n) operations needed to interpolate the polynomial via FFT.
+///
+/// # Panics
+/// Panics if:
+/// * Number of evaluations is not a power of two.
+/// * Number of evaluations is greater than the largest subgroup of field `B`.
+/// * `domain_offset` is zero.
+pub fn eval_barycentric<B, E>(evaluations: &[E], domain_offset: B, x: E) -> E
+where
+    B: StarkField,
     E: FieldElement + From<B>,
 {
-    xs.iter().map(|x| eval(p, *x)).collect()
+    let n = evaluations.len();
+    assert!(
+        n.is_power_of_two(),
+        "number of evaluations must be a power of 2"
+    );
+    assert!(domain_offset != B::ZERO, "domain offset cannot be zero");
+    if n == 1 {
+        return evaluations[0];
+    }
+
+    let g = B::get_root_of_unity(crate_utils::log2_unchecked(n))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", n, err));
+    let domain = crate_utils::get_power_series_with_offset_unchecked(g, domain_offset, n);
+
+    // if x is in the domain, the evaluation is already known; otherwise, none of the differences
+    // x - x_i is zero, and they can be inverted in a single batch
+    let differences = domain
+        .iter()
+        .map(|&x_i| x - E::from(x_i))
+        .collect::<Vec<_>>();
+    if let Some(i) = differences.iter().position(|&d| d == E::ZERO) {
+        return evaluations[i];
+    }
+    let inv_differences = crate_utils::batch_inversion(&differences);
+
+    let sum = evaluations
+        .iter()
+        .zip(domain.iter().zip(inv_differences))
+        .fold(E::ZERO, |acc, (&y_i, (&x_i, inv_d))| {
+            acc + y_i * E::from(x_i) * inv_d
+        });
+
+    let offset_n = domain_offset.exp((n as u32).into());
+    let scale = (E::from(offset_n) * E::from(n as u32)).inv();
+    (x.exp((n as u32).into()) - E::from(offset_n)) * scale * sum
 }
 
 // POLYNOMIAL INTERPOLATION
@@ -0,0 +1,185 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Multi-point polynomial evaluation via subproduct trees.
+//!
+//! For points x_0, ..., x_{n - 1}, the leaves of a subproduct tree are polynomials (x - x_i),
+//! and every internal node is the product of its children; the root is thus the product of all
+//! (x - x_i). A polynomial is evaluated by reducing it modulo the root, and then reducing the
+//! remainders modulo children of every node down to the leaves; the remainder at the leaf for
+//! x_i is the value of the polynomial at x_i.
+//!
+//! With FFT-based multiplication and Newton-iteration-based division, building the tree and
+//! computing all remainders requires O(n log^2 n) operations.
+
+use crate::{
+    fft,
+    field::{FieldElement, StarkField},
+    utils,
+};
+
+/// Products with fewer coefficients than this are computed via schoolbook multiplication.
+const MIN_FFT_MUL_SIZE: usize = 64;
+
+/// Remainders with fewer quotient coefficients than this are computed via long division.
+const MIN_FAST_DIV_SIZE: usize = 64;
+
+// MULTI-POINT EVALUATION
+// ================================================================================================
+
+/// Evaluates polynomial `p` at all points in `xs` using a subproduct tree.
+pub fn eval_many<E>(p: &[E], xs: &[E]) -> Vec<E>
+where
+    E: FieldElement + From<E::Base>,
+{
+    let tree = build_tree(xs);
+
+    // reduce the polynomial modulo the root, and then modulo every node of the tree going down;
+    // when a level has an odd number of nodes, the last node is carried to the parent level
+    // as is, and thus its remainder is carried down unchanged
+    let root = &tree[tree.len() - 1][0];
+    let mut remainders = vec![rem(p, root)];
+    for level in tree.iter().rev().skip(1) {
+        remainders = level
+            .iter()
+            .enumerate()
+            .map(|(i, node)| rem(&remainders[i / 2], node))
+            .collect();
+    }
+
+    remainders
+        .into_iter()
+        .map(|r| r.first().copied().unwrap_or(E::ZERO))
+        .collect()
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds a subproduct tree for points `xs`; the first level contains leaves of the tree, and
+/// the last level contains the root.
+fn build_tree<E>(xs: &[E]) -> Vec<Vec<Vec<E>>>
+where
+    E: FieldElement + From<E::Base>,
+{
+    let leaves = xs.iter().map(|&x| vec![-x, E::ONE]).collect::<Vec<_>>();
+    let mut tree = vec![leaves];
+    while tree[tree.len() - 1].len() > 1 {
+        let level = tree[tree.len() - 1]
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => mul(a, b),
+                [a] => a.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+        tree.push(level);
+    }
+    tree
+}
+
+/// Multiplies polynomial `a` by polynomial `b`; large products are computed via FFT when the
+/// base field contains a subgroup of sufficient size.
+fn mul<E>(a: &[E], b: &[E]) -> Vec<E>
+where
+    E: FieldElement + From<E::Base>,
+{
+    let result_len = a.len() + b.len() - 1;
+    let domain_size = result_len.next_power_of_two();
+    if a.len().min(b.len()) < MIN_FFT_MUL_SIZE
+        || utils::log2_unchecked(domain_size) > E::Base::TWO_ADICITY
+    {
+        return super::mul(a, b);
+    }
+
+    let mut a_evaluations = a.to_vec();
+    a_evaluations.resize(domain_size, E::ZERO);
+    let mut b_evaluations = b.to_vec();
+    b_evaluations.resize(domain_size, E::ZERO);
+
+    let twiddles = fft::get_twiddles::<E::Base>(domain_size);
+    fft::evaluate_poly(&mut a_evaluations, &twiddles);
+    fft::evaluate_poly(&mut b_evaluations, &twiddles);
+    for (a, b) in a_evaluations.iter_mut().zip(b_evaluations) {
+        *a *= b;
+    }
+
+    let inv_twiddles = fft::get_inv_twiddles::<E::Base>(domain_size);
+    fft::interpolate_poly(&mut a_evaluations, &inv_twiddles);
+    a_evaluations.truncate(result_len);
+    a_evaluations
+}
+
+/// Computes the remainder of division of polynomial `a` by monic polynomial `m`.
+fn rem<E>(a: &[E], m: &[E]) -> Vec<E>
+where
+    E: FieldElement + From<E::Base>,
+{
+    debug_assert_eq!(Some(&E::ONE), m.last(), "divisor must be monic");
+    let m_degree = m.len() - 1;
+    if a.len() <= m_degree {
+        return a.to_vec();
+    }
+
+    let q_len = a.len() - m_degree;
+    if q_len < MIN_FAST_DIV_SIZE || m_degree < MIN_FAST_DIV_SIZE {
+        return long_rem(a, m);
+    }
+
+    // the reversed quotient is the product of the reversed dividend and the inverse of the
+    // reversed divisor modulo x^q_len
+    let a_rev = a.iter().rev().take(q_len).copied().collect::<Vec<_>>();
+    let m_rev = m.iter().rev().copied().collect::<Vec<_>>();
+    let mut q = mul(&a_rev, &inv_series(&m_rev, q_len));
+    q.truncate(q_len);
+    q.reverse();
+
+    let qm = mul(&q, m);
+    a.iter()
+        .zip(qm)
+        .take(m_degree)
+        .map(|(&a, qm)| a - qm)
+        .collect()
+}
+
+/// Computes the remainder of division of polynomial `a` by monic polynomial `m` via long
+/// division.
+fn long_rem<E: FieldElement>(a: &[E], m: &[E]) -> Vec<E> {
+    let m_degree = m.len() - 1;
+    let mut result = a.to_vec();
+    for i in (m_degree..result.len()).rev() {
+        let quot = result[i];
+        if quot != E::ZERO {
+            for (j, &coeff) in m[..m_degree].iter().enumerate() {
+                result[i - m_degree + j] -= coeff * quot;
+            }
+        }
+    }
+    result.truncate(m_degree);
+    result
+}
+
+/// Computes the inverse of the power series `f` modulo x^n via Newton iteration; `f` must have
+/// constant term equal to one.
+fn inv_series<E>(f: &[E], n: usize) -> Vec<E>
+where
+    E: FieldElement + From<E::Base>,
+{
+    debug_assert_eq!(E::ONE, f[0], "constant term must be one");
+    let mut g = vec![E::ONE];
+    while g.len() < n {
+        // g' = g * (2 - f * g) mod x^len
+        let len = (2 * g.len()).min(n);
+        let mut e = mul(&f[..f.len().min(len)], &g);
+        e.truncate(len);
+        for value in e.iter_mut() {
+            *value = -*value;
+        }
+        e[0] += E::ONE.double();
+        g = mul(&g, &e);
+        g.truncate(len);
+    }
+    g
+}
@@ -4,8 +4,8 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{
-    field::{f128::BaseElement, FieldElement, StarkField},
-    utils::{get_power_series, log2, remove_leading_zeros},
+    field::{f128::BaseElement, m31, FieldElement, QuadExtension, StarkField},
+    utils::{get_power_series_unchecked, log2_unchecked, remove_leading_zeros},
 };
 
 #[test]
@@ -41,6 +41,95 @@ fn eval() {
     );
 }
 
+#[test]
+fn eval_many() {
+    let poly = BaseElement::prng_vector([1; 32], 10);
+    let xs = BaseElement::prng_vector([2; 32], 300);
+    let expected = xs
+        .iter()
+        .map(|&x| super::eval(&poly, x))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, super::eval_many(&poly, &xs));
+}
+
+#[test]
+fn eval_many_subproduct_tree() {
+    // the tree uses fast division when the polynomial is longer than the number of coordinates
+    for &(num_coeffs, num_xs) in [(300, 200), (1000, 130), (130, 700), (1, 3)].iter() {
+        let poly = BaseElement::prng_vector([1; 32], num_coeffs);
+        let xs = BaseElement::prng_vector([2; 32], num_xs);
+        let expected = xs
+            .iter()
+            .map(|&x| super::eval(&poly, x))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, super::subproduct::eval_many(&poly, &xs));
+    }
+
+    // coordinates in an extension field
+    let poly = BaseElement::prng_vector([3; 32], 256)
+        .into_iter()
+        .map(QuadExtension::from)
+        .collect::<Vec<_>>();
+    let xs = QuadExtension::<BaseElement>::prng_vector([4; 32], 256);
+    let expected = xs
+        .iter()
+        .map(|&x| super::eval(&poly, x))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, super::subproduct::eval_many(&poly, &xs));
+
+    // the tree falls back onto schoolbook multiplication in fields with small subgroups
+    let poly = m31::BaseElement::prng_vector([5; 32], 200);
+    let xs = m31::BaseElement::prng_vector([6; 32], 200);
+    let expected = xs
+        .iter()
+        .map(|&x| super::eval(&poly, x))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, super::subproduct::eval_many(&poly, &xs));
+}
+
+#[test]
+fn eval_barycentric() {
+    let n = 16;
+    let poly = BaseElement::prng_vector([1; 32], n);
+    let offset = BaseElement::GENERATOR;
+    let g = BaseElement::get_root_of_unity(log2_unchecked(n)).unwrap();
+    let evaluations = (0..n)
+        .map(|i| super::eval(&poly, offset * g.exp(i as u128)))
+        .collect::<Vec<_>>();
+
+    // points outside of the domain
+    for &x in BaseElement::prng_vector([2; 32], 4).iter() {
+        assert_eq!(
+            super::eval(&poly, x),
+            super::eval_barycentric(&evaluations, offset, x)
+        );
+    }
+
+    // points in the domain
+    assert_eq!(
+        evaluations[3],
+        super::eval_barycentric(&evaluations, offset, offset * g.exp(3))
+    );
+
+    // points in an extension field
+    let x = QuadExtension::<BaseElement>::prng_vector([3; 32], 1)[0];
+    let evaluations = evaluations
+        .iter()
+        .map(|&y| QuadExtension::from(y))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        super::eval(&poly, x),
+        super::eval_barycentric(&evaluations, offset, x)
+    );
+
+    // a single evaluation defines a constant polynomial
+    let x = BaseElement::from(5u8);
+    assert_eq!(
+        poly[0],
+        super::eval_barycentric(&poly[..1], BaseElement::ONE, x)
+    );
+}
+
 #[test]
 fn add() {
     let poly1: [BaseElement; 3] = [
@@ -225,8 +314,8 @@ fn syn_div() {
         .collect();
 
     // build the domain
-    let root = BaseElement::get_root_of_unity(log2(ys.len()));
-    let domain = get_power_series(root, ys.len());
+    let root = BaseElement::get_root_of_unity(log2_unchecked(ys.len())).unwrap();
+    let domain = get_power_series_unchecked(root, ys.len());
 
     // build the polynomial
     let poly = super::interpolate(&domain, &ys, false);
@@ -275,8 +364,8 @@ pub fn syn_div_in_place_with_exception() {
         .collect();
 
     // build the domain
-    let root = BaseElement::get_root_of_unity(log2(ys.len()));
-    let domain = get_power_series(root, ys.len());
+    let root = BaseElement::get_root_of_unity(log2_unchecked(ys.len())).unwrap();
+    let domain = get_power_series_unchecked(root, ys.len());
 
     // build the polynomial
     let poly = super::interpolate(&domain, &ys, false);
@@ -3,7 +3,10 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{errors::SerializationError, field::FieldElement};
+use crate::{
+    errors::{SerializationError, UtilsError},
+    field::FieldElement,
+};
 use utils::uninit_vector;
 
 #[cfg(feature = "concurrent")]
@@ -18,7 +21,23 @@ mod tests;
 /// Generates a vector with values [1, b, b^2, b^3, b^4, ..., b^(n-1)].
 /// When `concurrent` feature is enabled, series generation is done concurrently in multiple
 /// threads.
-pub fn get_power_series<E: FieldElement>(b: E, n: usize) -> Vec<E> {
+///
+/// Returns an error if `n` is zero.
+pub fn get_power_series<E: FieldElement>(b: E, n: usize) -> Result<Vec<E>, UtilsError> {
+    if n == 0 {
+        return Err(UtilsError::EmptyPowerSeries);
+    }
+    Ok(get_power_series_unchecked(b, n))
+}
+
+/// Generates a vector with values [1, b, b^2, b^3, b^4, ..., b^(n-1)].
+/// When `concurrent` feature is enabled, series generation is done concurrently in multiple
+/// threads.
+///
+/// # Panics
+/// Panics if `n` is zero.
+pub fn get_power_series_unchecked<E: FieldElement>(b: E, n: usize) -> Vec<E> {
+    assert!(n > 0, "power series must contain at least one element");
     const MIN_CONCURRENT_SIZE: usize = 1024;
     let mut result = uninit_vector(n);
     if cfg!(feature = "concurrent") && n >= MIN_CONCURRENT_SIZE && n.is_power_of_two() {
@@ -42,7 +61,27 @@ pub fn get_power_series<E: FieldElement>(b: E, n: usize) -> Vec<E> {
 /// Generates a vector with values [s, s * b, s * b^2, s * b^3, s * b^4, ..., s * b^(n-1)].
 /// When `concurrent` feature is enabled, series generation is done concurrently in multiple
 /// threads.
-pub fn get_power_series_with_offset<E: FieldElement>(b: E, s: E, n: usize) -> Vec<E> {
+///
+/// Returns an error if `n` is zero.
+pub fn get_power_series_with_offset<E: FieldElement>(
+    b: E,
+    s: E,
+    n: usize,
+) -> Result<Vec<E>, UtilsError> {
+    if n == 0 {
+        return Err(UtilsError::EmptyPowerSeries);
+    }
+    Ok(get_power_series_with_offset_unchecked(b, s, n))
+}
+
+/// Generates a vector with values [s, s * b, s * b^2, s * b^3, s * b^4, ..., s * b^(n-1)].
+/// When `concurrent` feature is enabled, series generation is done concurrently in multiple
+/// threads.
+///
+/// # Panics
+/// Panics if `n` is zero.
+pub fn get_power_series_with_offset_unchecked<E: FieldElement>(b: E, s: E, n: usize) -> Vec<E> {
+    assert!(n > 0, "power series must contain at least one element");
     const MIN_CONCURRENT_SIZE: usize = 1024;
     let mut result = uninit_vector(n);
     if cfg!(feature = "concurrent") && n >= MIN_CONCURRENT_SIZE && n.is_power_of_two() {
@@ -72,12 +111,16 @@ pub fn add_in_place<E: FieldElement>(a: &mut [E], b: &[E]) {
     );
 
     #[cfg(not(feature = "concurrent"))]
-    a.iter_mut().zip(b).for_each(|(a, &b)| *a += b);
+    E::batch_add(a, b);
 
     #[cfg(feature = "concurrent")]
-    a.par_iter_mut()
-        .zip(b.par_iter())
-        .for_each(|(a, &b)| *a += b);
+    {
+        // values are split into chunks so that each thread can add them in batches
+        const CHUNK_SIZE: usize = 1024;
+        a.par_chunks_mut(CHUNK_SIZE)
+            .zip(b.par_chunks(CHUNK_SIZE))
+            .for_each(|(a, b)| E::batch_add(a, b));
+    }
 }
 
 /// Computes a[i] + b[i] * c for all i and saves result into a.
@@ -125,9 +168,22 @@ pub fn batch_inversion<E: FieldElement>(values: &[E]) -> Vec<E> {
     result
 }
 
+/// Returns base 2 logarithm of `n`.
+///
+/// Returns an error if `n` is not a power of two.
+pub fn log2(n: usize) -> Result<u32, UtilsError> {
+    if !n.is_power_of_two() {
+        return Err(UtilsError::NotPowerOfTwo(n));
+    }
+    Ok(n.trailing_zeros())
+}
+
 /// Returns base 2 logarithm of `n`, where `n` is a power of two.
-pub fn log2(n: usize) -> u32 {
-    assert!(n.is_power_of_two(), "n must be a power of two");
+///
+/// # Panics
+/// Panics if `n` is not a power of two.
+pub fn log2_unchecked(n: usize) -> u32 {
+    assert!(n.is_power_of_two(), "n must be a power of two; was {}", n);
     n.trailing_zeros()
 }
 
@@ -206,7 +262,7 @@ pub fn read_elements_into<E: FieldElement>(
     }
 
     for i in (0..source.len()).step_by(E::ELEMENT_BYTES) {
-        match E::try_from(&source[i..i + E::ELEMENT_BYTES]) {
+        match E::read_from_bytes(&source[i..i + E::ELEMENT_BYTES]) {
             Ok(value) => destination[i / E::ELEMENT_BYTES] = value,
             Err(_) => return Err(SerializationError::FailedToReadElement(i)),
         }
@@ -235,3 +291,17 @@ pub fn read_elements_into_vec<E: FieldElement>(
     read_elements_into(source, &mut result)?;
     Ok(result)
 }
+
+/// Returns a vector of elements read from the provided slice of bytes. The elements are
+/// assumed to be stored in the slice one after the other in little-endian byte order.
+///
+/// This function is intended for bytes which come from a trusted source; untrusted bytes
+/// should be read via [read_elements_into_vec()].
+///
+/// # Panics
+/// Panics if the `source` bytes cannot be read into a vector of elements for any of the reasons
+/// listed for [read_elements_into_vec()].
+pub fn read_elements_into_vec_unchecked<E: FieldElement>(source: &[u8]) -> Vec<E> {
+    read_elements_into_vec(source)
+        .unwrap_or_else(|err| panic!("failed to read field elements from bytes: {}", err))
+}
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::SerializationError;
+use super::{SerializationError, UtilsError};
 use crate::field::{f128::BaseElement, FieldElement};
 
 // MATH FUNCTIONS
@@ -19,8 +19,12 @@ fn get_power_series() {
         *value = b.exp((i as u64).into());
     }
 
-    let actual = super::get_power_series(b, n);
+    let actual = super::get_power_series(b, n).unwrap();
     assert_eq!(expected, actual);
+    assert_eq!(expected, super::get_power_series_unchecked(b, n));
+
+    let result = super::get_power_series(b, 0);
+    assert_eq!(result, Err(UtilsError::EmptyPowerSeries));
 }
 
 #[test]
@@ -34,8 +38,15 @@ fn get_power_series_with_offset() {
         *value = s * b.exp((i as u64).into());
     }
 
-    let actual = super::get_power_series_with_offset(b, s, n);
+    let actual = super::get_power_series_with_offset(b, s, n).unwrap();
     assert_eq!(expected, actual);
+    assert_eq!(
+        expected,
+        super::get_power_series_with_offset_unchecked(b, s, n)
+    );
+
+    let result = super::get_power_series_with_offset(b, s, 0);
+    assert_eq!(result, Err(UtilsError::EmptyPowerSeries));
 }
 
 #[test]
@@ -78,6 +89,22 @@ fn batch_inversion() {
     }
 }
 
+#[test]
+fn log2() {
+    assert_eq!(Ok(0), super::log2(1));
+    assert_eq!(Ok(10), super::log2(1024));
+    assert_eq!(10, super::log2_unchecked(1024));
+
+    assert_eq!(Err(UtilsError::NotPowerOfTwo(0)), super::log2(0));
+    assert_eq!(Err(UtilsError::NotPowerOfTwo(1000)), super::log2(1000));
+}
+
+#[test]
+#[should_panic]
+fn log2_unchecked_not_power_of_two() {
+    super::log2_unchecked(1000);
+}
+
 // VECTOR FUNCTIONS
 // ================================================================================================
 
@@ -186,3 +213,10 @@ fn read_elements_into_vec() {
     let result = super::read_elements_into_vec::<BaseElement>(&bytes[16..]);
     assert_eq!(result, Err(SerializationError::FailedToReadElement(48)));
 }
+
+#[test]
+#[should_panic]
+fn read_elements_into_vec_unchecked_invalid_element() {
+    let bytes = [255u8; 16];
+    super::read_elements_into_vec_unchecked::<BaseElement>(&bytes);
+}
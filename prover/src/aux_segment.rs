@@ -0,0 +1,25 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::ExecutionTrace;
+use math::field::StarkField;
+
+// AUXILIARY SEGMENT BUILDER
+// ================================================================================================
+
+/// Builds the auxiliary segment of the execution trace for computations which declare one via
+/// [with_aux_segment()](common::ComputationContext::with_aux_segment).
+///
+/// The segment is built after the prover commits to the main segment of the trace, from random
+/// elements drawn from this commitment; thus, values of the main segment cannot depend on these
+/// elements. This allows the auxiliary segment to hold values such as running products of
+/// permutation arguments. The same elements are passed to the AIR via
+/// [Air::set_aux_rand_elements()](common::Air::set_aux_rand_elements).
+pub trait AuxSegmentBuilder<B: StarkField> {
+    /// Builds the auxiliary segment for the `main` segment of the execution trace using the
+    /// specified random `elements`; the segment must have the same length as the main segment,
+    /// and must consist of as many registers as declared by the AIR.
+    fn build_aux_segment(&self, main: &ExecutionTrace<B>, elements: &[B]) -> ExecutionTrace<B>;
+}
@@ -0,0 +1,138 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crypto::{Hasher, MerkleTree};
+use math::field::StarkField;
+use std::marker::PhantomData;
+
+#[cfg(test)]
+mod tests;
+
+// TRACE BLINDER
+// ================================================================================================
+
+/// Commits to blinded versions of trace registers designated by
+/// [Air::get_blinded_registers()](common::Air::get_blinded_registers).
+///
+/// Roots of these commitments are included in the proof as auxiliary commitments, and are
+/// absorbed into the seed from which constraint composition coefficients are drawn. This does
+/// not hide the designated registers: they remain part of the main trace commitment, and their
+/// values are included in query openings and in the out-of-domain frame like values of any other
+/// register. Nor does the proof attest that the auxiliary commitments are commitments to the
+/// values of these registers; the verifier checks only that the proof contains one auxiliary
+/// commitment per designated register.
+pub trait TraceBlinder<B: StarkField> {
+    /// Commits to a blinded version of the `column` of the execution trace which holds values
+    /// of the specified `register`, and returns the root of the commitment.
+    fn commit(&mut self, register: usize, column: &[B]) -> [u8; 32];
+}
+
+// SALTED BLINDER
+// ================================================================================================
+
+/// Commits to each register by building a Merkle tree in which a leaf at step i is
+/// hash(value || salt_i); salts are derived from a secret seed as hash(seed || register || i).
+///
+/// The seed must be uniformly random and must be kept secret; anyone who knows the seed can
+/// brute-force values of the blinded registers. Merkle trees for all committed registers are
+/// retained so that values at individual steps can later be opened via [SaltedBlinder::open()].
+pub struct SaltedBlinder<H: Hasher> {
+    seed: [u8; 32],
+    trees: Vec<(usize, MerkleTree)>,
+    _hasher: PhantomData<H>,
+}
+
+/// Opening of a value of a blinded register at a single step of the execution trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlindedOpening {
+    /// Salt with which the value was hashed into a leaf of the commitment.
+    pub salt: [u8; 32],
+    /// Merkle authentication path from the leaf to the root of the commitment.
+    pub path: Vec<[u8; 32]>,
+}
+
+impl<H: Hasher> SaltedBlinder<H> {
+    /// Returns a new blinder which derives salts from the specified secret `seed`.
+    pub fn new(seed: [u8; 32]) -> Self {
+        SaltedBlinder {
+            seed,
+            trees: Vec::new(),
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Returns an opening of the commitment to the specified `register` at the specified `step`,
+    /// or None if the register has not been committed to by this blinder.
+    ///
+    /// # Panics
+    /// Panics if `step` is outside of the execution trace.
+    pub fn open(&self, register: usize, step: usize) -> Option<BlindedOpening> {
+        self.trees
+            .iter()
+            .find(|(r, _)| *r == register)
+            .map(|(_, tree)| BlindedOpening {
+                salt: self.build_salt(register, step),
+                path: tree.prove(step),
+            })
+    }
+
+    /// Returns true if the `opening` proves that the blinded register committed to by `root`
+    /// has the specified `value` at the specified `step`.
+    pub fn verify<B: StarkField>(
+        root: &[u8; 32],
+        step: usize,
+        value: B,
+        opening: &BlindedOpening,
+    ) -> bool {
+        // a valid path contains at least the leaf and its sibling
+        opening.path.len() >= 2
+            && opening.path[0] == hash_value::<H, B>(value, &opening.salt)
+            && MerkleTree::verify(root, step, &opening.path, H::hash_fn())
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    fn build_salt(&self, register: usize, step: usize) -> [u8; 32] {
+        let mut data = [0u8; 48];
+        data[..32].copy_from_slice(&self.seed);
+        data[32..40].copy_from_slice(&(register as u64).to_le_bytes());
+        data[40..].copy_from_slice(&(step as u64).to_le_bytes());
+
+        let mut salt = [0u8; 32];
+        H::hash_fn()(&data, &mut salt);
+        salt
+    }
+}
+
+impl<B: StarkField, H: Hasher> TraceBlinder<B> for SaltedBlinder<H> {
+    fn commit(&mut self, register: usize, column: &[B]) -> [u8; 32] {
+        let leaves = column
+            .iter()
+            .enumerate()
+            .map(|(step, &value)| hash_value::<H, B>(value, &self.build_salt(register, step)))
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::new(leaves, H::hash_fn());
+        let root = *tree.root();
+
+        // a register committed to more than once retains only the latest commitment
+        self.trees.retain(|(r, _)| *r != register);
+        self.trees.push((register, tree));
+        root
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Hashes canonical bytes of the `value` together with the `salt` into a commitment leaf.
+fn hash_value<H: Hasher, B: StarkField>(value: B, salt: &[u8; 32]) -> [u8; 32] {
+    let mut data = value.to_canonical_bytes();
+    data.extend_from_slice(salt);
+
+    let mut leaf = [0u8; 32];
+    H::hash_fn()(&data, &mut leaf);
+    leaf
+}
@@ -0,0 +1,91 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{SaltedBlinder, TraceBlinder};
+use crate::{
+    tests::{build_fib_inputs, build_fib_trace, build_proof_options, BlindedFibAir, FibAir},
+    ProverError,
+};
+use crypto::hash::Blake3_256;
+use math::field::{f128::BaseElement, FieldElement};
+
+type Blinder = SaltedBlinder<Blake3_256>;
+
+#[test]
+fn salted_blinder_open_verify() {
+    let column = (0..16u128).map(BaseElement::new).collect::<Vec<_>>();
+    let mut blinder = Blinder::new([1; 32]);
+    let root = blinder.commit(2, &column);
+
+    for (step, &value) in column.iter().enumerate() {
+        let opening = blinder.open(2, step).unwrap();
+        assert!(Blinder::verify(&root, step, value, &opening));
+
+        // the opening must not verify against a different value or a different step
+        assert!(!Blinder::verify(
+            &root,
+            step,
+            value + BaseElement::ONE,
+            &opening
+        ));
+        assert!(!Blinder::verify(&root, (step + 1) % 16, value, &opening));
+    }
+
+    // registers which were not committed to cannot be opened
+    assert_eq!(None, blinder.open(1, 0));
+}
+
+#[test]
+fn salted_blinder_hides_values() {
+    let column = vec![BaseElement::ONE; 16];
+
+    // commitments to the same values depend on the seed and on the register
+    let mut blinder = Blinder::new([1; 32]);
+    let root = blinder.commit(0, &column);
+    assert_eq!(root, blinder.commit(0, &column));
+    assert_ne!(root, blinder.commit(1, &column));
+    assert_ne!(root, Blinder::new([2; 32]).commit(0, &column));
+
+    // identical values at different steps are hashed into different leaves
+    let opening0 = blinder.open(0, 0).unwrap();
+    let opening1 = blinder.open(0, 1).unwrap();
+    assert_ne!(opening0.path[0], opening1.path[0]);
+}
+
+#[test]
+fn prove_with_blinded_registers() {
+    let options = build_proof_options(false);
+    let build_pub_inputs = || build_fib_inputs(&build_fib_trace(16));
+
+    // a computation which designates blinded registers cannot be proven without a blinder
+    let result =
+        crate::prove::<BlindedFibAir>(build_fib_trace(16), build_pub_inputs(), options.clone());
+    assert!(matches!(
+        result,
+        Err(ProverError::TraceBlinderNotProvided(1))
+    ));
+
+    let mut blinder = Blinder::new([7; 32]);
+    let proof = crate::Prover::<BlindedFibAir>::new(options)
+        .with_blinder(&mut blinder)
+        .prove(build_fib_trace(16), build_pub_inputs())
+        .unwrap();
+    assert_eq!(1, proof.commitments.aux_roots.len());
+
+    // the blinded register can be opened against the commitment included in the proof
+    let aux_root = proof.commitments.aux_roots[0];
+    let opening = blinder.open(1, 0).unwrap();
+    let start = build_pub_inputs().start;
+    assert!(Blinder::verify(&aux_root, 0, start[1], &opening));
+
+    // the proof verifies only against an AIR which designates the same number of registers
+    assert!(verifier::verify::<BlindedFibAir>(proof.clone(), build_pub_inputs()).is_ok());
+    assert!(verifier::verify::<FibAir>(proof.clone(), build_pub_inputs()).is_err());
+
+    // auxiliary commitments are bound to the transcript, so they cannot be replaced
+    let mut proof = proof;
+    proof.commitments.aux_roots[0] = [1; 32];
+    assert!(verifier::verify::<BlindedFibAir>(proof, build_pub_inputs()).is_err());
+}
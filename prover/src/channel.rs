@@ -3,51 +3,89 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::{grinding::grinding_factor_for_budget, ProverObserver};
 use common::{
-    proof::{Commitments, Context, OodEvaluationFrame, Queries, StarkProof},
-    ComputationContext, EvaluationFrame, PublicCoin,
+    build_constraint_seed, build_fri_seed,
+    proof::{Commitments, Context, OodEvaluationFrame, Queries, StarkProof, PROOF_VERSION},
+    CompositionCoefficients, ComputationContext, ProofOfWork, PublicCoin,
 };
-use crypto::{DefaultRandomElementGenerator, Hasher};
+use crypto::{DefaultRandomElementGenerator, Hasher, RandomElementGenerator};
 use fri::{self, FriProof};
 use math::{
-    field::{FieldElement, StarkField},
-    utils::log2,
+    field::{ByteEncoding, FieldElement, StarkField},
+    utils::log2_unchecked,
 };
-use std::{convert::TryInto, marker::PhantomData};
-
-#[cfg(feature = "concurrent")]
-use rayon::prelude::*;
+use std::{cell::RefCell, marker::PhantomData};
 
 // TYPES AND INTERFACES
 // ================================================================================================
 
-pub struct ProverChannel<H: Hasher> {
+/// Channel through which the prover commits to values and draws randomness. Commitments are
+/// built using hash function `H`, while seeds and pseudo-random values of the Fiat-Shamir
+/// transcript are derived using hash function `T`.
+///
+/// If the channel is created with an observer, the observer is notified about every value as
+/// soon as it is committed to or drawn through the channel.
+pub struct ProverChannel<'a, H: Hasher, T: Hasher> {
     context: ComputationContext,
+    observer: Option<RefCell<&'a mut dyn ProverObserver>>,
     trace_root: Option<[u8; 32]>,
+    aux_roots: Vec<[u8; 32]>,
+    extra_root: Option<[u8; 32]>,
+    aux_segment_root: Option<[u8; 32]>,
     constraint_root: Option<[u8; 32]>,
+    ood_frame: Option<OodEvaluationFrame>,
     fri_roots: Vec<[u8; 32]>,
+    fri_remainder: Option<(Vec<u8>, usize)>,
     query_seed: Option<[u8; 32]>,
     pow_nonce: u64,
+    pow_difficulty: u32,
     _hasher: PhantomData<H>,
+    _transcript_hasher: PhantomData<T>,
 }
 
 // PROVER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<H: Hasher> ProverChannel<H> {
+impl<'a, H: Hasher, T: Hasher> ProverChannel<'a, H, T> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
     /// Creates a new prover channel for the specified proof `context`.
     pub fn new(context: &ComputationContext) -> Self {
         ProverChannel {
             context: context.clone(),
+            observer: None,
             trace_root: None,
+            aux_roots: Vec::new(),
+            extra_root: None,
+            aux_segment_root: None,
             constraint_root: None,
+            ood_frame: None,
             fri_roots: Vec::new(),
+            fri_remainder: None,
             query_seed: None,
             pow_nonce: 0,
+            pow_difficulty: 0,
             _hasher: PhantomData,
+            _transcript_hasher: PhantomData,
+        }
+    }
+
+    /// Creates a new prover channel for the specified proof `context` which notifies the
+    /// `observer` about all values committed to or drawn through the channel.
+    pub fn with_observer(
+        context: &ComputationContext,
+        observer: &'a mut dyn ProverObserver,
+    ) -> Self {
+        ProverChannel {
+            observer: Some(RefCell::new(observer)),
+            ..Self::new(context)
         }
     }
 
+    // COMMITMENT METHODS
+    // --------------------------------------------------------------------------------------------
+
     /// Commits the prover the extended execution trace.
     pub fn commit_trace(&mut self, trace_root: [u8; 32]) {
         assert!(
@@ -55,6 +93,62 @@ impl<H: Hasher> ProverChannel<H> {
             "trace root has already been committed"
         );
         self.trace_root = Some(trace_root);
+        self.notify(|observer| observer.on_trace_commitment(&trace_root));
+    }
+
+    /// Commits the prover to blinded versions of the designated trace `registers`; `aux_roots`
+    /// must contain a root for every register in `registers`, unless the registers were not
+    /// blinded, in which case `aux_roots` must be empty.
+    pub fn commit_aux_roots(&mut self, registers: &[usize], aux_roots: Vec<[u8; 32]>) {
+        assert!(
+            self.trace_root.is_some(),
+            "trace root has not been committed yet"
+        );
+        assert!(
+            self.constraint_root.is_none(),
+            "constraint root has already been committed"
+        );
+        for (&register, root) in registers.iter().zip(aux_roots.iter()) {
+            self.notify(|observer| observer.on_aux_commitment(register, root));
+        }
+        self.aux_roots = aux_roots;
+    }
+
+    /// Commits the prover to the extended extra polynomials.
+    pub fn commit_extra_root(&mut self, extra_root: [u8; 32]) {
+        assert!(
+            self.trace_root.is_some(),
+            "trace root has not been committed yet"
+        );
+        assert!(
+            self.extra_root.is_none(),
+            "extra root has already been committed"
+        );
+        assert!(
+            self.constraint_root.is_none(),
+            "constraint root has already been committed"
+        );
+        self.extra_root = Some(extra_root);
+        self.notify(|observer| observer.on_extra_commitment(&extra_root));
+    }
+
+    /// Commits the prover to the extended auxiliary segment of the execution trace; this must be
+    /// done after random elements for building the segment have been drawn.
+    pub fn commit_aux_segment(&mut self, aux_segment_root: [u8; 32]) {
+        assert!(
+            self.trace_root.is_some(),
+            "trace root has not been committed yet"
+        );
+        assert!(
+            self.aux_segment_root.is_none(),
+            "auxiliary segment root has already been committed"
+        );
+        assert!(
+            self.constraint_root.is_none(),
+            "constraint root has already been committed"
+        );
+        self.aux_segment_root = Some(aux_segment_root);
+        self.notify(|observer| observer.on_aux_segment_commitment(&aux_segment_root));
     }
 
     /// Commits the prover the the constraint evaluations.
@@ -64,81 +158,259 @@ impl<H: Hasher> ProverChannel<H> {
             "constraint root has already been committed"
         );
         self.constraint_root = Some(constraint_root);
+        self.notify(|observer| observer.on_constraint_commitment(&constraint_root));
+    }
+
+    /// Sends evaluations of the execution trace at the out-of-domain points to the verifier.
+    pub fn send_ood_frame(&mut self, ood_frame: OodEvaluationFrame) {
+        assert!(
+            self.constraint_root.is_some(),
+            "constraint root has not been committed yet"
+        );
+        assert!(
+            self.ood_frame.is_none(),
+            "out-of-domain frame has already been sent"
+        );
+        self.notify(|observer| {
+            observer.on_ood_frame(
+                &ood_frame.trace_at_z1,
+                &ood_frame.trace_at_z2,
+                &ood_frame.extra_at_z,
+            )
+        });
+        self.ood_frame = Some(ood_frame);
     }
 
-    /// Computes query seed from a combination of FRI layers and applies PoW to the seed
-    /// based on the grinding_factor specified by the options
-    pub fn grind_query_seed(&mut self) {
+    /// Commits the prover to the FRI remainder; the remainder is absorbed into the query seed
+    /// together with the bound on the degree of the remainder polynomial.
+    pub fn commit_fri_remainder<B: StarkField, E: FieldElement + From<B>>(
+        &mut self,
+        remainder: &[E],
+    ) {
         assert!(
             !self.fri_roots.is_empty(),
             "FRI layers haven't been computed yet"
         );
+        assert!(
+            self.fri_remainder.is_none(),
+            "FRI remainder has already been committed"
+        );
+        let degree_bound = self.context.fri_remainder_degree_bound::<B>();
+        let remainder = self
+            .context
+            .options()
+            .byte_encoding()
+            .encode_elements(remainder)
+            .into_owned();
+        self.fri_remainder = Some((remainder, degree_bound));
+        if self.observer.is_some() {
+            let commitments = self.commitments();
+            self.notify(|observer| observer.on_commitments(&commitments));
+        }
+    }
+
+    /// Computes query seed from a combination of FRI layers and the FRI remainder, and applies
+    /// the specified PoW scheme to the seed based on the grinding_factor specified by the options;
+    /// if the options specify a grinding budget, the difficulty is selected to fit into the budget.
+    pub fn grind_query_seed(&mut self, pow: &dyn ProofOfWork) {
+        assert!(
+            !self.fri_roots.is_empty(),
+            "FRI layers haven't been computed yet"
+        );
+        assert!(
+            self.fri_remainder.is_some(),
+            "FRI remainder hasn't been committed yet"
+        );
         assert!(
             self.query_seed.is_none(),
             "query seed has already been computed"
         );
         let options = self.context().options();
-        let seed = build_query_seed::<H>(&self.fri_roots);
-        let (seed, nonce) = find_pow_nonce::<H>(seed, options.grinding_factor());
+        let encoding = options.byte_encoding();
+        let (remainder, degree_bound) = self.fri_remainder.as_ref().unwrap();
+        let seed = build_fri_seed::<T>(&self.fri_roots, remainder, *degree_bound, encoding);
+        let grinding_factor = match options.grinding_budget() {
+            Some(budget) => {
+                grinding_factor_for_budget(pow, budget, options.grinding_factor(), T::hash_fn())
+            }
+            None => options.grinding_factor(),
+        };
+        let (nonce, seed) = pow.solve(&seed, grinding_factor, T::hash_fn(), encoding);
         self.query_seed = Some(seed);
         self.pow_nonce = nonce;
+        self.pow_difficulty = grinding_factor;
+        self.notify(|observer| observer.on_query_seed(&seed, nonce));
+    }
+
+    // DRAW METHODS
+    // --------------------------------------------------------------------------------------------
+    // these shadow the corresponding methods of the public coin so that drawn values are reported
+    // to the observer
+
+    /// Draws random elements for building the auxiliary segment of the execution trace in the
+    /// same way as [PublicCoin::draw_aux_rand_elements()].
+    pub fn draw_aux_rand_elements<B: FieldElement>(&self) -> Vec<B> {
+        let elements = PublicCoin::draw_aux_rand_elements::<B>(self);
+        let encoding = self.context.options().byte_encoding();
+        self.notify(|observer| observer.on_aux_rand_elements(&encoding.encode_elements(&elements)));
+        elements
+    }
+
+    /// Draws an out-of-domain point in the same way as [PublicCoin::draw_deep_point()].
+    pub fn draw_deep_point<E: FieldElement>(&self) -> E {
+        let z = PublicCoin::draw_deep_point::<E>(self);
+        let encoding = self.context.options().byte_encoding();
+        self.notify(|observer| observer.on_ood_point(&encoding.encode_elements(&[z])));
+        z
+    }
+
+    /// Draws coefficients for DEEP composition in the same way as
+    /// [PublicCoin::draw_composition_coefficients()].
+    pub fn draw_composition_coefficients<E: FieldElement>(&self) -> CompositionCoefficients<E> {
+        let coefficients = PublicCoin::draw_composition_coefficients::<E>(self);
+        if self.observer.is_some() {
+            let values = flatten_composition_coefficients(&coefficients);
+            let values = self
+                .context
+                .options()
+                .byte_encoding()
+                .encode_elements(&values)
+                .into_owned();
+            self.notify(|observer| observer.on_composition_coefficients(&values));
+        }
+        coefficients
+    }
+
+    /// Draws query positions for FRI layers in the same way as
+    /// [PublicCoin::draw_fri_query_positions()].
+    pub fn draw_fri_query_positions(&self) -> Vec<usize> {
+        let positions = PublicCoin::draw_fri_query_positions(self);
+        self.notify(|observer| observer.on_query_positions(&positions));
+        positions
+    }
+
+    /// Returns all commitments made so far; this must be called after the FRI layers have been
+    /// committed to.
+    pub fn commitments(&self) -> Commitments {
+        assert!(
+            !self.fri_roots.is_empty(),
+            "FRI layers haven't been computed yet"
+        );
+        Commitments {
+            trace_root: self.trace_root.unwrap(),
+            aux_roots: self.aux_roots.clone(),
+            extra_root: self.extra_root,
+            aux_segment_root: self.aux_segment_root,
+            constraint_root: self.constraint_root.unwrap(),
+            fri_roots: self.fri_roots.clone(),
+        }
+    }
+
+    /// Returns the grinding factor satisfied by the proof-of-work nonce.
+    pub fn pow_difficulty(&self) -> u32 {
+        assert!(self.query_seed.is_some(), "query seed is not set");
+        self.pow_difficulty
     }
 
     /// Builds a proof from the previously committed values as well as values
     /// passed in to this method
-    pub fn build_proof<B: StarkField, E: FieldElement + From<B>>(
+    pub fn build_proof<B: StarkField>(
         self,
+        num_constraints: usize,
         trace_queries: Queries,
         constraint_queries: Queries,
-        ood_frame: EvaluationFrame<E>,
+        extra_queries: Option<Queries>,
+        aux_segment_queries: Option<Queries>,
         fri_proof: FriProof,
     ) -> StarkProof {
+        assert!(
+            self.ood_frame.is_some(),
+            "out-of-domain frame has not been sent yet"
+        );
         StarkProof {
             context: Context {
-                lde_domain_depth: log2(self.context.lde_domain_size()) as u8,
+                version: PROOF_VERSION,
+                lde_domain_depth: log2_unchecked(self.context.lde_domain_size()) as u8,
                 ce_blowup_factor: self.context.ce_blowup_factor() as u8,
+                num_constraints: num_constraints as u32,
                 field_modulus_bytes: B::get_modulus_le_bytes(),
                 options: self.context().options().clone(),
+                air_id: self.context.air_id(),
             },
             commitments: Commitments {
                 trace_root: self.trace_root.unwrap(),
+                aux_roots: self.aux_roots,
+                extra_root: self.extra_root,
+                aux_segment_root: self.aux_segment_root,
                 constraint_root: self.constraint_root.unwrap(),
                 fri_roots: self.fri_roots,
             },
             trace_queries,
             constraint_queries,
-            ood_frame: OodEvaluationFrame {
-                trace_at_z1: E::elements_as_bytes(&ood_frame.current).to_vec(),
-                trace_at_z2: E::elements_as_bytes(&ood_frame.next).to_vec(),
-            },
+            extra_queries,
+            aux_segment_queries,
+            ood_frame: self.ood_frame.unwrap(),
             fri_proof,
             pow_nonce: self.pow_nonce,
+            pow_difficulty: self.pow_difficulty as u8,
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Passes the observer of this channel, if any, to the `event` function.
+    fn notify<F: FnOnce(&mut dyn ProverObserver)>(&self, event: F) {
+        if let Some(observer) = &self.observer {
+            event(&mut **observer.borrow_mut());
         }
     }
 }
 
-impl<H: Hasher> fri::ProverChannel for ProverChannel<H> {
+impl<'a, H: Hasher, T: Hasher> fri::ProverChannel for ProverChannel<'a, H, T> {
     type Hasher = H;
 
     /// Commits the prover to the a FRI layer.
     fn commit_fri_layer(&mut self, layer_root: [u8; 32]) {
+        let layer_index = self.fri_roots.len();
         self.fri_roots.push(layer_root);
+        self.notify(|observer| observer.on_fri_layer_commitment(layer_index, &layer_root));
     }
 }
 
 // PUBLIC COIN IMPLEMENTATION
 // ================================================================================================
 
-impl<H: Hasher> PublicCoin for ProverChannel<H> {
-    type Hasher = H;
+impl<'a, H: Hasher, T: Hasher> PublicCoin for ProverChannel<'a, H, T> {
+    type Hasher = T;
 
     fn context(&self) -> &ComputationContext {
         &self.context
     }
 
+    fn aux_segment_seed(&self) -> [u8; 32] {
+        assert!(
+            self.trace_root.is_some(),
+            "auxiliary segment seed is not set"
+        );
+        build_constraint_seed::<T>(
+            &self.trace_root.unwrap(),
+            &self.aux_roots,
+            self.extra_root.as_ref(),
+            None,
+            self.context.air_id().as_ref(),
+        )
+    }
+
     fn constraint_seed(&self) -> [u8; 32] {
         assert!(self.trace_root.is_some(), "constraint seed is not set");
-        self.trace_root.unwrap()
+        build_constraint_seed::<T>(
+            &self.trace_root.unwrap(),
+            &self.aux_roots,
+            self.extra_root.as_ref(),
+            self.aux_segment_root.as_ref(),
+            self.context.air_id().as_ref(),
+        )
     }
 
     fn composition_seed(&self) -> [u8; 32] {
@@ -155,65 +427,47 @@ impl<H: Hasher> PublicCoin for ProverChannel<H> {
     }
 }
 
-impl<H: Hasher> fri::PublicCoin for ProverChannel<H> {
-    type RandomElementGenerator = DefaultRandomElementGenerator<H>;
+impl<'a, H: Hasher, T: Hasher> fri::PublicCoin for ProverChannel<'a, H, T> {
+    type RandomElementGenerator = DefaultRandomElementGenerator<T>;
+
+    /// Draws a FRI folding coefficient in the same way as the default implementation, and
+    /// reports it to the observer.
+    fn draw_fri_alpha<E: FieldElement>(&self, layer_idx: usize) -> E {
+        let seed = self.fri_layer_commitments()[layer_idx];
+        let alpha =
+            Self::RandomElementGenerator::with_encoding(seed, 0, self.byte_encoding()).draw::<E>();
+        let encoding = self.byte_encoding();
+        self.notify(|observer| {
+            observer.on_fri_alpha(layer_idx, &encoding.encode_elements(&[alpha]))
+        });
+        alpha
+    }
 
     fn fri_layer_commitments(&self) -> &[[u8; 32]] {
         assert!(!self.fri_roots.is_empty(), "FRI layers are not set");
         &self.fri_roots
     }
+
+    fn byte_encoding(&self) -> ByteEncoding {
+        self.context.options().byte_encoding()
+    }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
-fn build_query_seed<H: Hasher>(fri_roots: &[[u8; 32]]) -> [u8; 32] {
-    let hash_fn = H::hash_fn();
-    // combine roots of all FIR layers into a single array of bytes
-    let mut root_bytes: Vec<u8> = Vec::with_capacity(fri_roots.len() * 32);
-    for root in fri_roots.iter() {
-        root.iter().for_each(|&v| root_bytes.push(v));
-    }
 
-    // hash the array of bytes into a single 32-byte value
-    let mut query_seed = [0u8; 32];
-    hash_fn(&root_bytes, &mut query_seed);
-
-    query_seed
-}
-
-fn find_pow_nonce<H: Hasher>(seed: [u8; 32], grinding_factor: u32) -> ([u8; 32], u64) {
-    let hash_fn = H::hash_fn();
-    let mut buf = [0u8; 64];
-    buf[0..32].copy_from_slice(&seed);
-
-    #[cfg(not(feature = "concurrent"))]
-    let nonce = (1..u64::MAX)
-        .find(|nonce| {
-            let mut result = [0u8; 32];
-            let mut buf = buf;
-
-            buf[56..].copy_from_slice(&nonce.to_le_bytes());
-            hash_fn(&buf, &mut result);
-            u64::from_le_bytes(result[..8].try_into().unwrap()).trailing_zeros() >= grinding_factor
-        })
-        .expect("nonce not found");
-
-    #[cfg(feature = "concurrent")]
-    let nonce = (1..u64::MAX)
-        .into_par_iter()
-        .find_any(|nonce| {
-            let mut result = [0u8; 32];
-            let mut buf = buf;
-
-            buf[56..].copy_from_slice(&nonce.to_le_bytes());
-            hash_fn(&buf, &mut result);
-            u64::from_le_bytes(result[..8].try_into().unwrap()).trailing_zeros() >= grinding_factor
-        })
-        .expect("nonce not found");
-
-    let mut result = [0u8; 32];
-    buf[56..].copy_from_slice(&nonce.to_le_bytes());
-    hash_fn(&buf, &mut result);
-
-    (result, nonce)
+/// Returns composition coefficients in the order in which they are drawn from the public coin.
+fn flatten_composition_coefficients<E: FieldElement>(
+    coefficients: &CompositionCoefficients<E>,
+) -> Vec<E> {
+    let mut result = Vec::new();
+    for &(c1, c2, c3) in coefficients.trace.iter() {
+        result.extend_from_slice(&[c1, c2, c3]);
+    }
+    result.extend_from_slice(&[coefficients.trace_degree.0, coefficients.trace_degree.1]);
+    result.push(coefficients.constraints);
+    for &(c1, c2) in coefficients.extra.iter() {
+        result.extend_from_slice(&[c1, c2]);
+    }
+    result
 }
@@ -0,0 +1,135 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::errors::{ProverError, ProvingPhase};
+use log::warn;
+use std::time::Instant;
+
+// PROVER CONFIG
+// ================================================================================================
+
+/// Resource limits applied by the prover during proof generation.
+///
+/// The monolith prover holds the extended execution trace, constraint evaluations, and FRI
+/// layers in memory at the same time. Before generating a proof, the prover estimates the peak
+/// amount of memory it will need (see `ComputationContext::estimate_prover_memory()`), and
+/// compares the estimate against the budget: if the budget is exceeded, a warning is logged, or,
+/// in strict mode, proof generation fails with [ProverError::MemoryBudgetExceeded].
+///
+/// A deadline can also be set for proof generation. The prover checks the deadline between
+/// phases of proof generation, and if it has passed, aborts with [ProverError::DeadlineExceeded]
+/// which specifies the last completed phase.
+///
+/// Constraint profiling can be enabled to collect statistics about transition constraint
+/// evaluations: for every constraint, the prover records the fraction of the constraint evaluation
+/// domain at which the constraint evaluates to zero, the average number of field operations
+/// needed to evaluate it, and its actual degree. The statistics are reported together with other
+/// prover metrics at debug log level. Profiling requires an additional pass over the constraint
+/// evaluation domain, and thus, should not be enabled in production.
+///
+/// The default config does not limit memory usage, does not set a deadline, and does not
+/// profile constraints.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProverConfig {
+    max_memory: usize,
+    strict: bool,
+    deadline: Option<Instant>,
+    profile_constraints: bool,
+}
+
+impl ProverConfig {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new config which limits the memory used by the prover to `max_memory` bytes.
+    pub fn new(max_memory: usize) -> Self {
+        ProverConfig {
+            max_memory,
+            strict: false,
+            deadline: None,
+            profile_constraints: false,
+        }
+    }
+
+    /// Makes the prover return an error instead of logging a warning when the memory budget is
+    /// exceeded.
+    pub fn with_strict_budget(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Makes the prover abort proof generation if it is still running at the `deadline`.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Makes the prover collect and report statistics about transition constraint evaluations.
+    pub fn with_constraint_profiling(mut self) -> Self {
+        self.profile_constraints = true;
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the maximum amount of memory (in bytes) the prover is allowed to use.
+    pub fn max_memory(&self) -> usize {
+        self.max_memory
+    }
+
+    /// Returns true if exceeding the memory budget results in an error.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Returns the deadline for proof generation, if one was set.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Returns true if the prover collects statistics about transition constraint evaluations.
+    pub fn profiles_constraints(&self) -> bool {
+        self.profile_constraints
+    }
+
+    // BUDGET CHECKS
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks the `estimated` amount of memory needed to generate a proof against the budget.
+    pub(crate) fn check_memory(&self, estimated: usize) -> Result<(), ProverError> {
+        if estimated <= self.max_memory {
+            return Ok(());
+        }
+        if self.strict {
+            return Err(ProverError::MemoryBudgetExceeded(
+                estimated,
+                self.max_memory,
+            ));
+        }
+        warn!(
+            "Estimated memory usage of {} bytes exceeds the prover budget of {} bytes",
+            estimated, self.max_memory
+        );
+        Ok(())
+    }
+
+    /// Returns an error if the deadline has passed; `completed` is the phase of proof generation
+    /// which has just been completed.
+    pub(crate) fn check_deadline(&self, completed: ProvingPhase) -> Result<(), ProverError> {
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                Err(ProverError::DeadlineExceeded(completed))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self::new(usize::MAX)
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::ProofOfWork;
+use crypto::HashFunction;
+use math::field::ByteEncoding;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of nonces checked when estimating the rate at which nonces can be checked.
+const HASH_RATE_SAMPLE_SIZE: u64 = 4096;
+
+// GRINDING BUDGET
+// ================================================================================================
+
+/// Returns the number of nonces per second which can be checked by the specified proof-of-work
+/// scheme on this machine when searching for a nonce; the rate is estimated by timing a small
+/// number of checks.
+pub fn measure_hash_rate(pow: &dyn ProofOfWork, hash_fn: HashFunction) -> f64 {
+    let seed = [0u8; 32];
+    let now = Instant::now();
+    for nonce in 1..=HASH_RATE_SAMPLE_SIZE {
+        black_box(pow.verify(&seed, nonce, 0, hash_fn, ByteEncoding::Native));
+    }
+    let elapsed = now.elapsed().as_secs_f64().max(f64::MIN_POSITIVE);
+    let rate = HASH_RATE_SAMPLE_SIZE as f64 / elapsed;
+
+    // nonces are searched for by all threads of the thread pool
+    #[cfg(feature = "concurrent")]
+    let rate = rate * rayon::current_num_threads() as f64;
+
+    rate
+}
+
+/// Returns the largest grinding factor for which a nonce is expected to be found within the
+/// specified wall-clock `budget` on this machine; the returned value is never smaller than
+/// `min_grinding_factor` and never greater than the largest grinding factor supported by the
+/// proof-of-work scheme.
+///
+/// A nonce satisfying grinding factor d is found after 2^d attempts on average, and thus, the
+/// grinding factor is computed as log2 of the number of nonces which can be checked within the
+/// budget at the rate returned by [measure_hash_rate()].
+pub fn grinding_factor_for_budget(
+    pow: &dyn ProofOfWork,
+    budget: Duration,
+    min_grinding_factor: u32,
+    hash_fn: HashFunction,
+) -> u32 {
+    let max_grinding_factor = pow.max_grinding_factor();
+    if max_grinding_factor <= min_grinding_factor {
+        return min_grinding_factor;
+    }
+    let hash_rate = measure_hash_rate(pow, hash_fn);
+    get_grinding_factor(hash_rate, budget, min_grinding_factor, max_grinding_factor)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns log2 of the number of nonces which can be checked within the `budget` at the
+/// specified `hash_rate`, clamped to the specified range.
+fn get_grinding_factor(
+    hash_rate: f64,
+    budget: Duration,
+    min_grinding_factor: u32,
+    max_grinding_factor: u32,
+) -> u32 {
+    let num_attempts = hash_rate * budget.as_secs_f64();
+    let grinding_factor = if num_attempts >= 1.0 {
+        num_attempts.log2().floor() as u32
+    } else {
+        0
+    };
+    grinding_factor.clamp(min_grinding_factor, max_grinding_factor)
+}
@@ -0,0 +1,138 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{get_grinding_factor, grinding_factor_for_budget, measure_hash_rate};
+use crate::tests::{build_fib_inputs, build_fib_trace, build_proof_options, prove_fib, FibAir};
+use common::{
+    errors::{ProofShapeError, VerifierError},
+    limits::MAX_GRINDING_FACTOR,
+    proof::StarkProof,
+    Deserializable, FieldExtension, HashFunction, HashProofOfWork, NoProofOfWork, ProofOptions,
+    Serializable,
+};
+use crypto::{hash::Blake3_256, Hasher};
+use std::time::Duration;
+
+#[test]
+fn grinding_factor_for_hash_rate() {
+    let max = MAX_GRINDING_FACTOR;
+
+    // 2^19 < 10^6 < 2^20, so about 2^19 nonces can be checked within a second
+    let rate = 1_000_000.0;
+    assert_eq!(
+        19,
+        get_grinding_factor(rate, Duration::from_secs(1), 0, max)
+    );
+    assert_eq!(
+        16,
+        get_grinding_factor(rate, Duration::from_millis(100), 0, max)
+    );
+
+    // the grinding factor is bounded by the minimum and by the largest allowed grinding factor
+    assert_eq!(
+        24,
+        get_grinding_factor(rate, Duration::from_secs(1), 24, max)
+    );
+    assert_eq!(
+        0,
+        get_grinding_factor(rate, Duration::from_nanos(1), 0, max)
+    );
+    assert_eq!(
+        32,
+        get_grinding_factor(rate, Duration::from_secs(1 << 20), 0, max)
+    );
+}
+
+#[test]
+fn grinding_factor_for_pow_scheme() {
+    let hash_fn = Blake3_256::hash_fn();
+
+    // nonces are never searched for when proof-of-work is disabled
+    let budget = Duration::from_secs(1);
+    assert_eq!(
+        0,
+        grinding_factor_for_budget(&NoProofOfWork, budget, 0, hash_fn)
+    );
+
+    // the hash rate of the default scheme is measured on this machine
+    assert!(measure_hash_rate(&HashProofOfWork, hash_fn) > 0.0);
+    let grinding_factor = grinding_factor_for_budget(&HashProofOfWork, budget, 4, hash_fn);
+    assert!((4..=MAX_GRINDING_FACTOR).contains(&grinding_factor));
+}
+
+#[test]
+fn prove_with_grinding_budget() {
+    // with a grinding budget, the grinding factor specifies the minimum difficulty
+    let budget = Duration::from_millis(20);
+    let options = ProofOptions::new(28, 16, 4, HashFunction::Blake3_256, FieldExtension::None)
+        .with_grinding_budget(budget);
+    let verify = |proof: StarkProof| {
+        verifier::verify::<FibAir>(proof, build_fib_inputs(&build_fib_trace(16)))
+    };
+
+    let proof = StarkProof::read_from_bytes(&prove_fib(16, options).to_bytes()).unwrap();
+    assert_eq!(Some(budget), proof.options().grinding_budget());
+    assert!(proof.pow_difficulty >= 4);
+    assert!(verify(proof.clone()).is_ok());
+
+    // the difficulty recorded in the proof counts towards the security level
+    let mut weaker_proof = proof.clone();
+    weaker_proof.pow_difficulty = 4;
+    assert_eq!(
+        weaker_proof.security_level(true) + (proof.pow_difficulty as u32 - 4),
+        proof.security_level(true)
+    );
+
+    // the recorded difficulty cannot be overstated, or fall below the grinding factor
+    let mut bad_proof = proof.clone();
+    bad_proof.pow_difficulty = 32;
+    let result = verify(bad_proof);
+    assert!(matches!(
+        result,
+        Err(VerifierError::QuerySeedProofOfWorkVerificationFailed)
+    ));
+    let mut bad_proof = proof;
+    bad_proof.pow_difficulty = 3;
+    let result = verify(bad_proof);
+    assert!(matches!(
+        result,
+        Err(VerifierError::InconsistentProof(
+            ProofShapeError::PowDifficultyTooLow(3, 4)
+        ))
+    ));
+}
+
+#[test]
+fn prove_with_custom_proof_of_work() {
+    let options = build_proof_options(false);
+    let build_pub_inputs = || build_fib_inputs(&build_fib_trace(16));
+
+    // a proof generated with proof-of-work disabled verifies only if proof-of-work is disabled
+    // for the verifier as well
+    let proof = crate::Prover::<FibAir>::new(options.clone())
+        .with_pow(&NoProofOfWork)
+        .prove(build_fib_trace(16), build_pub_inputs())
+        .unwrap();
+    assert_eq!(0, proof.pow_nonce);
+    let mut scratch = verifier::VerifierScratch::new();
+    let result = verifier::verify_with_pow::<FibAir>(
+        proof.clone(),
+        build_pub_inputs(),
+        &NoProofOfWork,
+        &mut scratch,
+    );
+    assert!(result.is_ok());
+    assert!(verifier::verify::<FibAir>(proof, build_pub_inputs()).is_err());
+
+    // and a proof generated with the default proof-of-work is rejected by such a verifier
+    let proof = prove_fib(16, options);
+    let result = verifier::verify_with_pow::<FibAir>(
+        proof,
+        build_pub_inputs(),
+        &NoProofOfWork,
+        &mut scratch,
+    );
+    assert!(result.is_err());
+}
@@ -0,0 +1,59 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Low-degree extension of columns.
+//!
+//! The prover extends every register of an execution trace by interpolating the register trace
+//! into a polynomial over the trace domain, and then evaluating this polynomial over the LDE
+//! domain. The functions in this module perform the same procedure for arbitrary columns, so
+//! that data outside of the execution trace can be extended consistently with the prover.
+
+use math::{fft, field::StarkField};
+
+#[cfg(test)]
+mod tests;
+
+// LOW-DEGREE EXTENSION
+// ================================================================================================
+
+/// Returns the low-degree extension of the `column` by the `blowup` factor.
+///
+/// The column is interpolated into a polynomial over the multiplicative subgroup of size
+/// `column.len()`, and the polynomial is evaluated over the subgroup of size
+/// `column.len() * blowup` shifted by the `offset`. To reproduce the extension of the execution
+/// trace computed by the prover, `blowup` must be set to the blowup factor of the proof options,
+/// and `offset` must be set to the domain offset returned by
+/// [ProofOptions::domain_offset()](crate::ProofOptions::domain_offset).
+///
+/// # Panics
+/// Panics if:
+/// * Length of the `column` is not a power of two or is smaller than 2.
+/// * `blowup` is not a power of two.
+/// * The base field does not have a multiplicative subgroup of size `column.len() * blowup`.
+pub fn extend<B: StarkField>(column: &[B], blowup: usize, offset: B) -> Vec<B> {
+    assert!(
+        column.len().is_power_of_two() && column.len() > 1,
+        "column length must be a power of 2 greater than 1, but was {}",
+        column.len()
+    );
+    assert!(
+        blowup.is_power_of_two(),
+        "blowup factor must be a power of 2, but was {}",
+        blowup
+    );
+    let inv_twiddles = fft::get_inv_twiddles::<B>(column.len());
+    let twiddles = fft::get_twiddles::<B>(column.len());
+
+    // the extension is computed in a single buffer; the column is placed at the start of it
+    let mut values = vec![B::ZERO; column.len() * blowup];
+    values[..column.len()].copy_from_slice(column);
+
+    // interpolate values into a polynomial; we do this over the un-shifted domain
+    fft::interpolate_poly(&mut values[..column.len()], &inv_twiddles);
+
+    // evaluate the polynomial over extended domain; the domain may be shifted by the offset
+    fft::evaluate_poly_in_place(&mut values, &twiddles, offset, blowup);
+    values
+}
@@ -0,0 +1,62 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    monolith::StarkDomain,
+    tests::{build_context, build_fib_trace},
+};
+use math::{
+    fft,
+    field::{f128::BaseElement, FieldElement, StarkField},
+    polynom,
+    utils::get_power_series_with_offset_unchecked,
+};
+
+#[test]
+fn extend_column() {
+    let column: Vec<BaseElement> = (0..8u32).map(BaseElement::from).collect();
+    let offset = BaseElement::GENERATOR;
+    let extended = super::extend(&column, 4, offset);
+    assert_eq!(32, extended.len());
+
+    // the extension is an evaluation of the column's interpolant over the shifted domain
+    let mut poly = column.clone();
+    fft::interpolate_poly(&mut poly, &fft::get_inv_twiddles::<BaseElement>(8));
+    let g = BaseElement::get_root_of_unity(5).unwrap();
+    let domain = get_power_series_with_offset_unchecked(g, offset, 32);
+    assert_eq!(polynom::eval_many(&poly, &domain), extended);
+
+    // every blowup-th value of the un-shifted extension is a value of the original column
+    let extended = super::extend(&column, 4, BaseElement::ONE);
+    let expected: Vec<BaseElement> = extended.iter().step_by(4).cloned().collect();
+    assert_eq!(column, expected);
+}
+
+#[test]
+fn extend_column_matches_trace_extension() {
+    let trace_length = 8;
+    let context = build_context(trace_length, 2, 4);
+    let trace = build_fib_trace(trace_length * 2);
+    let column = trace.get_register(0).to_vec();
+    let domain = StarkDomain::new(&context);
+    let (extended_trace, _) = trace.extend(&domain);
+
+    let extended = super::extend(
+        &column,
+        context.options().blowup_factor(),
+        context.domain_offset(),
+    );
+    let expected: Vec<BaseElement> = (0..extended_trace.len())
+        .map(|step| extended_trace.get(0, step))
+        .collect();
+    assert_eq!(expected, extended);
+}
+
+#[test]
+#[should_panic(expected = "column length must be a power of 2 greater than 1, but was 6")]
+fn extend_column_invalid_length() {
+    let column = vec![BaseElement::ONE; 6];
+    super::extend(&column, 4, BaseElement::GENERATOR);
+}
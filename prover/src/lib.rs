@@ -4,14 +4,47 @@
 // LICENSE file in the root directory of this source tree.
 
 mod monolith;
-pub use monolith::{prove, ExecutionTrace, ExecutionTraceFragment};
+pub use monolith::{
+    commit_trace, prove, prove_committed, AirCache, CommittedTrace, ExecutionTrace,
+    ExecutionTraceFragment, Prover, TraceWord,
+};
+
+pub mod lde;
+
+mod aux_segment;
+pub use aux_segment::AuxSegmentBuilder;
+
+mod blinding;
+pub use blinding::{BlindedOpening, SaltedBlinder, TraceBlinder};
 
 mod channel;
 
+mod config;
+pub use config::ProverConfig;
+
+mod grinding;
+pub use grinding::{grinding_factor_for_budget, measure_hash_rate};
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+mod observer;
+pub use observer::{NullObserver, ProverObserver};
+
+#[cfg(feature = "encoding")]
+pub use common::{errors::EncodingError, TextEncoding};
 pub use common::{
-    proof::StarkProof, Air, Assertion, ComputationContext, EvaluationFrame, FieldExtension,
-    HashFunction, ProofOptions, TraceInfo, TransitionConstraintDegree, TransitionConstraintGroup,
+    errors::{ProverError, ProvingPhase, TraceBufferError},
+    limits,
+    proof::{Commitments, StarkProof, TraceChunkOpening},
+    Air, Assertion, ByteEncoding, ByteReader, ByteWriter, CeBlowupReport, CoefficientScheme,
+    ComputationContext, CostModel, Deserializable, DeserializationError, EvaluationFrame,
+    FieldExtension, HashFunction, HashProofOfWork, HashedPublicInputs, NoProofOfWork,
+    ParameterCandidate, ParameterSearch, PeriodicColumn, ProofOfWork, ProofOptions, ProvingCost,
+    Serializable, SliceReader, TraceInfo, TraceRowHashing, TransitionConstraintDegree,
+    TransitionConstraintGroup, VerificationCost,
 };
+
 pub use crypto;
 pub use math;
 
@@ -0,0 +1,222 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    constraints::{DivisorTable, PeriodicValueTable},
+    StarkDomain,
+};
+use common::{ComputationContext, ConstraintDivisor};
+use math::field::StarkField;
+use std::sync::Arc;
+
+// AIR CACHE
+// ================================================================================================
+
+/// Static data derived from an AIR which can be re-used across multiple proofs.
+///
+/// Building a STARK domain, evaluating periodic columns over the constraint evaluation domain,
+/// computing degree adjustment factors for groups of transition constraints, and building
+/// inverse divisor tables does not depend on the execution trace. When many instances of the
+/// same computation are proven with traces of the same length, passing the same cache to
+/// [Prover::with_cache()](crate::Prover::with_cache) lets the prover build this data once and
+/// re-use it for all subsequent proofs.
+///
+/// A cache is tied to a specific set of domain parameters: trace length, sizes of the constraint
+/// evaluation and LDE domains, and domain offset. If a proof is generated with different
+/// parameters, the cache is cleared and re-populated for the new parameters. Periodic columns
+/// and divisors may depend on public inputs; cached values for these are re-used only if they
+/// were built for identical columns and divisors.
+///
+/// Using a cache does not affect the generated proofs: proofs generated with and without a cache
+/// are the same bit-for-bit.
+#[allow(clippy::type_complexity)]
+pub struct AirCache<B: StarkField> {
+    domain_key: Option<DomainKey<B>>,
+    domain: Option<Arc<StarkDomain<B>>>,
+    /// periodic column polynomials together with the table built from them
+    periodic_values: Option<(Arc<Vec<Vec<B>>>, Arc<PeriodicValueTable<B>>)>,
+    /// degree adjustments of transition constraint groups together with g^degree_adjustment
+    transition_xp_steps: Option<(Vec<u32>, Vec<B>)>,
+    divisor_tables: Vec<(ConstraintDivisor<B>, Arc<DivisorTable<B>>)>,
+    num_hits: usize,
+    num_misses: usize,
+}
+
+/// Parameters of the domains for which the data in a cache was built.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct DomainKey<B: StarkField> {
+    trace_length: usize,
+    ce_domain_size: usize,
+    lde_domain_size: usize,
+    domain_offset: B,
+}
+
+impl<B: StarkField> AirCache<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new empty cache.
+    pub fn new() -> Self {
+        AirCache {
+            domain_key: None,
+            domain: None,
+            periodic_values: None,
+            transition_xp_steps: None,
+            divisor_tables: Vec::new(),
+            num_hits: 0,
+            num_misses: 0,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of times cached data was re-used.
+    pub fn num_hits(&self) -> usize {
+        self.num_hits
+    }
+
+    /// Returns the number of times data had to be built because it was not in the cache.
+    pub fn num_misses(&self) -> usize {
+        self.num_misses
+    }
+
+    /// Returns true if no data has been cached yet.
+    pub fn is_empty(&self) -> bool {
+        self.domain.is_none()
+            && self.periodic_values.is_none()
+            && self.transition_xp_steps.is_none()
+            && self.divisor_tables.is_empty()
+    }
+
+    // DATA MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Removes all cached data; hit and miss counters are not affected.
+    pub fn clear(&mut self) {
+        self.domain_key = None;
+        self.domain = None;
+        self.periodic_values = None;
+        self.transition_xp_steps = None;
+        self.divisor_tables.clear();
+    }
+
+    /// Makes sure the data in this cache was built for the domains described by the `context`;
+    /// if it wasn't, the cache is cleared.
+    pub(crate) fn prepare(&mut self, context: &ComputationContext) {
+        let key = DomainKey {
+            trace_length: context.trace_length(),
+            ce_domain_size: context.ce_domain_size(),
+            lde_domain_size: context.lde_domain_size(),
+            domain_offset: context.domain_offset(),
+        };
+        if self.domain_key != Some(key) {
+            self.clear();
+            self.domain_key = Some(key);
+        }
+    }
+
+    // CACHED DATA
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the STARK domain for the computation described by the `context`.
+    pub(crate) fn get_domain(&mut self, context: &ComputationContext) -> Arc<StarkDomain<B>> {
+        self.prepare(context);
+        match &self.domain {
+            Some(domain) => {
+                self.num_hits += 1;
+                domain.clone()
+            }
+            None => {
+                self.num_misses += 1;
+                let domain = Arc::new(StarkDomain::new(context));
+                self.domain = Some(domain.clone());
+                domain
+            }
+        }
+    }
+
+    /// Returns the table of periodic values built from the specified periodic column `polys`; if
+    /// the table is not in the cache, it is built using the `build` function.
+    ///
+    /// Polynomials shared with the cached ones (e.g., via the context of a computation) are
+    /// matched without comparing their coefficients.
+    pub(crate) fn get_periodic_values<F>(
+        &mut self,
+        polys: Arc<Vec<Vec<B>>>,
+        build: F,
+    ) -> Arc<PeriodicValueTable<B>>
+    where
+        F: FnOnce(&[Vec<B>]) -> PeriodicValueTable<B>,
+    {
+        match &self.periodic_values {
+            Some((cached_polys, table))
+                if Arc::ptr_eq(cached_polys, &polys) || *cached_polys == polys =>
+            {
+                self.num_hits += 1;
+                table.clone()
+            }
+            _ => {
+                self.num_misses += 1;
+                let table = Arc::new(build(&polys));
+                self.periodic_values = Some((polys, table.clone()));
+                table
+            }
+        }
+    }
+
+    /// Returns g^degree_adjustment for each of the specified `degree_adjustments`; if these are
+    /// not in the cache, they are computed using the `build` function.
+    pub(crate) fn get_transition_xp_steps<F>(
+        &mut self,
+        degree_adjustments: Vec<u32>,
+        build: F,
+    ) -> Vec<B>
+    where
+        F: FnOnce() -> Vec<B>,
+    {
+        match &self.transition_xp_steps {
+            Some((cached_adjustments, xp_steps)) if *cached_adjustments == degree_adjustments => {
+                self.num_hits += 1;
+                xp_steps.clone()
+            }
+            _ => {
+                self.num_misses += 1;
+                let xp_steps = build();
+                self.transition_xp_steps = Some((degree_adjustments, xp_steps.clone()));
+                xp_steps
+            }
+        }
+    }
+
+    /// Returns the inverse table for the specified `divisor`; if the table is not in the cache,
+    /// it is built using the `build` function.
+    pub(crate) fn get_divisor_table<F>(
+        &mut self,
+        divisor: &ConstraintDivisor<B>,
+        build: F,
+    ) -> Arc<DivisorTable<B>>
+    where
+        F: FnOnce() -> DivisorTable<B>,
+    {
+        match self.divisor_tables.iter().find(|(d, _)| d == divisor) {
+            Some((_, table)) => {
+                self.num_hits += 1;
+                table.clone()
+            }
+            None => {
+                self.num_misses += 1;
+                let table = Arc::new(build());
+                self.divisor_tables.push((divisor.clone(), table.clone()));
+                table
+            }
+        }
+    }
+}
+
+impl<B: StarkField> Default for AirCache<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
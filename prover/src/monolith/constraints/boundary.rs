@@ -86,27 +86,48 @@ impl<B: StarkField, E: FieldElement + From<B>> BoundaryConstraintGroup<B, E> {
         result
     }
 
-    /// Evaluates the constraints contained in this group at the specified step of the
-    /// execution trace.
-    pub fn evaluate(&self, state: &[B], ce_step: usize, x: B, xp: E) -> E {
-        let mut result = E::ZERO;
+    /// Evaluates the constraints contained in this group at a range of consecutive steps of the
+    /// constraint evaluation domain starting with `first_ce_step`, and saves the results into
+    /// `result`.
+    ///
+    /// `states` must contain trace states at all steps in the range stored one after another,
+    /// while `xs` and `xps` must contain domain values and degree adjustment factors for each
+    /// step respectively. The constraints are evaluated one at a time over all steps; this
+    /// way, values which depend only on the constraint (e.g., its coefficients) are read once
+    /// per range rather than once per step.
+    pub fn evaluate_over_domain(
+        &self,
+        states: &[B],
+        first_ce_step: usize,
+        xs: &[B],
+        xps: &[E],
+        result: &mut [E],
+    ) {
+        debug_assert_eq!(xs.len(), xps.len(), "inconsistent number of domain values");
+        debug_assert_eq!(xs.len(), result.len(), "inconsistent result length");
+        let width = states.len() / xs.len();
+        result.fill(E::ZERO);
 
         // evaluate all single-value constraints
         for constraint in self.single_value_constraints.iter() {
-            result += constraint.evaluate(state, xp);
+            for (i, (value, &xp)) in result.iter_mut().zip(xps).enumerate() {
+                *value += constraint.evaluate(&states[i * width..], xp);
+            }
         }
 
         // evaluate all small polynomial constraints
         for constraint in self.small_poly_constraints.iter() {
-            result += constraint.evaluate(state, x, xp);
+            for (i, (value, &xp)) in result.iter_mut().zip(xps).enumerate() {
+                *value += constraint.evaluate(&states[i * width..], xs[i], xp);
+            }
         }
 
         // evaluate all large polynomial constraints
         for constraint in self.large_poly_constraints.iter() {
-            result += constraint.evaluate(state, ce_step, xp);
+            for (i, (value, &xp)) in result.iter_mut().zip(xps).enumerate() {
+                *value += constraint.evaluate(&states[i * width..], first_ce_step + i, xp);
+            }
         }
-
-        result
     }
 }
 
@@ -5,10 +5,10 @@
 
 use common::{
     proof::Queries,
-    utils::{evaluations_per_leaf, map_trace_to_constraint_positions},
+    utils::{evaluations_per_leaf, get_leaf_range, map_trace_to_constraint_positions},
 };
 use crypto::{Hasher, MerkleTree};
-use math::field::FieldElement;
+use math::field::{ByteEncoding, FieldElement};
 use std::marker::PhantomData;
 use utils::{group_slice_elements, uninit_vector};
 
@@ -21,6 +21,7 @@ use rayon::prelude::*;
 pub struct ConstraintCommitment<E: FieldElement, H: Hasher> {
     evaluations: Vec<E>,
     commitment: MerkleTree,
+    encoding: ByteEncoding,
     _element: PhantomData<E>,
     _hasher: PhantomData<H>,
 }
@@ -28,8 +29,9 @@ pub struct ConstraintCommitment<E: FieldElement, H: Hasher> {
 impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
     /// Commits to the constraint evaluations by putting them into a Merkle tree; since
     /// evaluations for a specific step are compressed into a single field element, we try
-    /// to put multiple evaluations into a single leaf whenever possible.
-    pub fn new(evaluations: Vec<E>) -> ConstraintCommitment<E, H> {
+    /// to put multiple evaluations into a single leaf whenever possible. Evaluations are
+    /// encoded into leaves using the specified byte `encoding`.
+    pub fn new(evaluations: Vec<E>, encoding: ByteEncoding) -> ConstraintCommitment<E, H> {
         assert!(
             evaluations.len().is_power_of_two(),
             "number of values must be a power of 2"
@@ -38,9 +40,11 @@ impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
         // determine how many evaluations should go into a single leaf and hash them
         let evaluations_per_leaf = evaluations_per_leaf::<E, H>();
         let hashed_evaluations = match evaluations_per_leaf {
-            1 => hash_evaluations::<E, H, 1>(&evaluations),
-            2 => hash_evaluations::<E, H, 2>(&evaluations),
-            4 => hash_evaluations::<E, H, 4>(&evaluations),
+            1 => hash_evaluations::<E, H, 1>(&evaluations, encoding),
+            2 => hash_evaluations::<E, H, 2>(&evaluations, encoding),
+            4 => hash_evaluations::<E, H, 4>(&evaluations, encoding),
+            8 => hash_evaluations::<E, H, 8>(&evaluations, encoding),
+            16 => hash_evaluations::<E, H, 16>(&evaluations, encoding),
             _ => panic!(
                 "invalid number of evaluations per leaf: {}",
                 evaluations_per_leaf
@@ -51,6 +55,7 @@ impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
         ConstraintCommitment {
             evaluations,
             commitment: MerkleTree::new(hashed_evaluations, H::hash_fn()),
+            encoding,
             _element: PhantomData,
             _hasher: PhantomData,
         }
@@ -67,8 +72,9 @@ impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
     }
 
     /// Returns constraint evaluations at the specified positions along with Merkle
-    /// authentication paths from the root of the commitment to these evaluations.
-    pub fn query(self, trace_positions: &[usize]) -> Queries {
+    /// authentication paths from the root of the commitment to these evaluations. The
+    /// commitment is not consumed, and thus, can be queried again.
+    pub fn query(&self, trace_positions: &[usize]) -> Queries {
         // first, map trace positions to the corresponding positions in the constraint tree;
         // we do this because multiple constraint evaluations may be stored in a single leaf
         let evaluations_per_leaf = evaluations_per_leaf::<E, H>();
@@ -78,23 +84,25 @@ impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
         // build Merkle authentication paths to the leaves specified by constraint positions
         let merkle_proof = self.commitment.prove_batch(&constraint_positions);
 
-        // determine a set of evaluations corresponding to each position
-        let mut evaluations = Vec::new();
-        for position in constraint_positions {
-            let start = position * evaluations_per_leaf;
-            evaluations.push(self.evaluations[start..start + evaluations_per_leaf].to_vec());
-        }
+        // determine a set of evaluations corresponding to each position; evaluations are
+        // borrowed directly from the committed evaluation vector
+        let evaluations = constraint_positions
+            .iter()
+            .map(|&position| &self.evaluations[get_leaf_range(position, evaluations_per_leaf)])
+            .collect::<Vec<_>>();
 
-        Queries::new(merkle_proof, evaluations)
+        Queries::new(merkle_proof, &evaluations, self.encoding)
     }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Computes hashes of evaluations grouped by N elements and returns the resulting hashes.
+/// Computes hashes of evaluations grouped by N elements and encoded using the specified byte
+/// `encoding`, and returns the resulting hashes.
 fn hash_evaluations<E: FieldElement, H: Hasher, const N: usize>(
     evaluations: &[E],
+    encoding: ByteEncoding,
 ) -> Vec<[u8; 32]> {
     let evaluations = group_slice_elements::<E, N>(evaluations);
 
@@ -103,14 +111,14 @@ fn hash_evaluations<E: FieldElement, H: Hasher, const N: usize>(
 
     #[cfg(not(feature = "concurrent"))]
     for (result, evaluations) in result.iter_mut().zip(evaluations.iter()) {
-        hash_fn(E::elements_as_bytes(evaluations), result);
+        hash_fn(&encoding.encode_elements(evaluations), result);
     }
     #[cfg(feature = "concurrent")]
     result
         .par_iter_mut()
         .zip(evaluations.par_iter())
         .for_each(|(result, evaluations)| {
-            hash_fn(E::elements_as_bytes(evaluations), result);
+            hash_fn(&encoding.encode_elements(evaluations), result);
         });
 
     result
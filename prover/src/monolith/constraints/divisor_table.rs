@@ -0,0 +1,172 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::ConstraintDivisor;
+use core::cmp;
+use math::{
+    field::{FieldElement, StarkField},
+    utils::{batch_inversion, log2_unchecked},
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Minimum number of evaluations which are divided by the divisor in a single batch.
+const MIN_BATCH_SIZE: usize = 64;
+
+// DIVISOR TABLE
+// ================================================================================================
+
+/// Inverse of a constraint divisor evaluated over the constraint evaluation domain.
+///
+/// The constraint evaluation domain is a coset s * <g> of a multiplicative subgroup of size N.
+/// For a divisor with numerator x^n - a, where n divides N, values of x^n over the domain repeat
+/// with a period of N / n: (s * g^(i + N / n))^n = (s * g^i)^n. Thus, only N / n inverses of the
+/// numerator need to be computed; these are computed using a single batch inversion. Exemption
+/// points of a divisor are handled by multiplying by (x - e), which does not require inversion.
+pub struct DivisorTable<B: StarkField> {
+    inv_numerator: Vec<B>,
+    exemption: Option<B>,
+    domain_offset: B,
+    domain_generator: B,
+}
+
+impl<B: StarkField> DivisorTable<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Builds a table of inverse values of the `divisor` over the constraint evaluation domain of
+    /// the specified size and offset.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * the divisor has more than one numerator term or more than one exemption point;
+    /// * the degree of the numerator does not divide the size of the domain;
+    /// * the numerator evaluates to zero at any point of the domain.
+    pub fn new(divisor: &ConstraintDivisor<B>, domain_size: usize, domain_offset: B) -> Self {
+        let numerator = divisor.numerator();
+        assert!(
+            numerator.len() == 1,
+            "complex divisors are not yet supported"
+        );
+        assert!(
+            divisor.exclude().len() <= 1,
+            "multiple exclusion points are not yet supported"
+        );
+        let (degree, a) = numerator[0];
+        assert!(
+            domain_size.checked_rem(degree) == Some(0),
+            "divisor degree {} does not divide domain size {}",
+            degree,
+            domain_size
+        );
+
+        // compute values of x^n - a over a single period of the domain
+        let domain_generator = B::get_root_of_unity(log2_unchecked(domain_size))
+            .expect("constraint evaluation domain must fit into the field");
+        let period = domain_size / degree;
+        let step = domain_generator.exp((degree as u64).into());
+        let mut xn = domain_offset.exp((degree as u64).into());
+        let mut values = Vec::with_capacity(period);
+        for _ in 0..period {
+            values.push(xn - a);
+            xn *= step;
+        }
+        assert!(
+            values.iter().all(|&v| v != B::ZERO),
+            "divisor evaluates to zero over the constraint evaluation domain"
+        );
+
+        DivisorTable {
+            inv_numerator: batch_inversion(&values),
+            exemption: divisor.exclude().first().copied(),
+            domain_offset,
+            domain_generator,
+        }
+    }
+
+    // PUBLIC METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Divides `evaluations` over the constraint evaluation domain by the divisor in place.
+    pub fn divide<E: FieldElement + From<B>>(&self, evaluations: &mut [E]) {
+        let period = self.inv_numerator.len();
+        match self.exemption {
+            None => {
+                // periods are usually short; to multiply evaluations by the inverses in batches,
+                // the inverses are repeated until they fill at least MIN_BATCH_SIZE elements
+                let batch_size = cmp::max(period, MIN_BATCH_SIZE);
+                let inv_numerator = self
+                    .inv_numerator
+                    .iter()
+                    .cycle()
+                    .take(batch_size)
+                    .map(|&inv| E::from(inv))
+                    .collect::<Vec<_>>();
+                for chunk in evaluations.chunks_mut(batch_size) {
+                    E::batch_mul(chunk, &inv_numerator[..chunk.len()]);
+                }
+            }
+            Some(exemption) => {
+                let mut x = self.domain_offset;
+                for chunk in evaluations.chunks_mut(period) {
+                    for (value, &inv) in chunk.iter_mut().zip(self.inv_numerator.iter()) {
+                        *value *= E::from(inv * (x - exemption));
+                        x *= self.domain_generator;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use common::ConstraintDivisor;
+    use math::{
+        field::{f128::BaseElement, FieldElement, StarkField},
+        utils::{get_power_series_with_offset_unchecked, log2_unchecked},
+    };
+
+    #[test]
+    fn divisor_table() {
+        let domain_size = 64;
+        let domain_offset = BaseElement::GENERATOR;
+        let domain = build_ce_domain(domain_size, domain_offset);
+        let g = BaseElement::get_root_of_unity(log2_unchecked(8)).unwrap();
+
+        let divisors = [
+            // transition divisor for a trace of length 8
+            ConstraintDivisor::new(vec![(8, BaseElement::ONE)], vec![g.inv()]),
+            // periodic assertion divisor
+            ConstraintDivisor::new(vec![(4, BaseElement::new(5))], vec![]),
+            // single-step assertion divisor
+            ConstraintDivisor::new(vec![(1, g)], vec![]),
+        ];
+
+        for divisor in divisors.iter() {
+            let values = (0..domain_size as u128)
+                .map(BaseElement::new)
+                .collect::<Vec<_>>();
+            let expected = values
+                .iter()
+                .zip(domain.iter())
+                .map(|(&v, &x)| v / divisor.evaluate_at(x))
+                .collect::<Vec<_>>();
+
+            let table = super::DivisorTable::new(divisor, domain_size, domain_offset);
+            let mut actual = values;
+            table.divide(&mut actual);
+            assert_eq!(expected, actual);
+        }
+    }
+
+    fn build_ce_domain(domain_size: usize, domain_offset: BaseElement) -> Vec<BaseElement> {
+        let g = BaseElement::get_root_of_unity(log2_unchecked(domain_size)).unwrap();
+        get_power_series_with_offset_unchecked(g, domain_offset, domain_size)
+    }
+}
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{ConstraintPoly, StarkDomain};
+use super::{AirCache, ConstraintPoly, DivisorTable, StarkDomain};
 use common::{errors::ProverError, ConstraintDivisor};
 use math::{
     fft,
@@ -11,6 +11,7 @@ use math::{
     polynom,
     utils::add_in_place,
 };
+use std::sync::Arc;
 use utils::uninit_vector;
 
 #[cfg(feature = "concurrent")]
@@ -107,7 +108,7 @@ impl<B: StarkField, E: FieldElement + From<B>> ConstraintEvaluationTable<B, E> {
     /// In concurrent mode, we break the table into fragments and update each fragment in
     /// separate threads.
     #[cfg(feature = "concurrent")]
-    pub fn fragments(&mut self, num_fragments: usize) -> Vec<TableFragment<E>> {
+    pub fn fragments(&mut self, num_fragments: usize) -> Vec<TableFragment<B, E>> {
         let fragment_size = self.num_rows() / num_fragments;
         assert!(
             fragment_size >= MIN_FRAGMENT_SIZE,
@@ -123,65 +124,116 @@ impl<B: StarkField, E: FieldElement + From<B>> ConstraintEvaluationTable<B, E> {
             }
         });
 
-        fragment_data
+        // in debug mode, also break transition constraint evaluations into fragments
+        #[cfg(debug_assertions)]
+        let mut t_fragment_data = (0..num_fragments).map(|_| Vec::new()).collect::<Vec<_>>();
+        #[cfg(debug_assertions)]
+        self.t_evaluations.iter_mut().for_each(|column| {
+            for (i, fragment) in column.chunks_mut(fragment_size).enumerate() {
+                t_fragment_data[i].push(fragment);
+            }
+        });
+
+        #[cfg(debug_assertions)]
+        let fragments = fragment_data
+            .into_iter()
+            .zip(t_fragment_data)
+            .enumerate()
+            .map(|(i, (data, t_data))| TableFragment {
+                offset: i * fragment_size,
+                data,
+                t_data,
+            })
+            .collect();
+
+        #[cfg(not(debug_assertions))]
+        let fragments = fragment_data
             .into_iter()
             .enumerate()
             .map(|(i, data)| TableFragment {
                 offset: i * fragment_size,
                 data,
+                _base_field: core::marker::PhantomData,
             })
-            .collect()
+            .collect();
+
+        fragments
     }
 
     // CONSTRAINT COMPOSITION
     // --------------------------------------------------------------------------------------------
-    /// Interpolates all constraint evaluations into polynomials, divides them by their respective
-    /// divisors, and combines the results into a single polynomial
-    pub fn into_poly(self) -> Result<ConstraintPoly<E>, ProverError> {
+    /// Divides all constraint evaluations by their respective divisors, combines the results, and
+    /// interpolates the combined evaluations into a single polynomial.
+    ///
+    /// Division is done in evaluation form using tables of inverse divisor values; columns with
+    /// the same divisor share the same table. This works because the constraint evaluation
+    /// domain is a coset which does not intersect the trace domain, and thus, divisors do not
+    /// evaluate to zero over it. Since quotients are combined before interpolation, only a
+    /// single interpolation is required regardless of the number of columns.
+    ///
+    /// Divisor tables are taken from the `cache` if available, and are added to it otherwise.
+    pub fn into_poly(self, cache: &mut AirCache<B>) -> Result<ConstraintPoly<E>, ProverError> {
         let constraint_poly_degree = self.constraint_poly_degree();
         let domain_offset = self.domain_offset;
+        let num_rows = self.num_rows();
+
+        // get inverse divisor tables, one per distinct divisor
+        let mut tables: Vec<(&ConstraintDivisor<B>, Arc<DivisorTable<B>>)> = Vec::new();
+        let mut column_tables = Vec::with_capacity(self.divisors.len());
+        for divisor in self.divisors.iter() {
+            let table_idx = match tables.iter().position(|(d, _)| *d == divisor) {
+                Some(table_idx) => table_idx,
+                None => {
+                    let table = cache.get_divisor_table(divisor, || {
+                        DivisorTable::new(divisor, num_rows, domain_offset)
+                    });
+                    tables.push((divisor, table));
+                    tables.len() - 1
+                }
+            };
+            column_tables.push(table_idx);
+        }
 
-        // allocate memory for the combined polynomial
-        let mut combined_poly = E::zeroed_vector(self.num_rows());
-
-        // build twiddles for interpolation; these can be used to interpolate all polynomials
-        let inv_twiddles = fft::get_inv_twiddles::<B>(self.num_rows());
+        // divide evaluations in each column by the divisor of the column
+        let mut columns = self.evaluations;
 
         #[cfg(feature = "concurrent")]
-        {
-            let divisors = self.divisors;
-            let polys = self
-                .evaluations
-                .into_par_iter()
-                .zip(divisors.par_iter())
-                .map(|(column, divisor)| {
-                    apply_divisor(column, divisor, &inv_twiddles, domain_offset)
-                })
-                .collect::<Vec<_>>();
-
-            for poly in polys.into_iter() {
-                #[cfg(debug_assertions)]
-                validate_degree(&poly, constraint_poly_degree)?;
-                add_in_place(&mut combined_poly, &poly);
-            }
-        }
+        columns
+            .par_iter_mut()
+            .zip(column_tables.par_iter())
+            .for_each(|(column, &table_idx)| tables[table_idx].1.divide(column));
 
-        // iterate over all columns of the constraint evaluation table
         #[cfg(not(feature = "concurrent"))]
-        for (column, divisor) in self.evaluations.into_iter().zip(self.divisors.iter()) {
-            let poly = apply_divisor(column, divisor, &inv_twiddles, domain_offset);
-            #[cfg(debug_assertions)]
+        for (column, &table_idx) in columns.iter_mut().zip(column_tables.iter()) {
+            tables[table_idx].1.divide(column);
+        }
+
+        // build twiddles for interpolation; these can be used to interpolate all polynomials
+        let inv_twiddles = fft::get_inv_twiddles::<B>(num_rows);
+
+        // when in debug mode, make sure that each quotient has the expected degree
+        #[cfg(debug_assertions)]
+        for column in columns.iter() {
+            let mut poly = column.clone();
+            fft::interpolate_poly_with_offset(&mut poly, &inv_twiddles, domain_offset);
             validate_degree(&poly, constraint_poly_degree)?;
-            add_in_place(&mut combined_poly, &poly);
         }
 
+        // combine all quotients and interpolate them into a single polynomial
+        let mut columns = columns.into_iter();
+        let mut combined_poly = columns.next().expect("no constraint evaluation columns");
+        for column in columns {
+            add_in_place(&mut combined_poly, &column);
+        }
+        fft::interpolate_poly_with_offset(&mut combined_poly, &inv_twiddles, domain_offset);
+
         Ok(ConstraintPoly::new(combined_poly, constraint_poly_degree))
     }
 
     // DEBUG HELPERS
     // --------------------------------------------------------------------------------------------
 
-    #[cfg(all(debug_assertions, not(feature = "concurrent")))]
+    #[cfg(debug_assertions)]
     pub fn update_transition_evaluations(&mut self, row_idx: usize, row_data: &[B]) {
         for (column, &value) in self.t_evaluations.iter_mut().zip(row_data) {
             column[row_idx] = value;
@@ -238,13 +290,17 @@ impl<B: StarkField, E: FieldElement + From<B>> ConstraintEvaluationTable<B, E> {
 // ================================================================================================
 
 #[cfg(feature = "concurrent")]
-pub struct TableFragment<'a, E: FieldElement> {
+pub struct TableFragment<'a, B: StarkField, E: FieldElement> {
     offset: usize,
     data: Vec<&'a mut [E]>,
+    #[cfg(debug_assertions)]
+    t_data: Vec<&'a mut [B]>,
+    #[cfg(not(debug_assertions))]
+    _base_field: core::marker::PhantomData<B>,
 }
 
 #[cfg(feature = "concurrent")]
-impl<'a, E: FieldElement> TableFragment<'a, E> {
+impl<'a, B: StarkField, E: FieldElement> TableFragment<'a, B, E> {
     /// Returns the row at which the fragment starts.
     pub fn offset(&self) -> usize {
         self.offset
@@ -267,49 +323,20 @@ impl<'a, E: FieldElement> TableFragment<'a, E> {
             column[row_idx] = value;
         }
     }
+
+    /// Updates transition constraint evaluations in a single row of the fragment; this is used
+    /// in debug mode only to validate transition constraint degrees.
+    #[cfg(debug_assertions)]
+    pub fn update_transition_evaluations(&mut self, row_idx: usize, row_data: &[B]) {
+        for (column, &value) in self.t_data.iter_mut().zip(row_data) {
+            column[row_idx] = value;
+        }
+    }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
-fn apply_divisor<B: StarkField, E: FieldElement + From<B>>(
-    mut column: Vec<E>,
-    divisor: &ConstraintDivisor<B>,
-    inv_twiddles: &[B],
-    domain_offset: B,
-) -> Vec<E> {
-    let numerator = divisor.numerator();
-    assert!(
-        numerator.len() == 1,
-        "complex divisors are not yet supported"
-    );
-    assert!(
-        divisor.exclude().len() <= 1,
-        "multiple exclusion points are not yet supported"
-    );
-
-    // convert the polynomial into coefficient form by interpolating the evaluations
-    // over the evaluation domain
-    fft::interpolate_poly_with_offset(&mut column, &inv_twiddles, domain_offset);
-    let mut poly = column;
-
-    // divide the polynomial by its divisor
-    let numerator = numerator[0];
-    let degree = numerator.0;
-
-    if divisor.exclude().is_empty() {
-        // the form of the divisor is just (x^degree - a)
-        let a = E::from(numerator.1);
-        polynom::syn_div_in_place(&mut poly, degree, a);
-    } else {
-        // the form of divisor is (x^degree - 1) / (x - exception)
-        let exception = E::from(divisor.exclude()[0]);
-        polynom::syn_div_in_place_with_exception(&mut poly, degree, exception);
-    }
-
-    poly
-}
-
 /// makes sure that the post-division degree of the polynomial matches the expected degree
 #[cfg(debug_assertions)]
 fn validate_degree<E: FieldElement>(
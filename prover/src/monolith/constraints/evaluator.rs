@@ -4,11 +4,18 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    BoundaryConstraintGroup, ConstraintEvaluationTable, PeriodicValueTable, StarkDomain, TraceTable,
+    AirCache, BoundaryConstraintGroup, ConstraintEvaluationTable, ConstraintProfile, ExtendedTrace,
+    PeriodicValueTable, StarkDomain, TransitionConstraintStats,
 };
 use common::{Air, ConstraintDivisor, EvaluationFrame, PublicCoin, TransitionConstraintGroup};
-use math::field::FieldElement;
-use std::collections::HashMap;
+use core::cmp;
+use math::{
+    fft,
+    field::{FieldElement, ProfiledElement, StarkField},
+    polynom,
+    utils::log2_unchecked,
+};
+use std::{collections::HashMap, sync::Arc};
 
 #[cfg(feature = "concurrent")]
 use rayon::prelude::*;
@@ -18,6 +25,18 @@ use rayon::prelude::*;
 
 const MIN_CONCURRENT_DOMAIN_SIZE: usize = 8192;
 
+/// Number of consecutive steps of the constraint evaluation domain over which boundary
+/// constraints are evaluated at once.
+const BATCH_SIZE: usize = 64;
+
+/// Number of rows of transition constraint evaluations kept in evaluation buffers. In debug
+/// mode, evaluations at all steps of a batch are kept so that their degrees can be validated;
+/// otherwise, evaluations are merged as soon as they are computed, and a single row is re-used.
+#[cfg(debug_assertions)]
+const NUM_T_ROWS: usize = BATCH_SIZE;
+#[cfg(not(debug_assertions))]
+const NUM_T_ROWS: usize = 1;
+
 // CONSTRAINT EVALUATOR
 // ================================================================================================
 
@@ -25,11 +44,17 @@ pub struct ConstraintEvaluator<A: Air, E: FieldElement + From<A::BaseElement>> {
     air: A,
     boundary_constraints: Vec<BoundaryConstraintGroup<A::BaseElement, E>>,
     transition_constraints: Vec<TransitionConstraintGroup<E>>,
-    periodic_values: PeriodicValueTable<A::BaseElement>,
+    /// g^degree_adjustment for every transition constraint group, where g is the generator of
+    /// the constraint evaluation domain.
+    transition_xp_steps: Vec<A::BaseElement>,
+    periodic_values: Arc<PeriodicValueTable<A::BaseElement>>,
     divisors: Vec<ConstraintDivisor<A::BaseElement>>,
 
     #[cfg(debug_assertions)]
     transition_constraint_degrees: Vec<usize>,
+    /// registers whose next-state values must not be read by transition constraints
+    #[cfg(debug_assertions)]
+    local_registers: Vec<usize>,
 }
 
 impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
@@ -37,7 +62,10 @@ impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
     // --------------------------------------------------------------------------------------------
     /// Returns a new evaluator which can be used to evaluate transition and boundary constraints
     /// over extended execution trace.
-    pub fn new<C: PublicCoin>(air: A, coin: &C) -> Self {
+    ///
+    /// Data which does not depend on the execution trace (i.e., degree adjustment factors and
+    /// periodic values) is taken from the `cache` if available, and is added to it otherwise.
+    pub fn new<C: PublicCoin>(air: A, coin: &C, cache: &mut AirCache<A::BaseElement>) -> Self {
         // collect expected degrees for all transition constraints to compare them against actual
         // degrees; we do this in debug mode only because this comparison is expensive
         #[cfg(debug_assertions)]
@@ -48,18 +76,54 @@ impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
             .map(|d| d.get_evaluation_degree(air.context().trace_length()))
             .collect();
 
+        // collect local registers so that we can check that transition constraints do not read
+        // their next-state values; the verifier sets these values to zeros at the OOD point
+        #[cfg(debug_assertions)]
+        let local_registers = {
+            let next_state_registers = air.context().next_state_registers();
+            (0..air.context().trace_width())
+                .filter(|r| !next_state_registers.contains(r))
+                .collect()
+        };
+
         // build transition constraint groups; these will be used later to compute a random
         // linear combination of transition constraint evaluations.
-        let transition_constraints =
+        let transition_constraints: Vec<TransitionConstraintGroup<E>> =
             air.get_transition_constraints(coin.get_transition_coefficient_prng());
 
-        // build periodic value table
-        let periodic_values = PeriodicValueTable::new(&air);
+        // degree adjustment factors of transition constraint groups are computed incrementally
+        // for consecutive steps of the constraint evaluation domain: since x_{i+1} = x_i * g,
+        // x_{i+1}^p = x_i^p * g^p
+        let degree_adjustments = transition_constraints
+            .iter()
+            .map(|group| group.degree_adjustment())
+            .collect::<Vec<_>>();
+        let transition_xp_steps = cache.get_transition_xp_steps(degree_adjustments.clone(), || {
+            let ce_domain_size = air.context().ce_domain_size();
+            let g = A::BaseElement::get_root_of_unity(log2_unchecked(ce_domain_size))
+                .expect("constraint evaluation domain must fit into the field");
+            degree_adjustments
+                .iter()
+                .map(|&adjustment| g.exp(adjustment.into()))
+                .collect()
+        });
+
+        // build periodic value table; the table is identified by polynomials of periodic columns
+        // because values of the columns may be supplied lazily. polynomials cached in the context
+        // are shared rather than copied; they are copied only if the AIR does not use the cache
+        let periodic_polys = air.get_periodic_column_polys();
+        let periodic_polys = match air.context().cached_periodic_column_polys() {
+            Some(polys) if std::ptr::eq(polys.as_slice(), periodic_polys) => polys,
+            _ => Arc::new(periodic_polys.to_vec()),
+        };
+        let periodic_values =
+            cache.get_periodic_values(periodic_polys, |polys| PeriodicValueTable::new(&air, polys));
 
         // set divisor for transition constraints; since divisors for all transition constraints
         // are the same: (x^steps - 1) / (x - x_at_last_step), all transition constraints will be
         // merged into a single value, and the divisor for that value will be first in the list
-        let mut divisors = vec![ConstraintDivisor::from_transition(air.context())];
+        let transition_divisor = air.context().constraint_layout().transition_divisor();
+        let mut divisors = vec![transition_divisor.clone()];
 
         // build boundary constraints and also append divisors for each group of boundary
         // constraints to the divisor list
@@ -77,10 +141,13 @@ impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
             air,
             boundary_constraints,
             transition_constraints,
+            transition_xp_steps,
             periodic_values,
             divisors,
             #[cfg(debug_assertions)]
             transition_constraint_degrees,
+            #[cfg(debug_assertions)]
+            local_registers,
         }
     }
 
@@ -91,7 +158,7 @@ impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
     /// constraint evaluation domain can be many times smaller than the full LDE domain.
     pub fn evaluate(
         &self,
-        trace: &TraceTable<A::BaseElement>,
+        trace: &ExtendedTrace<A::BaseElement>,
         domain: &StarkDomain<A::BaseElement>,
     ) -> ConstraintEvaluationTable<A::BaseElement, E> {
         assert_eq!(
@@ -130,44 +197,107 @@ impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
         evaluation_table
     }
 
+    /// Evaluates transition constraints against the provided extended execution trace at every
+    /// step of the constraint evaluation domain, and collects statistics about the evaluations.
+    ///
+    /// Constraints are evaluated over [ProfiledElement]s so that the number of field operations
+    /// needed to evaluate each constraint can be counted; actual degrees of constraints are
+    /// determined by interpolating their evaluations. This is much slower than [evaluate()], and
+    /// is intended only for finding redundant constraints and over-declared degrees.
+    ///
+    /// [evaluate()]: ConstraintEvaluator::evaluate
+    pub fn profile(
+        &self,
+        trace: &ExtendedTrace<A::BaseElement>,
+        domain: &StarkDomain<A::BaseElement>,
+    ) -> ConstraintProfile {
+        let context = self.air.context();
+        let num_constraints = self.air.num_transition_constraints();
+        let ce_domain_size = domain.ce_domain_size();
+
+        let mut frame = EvaluationFrame::<A::BaseElement>::new(trace.width());
+        let mut periodic_row = A::BaseElement::zeroed_vector(self.periodic_values.width());
+        let mut results = vec![ProfiledElement::ZERO; num_constraints];
+
+        let mut evaluations = vec![Vec::with_capacity(ce_domain_size); num_constraints];
+        let mut num_zeros = vec![0usize; num_constraints];
+        let mut num_ops = vec![0u64; num_constraints];
+        for step in 0..ce_domain_size {
+            let lde_step = domain.ce_step_to_lde_info(step).0;
+            trace.read_frame_into(lde_step, &mut frame);
+            let profiled_frame = EvaluationFrame {
+                current: to_profiled(&frame.current),
+                next: to_profiled(&frame.next),
+            };
+            self.periodic_values
+                .read_rows_into(step, 1, &mut periodic_row);
+            let periodic_values = to_profiled(&periodic_row);
+
+            results.fill(ProfiledElement::ZERO);
+            self.air
+                .evaluate_transition(&profiled_frame, &periodic_values, &mut results);
+
+            for (i, result) in results.iter().enumerate() {
+                if result.value() == A::BaseElement::ZERO {
+                    num_zeros[i] += 1;
+                }
+                num_ops[i] = num_ops[i].saturating_add(result.ops());
+                evaluations[i].push(result.value());
+            }
+        }
+
+        // determine actual degrees of constraints by interpolating their evaluations
+        let inv_twiddles = fft::get_inv_twiddles::<A::BaseElement>(ce_domain_size);
+        let constraints = evaluations
+            .into_iter()
+            .zip(context.transition_constraint_degrees())
+            .enumerate()
+            .map(|(i, (mut poly, degree))| {
+                fft::interpolate_poly(&mut poly, &inv_twiddles);
+                TransitionConstraintStats {
+                    zero_fraction: num_zeros[i] as f64 / ce_domain_size as f64,
+                    avg_cost: num_ops[i] as f64 / ce_domain_size as f64,
+                    declared_degree: degree.get_evaluation_degree(context.trace_length()),
+                    actual_degree: polynom::degree_of(&poly),
+                }
+            })
+            .collect();
+
+        ConstraintProfile::new(constraints)
+    }
+
     // EVALUATION HELPERS
     // --------------------------------------------------------------------------------------------
 
     /// Evaluates the constraints in a single thread and saves the result into `evaluation_table`.
     pub fn evaluate_sequential(
         &self,
-        trace: &TraceTable<A::BaseElement>,
+        trace: &ExtendedTrace<A::BaseElement>,
         domain: &StarkDomain<A::BaseElement>,
         evaluation_table: &mut ConstraintEvaluationTable<A::BaseElement, E>,
     ) {
-        // initialize buffers to hold trace values and evaluation results at each step
-        let mut ev_frame = EvaluationFrame::new(trace.width());
-        let mut evaluations = vec![E::ZERO; evaluation_table.num_columns()];
-        let mut t_evaluations = vec![A::BaseElement::ZERO; self.air.num_transition_constraints()];
-
-        for step in 0..evaluation_table.num_rows() {
-            // translate steps in the constraint evaluation domain to steps in LDE domain
-            let (lde_step, x) = domain.ce_step_to_lde_info(step);
-
-            // update evaluation frame buffer with data from the execution trace; this will
-            // read current and next rows from the trace into the buffer
-            trace.read_frame_into(lde_step, &mut ev_frame);
-
-            // evaluate transition constraints and save the merged result the first slot of the
-            // evaluations buffer
-            evaluations[0] =
-                self.evaluate_transition_constraints(&ev_frame, x, step, &mut t_evaluations);
+        // initialize buffers to hold trace values and evaluation results for a batch of steps
+        let mut buffers = EvaluationBuffers::new(
+            evaluation_table.num_columns(),
+            self.air.num_transition_constraints(),
+            self.transition_constraints.len(),
+            self.periodic_values.width(),
+        );
+        buffers.reuse(trace.width());
 
-            // when in debug mode, save transition constraint evaluations
-            #[cfg(all(debug_assertions, not(feature = "concurrent")))]
-            evaluation_table.update_transition_evaluations(step, &t_evaluations);
+        let num_rows = evaluation_table.num_rows();
+        for batch_start in (0..num_rows).step_by(BATCH_SIZE) {
+            let batch_size = cmp::min(BATCH_SIZE, num_rows - batch_start);
+            self.evaluate_batch(trace, domain, batch_start, batch_size, &mut buffers);
 
-            // evaluate boundary constraints; the results go into remaining slots of the
-            // evaluations buffer
-            self.evaluate_boundary_constraints(&ev_frame.current, x, step, &mut evaluations[1..]);
+            // record the results in the evaluation table
+            for i in 0..batch_size {
+                evaluation_table.update_row(batch_start + i, buffers.row(i));
 
-            // record the result in the evaluation table
-            evaluation_table.update_row(step, &evaluations);
+                // when in debug mode, save transition constraint evaluations
+                #[cfg(debug_assertions)]
+                evaluation_table.update_transition_evaluations(batch_start + i, buffers.t_row(i));
+            }
         }
     }
 
@@ -178,115 +308,378 @@ impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
     #[cfg(feature = "concurrent")]
     fn evaluate_concurrent(
         &self,
-        trace: &TraceTable<A::BaseElement>,
+        trace: &ExtendedTrace<A::BaseElement>,
         domain: &StarkDomain<A::BaseElement>,
         evaluation_table: &mut ConstraintEvaluationTable<A::BaseElement, E>,
     ) {
         let num_evaluation_columns = evaluation_table.num_columns();
         let num_fragments = rayon::current_num_threads().next_power_of_two();
 
+        // buffers are initialized once per rayon job rather than once per fragment; this way,
+        // a thread which processes several fragments re-uses the same buffers for all of them,
+        // and only prepares the buffers for the trace at the start of every fragment
+        let init_buffers = || {
+            EvaluationBuffers::new(
+                num_evaluation_columns,
+                self.air.num_transition_constraints(),
+                self.transition_constraints.len(),
+                self.periodic_values.width(),
+            )
+        };
+
         evaluation_table
             .fragments(num_fragments)
             .par_iter_mut()
-            .for_each(|fragment| {
-                // initialize buffers to hold trace values and evaluation results at each
-                // step; in concurrent mode we do this separately for each fragment
-                let mut ev_frame = EvaluationFrame::new(trace.width());
-                let mut evaluations = vec![E::ZERO; num_evaluation_columns];
-                let mut t_evaluations =
-                    vec![A::BaseElement::ZERO; self.air.num_transition_constraints()];
-
-                for i in 0..fragment.num_rows() {
-                    let step = i + fragment.offset();
-
-                    // translate steps in the constraint evaluation domain to steps in LDE domain
-                    let (lde_step, x) = domain.ce_step_to_lde_info(step);
-
-                    // update evaluation frame buffer with data from the execution trace;
-                    // this will read current and next rows from the trace into the buffer
-                    trace.read_frame_into(lde_step, &mut ev_frame);
-
-                    // evaluate transition constraints and save the merged result the
-                    // first slot of the evaluations buffer
-                    evaluations[0] = self.evaluate_transition_constraints(
-                        &ev_frame,
-                        x,
-                        step,
-                        &mut t_evaluations,
-                    );
-
-                    // TODO: in debug mode, save t_evaluations into the fragment
-
-                    // evaluate boundary constraints; the results go into remaining slots
-                    // of the evaluations buffer
-                    let current_state = &ev_frame.current;
-                    self.evaluate_boundary_constraints(
-                        current_state,
-                        x,
-                        step,
-                        &mut evaluations[1..],
-                    );
-
-                    // record the result in the evaluation table
-                    fragment.update_row(i, &evaluations);
+            .for_each_init(init_buffers, |buffers, fragment| {
+                buffers.reuse(trace.width());
+                let num_rows = fragment.num_rows();
+                for batch_start in (0..num_rows).step_by(BATCH_SIZE) {
+                    let batch_size = cmp::min(BATCH_SIZE, num_rows - batch_start);
+                    let step = batch_start + fragment.offset();
+                    self.evaluate_batch(trace, domain, step, batch_size, buffers);
+
+                    // record the results in the evaluation table fragment
+                    for i in 0..batch_size {
+                        fragment.update_row(batch_start + i, buffers.row(i));
+
+                        // when in debug mode, save transition constraint evaluations
+                        #[cfg(debug_assertions)]
+                        fragment.update_transition_evaluations(batch_start + i, buffers.t_row(i));
+                    }
                 }
             });
     }
 
-    /// Evaluates transition constraints at the specified step of the execution trace. `step` is
-    /// the step in the constraint evaluation, and `x` is the corresponding domain value. That
-    /// is, x = s * g^step, where g is the generator of the constraint evaluation domain, and s
-    /// is the domain offset.
+    /// Evaluates constraints at `batch_size` consecutive steps of the constraint evaluation
+    /// domain starting with `first_step`, and saves the results into `buffers`.
+    ///
+    /// Transition constraints are evaluated one step at a time, and their evaluations are merged
+    /// into a single value as soon as they are computed; boundary constraints are evaluated one
+    /// group at a time over all steps in the batch, which lets each group share the work of
+    /// evaluating its constraints across these steps.
+    fn evaluate_batch(
+        &self,
+        trace: &ExtendedTrace<A::BaseElement>,
+        domain: &StarkDomain<A::BaseElement>,
+        first_step: usize,
+        batch_size: usize,
+        buffers: &mut EvaluationBuffers<A::BaseElement, E>,
+    ) {
+        buffers.resize(batch_size);
+        let width = buffers.frame.width();
+        let num_columns = buffers.num_columns;
+        let num_t_constraints = buffers.num_transition_constraints;
+
+        // compute domain values and degree adjustment factors of transition constraint groups
+        // for all steps in the batch
+        for (i, x) in buffers.xs.iter_mut().enumerate() {
+            *x = domain.ce_step_to_lde_info(first_step + i).1;
+        }
+        self.compute_transition_xps(&buffers.xs, &mut buffers.t_xps);
+
+        // assemble rows of periodic values for all steps in the batch
+        let num_periodic = buffers.num_periodic_columns;
+        self.periodic_values
+            .read_rows_into(first_step, batch_size, &mut buffers.periodic_values);
+
+        for i in 0..batch_size {
+            let step = first_step + i;
+
+            // update evaluation frame buffer with data from the execution trace; this will
+            // read current and next rows from the trace into the buffer; we also keep a copy
+            // of the current row for boundary constraint evaluation
+            let lde_step = domain.ce_step_to_lde_info(step).0;
+            trace.read_frame_into(lde_step, &mut buffers.frame);
+            buffers.states[i * width..(i + 1) * width].copy_from_slice(&buffers.frame.current);
+
+            // evaluate transition constraints and save the merged result into the first slot
+            // of the evaluations row
+            let t_row = i % NUM_T_ROWS;
+            let t_evaluations = &mut buffers.t_evaluations
+                [t_row * num_t_constraints..(t_row + 1) * num_t_constraints];
+            let periodic_values =
+                &buffers.periodic_values[i * num_periodic..(i + 1) * num_periodic];
+            buffers.evaluations[i * num_columns] = self.evaluate_transition_constraints(
+                &buffers.frame,
+                periodic_values,
+                &buffers.t_xps[i * buffers.num_transition_groups..],
+                t_evaluations,
+            );
+
+            // when in debug mode, make sure transition constraints do not read next-state values
+            // of local registers; this is checked at the first step of every batch only
+            #[cfg(debug_assertions)]
+            if i == 0 {
+                self.validate_local_register_reads(&buffers.frame, periodic_values, t_evaluations);
+            }
+        }
+
+        // evaluate boundary constraints; the results go into remaining slots of the
+        // evaluations rows
+        self.evaluate_boundary_constraints(first_step, batch_size, buffers);
+    }
+
+    /// Evaluates transition constraints at a single step of the execution trace, and merges
+    /// the evaluations into a single value. `periodic_values` must contain values of periodic
+    /// columns at this step, and `xps` must start with degree adjustment factors of transition
+    /// constraint groups at this step.
     fn evaluate_transition_constraints(
         &self,
         frame: &EvaluationFrame<A::BaseElement>,
-        x: A::BaseElement,
-        step: usize,
+        periodic_values: &[A::BaseElement],
+        xps: &[A::BaseElement],
         evaluations: &mut [A::BaseElement],
     ) -> E {
         // TODO: use a more efficient way to zero out memory
         evaluations.fill(A::BaseElement::ZERO);
 
-        // get periodic values at the evaluation step
-        let periodic_values = self.periodic_values.get_row(step);
-
         // evaluate transition constraints and save the results into evaluations buffer
         self.air
             .evaluate_transition(frame, periodic_values, evaluations);
 
         // merge transition constraint evaluations into a single value and return it;
         // we can do this here because all transition constraints have the same divisor.
-        self.transition_constraints
+        merge_transition_evaluations(&self.transition_constraints, evaluations, xps)
+    }
+
+    /// Checks that evaluations of transition constraints at a single step do not change when
+    /// next-state values of local registers in the `frame` are changed; `periodic_values` must
+    /// contain values of periodic columns at this step, and `evaluations` must contain
+    /// evaluations of transition constraints against the unchanged `frame`.
+    ///
+    /// # Panics
+    /// Panics if an evaluation of any transition constraint depends on a next-state value of a
+    /// local register.
+    #[cfg(debug_assertions)]
+    fn validate_local_register_reads(
+        &self,
+        frame: &EvaluationFrame<A::BaseElement>,
+        periodic_values: &[A::BaseElement],
+        evaluations: &[A::BaseElement],
+    ) {
+        if self.local_registers.is_empty() {
+            return;
+        }
+
+        let mut changed_frame = EvaluationFrame {
+            current: frame.current.clone(),
+            next: frame.next.clone(),
+        };
+        let mut changed_evaluations = vec![A::BaseElement::ZERO; evaluations.len()];
+        for &register in self.local_registers.iter() {
+            changed_frame.next[register] += A::BaseElement::ONE;
+            changed_evaluations.fill(A::BaseElement::ZERO);
+            self.air
+                .evaluate_transition(&changed_frame, periodic_values, &mut changed_evaluations);
+            if let Some(i) = evaluations
+                .iter()
+                .zip(changed_evaluations.iter())
+                .position(|(a, b)| a != b)
+            {
+                panic!(
+                    "transition constraint {} reads next-state value of local register {}",
+                    i, register
+                );
+            }
+            changed_frame.next[register] = frame.next[register];
+        }
+    }
+
+    /// Computes degree adjustment factors of all transition constraint groups for consecutive
+    /// domain values `xs`, and saves them into `xps`; factors of all groups at the same step are
+    /// stored next to each other.
+    fn compute_transition_xps(&self, xs: &[A::BaseElement], xps: &mut [A::BaseElement]) {
+        let num_groups = self.transition_constraints.len();
+        for (j, (group, &xp_step)) in self
+            .transition_constraints
             .iter()
-            .fold(E::ZERO, |result, group| {
-                result + group.merge_evaluations(evaluations, x)
-            })
+            .zip(&self.transition_xp_steps)
+            .enumerate()
+        {
+            let mut xp = xs[0].exp(group.degree_adjustment().into());
+            for xps in xps.chunks_mut(num_groups) {
+                xps[j] = xp;
+                xp *= xp_step;
+            }
+        }
     }
 
-    /// Evaluates all boundary constraint groups at a specific step of the execution trace.
-    /// `step` is the step in the constraint evaluation domain, and `x` is the corresponding
-    /// domain value. That is, x = s * g^step, where g is the generator of the constraint
-    /// evaluation domain, and s is the domain offset.
+    /// Evaluates all boundary constraint groups over a batch of steps of the execution trace.
+    /// Trace states and domain values for each step in the batch are expected to be in the
+    /// `states` and `xs` buffers; the results are written into `evaluations` buffer starting
+    /// with the second slot of each row.
     fn evaluate_boundary_constraints(
         &self,
-        state: &[A::BaseElement],
-        x: A::BaseElement,
-        step: usize,
-        result: &mut [E],
+        first_step: usize,
+        batch_size: usize,
+        buffers: &mut EvaluationBuffers<A::BaseElement, E>,
     ) {
         // compute the adjustment degree outside of the group so that we can re-use
         // it for groups which have the same adjustment degree
         let mut degree_adjustment = self.boundary_constraints[0].degree_adjustment;
-        let mut xp = E::from(x.exp(degree_adjustment.into()));
+        compute_xps(&buffers.xs, degree_adjustment, &mut buffers.xps);
 
-        for (group, result) in self.boundary_constraints.iter().zip(result.iter_mut()) {
+        let num_columns = buffers.num_columns;
+        for (j, group) in self.boundary_constraints.iter().enumerate() {
             // recompute adjustment degree only when it has changed
             if group.degree_adjustment != degree_adjustment {
                 degree_adjustment = group.degree_adjustment;
-                xp = E::from(x.exp(degree_adjustment.into()));
+                compute_xps(&buffers.xs, degree_adjustment, &mut buffers.xps);
             }
-            // evaluate the group and save the result
-            *result = group.evaluate(state, step, x, xp);
+
+            // evaluate the group over all steps in the batch and save the results
+            group.evaluate_over_domain(
+                &buffers.states,
+                first_step,
+                &buffers.xs,
+                &buffers.xps,
+                &mut buffers.group_evaluations,
+            );
+            for i in 0..batch_size {
+                buffers.evaluations[i * num_columns + j + 1] = buffers.group_evaluations[i];
+            }
+        }
+    }
+}
+
+// EVALUATION BUFFERS
+// ================================================================================================
+
+/// Buffers which hold trace values and constraint evaluations for a batch of steps of the
+/// constraint evaluation domain; these are allocated once per thread and are overwritten for
+/// every batch.
+struct EvaluationBuffers<B: FieldElement, E: FieldElement + From<B>> {
+    frame: EvaluationFrame<B>,
+    num_columns: usize,
+    num_transition_constraints: usize,
+    num_transition_groups: usize,
+    num_periodic_columns: usize,
+    /// Current trace states at every step of the batch; states are stored one after another.
+    states: Vec<B>,
+    /// Domain values at every step of the batch.
+    xs: Vec<B>,
+    /// Degree adjustment factors at every step of the batch.
+    xps: Vec<E>,
+    /// Constraint evaluation rows for every step of the batch.
+    evaluations: Vec<E>,
+    /// Evaluations of a single boundary constraint group at every step of the batch.
+    group_evaluations: Vec<E>,
+    /// Degree adjustment factors of transition constraint groups at every step of the batch;
+    /// factors of all groups at the same step are stored next to each other.
+    t_xps: Vec<B>,
+    /// Transition constraint evaluations for NUM_T_ROWS steps of the batch.
+    t_evaluations: Vec<B>,
+    /// Values of periodic columns at every step of the batch; values for the same step are
+    /// stored next to each other.
+    periodic_values: Vec<B>,
+}
+
+impl<B: FieldElement, E: FieldElement + From<B>> EvaluationBuffers<B, E> {
+    /// Returns new buffers which are not yet prepared for any trace; [reuse()] must be called
+    /// before the buffers are used.
+    ///
+    /// [reuse()]: EvaluationBuffers::reuse
+    fn new(
+        num_columns: usize,
+        num_transition_constraints: usize,
+        num_transition_groups: usize,
+        num_periodic_columns: usize,
+    ) -> Self {
+        EvaluationBuffers {
+            frame: EvaluationFrame::new(0),
+            num_columns,
+            num_transition_constraints,
+            num_transition_groups,
+            num_periodic_columns,
+            states: Vec::new(),
+            xs: B::zeroed_vector(BATCH_SIZE),
+            xps: E::zeroed_vector(BATCH_SIZE),
+            evaluations: E::zeroed_vector(num_columns * BATCH_SIZE),
+            group_evaluations: E::zeroed_vector(BATCH_SIZE),
+            t_xps: B::zeroed_vector(num_transition_groups * BATCH_SIZE),
+            t_evaluations: B::zeroed_vector(num_transition_constraints * NUM_T_ROWS),
+            periodic_values: B::zeroed_vector(num_periodic_columns * BATCH_SIZE),
+        }
+    }
+
+    /// Prepares these buffers to hold states of an execution trace with the specified number of
+    /// registers; memory is re-allocated only if the buffers are not large enough already.
+    fn reuse(&mut self, trace_width: usize) {
+        self.frame.reuse(trace_width);
+        self.states.resize(trace_width * BATCH_SIZE, B::ZERO);
+    }
+
+    /// Sets the number of steps in the current batch; this never re-allocates memory because
+    /// batches are never larger than BATCH_SIZE.
+    fn resize(&mut self, batch_size: usize) {
+        let width = self.frame.width();
+        self.states.resize(width * batch_size, B::ZERO);
+        self.xs.resize(batch_size, B::ZERO);
+        self.xps.resize(batch_size, E::ZERO);
+        self.evaluations
+            .resize(self.num_columns * batch_size, E::ZERO);
+        self.group_evaluations.resize(batch_size, E::ZERO);
+        self.t_xps
+            .resize(self.num_transition_groups * batch_size, B::ZERO);
+        self.periodic_values
+            .resize(self.num_periodic_columns * batch_size, B::ZERO);
+    }
+
+    /// Returns constraint evaluations at the i-th step of the batch.
+    fn row(&self, i: usize) -> &[E] {
+        &self.evaluations[i * self.num_columns..(i + 1) * self.num_columns]
+    }
+
+    /// Returns transition constraint evaluations at the i-th step of the batch.
+    #[cfg(debug_assertions)]
+    fn t_row(&self, i: usize) -> &[B] {
+        let n = self.num_transition_constraints;
+        &self.t_evaluations[i * n..(i + 1) * n]
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Merges transition constraint evaluations at a single step into a single value; `xps` must
+/// start with degree adjustment factors of the constraint `groups` at this step.
+///
+/// For every group, this computes sum(e_i * cc0_i) + xp * sum(e_i * cc1_i), where e_i are
+/// evaluations of constraints in the group. This is equal to the value computed by
+/// [TransitionConstraintGroup::merge_evaluations()], but the degree adjustment factor is applied
+/// once per group rather than once per constraint.
+#[inline(always)]
+fn merge_transition_evaluations<B: StarkField, E: FieldElement + From<B>>(
+    groups: &[TransitionConstraintGroup<E>],
+    evaluations: &[B],
+    xps: &[B],
+) -> E {
+    let mut result = E::ZERO;
+    for (group, &xp) in groups.iter().zip(xps) {
+        let mut acc0 = E::ZERO;
+        let mut acc1 = E::ZERO;
+        for (&constraint_idx, &(cc0, cc1)) in group.indexes().iter().zip(group.coefficients()) {
+            let evaluation = E::from(evaluations[constraint_idx]);
+            acc0 += evaluation * cc0;
+            acc1 += evaluation * cc1;
         }
+        result += acc0 + acc1 * E::from(xp);
+    }
+    result
+}
+
+/// Converts `values` into elements which count field operations performed on them.
+fn to_profiled<B: StarkField>(values: &[B]) -> Vec<ProfiledElement<B>> {
+    values.iter().map(|&v| ProfiledElement::new(v)).collect()
+}
+
+/// Computes x^degree_adjustment for all values in `xs` and saves the results into `xps`.
+fn compute_xps<B: FieldElement, E: FieldElement + From<B>>(
+    xs: &[B],
+    degree_adjustment: u32,
+    xps: &mut [E],
+) {
+    for (xp, &x) in xps.iter_mut().zip(xs) {
+        *xp = E::from(x.exp(degree_adjustment.into()));
     }
 }
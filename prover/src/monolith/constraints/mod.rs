@@ -3,13 +3,13 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{trace::TraceTable, StarkDomain};
+use super::{trace::ExtendedTrace, AirCache, StarkDomain};
 
 mod boundary;
 use boundary::BoundaryConstraintGroup;
 
 mod periodic_table;
-use periodic_table::PeriodicValueTable;
+pub use periodic_table::PeriodicValueTable;
 
 mod evaluator;
 pub use evaluator::ConstraintEvaluator;
@@ -17,8 +17,17 @@ pub use evaluator::ConstraintEvaluator;
 mod constraint_poly;
 pub use constraint_poly::ConstraintPoly;
 
+mod divisor_table;
+pub use divisor_table::DivisorTable;
+
 mod evaluation_table;
 pub use evaluation_table::ConstraintEvaluationTable;
 
+mod profiler;
+pub use profiler::{ConstraintProfile, TransitionConstraintStats};
+
 mod commitment;
 pub use commitment::ConstraintCommitment;
+
+#[cfg(test)]
+mod tests;
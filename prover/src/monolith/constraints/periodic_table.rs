@@ -6,41 +6,27 @@
 use common::Air;
 use math::{fft, field::StarkField};
 use std::collections::HashMap;
-use utils::uninit_vector;
 
 pub struct PeriodicValueTable<B: StarkField> {
-    values: Vec<B>,
-    length: usize,
-    width: usize,
+    /// Evaluations of each periodic column over a single cycle of the column in the constraint
+    /// evaluation domain.
+    columns: Vec<Vec<B>>,
 }
 
 impl<B: StarkField> PeriodicValueTable<B> {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Builds a table of periodic column values for the specified AIR. The table contains expanded
-    /// values of all periodic columns normalized to the same length. This enables simple lookup
-    /// into the able using step index of the constraint evaluation domain.
-    pub fn new<A: Air<BaseElement = B>>(air: &A) -> PeriodicValueTable<B> {
-        // get a list of polynomials describing periodic columns from AIR. if there are no
-        // periodic columns return an empty table
-        let polys = air.get_periodic_column_polys();
-        if polys.is_empty() {
-            return PeriodicValueTable {
-                values: Vec::new(),
-                length: 0,
-                width: 0,
-            };
-        }
-
-        // determine the size of the biggest polynomial in the set. unwrap is OK here
-        // because if we get here, there must be at least one polynomial in the set.
-        let max_poly_size = polys.iter().max_by_key(|p| p.len()).unwrap().len();
-
+    /// Builds a table of periodic column values for the specified AIR from periodic column
+    /// `polys`. Each column is evaluated over a single cycle only, and values of all columns at
+    /// a given step are assembled into rows when needed via [read_rows_into()].
+    ///
+    /// [read_rows_into()]: PeriodicValueTable::read_rows_into
+    pub fn new<A: Air<BaseElement = B>>(air: &A, polys: &[Vec<B>]) -> PeriodicValueTable<B> {
         // cache twiddles used for polynomial evaluation here so that we don't have to re-build
         // them for polynomials of the same size
         let mut twiddle_map = HashMap::new();
 
-        let evaluations = polys
+        let columns = polys
             .iter()
             .map(|poly| {
                 let poly_size = poly.len();
@@ -50,41 +36,35 @@ impl<B: StarkField> PeriodicValueTable<B> {
                     .entry(poly_size)
                     .or_insert_with(|| fft::get_twiddles(poly_size));
 
-                fft::evaluate_poly_with_offset(&poly, twiddles, offset, air.ce_blowup_factor())
+                fft::evaluate_poly_with_offset(poly, twiddles, offset, air.ce_blowup_factor())
             })
-            .collect::<Vec<_>>();
-
-        // allocate memory to hold all expanded values and copy polynomial evaluations into the
-        // table in such a way that values for the same row are adjacent to each other.
-        let row_width = polys.len();
-        let column_length = max_poly_size * air.ce_blowup_factor();
-        let mut values = uninit_vector(row_width * column_length);
-        for i in 0..column_length {
-            for (j, column) in evaluations.iter().enumerate() {
-                values[i * row_width + j] = column[i % column.len()];
-            }
-        }
+            .collect();
 
-        PeriodicValueTable {
-            values,
-            length: column_length,
-            width: row_width,
-        }
+        PeriodicValueTable { columns }
     }
 
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
-    pub fn is_empty(&self) -> bool {
-        self.width == 0
+    /// Returns the number of periodic columns in this table.
+    pub fn width(&self) -> usize {
+        self.columns.len()
     }
 
-    pub fn get_row(&self, ce_step: usize) -> &[B] {
-        if self.is_empty() {
-            &[]
-        } else {
-            let start = (ce_step % self.length) * self.width;
-            &self.values[start..start + self.width]
+    // PUBLIC METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Copies values of all periodic columns at `num_rows` consecutive steps of the constraint
+    /// evaluation domain starting with `first_step` into `rows`; values for the same step are
+    /// stored next to each other.
+    pub fn read_rows_into(&self, first_step: usize, num_rows: usize, rows: &mut [B]) {
+        let width = self.width();
+        debug_assert_eq!(width * num_rows, rows.len());
+        for (j, column) in self.columns.iter().enumerate() {
+            let column_length = column.len();
+            for i in 0..num_rows {
+                rows[i * width + j] = column[(first_step + i) % column_length];
+            }
         }
     }
 }
@@ -99,7 +79,7 @@ mod tests {
     use math::{
         field::{f128::BaseElement, FieldElement, StarkField},
         polynom,
-        utils::{get_power_series_with_offset, log2},
+        utils::{get_power_series_with_offset_unchecked, log2_unchecked},
     };
 
     #[test]
@@ -118,12 +98,13 @@ mod tests {
         let air = MockAir::with_periodic_columns(vec![col1, col2], trace_length);
 
         // build a table of periodic values
-        let table = super::PeriodicValueTable::new(&air);
+        let polys = air.get_periodic_column_polys();
+        let table = super::PeriodicValueTable::new(&air, polys);
 
-        assert_eq!(2, table.width);
-        assert_eq!(4 * air.ce_blowup_factor(), table.length);
+        assert_eq!(2, table.width());
+        assert_eq!(2 * air.ce_blowup_factor(), table.columns[0].len());
+        assert_eq!(4 * air.ce_blowup_factor(), table.columns[1].len());
 
-        let polys = air.get_periodic_column_polys();
         let domain = build_ce_domain(air.ce_domain_size(), air.domain_offset());
 
         // build expected values by evaluating polynomials over shifted ce_domain
@@ -141,19 +122,23 @@ mod tests {
             })
             .collect::<Vec<_>>();
 
-        // build actual values by recording rows of the table at each step of ce_domain
+        // build actual values by reading rows of the table in batches of 3 steps
         let mut actual = vec![Vec::new(), Vec::new()];
-        for i in 0..air.ce_domain_size() {
-            let row = table.get_row(i);
-            actual[0].push(row[0]);
-            actual[1].push(row[1]);
+        let mut rows = [BaseElement::ZERO; 6];
+        for first_step in (0..air.ce_domain_size()).step_by(3) {
+            let num_rows = 3.min(air.ce_domain_size() - first_step);
+            table.read_rows_into(first_step, num_rows, &mut rows[..num_rows * 2]);
+            for row in rows[..num_rows * 2].chunks(2) {
+                actual[0].push(row[0]);
+                actual[1].push(row[1]);
+            }
         }
 
         assert_eq!(expected, actual);
     }
 
     fn build_ce_domain(domain_size: usize, domain_offset: BaseElement) -> Vec<BaseElement> {
-        let g = BaseElement::get_root_of_unity(log2(domain_size));
-        get_power_series_with_offset(g, domain_offset, domain_size)
+        let g = BaseElement::get_root_of_unity(log2_unchecked(domain_size)).unwrap();
+        get_power_series_with_offset_unchecked(g, domain_offset, domain_size)
     }
 }
@@ -0,0 +1,81 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use log::debug;
+
+// CONSTRAINT PROFILE
+// ================================================================================================
+
+/// Statistics about evaluations of transition constraints over the constraint evaluation domain.
+///
+/// A profile is built only when constraint profiling is enabled in the prover config (see
+/// [ProverConfig::with_constraint_profiling()](crate::ProverConfig::with_constraint_profiling)),
+/// and is reported together with other prover metrics at debug log level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintProfile {
+    constraints: Vec<TransitionConstraintStats>,
+}
+
+/// Statistics about evaluations of a single transition constraint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionConstraintStats {
+    /// Fraction of constraint evaluation domain points at which the constraint evaluates to zero.
+    pub zero_fraction: f64,
+    /// Average number of field operations needed to evaluate the constraint at a single point.
+    pub avg_cost: f64,
+    /// Degree of the constraint polynomial as declared by the AIR.
+    pub declared_degree: usize,
+    /// Actual degree of the constraint polynomial over the constraint evaluation domain.
+    pub actual_degree: usize,
+}
+
+impl ConstraintProfile {
+    /// Returns a new profile for the specified constraint statistics.
+    pub fn new(constraints: Vec<TransitionConstraintStats>) -> Self {
+        ConstraintProfile { constraints }
+    }
+
+    /// Returns statistics for all transition constraints in the order in which the constraints
+    /// are evaluated by the AIR.
+    pub fn constraints(&self) -> &[TransitionConstraintStats] {
+        &self.constraints
+    }
+
+    /// Writes statistics for all transition constraints into the debug log. Cost of each
+    /// constraint is also reported as a share of the total cost of evaluating all transition
+    /// constraints so that the hot constraints are easy to spot.
+    pub fn log(&self) {
+        let total_cost: f64 = self.constraints.iter().map(|c| c.avg_cost).sum();
+        for (i, stats) in self.constraints.iter().enumerate() {
+            let cost_share = if total_cost > 0.0 {
+                stats.avg_cost / total_cost
+            } else {
+                0.0
+            };
+            debug!(
+                "Transition constraint {}: zero at {:.1}% of domain, {:.1} field ops per \
+                evaluation ({:.1}% of total), degree {} (declared {}){}",
+                i,
+                stats.zero_fraction * 100.0,
+                stats.avg_cost,
+                cost_share * 100.0,
+                stats.actual_degree,
+                stats.declared_degree,
+                if stats.is_over_declared() {
+                    "; degree is over-declared"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+}
+
+impl TransitionConstraintStats {
+    /// Returns true if the constraint polynomial has a lower degree than declared by the AIR.
+    pub fn is_over_declared(&self) -> bool {
+        self.actual_degree < self.declared_degree
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ConstraintEvaluator, ConstraintProfile};
+use crate::{
+    channel::ProverChannel,
+    monolith::{trace::ExtendedTrace, AirCache, StarkDomain},
+    tests::build_fib_trace,
+};
+use common::{
+    evaluate_constraints, Air, Assertion, ComputationContext, EvaluationFrame, FieldExtension,
+    HashFunction, ProofOptions, TraceInfo, TransitionConstraintDegree,
+};
+use crypto::hash::Blake3_256;
+use math::field::{f128::BaseElement, FieldElement, QuadExtension};
+
+const TRACE_LENGTH: usize = 16;
+
+#[test]
+fn evaluate_constraints_at_ood_point() {
+    check_ood_constraint_evaluation::<BaseElement>();
+}
+
+#[test]
+fn evaluate_constraints_at_ood_point_in_extension_field() {
+    check_ood_constraint_evaluation::<QuadExtension<BaseElement>>();
+}
+
+/// Checks that evaluating constraints against the OOD frame at z yields the same value as the
+/// evaluation of the constraint composition polynomial built by the prover at z.
+fn check_ood_constraint_evaluation<E: FieldElement + From<BaseElement>>() {
+    let air = build_air();
+    let domain = StarkDomain::new(air.context());
+    let (extended_trace, trace_polys) = build_fib_trace(TRACE_LENGTH * 2).extend(&domain);
+
+    // commitment roots are arbitrary here; they are used only to seed the public coin
+    let mut channel = ProverChannel::<Blake3_256, Blake3_256>::new(air.context());
+    channel.commit_trace([1; 32]);
+    let mut cache = AirCache::new();
+    let evaluator = ConstraintEvaluator::<FibAir, E>::new(build_air(), &channel, &mut cache);
+    let constraint_poly = evaluator
+        .evaluate(&ExtendedTrace::new(&extended_trace, None), &domain)
+        .into_poly(&mut cache)
+        .unwrap();
+    channel.commit_constraints([2; 32]);
+
+    // build the out-of-domain frame from trace polynomials
+    let z = channel.draw_deep_point::<E>();
+    let g = air.context().get_trace_domain_generator::<BaseElement>();
+    let ood_frame = EvaluationFrame {
+        current: trace_polys.evaluate_at(z),
+        next: trace_polys.evaluate_at(z * E::from(g)),
+    };
+
+    let mut periodic_values = Vec::new();
+    let mut t_evaluations = Vec::new();
+    let result = evaluate_constraints(
+        &air,
+        &channel,
+        &ood_frame,
+        z,
+        &mut periodic_values,
+        &mut t_evaluations,
+    );
+    assert_eq!(constraint_poly.evaluate_at(z), result);
+    assert_eq!(2, t_evaluations.len());
+
+    // a frame which does not come from the trace polynomials yields a different value
+    let mut invalid_frame = ood_frame;
+    invalid_frame.next[0] += E::ONE;
+    let result = evaluate_constraints(
+        &air,
+        &channel,
+        &invalid_frame,
+        z,
+        &mut periodic_values,
+        &mut t_evaluations,
+    );
+    assert_ne!(constraint_poly.evaluate_at(z), result);
+}
+
+#[test]
+fn profile_transition_constraints() {
+    let air = build_air();
+    let profile = profile_constraints(build_air(), &air);
+    let stats = profile.constraints();
+    assert_eq!(2, stats.len());
+
+    // next[0] - (current[0] + current[1]) takes 2 operations, and
+    // periodic[0] * (next[1] - (current[0] + two * current[1])) takes 4 operations
+    assert_eq!(2.0, stats[0].avg_cost);
+    assert_eq!(4.0, stats[1].avg_cost);
+
+    // the constraint evaluation domain is a coset of the trace domain, so constraints do not
+    // evaluate to zero anywhere in it, even for a valid trace
+    for stats in stats.iter() {
+        assert_eq!(0.0, stats.zero_fraction);
+        assert_eq!(stats.declared_degree, stats.actual_degree);
+        assert!(!stats.is_over_declared());
+    }
+
+    // a redundant constraint evaluates to zero everywhere, and a constraint of degree 1 declared
+    // as a degree 2 constraint is reported as over-declared
+    let trace_info = TraceInfo {
+        length: TRACE_LENGTH,
+        meta: Vec::new(),
+    };
+    let options = air.context().options().clone();
+    let redundant_air = RedundantAir::new(trace_info, air.result, options);
+    let profile = profile_constraints(redundant_air, &air);
+    let stats = profile.constraints();
+    assert_eq!(3, stats.len());
+    assert!(!stats[0].is_over_declared());
+    assert_eq!(1.0, stats[1].zero_fraction);
+    assert!(stats[1].is_over_declared());
+    assert_eq!(0.0, stats[2].zero_fraction);
+    assert!(stats[2].actual_degree < stats[2].declared_degree);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "transition constraint 1 reads next-state value of local register 1")]
+fn evaluate_constraints_reading_local_register() {
+    // the second transition constraint of FibAir reads the next-state value of register 1
+    let trace_info = TraceInfo {
+        length: TRACE_LENGTH,
+        meta: Vec::new(),
+    };
+    let fib_air = build_air();
+    let options = fib_air.context().options().clone();
+    let air = LocalRegisterAir::new(trace_info, fib_air.result, options);
+
+    let domain = StarkDomain::new(air.context());
+    let (extended_trace, _) = build_fib_trace(TRACE_LENGTH * 2).extend(&domain);
+    let mut channel = ProverChannel::<Blake3_256, Blake3_256>::new(air.context());
+    channel.commit_trace([1; 32]);
+    let mut cache = AirCache::new();
+    let evaluator = ConstraintEvaluator::<_, BaseElement>::new(air, &channel, &mut cache);
+    evaluator.evaluate(&ExtendedTrace::new(&extended_trace, None), &domain);
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// AIR for the trace built by [build_fib_trace()]; the second transition constraint is scaled by
+/// a periodic column so that periodic values are exercised too.
+struct FibAir {
+    context: ComputationContext,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::with_cycles(1, vec![2]),
+        ];
+        FibAir {
+            context: ComputationContext::new(2, trace_info.length, degrees, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        let two = E::from(2u8);
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = periodic_values[0] * (next[1] - (current[0] + two * current[1]));
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, TRACE_LENGTH - 1, self.result),
+        ]
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        vec![vec![BaseElement::ONE, BaseElement::from(2u8)]]
+    }
+}
+
+/// AIR for the trace built by [build_fib_trace()] with a redundant constraint and a constraint
+/// with an over-declared degree.
+struct RedundantAir(FibAir);
+
+impl Air for RedundantAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(2),
+            TransitionConstraintDegree::new(2),
+        ];
+        let context = ComputationContext::new(2, trace_info.length, degrees, options);
+        RedundantAir(FibAir { context, result })
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.0.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        let two = E::from(2u8);
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = (current[0] + current[1]) - (current[1] + current[0]);
+        result[2] = next[1] - (current[0] + two * current[1]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        self.0.get_assertions()
+    }
+}
+
+/// AIR for the trace built by [build_fib_trace()] which marks register 1 as local, even though
+/// transition constraints read its next-state value.
+struct LocalRegisterAir(FibAir);
+
+impl Air for LocalRegisterAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let mut air = FibAir::new(trace_info, result, options);
+        air.context = air.context.with_local_registers(&[1]);
+        LocalRegisterAir(air)
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.0.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        self.0.get_assertions()
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        self.0.get_periodic_column_values()
+    }
+}
+
+fn build_air() -> FibAir {
+    let trace = build_fib_trace(TRACE_LENGTH * 2);
+    let result = trace.get(1, TRACE_LENGTH - 1);
+    let options = ProofOptions::new(32, 8, 0, HashFunction::Blake3_256, FieldExtension::None);
+    let trace_info = TraceInfo {
+        length: TRACE_LENGTH,
+        meta: Vec::new(),
+    };
+    FibAir::new(trace_info, result, options)
+}
+
+/// Profiles transition constraints of the specified AIR against the trace for the `fib_air`.
+fn profile_constraints<A: Air<BaseElement = BaseElement>>(
+    air: A,
+    fib_air: &FibAir,
+) -> ConstraintProfile {
+    let domain = StarkDomain::new(fib_air.context());
+    let (extended_trace, _) = build_fib_trace(TRACE_LENGTH * 2).extend(&domain);
+
+    let mut channel = ProverChannel::<Blake3_256, Blake3_256>::new(fib_air.context());
+    channel.commit_trace([1; 32]);
+    let mut cache = AirCache::new();
+    let evaluator = ConstraintEvaluator::<A, BaseElement>::new(air, &channel, &mut cache);
+    evaluator.profile(&ExtendedTrace::new(&extended_trace, None), &domain)
+}
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{constraints::ConstraintPoly, StarkDomain, TracePolyTable};
-use common::{CompositionCoefficients, ComputationContext, EvaluationFrame};
+use common::{CompositionCoefficients, ComputationContext, DeepComposer, EvaluationFrame};
 use math::{
     fft,
     field::{FieldElement, StarkField},
@@ -20,9 +20,7 @@ use rayon::prelude::*;
 pub struct CompositionPoly<E: FieldElement> {
     coefficients: Vec<E>,
     degree: usize,
-    cc: CompositionCoefficients<E>,
-    z: E,
-    field_extension: bool,
+    composer: DeepComposer<E>,
 }
 
 impl<E: FieldElement> CompositionPoly<E> {
@@ -30,13 +28,19 @@ impl<E: FieldElement> CompositionPoly<E> {
     // --------------------------------------------------------------------------------------------
     /// Returns a new composition polynomial. This also initializes memory needed to hold
     /// polynomial coefficients.
-    pub fn new(context: &ComputationContext, z: E, cc: CompositionCoefficients<E>) -> Self {
+    pub fn new<B>(context: &ComputationContext, z: E, cc: CompositionCoefficients<E>) -> Self
+    where
+        B: StarkField,
+        E: From<B>,
+    {
+        // memory for coefficients is reserved for the entire LDE domain so that the polynomial
+        // can later be evaluated over the LDE domain in-place
+        let mut coefficients = Vec::with_capacity(context.lde_domain_size());
+        coefficients.resize(context.ce_domain_size(), E::ZERO);
         CompositionPoly {
-            coefficients: E::zeroed_vector(context.ce_domain_size()),
+            coefficients,
             degree: context.deep_composition_degree(),
-            cc,
-            z,
-            field_extension: !context.options().field_extension().is_none(),
+            composer: DeepComposer::new::<B>(context, z, cc),
         }
     }
 
@@ -54,63 +58,65 @@ impl<E: FieldElement> CompositionPoly<E> {
     /// the composition polynomial. The combination is done as follows:
     /// 1. First, state of trace registers at deep points z and z * g are computed;
     /// 2. Then, polynomials T1_i(x) = (T_i(x) - T_i(z)) / (x - z) and
-    /// T2_i(x) = (T_i(x) - T_i(z * g)) / (x - z * g) are computed for all i and combined
-    /// together into a single polynomial using a pseudo-random linear combination;
+    /// T2_i(x) = (T_i(x) - T_i(z * g)) / (x - z * g) are computed for all i (T2_i(x) only for
+    /// registers which are not local) and combined together into a single polynomial using a
+    /// pseudo-random linear combination;
     /// 3. Then the degree of the polynomial is adjusted to match the composition degree.
-    pub fn add_trace_polys<B>(&mut self, trace_polys: TracePolyTable<B>) -> EvaluationFrame<E>
+    pub fn add_trace_polys<B>(&mut self, trace_polys: &TracePolyTable<B>) -> EvaluationFrame<E>
     where
         B: StarkField,
         E: From<B>,
     {
-        // compute a second out-of-domain point offset from z by exactly trace generator; this point
-        // defines the "next" computation state in relation to point z
+        // out-of-domain points z and z * g, as well as conjugate of z when field extension is
+        // enabled, are defined by the DEEP composer shared with the verifier
         let trace_length = trace_polys.poly_size();
-        let g = E::from(B::get_root_of_unity(utils::log2(trace_length)));
-        let next_z = self.z * g;
+        let z = self.composer.z();
+        let next_z = self.composer.next_z();
+        let z_conjugate = self.composer.z_conjugate();
+        let cc = self.composer.coefficients();
 
-        // compute state of registers at deep points z and z * g
-        let trace_state1 = trace_polys.evaluate_at(self.z);
-        let trace_state2 = trace_polys.evaluate_at(next_z);
+        // compute state of registers at deep points z and z * g; local registers are not
+        // evaluated at z * g, and their next-state values are left as zeros
+        let trace_state1 = trace_polys.evaluate_at(z);
+        let mut trace_state2 = E::zeroed_vector(trace_polys.num_polys());
+        for &i in self.composer.next_state_registers() {
+            trace_state2[i] = polynom::eval(trace_polys.get_poly(i), next_z);
+        }
 
         // combine trace polynomials into 2 composition polynomials T1(x) and T2(x), and if
         // we are using a field extension, also T3(x)
-        let polys = trace_polys.into_vec();
         let mut t1_composition = E::zeroed_vector(trace_length);
         let mut t2_composition = E::zeroed_vector(trace_length);
-        let mut t3_composition = if self.field_extension {
+        let mut t3_composition = if z_conjugate.is_some() {
             E::zeroed_vector(trace_length)
         } else {
             Vec::new()
         };
-        for (i, poly) in polys.into_iter().enumerate() {
+        let next_state_registers = self.composer.next_state_registers();
+        for i in 0..trace_polys.num_polys() {
+            let poly = trace_polys.get_poly(i);
+
             // compute T1(x) = T(x) - T(z), multiply it by a pseudo-random coefficient,
             // and add the result into composition polynomial
-            acc_poly(
-                &mut t1_composition,
-                &poly,
-                trace_state1[i],
-                self.cc.trace[i].0,
-            );
+            acc_poly(&mut t1_composition, poly, trace_state1[i], cc.trace[i].0);
 
             // compute T2(x) = T(x) - T(z * g), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_poly(
-                &mut t2_composition,
-                &poly,
-                trace_state2[i],
-                self.cc.trace[i].1,
-            );
+            // and add the result into composition polynomial; this is skipped for local
+            // registers
+            if next_state_registers.binary_search(&i).is_ok() {
+                acc_poly(&mut t2_composition, poly, trace_state2[i], cc.trace[i].1);
+            }
 
             // compute T3(x) = T(x) - T(z_conjugate), multiply it by a pseudo-random coefficient,
             // and add the result into composition polynomial. When extension field is enabled,
             // this constraint is needed to prove that the trace is defined over the base field,
             // rather than the extension field.
-            if self.field_extension {
+            if z_conjugate.is_some() {
                 acc_poly(
                     &mut t3_composition,
-                    &poly,
+                    poly,
                     trace_state1[i].conjugate(),
-                    self.cc.trace[i].2,
+                    cc.trace[i].2,
                 );
             }
         }
@@ -120,15 +126,69 @@ impl<E: FieldElement> CompositionPoly<E> {
         // is a single trace polynomial T(x) and deg(T(x)) = trace_length - 2.
         let trace_poly = merge_trace_compositions(
             vec![t1_composition, t2_composition, t3_composition],
-            vec![self.z, next_z, self.z.conjugate()],
+            vec![z, next_z, z_conjugate.unwrap_or(E::ZERO)],
         );
         debug_assert_eq!(trace_length - 2, polynom::degree_of(&trace_poly));
 
+        self.add_adjusted_trace_poly(&trace_poly);
+
+        // trace states at OOD points z and z * g are returned to be included in the proof
+        EvaluationFrame {
+            current: trace_state1,
+            next: trace_state2,
+        }
+    }
+
+    /// Combines extra polynomials committed to by the prover into a single polynomial and adds
+    /// the result to the composition polynomial. This is done in the same way as for trace
+    /// polynomials, except that extra polynomials are not evaluated at z * g; thus, only
+    /// T1_i(x) and (when field extension is enabled) T3_i(x) are computed for every extra
+    /// polynomial i. Evaluations of extra polynomials at z are returned.
+    pub fn add_extra_polys<B>(&mut self, extra_polys: &TracePolyTable<B>) -> Vec<E>
+    where
+        B: StarkField,
+        E: From<B>,
+    {
+        let poly_size = extra_polys.poly_size();
+        let z = self.composer.z();
+        let z_conjugate = self.composer.z_conjugate();
+        let cc = self.composer.coefficients();
+
+        let extra_at_z = extra_polys.evaluate_at(z);
+        let mut t1_composition = E::zeroed_vector(poly_size);
+        let mut t3_composition = if z_conjugate.is_some() {
+            E::zeroed_vector(poly_size)
+        } else {
+            Vec::new()
+        };
+        for (i, (&value_at_z, &(k1, k3))) in extra_at_z.iter().zip(cc.extra.iter()).enumerate() {
+            let poly = extra_polys.get_poly(i);
+            acc_poly(&mut t1_composition, poly, value_at_z, k1);
+            if z_conjugate.is_some() {
+                acc_poly(&mut t3_composition, poly, value_at_z.conjugate(), k3);
+            }
+        }
+
+        let extra_poly = merge_trace_compositions(
+            vec![t1_composition, t3_composition],
+            vec![z, z_conjugate.unwrap_or(E::ZERO)],
+        );
+        self.add_adjusted_trace_poly(&extra_poly);
+
+        extra_at_z
+    }
+
+    /// Adds the combination of trace polynomials `trace_poly` of degree trace_length - 2 to the
+    /// composition polynomial after adjusting its degree to match the composition degree.
+    fn add_adjusted_trace_poly(&mut self, trace_poly: &[E]) {
         // we need to make sure that the degree of trace polynomial T(x) matches the degree
         // of composition polynomial; to do this, we compute a linear combination of T(x)
         // with itself multiplied by x^p, where p is the incremental degree needed to match
         // the composition degree.
-        let incremental_degree = self.degree() - (trace_length - 2);
+        let trace_length = trace_poly.len();
+        let incremental_degree = self.composer.incremental_degree();
+        debug_assert_eq!(self.degree(), incremental_degree + trace_length - 2);
+        let cc = self.composer.coefficients();
 
         // The next few lines are an optimized way of computing:
         // C(x) = T(x) * k_1 + T(x) * x^incremental_degree * k_2
@@ -137,21 +197,15 @@ impl<E: FieldElement> CompositionPoly<E> {
         // this is equivalent to T(x) * k_1
         utils::mul_acc(
             &mut self.coefficients[..trace_length],
-            &trace_poly,
-            self.cc.trace_degree.0,
+            trace_poly,
+            cc.trace_degree.0,
         );
         // this is equivalent to T(x) * x^incremental_degree * k_2
         utils::mul_acc(
             &mut self.coefficients[incremental_degree..(incremental_degree + trace_length)],
-            &trace_poly,
-            self.cc.trace_degree.1,
+            trace_poly,
+            cc.trace_degree.1,
         );
-
-        // trace states at OOD points z and z * g are returned to be included in the proof
-        EvaluationFrame {
-            current: trace_state1,
-            next: trace_state2,
-        }
     }
 
     // CONSTRAINT POLYNOMIAL COMPOSITION
@@ -168,34 +222,41 @@ impl<E: FieldElement> CompositionPoly<E> {
             .collect::<Vec<_>>();
 
         // evaluate the polynomial at point z
-        let value_at_z = polynom::eval(&constraint_poly, self.z);
+        let z = self.composer.z();
+        let value_at_z = polynom::eval(&constraint_poly, z);
 
         // compute C(x) = (P(x) - P(z)) / (x - z)
         constraint_poly[0] -= value_at_z;
-        polynom::syn_div_in_place(&mut constraint_poly, 1, self.z);
+        polynom::syn_div_in_place(&mut constraint_poly, 1, z);
 
         // add C(x) * K into the result
         utils::mul_acc(
             &mut self.coefficients[..constraint_poly.len()],
             &constraint_poly,
-            self.cc.constraints,
+            self.composer.coefficients().constraints,
         );
     }
 
     // LOW-DEGREE EXTENSION
     // --------------------------------------------------------------------------------------------
     /// Evaluates DEEP composition polynomial over the specified LDE domain and returns the result.
+    ///
+    /// The evaluation is done in-place in the memory which holds the coefficients, and thus, the
+    /// coefficients and the evaluations are never held in memory at the same time.
     pub fn evaluate<B>(self, domain: &StarkDomain<B>) -> Vec<E>
     where
         B: StarkField,
         E: From<B>,
     {
-        fft::evaluate_poly_with_offset(
-            &self.coefficients,
+        let mut evaluations = self.coefficients;
+        evaluations.resize(domain.lde_domain_size(), E::ZERO);
+        fft::evaluate_poly_in_place(
+            &mut evaluations,
             domain.ce_twiddles(),
             domain.offset(),
             domain.ce_to_lde_blowup(),
-        )
+        );
+        evaluations
     }
 }
 
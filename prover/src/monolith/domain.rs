@@ -7,7 +7,7 @@ use common::ComputationContext;
 use math::{
     fft,
     field::StarkField,
-    utils::{get_power_series_with_offset, log2},
+    utils::{get_power_series_with_offset_unchecked, log2_unchecked},
 };
 
 // TYPES AND INTERFACES
@@ -36,14 +36,27 @@ pub struct StarkDomain<B: StarkField> {
 impl<B: StarkField> StarkDomain<B> {
     /// Returns a new STARK domain initialized with the provided `context`.
     pub fn new(context: &ComputationContext) -> Self {
-        let lde_domain = build_lde_domain(context.lde_domain_size(), context.domain_offset());
-        let trace_twiddles = fft::get_twiddles(context.trace_length());
-        let ce_twiddles = fft::get_twiddles(context.ce_domain_size());
+        Self::from_sizes(
+            context.trace_length(),
+            context.ce_domain_size(),
+            context.lde_domain_size(),
+            context.domain_offset(),
+        )
+    }
+
+    /// Returns a new STARK domain with the specified sizes of trace, constraint evaluation, and
+    /// LDE domains; the LDE domain is shifted by the specified `offset`.
+    pub fn from_sizes(
+        trace_length: usize,
+        ce_domain_size: usize,
+        lde_domain_size: usize,
+        offset: B,
+    ) -> Self {
         StarkDomain {
-            lde_domain,
-            trace_twiddles,
-            ce_twiddles,
-            ce_to_lde_blowup: context.lde_domain_size() / context.ce_domain_size(),
+            lde_domain: build_lde_domain(lde_domain_size, offset),
+            trace_twiddles: fft::get_twiddles(trace_length),
+            ce_twiddles: fft::get_twiddles(ce_domain_size),
+            ce_to_lde_blowup: lde_domain_size / ce_domain_size,
         }
     }
 
@@ -117,6 +130,7 @@ impl<B: StarkField> StarkDomain<B> {
 // ================================================================================================
 
 fn build_lde_domain<B: StarkField>(domain_size: usize, offset: B) -> Vec<B> {
-    let g = B::get_root_of_unity(log2(domain_size));
-    get_power_series_with_offset(g, offset, domain_size)
+    let g = B::get_root_of_unity(log2_unchecked(domain_size))
+        .unwrap_or_else(|err| panic!("invalid domain size {}: {}", domain_size, err));
+    get_power_series_with_offset_unchecked(g, offset, domain_size)
 }
@@ -4,87 +4,252 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
+    super::{aux_segment::AuxSegmentBuilder, blinding::TraceBlinder, config::ProverConfig},
     constraints::{ConstraintCommitment, ConstraintEvaluator},
     deep_fri::CompositionPoly,
-    trace::ExecutionTrace,
-    ProverChannel, StarkDomain,
+    trace::{CommittedTrace, ExecutionTrace, ExtendedTrace, TracePolyTable},
+    AirCache, ProverChannel, ProverObserver, StarkDomain,
+};
+use common::{
+    errors::{ProverError, ProvingPhase},
+    proof::{OodEvaluationFrame, StarkProof},
+    Air, ProofOfWork, ProofOptions,
 };
-use common::{errors::ProverError, proof::StarkProof, Air, PublicCoin};
 use crypto::Hasher;
 use log::debug;
-use math::{fft::infer_degree, field::FieldElement, utils::log2};
+use math::{
+    fft::infer_degree,
+    field::{FieldElement, StarkField},
+    utils::log2_unchecked,
+};
 use std::time::Instant;
 
+// PROVER TRACE
+// ================================================================================================
+
+/// Execution trace against which a proof is generated.
+pub enum ProverTrace<'a, B: StarkField> {
+    /// An execution trace which is extended and committed to as a part of proof generation.
+    Execution(ExecutionTrace<B>),
+    /// An execution trace which has already been extended and committed to.
+    Committed(&'a CommittedTrace<B>),
+}
+
+// PROVER HOOKS
+// ================================================================================================
+
+/// Inputs and extension points of proof generation in addition to the AIR and the execution
+/// trace; these are set up by [Prover](super::Prover).
+pub struct ProverHooks<'a, B: StarkField> {
+    /// Extra polynomials to commit to together with the execution trace.
+    pub extra_polys: Option<ExecutionTrace<B>>,
+    /// Builder of the auxiliary trace segment.
+    pub aux_builder: Option<&'a dyn AuxSegmentBuilder<B>>,
+    /// Proof-of-work scheme applied to the query seed.
+    pub pow: &'a dyn ProofOfWork,
+    /// Blinder used to commit to blinded versions of trace registers.
+    pub blinder: Option<&'a mut dyn TraceBlinder<B>>,
+    /// Observer notified about commitments and challenges.
+    pub observer: &'a mut dyn ProverObserver,
+    /// Resource limits of proof generation.
+    pub config: &'a ProverConfig,
+    /// Cache of static data derived from the AIR.
+    pub cache: &'a mut AirCache<B>,
+}
+
 // PROOF GENERATION PROCEDURE
 // ================================================================================================
 
-pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>(
-    air: A,
-    trace: ExecutionTrace<A::BaseElement>,
-) -> Result<StarkProof, ProverError> {
+/// Generates a proof for the specified `air` and `trace`; commitments are built using hash
+/// function `H`, while the Fiat-Shamir transcript is derived using hash function `T`.
+///
+/// If the `trace` has already been committed to, the commitment must have been built using
+/// hash function `H`, and the blinder is not used. Extra polynomials, if any, are expected to be
+/// consistent with the number of extra polynomials declared by the AIR. The auxiliary segment
+/// builder is expected to be provided if and only if the AIR declares an auxiliary segment, in
+/// which case the `trace` must not have been committed to yet.
+pub fn generate_proof<A, E, H, T>(
+    mut air: A,
+    trace: ProverTrace<A::BaseElement>,
+    hooks: ProverHooks<A::BaseElement>,
+) -> Result<StarkProof, ProverError>
+where
+    A: Air,
+    E: FieldElement + From<A::BaseElement>,
+    H: Hasher,
+    T: Hasher,
+{
+    let ProverHooks {
+        extra_polys,
+        aux_builder,
+        pow,
+        blinder,
+        observer,
+        config,
+        cache,
+    } = hooks;
+
     // create a channel; this simulates interaction between the prover and the verifier;
     // the channel will be used to commit to values and to draw randomness that should
     // come from the verifier
-    let mut channel = ProverChannel::<H>::new(air.context());
+    let mut channel = ProverChannel::<H, T>::with_observer(air.context(), observer);
 
     let context = air.context().clone(); // TODO: find a better way?
+    let num_constraints = air.num_constraints();
 
-    // 1 ----- extend execution trace -------------------------------------------------------------
+    // 1 ----- commit to blinded registers ---------------------------------------------------------
 
-    // build computation domain; this is used later for polynomial evaluations
+    // this must be done before the trace is extended because the blinder commits to the values
+    // of the original execution trace; the commitments are sent to the channel later, after the
+    // commitment to the extended trace
     let now = Instant::now();
-    let domain = StarkDomain::new(air.context());
-    debug!(
-        "Built domain of 2^{} elements in {} ms",
-        log2(domain.lde_domain_size()),
-        now.elapsed().as_millis()
-    );
-
-    // extend the execution trace; this interpolates each register of the trace into a polynomial,
-    // and then evaluates the polynomial over the LDE domain; each of the trace polynomials has
-    // degree = trace_length - 1
-    let (extended_trace, trace_polys) = trace.extend(&domain);
+    let blinded_registers = air.get_blinded_registers();
+    let mut aux_roots = Vec::with_capacity(blinded_registers.len());
+    if let (Some(blinder), ProverTrace::Execution(trace)) = (blinder, &trace) {
+        for &register in blinded_registers.iter() {
+            assert!(
+                register < trace.width(),
+                "blinded register {} is out of bounds for trace of width {}",
+                register,
+                trace.width()
+            );
+            aux_roots.push(blinder.commit(register, trace.get_register(register)));
+        }
+        debug!(
+            "Committed to {} blinded registers in {} ms",
+            aux_roots.len(),
+            now.elapsed().as_millis()
+        );
+    }
+
+    // 2 ----- build computation domain -----------------------------------------------------------
+
+    // build computation domain (or get it from the cache); this is used later for polynomial
+    // evaluations
+    let now = Instant::now();
+    let domain = cache.get_domain(air.context());
     debug!(
-        "Extended execution trace of {} registers from 2^{} to 2^{} steps ({}x blowup) in {} ms",
-        extended_trace.width(),
-        log2(trace_polys.poly_size()),
-        log2(extended_trace.len()),
-        extended_trace.blowup(),
+        "Prepared domain of 2^{} elements in {} ms",
+        log2_unchecked(domain.lde_domain_size()),
         now.elapsed().as_millis()
     );
 
-    // 2 ----- commit to the extended execution trace ---------------------------------------------
-    let now = Instant::now();
-    let trace_tree = extended_trace.build_commitment::<H>();
+    // 3 ----- extend and commit to the execution trace -------------------------------------------
+
+    // this is skipped if the trace has already been extended and committed to; if the auxiliary
+    // segment is to be built, a copy of the main segment is retained for the builder
+    let committed_trace;
+    let mut main_trace = None;
+    let trace = match trace {
+        ProverTrace::Execution(trace) => {
+            if aux_builder.is_some() {
+                main_trace = Some(trace.clone());
+            }
+            let layout = air.get_column_layout();
+            committed_trace =
+                commit_trace::<_, H>(trace, &domain, context.options(), layout, config)?;
+            &committed_trace
+        }
+        ProverTrace::Committed(trace) => trace,
+    };
+    let extended_trace = trace.extended_trace();
+    let trace_tree = trace.trace_tree();
     channel.commit_trace(*trace_tree.root());
-    debug!(
-        "Committed to extended execution trace by building a Merkle tree of depth {} in {} ms",
-        trace_tree.depth(),
-        now.elapsed().as_millis()
+    channel.commit_aux_roots(&blinded_registers, aux_roots);
+
+    // extend and commit to extra polynomials in the same way as to the execution trace; this
+    // must be done before constraint evaluation so that the commitment is bound into the seed
+    // of constraint composition coefficients
+    let extra_trace = match extra_polys {
+        Some(extra_polys) => {
+            let layout = (0..extra_polys.width()).collect();
+            let extra_trace =
+                commit_trace::<_, H>(extra_polys, &domain, context.options(), layout, config)?;
+            channel.commit_extra_root(*extra_trace.trace_tree().root());
+            Some(extra_trace)
+        }
+        None => None,
+    };
+
+    // build the auxiliary segment of the trace from random elements drawn from the commitments
+    // made so far, and commit to the segment in the same way as to the main segment; the random
+    // elements are passed to the AIR before any constraints are built
+    let aux_trace = match (aux_builder, main_trace) {
+        (Some(builder), Some(main_trace)) => {
+            let now = Instant::now();
+            let rand_elements = channel.draw_aux_rand_elements::<A::BaseElement>();
+            air.set_aux_rand_elements(&rand_elements);
+            let aux_segment = builder.build_aux_segment(&main_trace, &rand_elements);
+            let aux_segment_width = context.aux_segment_width();
+            if aux_segment.width() != aux_segment_width {
+                return Err(ProverError::AuxSegmentWidthMismatch(
+                    aux_segment_width,
+                    aux_segment.width(),
+                ));
+            }
+            debug!(
+                "Built auxiliary trace segment of {} registers in {} ms",
+                aux_segment.width(),
+                now.elapsed().as_millis()
+            );
+
+            // make sure the full trace is valid against the AIR; as with the main segment, this
+            // is done in debug mode only
+            #[cfg(debug_assertions)]
+            main_trace
+                .append_segment(aux_segment.clone())
+                .validate(&air);
+
+            let layout = (0..aux_segment.width()).collect();
+            let aux_trace =
+                commit_trace::<_, H>(aux_segment, &domain, context.options(), layout, config)?;
+            channel.commit_aux_segment(*aux_trace.trace_tree().root());
+            Some(aux_trace)
+        }
+        _ => None,
+    };
+    let full_trace = ExtendedTrace::new(
+        extended_trace,
+        aux_trace
+            .as_ref()
+            .map(|aux_trace| aux_trace.extended_trace()),
     );
 
-    // 3 ----- evaluate constraints ---------------------------------------------------------------
+    // 4 ----- evaluate constraints ---------------------------------------------------------------
     let now = Instant::now();
 
     // build constraint evaluator; the channel is passed in for the evaluator to draw random
     // values from; these values are used by the evaluator to compute a random linear
     // combination of constraint evaluations
-    let evaluator = ConstraintEvaluator::new(air, &channel);
+    let evaluator = ConstraintEvaluator::new(air, &channel, cache);
 
     // apply constraint evaluator to the extended trace table to generate a
     // constraint evaluation table
-    let constraint_evaluations = evaluator.evaluate(&extended_trace, &domain);
+    let constraint_evaluations = evaluator.evaluate(&full_trace, &domain);
     debug!(
         "Evaluated constraints over domain of 2^{} elements in {} ms",
-        log2(constraint_evaluations.num_rows()),
+        log2_unchecked(constraint_evaluations.num_rows()),
         now.elapsed().as_millis()
     );
 
-    // 4 ----- commit to constraint evaluations ---------------------------------------------------
+    // when profiling is enabled, collect and report statistics about transition constraints
+    if config.profiles_constraints() {
+        let now = Instant::now();
+        let profile = evaluator.profile(&full_trace, &domain);
+        profile.log();
+        debug!(
+            "Profiled {} transition constraints in {} ms",
+            profile.constraints().len(),
+            now.elapsed().as_millis()
+        );
+    }
+    config.check_deadline(ProvingPhase::ConstraintEvaluation)?;
+
+    // 5 ----- commit to constraint evaluations ---------------------------------------------------
 
     // first, build a single constraint polynomial from all constraint evaluations
     let now = Instant::now();
-    let constraint_poly = constraint_evaluations.into_poly()?;
+    let constraint_poly = constraint_evaluations.into_poly(cache)?;
     debug!(
         "Converted constraint evaluations into a single polynomial of degree {} in {} ms",
         constraint_poly.degree(),
@@ -96,21 +261,25 @@ pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>
     let combined_constraint_evaluations = constraint_poly.evaluate(&domain);
     debug!(
         "Evaluated constraint polynomial over LDE domain (2^{} elements) in {} ms",
-        log2(combined_constraint_evaluations.len()),
+        log2_unchecked(combined_constraint_evaluations.len()),
         now.elapsed().as_millis()
     );
 
     // finally, commit to constraint polynomial evaluations
     let now = Instant::now();
-    let constraint_commitment = ConstraintCommitment::<E, H>::new(combined_constraint_evaluations);
+    let constraint_commitment = ConstraintCommitment::<E, H>::new(
+        combined_constraint_evaluations,
+        context.options().byte_encoding(),
+    );
     channel.commit_constraints(constraint_commitment.root());
     debug!(
         "Committed to constraint evaluations by building a Merkle tree of depth {} in {} ms",
         constraint_commitment.tree_depth(),
         now.elapsed().as_millis()
     );
+    config.check_deadline(ProvingPhase::ConstraintCommitment)?;
 
-    // 5 ----- build DEEP composition polynomial --------------------------------------------------
+    // 6 ----- build DEEP composition polynomial --------------------------------------------------
     let now = Instant::now();
 
     // draw an out-of-domain point z. Depending on the type of E, the point is drawn either
@@ -126,11 +295,45 @@ pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>
     let coefficients = channel.draw_composition_coefficients();
 
     // initialize composition polynomial
-    let mut composition_poly = CompositionPoly::new(&context, z, coefficients);
+    let mut composition_poly = CompositionPoly::new::<A::BaseElement>(&context, z, coefficients);
 
     // combine all trace polynomials together and merge them into the composition polynomial;
-    // ood_frame are trace states at two out-of-domain points, and will go into the proof
-    let ood_frame = composition_poly.add_trace_polys(trace_polys);
+    // ood_frame are trace states at two out-of-domain points, and will go into the proof.
+    // polynomials of the auxiliary segment, if any, follow polynomials of the main segment
+    let ood_frame = match &aux_trace {
+        Some(aux_trace) => {
+            let (main_polys, aux_polys) = (trace.trace_polys(), aux_trace.trace_polys());
+            let polys = (0..main_polys.num_polys())
+                .map(|i| main_polys.get_poly(i))
+                .chain((0..aux_polys.num_polys()).map(|i| aux_polys.get_poly(i)))
+                .map(|poly| poly.to_vec())
+                .collect();
+            composition_poly.add_trace_polys(&TracePolyTable::new(polys))
+        }
+        None => composition_poly.add_trace_polys(trace.trace_polys()),
+    };
+
+    // extra polynomials are merged into the composition polynomial in the same way, but they
+    // are evaluated only at z
+    let extra_at_z = match &extra_trace {
+        Some(extra_trace) => composition_poly.add_extra_polys(extra_trace.trace_polys()),
+        None => Vec::new(),
+    };
+
+    // values of local registers at z * g are not referenced by any constraint, and thus, are
+    // not included in the proof
+    let trace_at_z2 = context
+        .next_state_registers()
+        .iter()
+        .map(|&i| ood_frame.next[i])
+        .collect::<Vec<_>>();
+    let encoding = context.options().byte_encoding();
+    let ood_frame = OodEvaluationFrame {
+        trace_at_z1: encoding.encode_elements(&ood_frame.current).into_owned(),
+        trace_at_z2: encoding.encode_elements(&trace_at_z2).into_owned(),
+        extra_at_z: encoding.encode_elements(&extra_at_z).into_owned(),
+    };
+    channel.send_ood_frame(ood_frame);
 
     // merge constraint polynomial into the composition polynomial
     composition_poly.add_constraint_poly(constraint_poly);
@@ -141,7 +344,7 @@ pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>
         now.elapsed().as_millis()
     );
 
-    // 6 ----- evaluate DEEP composition polynomial over LDE domain -------------------------------
+    // 7 ----- evaluate DEEP composition polynomial over LDE domain -------------------------------
     let now = Instant::now();
     let composed_evaluations = composition_poly.evaluate(&domain);
     // we check the following condition in debug mode only because infer_degree is an expensive
@@ -152,11 +355,12 @@ pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>
     );
     debug!(
         "Evaluated DEEP composition polynomial over LDE domain (2^{} elements) in {} ms",
-        log2(context.lde_domain_size()),
+        log2_unchecked(context.lde_domain_size()),
         now.elapsed().as_millis()
     );
+    config.check_deadline(ProvingPhase::DeepComposition)?;
 
-    // 7 ----- compute FRI layers for the composition polynomial ----------------------------------
+    // 8 ----- compute FRI layers for the composition polynomial ----------------------------------
     let now = Instant::now();
     let mut fri_prover = fri::FriProver::new(context.options().to_fri_options());
     fri_prover.build_layers(&mut channel, composed_evaluations, &domain.lde_values());
@@ -165,44 +369,123 @@ pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>
         fri_prover.num_layers(),
         now.elapsed().as_millis()
     );
+    config.check_deadline(ProvingPhase::FriCommitment)?;
 
-    // 8 ----- determine query positions ----------------------------------------------------------
+    // 9 ----- determine query positions ----------------------------------------------------------
     let now = Instant::now();
 
-    // apply proof-of-work to the query seed
-    channel.grind_query_seed();
+    // bind the FRI remainder to the query seed, and apply proof-of-work to the seed
+    channel.commit_fri_remainder::<A::BaseElement, E>(&fri_prover.remainder());
+    channel.grind_query_seed(pow);
+    debug!(
+        "Applied proof-of-work with difficulty of {} bits to the query seed",
+        channel.pow_difficulty()
+    );
 
-    // generate pseudo-random query positions
-    let query_positions = channel.draw_query_positions();
+    // generate pseudo-random query positions; FRI layers are queried at all of these positions,
+    // while trace and constraint commitments are queried only at the first num_queries of them
+    let fri_query_positions = channel.draw_fri_query_positions();
+    let query_positions = &fri_query_positions[..context.options().num_queries()];
     debug!(
-        "Determined {} query positions in {} ms",
+        "Determined {} query positions ({} for FRI only) in {} ms",
         query_positions.len(),
+        fri_query_positions.len() - query_positions.len(),
         now.elapsed().as_millis()
     );
+    config.check_deadline(ProvingPhase::QueryGeneration)?;
 
-    // 9 ----- build proof object -----------------------------------------------------------------
+    // 10 ----- build proof object ----------------------------------------------------------------
     let now = Instant::now();
 
     // generate FRI proof
-    let fri_proof = fri_prover.build_proof(&query_positions);
+    let fri_proof = fri_prover.build_proof(&fri_query_positions);
 
     // query the execution trace at the selected position; for each query, we need the
     // state of the trace at that position + Merkle authentication path
-    let trace_queries = extended_trace.query(trace_tree, &query_positions);
+    let mut trace_queries = extended_trace.query(trace_tree, query_positions, encoding);
+    if context.options().compact_trace_queries() {
+        trace_queries = trace_queries.compact::<A::BaseElement>(encoding);
+    }
 
     // query the constraint commitment at the selected positions; for each query, we need just
     // a Merkle authentication path. this is because constraint evaluations for each step are
     // merged into a single value and Merkle authentication paths contain these values already
-    let constraint_queries = constraint_commitment.query(&query_positions);
+    let constraint_queries = constraint_commitment.query(query_positions);
+
+    // query extra polynomials and the auxiliary trace segment at the same positions as the
+    // execution trace
+    let query_trace = |committed: &CommittedTrace<A::BaseElement>| {
+        let queries =
+            committed
+                .extended_trace()
+                .query(committed.trace_tree(), query_positions, encoding);
+        if context.options().compact_trace_queries() {
+            queries.compact::<A::BaseElement>(encoding)
+        } else {
+            queries
+        }
+    };
+    let extra_queries = extra_trace.as_ref().map(query_trace);
+    let aux_segment_queries = aux_trace.as_ref().map(query_trace);
 
     // build the proof object
-    let proof = channel.build_proof::<A::BaseElement, E>(
+    let proof = channel.build_proof::<A::BaseElement>(
+        num_constraints,
         trace_queries,
         constraint_queries,
-        ood_frame,
+        extra_queries,
+        aux_segment_queries,
         fri_proof,
     );
     debug!("Built proof object in {} ms", now.elapsed().as_millis());
 
     Ok(proof)
 }
+
+/// Extends the execution `trace` over the LDE `domain`, and commits to the extended trace using
+/// hash function `H`; values of trace registers are placed into rows of the commitment according
+/// to the specified column `layout`.
+pub fn commit_trace<B: StarkField, H: Hasher>(
+    trace: ExecutionTrace<B>,
+    domain: &StarkDomain<B>,
+    options: &ProofOptions,
+    layout: Vec<usize>,
+    config: &ProverConfig,
+) -> Result<CommittedTrace<B>, ProverError> {
+    // extend the execution trace; this interpolates each register of the trace into a polynomial,
+    // and then evaluates the polynomial over the LDE domain; each of the trace polynomials has
+    // degree = trace_length - 1
+    let now = Instant::now();
+    let (mut extended_trace, trace_polys) = trace.extend(domain);
+    debug!(
+        "Extended execution trace of {} registers from 2^{} to 2^{} steps ({}x blowup) in {} ms",
+        extended_trace.width(),
+        log2_unchecked(trace_polys.poly_size()),
+        log2_unchecked(extended_trace.len()),
+        extended_trace.blowup(),
+        now.elapsed().as_millis()
+    );
+    config.check_deadline(ProvingPhase::TraceExtension)?;
+
+    // commit to the extended execution trace
+    let now = Instant::now();
+    extended_trace.set_column_layout(layout);
+    let trace_tree = extended_trace.build_commitment::<H>(
+        options.trace_leaf_batching(),
+        options.trace_row_hashing(),
+        options.byte_encoding(),
+    );
+    debug!(
+        "Committed to extended execution trace by building a Merkle tree of depth {} in {} ms",
+        trace_tree.depth(),
+        now.elapsed().as_millis()
+    );
+    config.check_deadline(ProvingPhase::TraceCommitment)?;
+
+    Ok(CommittedTrace::new(
+        options.clone(),
+        extended_trace,
+        trace_polys,
+        trace_tree,
+    ))
+}
@@ -3,70 +3,451 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::channel::ProverChannel;
+use crate::{
+    aux_segment::AuxSegmentBuilder,
+    blinding::TraceBlinder,
+    channel::ProverChannel,
+    config::ProverConfig,
+    observer::{NullObserver, ProverObserver},
+};
 use common::{
-    errors::ProverError, proof::StarkProof, Air, FieldExtension, HashFunction, ProofOptions,
-    TraceInfo,
+    errors::ProverError, limits, proof::StarkProof, Air, ExtensionVisitor, HashFunction,
+    HashProofOfWork, ProofOfWork, ProofOptions, TraceInfo,
 };
 use crypto::hash::{Blake3_256, Sha3_256};
-use math::field::QuadExtension;
+use math::field::{ExtensionOf, StarkField};
 
 mod domain;
-use domain::StarkDomain;
+pub(crate) use domain::StarkDomain;
+
+mod cache;
+pub use cache::AirCache;
 
 mod constraints;
 mod deep_fri;
 
 mod trace;
-pub use trace::{ExecutionTrace, ExecutionTraceFragment, TracePolyTable};
+pub use trace::{
+    CommittedTrace, ExecutionTrace, ExecutionTraceFragment, TracePolyTable, TraceWord,
+};
 
 mod generation;
-use generation::generate_proof;
+use generation::{generate_proof, ProverHooks, ProverTrace};
+
+#[cfg(test)]
+mod tests;
 
 // PROVER
 // ================================================================================================
 
 /// Generates a STARK proof attesting that the specified `trace` is a valid execution trace of the
 /// computation described by AIR generated using the specified public inputs.
-#[rustfmt::skip]
+///
+/// This is the same as `Prover::<AIR>::new(options).prove(trace, pub_inputs)`; use [Prover] to
+/// customize proof generation.
+///
+/// Proof generation is deterministic: for identical trace, public inputs, and options, the
+/// resulting proof is the same bit-for-bit regardless of the number of threads used when the
+/// `concurrent` feature is enabled. The only exception are proofs generated with options which
+/// specify a grinding budget (see [ProofOptions::with_grinding_budget()]): the proof-of-work
+/// difficulty for such proofs depends on the speed of the machine, and thus, these proofs are not
+/// deterministic.
 pub fn prove<AIR: Air>(
     trace: ExecutionTrace<AIR::BaseElement>,
     pub_inputs: AIR::PublicInputs,
     options: ProofOptions,
 ) -> Result<StarkProof, ProverError> {
-    // create an instance of AIR for the provided parameters. this takes a generic description of
-    // the computation (provided via AIR type), and creates a description of a specific execution
-    // of the computation for the provided public inputs.
+    Prover::<AIR>::new(options).prove(trace, pub_inputs)
+}
+
+/// Generates STARK proofs for the computation described by `AIR`.
+///
+/// A prover is instantiated from proof options, and can then be customized via its `with_*`
+/// methods, which can be combined freely: for example, a proof for a computation with an
+/// auxiliary trace segment can be generated under a deadline while an observer records the
+/// transcript. A prover without any customizations generates the same proofs as [prove()].
+pub struct Prover<'a, AIR: Air> {
+    options: ProofOptions,
+    config: ProverConfig,
+    extra_polys: Option<ExecutionTrace<AIR::BaseElement>>,
+    aux_builder: Option<&'a dyn AuxSegmentBuilder<AIR::BaseElement>>,
+    pow: &'a dyn ProofOfWork,
+    blinder: Option<&'a mut dyn TraceBlinder<AIR::BaseElement>>,
+    observer: Option<&'a mut dyn ProverObserver>,
+    cache: Option<&'a mut AirCache<AIR::BaseElement>>,
+}
+
+impl<'a, AIR: Air> Prover<'a, AIR> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new prover which generates proofs with the specified `options`.
+    pub fn new(options: ProofOptions) -> Self {
+        Prover {
+            options,
+            config: ProverConfig::default(),
+            extra_polys: None,
+            aux_builder: None,
+            pow: &HashProofOfWork,
+            blinder: None,
+            observer: None,
+            cache: None,
+        }
+    }
+
+    // BUILDER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Makes the prover check the estimated amount of memory needed to generate a proof against
+    /// the budget specified by the `config`, and abort proof generation if the deadline specified
+    /// by the `config` passes.
+    pub fn with_config(mut self, config: ProverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Makes the prover apply the specified proof-of-work scheme to the query seed instead of the
+    /// default [HashProofOfWork]. The proof can be verified only by a verifier which uses the
+    /// same scheme.
+    pub fn with_pow(mut self, pow: &'a dyn ProofOfWork) -> Self {
+        self.pow = pow;
+        self
+    }
+
+    /// Makes the prover notify the specified `observer` about commitments and challenges as they
+    /// are produced during proof generation.
+    pub fn with_observer(mut self, observer: &'a mut dyn ProverObserver) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Makes the prover use the specified `blinder` to commit to blinded versions of trace
+    /// registers designated by the AIR. Roots of these commitments are included in the proof,
+    /// while the blinding information remains with the `blinder`.
+    ///
+    /// A blinder must be provided for computations which designate blinded registers; it is not
+    /// used for other computations.
+    pub fn with_blinder(mut self, blinder: &'a mut dyn TraceBlinder<AIR::BaseElement>) -> Self {
+        self.blinder = Some(blinder);
+        self
+    }
+
+    /// Makes the prover take static data derived from the AIR from the specified `cache`, and add
+    /// the data to the `cache` if it is not there yet.
+    ///
+    /// This is useful for generating many proofs of the same computation with traces of the same
+    /// length: data such as the STARK domain, periodic column values, and inverse divisor tables
+    /// is built only once. The generated proofs are the same as proofs generated without a cache.
+    pub fn with_cache(mut self, cache: &'a mut AirCache<AIR::BaseElement>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Makes the prover commit to the specified extra polynomials together with the execution
+    /// trace.
+    ///
+    /// Extra polynomials are defined by their evaluations over the trace domain, one polynomial
+    /// per register of `extra_polys`. They are extended and committed to in the same way as the
+    /// execution trace, are evaluated at the out-of-domain point z, and are included into the
+    /// DEEP composition polynomial; thus, the proof attests that the committed extra polynomials
+    /// have the claimed values at z. This allows protocols built on top of the STARK (e.g.,
+    /// custom accumulators) to reuse the commitment and opening machinery of the prover.
+    ///
+    /// Extra polynomials must be provided for computations which declare them via
+    /// [with_extra_polys()](crate::ComputationContext::with_extra_polys).
+    pub fn with_extra_polys(mut self, extra_polys: ExecutionTrace<AIR::BaseElement>) -> Self {
+        self.extra_polys = Some(extra_polys);
+        self
+    }
+
+    /// Makes the prover use the specified `builder` to build the auxiliary trace segment; the
+    /// trace passed to [Prover::prove()] must then contain only the main segment of the
+    /// execution trace.
+    ///
+    /// The prover commits to the main segment first, draws random elements from this commitment,
+    /// and then uses the `builder` to build the auxiliary segment from the main segment and these
+    /// elements. The auxiliary segment is committed to separately, and its registers follow the
+    /// registers of the main segment in evaluation frames passed to transition constraints.
+    ///
+    /// A builder must be provided for computations which declare an auxiliary segment via
+    /// [with_aux_segment()](crate::ComputationContext::with_aux_segment); it is not used for
+    /// other computations.
+    pub fn with_aux_segment(
+        mut self,
+        builder: &'a dyn AuxSegmentBuilder<AIR::BaseElement>,
+    ) -> Self {
+        self.aux_builder = Some(builder);
+        self
+    }
+
+    // PROOF GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Generates a STARK proof attesting that the specified `trace` is a valid execution trace of
+    /// the computation described by AIR generated using the specified public inputs.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The trace is too long for the base field, or the proof options are not consistent with
+    ///   the base field of the computation.
+    /// * The memory budget is exceeded and the config is strict; otherwise, exceeding the budget
+    ///   results only in a logged warning.
+    /// * The deadline passes before the proof is generated; the error specifies the last phase of
+    ///   proof generation which was completed.
+    /// * The computation designates blinded registers, but no blinder was provided.
+    /// * The number of registers in extra polynomials is different from the number of extra
+    ///   polynomials declared by the AIR, or extra polynomials and the trace have different
+    ///   lengths.
+    /// * The computation declares an auxiliary segment, but no builder was provided, or the
+    ///   builder returns a segment of a different width than declared by the AIR.
+    pub fn prove(
+        self,
+        trace: ExecutionTrace<AIR::BaseElement>,
+        pub_inputs: AIR::PublicInputs,
+    ) -> Result<StarkProof, ProverError> {
+        let Prover {
+            options,
+            config,
+            extra_polys,
+            aux_builder,
+            pow,
+            blinder,
+            observer,
+            cache,
+        } = self;
+        check_trace_length::<AIR::BaseElement>(trace.len(), &options)?;
+
+        // create an instance of AIR for the provided parameters. this takes a generic description
+        // of the computation (provided via AIR type), and creates a description of a specific
+        // execution of the computation for the provided public inputs.
+        let trace_info = TraceInfo {
+            length: trace.len(),
+            meta: Vec::new(),
+        };
+        let air = AIR::new(trace_info, pub_inputs, options);
+
+        // make sure the proof parameters are consistent with the base field of the computation
+        air.context()
+            .validate::<AIR::BaseElement>()
+            .map_err(ProverError::InvalidParameters)?;
+
+        // make sure proof generation fits into the memory budget
+        config.check_memory(air.context().estimate_prover_memory::<AIR::BaseElement>())?;
+
+        // make sure we can commit to blinded registers if the computation designates any
+        let num_blinded_registers = air.get_blinded_registers().len();
+        if num_blinded_registers > 0 && blinder.is_none() {
+            return Err(ProverError::TraceBlinderNotProvided(num_blinded_registers));
+        }
+
+        // make sure extra polynomials match the ones declared by the computation
+        let num_extra_polys = air.context().num_extra_polys();
+        let num_provided_polys = extra_polys.as_ref().map_or(0, |polys| polys.width());
+        if num_provided_polys != num_extra_polys {
+            return Err(ProverError::ExtraPolyCountMismatch(
+                num_extra_polys,
+                num_provided_polys,
+            ));
+        }
+        if let Some(extra_polys) = &extra_polys {
+            if extra_polys.len() != trace.len() {
+                return Err(ProverError::ExtraPolyLengthMismatch(
+                    extra_polys.len(),
+                    trace.len(),
+                ));
+            }
+        }
+
+        // make sure the auxiliary segment can be built if the computation declares one; the
+        // builder is not used otherwise
+        let aux_segment_width = air.context().aux_segment_width();
+        if aux_segment_width > 0 && aux_builder.is_none() {
+            return Err(ProverError::AuxSegmentBuilderNotProvided(aux_segment_width));
+        }
+        let aux_builder = aux_builder.filter(|_| aux_segment_width > 0);
+
+        // make sure the specified trace is valid against the AIR. This checks validity of both,
+        // assertions and state transitions. we do this in debug mode only because this is a very
+        // expensive operation. when the computation declares an auxiliary segment, the trace is
+        // validated together with the segment once the segment is built.
+        #[cfg(debug_assertions)]
+        {
+            if aux_builder.is_none() {
+                trace.validate(&air);
+            }
+        }
+
+        // figure out which version of the generic proof generation procedure to run. this is a
+        // sort of static dispatch for selecting generic parameters: extension field and hash
+        // functions.
+        let (null_observer, new_cache) = (&mut NullObserver, &mut AirCache::new());
+        let field_extension = air.context().options().field_extension();
+        field_extension.visit(ProofGenerator {
+            air,
+            trace: ProverTrace::Execution(trace),
+            hooks: ProverHooks {
+                extra_polys,
+                aux_builder,
+                pow,
+                blinder: blinder.map(|blinder| blinder as &mut dyn TraceBlinder<_>),
+                observer: match observer {
+                    Some(observer) => observer,
+                    None => null_observer,
+                },
+                config: &config,
+                cache: cache.unwrap_or(new_cache),
+            },
+        })
+    }
+}
+
+// MULTIPLE STATEMENTS
+// ================================================================================================
+
+/// Extends the specified `trace` over the LDE domain and commits to it, so that proofs of several
+/// statements can be generated against the same trace via [prove_committed()].
+///
+/// The commitment is built using the blowup factor, hash function, trace leaf batching, and trace
+/// row hashing specified by the `options`; values of trace registers are placed into rows of the
+/// commitment in the order of their indexes. Validity of the trace is not checked at this point because
+/// the trace is not tied to any specific AIR; use [ExecutionTrace::check()] for this purpose.
+///
+/// # Errors
+/// Returns an error if the LDE domain for the trace does not fit into the two-adic subgroup
+/// of the base field.
+pub fn commit_trace<B: StarkField>(
+    trace: ExecutionTrace<B>,
+    options: &ProofOptions,
+) -> Result<CommittedTrace<B>, ProverError> {
+    check_trace_length::<B>(trace.len(), options)?;
+
+    // constraints are not evaluated here, and thus, the constraint evaluation domain is
+    // irrelevant; we set it to the smallest possible size
+    let trace_length = trace.len();
+    let lde_domain_size = trace_length * options.blowup_factor();
+    let offset = options.domain_offset();
+    let domain = StarkDomain::from_sizes(trace_length, trace_length, lde_domain_size, offset);
+
+    let layout = (0..trace.width()).collect();
+    let config = ProverConfig::default();
+    match options.hash_fn() {
+        HashFunction::Blake3_256 => {
+            generation::commit_trace::<B, Blake3_256>(trace, &domain, options, layout, &config)
+        }
+        HashFunction::Sha3_256 => {
+            generation::commit_trace::<B, Sha3_256>(trace, &domain, options, layout, &config)
+        }
+    }
+}
+
+/// Generates a STARK proof attesting that the committed `trace` is a valid execution trace of
+/// the computation described by AIR generated using the specified public inputs.
+///
+/// The trace is not extended or committed to again, and thus, generating proofs of several
+/// statements about the same trace (e.g., for different AIRs or for different public inputs)
+/// is cheaper than calling [prove()] for each of them. The proofs are independent of each
+/// other and can be verified separately; all of them contain the same trace commitment root.
+///
+/// # Errors
+/// Returns an error if the `options` specify a different blowup factor, hash function, trace leaf
+/// batching, or trace row hashing than the options with which the trace was committed to, if the
+/// AIR expects a trace of a different width or a different column layout, or if the AIR
+/// designates blinded registers or declares extra polynomials or an auxiliary trace segment.
+pub fn prove_committed<AIR: Air>(
+    trace: &CommittedTrace<AIR::BaseElement>,
+    pub_inputs: AIR::PublicInputs,
+    options: ProofOptions,
+) -> Result<StarkProof, ProverError> {
     let trace_info = TraceInfo {
-        length: trace.len(),
+        length: trace.trace_length(),
         meta: Vec::new(),
     };
     let air = AIR::new(trace_info, pub_inputs, options);
+    air.context()
+        .validate::<AIR::BaseElement>()
+        .map_err(ProverError::InvalidParameters)?;
+    let aux_segment_width = air.context().aux_segment_width();
+    if aux_segment_width > 0 {
+        return Err(ProverError::AuxSegmentBuilderNotProvided(aux_segment_width));
+    }
+    trace.check_air(&air)?;
+    let num_extra_polys = air.context().num_extra_polys();
+    if num_extra_polys > 0 {
+        return Err(ProverError::ExtraPolyCountMismatch(num_extra_polys, 0));
+    }
 
-    // make sure the specified trace is valid against the AIR. This checks validity of both,
-    // assertions and state transitions. we do this in debug mode only because this is a very
-    // expensive operation.
-    #[cfg(debug_assertions)]
-    trace.validate(&air);
-
-    // figure out which version of the generic proof generation procedure to run. this is a sort
-    // of static dispatch for selecting two generic parameter: extension field and hash function.
-    match air.context().options().field_extension() {
-        FieldExtension::None => match air.context().options().hash_fn() {
-            HashFunction::Blake3_256 => {
-                generate_proof::<AIR, AIR::BaseElement, Blake3_256>(air, trace)
-            }
-            HashFunction::Sha3_256 => {
-                generate_proof::<AIR, AIR::BaseElement, Sha3_256>(air, trace)
-            },
-        },
-        FieldExtension::Quadratic => match air.context().options().hash_fn() {
-            HashFunction::Blake3_256 => {
-                generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Blake3_256>(air, trace)
-            }
-            HashFunction::Sha3_256 => {
-                generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Sha3_256>(air, trace)
-            }
+    let field_extension = air.context().options().field_extension();
+    field_extension.visit(ProofGenerator {
+        air,
+        trace: ProverTrace::Committed(trace),
+        hooks: ProverHooks {
+            extra_polys: None,
+            aux_builder: None,
+            pow: &HashProofOfWork,
+            blinder: None,
+            observer: &mut NullObserver,
+            config: &ProverConfig::default(),
+            cache: &mut AirCache::new(),
         },
+    })
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Arguments of the generic proof generation procedure; the procedure is run in the field
+/// selected by the field extension specified in proof options.
+struct ProofGenerator<'a, AIR: Air> {
+    air: AIR,
+    trace: ProverTrace<'a, AIR::BaseElement>,
+    hooks: ProverHooks<'a, AIR::BaseElement>,
+}
+
+impl<AIR: Air> ExtensionVisitor<AIR::BaseElement> for ProofGenerator<'_, AIR> {
+    type Output = Result<StarkProof, ProverError>;
+
+    fn visit<E: ExtensionOf<AIR::BaseElement>>(self) -> Self::Output {
+        generate_proof_with_hashes::<AIR, E>(self.air, self.trace, self.hooks)
+    }
+}
+
+/// Selects hash functions for building commitments and for the Fiat-Shamir transcript, and runs
+/// the generic proof generation procedure for the selected functions.
+fn generate_proof_with_hashes<AIR: Air, E: ExtensionOf<AIR::BaseElement>>(
+    air: AIR,
+    trace: ProverTrace<AIR::BaseElement>,
+    hooks: ProverHooks<AIR::BaseElement>,
+) -> Result<StarkProof, ProverError> {
+    let options = air.context().options();
+    match (options.hash_fn(), options.transcript_hash_fn()) {
+        (HashFunction::Blake3_256, HashFunction::Blake3_256) => {
+            generate_proof::<AIR, E, Blake3_256, Blake3_256>(air, trace, hooks)
+        }
+        (HashFunction::Blake3_256, HashFunction::Sha3_256) => {
+            generate_proof::<AIR, E, Blake3_256, Sha3_256>(air, trace, hooks)
+        }
+        (HashFunction::Sha3_256, HashFunction::Blake3_256) => {
+            generate_proof::<AIR, E, Sha3_256, Blake3_256>(air, trace, hooks)
+        }
+        (HashFunction::Sha3_256, HashFunction::Sha3_256) => {
+            generate_proof::<AIR, E, Sha3_256, Sha3_256>(air, trace, hooks)
+        }
+    }
+}
+
+/// Makes sure the LDE domain for a trace of the specified length fits into the two-adic subgroup
+/// of the base field; otherwise, the domain generator would not exist and we would not be able
+/// to build the LDE.
+fn check_trace_length<B: StarkField>(
+    trace_length: usize,
+    options: &ProofOptions,
+) -> Result<(), ProverError> {
+    let max_trace_length = limits::max_trace_length::<B>(options.blowup_factor());
+    if trace_length > max_trace_length {
+        return Err(ProverError::TraceTooLongForField(
+            trace_length,
+            max_trace_length.trailing_zeros(),
+        ));
     }
+    Ok(())
 }
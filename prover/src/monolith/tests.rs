@@ -0,0 +1,723 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    tests::{
+        build_fib_inputs, build_fib_trace, build_proof_options, prove_fib, BlindedFibAir, FibAir,
+        FibInputs,
+    },
+    AirCache, ExecutionTrace, ProverConfig, ProverError, ProverObserver, ProvingPhase,
+};
+use common::{
+    errors::{ProofShapeError, VerifierError},
+    proof::{Commitments, StarkProof, TraceChunkOpening},
+    Air, Assertion, ComputationContext, Deserializable, EvaluationFrame, FieldExtension,
+    HashFunction, ProofOptions, Serializable, TraceInfo, TraceRowHashing,
+};
+use crypto::{hash::Blake3_256, DefaultRandomElementGenerator, RandomElementGenerator};
+use math::{
+    field::{f128::BaseElement, FieldElement},
+    polynom,
+};
+use std::time::{Duration, Instant};
+
+// PROOF OPTIONS
+// ================================================================================================
+
+#[test]
+fn prove_verify_cubic_extension() {
+    let options = ProofOptions::new(28, 16, 0, HashFunction::Blake3_256, FieldExtension::Cubic);
+    check_proof_verification(16, options);
+}
+
+#[test]
+fn prove_verify_trace_leaf_batching() {
+    let options = build_proof_options(false).with_trace_leaf_batching(8);
+    check_proof_verification(16, options);
+}
+
+#[test]
+fn prove_verify_chunked_row_hashing() {
+    let options = build_proof_options(false)
+        .with_trace_row_hashing(TraceRowHashing::Chunked(1))
+        .with_trace_leaf_batching(4);
+    check_proof_verification(16, options);
+}
+
+#[test]
+fn prove_verify_extra_fri_queries() {
+    let options = build_proof_options(false).with_num_fri_queries(40);
+    check_proof_verification(16, options);
+}
+
+#[test]
+fn prove_verify_compact_trace_queries() {
+    let options = build_proof_options(false).with_compact_trace_queries();
+    check_proof_verification(16, options);
+}
+
+#[test]
+fn prove_verify_fri_max_remainder_degree() {
+    let options = build_proof_options(false).with_fri_max_remainder_degree(1);
+    check_proof_verification(64, options);
+}
+
+#[test]
+fn fri_max_remainder_degree_reduces_fri_layers() {
+    let proof = prove_fib(1024, build_proof_options(false));
+    let options = build_proof_options(false).with_fri_max_remainder_degree(63);
+    let early_proof = prove_fib(1024, options);
+    assert!(early_proof.commitments.fri_roots.len() < proof.commitments.fri_roots.len());
+
+    let pub_inputs = build_fib_inputs(&build_fib_trace(1024));
+    verifier::verify::<FibAir>(early_proof, pub_inputs).unwrap();
+}
+
+#[test]
+fn prove_verify_transcript_hash_fn() {
+    let options = build_proof_options(false).with_transcript_hash_fn(HashFunction::Sha3_256);
+    check_proof_verification(16, options);
+    let options = build_proof_options(true).with_transcript_hash_fn(HashFunction::Sha3_256);
+    check_proof_verification(16, options);
+}
+
+// OBSERVER
+// ================================================================================================
+
+#[test]
+fn prove_with_observer_events() {
+    let trace = build_fib_trace(16);
+    let pub_inputs = build_fib_inputs(&trace);
+    let mut observer = RecordingObserver::default();
+    let proof = crate::Prover::<FibAir>::new(build_proof_options(false))
+        .with_observer(&mut observer)
+        .prove(trace, pub_inputs.clone())
+        .unwrap();
+
+    // recorded events must match the values which ended up in the proof
+    assert_eq!(
+        vec![
+            proof.commitments.trace_root,
+            proof.commitments.constraint_root
+        ],
+        observer.roots
+    );
+    assert_eq!(proof.commitments.fri_roots, observer.fri_roots);
+    let commitments = observer.commitments.unwrap();
+    assert_eq!(proof.commitments.trace_root, commitments.trace_root);
+    assert_eq!(
+        proof.commitments.constraint_root,
+        commitments.constraint_root
+    );
+    assert_eq!(proof.commitments.fri_roots, commitments.fri_roots);
+    assert!(commitments.aux_roots.is_empty());
+    assert_eq!(proof.ood_frame.trace_at_z1, observer.ood_frame.0);
+    assert_eq!(proof.ood_frame.trace_at_z2, observer.ood_frame.1);
+    assert!(observer.ood_frame.2.is_empty());
+    assert_eq!(proof.pow_nonce, observer.pow_nonce);
+    assert_eq!(16, observer.z.len());
+    assert!(!observer.positions.is_empty());
+
+    // a folding coefficient is drawn from every FRI layer right after the layer is committed to
+    for (root, alpha) in observer.fri_roots.iter().zip(observer.fri_alphas.iter()) {
+        let expected =
+            DefaultRandomElementGenerator::<Blake3_256>::new(*root, 0).draw::<BaseElement>();
+        assert_eq!(expected.to_bytes(), *alpha);
+    }
+
+    // 2 trace registers * 3 + 2 degree adjustment coefficients + 1 constraint coefficient
+    assert_eq!(9 * 16, observer.coefficients.len());
+
+    // events are reported in the order in which they happen in the protocol
+    let num_fri_layers = proof.commitments.fri_roots.len();
+    let mut expected = vec!["trace", "constraints", "z", "coefficients", "ood_frame"];
+    for _ in 0..num_fri_layers {
+        expected.extend_from_slice(&["fri_layer", "fri_alpha"]);
+    }
+    expected.extend_from_slice(&["commitments", "query_seed", "positions"]);
+    assert_eq!(expected, observer.events);
+
+    assert!(verifier::verify::<FibAir>(proof, pub_inputs).is_ok());
+}
+
+// EXTRA POLYNOMIALS
+// ================================================================================================
+
+#[test]
+fn prove_with_extra_polys() {
+    let options = build_proof_options(false);
+    let build_pub_inputs = || build_fib_inputs(&build_fib_trace(16));
+    let build_extra_polys = |width: usize, length: usize| {
+        let columns = (0..width)
+            .map(|i| BaseElement::prng_vector([i as u8 + 1; 32], length))
+            .collect::<Vec<_>>();
+        ExecutionTrace::init(columns)
+    };
+    let trace_length = build_fib_trace(16).len();
+
+    // a computation which declares extra polynomials cannot be proven without them, or with a
+    // different number of them
+    let result =
+        crate::prove::<ExtraPolyFibAir>(build_fib_trace(16), build_pub_inputs(), options.clone());
+    assert!(matches!(
+        result,
+        Err(ProverError::ExtraPolyCountMismatch(2, 0))
+    ));
+    let result = crate::Prover::<ExtraPolyFibAir>::new(options.clone())
+        .with_extra_polys(build_extra_polys(1, trace_length))
+        .prove(build_fib_trace(16), build_pub_inputs());
+    assert!(matches!(
+        result,
+        Err(ProverError::ExtraPolyCountMismatch(2, 1))
+    ));
+    let result = crate::Prover::<ExtraPolyFibAir>::new(options.clone())
+        .with_extra_polys(build_extra_polys(2, trace_length * 2))
+        .prove(build_fib_trace(16), build_pub_inputs());
+    assert!(matches!(
+        result,
+        Err(ProverError::ExtraPolyLengthMismatch(l1, l2)) if l1 == 2 * l2 && l2 == trace_length
+    ));
+
+    let option_sets = vec![
+        options.clone(),
+        build_proof_options(true),
+        options.clone().with_compact_trace_queries(),
+        options.clone().with_trace_leaf_batching(4),
+    ];
+    for options in option_sets {
+        let proof = crate::Prover::<ExtraPolyFibAir>::new(options.clone())
+            .with_extra_polys(build_extra_polys(2, trace_length))
+            .prove(build_fib_trace(16), build_pub_inputs())
+            .unwrap();
+        assert!(proof.commitments.extra_root.is_some());
+        assert!(verifier::verify::<ExtraPolyFibAir>(proof.clone(), build_pub_inputs()).is_ok());
+
+        // the proof verifies only against an AIR which declares the same extra polynomials
+        let result = verifier::verify::<FibAir>(proof.clone(), build_pub_inputs());
+        assert!(matches!(
+            result,
+            Err(VerifierError::InconsistentProof(
+                ProofShapeError::ExtraPolysMismatch("commits to", 0)
+            ))
+        ));
+
+        // tampering with openings of extra polynomials is detected in addition to all other
+        // mutations
+        let num_mutations =
+            verifier::mutation::check_mutations::<ExtraPolyFibAir, _>(&proof, build_pub_inputs);
+        let base_proof = prove_fib(16, options);
+        let num_base_mutations =
+            verifier::mutation::check_mutations::<FibAir, _>(&base_proof, build_pub_inputs);
+        assert_eq!(num_base_mutations + 2, num_mutations);
+    }
+
+    // evaluations of extra polynomials at the out-of-domain point are opened in the proof, and
+    // are passed to the observer together with the out-of-domain frame
+    let extra_polys = build_extra_polys(2, trace_length);
+    let mut observer = RecordingObserver::default();
+    let proof = crate::Prover::<ExtraPolyFibAir>::new(options.clone())
+        .with_extra_polys(extra_polys.clone())
+        .with_observer(&mut observer)
+        .prove(build_fib_trace(16), build_pub_inputs())
+        .unwrap();
+    assert_eq!(proof.ood_frame.extra_at_z, observer.ood_frame.2);
+    let trace_info = TraceInfo {
+        length: trace_length,
+        meta: vec![],
+    };
+    let air = ExtraPolyFibAir::new(trace_info, build_pub_inputs(), options);
+    let challenges =
+        verifier::extract_challenges::<BaseElement, BaseElement>(&proof, air.context()).unwrap();
+    let g = air.context().get_trace_domain_generator::<BaseElement>();
+    let xs = (0..trace_length)
+        .map(|i| g.exp((i as u64).into()))
+        .collect::<Vec<_>>();
+    let expected = (0..extra_polys.width())
+        .map(|i| {
+            let poly = polynom::interpolate(&xs, extra_polys.get_register(i), false);
+            polynom::eval(&poly, challenges.ood_point)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        BaseElement::elements_as_bytes(&expected),
+        &proof.ood_frame.extra_at_z[..]
+    );
+
+    // the commitment to extra polynomials is bound to the transcript, so it cannot be replaced
+    let mut bad_proof = proof;
+    bad_proof.commitments.extra_root = Some([1; 32]);
+    let result = verifier::verify::<ExtraPolyFibAir>(bad_proof, build_pub_inputs());
+    assert!(matches!(
+        result,
+        Err(VerifierError::ExtraQueryDoesNotMatchCommitment)
+    ));
+}
+
+// COLUMN LAYOUT
+// ================================================================================================
+
+#[test]
+fn prove_with_column_layout() {
+    let trace = build_fib_trace(16);
+    let pub_inputs = build_fib_inputs(&trace);
+    let proof =
+        crate::prove::<ReorderedFibAir>(trace, pub_inputs.clone(), build_proof_options(false))
+            .unwrap();
+
+    // committed rows are arranged according to the layout, so the proof verifies only against
+    // an AIR with the same layout
+    assert!(verifier::verify::<ReorderedFibAir>(proof.clone(), pub_inputs.clone()).is_ok());
+    assert!(verifier::verify::<FibAir>(proof, pub_inputs).is_err());
+}
+
+// PROVER CONFIG
+// ================================================================================================
+
+#[test]
+fn prove_with_memory_budget() {
+    let options = build_proof_options(false);
+    let build_pub_inputs = || build_fib_inputs(&build_fib_trace(64));
+    let air = FibAir::new(
+        TraceInfo {
+            length: 32,
+            meta: Vec::new(),
+        },
+        build_pub_inputs(),
+        options.clone(),
+    );
+    let estimated = air.context().estimate_prover_memory::<BaseElement>();
+    let expected = prove_fib(64, options.clone()).to_bytes();
+
+    // a budget which fits the estimate does not affect the proof
+    let config = ProverConfig::new(estimated).with_strict_budget();
+    let proof = crate::Prover::<FibAir>::new(options.clone())
+        .with_config(config)
+        .prove(build_fib_trace(64), build_pub_inputs())
+        .unwrap();
+    assert_eq!(expected, proof.to_bytes());
+
+    // in strict mode, exceeding the budget is an error
+    let config = ProverConfig::new(estimated - 1).with_strict_budget();
+    let result = crate::Prover::<FibAir>::new(options.clone())
+        .with_config(config)
+        .prove(build_fib_trace(64), build_pub_inputs());
+    assert!(matches!(
+        result,
+        Err(ProverError::MemoryBudgetExceeded(e, b)) if e == estimated && b == estimated - 1
+    ));
+
+    // otherwise, exceeding the budget results only in a warning
+    let config = ProverConfig::new(estimated - 1);
+    let proof = crate::Prover::<FibAir>::new(options)
+        .with_config(config)
+        .prove(build_fib_trace(64), build_pub_inputs())
+        .unwrap();
+    verifier::verify::<FibAir>(proof, build_pub_inputs()).unwrap();
+}
+
+#[test]
+fn prove_with_deadline() {
+    let options = build_proof_options(false);
+    let build_pub_inputs = || build_fib_inputs(&build_fib_trace(64));
+
+    // a deadline which has already passed aborts proof generation after the first phase
+    let config = ProverConfig::default().with_deadline(Instant::now());
+    let result = crate::Prover::<FibAir>::new(options.clone())
+        .with_config(config)
+        .prove(build_fib_trace(64), build_pub_inputs());
+    assert!(matches!(
+        result,
+        Err(ProverError::DeadlineExceeded(ProvingPhase::TraceExtension))
+    ));
+
+    // a distant deadline does not affect the proof
+    let config = ProverConfig::default().with_deadline(Instant::now() + Duration::from_secs(3600));
+    let proof = crate::Prover::<FibAir>::new(options.clone())
+        .with_config(config)
+        .prove(build_fib_trace(64), build_pub_inputs())
+        .unwrap();
+    assert_eq!(prove_fib(64, options).to_bytes(), proof.to_bytes());
+}
+
+#[test]
+fn prove_with_constraint_profiling() {
+    let options = build_proof_options(false);
+    let trace = build_fib_trace(64);
+    let pub_inputs = build_fib_inputs(&trace);
+
+    // profiling constraints does not affect the proof
+    let config = ProverConfig::default().with_constraint_profiling();
+    let proof = crate::Prover::<FibAir>::new(options.clone())
+        .with_config(config)
+        .prove(trace, pub_inputs)
+        .unwrap();
+    assert_eq!(prove_fib(64, options).to_bytes(), proof.to_bytes());
+}
+
+// AIR CACHE
+// ================================================================================================
+
+#[test]
+fn prove_with_air_cache() {
+    let mut cache = AirCache::new();
+
+    // the first proof populates the cache
+    prove_and_compare_with_cache(64, &mut cache);
+    let num_misses = cache.num_misses();
+    assert!(num_misses > 0);
+    assert_eq!(0, cache.num_hits());
+
+    // the second proof for the same sequence length re-uses all cached data
+    prove_and_compare_with_cache(64, &mut cache);
+    assert_eq!(num_misses, cache.num_misses());
+    assert_eq!(num_misses, cache.num_hits());
+
+    // changing the sequence length changes the domains, and thus, the cache is re-built
+    prove_and_compare_with_cache(32, &mut cache);
+    assert_eq!(2 * num_misses, cache.num_misses());
+    assert_eq!(num_misses, cache.num_hits());
+}
+
+// COMMITTED TRACE
+// ================================================================================================
+
+#[test]
+fn prove_multiple_statements() {
+    let options = build_proof_options(false);
+    let build_pub_inputs = || build_fib_inputs(&build_fib_trace(64));
+    let trace = crate::commit_trace(build_fib_trace(64), &options).unwrap();
+
+    // a proof generated against a committed trace is the same as a regular proof
+    let proof =
+        crate::prove_committed::<FibAir>(&trace, build_pub_inputs(), options.clone()).unwrap();
+    assert_eq!(prove_fib(64, options.clone()).to_bytes(), proof.to_bytes());
+    assert_eq!(trace.root(), proof.commitments.trace_root);
+
+    // proofs of other statements about the same trace share the trace root, and options which do
+    // not affect the trace commitment may differ between proofs
+    let start_options = options.clone().with_num_fri_queries(48);
+    let start_proof =
+        crate::prove_committed::<StartFibAir>(&trace, build_pub_inputs(), start_options).unwrap();
+    assert_eq!(trace.root(), start_proof.commitments.trace_root);
+    assert!(verifier::verify::<StartFibAir>(start_proof.clone(), build_pub_inputs()).is_ok());
+    assert!(verifier::verify::<FibAir>(start_proof, build_pub_inputs()).is_err());
+    assert!(verifier::verify::<FibAir>(proof, build_pub_inputs()).is_ok());
+
+    let ext_options = build_proof_options(true);
+    let ext_proof =
+        crate::prove_committed::<FibAir>(&trace, build_pub_inputs(), ext_options).unwrap();
+    assert_eq!(trace.root(), ext_proof.commitments.trace_root);
+    assert!(verifier::verify::<FibAir>(ext_proof, build_pub_inputs()).is_ok());
+
+    // options which affect the trace commitment must be the same as when the trace was committed
+    let bad_options = ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, FieldExtension::None);
+    let result = crate::prove_committed::<FibAir>(&trace, build_pub_inputs(), bad_options);
+    assert!(matches!(
+        result,
+        Err(ProverError::CommittedTraceMismatch("blowup factor"))
+    ));
+    let bad_options = ProofOptions::new(28, 16, 0, HashFunction::Sha3_256, FieldExtension::None);
+    let result = crate::prove_committed::<FibAir>(&trace, build_pub_inputs(), bad_options);
+    assert!(matches!(
+        result,
+        Err(ProverError::CommittedTraceMismatch("hash function"))
+    ));
+    let bad_options = options.clone().with_trace_leaf_batching(2);
+    let result = crate::prove_committed::<FibAir>(&trace, build_pub_inputs(), bad_options);
+    assert!(matches!(
+        result,
+        Err(ProverError::CommittedTraceMismatch("trace leaf batching"))
+    ));
+    let bad_options = options
+        .clone()
+        .with_trace_row_hashing(TraceRowHashing::Chunked(1));
+    let result = crate::prove_committed::<FibAir>(&trace, build_pub_inputs(), bad_options);
+    assert!(matches!(
+        result,
+        Err(ProverError::CommittedTraceMismatch("trace row hashing"))
+    ));
+
+    // the same goes for the column layout, and blinded registers cannot be committed to at all
+    let result =
+        crate::prove_committed::<ReorderedFibAir>(&trace, build_pub_inputs(), options.clone());
+    assert!(matches!(
+        result,
+        Err(ProverError::CommittedTraceMismatch("column layout"))
+    ));
+    let result = crate::prove_committed::<BlindedFibAir>(&trace, build_pub_inputs(), options);
+    assert!(matches!(
+        result,
+        Err(ProverError::TraceBlinderNotProvided(1))
+    ));
+}
+
+#[test]
+fn open_trace_chunks() {
+    let options = build_proof_options(false)
+        .with_trace_row_hashing(TraceRowHashing::Chunked(1))
+        .with_trace_leaf_batching(2);
+    let trace = crate::commit_trace(build_fib_trace(64), &options).unwrap();
+    let (width, length) = (trace.trace_width(), trace.trace_length());
+    let positions = [3, 17, 18, 200];
+
+    // opening both chunks reveals full rows, and a single chunk reveals one register of each row
+    let verify = |root: &[u8; 32], chunks: &[usize], opening: &TraceChunkOpening| {
+        verifier::verify_trace_chunks::<BaseElement>(
+            root, &options, width, length, &positions, chunks, opening,
+        )
+    };
+    let rows = verify(
+        &trace.root(),
+        &[0, 1],
+        &trace.open_chunks(&positions, &[0, 1]),
+    )
+    .unwrap();
+    assert_eq!(positions.len(), rows.len());
+    for (i, &chunk) in [0, 1].iter().enumerate() {
+        let opening = trace.open_chunks(&positions, &[chunk]);
+        let values = verify(&trace.root(), &[chunk], &opening).unwrap();
+        let expected = rows.iter().map(|row| vec![row[i]]).collect::<Vec<_>>();
+        assert_eq!(expected, values);
+
+        // the opening survives serialization
+        let bytes = opening.to_bytes();
+        let opening = TraceChunkOpening::read_from_bytes(&bytes).unwrap();
+        assert_eq!(expected, verify(&trace.root(), &[chunk], &opening).unwrap());
+    }
+
+    // tampered values, wrong chunks, or a wrong root are rejected
+    let mut opening = trace.open_chunks(&positions, &[0]);
+    opening.leaves.values[0][0] ^= 1;
+    assert!(matches!(
+        verify(&trace.root(), &[0], &opening),
+        Err(VerifierError::TraceQueryDoesNotMatchCommitment)
+    ));
+    let opening = trace.open_chunks(&positions, &[0]);
+    assert!(matches!(
+        verify(&trace.root(), &[1], &opening),
+        Err(VerifierError::TraceQueryDoesNotMatchCommitment)
+    ));
+    assert!(matches!(
+        verify(&[0; 32], &[0], &opening),
+        Err(VerifierError::TraceQueryDoesNotMatchCommitment)
+    ));
+
+    // commitments with flat row hashing cannot be opened at chunks
+    let result = verifier::verify_trace_chunks::<BaseElement>(
+        &trace.root(),
+        &build_proof_options(false),
+        width,
+        length,
+        &positions,
+        &[0],
+        &opening,
+    );
+    assert!(matches!(result, Err(VerifierError::TraceRowsNotChunked)));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Generates a proof of computing a Fibonacci sequence of the specified length, and makes sure
+/// the proof verifies against the correct result only.
+fn check_proof_verification(sequence_length: usize, options: ProofOptions) {
+    let pub_inputs = build_fib_inputs(&build_fib_trace(sequence_length));
+    let proof =
+        StarkProof::read_from_bytes(&prove_fib(sequence_length, options).to_bytes()).unwrap();
+    assert!(verifier::verify::<FibAir>(proof.clone(), pub_inputs.clone()).is_ok());
+
+    let pub_inputs = FibInputs {
+        result: pub_inputs.result + BaseElement::ONE,
+        ..pub_inputs
+    };
+    assert!(verifier::verify::<FibAir>(proof, pub_inputs).is_err());
+}
+
+/// Generates a proof for a sequence of the specified length using the `cache`, and makes sure
+/// the proof is the same as a proof generated without the cache.
+fn prove_and_compare_with_cache(sequence_length: usize, cache: &mut AirCache<BaseElement>) {
+    let options = build_proof_options(false);
+    let trace = build_fib_trace(sequence_length);
+    let pub_inputs = build_fib_inputs(&trace);
+    let proof = crate::Prover::<FibAir>::new(options.clone())
+        .with_cache(cache)
+        .prove(trace, pub_inputs)
+        .unwrap();
+    assert_eq!(
+        prove_fib(sequence_length, options).to_bytes(),
+        proof.to_bytes()
+    );
+}
+
+// TEST AIRS
+// ================================================================================================
+
+/// Fibonacci AIR which declares two extra polynomials.
+struct ExtraPolyFibAir(FibAir, ComputationContext);
+
+impl Air for ExtraPolyFibAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = FibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: FibInputs, options: ProofOptions) -> Self {
+        let air = FibAir::new(trace_info, pub_inputs, options);
+        let context = air.context().clone().with_extra_polys(2);
+        ExtraPolyFibAir(air, context)
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.1
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        self.0.get_assertions()
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+}
+
+/// Fibonacci AIR which swaps the registers in rows of the trace commitment.
+struct ReorderedFibAir(FibAir);
+
+impl Air for ReorderedFibAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = FibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: FibInputs, options: ProofOptions) -> Self {
+        ReorderedFibAir(FibAir::new(trace_info, pub_inputs, options))
+    }
+
+    fn context(&self) -> &ComputationContext {
+        self.0.context()
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        self.0.get_assertions()
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_column_layout(&self) -> Vec<usize> {
+        vec![1, 0]
+    }
+}
+
+/// Fibonacci AIR which asserts only the starting terms of the sequence.
+struct StartFibAir(FibAir);
+
+impl Air for StartFibAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = FibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: FibInputs, options: ProofOptions) -> Self {
+        StartFibAir(FibAir::new(trace_info, pub_inputs, options))
+    }
+
+    fn context(&self) -> &ComputationContext {
+        self.0.context()
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        self.0.get_assertions().into_iter().take(2).collect()
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+}
+
+// RECORDING OBSERVER
+// ================================================================================================
+
+#[derive(Default)]
+struct RecordingObserver {
+    roots: Vec<[u8; 32]>,
+    z: Vec<u8>,
+    ood_frame: (Vec<u8>, Vec<u8>, Vec<u8>),
+    coefficients: Vec<u8>,
+    fri_roots: Vec<[u8; 32]>,
+    fri_alphas: Vec<Vec<u8>>,
+    events: Vec<&'static str>,
+    commitments: Option<Commitments>,
+    pow_nonce: u64,
+    positions: Vec<usize>,
+}
+
+impl ProverObserver for RecordingObserver {
+    fn on_trace_commitment(&mut self, root: &[u8; 32]) {
+        self.events.push("trace");
+        self.roots.push(*root);
+    }
+
+    fn on_constraint_commitment(&mut self, root: &[u8; 32]) {
+        self.events.push("constraints");
+        self.roots.push(*root);
+    }
+
+    fn on_ood_point(&mut self, z: &[u8]) {
+        self.events.push("z");
+        self.z = z.to_vec();
+    }
+
+    fn on_composition_coefficients(&mut self, coefficients: &[u8]) {
+        self.events.push("coefficients");
+        self.coefficients = coefficients.to_vec();
+    }
+
+    fn on_ood_frame(&mut self, trace_at_z1: &[u8], trace_at_z2: &[u8], extra_at_z: &[u8]) {
+        self.events.push("ood_frame");
+        self.ood_frame = (
+            trace_at_z1.to_vec(),
+            trace_at_z2.to_vec(),
+            extra_at_z.to_vec(),
+        );
+    }
+
+    fn on_fri_layer_commitment(&mut self, layer_index: usize, root: &[u8; 32]) {
+        self.events.push("fri_layer");
+        assert_eq!(self.fri_roots.len(), layer_index);
+        self.fri_roots.push(*root);
+    }
+
+    fn on_fri_alpha(&mut self, layer_index: usize, alpha: &[u8]) {
+        self.events.push("fri_alpha");
+        assert_eq!(self.fri_alphas.len(), layer_index);
+        self.fri_alphas.push(alpha.to_vec());
+    }
+
+    fn on_commitments(&mut self, commitments: &Commitments) {
+        self.events.push("commitments");
+        self.commitments = Some(commitments.clone());
+    }
+
+    fn on_query_seed(&mut self, _seed: &[u8; 32], pow_nonce: u64) {
+        self.events.push("query_seed");
+        self.pow_nonce = pow_nonce;
+    }
+
+    fn on_query_positions(&mut self, positions: &[usize]) {
+        self.events.push("positions");
+        self.positions = positions.to_vec();
+    }
+}
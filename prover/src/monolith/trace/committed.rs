@@ -0,0 +1,167 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{TracePolyTable, TraceTable};
+use common::{
+    errors::ProverError, proof::TraceChunkOpening, Air, HashFunction, ProofOptions, TraceRowHashing,
+};
+use crypto::{
+    hash::{Blake3_256, Sha3_256},
+    MerkleTree,
+};
+use math::field::StarkField;
+
+// COMMITTED TRACE
+// ================================================================================================
+
+/// An execution trace which has been extended over the LDE domain and committed to.
+///
+/// A committed trace can back proofs of several independent statements: proofs for different
+/// AIRs, or for the same AIR with different public inputs, can be generated against it via
+/// [prove_committed()](crate::prove_committed) without extending and committing to the trace
+/// again. All such proofs contain the same trace commitment root.
+///
+/// The parameters which determine the trace commitment (blowup factor, hash function, trace
+/// leaf batching, trace row hashing, and byte encoding) are fixed when the trace is committed
+/// to; proofs generated against the trace must use the same values for these parameters. Other proof options (e.g., number of
+/// queries, grinding factor, or field extension) may differ between proofs.
+pub struct CommittedTrace<B: StarkField> {
+    options: ProofOptions,
+    extended_trace: TraceTable<B>,
+    trace_polys: TracePolyTable<B>,
+    trace_tree: MerkleTree,
+}
+
+impl<B: StarkField> CommittedTrace<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new committed trace; `options` are the options with which the commitment to
+    /// the `extended_trace` was built.
+    pub(crate) fn new(
+        options: ProofOptions,
+        extended_trace: TraceTable<B>,
+        trace_polys: TracePolyTable<B>,
+        trace_tree: MerkleTree,
+    ) -> Self {
+        CommittedTrace {
+            options,
+            extended_trace,
+            trace_polys,
+            trace_tree,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the root of the trace commitment; this root is included in all proofs generated
+    /// against this trace.
+    pub fn root(&self) -> [u8; 32] {
+        *self.trace_tree.root()
+    }
+
+    /// Returns the number of registers in the execution trace.
+    pub fn trace_width(&self) -> usize {
+        self.extended_trace.width()
+    }
+
+    /// Returns the number of steps in the execution trace (before it was extended).
+    pub fn trace_length(&self) -> usize {
+        self.trace_polys.poly_size()
+    }
+
+    /// Returns the options with which the commitment to the trace was built.
+    pub fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    // CHUNK OPENINGS
+    // --------------------------------------------------------------------------------------------
+
+    /// Opens the trace commitment at the specified `chunks` of the rows at the specified
+    /// positions of the LDE domain; chunk indexes must be unique and must be listed in ascending
+    /// order.
+    ///
+    /// Only values of the requested chunks are included in the opening, together with short
+    /// authentication paths to them; the opening can be checked against the commitment root
+    /// via `verify_trace_chunks()` function of the verifier crate.
+    ///
+    /// # Panics
+    /// Panics if the trace was not committed to with [TraceRowHashing::Chunked] row hashing, or
+    /// if any of the chunk indexes or positions is out of bounds.
+    pub fn open_chunks(&self, positions: &[usize], chunks: &[usize]) -> TraceChunkOpening {
+        let chunk_width = match self.options.trace_row_hashing() {
+            TraceRowHashing::Chunked(chunk_width) => chunk_width as usize,
+            TraceRowHashing::Flat => panic!("trace rows were not hashed in chunks"),
+        };
+        let (table, tree) = (&self.extended_trace, &self.trace_tree);
+        let encoding = self.options.byte_encoding();
+        match self.options.hash_fn() {
+            HashFunction::Blake3_256 => {
+                table.open_chunks::<Blake3_256>(tree, chunk_width, positions, chunks, encoding)
+            }
+            HashFunction::Sha3_256 => {
+                table.open_chunks::<Sha3_256>(tree, chunk_width, positions, chunks, encoding)
+            }
+        }
+    }
+
+    // CRATE ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the execution trace extended over the LDE domain.
+    pub(crate) fn extended_trace(&self) -> &TraceTable<B> {
+        &self.extended_trace
+    }
+
+    /// Returns polynomials interpolated from the registers of the execution trace.
+    pub(crate) fn trace_polys(&self) -> &TracePolyTable<B> {
+        &self.trace_polys
+    }
+
+    /// Returns the Merkle tree built from the rows of the extended execution trace.
+    pub(crate) fn trace_tree(&self) -> &MerkleTree {
+        &self.trace_tree
+    }
+
+    /// Makes sure a proof for the specified `air` can be generated against this trace.
+    ///
+    /// # Errors
+    /// Returns an error if the `air` expects a trace of a different shape, requires the trace
+    /// to be committed to with different parameters, or designates blinded registers; values
+    /// of blinded registers must be committed to before the trace is extended, and thus, such
+    /// computations cannot be proven against a committed trace.
+    pub(crate) fn check_air<A: Air<BaseElement = B>>(&self, air: &A) -> Result<(), ProverError> {
+        let options = air.context().options();
+        if air.trace_width() != self.trace_width() {
+            return Err(ProverError::CommittedTraceMismatch("trace width"));
+        }
+        if options.blowup_factor() != self.options.blowup_factor() {
+            return Err(ProverError::CommittedTraceMismatch("blowup factor"));
+        }
+        if options.hash_fn() != self.options.hash_fn() {
+            return Err(ProverError::CommittedTraceMismatch("hash function"));
+        }
+        if options.trace_leaf_batching() != self.options.trace_leaf_batching() {
+            return Err(ProverError::CommittedTraceMismatch("trace leaf batching"));
+        }
+        if options.trace_row_hashing() != self.options.trace_row_hashing() {
+            return Err(ProverError::CommittedTraceMismatch("trace row hashing"));
+        }
+        if options.byte_encoding() != self.options.byte_encoding() {
+            return Err(ProverError::CommittedTraceMismatch("byte encoding"));
+        }
+        if air.get_column_layout() != self.extended_trace.column_layout() {
+            return Err(ProverError::CommittedTraceMismatch("column layout"));
+        }
+
+        let num_blinded_registers = air.get_blinded_registers().len();
+        if num_blinded_registers > 0 {
+            return Err(ProverError::TraceBlinderNotProvided(num_blinded_registers));
+        }
+
+        Ok(())
+    }
+}
@@ -4,21 +4,29 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{StarkDomain, TracePolyTable, TraceTable};
-use common::{Air, EvaluationFrame};
-use math::{fft, field::StarkField, polynom};
+use common::{
+    errors::{ProverError, TraceBufferError},
+    limits::{MAX_TRACE_WIDTH, MIN_TRACE_LENGTH},
+    Air, EvaluationFrame,
+};
+use core::mem;
+use math::{fft, field::StarkField};
 use utils::uninit_vector;
 
+#[cfg(feature = "arrow")]
+use arrow_buffer::{ArrowNativeType, ScalarBuffer};
+
 #[cfg(feature = "concurrent")]
 use rayon::prelude::*;
 
 // CONSTANTS
 // ================================================================================================
 
-const MIN_TRACE_LENGTH: usize = 8;
 const MIN_FRAGMENT_LENGTH: usize = 2;
 
 // TRACE TABLE
 // ================================================================================================
+#[derive(Clone)]
 pub struct ExecutionTrace<B: StarkField>(Vec<Vec<B>>);
 
 impl<B: StarkField> ExecutionTrace<B> {
@@ -33,6 +41,12 @@ impl<B: StarkField> ExecutionTrace<B> {
             width > 0,
             "execution trace must consist of at least one register"
         );
+        assert!(
+            width <= MAX_TRACE_WIDTH,
+            "execution trace cannot consist of more than {} registers, but was {}",
+            MAX_TRACE_WIDTH,
+            width
+        );
         assert!(
             length >= MIN_TRACE_LENGTH,
             "execution trace must be at lest {} steps long, but was {}",
@@ -54,6 +68,12 @@ impl<B: StarkField> ExecutionTrace<B> {
             !registers.is_empty(),
             "execution trace must consist of at least one register"
         );
+        assert!(
+            registers.len() <= MAX_TRACE_WIDTH,
+            "execution trace cannot consist of more than {} registers, but was {}",
+            MAX_TRACE_WIDTH,
+            registers.len()
+        );
         let trace_length = registers[0].len();
         assert!(
             trace_length >= MIN_TRACE_LENGTH,
@@ -75,6 +95,133 @@ impl<B: StarkField> ExecutionTrace<B> {
         ExecutionTrace(registers)
     }
 
+    /// Creates a new execution trace by adopting externally-owned register traces encoded as
+    /// vectors of raw words (e.g., `Vec<u64>` or `Vec<u128>`).
+    ///
+    /// The memory of the provided vectors is re-interpreted as memory of field elements without
+    /// copying. This requires field elements to be stored in canonical representation, and the
+    /// words to have the same size and alignment as field elements (e.g., `u64` words for
+    /// 64-bit fields, and `u128` words for 128-bit fields).
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of registers is zero or greater than [MAX_TRACE_WIDTH].
+    /// * The length of the first register is smaller than [MIN_TRACE_LENGTH] or is not a power
+    ///   of two, or the lengths of other registers are different from it.
+    /// * The layout of words is different from the layout of field elements, or field elements
+    ///   are not stored in canonical representation.
+    /// * Any of the words is not a valid field element (i.e., is not smaller than the modulus).
+    pub fn from_raw_columns<W: TraceWord>(
+        registers: Vec<Vec<W>>,
+    ) -> Result<Self, TraceBufferError> {
+        if registers.is_empty() {
+            return Err(TraceBufferError::NoRegisters);
+        }
+        if registers.len() > MAX_TRACE_WIDTH {
+            return Err(TraceBufferError::TooManyRegisters(
+                MAX_TRACE_WIDTH,
+                registers.len(),
+            ));
+        }
+        let trace_length = registers[0].len();
+        if trace_length < MIN_TRACE_LENGTH {
+            return Err(TraceBufferError::TraceTooShort(
+                MIN_TRACE_LENGTH,
+                trace_length,
+            ));
+        }
+        if !trace_length.is_power_of_two() {
+            return Err(TraceBufferError::TraceLengthNotPowerOfTwo(trace_length));
+        }
+        for (i, register) in registers.iter().enumerate() {
+            if register.len() != trace_length {
+                return Err(TraceBufferError::RegisterLengthMismatch(
+                    i,
+                    register.len(),
+                    trace_length,
+                ));
+            }
+        }
+
+        if mem::size_of::<W>() != mem::size_of::<B>()
+            || mem::align_of::<W>() != mem::align_of::<B>()
+        {
+            return Err(TraceBufferError::IncompatibleWordLayout(
+                mem::size_of::<W>(),
+                mem::align_of::<W>(),
+                mem::size_of::<B>(),
+                mem::align_of::<B>(),
+            ));
+        }
+        if B::elements_as_bytes(&[B::ONE]) != B::ONE.to_canonical_bytes().as_slice() {
+            return Err(TraceBufferError::NonCanonicalRepresentation);
+        }
+
+        let registers = registers
+            .into_iter()
+            .map(|register| {
+                // this is safe because words and field elements have the same size and alignment,
+                // and thus, the allocation can be released with the layout of field elements;
+                // also, any bit pattern of a word is a valid value of an element's internal type,
+                // and values which are not valid field elements are rejected below.
+                let mut register = mem::ManuallyDrop::new(register);
+                let p = register.as_mut_ptr();
+                let len = register.len();
+                let cap = register.capacity();
+                unsafe { Vec::from_raw_parts(p as *mut B, len, cap) }
+            })
+            .collect::<Vec<Vec<B>>>();
+
+        for (i, register) in registers.iter().enumerate() {
+            if let Some(step) = register.iter().position(|v| v.as_int() >= B::MODULUS) {
+                return Err(TraceBufferError::InvalidValue(i, step));
+            }
+        }
+
+        Ok(ExecutionTrace(registers))
+    }
+
+    /// Creates a new execution trace by adopting register traces held in Apache Arrow buffers.
+    ///
+    /// A buffer is adopted without copying when it is not shared and its memory was allocated
+    /// as a vector of words (e.g., when the buffer was built from a `Vec`); otherwise, values of
+    /// the buffer are copied into a newly allocated vector. The adopted words are then handled
+    /// as described in [ExecutionTrace::from_raw_columns()].
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [ExecutionTrace::from_raw_columns()].
+    #[cfg(feature = "arrow")]
+    pub fn from_arrow_columns<W: TraceWord + ArrowNativeType>(
+        registers: Vec<ScalarBuffer<W>>,
+    ) -> Result<Self, TraceBufferError> {
+        let registers = registers
+            .into_iter()
+            .map(|register| match register.into_inner().into_vec::<W>() {
+                Ok(register) => register,
+                Err(buffer) => buffer.typed_data::<W>().to_vec(),
+            })
+            .collect();
+        Self::from_raw_columns(registers)
+    }
+
+    /// Returns this execution trace with registers of the specified auxiliary `segment` appended
+    /// to its registers. This can be used to check validity of the full trace of a computation
+    /// which declares an auxiliary trace segment.
+    ///
+    /// # Panics
+    /// Panics if the segment has a different length than this trace, or if the resulting trace
+    /// would consist of more than [MAX_TRACE_WIDTH] registers.
+    pub fn append_segment(self, segment: ExecutionTrace<B>) -> Self {
+        assert_eq!(
+            self.len(),
+            segment.len(),
+            "auxiliary trace segment must have the same length as the execution trace"
+        );
+        let mut registers = self.0;
+        registers.extend(segment.0);
+        ExecutionTrace::init(registers)
+    }
+
     // DATA MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -186,8 +333,18 @@ impl<B: StarkField> ExecutionTrace<B> {
     ///
     /// NOTE: this is a very expensive operation and is intended for use only in debug mode.
     pub fn validate<A: Air<BaseElement = B>>(&self, air: &A) {
-        // TODO: eventually, this should return errors instead of panicking
+        if let Err(error) = self.check(air) {
+            panic!("{}", error);
+        }
+    }
 
+    /// Checks if this execution trace is valid against the specified AIR, and returns an error
+    /// describing the first violated assertion or transition constraint if not. Assertions are
+    /// checked before transition constraints.
+    ///
+    /// # Panics
+    /// Panics if the width of this trace is different from the trace width of the AIR.
+    pub fn check<A: Air<BaseElement = B>>(&self, air: &A) -> Result<(), ProverError> {
         // make sure the width align; if they don't something went terribly wrong
         assert!(
             self.width() == air.trace_width(),
@@ -198,36 +355,36 @@ impl<B: StarkField> ExecutionTrace<B> {
 
         // --- 1. make sure the assertions are valid ----------------------------------------------
         for assertion in air.get_assertions() {
+            let mut violation = None;
             assertion.apply(self.len(), |step, value| {
-                assert!(
-                    value == self.get(assertion.register(), step),
-                    "trace does not satisfy assertion trace({}, {}) == {}",
+                if violation.is_none() && value != self.get(assertion.register(), step) {
+                    violation = Some(step);
+                }
+            });
+            if let Some(step) = violation {
+                return Err(ProverError::UnsatisfiedAssertion(
                     assertion.register(),
                     step,
-                    value
-                );
-            });
+                ));
+            }
         }
 
         // --- 2. make sure this trace satisfies all transition constraints -----------------------
 
-        // collect the info needed to build periodic values for a specific step
-        let g = air.trace_domain_generator();
-        let periodic_values_polys = air.get_periodic_column_polys();
-        let mut periodic_values = vec![B::ZERO; periodic_values_polys.len()];
+        // initialize a buffer to hold periodic values for a specific step; on the trace domain,
+        // values of periodic columns are the same as the values returned by the columns, and
+        // thus, there is no need to evaluate periodic column polynomials
+        let periodic_columns = air.get_periodic_columns();
+        let mut periodic_values = Vec::with_capacity(periodic_columns.len());
 
         // initialize buffers to hold evaluation frames and results of constraint evaluations
-        let mut x = B::ONE;
         let mut ev_frame = EvaluationFrame::new(self.width());
         let mut evaluations = vec![B::ZERO; air.num_transition_constraints()];
 
         for step in 0..self.len() - 1 {
             // build periodic values
-            for (p, v) in periodic_values_polys.iter().zip(periodic_values.iter_mut()) {
-                let num_cycles = air.trace_length() / p.len();
-                let x = x.exp((num_cycles as u32).into());
-                *v = polynom::eval(p, x);
-            }
+            periodic_values.clear();
+            periodic_values.extend(periodic_columns.iter().map(|column| column.get(step)));
 
             // build evaluation frame
             self.read_row_into(step, &mut ev_frame.current);
@@ -237,18 +394,15 @@ impl<B: StarkField> ExecutionTrace<B> {
             air.evaluate_transition(&ev_frame, &periodic_values, &mut evaluations);
 
             // make sure all constraints evaluated to ZERO
-            for (i, &evaluation) in evaluations.iter().enumerate() {
-                assert!(
-                    evaluation == B::ZERO,
-                    "transition constraint {} did not evaluate to ZERO at step {}",
-                    i,
-                    step
-                );
+            if let Some(i) = evaluations
+                .iter()
+                .position(|&evaluation| evaluation != B::ZERO)
+            {
+                return Err(ProverError::UnsatisfiedTransitionConstraintError(i, step));
             }
-
-            // update x coordinate of the domain
-            x *= g;
         }
+
+        Ok(())
     }
 
     // LOW-DEGREE EXTENSION
@@ -266,21 +420,25 @@ impl<B: StarkField> ExecutionTrace<B> {
         // don't have to rebuild these twiddles for every register.
         let inv_twiddles = fft::get_inv_twiddles::<B>(domain.trace_length());
 
-        // extend all registers (either in multiple threads or in a single thread); the extension
-        // procedure first interpolates register traces into polynomials (in-place), then evaluates
-        // these polynomials over a larger domain, and then returns extended evaluations.
+        // interpolate register traces into polynomials (in-place); when `concurrent` feature is
+        // enabled, wide traces are interpolated one register per task, and narrow traces are
+        // interpolated one register at a time using all available threads for each register.
+        fft::interpolate_poly_batch(&mut self.0, &inv_twiddles);
+
+        // evaluate register polynomials over the LDE domain (either in multiple threads or in a
+        // single thread), and return the extended evaluations.
         #[cfg(feature = "concurrent")]
         let extended_trace = self
             .0
-            .par_iter_mut()
-            .map(|register_trace| extend_register(register_trace, &domain, &inv_twiddles))
+            .par_iter()
+            .map(|poly| extend_register(poly, &domain))
             .collect();
 
         #[cfg(not(feature = "concurrent"))]
         let extended_trace = self
             .0
-            .iter_mut()
-            .map(|register_trace| extend_register(register_trace, &domain, &inv_twiddles))
+            .iter()
+            .map(|poly| extend_register(poly, &domain))
             .collect();
 
         (
@@ -290,6 +448,20 @@ impl<B: StarkField> ExecutionTrace<B> {
     }
 }
 
+// TRACE WORDS
+// ================================================================================================
+
+/// A primitive unsigned integer type which can back register traces adopted via
+/// [ExecutionTrace::from_raw_columns()].
+///
+/// # Safety
+/// Any bit pattern must be a valid value of the type, and the type must not contain padding.
+pub unsafe trait TraceWord: Copy + 'static {}
+
+unsafe impl TraceWord for u32 {}
+unsafe impl TraceWord for u64 {}
+unsafe impl TraceWord for u128 {}
+
 // TRACE FRAGMENTS
 // ================================================================================================
 
@@ -356,19 +528,11 @@ impl<'a, B: StarkField> ExecutionTraceFragment<'a, B> {
 // ================================================================================================
 
 #[inline(always)]
-fn extend_register<B: StarkField>(
-    trace: &mut [B],
-    domain: &StarkDomain<B>,
-    inv_twiddles: &[B],
-) -> Vec<B> {
-    let domain_offset = domain.offset();
-    let twiddles = domain.trace_twiddles();
-    let blowup_factor = domain.trace_to_lde_blowup();
-
-    // interpolate register trace into a polynomial; we do this over the un-shifted trace_domain
-    fft::interpolate_poly(trace, inv_twiddles);
-
-    // evaluate the polynomial over extended domain; the domain may be shifted by the
-    // domain_offset
-    fft::evaluate_poly_with_offset(trace, twiddles, domain_offset, blowup_factor)
+fn extend_register<B: StarkField>(poly: &[B], domain: &StarkDomain<B>) -> Vec<B> {
+    fft::evaluate_poly_with_offset(
+        poly,
+        domain.trace_twiddles(),
+        domain.offset(),
+        domain.trace_to_lde_blowup(),
+    )
 }
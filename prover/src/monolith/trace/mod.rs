@@ -6,13 +6,16 @@
 use super::StarkDomain;
 
 mod trace_table;
-pub use trace_table::TraceTable;
+pub use trace_table::{ExtendedTrace, TraceTable};
 
 mod poly_table;
 pub use poly_table::TracePolyTable;
 
 mod execution_trace;
-pub use execution_trace::{ExecutionTrace, ExecutionTraceFragment};
+pub use execution_trace::{ExecutionTrace, ExecutionTraceFragment, TraceWord};
+
+mod committed;
+pub use committed::CommittedTrace;
 
 #[cfg(test)]
 mod tests;
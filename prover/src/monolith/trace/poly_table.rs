@@ -50,7 +50,6 @@ impl<B: StarkField> TracePolyTable<B> {
     }
 
     /// Returns a trace polynomial at the specified index.
-    #[cfg(test)]
     pub fn get_poly(&self, idx: usize) -> &[B] {
         &self.0[idx]
     }
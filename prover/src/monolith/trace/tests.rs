@@ -3,17 +3,21 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use super::{ExecutionTrace, TraceTable};
 use crate::{
     monolith::StarkDomain,
     tests::{build_context, build_fib_trace},
 };
+use common::{errors::TraceBufferError, utils::map_trace_positions_to_leaves, TraceRowHashing};
 use crypto::{hash::Blake3_256, Hasher, MerkleTree};
 use math::{
-    field::{f128::BaseElement, FieldElement, StarkField},
+    field::{f128::BaseElement, f62, ByteEncoding, FieldElement, StarkField},
     polynom,
-    utils::{get_power_series, log2},
+    utils::{get_power_series_unchecked, log2_unchecked},
 };
 
+const NATIVE: ByteEncoding = ByteEncoding::Native;
+
 #[test]
 fn new_trace_table() {
     let trace_length = 8;
@@ -35,6 +39,87 @@ fn new_trace_table() {
     assert_eq!(expected, trace.get_register(1));
 }
 
+#[test]
+fn adopt_raw_columns() {
+    let registers: Vec<Vec<u128>> = vec![(0..8).collect(), (8..16).collect()];
+    let pointers = registers
+        .iter()
+        .map(|r| r.as_ptr() as usize)
+        .collect::<Vec<_>>();
+
+    let trace = ExecutionTrace::<BaseElement>::from_raw_columns(registers).unwrap();
+    assert_eq!(2, trace.width());
+    assert_eq!(8, trace.len());
+    assert_eq!(BaseElement::from(13u8), trace.get(1, 5));
+
+    // register traces were adopted without copying
+    for (i, &pointer) in pointers.iter().enumerate() {
+        assert_eq!(pointer, trace.get_register(i).as_ptr() as usize);
+    }
+
+    let registers: Vec<Vec<u64>> = vec![(0..8).collect()];
+    let trace = ExecutionTrace::<f62::BaseElement>::from_raw_columns(registers).unwrap();
+    assert_eq!(f62::BaseElement::from(7u8), trace.get(0, 7));
+}
+
+#[test]
+fn adopt_invalid_raw_columns() {
+    let result = ExecutionTrace::<BaseElement>::from_raw_columns(Vec::<Vec<u128>>::new());
+    assert_eq!(Some(TraceBufferError::NoRegisters), result.err());
+
+    let result = ExecutionTrace::<BaseElement>::from_raw_columns(vec![vec![0u128; 4]]);
+    assert_eq!(Some(TraceBufferError::TraceTooShort(8, 4)), result.err());
+
+    let result = ExecutionTrace::<BaseElement>::from_raw_columns(vec![vec![0u128; 12]]);
+    assert_eq!(
+        Some(TraceBufferError::TraceLengthNotPowerOfTwo(12)),
+        result.err()
+    );
+
+    let result =
+        ExecutionTrace::<BaseElement>::from_raw_columns(vec![vec![0u128; 8], vec![0u128; 16]]);
+    assert_eq!(
+        Some(TraceBufferError::RegisterLengthMismatch(1, 16, 8)),
+        result.err()
+    );
+
+    // 64-bit words cannot back 128-bit field elements
+    let result = ExecutionTrace::<BaseElement>::from_raw_columns(vec![vec![0u64; 8]]);
+    assert_eq!(
+        Some(TraceBufferError::IncompatibleWordLayout(8, 8, 16, 16)),
+        result.err()
+    );
+
+    // values must be smaller than the field modulus
+    let mut register = vec![0u128; 8];
+    register[3] = BaseElement::MODULUS;
+    let result = ExecutionTrace::<BaseElement>::from_raw_columns(vec![vec![0u128; 8], register]);
+    assert_eq!(Some(TraceBufferError::InvalidValue(1, 3)), result.err());
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn adopt_arrow_columns() {
+    use arrow_buffer::{Buffer, ScalarBuffer};
+
+    // a buffer built from a vector is adopted without copying
+    let values: Vec<u64> = (0..8).collect();
+    let pointer = values.as_ptr() as usize;
+    let owned = ScalarBuffer::<u64>::from(values);
+
+    // a buffer allocated by Arrow itself is copied
+    let shared = ScalarBuffer::<u64>::from(Buffer::from_slice_ref((8..16).collect::<Vec<u64>>()));
+
+    let trace =
+        ExecutionTrace::<f62::BaseElement>::from_arrow_columns(vec![owned, shared]).unwrap();
+    assert_eq!(pointer, trace.get_register(0).as_ptr() as usize);
+    assert_eq!(f62::BaseElement::from(15u8), trace.get(1, 7));
+
+    let invalid = ScalarBuffer::<u64>::from(vec![f62::BaseElement::MODULUS; 8]);
+    let result = ExecutionTrace::<f62::BaseElement>::from_arrow_columns(vec![invalid]);
+    assert_eq!(Some(TraceBufferError::InvalidValue(0, 0)), result.err());
+}
+
 #[test]
 fn extend_trace_table() {
     // build and extend trace table
@@ -48,8 +133,8 @@ fn extend_trace_table() {
     assert_eq!(32, extended_trace.len());
 
     // make sure trace polynomials evaluate to Fibonacci trace
-    let trace_root = BaseElement::get_root_of_unity(log2(trace_length));
-    let trace_domain = get_power_series(trace_root, trace_length);
+    let trace_root = BaseElement::get_root_of_unity(log2_unchecked(trace_length)).unwrap();
+    let trace_domain = get_power_series_unchecked(trace_root, trace_length);
     assert_eq!(2, trace_polys.num_polys());
     assert_eq!(
         vec![1u32, 2, 5, 13, 34, 89, 233, 610]
@@ -87,7 +172,8 @@ fn commit_trace_table() {
     let (extended_trace, _) = trace.extend(&domain);
 
     // commit to the trace
-    let trace_tree = extended_trace.build_commitment::<Blake3_256>();
+    let trace_tree =
+        extended_trace.build_commitment::<Blake3_256>(1, TraceRowHashing::Flat, NATIVE);
 
     // build Merkle tree from trace rows
     let hash_fn = Blake3_256::hash_fn();
@@ -107,3 +193,200 @@ fn commit_trace_table() {
     // compare the result
     assert_eq!(expected_tree.root(), trace_tree.root())
 }
+
+#[test]
+fn commit_trace_table_with_leaf_batching() {
+    // build and extend trace table
+    let trace_length = 8;
+    let context = build_context(trace_length, 2, 4);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&context);
+    let (extended_trace, _) = trace.extend(&domain);
+
+    // commit to the trace with 4 rows per leaf
+    let rows_per_leaf = 4;
+    let trace_tree =
+        extended_trace.build_commitment::<Blake3_256>(rows_per_leaf, TraceRowHashing::Flat, NATIVE);
+    assert_eq!(
+        extended_trace.len() / rows_per_leaf,
+        trace_tree.leaves().len()
+    );
+
+    // build Merkle tree from groups of adjacent trace rows
+    let hash_fn = Blake3_256::hash_fn();
+    let mut hashed_states = Vec::new();
+    for i in (0..extended_trace.len()).step_by(rows_per_leaf) {
+        let mut leaf_state = Vec::new();
+        for step in i..(i + rows_per_leaf) {
+            for register in 0..extended_trace.width() {
+                leaf_state.push(extended_trace.get(register, step));
+            }
+        }
+        let mut buf = [0; 32];
+        hash_fn(BaseElement::elements_as_bytes(&leaf_state), &mut buf);
+        hashed_states.push(buf);
+    }
+    let expected_tree = MerkleTree::new(hashed_states, hash_fn);
+    assert_eq!(expected_tree.root(), trace_tree.root());
+
+    // positions 5 and 6 fall into the same leaf, so only two leaves are opened
+    let queries = extended_trace.query(&trace_tree, &[5, 6, 17], NATIVE);
+    assert_eq!(2, queries.values.len());
+    let row_bytes = extended_trace.width() * BaseElement::ELEMENT_BYTES;
+    let mut row = vec![BaseElement::ZERO; extended_trace.width()];
+    extended_trace.read_row_into(17, &mut row);
+    assert_eq!(
+        BaseElement::elements_as_bytes(&row),
+        &queries.values[1][row_bytes..(2 * row_bytes)]
+    );
+}
+
+#[test]
+fn commit_trace_table_with_column_layout() {
+    // build and extend trace table
+    let trace_length = 8;
+    let context = build_context(trace_length, 2, 4);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&context);
+    let (mut extended_trace, _) = trace.extend(&domain);
+
+    // commit to the trace with registers swapped in committed rows
+    extended_trace.set_column_layout(vec![1, 0]);
+    let trace_tree =
+        extended_trace.build_commitment::<Blake3_256>(1, TraceRowHashing::Flat, NATIVE);
+
+    let hash_fn = Blake3_256::hash_fn();
+    let mut hashed_states = Vec::new();
+    for i in 0..extended_trace.len() {
+        let row = [extended_trace.get(1, i), extended_trace.get(0, i)];
+        let mut buf = [0; 32];
+        hash_fn(BaseElement::elements_as_bytes(&row), &mut buf);
+        hashed_states.push(buf);
+    }
+    let expected_tree = MerkleTree::new(hashed_states, hash_fn);
+    assert_eq!(expected_tree.root(), trace_tree.root());
+
+    // queried rows are arranged in the same way as the committed rows
+    let queries = extended_trace.query(&trace_tree, &[3], NATIVE);
+    let row = [extended_trace.get(1, 3), extended_trace.get(0, 3)];
+    assert_eq!(BaseElement::elements_as_bytes(&row), &queries.values[0][..]);
+}
+
+#[test]
+fn commit_trace_table_with_packed_rows() {
+    // over a field with 8-byte elements, a row of a 2-register trace is 16 bytes long
+    let data = vec![
+        (0..16u32).map(f62::BaseElement::from).collect::<Vec<_>>(),
+        (16..32u32).map(f62::BaseElement::from).collect::<Vec<_>>(),
+    ];
+    let table = TraceTable::new(data, 1);
+    let hash_fn = Blake3_256::hash_fn();
+
+    // leaves of up to 32 bytes are packed without hashing; larger leaves are hashed
+    for &rows_per_leaf in [1, 2, 4].iter() {
+        let trace_tree =
+            table.build_commitment::<Blake3_256>(rows_per_leaf, TraceRowHashing::Flat, NATIVE);
+        for (i, leaf) in trace_tree.leaves().iter().enumerate() {
+            let mut leaf_state = Vec::new();
+            for step in (i * rows_per_leaf)..((i + 1) * rows_per_leaf) {
+                leaf_state.push(table.get(0, step));
+                leaf_state.push(table.get(1, step));
+            }
+            let leaf_bytes = f62::BaseElement::elements_as_bytes(&leaf_state);
+            let mut expected = [0; 32];
+            if rows_per_leaf <= 2 {
+                expected[..leaf_bytes.len()].copy_from_slice(leaf_bytes);
+            } else {
+                hash_fn(leaf_bytes, &mut expected);
+            }
+            assert_eq!(&expected, leaf);
+        }
+
+        // the verifier computes the same leaves from the queried rows
+        let positions = [1, 6, 11];
+        let queries = table.query(&trace_tree, &positions, NATIVE);
+        let num_leaves = trace_tree.leaves().len();
+        let (proof, _) = queries.into_trace_batch::<f62::BaseElement, Blake3_256>(
+            num_leaves,
+            TraceRowHashing::Flat,
+            table.width(),
+            NATIVE,
+        );
+        let leaf_positions = map_trace_positions_to_leaves(&positions, rows_per_leaf);
+        assert!(MerkleTree::verify_batch(
+            trace_tree.root(),
+            &leaf_positions,
+            &proof,
+            hash_fn
+        ));
+    }
+}
+
+#[test]
+fn commit_trace_table_with_chunked_rows() {
+    // a row of a 3-register trace is split into 2 chunks of 2 columns (the last one is short),
+    // and each row is padded to 2 chunk digests
+    let data = vec![
+        (0..16u32).map(f62::BaseElement::from).collect::<Vec<_>>(),
+        (16..32u32).map(f62::BaseElement::from).collect::<Vec<_>>(),
+        (32..48u32).map(f62::BaseElement::from).collect::<Vec<_>>(),
+    ];
+    let table = TraceTable::new(data, 1);
+    let hash_fn = Blake3_256::hash_fn();
+    let row_hashing = TraceRowHashing::Chunked(2);
+
+    for &rows_per_leaf in [1, 2].iter() {
+        let trace_tree = table.build_commitment::<Blake3_256>(rows_per_leaf, row_hashing, NATIVE);
+        for (i, leaf) in trace_tree.leaves().iter().enumerate() {
+            let mut chunk_digests = Vec::new();
+            for step in (i * rows_per_leaf)..((i + 1) * rows_per_leaf) {
+                let row = [table.get(0, step), table.get(1, step), table.get(2, step)];
+                for chunk in row.chunks(2) {
+                    let mut digest = [0; 32];
+                    hash_fn(f62::BaseElement::elements_as_bytes(chunk), &mut digest);
+                    chunk_digests.push(digest);
+                }
+            }
+            let expected = MerkleTree::new(chunk_digests, hash_fn);
+            assert_eq!(expected.root(), leaf);
+        }
+
+        // full rows can still be queried and authenticated against the commitment
+        let positions = [1, 6, 11];
+        let queries = table.query(&trace_tree, &positions, NATIVE);
+        let num_leaves = trace_tree.leaves().len();
+        let (proof, _) = queries.into_trace_batch::<f62::BaseElement, Blake3_256>(
+            num_leaves,
+            row_hashing,
+            table.width(),
+            NATIVE,
+        );
+        let leaf_positions = map_trace_positions_to_leaves(&positions, rows_per_leaf);
+        assert!(MerkleTree::verify_batch(
+            trace_tree.root(),
+            &leaf_positions,
+            &proof,
+            hash_fn
+        ));
+
+        // opening a single chunk reveals only the columns of that chunk
+        let opening = table.open_chunks::<Blake3_256>(&trace_tree, 2, &positions, &[1], NATIVE);
+        let expected = positions
+            .iter()
+            .map(|&step| f62::BaseElement::elements_as_bytes(&[table.get(2, step)]).to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(expected, opening.leaves.values);
+        assert_eq!(positions.len(), opening.chunk_paths.len());
+    }
+}
+
+#[test]
+#[should_panic(expected = "is not a permutation of 2 registers")]
+fn set_invalid_column_layout() {
+    let trace_length = 8;
+    let context = build_context(trace_length, 2, 4);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&context);
+    let (mut extended_trace, _) = trace.extend(&domain);
+    extended_trace.set_column_layout(vec![1, 1]);
+}
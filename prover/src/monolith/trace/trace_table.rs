@@ -3,11 +3,22 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use common::{proof::Queries, EvaluationFrame};
+use common::{
+    proof::{Queries, TraceChunkOpening},
+    utils::{
+        get_leaf_range, get_num_row_chunks, get_padded_chunks_per_row, get_queried_rows,
+        hash_trace_leaf, hash_trace_leaf_chunks, is_valid_column_layout,
+        map_trace_positions_to_leaves,
+    },
+    EvaluationFrame, TraceRowHashing,
+};
 use crypto::{Hasher, MerkleTree};
-use math::field::StarkField;
+use math::field::{ByteEncoding, StarkField};
 use utils::uninit_vector;
 
+#[cfg(feature = "concurrent")]
+use core::cmp;
+
 #[cfg(feature = "concurrent")]
 use rayon::prelude::*;
 
@@ -16,6 +27,7 @@ use rayon::prelude::*;
 pub struct TraceTable<B: StarkField> {
     data: Vec<Vec<B>>,
     blowup: usize,
+    layout: Vec<usize>,
 }
 
 impl<B: StarkField> TraceTable<B> {
@@ -23,12 +35,38 @@ impl<B: StarkField> TraceTable<B> {
     // --------------------------------------------------------------------------------------------
     /// Creates a new trace table from a list of provided register traces.
     pub(super) fn new(data: Vec<Vec<B>>, blowup: usize) -> Self {
-        TraceTable { data, blowup }
+        let layout = (0..data.len()).collect();
+        TraceTable {
+            data,
+            blowup,
+            layout,
+        }
+    }
+
+    /// Sets the column at which values of each register are placed in rows of the trace
+    /// commitment; by default, registers are placed in the order of their indexes.
+    ///
+    /// # Panics
+    /// Panics if `layout` is not a permutation of register indexes.
+    pub fn set_column_layout(&mut self, layout: Vec<usize>) {
+        assert!(
+            is_valid_column_layout(&layout, self.width()),
+            "column layout {:?} is not a permutation of {} registers",
+            layout,
+            self.width()
+        );
+        self.layout = layout;
     }
 
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the column at which values of each register are placed in rows of the trace
+    /// commitment.
+    pub fn column_layout(&self) -> &[usize] {
+        &self.layout
+    }
+
     /// Returns number of registers in the trace table.
     pub fn width(&self) -> usize {
         self.data.len()
@@ -63,79 +101,253 @@ impl<B: StarkField> TraceTable<B> {
         }
     }
 
-    /// Reads current and next rows from the execution trace table into the specified frame.
-    pub fn read_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<B>) {
-        // at the end of the trace, next state wraps around and we read the first step again
-        let next_lde_step = (lde_step + self.blowup()) % self.len();
-
-        self.read_row_into(lde_step, &mut frame.current);
-        self.read_row_into(next_lde_step, &mut frame.next);
-    }
-
     // TRACE COMMITMENT
     // --------------------------------------------------------------------------------------------
-    /// Builds a Merkle tree out of trace table rows (hash of each row becomes a leaf in the tree).
-    pub fn build_commitment<H: Hasher>(&self) -> MerkleTree {
-        let hash_fn = H::hash_fn();
-        // allocate vector to store row hashes
-        let mut hashed_states = uninit_vector::<[u8; 32]>(self.len());
-
-        // iterate though table rows, hashing each row; the hashing is done by first copying
-        // the state into trace_state buffer to avoid unneeded allocations, and then by applying
-        // the hash function to the buffer.
+    /// Builds a Merkle tree out of trace table rows; `rows_per_leaf` adjacent rows are encoded
+    /// using the specified byte `encoding` and hashed together to become a leaf in the tree
+    /// according to the `row_hashing` strategy. For small fields, rows which fit into a single
+    /// digest may be packed into a leaf without hashing, as described in [hash_trace_leaf()].
+    pub fn build_commitment<H: Hasher>(
+        &self,
+        rows_per_leaf: usize,
+        row_hashing: TraceRowHashing,
+        encoding: ByteEncoding,
+    ) -> MerkleTree {
+        assert!(
+            rows_per_leaf.is_power_of_two() && rows_per_leaf <= self.len(),
+            "rows per leaf must be a power of 2 no greater than trace length, but was {}",
+            rows_per_leaf
+        );
+        // allocate vector to store leaf hashes
+        let mut hashed_states = uninit_vector::<[u8; 32]>(self.len() / rows_per_leaf);
+
+        // iterate though table rows, hashing each group of rows; the hashing is done by first
+        // copying the rows into leaf_state buffer to avoid unneeded allocations, and then by
+        // applying the hash function to the buffer.
         #[cfg(feature = "concurrent")]
         {
-            let batch_size = hashed_states.len() / rayon::current_num_threads().next_power_of_two();
+            let batch_size = cmp::max(
+                hashed_states.len() / rayon::current_num_threads().next_power_of_two(),
+                1,
+            );
             hashed_states
                 .par_chunks_mut(batch_size)
                 .enumerate()
                 .for_each(|(batch_idx, hashed_states_batch)| {
                     let offset = batch_idx * batch_size;
-                    let mut trace_state = vec![B::ZERO; self.width()];
-                    for (i, row_hash) in hashed_states_batch.iter_mut().enumerate() {
-                        self.read_row_into(i + offset, &mut trace_state);
-                        hash_fn(B::elements_as_bytes(&trace_state), row_hash);
+                    let mut leaf_state = vec![B::ZERO; self.width() * rows_per_leaf];
+                    for (i, leaf_hash) in hashed_states_batch.iter_mut().enumerate() {
+                        self.read_leaf_into(i + offset, rows_per_leaf, &mut leaf_state);
+                        let leaf_bytes = encoding.encode_elements(&leaf_state);
+                        hash_trace_leaf::<B, H>(
+                            &leaf_bytes,
+                            row_hashing,
+                            self.width(),
+                            encoding,
+                            leaf_hash,
+                        );
                     }
                 });
         }
 
         #[cfg(not(feature = "concurrent"))]
         {
-            let mut trace_state = vec![B::ZERO; self.width()];
-            for (i, row_hash) in hashed_states.iter_mut().enumerate() {
-                self.read_row_into(i, &mut trace_state);
-                hash_fn(B::elements_as_bytes(&trace_state), row_hash);
+            let mut leaf_state = vec![B::ZERO; self.width() * rows_per_leaf];
+            for (i, leaf_hash) in hashed_states.iter_mut().enumerate() {
+                self.read_leaf_into(i, rows_per_leaf, &mut leaf_state);
+                let leaf_bytes = encoding.encode_elements(&leaf_state);
+                hash_trace_leaf::<B, H>(
+                    &leaf_bytes,
+                    row_hashing,
+                    self.width(),
+                    encoding,
+                    leaf_hash,
+                );
             }
         }
 
-        // build Merkle tree out of hashed rows
-        MerkleTree::new(hashed_states, hash_fn)
+        // build Merkle tree out of hashed leaves
+        MerkleTree::new(hashed_states, H::hash_fn())
     }
 
     // QUERY TRACE
     // --------------------------------------------------------------------------------------------
     /// Returns trace table rows at the specified positions along with Merkle authentication paths
-    /// from the `commitment` root to these rows.
-    pub fn query(&self, commitment: MerkleTree, positions: &[usize]) -> Queries {
+    /// from the `commitment` root to these rows. When several rows are hashed into a single leaf
+    /// of the commitment, all rows of the leaves containing the specified positions are returned,
+    /// one leaf per unique leaf index; values of the rows are encoded using the specified byte
+    /// `encoding`. The commitment is only borrowed, and thus, can be used again after the
+    /// queries are built.
+    pub fn query(
+        &self,
+        commitment: &MerkleTree,
+        positions: &[usize],
+        encoding: ByteEncoding,
+    ) -> Queries {
+        let num_leaves = commitment.leaves().len();
+        let rows_per_leaf = self.len() / num_leaves;
         assert_eq!(
             self.len(),
-            commitment.leaves().len(),
+            num_leaves * rows_per_leaf,
             "inconsistent trace table commitment"
         );
 
-        // allocate memory for queried trace states
-        let mut trace_states = Vec::with_capacity(positions.len());
+        // map positions to leaf indexes; multiple positions may fall into the same leaf
+        let leaf_positions = map_trace_positions_to_leaves(positions, rows_per_leaf);
+
+        // copy values from the trace table at the specified leaves into a single buffer;
+        // since the table is stored in column-major order, rows have to be assembled anyway,
+        // but this way we allocate memory for all of them at once
+        let leaf_width = self.width() * rows_per_leaf;
+        let mut leaf_states = vec![B::ZERO; leaf_positions.len() * leaf_width];
+        for (&i, leaf) in leaf_positions
+            .iter()
+            .zip(leaf_states.chunks_mut(leaf_width))
+        {
+            self.read_leaf_into(i, rows_per_leaf, leaf);
+        }
+        let leaf_states = leaf_states.chunks(leaf_width).collect::<Vec<_>>();
+
+        // build Merkle authentication paths to the leaves specified by leaf positions
+        let trace_proof = commitment.prove_batch(&leaf_positions);
+
+        Queries::new(trace_proof, &leaf_states, encoding)
+    }
+
+    /// Returns values of the specified `chunks` of trace table rows at the specified positions
+    /// along with authentication paths from the `commitment` root to these chunks; the
+    /// commitment must have been built with [TraceRowHashing::Chunked] hashing of rows split
+    /// into chunks of `chunk_width` columns, with values encoded using the specified byte
+    /// `encoding`. Chunk indexes must be unique and must be listed in ascending order.
+    pub fn open_chunks<H: Hasher>(
+        &self,
+        commitment: &MerkleTree,
+        chunk_width: usize,
+        positions: &[usize],
+        chunks: &[usize],
+        encoding: ByteEncoding,
+    ) -> TraceChunkOpening {
+        let num_chunks = get_num_row_chunks(self.width(), chunk_width);
+        assert!(
+            chunks.windows(2).all(|w| w[0] < w[1]),
+            "chunk indexes must be unique and sorted in ascending order"
+        );
+        assert!(
+            chunks.last().is_some_and(|&chunk| chunk < num_chunks),
+            "chunk indexes must be smaller than the number of chunks in a row ({})",
+            num_chunks
+        );
+
+        let num_leaves = commitment.leaves().len();
+        let rows_per_leaf = self.len() / num_leaves;
+        let leaf_positions = map_trace_positions_to_leaves(positions, rows_per_leaf);
+
+        let row_bytes = self.width() * encoding.element_bytes::<B>();
+        let chunk_bytes = chunk_width * encoding.element_bytes::<B>();
+        let padded_chunks = get_padded_chunks_per_row(num_chunks);
+
+        let mut leaf_state = vec![B::ZERO; self.width() * rows_per_leaf];
+        let mut values = Vec::with_capacity(leaf_positions.len());
+        let mut chunk_paths = Vec::with_capacity(leaf_positions.len());
+        for &leaf in leaf_positions.iter() {
+            self.read_leaf_into(leaf, rows_per_leaf, &mut leaf_state);
+            let leaf_bytes = encoding.encode_elements(&leaf_state);
+
+            // collect values of the requested chunks in all queried rows of the leaf, and
+            // compute indexes of these chunks in the Merkle tree of chunk digests of the leaf
+            let mut leaf_values = Vec::new();
+            let mut chunk_indexes = Vec::new();
+            for row in get_queried_rows(leaf, rows_per_leaf, positions) {
+                let row_bytes = &leaf_bytes[row * row_bytes..(row + 1) * row_bytes];
+                let row_chunks = row_bytes.chunks(chunk_bytes).collect::<Vec<_>>();
+                for &chunk in chunks.iter() {
+                    leaf_values.extend_from_slice(row_chunks[chunk]);
+                    chunk_indexes.push(row * padded_chunks + chunk);
+                }
+            }
+
+            let digests = hash_trace_leaf_chunks::<H>(&leaf_bytes, row_bytes, chunk_bytes);
+            let chunk_tree = MerkleTree::new(digests, H::hash_fn());
+            chunk_paths.push(chunk_tree.prove_batch(&chunk_indexes).nodes);
+            values.push(leaf_values);
+        }
+
+        TraceChunkOpening {
+            leaves: Queries {
+                paths: commitment.prove_batch(&leaf_positions).nodes,
+                values,
+                compact_values: None,
+            },
+            chunk_paths,
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Copies `rows_per_leaf` rows which make up the leaf at the specified index into the
+    /// `destination` slice; the rows are written one after the other, and values within each
+    /// row are arranged according to the column layout.
+    fn read_leaf_into(&self, leaf_idx: usize, rows_per_leaf: usize, destination: &mut [B]) {
+        let first_step = get_leaf_range(leaf_idx, rows_per_leaf).start;
+        for (i, row) in destination.chunks_mut(self.width()).enumerate() {
+            for (register, &column) in self.data.iter().zip(self.layout.iter()) {
+                row[column] = register[first_step + i];
+            }
+        }
+    }
+}
+
+// EXTENDED TRACE
+// ================================================================================================
+
+/// Extended execution trace against which constraints are evaluated; this consists of the main
+/// segment of the trace and, if the computation declares one, the auxiliary segment. The two
+/// segments are committed to separately, but registers of the auxiliary segment follow registers
+/// of the main segment in evaluation frames.
+pub struct ExtendedTrace<'a, B: StarkField> {
+    main: &'a TraceTable<B>,
+    aux: Option<&'a TraceTable<B>>,
+}
 
-        // copy values from the trace table at the specified positions into rows
-        // and append the rows to trace_states
-        for &i in positions.iter() {
-            let row = self.data.iter().map(|r| r[i]).collect();
-            trace_states.push(row);
+impl<'a, B: StarkField> ExtendedTrace<'a, B> {
+    /// Returns a view of the extended trace consisting of the specified segments.
+    ///
+    /// # Panics
+    /// Panics if the segments have different lengths.
+    pub fn new(main: &'a TraceTable<B>, aux: Option<&'a TraceTable<B>>) -> Self {
+        if let Some(aux) = aux {
+            assert_eq!(
+                main.len(),
+                aux.len(),
+                "auxiliary trace segment must have the same length as the main segment"
+            );
         }
+        ExtendedTrace { main, aux }
+    }
+
+    /// Returns the number of registers in both segments of the trace.
+    pub fn width(&self) -> usize {
+        self.main.width() + self.aux.map_or(0, |aux| aux.width())
+    }
 
-        // build Merkle authentication paths to the leaves specified by positions
-        let trace_proof = commitment.prove_batch(&positions);
+    /// Returns the number of states in the trace.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.main.len()
+    }
 
-        Queries::new(trace_proof, trace_states)
+    /// Reads current and next rows of both segments of the trace into the specified frame.
+    pub fn read_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<B>) {
+        // at the end of the trace, next state wraps around and we read the first step again
+        let next_lde_step = (lde_step + self.main.blowup()) % self.len();
+
+        self.main.read_row_into(lde_step, &mut frame.current);
+        self.main.read_row_into(next_lde_step, &mut frame.next);
+        if let Some(aux) = self.aux {
+            let main_width = self.main.width();
+            aux.read_row_into(lde_step, &mut frame.current[main_width..]);
+            aux.read_row_into(next_lde_step, &mut frame.next[main_width..]);
+        }
     }
 }
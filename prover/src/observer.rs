@@ -0,0 +1,101 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::proof::Commitments;
+
+// PROVER OBSERVER
+// ================================================================================================
+
+/// Receives notifications about transcript events as they occur during proof generation.
+///
+/// An observer can be passed to [Prover::with_observer()](crate::Prover::with_observer) to record
+/// or co-sign commitments and challenges without modifying the prover. Events are delivered by the
+/// prover channel at the moment a value is committed to or drawn, and thus, in the order in which
+/// they happen in the protocol:
+///
+/// 1. trace commitment;
+/// 2. commitments to blinded trace registers, one per register designated by the AIR;
+/// 3. commitment to extra polynomials, if the AIR declares any;
+/// 4. random elements for the auxiliary trace segment and commitment to the segment, if the AIR
+///    declares one;
+/// 5. constraint commitment;
+/// 6. out-of-domain point;
+/// 7. DEEP composition coefficients;
+/// 8. out-of-domain evaluation frame;
+/// 9. FRI layer commitment followed by the folding coefficient drawn from it, for every layer;
+/// 10. all of the above commitments together;
+/// 11. query seed and proof-of-work nonce;
+/// 12. query positions.
+///
+/// Random coefficients for constraint composition are drawn lazily from a seed derived from the
+/// trace commitment (and all other commitments listed above before it, if any); so,
+/// these are not reported separately. Field elements are passed as bytes encoded according to
+/// the byte encoding of the proof options so that the trait can be used as a trait object
+/// regardless of the field in which the proof is generated.
+///
+/// All methods have empty default implementations, so an observer needs to implement only the
+/// events it is interested in.
+pub trait ProverObserver {
+    /// Called after the prover commits to the extended execution trace.
+    fn on_trace_commitment(&mut self, _root: &[u8; 32]) {}
+
+    /// Called after the prover commits to a blinded version of the specified trace `register`.
+    fn on_aux_commitment(&mut self, _register: usize, _root: &[u8; 32]) {}
+
+    /// Called after the prover commits to the extended extra polynomials.
+    fn on_extra_commitment(&mut self, _root: &[u8; 32]) {}
+
+    /// Called after random elements for building the auxiliary segment of the execution trace
+    /// are drawn from the commitments made so far.
+    fn on_aux_rand_elements(&mut self, _elements: &[u8]) {}
+
+    /// Called after the prover commits to the extended auxiliary segment of the execution trace.
+    fn on_aux_segment_commitment(&mut self, _root: &[u8; 32]) {}
+
+    /// Called after the prover commits to the evaluations of the constraint polynomial.
+    fn on_constraint_commitment(&mut self, _root: &[u8; 32]) {}
+
+    /// Called after an out-of-domain point `z` is drawn; the point may be an element of an
+    /// extension field.
+    fn on_ood_point(&mut self, _z: &[u8]) {}
+
+    /// Called after coefficients for DEEP composition are drawn; coefficients are listed in the
+    /// order in which they are drawn: three per trace register, two for degree adjustment, one
+    /// for the constraint polynomial, and two per extra polynomial.
+    fn on_composition_coefficients(&mut self, _coefficients: &[u8]) {}
+
+    /// Called after the execution trace is evaluated at the out-of-domain points `z` and
+    /// `z * g`, where `g` is the generator of the trace domain; evaluations of local registers
+    /// at `z * g` are omitted. `extra_at_z` contains evaluations of extra polynomials at `z`, and
+    /// is empty if the prover does not commit to any extra polynomials.
+    fn on_ood_frame(&mut self, _trace_at_z1: &[u8], _trace_at_z2: &[u8], _extra_at_z: &[u8]) {}
+
+    /// Called after the prover commits to a layer of the FRI protocol.
+    fn on_fri_layer_commitment(&mut self, _layer_index: usize, _root: &[u8; 32]) {}
+
+    /// Called after the coefficient used to fold the specified FRI layer is drawn from the
+    /// commitment to this layer.
+    fn on_fri_alpha(&mut self, _layer_index: usize, _alpha: &[u8]) {}
+
+    /// Called once the prover has committed to the last FRI layer and to the FRI remainder with
+    /// all commitments which will be included in the proof. This happens before proof-of-work is
+    /// applied to the query seed and before the proof is built, so commitments can be anchored
+    /// externally (e.g., on a blockchain) without waiting for the rest of proof generation.
+    fn on_commitments(&mut self, _commitments: &Commitments) {}
+
+    /// Called after the query seed is determined by applying proof-of-work to the FRI layer
+    /// commitments.
+    fn on_query_seed(&mut self, _seed: &[u8; 32], _pow_nonce: u64) {}
+
+    /// Called after query positions are drawn from the query seed; FRI layers are queried at all
+    /// of the `positions`, while trace and constraint commitments are queried only at the first
+    /// num_queries of them.
+    fn on_query_positions(&mut self, _positions: &[usize]) {}
+}
+
+/// An observer which ignores all events; this is the observer used by [prove()](crate::prove).
+pub struct NullObserver;
+
+impl ProverObserver for NullObserver {}
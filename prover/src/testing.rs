@@ -0,0 +1,182 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Property-testing harness for AIR implementations.
+//!
+//! This module is available only when the `testing` feature is enabled. It is intended for
+//! authors of AIRs: given a function which builds random valid execution traces of a
+//! computation, [check_air()] makes sure that the traces can be proven and verified, and that
+//! corrupting any single cell of a trace makes it invalid against the AIR.
+
+use crate::{prove, Air, ExecutionTrace, ProofOptions, TraceInfo};
+use math::field::FieldElement;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub use rand;
+
+// AIR STRATEGY
+// ================================================================================================
+
+/// Function which builds a random valid execution trace of a computation together with public
+/// inputs against which the trace is valid.
+pub type TraceBuilder<A> = dyn Fn(
+    &mut StdRng,
+) -> (
+    ExecutionTrace<<A as Air>::BaseElement>,
+    <A as Air>::PublicInputs,
+);
+
+/// Describes how [check_air()] exercises an AIR.
+pub struct AirStrategy<A: Air> {
+    options: ProofOptions,
+    build_fn: Box<TraceBuilder<A>>,
+    num_cases: usize,
+    num_corruptions: usize,
+    seed: u64,
+}
+
+impl<A: Air> AirStrategy<A> {
+    /// Returns a new strategy which generates proofs with the specified `options` for traces
+    /// built by `build_fn`. By default, 8 traces are generated, and 4 cells are corrupted in each
+    /// of them.
+    ///
+    /// The `build_fn` must draw all of its randomness from the provided generator so that
+    /// failures can be reproduced from the seed of the strategy.
+    pub fn new<F>(options: ProofOptions, build_fn: F) -> Self
+    where
+        F: Fn(&mut StdRng) -> (ExecutionTrace<A::BaseElement>, A::PublicInputs) + 'static,
+    {
+        AirStrategy {
+            options,
+            build_fn: Box::new(build_fn),
+            num_cases: 8,
+            num_corruptions: 4,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of random traces to generate.
+    pub fn with_num_cases(mut self, num_cases: usize) -> Self {
+        self.num_cases = num_cases;
+        self
+    }
+
+    /// Sets the number of random single-cell corruptions to check for each generated trace.
+    pub fn with_num_corruptions(mut self, num_corruptions: usize) -> Self {
+        self.num_corruptions = num_corruptions;
+        self
+    }
+
+    /// Sets the seed of the random generator used to build and corrupt traces.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+// AIR CHECKER
+// ================================================================================================
+
+/// Checks the AIR `A` against random execution traces produced by the `strategy`.
+///
+/// For each generated trace, the following is checked:
+/// * the trace satisfies the AIR, and a proof generated for it is accepted by the verifier;
+/// * adding a random non-zero value to a random cell of the trace makes the trace violate at
+///   least one assertion or transition constraint of the AIR. In release builds, a proof is also
+///   generated for the corrupted trace, and either proof generation or verification must fail.
+///
+/// # Panics
+/// Panics if any of the checks fails; the panic message contains the seed of the strategy and
+/// the index of the failing case.
+pub fn check_air<A>(strategy: &AirStrategy<A>)
+where
+    A: Air,
+    A::PublicInputs: Clone,
+{
+    let mut rng = StdRng::seed_from_u64(strategy.seed);
+    for case in 0..strategy.num_cases {
+        let (trace, pub_inputs) = (strategy.build_fn)(&mut rng);
+        let air = build_air::<A>(&trace, pub_inputs.clone(), strategy.options.clone());
+
+        if let Err(error) = trace.check(&air) {
+            panic!(
+                "case {} (seed {}): generated trace is not valid: {}",
+                case, strategy.seed, error
+            );
+        }
+        let proof = prove::<A>(trace.clone(), pub_inputs.clone(), strategy.options.clone())
+            .unwrap_or_else(|error| {
+                panic!(
+                    "case {} (seed {}): failed to prove valid trace: {}",
+                    case, strategy.seed, error
+                )
+            });
+        if let Err(error) = verifier::verify::<A>(proof, pub_inputs.clone()) {
+            panic!(
+                "case {} (seed {}): proof of valid trace was rejected: {}",
+                case, strategy.seed, error
+            );
+        }
+
+        for _ in 0..strategy.num_corruptions {
+            let register = rng.gen_range(0..trace.width());
+            let step = rng.gen_range(0..trace.len());
+
+            let mut corrupted = trace.clone();
+            let value = corrupted.get(register, step) + draw_nonzero_element(&mut rng);
+            corrupted.set(register, step, value);
+
+            assert!(
+                corrupted.check(&air).is_err(),
+                "case {} (seed {}): trace cell ({}, {}) is not constrained by the AIR",
+                case,
+                strategy.seed,
+                register,
+                step
+            );
+
+            // in debug builds, the prover panics on invalid traces
+            #[cfg(not(debug_assertions))]
+            if let Ok(proof) = prove::<A>(corrupted, pub_inputs.clone(), strategy.options.clone()) {
+                assert!(
+                    verifier::verify::<A>(proof, pub_inputs.clone()).is_err(),
+                    "case {} (seed {}): proof of trace corrupted at cell ({}, {}) was accepted",
+                    case,
+                    strategy.seed,
+                    register,
+                    step
+                );
+            }
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds an instance of the AIR `A` for the specified trace in the same way as the prover does.
+fn build_air<A: Air>(
+    trace: &ExecutionTrace<A::BaseElement>,
+    pub_inputs: A::PublicInputs,
+    options: ProofOptions,
+) -> A {
+    let trace_info = TraceInfo {
+        length: trace.len(),
+        meta: Vec::new(),
+    };
+    A::new(trace_info, pub_inputs, options)
+}
+
+/// Draws a uniformly random non-zero field element from the specified generator.
+fn draw_nonzero_element<E: FieldElement>(rng: &mut StdRng) -> E {
+    let mut bytes = vec![0u8; E::ELEMENT_BYTES];
+    loop {
+        rng.fill(&mut bytes[..]);
+        match E::from_random_bytes(&bytes) {
+            Some(element) if element != E::ZERO => return element,
+            _ => continue,
+        }
+    }
+}
@@ -5,8 +5,9 @@
 
 use crate::ExecutionTrace;
 use common::{
-    Air, Assertion, ComputationContext, EvaluationFrame, FieldExtension, HashFunction,
-    ProofOptions, TraceInfo, TransitionConstraintDegree,
+    proof::StarkProof, Air, Assertion, ByteWriter, ComputationContext, EvaluationFrame,
+    FieldExtension, HashFunction, ProofOptions, Serializable, TraceInfo,
+    TransitionConstraintDegree,
 };
 use math::field::{f128::BaseElement, FieldElement};
 
@@ -27,6 +28,122 @@ pub fn build_fib_trace(length: usize) -> ExecutionTrace<BaseElement> {
     ExecutionTrace::init(vec![reg1, reg2])
 }
 
+/// Returns public inputs of [FibAir] for the specified trace built by [build_fib_trace()].
+pub fn build_fib_inputs(trace: &ExecutionTrace<BaseElement>) -> FibInputs {
+    FibInputs {
+        start: [trace.get(0, 0), trace.get(1, 0)],
+        result: trace.get(1, trace.len() - 1),
+    }
+}
+
+/// Generates a proof of computing a Fibonacci sequence of the specified length.
+pub fn prove_fib(sequence_length: usize, options: ProofOptions) -> StarkProof {
+    let trace = build_fib_trace(sequence_length);
+    let pub_inputs = build_fib_inputs(&trace);
+    crate::prove::<FibAir>(trace, pub_inputs, options).unwrap()
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// Public inputs of [FibAir]: the first two terms of the sequence and the expected value of the
+/// term at which the sequence terminates.
+#[derive(Clone)]
+pub struct FibInputs {
+    pub start: [BaseElement; 2],
+    pub result: BaseElement,
+}
+
+impl Serializable for FibInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        BaseElement::write_batch_into(&self.start, target);
+        target.write(&self.result);
+    }
+}
+
+/// AIR for the trace built by [build_fib_trace()]; each row holds two consecutive terms of the
+/// sequence.
+pub struct FibAir {
+    context: ComputationContext,
+    start: [BaseElement; 2],
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = FibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: FibInputs, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+        ];
+        FibAir {
+            context: ComputationContext::new(2, trace_info.length, degrees, options),
+            start: pub_inputs.start,
+            result: pub_inputs.result,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, self.start[0]),
+            Assertion::single(1, 0, self.start[1]),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+}
+
+/// Fibonacci AIR which designates the second register as blinded.
+pub struct BlindedFibAir(FibAir);
+
+impl Air for BlindedFibAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = FibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: FibInputs, options: ProofOptions) -> Self {
+        BlindedFibAir(FibAir::new(trace_info, pub_inputs, options))
+    }
+
+    fn context(&self) -> &ComputationContext {
+        self.0.context()
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        self.0.get_assertions()
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_blinded_registers(&self) -> Vec<usize> {
+        vec![1]
+    }
+}
+
 // MOCK AIR
 // ================================================================================================
 
@@ -119,3 +236,13 @@ pub fn build_context(
     let t_degrees = vec![TransitionConstraintDegree::new(2)];
     ComputationContext::new(trace_width, trace_length, t_degrees, options)
 }
+
+/// Returns options for proofs generated in end-to-end tests.
+pub fn build_proof_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 16, 0, HashFunction::Blake3_256, extension)
+}
@@ -5,6 +5,11 @@
 
 use core::{mem, slice};
 
+mod serialization;
+pub use serialization::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
 #[cfg(test)]
 mod tests;
 
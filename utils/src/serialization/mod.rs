@@ -0,0 +1,359 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::convert::TryInto;
+use thiserror::Error;
+
+#[cfg(test)]
+mod tests;
+
+// SERIALIZABLE TRAIT
+// ================================================================================================
+
+/// Defines how a value is serialized into a sequence of bytes.
+///
+/// All multi-byte integers are written in little-endian byte order. Collections are not prefixed
+/// with their lengths implicitly; a type which contains a variable number of values must write
+/// the number of values explicitly (e.g., via [ByteWriter::write_u32()]) so that it can be read
+/// back using the corresponding [Deserializable] implementation.
+pub trait Serializable: Sized {
+    /// Serializes `self` into bytes and writes these bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W);
+
+    /// Serializes `self` into a vector of bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Serializes all values in the `source` one after another into the `target`; the number of
+    /// values is not written.
+    fn write_batch_into<W: ByteWriter>(source: &[Self], target: &mut W) {
+        for value in source {
+            value.write_into(target);
+        }
+    }
+}
+
+impl Serializable for () {
+    fn write_into<W: ByteWriter>(&self, _target: &mut W) {}
+}
+
+impl Serializable for bool {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Serializable for u8 {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self);
+    }
+}
+
+impl Serializable for u16 {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u16(*self);
+    }
+}
+
+impl Serializable for u32 {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(*self);
+    }
+}
+
+impl Serializable for u64 {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u64(*self);
+    }
+}
+
+impl Serializable for u128 {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(&self.to_le_bytes());
+    }
+}
+
+impl<const N: usize> Serializable for [u8; N] {
+    /// Writes the bytes of the array as is; this is used, for example, for 32-byte digests.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(self);
+    }
+}
+
+// DESERIALIZABLE TRAIT
+// ================================================================================================
+
+/// Defines how a value is read from a sequence of bytes produced by the corresponding
+/// [Serializable] implementation.
+pub trait Deserializable: Sized {
+    /// Reads a sequence of bytes from the `source`, attempts to deserialize these bytes into a
+    /// value, and returns the value.
+    ///
+    /// # Errors
+    /// Returns an error if the `source` does not contain enough bytes, or if the bytes do not
+    /// encode a valid value.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError>;
+
+    /// Deserializes a value from the provided `bytes`.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes do not encode a valid value, or if not all of the bytes
+    /// were consumed while reading the value.
+    fn read_from_bytes(bytes: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(bytes);
+        let result = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes(
+                bytes.len() - source.position(),
+            ));
+        }
+        Ok(result)
+    }
+
+    /// Reads `num_values` values one after another from the `source`.
+    ///
+    /// # Errors
+    /// Returns an error if any of the values could not be read.
+    fn read_batch_from<R: ByteReader>(
+        source: &mut R,
+        num_values: usize,
+    ) -> Result<Vec<Self>, DeserializationError> {
+        // the number of values may come from untrusted input; thus, memory is not reserved up
+        // front, and reading fails as soon as the source runs out of bytes
+        let mut result = Vec::new();
+        for _ in 0..num_values {
+            result.push(Self::read_from(source)?);
+        }
+        Ok(result)
+    }
+}
+
+impl Deserializable for () {
+    fn read_from<R: ByteReader>(_source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(())
+    }
+}
+
+impl Deserializable for bool {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "{} is not a valid boolean value",
+                value
+            ))),
+        }
+    }
+}
+
+impl Deserializable for u8 {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        source.read_u8()
+    }
+}
+
+impl Deserializable for u16 {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        source.read_u16()
+    }
+}
+
+impl Deserializable for u32 {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        source.read_u32()
+    }
+}
+
+impl Deserializable for u64 {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        source.read_u64()
+    }
+}
+
+impl Deserializable for u128 {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(u128::from_le_bytes(source.read_u8_array()?))
+    }
+}
+
+impl<const N: usize> Deserializable for [u8; N] {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        source.read_u8_array()
+    }
+}
+
+// BYTE WRITER
+// ================================================================================================
+
+/// Defines how primitive values are written into a sequence of bytes.
+pub trait ByteWriter: Sized {
+    /// Writes a single byte into `self`.
+    fn write_u8(&mut self, value: u8);
+
+    /// Writes a sequence of bytes into `self`.
+    fn write_u8_slice(&mut self, values: &[u8]);
+
+    /// Writes a u16 value in little-endian byte order into `self`.
+    fn write_u16(&mut self, value: u16) {
+        self.write_u8_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a u32 value in little-endian byte order into `self`.
+    fn write_u32(&mut self, value: u32) {
+        self.write_u8_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a u64 value in little-endian byte order into `self`.
+    fn write_u64(&mut self, value: u64) {
+        self.write_u8_slice(&value.to_le_bytes());
+    }
+
+    /// Serializes a value into `self`.
+    fn write<S: Serializable>(&mut self, value: &S) {
+        value.write_into(self)
+    }
+}
+
+impl ByteWriter for Vec<u8> {
+    fn write_u8(&mut self, value: u8) {
+        self.push(value);
+    }
+
+    fn write_u8_slice(&mut self, values: &[u8]) {
+        self.extend_from_slice(values);
+    }
+}
+
+// BYTE READER
+// ================================================================================================
+
+/// Defines how primitive values are read from a sequence of bytes.
+pub trait ByteReader {
+    /// Returns a single byte read from `self`.
+    ///
+    /// # Errors
+    /// Returns an error if no more bytes are left in `self`.
+    fn read_u8(&mut self) -> Result<u8, DeserializationError>;
+
+    /// Returns a vector of `len` bytes read from `self`.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than `len` bytes are left in `self`.
+    fn read_u8_vec(&mut self, len: usize) -> Result<Vec<u8>, DeserializationError>;
+
+    /// Returns true if there are more bytes left to be read from `self`.
+    fn has_more_bytes(&self) -> bool;
+
+    /// Returns a byte array of length `N` read from `self`.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than `N` bytes are left in `self`.
+    fn read_u8_array<const N: usize>(&mut self) -> Result<[u8; N], DeserializationError> {
+        let bytes = self.read_u8_vec(N)?;
+        // read_u8_vec() returns exactly N bytes
+        Ok(bytes.try_into().unwrap())
+    }
+
+    /// Returns a u16 value read from `self` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than two bytes are left in `self`.
+    fn read_u16(&mut self) -> Result<u16, DeserializationError> {
+        Ok(u16::from_le_bytes(self.read_u8_array()?))
+    }
+
+    /// Returns a u32 value read from `self` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than four bytes are left in `self`.
+    fn read_u32(&mut self) -> Result<u32, DeserializationError> {
+        Ok(u32::from_le_bytes(self.read_u8_array()?))
+    }
+
+    /// Returns a u64 value read from `self` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than eight bytes are left in `self`.
+    fn read_u64(&mut self) -> Result<u64, DeserializationError> {
+        Ok(u64::from_le_bytes(self.read_u8_array()?))
+    }
+
+    /// Reads a value from `self` using its [Deserializable] implementation.
+    ///
+    /// # Errors
+    /// Returns an error if the value could not be read.
+    fn read<D: Deserializable>(&mut self) -> Result<D, DeserializationError>
+    where
+        Self: Sized,
+    {
+        D::read_from(self)
+    }
+}
+
+/// Implements [ByteReader] for a slice of bytes; bytes are read starting from the beginning of
+/// the slice.
+pub struct SliceReader<'a> {
+    source: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Returns a new reader for the specified `source`.
+    pub fn new(source: &'a [u8]) -> Self {
+        SliceReader { source, pos: 0 }
+    }
+
+    /// Returns the number of bytes read from the source so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'a> ByteReader for SliceReader<'a> {
+    fn read_u8(&mut self) -> Result<u8, DeserializationError> {
+        let result = *self
+            .source
+            .get(self.pos)
+            .ok_or(DeserializationError::UnexpectedEOF)?;
+        self.pos += 1;
+        Ok(result)
+    }
+
+    fn read_u8_vec(&mut self, len: usize) -> Result<Vec<u8>, DeserializationError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .ok_or(DeserializationError::UnexpectedEOF)?;
+        if end > self.source.len() {
+            return Err(DeserializationError::UnexpectedEOF);
+        }
+        let result = self.source[self.pos..end].to_vec();
+        self.pos = end;
+        Ok(result)
+    }
+
+    fn has_more_bytes(&self) -> bool {
+        self.pos < self.source.len()
+    }
+}
+
+// DESERIALIZATION ERROR
+// ================================================================================================
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DeserializationError {
+    #[error("unexpected end of data")]
+    UnexpectedEOF,
+
+    #[error("{0} bytes were left unconsumed after deserialization")]
+    UnconsumedBytes(usize),
+
+    #[error("invalid value: {0}")]
+    InvalidValue(String),
+}
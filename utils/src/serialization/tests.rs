@@ -0,0 +1,71 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+#[test]
+fn primitives_round_trip() {
+    let mut bytes = Vec::new();
+    bytes.write(&true);
+    bytes.write(&7u8);
+    bytes.write(&0x0102u16);
+    bytes.write(&0x01020304u32);
+    bytes.write(&u64::MAX);
+    bytes.write(&(u128::MAX - 1));
+    bytes.write(&[9u8; 32]);
+    assert_eq!(1 + 1 + 2 + 4 + 8 + 16 + 32, bytes.len());
+
+    // integers are written in little-endian byte order
+    assert_eq!([0x02, 0x01], bytes[2..4]);
+
+    let mut source = SliceReader::new(&bytes);
+    assert!(source.read::<bool>().unwrap());
+    assert_eq!(7u8, source.read().unwrap());
+    assert_eq!(0x0102u16, source.read().unwrap());
+    assert_eq!(0x01020304u32, source.read().unwrap());
+    assert_eq!(u64::MAX, source.read().unwrap());
+    assert_eq!(u128::MAX - 1, source.read().unwrap());
+    assert_eq!([9u8; 32], source.read::<[u8; 32]>().unwrap());
+    assert!(!source.has_more_bytes());
+}
+
+#[test]
+fn batch_round_trip() {
+    let values = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+    let mut bytes = Vec::new();
+    <[u8; 32]>::write_batch_into(&values, &mut bytes);
+    assert_eq!(96, bytes.len());
+
+    let mut source = SliceReader::new(&bytes);
+    assert_eq!(values, <[u8; 32]>::read_batch_from(&mut source, 3).unwrap());
+}
+
+#[test]
+fn deserialization_errors() {
+    // not enough bytes
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        u32::read_from_bytes(&[1, 2, 3])
+    );
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        <[u8; 32]>::read_from_bytes(&[0; 31])
+    );
+
+    // too many bytes
+    assert_eq!(
+        Err(DeserializationError::UnconsumedBytes(2)),
+        u16::read_from_bytes(&[1, 2, 3, 4])
+    );
+
+    // invalid value
+    assert!(matches!(
+        bool::read_from_bytes(&[2]),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+    assert_eq!(Ok(()), <()>::read_from_bytes(&[]));
+}
@@ -0,0 +1,928 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::channel::build_query_seed;
+use common::{
+    build_constraint_seed, build_fri_seed,
+    errors::VerifierError,
+    proof::{Commitments, StarkProof},
+    utils, Air, CompositionCoefficients, ComputationContext, ExtensionVisitor, HashFunction,
+    HashProofOfWork, PublicCoin,
+};
+use crypto::{
+    hash::{Blake3_256, Sha3_256},
+    DefaultRandomElementGenerator, Hasher,
+};
+use fri::{utils::map_positions_to_indexes, PublicCoin as FriPublicCoin};
+use math::field::{ByteEncoding, ExtensionOf, FieldElement, StarkField};
+use std::{fmt::Write, marker::PhantomData};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of evaluations folded into a single value at each FRI layer.
+const FRI_FOLDING_FACTOR: usize = 4;
+
+// QUERY TRANSCRIPT
+// ================================================================================================
+
+/// Query positions derived from a proof, together with the commitments against which the
+/// prover's answers to each query are authenticated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryTranscript {
+    /// Seed from which query positions are drawn; this is derived from FRI layer commitments and
+    /// the proof-of-work nonce included in the proof.
+    pub query_seed: [u8; 32],
+    /// Queries in the order in which their positions were drawn from the query seed.
+    pub queries: Vec<QueryRecord>,
+    /// Queries against FRI layers only; these are drawn after trace queries when the proof
+    /// options specify more FRI queries than trace queries, and are empty otherwise.
+    pub fri_queries: Vec<FriQueryRecord>,
+}
+
+/// Describes a single query against the LDE domain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryRecord {
+    /// Position of the query in the LDE domain.
+    pub position: usize,
+    /// Leaf of the trace commitment which contains the trace row at the queried position.
+    pub trace: CommitmentOpening,
+    /// Leaf of the commitment to extra polynomials which contains their evaluations at the
+    /// queried position; this is None when the proof contains no extra polynomials.
+    pub extra: Option<CommitmentOpening>,
+    /// Leaf of the auxiliary segment commitment which contains the auxiliary trace row at the
+    /// queried position; this is None when the computation has no auxiliary trace segment.
+    pub aux_segment: Option<CommitmentOpening>,
+    /// Leaf of the constraint commitment which contains the constraint evaluation at the
+    /// queried position.
+    pub constraints: CommitmentOpening,
+    /// Leaves of FRI layer commitments which contain the values folded from the queried
+    /// position, one per FRI layer; the FRI remainder is included in the proof in full, and
+    /// thus, has no per-query opening.
+    pub fri_layers: Vec<CommitmentOpening>,
+}
+
+/// Describes a single query against FRI layers which is not accompanied by trace and constraint
+/// openings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FriQueryRecord {
+    /// Position of the query in the LDE domain.
+    pub position: usize,
+    /// Leaves of FRI layer commitments which contain the values folded from the queried
+    /// position, one per FRI layer.
+    pub fri_layers: Vec<CommitmentOpening>,
+}
+
+/// A leaf of a Merkle tree commitment which the prover must open to answer a query.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommitmentOpening {
+    /// Root of the Merkle tree.
+    pub root: [u8; 32],
+    /// Index of the leaf in the Merkle tree.
+    pub leaf_index: usize,
+}
+
+// QUERY DERIVATION
+// ================================================================================================
+
+/// Recomputes the query positions for the specified `proof` of a computation described by the
+/// `context`, and returns them together with the commitment leaves which must be opened for
+/// each position.
+///
+/// This repeats the transcript derivation performed by the verifier, but does not check any
+/// of the openings included in the proof; thus, it can be used by external auditors and
+/// alternative verifier implementations to cross-check derivation of query positions
+/// independently of the rest of the verification procedure.
+///
+/// The proof is assumed to be generated using the default [HashProofOfWork] scheme.
+///
+/// # Errors
+/// Returns an error if proof-of-work included in the proof is not valid.
+pub fn derive_queries<B: StarkField>(
+    proof: &StarkProof,
+    context: &ComputationContext,
+) -> Result<QueryTranscript, VerifierError> {
+    let field_extension = context.options().field_extension();
+    field_extension.visit(QueryDeriver::<B> {
+        proof,
+        context,
+        _base: PhantomData,
+    })
+}
+
+/// Arguments of [derive_queries()]; queries are derived in the field selected by the field
+/// extension specified in proof options.
+struct QueryDeriver<'a, B: StarkField> {
+    proof: &'a StarkProof,
+    context: &'a ComputationContext,
+    _base: PhantomData<B>,
+}
+
+impl<B: StarkField> ExtensionVisitor<B> for QueryDeriver<'_, B> {
+    type Output = Result<QueryTranscript, VerifierError>;
+
+    fn visit<E: ExtensionOf<B>>(self) -> Self::Output {
+        let (proof, context) = (self.proof, self.context);
+        match context.options().hash_fn() {
+            HashFunction::Blake3_256 => derive_queries_with::<B, E, Blake3_256>(proof, context),
+            HashFunction::Sha3_256 => derive_queries_with::<B, E, Sha3_256>(proof, context),
+        }
+    }
+}
+
+/// Derives queries for a proof in which constraint evaluations are elements of field `E`, and
+/// commitments are built using hash function `H`.
+fn derive_queries_with<B, E, H>(
+    proof: &StarkProof,
+    context: &ComputationContext,
+) -> Result<QueryTranscript, VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    H: Hasher,
+{
+    match context.options().transcript_hash_fn() {
+        HashFunction::Blake3_256 => {
+            derive_queries_with_hashes::<B, E, H, Blake3_256>(proof, context)
+        }
+        HashFunction::Sha3_256 => derive_queries_with_hashes::<B, E, H, Sha3_256>(proof, context),
+    }
+}
+
+/// Derives queries for a proof in which constraint evaluations are elements of field `E`,
+/// commitments are built using hash function `H`, and the transcript is derived using hash
+/// function `T`.
+fn derive_queries_with_hashes<B, E, H, T>(
+    proof: &StarkProof,
+    context: &ComputationContext,
+) -> Result<QueryTranscript, VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    H: Hasher,
+    T: Hasher,
+{
+    let commitments = &proof.commitments;
+    let query_seed = build_query_seed::<B, T>(
+        &commitments.fri_roots,
+        &proof.fri_proof.rem_values,
+        proof.pow_nonce,
+        proof.pow_difficulty,
+        context,
+        &HashProofOfWork,
+    )?;
+    let coin = ProofCoin::<T>::new(context, commitments, query_seed);
+    let positions = coin.draw_fri_query_positions();
+    let (positions, fri_positions) = positions.split_at(context.options().num_queries());
+
+    // determine how query positions map to commitment leaves
+    let lde_domain_size = context.lde_domain_size();
+    let rows_per_leaf = context.options().trace_leaf_batching();
+    let evaluations_per_leaf = utils::evaluations_per_leaf::<E, H>();
+    let num_fri_layers = context
+        .options()
+        .to_fri_options::<B>()
+        .num_fri_layers(lde_domain_size);
+    let num_fri_partitions = if proof.fri_proof.partitioned {
+        let encoding = context.options().byte_encoding();
+        proof.fri_proof.rem_values.len() / encoding.element_bytes::<E>()
+    } else {
+        1
+    };
+
+    // each FRI layer folds the position of the previous layer into a domain which is smaller
+    // by the folding factor
+    let open_fri_layers = |position: usize| {
+        let mut fri_layers = Vec::with_capacity(num_fri_layers);
+        let mut domain_size = lde_domain_size;
+        let mut layer_position = position;
+        for &root in commitments.fri_roots.iter().take(num_fri_layers) {
+            layer_position %= domain_size / FRI_FOLDING_FACTOR;
+            let leaf_index = map_positions_to_indexes(
+                &[layer_position],
+                domain_size,
+                FRI_FOLDING_FACTOR,
+                num_fri_partitions,
+            )[0];
+            fri_layers.push(CommitmentOpening { root, leaf_index });
+            domain_size /= FRI_FOLDING_FACTOR;
+        }
+        fri_layers
+    };
+
+    let queries = positions
+        .iter()
+        .map(|&position| QueryRecord {
+            position,
+            trace: CommitmentOpening {
+                root: commitments.trace_root,
+                leaf_index: utils::get_leaf_index(position, rows_per_leaf),
+            },
+            extra: commitments.extra_root.map(|root| CommitmentOpening {
+                root,
+                leaf_index: utils::get_leaf_index(position, rows_per_leaf),
+            }),
+            aux_segment: commitments.aux_segment_root.map(|root| CommitmentOpening {
+                root,
+                leaf_index: utils::get_leaf_index(position, rows_per_leaf),
+            }),
+            constraints: CommitmentOpening {
+                root: commitments.constraint_root,
+                leaf_index: utils::get_leaf_index(position, evaluations_per_leaf),
+            },
+            fri_layers: open_fri_layers(position),
+        })
+        .collect();
+
+    let fri_queries = fri_positions
+        .iter()
+        .map(|&position| FriQueryRecord {
+            position,
+            fri_layers: open_fri_layers(position),
+        })
+        .collect();
+
+    Ok(QueryTranscript {
+        query_seed,
+        queries,
+        fri_queries,
+    })
+}
+
+// ROOT RECOMPUTATION
+// ================================================================================================
+
+/// Roots of trace and constraint commitments recomputed from the data opened in a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecomputedRoots {
+    /// Root of the trace commitment to which the queried trace rows and their authentication
+    /// paths resolve.
+    pub trace_root: [u8; 32],
+    /// Root of the commitment to extra polynomials to which their queried evaluations and
+    /// authentication paths resolve; this is None when the proof contains no extra polynomials.
+    pub extra_root: Option<[u8; 32]>,
+    /// Root of the auxiliary segment commitment to which the queried auxiliary trace rows and
+    /// authentication paths resolve; this is None when the computation has no auxiliary segment.
+    pub aux_segment_root: Option<[u8; 32]>,
+    /// Root of the constraint commitment to which the queried constraint evaluations and their
+    /// authentication paths resolve.
+    pub constraint_root: [u8; 32],
+}
+
+/// Recomputes roots of trace and constraint commitments from the queried values and
+/// authentication paths included in the specified `proof` of a computation described by the
+/// `context`.
+///
+/// The recomputed roots are not compared against the commitments included in the proof. This
+/// allows relying parties to check the openings against roots stored elsewhere (e.g., roots
+/// previously anchored on-chain) in split verification flows, where commitments are checked in
+/// a different system than the rest of the proof. As with [derive_queries()], the proof is
+/// assumed to be generated using the default [HashProofOfWork] scheme.
+///
+/// # Errors
+/// Returns an error if:
+/// * proof-of-work included in the proof is not valid;
+/// * queried values or authentication paths included in the proof are malformed, and thus, do
+///   not resolve to a root.
+pub fn recompute_commitment_roots<B: StarkField>(
+    proof: &StarkProof,
+    context: &ComputationContext,
+) -> Result<RecomputedRoots, VerifierError> {
+    let field_extension = context.options().field_extension();
+    field_extension.visit(RootRecomputer::<B> {
+        proof,
+        context,
+        _base: PhantomData,
+    })
+}
+
+/// Arguments of [recompute_commitment_roots()]; roots are recomputed in the field selected by the
+/// field extension specified in proof options.
+struct RootRecomputer<'a, B: StarkField> {
+    proof: &'a StarkProof,
+    context: &'a ComputationContext,
+    _base: PhantomData<B>,
+}
+
+impl<B: StarkField> ExtensionVisitor<B> for RootRecomputer<'_, B> {
+    type Output = Result<RecomputedRoots, VerifierError>;
+
+    fn visit<E: ExtensionOf<B>>(self) -> Self::Output {
+        let (proof, context) = (self.proof, self.context);
+        match context.options().hash_fn() {
+            HashFunction::Blake3_256 => recompute_roots_with::<B, E, Blake3_256>(proof, context),
+            HashFunction::Sha3_256 => recompute_roots_with::<B, E, Sha3_256>(proof, context),
+        }
+    }
+}
+
+/// Recomputes commitment roots for a proof in which constraint evaluations are elements of
+/// field `E`, and commitments are built using hash function `H`.
+fn recompute_roots_with<B, E, H>(
+    proof: &StarkProof,
+    context: &ComputationContext,
+) -> Result<RecomputedRoots, VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    H: Hasher,
+{
+    let transcript = derive_queries_with::<B, E, H>(proof, context)?;
+    let positions = transcript
+        .queries
+        .iter()
+        .map(|query| query.position)
+        .collect::<Vec<_>>();
+    let lde_domain_size = context.lde_domain_size();
+
+    // recompute the root of the trace commitment
+    let encoding = context.options().byte_encoding();
+    let rows_per_leaf = context.options().trace_leaf_batching();
+    let trace_leaves = utils::map_trace_positions_to_leaves(&positions, rows_per_leaf);
+    let trace_queries = if context.options().compact_trace_queries() {
+        proof
+            .trace_queries
+            .clone()
+            .expand::<B>(context.main_trace_width() * rows_per_leaf, encoding)
+            .map_err(|_| VerifierError::TraceQueryDeserializationFailed)?
+    } else {
+        proof.trace_queries.clone()
+    };
+    let (trace_proof, _) = trace_queries.into_trace_batch::<B, H>(
+        lde_domain_size / rows_per_leaf,
+        context.options().trace_row_hashing(),
+        context.main_trace_width(),
+        encoding,
+    );
+    let trace_root = trace_proof
+        .get_root(&trace_leaves, H::hash_fn())
+        .ok_or(VerifierError::TraceQueryDeserializationFailed)?;
+
+    // recompute the root of the commitment to extra polynomials; these are committed to in the
+    // same way as the execution trace
+    let extra_root = match &proof.extra_queries {
+        Some(extra_queries) => {
+            let num_extra_polys = context.num_extra_polys();
+            let extra_queries = if context.options().compact_trace_queries() {
+                extra_queries
+                    .clone()
+                    .expand::<B>(num_extra_polys * rows_per_leaf, encoding)
+                    .map_err(|_| VerifierError::ExtraQueryDeserializationFailed)?
+            } else {
+                extra_queries.clone()
+            };
+            let (extra_proof, _) = extra_queries.into_trace_batch::<B, H>(
+                lde_domain_size / rows_per_leaf,
+                context.options().trace_row_hashing(),
+                num_extra_polys,
+                encoding,
+            );
+            let extra_root = extra_proof
+                .get_root(&trace_leaves, H::hash_fn())
+                .ok_or(VerifierError::ExtraQueryDeserializationFailed)?;
+            Some(extra_root)
+        }
+        None => None,
+    };
+
+    // recompute the root of the auxiliary segment commitment; the auxiliary segment is also
+    // committed to in the same way as the execution trace
+    let aux_segment_root = match &proof.aux_segment_queries {
+        Some(aux_queries) => {
+            let aux_width = context.aux_segment_width();
+            let aux_queries = if context.options().compact_trace_queries() {
+                aux_queries
+                    .clone()
+                    .expand::<B>(aux_width * rows_per_leaf, encoding)
+                    .map_err(|_| VerifierError::AuxSegmentQueryDeserializationFailed)?
+            } else {
+                aux_queries.clone()
+            };
+            let (aux_proof, _) = aux_queries.into_trace_batch::<B, H>(
+                lde_domain_size / rows_per_leaf,
+                context.options().trace_row_hashing(),
+                aux_width,
+                encoding,
+            );
+            let aux_root = aux_proof
+                .get_root(&trace_leaves, H::hash_fn())
+                .ok_or(VerifierError::AuxSegmentQueryDeserializationFailed)?;
+            Some(aux_root)
+        }
+        None => None,
+    };
+
+    // recompute the root of the constraint commitment
+    let evaluations_per_leaf = utils::evaluations_per_leaf::<E, H>();
+    let constraint_leaves =
+        utils::map_trace_to_constraint_positions(&positions, evaluations_per_leaf);
+    let (constraint_proof, _) = proof
+        .constraint_queries
+        .clone()
+        .into_batch::<H>(lde_domain_size / evaluations_per_leaf);
+    let constraint_root = constraint_proof
+        .get_root(&constraint_leaves, H::hash_fn())
+        .ok_or(VerifierError::ConstraintQueryDeserializationFailed)?;
+
+    Ok(RecomputedRoots {
+        trace_root,
+        extra_root,
+        aux_segment_root,
+        constraint_root,
+    })
+}
+
+// CHALLENGE EXTRACTION
+// ================================================================================================
+
+/// Challenges which the verifier derives from commitments included in a proof.
+#[derive(Debug)]
+pub struct Challenges<E: FieldElement> {
+    /// Out-of-domain point z at which trace polynomials and the constraint composition
+    /// polynomial are evaluated.
+    pub ood_point: E,
+    /// Coefficients used to combine trace and constraint polynomials into the DEEP composition
+    /// polynomial.
+    pub composition_coefficients: CompositionCoefficients<E>,
+    /// Coefficients used to fold evaluations of the DEEP composition polynomial, one per FRI
+    /// layer; the FRI remainder is not folded, and thus, has no coefficient.
+    pub fri_alphas: Vec<E>,
+    /// Seed from which query positions are drawn.
+    pub query_seed: [u8; 32],
+}
+
+/// Recomputes the challenges derived by the verifier for the specified `proof` of a computation
+/// described by the `context`.
+///
+/// Challenges are returned as elements of field `E`, which must be the field in which the proof
+/// was generated: the base field `B` when field extension is disabled, or its extension
+/// otherwise. As with [derive_queries()], none of the openings included in the proof are
+/// checked, and the proof is assumed to be generated using the default [HashProofOfWork]
+/// scheme. This allows outer protocols, in which the STARK is a sub-protocol, to bind to
+/// challenges of the STARK transcript.
+///
+/// # Errors
+/// Returns an error if:
+/// * `E` is not the field in which the proof was generated;
+/// * proof-of-work included in the proof is not valid.
+pub fn extract_challenges<B, E>(
+    proof: &StarkProof,
+    context: &ComputationContext,
+) -> Result<Challenges<E>, VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+{
+    let element_bytes = B::ELEMENT_BYTES * context.options().field_extension().degree();
+    if E::ELEMENT_BYTES != element_bytes {
+        return Err(VerifierError::ChallengeFieldMismatch(
+            E::ELEMENT_BYTES,
+            element_bytes,
+        ));
+    }
+
+    match context.options().transcript_hash_fn() {
+        HashFunction::Blake3_256 => extract_challenges_with::<B, E, Blake3_256>(proof, context),
+        HashFunction::Sha3_256 => extract_challenges_with::<B, E, Sha3_256>(proof, context),
+    }
+}
+
+/// Extracts challenges for a proof in which the transcript is derived using hash function `T`.
+fn extract_challenges_with<B, E, T>(
+    proof: &StarkProof,
+    context: &ComputationContext,
+) -> Result<Challenges<E>, VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    T: Hasher,
+{
+    let commitments = &proof.commitments;
+    let query_seed = build_query_seed::<B, T>(
+        &commitments.fri_roots,
+        &proof.fri_proof.rem_values,
+        proof.pow_nonce,
+        proof.pow_difficulty,
+        context,
+        &HashProofOfWork,
+    )?;
+    let coin = ProofCoin::<T>::new(context, commitments, query_seed);
+
+    let num_fri_layers = context
+        .options()
+        .to_fri_options::<B>()
+        .num_fri_layers(context.lde_domain_size());
+
+    Ok(Challenges {
+        ood_point: coin.draw_deep_point(),
+        composition_coefficients: coin.draw_composition_coefficients(),
+        fri_alphas: (0..num_fri_layers)
+            .map(|i| coin.draw_fri_alpha(i))
+            .collect(),
+        query_seed,
+    })
+}
+
+// TRANSCRIPT EXPORT
+// ================================================================================================
+
+/// Transcript of the challenges which the verifier derives from a proof, listed in the order in
+/// which the verifier derives them.
+///
+/// The transcript is intended to be used as a test vector: an alternative verifier
+/// implementation can replay the same derivation for the same proof and compare its results
+/// against the transcript byte-for-byte.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptVector {
+    /// Steps of the transcript derivation.
+    pub steps: Vec<TranscriptStep>,
+}
+
+/// A single step of the transcript derivation.
+///
+/// At every step the verifier absorbs data included in the proof, and then squeezes one or more
+/// challenges out of it together with the values squeezed at the previous steps. Field elements
+/// are encoded using their canonical little-endian byte representation, and integers are
+/// encoded as 8-byte little-endian values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptStep {
+    /// Name of the step.
+    pub label: String,
+    /// Data absorbed at this step; this is empty for steps which only squeeze more values out
+    /// of the data absorbed previously.
+    pub absorbed: Vec<u8>,
+    /// Values squeezed at this step.
+    pub squeezed: Vec<Vec<u8>>,
+}
+
+impl TranscriptVector {
+    /// Returns this transcript encoded as a JSON document, with all byte strings encoded as
+    /// lowercase hex.
+    pub fn to_json(&self) -> String {
+        let mut result = String::from("{\n  \"steps\": [\n");
+        for (i, step) in self.steps.iter().enumerate() {
+            let squeezed = step
+                .squeezed
+                .iter()
+                .map(|value| format!("\"{}\"", to_hex(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(
+                result,
+                "    {{ \"label\": \"{}\", \"absorbed\": \"{}\", \"squeezed\": [{}] }}",
+                step.label,
+                to_hex(&step.absorbed),
+                squeezed
+            )
+            .unwrap();
+            result.push_str(if i + 1 < self.steps.len() {
+                ",\n"
+            } else {
+                "\n"
+            });
+        }
+        result.push_str("  ]\n}\n");
+        result
+    }
+}
+
+/// Replays the transcript derivation performed by the verifier for the specified `proof` of a
+/// computation described by the `air`, and returns the data absorbed and the challenges
+/// squeezed at every step.
+///
+/// The transcript consists of the following steps:
+/// * `aux_rand_elements`: present only for computations with an auxiliary trace segment;
+///   absorbs the trace root followed by roots of auxiliary commitments, and squeezes the random
+///   elements from which the auxiliary segment is built.
+/// * `constraint_seed`: absorbs the trace root followed by roots of auxiliary commitments and
+///   the root of the auxiliary segment commitment, and squeezes the seed for constraint
+///   composition coefficients.
+/// * `transition_coefficients`: squeezes a pair of coefficients for every transition
+///   constraint, in the order of constraint indexes.
+/// * `boundary_coefficients`: squeezes a pair of coefficients for every boundary constraint,
+///   in the order of boundary constraint groups returned by the AIR.
+/// * `ood_point`: absorbs the constraint root, and squeezes the out-of-domain point z.
+/// * `composition_coefficients`: squeezes the DEEP composition coefficients: a triple per trace
+///   register, followed by a pair for trace degree adjustment and a single coefficient for
+///   constraints.
+/// * `fri_alpha_{i}`: absorbs the root of FRI layer i, and squeezes its folding coefficient.
+/// * `fri_seed`: absorbs roots of all FRI layers, the FRI remainder, and the remainder degree
+///   bound, and squeezes the seed to which proof-of-work is applied.
+/// * `query_seed`: absorbs the proof-of-work nonce, and squeezes the query seed.
+/// * `query_positions`: squeezes positions of all queries, including FRI-only queries.
+///
+/// As with [derive_queries()], none of the openings included in the proof are checked, and the
+/// proof is assumed to be generated using the default [HashProofOfWork] scheme.
+///
+/// # Errors
+/// Returns an error if proof-of-work included in the proof is not valid.
+pub fn export_transcript<A: Air>(
+    proof: &StarkProof,
+    air: &A,
+) -> Result<TranscriptVector, VerifierError> {
+    let field_extension = air.context().options().field_extension();
+    field_extension.visit(TranscriptExporter { proof, air })
+}
+
+/// Arguments of [export_transcript()]; the transcript is exported in the field selected by the
+/// field extension specified in proof options.
+struct TranscriptExporter<'a, A: Air> {
+    proof: &'a StarkProof,
+    air: &'a A,
+}
+
+impl<A: Air> ExtensionVisitor<A::BaseElement> for TranscriptExporter<'_, A> {
+    type Output = Result<TranscriptVector, VerifierError>;
+
+    fn visit<E: ExtensionOf<A::BaseElement>>(self) -> Self::Output {
+        let (proof, air) = (self.proof, self.air);
+        match air.context().options().transcript_hash_fn() {
+            HashFunction::Blake3_256 => export_transcript_with::<A, E, Blake3_256>(proof, air),
+            HashFunction::Sha3_256 => export_transcript_with::<A, E, Sha3_256>(proof, air),
+        }
+    }
+}
+
+/// Exports the transcript for a proof in which challenges are elements of field `E`, and the
+/// transcript is derived using hash function `T`.
+fn export_transcript_with<A, E, T>(
+    proof: &StarkProof,
+    air: &A,
+) -> Result<TranscriptVector, VerifierError>
+where
+    A: Air,
+    E: FieldElement + From<A::BaseElement>,
+    T: Hasher,
+{
+    let context = air.context();
+    let commitments = &proof.commitments;
+    let mut steps = Vec::new();
+
+    // the query seed is not needed to draw any of the challenges before it; it is set once
+    // proof-of-work is verified below
+    let mut coin = ProofCoin::<T>::new(context, commitments, [0; 32]);
+    let air_id = context.air_id();
+
+    // --- auxiliary segment ----------------------------------------------------------------------
+    if context.aux_segment_width() > 0 {
+        let mut absorbed = air_id.map_or(Vec::new(), |air_id| air_id.to_vec());
+        absorbed.extend_from_slice(&commitments.trace_root);
+        for root in commitments.aux_roots.iter() {
+            absorbed.extend_from_slice(root);
+        }
+        if let Some(extra_root) = commitments.extra_root {
+            absorbed.extend_from_slice(&extra_root);
+        }
+        let squeezed = coin
+            .draw_aux_rand_elements::<A::BaseElement>()
+            .into_iter()
+            .map(FieldElement::to_canonical_bytes)
+            .collect();
+        steps.push(TranscriptStep::new("aux_rand_elements", absorbed, squeezed));
+    }
+
+    // --- constraint composition coefficients ----------------------------------------------------
+    let mut absorbed = air_id.map_or(Vec::new(), |air_id| air_id.to_vec());
+    absorbed.extend_from_slice(&commitments.trace_root);
+    for root in commitments.aux_roots.iter() {
+        absorbed.extend_from_slice(root);
+    }
+    if let Some(extra_root) = commitments.extra_root {
+        absorbed.extend_from_slice(&extra_root);
+    }
+    if let Some(aux_segment_root) = commitments.aux_segment_root {
+        absorbed.extend_from_slice(&aux_segment_root);
+    }
+    let constraint_seed = build_constraint_seed::<T>(
+        &commitments.trace_root,
+        &commitments.aux_roots,
+        commitments.extra_root.as_ref(),
+        commitments.aux_segment_root.as_ref(),
+        air_id.as_ref(),
+    );
+    steps.push(TranscriptStep::new(
+        "constraint_seed",
+        absorbed,
+        vec![constraint_seed.to_vec()],
+    ));
+
+    // transition coefficients are listed in the order of constraint indexes, regardless of
+    // how constraints are grouped
+    let transition_groups =
+        air.get_transition_constraints::<E, _>(coin.get_transition_coefficient_prng());
+    let mut transition_cc = transition_groups
+        .iter()
+        .flat_map(|group| group.indexes().iter().zip(group.coefficients().iter()))
+        .collect::<Vec<_>>();
+    transition_cc.sort_by_key(|&(&i, _)| i);
+    let squeezed = transition_cc
+        .into_iter()
+        .flat_map(|(_, &cc)| pair_to_bytes(cc))
+        .collect();
+    steps.push(TranscriptStep::new(
+        "transition_coefficients",
+        vec![],
+        squeezed,
+    ));
+
+    let boundary_groups =
+        air.get_boundary_constraints::<E, _>(coin.get_boundary_coefficient_prng());
+    let squeezed = boundary_groups
+        .iter()
+        .flat_map(|group| group.constraints().iter())
+        .flat_map(|constraint| pair_to_bytes(*constraint.cc()))
+        .collect();
+    steps.push(TranscriptStep::new(
+        "boundary_coefficients",
+        vec![],
+        squeezed,
+    ));
+
+    // --- DEEP composition -----------------------------------------------------------------------
+    let z = coin.draw_deep_point::<E>();
+    let absorbed = commitments.constraint_root.to_vec();
+    steps.push(TranscriptStep::new(
+        "ood_point",
+        absorbed,
+        vec![z.to_canonical_bytes()],
+    ));
+
+    let cc = coin.draw_composition_coefficients::<E>();
+    let mut squeezed = Vec::new();
+    for &(c1, c2, c3) in cc.trace.iter() {
+        squeezed.extend_from_slice(&[c1, c2, c3]);
+    }
+    squeezed.extend_from_slice(&[cc.trace_degree.0, cc.trace_degree.1, cc.constraints]);
+    for &(c1, c2) in cc.extra.iter() {
+        squeezed.extend_from_slice(&[c1, c2]);
+    }
+    let squeezed = squeezed.into_iter().map(E::to_canonical_bytes).collect();
+    steps.push(TranscriptStep::new(
+        "composition_coefficients",
+        vec![],
+        squeezed,
+    ));
+
+    // --- FRI layers -----------------------------------------------------------------------------
+    let num_fri_layers = context
+        .options()
+        .to_fri_options::<A::BaseElement>()
+        .num_fri_layers(context.lde_domain_size());
+    for (i, root) in commitments
+        .fri_roots
+        .iter()
+        .take(num_fri_layers)
+        .enumerate()
+    {
+        let alpha = coin.draw_fri_alpha::<E>(i);
+        let label = format!("fri_alpha_{}", i);
+        steps.push(TranscriptStep::new(
+            &label,
+            root.to_vec(),
+            vec![alpha.to_canonical_bytes()],
+        ));
+    }
+
+    // --- query positions ------------------------------------------------------------------------
+    let remainder = &proof.fri_proof.rem_values;
+    let degree_bound = context.fri_remainder_degree_bound::<A::BaseElement>();
+    let encoding = context.options().byte_encoding();
+    let fri_seed = build_fri_seed::<T>(&commitments.fri_roots, remainder, degree_bound, encoding);
+    let mut absorbed = commitments.fri_roots.concat();
+    absorbed.extend_from_slice(remainder);
+    encoding.write_u64(degree_bound as u64, &mut absorbed);
+    steps.push(TranscriptStep::new(
+        "fri_seed",
+        absorbed,
+        vec![fri_seed.to_vec()],
+    ));
+
+    let query_seed = build_query_seed::<A::BaseElement, T>(
+        &commitments.fri_roots,
+        remainder,
+        proof.pow_nonce,
+        proof.pow_difficulty,
+        context,
+        &HashProofOfWork,
+    )?;
+    let absorbed = encoding.encode_u64(proof.pow_nonce).to_vec();
+    steps.push(TranscriptStep::new(
+        "query_seed",
+        absorbed,
+        vec![query_seed.to_vec()],
+    ));
+
+    coin.query_seed = query_seed;
+    let squeezed = coin
+        .draw_fri_query_positions()
+        .into_iter()
+        .map(|position| encoding.encode_u64(position as u64).to_vec())
+        .collect();
+    steps.push(TranscriptStep::new("query_positions", vec![], squeezed));
+
+    Ok(TranscriptVector { steps })
+}
+
+impl TranscriptStep {
+    fn new(label: &str, absorbed: Vec<u8>, squeezed: Vec<Vec<u8>>) -> Self {
+        TranscriptStep {
+            label: label.to_string(),
+            absorbed,
+            squeezed,
+        }
+    }
+}
+
+// PROOF COIN
+// ================================================================================================
+
+/// Public coin which derives all seeds from the commitments included in a proof using the
+/// transcript hash function `T`.
+struct ProofCoin<'a, T: Hasher> {
+    context: &'a ComputationContext,
+    commitments: &'a Commitments,
+    query_seed: [u8; 32],
+    _hasher: PhantomData<T>,
+}
+
+impl<'a, T: Hasher> ProofCoin<'a, T> {
+    fn new(
+        context: &'a ComputationContext,
+        commitments: &'a Commitments,
+        query_seed: [u8; 32],
+    ) -> Self {
+        ProofCoin {
+            context,
+            commitments,
+            query_seed,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Hasher> PublicCoin for ProofCoin<'a, T> {
+    type Hasher = T;
+
+    fn context(&self) -> &ComputationContext {
+        self.context
+    }
+
+    fn aux_segment_seed(&self) -> [u8; 32] {
+        build_constraint_seed::<T>(
+            &self.commitments.trace_root,
+            &self.commitments.aux_roots,
+            self.commitments.extra_root.as_ref(),
+            None,
+            self.context.air_id().as_ref(),
+        )
+    }
+
+    fn constraint_seed(&self) -> [u8; 32] {
+        build_constraint_seed::<T>(
+            &self.commitments.trace_root,
+            &self.commitments.aux_roots,
+            self.commitments.extra_root.as_ref(),
+            self.commitments.aux_segment_root.as_ref(),
+            self.context.air_id().as_ref(),
+        )
+    }
+
+    fn composition_seed(&self) -> [u8; 32] {
+        self.commitments.constraint_root
+    }
+
+    fn query_seed(&self) -> [u8; 32] {
+        self.query_seed
+    }
+}
+
+impl<'a, T: Hasher> FriPublicCoin for ProofCoin<'a, T> {
+    type RandomElementGenerator = DefaultRandomElementGenerator<T>;
+
+    fn fri_layer_commitments(&self) -> &[[u8; 32]] {
+        &self.commitments.fri_roots
+    }
+
+    fn byte_encoding(&self) -> ByteEncoding {
+        self.context.options().byte_encoding()
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns canonical encodings of both coefficients in the pair.
+fn pair_to_bytes<E: FieldElement>(cc: (E, E)) -> Vec<Vec<u8>> {
+    vec![cc.0.to_canonical_bytes(), cc.1.to_canonical_bytes()]
+}
+
+/// Encodes the `bytes` as a lowercase hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(result, "{:02x}", byte).unwrap();
+    }
+    result
+}
@@ -3,18 +3,16 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::scratch;
 use common::{
-    errors::VerifierError,
-    proof::{Commitments, OodEvaluationFrame, Queries, StarkProof},
-    utils, Air, ComputationContext, EvaluationFrame, ProofOptions, PublicCoin,
+    build_constraint_seed, build_fri_seed,
+    errors::{ProofShapeError, VerifierError},
+    proof::{Commitments, OodEvaluationFrame, StarkProof},
+    utils, Air, ComputationContext, EvaluationFrame, ProofOfWork, PublicCoin,
 };
 use crypto::{BatchMerkleProof, DefaultRandomElementGenerator, Hasher, MerkleTree};
 use fri::{PublicCoin as FriPublicCoin, VerifierChannel as FriVerifierChannel};
-use math::{
-    field::{FieldElement, StarkField},
-    utils::read_elements_into_vec,
-};
-use std::convert::TryInto;
+use math::field::{ByteEncoding, FieldElement, StarkField};
 use std::marker::PhantomData;
 
 // TYPES AND INTERFACES
@@ -22,11 +20,22 @@ use std::marker::PhantomData;
 
 type Bytes = Vec<u8>;
 
-pub struct VerifierChannel<B: StarkField, E: FieldElement + From<B>, H: Hasher> {
+/// Channel through which the verifier reads commitments and openings from a proof, and draws
+/// randomness. Commitments are verified using hash function `H`, while seeds and pseudo-random
+/// values of the Fiat-Shamir transcript are derived using hash function `T`.
+pub struct VerifierChannel<B: StarkField, E: FieldElement + From<B>, H: Hasher, T: Hasher> {
     context: ComputationContext,
     commitments: Commitments,
-    trace_queries: Queries,
-    constraint_queries: Queries,
+    column_layout: Vec<usize>,
+    trace_proof: BatchMerkleProof,
+    trace_values: Vec<Bytes>,
+    constraint_proof: BatchMerkleProof,
+    constraint_values: Vec<Bytes>,
+    extra_proof: Option<BatchMerkleProof>,
+    extra_values: Vec<Bytes>,
+    aux_segment_proof: Option<BatchMerkleProof>,
+    aux_segment_values: Vec<Bytes>,
+    aux_segment_layout: Vec<usize>,
     ood_frame: OodEvaluationFrame,
     fri_layer_proofs: Vec<BatchMerkleProof>,
     fri_layer_queries: Vec<Vec<Bytes>>,
@@ -36,21 +45,159 @@ pub struct VerifierChannel<B: StarkField, E: FieldElement + From<B>, H: Hasher>
     _base_element: PhantomData<B>,
     _extension_element: PhantomData<E>,
     _hasher: PhantomData<H>,
+    _transcript_hasher: PhantomData<T>,
 }
 
 // VERIFIER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<B, E, H> VerifierChannel<B, E, H>
+impl<B, E, H, T> VerifierChannel<B, E, H, T>
 where
     B: StarkField,
     E: FieldElement + From<B>,
     H: Hasher,
+    T: Hasher,
 {
-    /// Creates and returns a new verifier channel initialized from the specified `proof`.
-    pub fn new<A: Air<BaseElement = B>>(air: &A, proof: StarkProof) -> Result<Self, VerifierError> {
-        // TODO: validate field modulus
-        // TODO: verify ce blowup factor
+    /// Creates and returns a new verifier channel initialized from the specified `proof`; the
+    /// query seed is derived using the specified proof-of-work scheme `pow`.
+    pub fn new<A: Air<BaseElement = B>>(
+        air: &A,
+        proof: StarkProof,
+        pow: &dyn ProofOfWork,
+    ) -> Result<Self, VerifierError> {
+        // --- make sure the proof was generated for this computation -----------------------------
+        if proof.context.field_modulus_bytes != B::get_modulus_le_bytes() {
+            return Err(ProofShapeError::FieldModulusMismatch.into());
+        }
+        let ce_blowup_factor = proof.context.ce_blowup_factor as usize;
+        if ce_blowup_factor != air.ce_blowup_factor() {
+            return Err(ProofShapeError::CeBlowupFactorMismatch(
+                ce_blowup_factor,
+                air.ce_blowup_factor(),
+            )
+            .into());
+        }
+        let num_constraints = air.num_constraints();
+        if proof.context.num_constraints as usize != num_constraints {
+            return Err(ProofShapeError::NumConstraintsMismatch(
+                proof.context.num_constraints as usize,
+                num_constraints,
+            )
+            .into());
+        }
+        if proof.context.air_id != air.context().air_id() {
+            return Err(ProofShapeError::AirIdMismatch.into());
+        }
+        let num_extra_polys = air.context().num_extra_polys();
+        if proof.commitments.extra_root.is_some() != (num_extra_polys > 0) {
+            let presence = if num_extra_polys > 0 {
+                "does not commit to"
+            } else {
+                "commits to"
+            };
+            return Err(ProofShapeError::ExtraPolysMismatch(presence, num_extra_polys).into());
+        }
+        if proof.extra_queries.is_some() != (num_extra_polys > 0) {
+            let presence = if num_extra_polys > 0 {
+                "does not open"
+            } else {
+                "opens"
+            };
+            return Err(ProofShapeError::ExtraPolysMismatch(presence, num_extra_polys).into());
+        }
+        let aux_segment_width = air.context().aux_segment_width();
+        if proof.commitments.aux_segment_root.is_some() != (aux_segment_width > 0) {
+            let presence = if aux_segment_width > 0 {
+                "does not commit to"
+            } else {
+                "commits to"
+            };
+            return Err(ProofShapeError::AuxSegmentMismatch(presence, aux_segment_width).into());
+        }
+        if proof.aux_segment_queries.is_some() != (aux_segment_width > 0) {
+            let presence = if aux_segment_width > 0 {
+                "does not open"
+            } else {
+                "opens"
+            };
+            return Err(ProofShapeError::AuxSegmentMismatch(presence, aux_segment_width).into());
+        }
+        validate_fri_proof_shape::<B, E>(&proof, air.context())?;
+
+        // --- determine how registers are arranged in trace commitment rows ----------------------
+        // the column layout of the main segment is validated before the channel is created;
+        // registers of the auxiliary trace segment are always placed in the order of their indexes
+        let main_trace_width = air.context().main_trace_width();
+        let column_layout = air.get_column_layout();
+        let aux_segment_layout = (0..aux_segment_width).collect();
+
+        // --- parse trace and constraint queries ------------------------------------------------
+        let encoding = air.context().options().byte_encoding();
+        let rows_per_leaf = air.context().options().trace_leaf_batching();
+        let num_trace_leaves = air.context().lde_domain_size() / rows_per_leaf;
+        let trace_queries = if air.context().options().compact_trace_queries() {
+            proof
+                .trace_queries
+                .expand::<B>(main_trace_width * rows_per_leaf, encoding)
+                .map_err(|_| VerifierError::TraceQueryDeserializationFailed)?
+        } else {
+            proof.trace_queries
+        };
+        let (trace_proof, trace_values) = trace_queries.into_trace_batch::<B, H>(
+            num_trace_leaves,
+            air.context().options().trace_row_hashing(),
+            main_trace_width,
+            encoding,
+        );
+        let evaluations_per_leaf = utils::evaluations_per_leaf::<E, H>();
+        let num_constraint_leaves = air.context().lde_domain_size() / evaluations_per_leaf;
+        let (constraint_proof, constraint_values) = proof
+            .constraint_queries
+            .into_batch::<H>(num_constraint_leaves);
+
+        // extra polynomials are committed to in the same way as the execution trace, but their
+        // values are placed into rows of the commitment in the order of their indexes
+        let (extra_proof, extra_values) = match proof.extra_queries {
+            Some(extra_queries) => {
+                let extra_queries = if air.context().options().compact_trace_queries() {
+                    extra_queries
+                        .expand::<B>(num_extra_polys * rows_per_leaf, encoding)
+                        .map_err(|_| VerifierError::ExtraQueryDeserializationFailed)?
+                } else {
+                    extra_queries
+                };
+                let (extra_proof, extra_values) = extra_queries.into_trace_batch::<B, H>(
+                    num_trace_leaves,
+                    air.context().options().trace_row_hashing(),
+                    num_extra_polys,
+                    encoding,
+                );
+                (Some(extra_proof), extra_values)
+            }
+            None => (None, Vec::new()),
+        };
+
+        // the auxiliary trace segment is committed to in the same way as extra polynomials
+        let (aux_segment_proof, aux_segment_values) = match proof.aux_segment_queries {
+            Some(aux_segment_queries) => {
+                let aux_segment_queries = if air.context().options().compact_trace_queries() {
+                    aux_segment_queries
+                        .expand::<B>(aux_segment_width * rows_per_leaf, encoding)
+                        .map_err(|_| VerifierError::AuxSegmentQueryDeserializationFailed)?
+                } else {
+                    aux_segment_queries
+                };
+                let (aux_segment_proof, aux_segment_values) = aux_segment_queries
+                    .into_trace_batch::<B, H>(
+                        num_trace_leaves,
+                        air.context().options().trace_row_hashing(),
+                        aux_segment_width,
+                        encoding,
+                    );
+                (Some(aux_segment_proof), aux_segment_values)
+            }
+            None => (None, Vec::new()),
+        };
 
         // --- parse FRI proofs -------------------------------------------------------------------
         let fri_partitioned = proof.fri_proof.partitioned;
@@ -58,18 +205,29 @@ where
             Self::parse_fri_proof(proof.fri_proof);
 
         // --- build query seed -------------------------------------------------------------------
-        let query_seed = build_query_seed::<H>(
+        let query_seed = build_query_seed::<B, T>(
             &proof.commitments.fri_roots,
+            &fri_remainder,
             proof.pow_nonce,
-            &air.context().options(),
+            proof.pow_difficulty,
+            air.context(),
+            pow,
         )?;
 
         Ok(VerifierChannel {
             context: air.context().clone(),
             commitments: proof.commitments,
+            column_layout,
             ood_frame: proof.ood_frame,
-            trace_queries: proof.trace_queries,
-            constraint_queries: proof.constraint_queries,
+            trace_proof,
+            trace_values,
+            constraint_proof,
+            constraint_values,
+            extra_proof,
+            extra_values,
+            aux_segment_proof,
+            aux_segment_values,
+            aux_segment_layout,
             fri_layer_proofs,
             fri_layer_queries,
             fri_remainder,
@@ -78,120 +236,211 @@ where
             _base_element: PhantomData,
             _extension_element: PhantomData,
             _hasher: PhantomData,
+            _transcript_hasher: PhantomData,
         })
     }
 
-    /// Returns trace polynomial evaluations at OOD points z and z * g, where g is the generator
-    /// of the LDE domain.
-    pub fn read_ood_frame(&self) -> Result<EvaluationFrame<E>, VerifierError> {
-        let current = match read_elements_into_vec(&self.ood_frame.trace_at_z1) {
-            Ok(elements) => {
-                if elements.len() != self.context.trace_width() {
-                    return Err(VerifierError::OodFrameDeserializationFailed);
-                }
-                elements
-            }
-            Err(_) => return Err(VerifierError::OodFrameDeserializationFailed),
-        };
-        let next = match read_elements_into_vec(&self.ood_frame.trace_at_z2) {
-            Ok(elements) => {
-                if elements.len() != self.context.trace_width() {
-                    return Err(VerifierError::OodFrameDeserializationFailed);
+    /// Reads trace polynomial evaluations at OOD points z and z * g, where g is the generator
+    /// of the LDE domain, into the provided `frame`. The proof does not contain evaluations of
+    /// local registers at z * g; next-state values of these registers are set to zeros.
+    pub fn read_ood_frame_into(&self, frame: &mut EvaluationFrame<E>) -> Result<(), VerifierError> {
+        let trace_width = self.context.trace_width();
+        let encoding = self.context.options().byte_encoding();
+        read_ood_state_into::<B, E>(
+            &self.ood_frame.trace_at_z1,
+            &mut frame.current,
+            trace_width,
+            encoding,
+        )?;
+
+        let next_state_registers = self.context.next_state_registers();
+        read_ood_state_into::<B, E>(
+            &self.ood_frame.trace_at_z2,
+            &mut frame.next,
+            next_state_registers.len(),
+            encoding,
+        )?;
+        if next_state_registers.len() < trace_width {
+            // spread the values out to the positions of their registers; register indexes are
+            // in ascending order, and thus, the values can be moved back to front in place
+            frame.next.resize(trace_width, E::ZERO);
+            for (j, &i) in next_state_registers.iter().enumerate().rev() {
+                frame.next[i] = frame.next[j];
+                if i != j {
+                    frame.next[j] = E::ZERO;
                 }
-                elements
             }
-            Err(_) => return Err(VerifierError::OodFrameDeserializationFailed),
-        };
-
-        Ok(EvaluationFrame { current, next })
+        }
+        Ok(())
     }
 
-    /// Returns trace states at the specified positions. This also checks if the
-    /// trace states are valid against the trace commitment sent by the prover.
-    pub fn read_trace_states(&self, positions: &[usize]) -> Result<Vec<Vec<B>>, VerifierError> {
-        // TODO: avoid cloning
-        let (trace_proof, trace_values) = self
-            .trace_queries
-            .clone()
-            .into_batch::<H>(self.context.lde_domain_size());
+    /// Reads evaluations of extra polynomials at OOD point z into the provided `result` vector;
+    /// the vector is left empty when the computation declares no extra polynomials.
+    pub fn read_ood_extra_into(&self, result: &mut Vec<E>) -> Result<(), VerifierError> {
+        let num_extra_polys = self.context.num_extra_polys();
+        let encoding = self.context.options().byte_encoding();
+        read_ood_state_into::<B, E>(
+            &self.ood_frame.extra_at_z,
+            result,
+            num_extra_polys,
+            encoding,
+        )
+    }
 
-        // make sure the states included in the proof correspond to the trace commitment
-        if !MerkleTree::verify_batch(
-            &self.commitments.trace_root,
-            positions,
-            &trace_proof,
-            H::hash_fn(),
-        ) {
-            return Err(VerifierError::TraceQueryDoesNotMatchCommitment);
+    /// Reads trace states at the specified positions into the provided `states` vector; the
+    /// states are written one after the other, and `leaf_positions` is used as a buffer for
+    /// indexes of the trace commitment leaves. This also checks if the trace states are valid
+    /// against the trace commitment sent by the prover.
+    ///
+    /// Values in the committed rows are arranged according to the column layout of the AIR, but
+    /// the states are written with registers in the order of their indexes. If the computation
+    /// declares an auxiliary trace segment, the segment is read from its own commitment, and its
+    /// registers follow the registers of the main segment in each state.
+    pub fn read_trace_states_into(
+        &self,
+        positions: &[usize],
+        leaf_positions: &mut Vec<usize>,
+        states: &mut Vec<B>,
+    ) -> Result<(), VerifierError> {
+        let trace_width = self.context.trace_width();
+        scratch::reset(states, positions.len() * trace_width);
+
+        let rows = CommittedRows {
+            root: &self.commitments.trace_root,
+            proof: &self.trace_proof,
+            values: &self.trace_values,
+            column_layout: &self.column_layout,
+            first_register: 0,
+        };
+        match rows.read_into::<B, H>(&self.context, positions, leaf_positions, states) {
+            Ok(()) => (),
+            Err(RowReadError::CommitmentMismatch) => {
+                return Err(VerifierError::TraceQueryDoesNotMatchCommitment)
+            }
+            Err(RowReadError::Deserialization) => {
+                return Err(VerifierError::TraceQueryDeserializationFailed)
+            }
         }
 
-        // convert query bytes into field elements of appropriate type
-        let mut states = Vec::new();
-        for state_bytes in trace_values.iter() {
-            let trace_state = match read_elements_into_vec(state_bytes) {
-                Ok(elements) => {
-                    if elements.len() != self.context.trace_width() {
-                        return Err(VerifierError::TraceQueryDeserializationFailed);
-                    }
-                    elements
-                }
-                Err(_) => return Err(VerifierError::TraceQueryDeserializationFailed),
-            };
-            states.push(trace_state);
+        let (root, proof) = match (&self.commitments.aux_segment_root, &self.aux_segment_proof) {
+            (Some(root), Some(proof)) => (root, proof),
+            _ => return Ok(()),
+        };
+        let rows = CommittedRows {
+            root,
+            proof,
+            values: &self.aux_segment_values,
+            column_layout: &self.aux_segment_layout,
+            first_register: self.context.main_trace_width(),
+        };
+        match rows.read_into::<B, H>(&self.context, positions, leaf_positions, states) {
+            Ok(()) => Ok(()),
+            Err(RowReadError::CommitmentMismatch) => {
+                Err(VerifierError::AuxSegmentQueryDoesNotMatchCommitment)
+            }
+            Err(RowReadError::Deserialization) => {
+                Err(VerifierError::AuxSegmentQueryDeserializationFailed)
+            }
         }
+    }
 
-        Ok(states)
+    /// Reads evaluations of extra polynomials at the specified positions into the provided
+    /// `states` vector in the same way as
+    /// [read_trace_states_into()](VerifierChannel::read_trace_states_into) reads trace states;
+    /// the vector is left empty when the computation declares no extra polynomials.
+    pub fn read_extra_states_into(
+        &self,
+        positions: &[usize],
+        leaf_positions: &mut Vec<usize>,
+        states: &mut Vec<B>,
+    ) -> Result<(), VerifierError> {
+        let (root, proof) = match (&self.commitments.extra_root, &self.extra_proof) {
+            (Some(root), Some(proof)) => (root, proof),
+            _ => {
+                states.clear();
+                return Ok(());
+            }
+        };
+        let num_extra_polys = self.context.num_extra_polys();
+        scratch::reset(states, positions.len() * num_extra_polys);
+        let column_layout = (0..num_extra_polys).collect::<Vec<_>>();
+        let rows = CommittedRows {
+            root,
+            proof,
+            values: &self.extra_values,
+            column_layout: &column_layout,
+            first_register: 0,
+        };
+        match rows.read_into::<B, H>(&self.context, positions, leaf_positions, states) {
+            Ok(()) => Ok(()),
+            Err(RowReadError::CommitmentMismatch) => {
+                Err(VerifierError::ExtraQueryDoesNotMatchCommitment)
+            }
+            Err(RowReadError::Deserialization) => {
+                Err(VerifierError::ExtraQueryDeserializationFailed)
+            }
+        }
     }
 
-    /// Returns constraint evaluations at the specified positions. This also checks if the
-    /// constraint evaluations are valid against the constraint commitment sent by the prover.
-    pub fn read_constraint_evaluations(
+    /// Reads constraint evaluations at the specified positions into the provided `evaluations`
+    /// vector; `c_positions` is used as a buffer for indexes of the constraint commitment
+    /// leaves. This also checks if the constraint evaluations are valid against the constraint
+    /// commitment sent by the prover.
+    pub fn read_constraint_evaluations_into(
         &self,
         positions: &[usize],
-    ) -> Result<Vec<E>, VerifierError> {
+        c_positions: &mut Vec<usize>,
+        evaluations: &mut Vec<E>,
+    ) -> Result<(), VerifierError> {
         let evaluations_per_leaf = utils::evaluations_per_leaf::<E, H>();
-        let num_leaves = self.context.lde_domain_size() / evaluations_per_leaf;
-        // TODO: avoid cloning
-        let (constraint_proof, constraint_values) =
-            self.constraint_queries.clone().into_batch::<H>(num_leaves);
-        let c_positions = utils::map_trace_to_constraint_positions(positions, evaluations_per_leaf);
+        utils::map_trace_to_constraint_positions_into(positions, evaluations_per_leaf, c_positions);
         if !MerkleTree::verify_batch(
             &self.commitments.constraint_root,
-            &c_positions,
-            &constraint_proof,
+            c_positions,
+            &self.constraint_proof,
             H::hash_fn(),
         ) {
             return Err(VerifierError::ConstraintQueryDoesNotMatchCommitment);
         }
 
-        // build constraint evaluation values from the leaves of constraint Merkle proof
-        let mut evaluations: Vec<E> = Vec::with_capacity(positions.len());
+        // make sure each leaf contains exactly the expected number of evaluations
+        let encoding = self.context.options().byte_encoding();
+        let element_bytes = encoding.element_bytes::<E>();
+        let leaf_bytes = evaluations_per_leaf * element_bytes;
+        if self.constraint_values.len() != c_positions.len()
+            || self
+                .constraint_values
+                .iter()
+                .any(|leaf| leaf.len() != leaf_bytes)
+        {
+            return Err(VerifierError::ConstraintQueryDeserializationFailed);
+        }
 
+        // build constraint evaluation values from the leaves of constraint Merkle proof
+        evaluations.clear();
         for &position in positions.iter() {
-            // TODO: position computation should be in common
-            let leaf_idx = c_positions
-                .iter()
-                .position(|&v| v == position / evaluations_per_leaf)
-                .unwrap();
-            let element_start = (position % evaluations_per_leaf) * E::ELEMENT_BYTES;
-            let element_bytes =
-                &constraint_values[leaf_idx][element_start..(element_start + E::ELEMENT_BYTES)];
-            evaluations.push(E::try_from(element_bytes).map_err(|_| {
-                // TODO: display the error from try_from; this requires adding
-                // Debug trait bound to the Error associated type of TryFrom
-                VerifierError::ConstraintQueryDeserializationFailed
-            })?);
+            let (leaf_idx, element_idx) =
+                utils::locate_in_leaves(position, c_positions, evaluations_per_leaf).unwrap();
+            let element_start = element_idx * element_bytes;
+            let element =
+                &self.constraint_values[leaf_idx][element_start..(element_start + element_bytes)];
+            let mut value = [E::ZERO];
+            match encoding.decode_elements_into(element, &mut value) {
+                Ok(1) => evaluations.push(value[0]),
+                _ => return Err(VerifierError::ConstraintQueryDeserializationFailed),
+            }
         }
 
-        Ok(evaluations)
+        Ok(())
     }
 }
 
-impl<B, E, H> FriVerifierChannel<E> for VerifierChannel<B, E, H>
+impl<B, E, H, T> FriVerifierChannel<E> for VerifierChannel<B, E, H, T>
 where
     B: StarkField,
     E: FieldElement + From<B>,
     H: Hasher,
+    T: Hasher,
 {
     type Hasher = H;
 
@@ -214,20 +463,37 @@ where
 
 // PUBLIC COIN IMPLEMENTATIONS
 // ================================================================================================
-impl<B, E, H> PublicCoin for VerifierChannel<B, E, H>
+impl<B, E, H, T> PublicCoin for VerifierChannel<B, E, H, T>
 where
     B: StarkField,
     E: FieldElement + From<B>,
     H: Hasher,
+    T: Hasher,
 {
-    type Hasher = H;
+    type Hasher = T;
 
     fn context(&self) -> &ComputationContext {
         &self.context
     }
 
+    fn aux_segment_seed(&self) -> [u8; 32] {
+        build_constraint_seed::<T>(
+            &self.commitments.trace_root,
+            &self.commitments.aux_roots,
+            self.commitments.extra_root.as_ref(),
+            None,
+            self.context.air_id().as_ref(),
+        )
+    }
+
     fn constraint_seed(&self) -> [u8; 32] {
-        self.commitments.trace_root
+        build_constraint_seed::<T>(
+            &self.commitments.trace_root,
+            &self.commitments.aux_roots,
+            self.commitments.extra_root.as_ref(),
+            self.commitments.aux_segment_root.as_ref(),
+            self.context.air_id().as_ref(),
+        )
     }
 
     fn composition_seed(&self) -> [u8; 32] {
@@ -239,49 +505,218 @@ where
     }
 }
 
-impl<B, E, H> FriPublicCoin for VerifierChannel<B, E, H>
+impl<B, E, H, T> FriPublicCoin for VerifierChannel<B, E, H, T>
 where
     B: StarkField,
     E: FieldElement + From<B>,
     H: Hasher,
+    T: Hasher,
 {
-    type RandomElementGenerator = DefaultRandomElementGenerator<H>;
+    type RandomElementGenerator = DefaultRandomElementGenerator<T>;
 
     fn fri_layer_commitments(&self) -> &[[u8; 32]] {
         &self.commitments.fri_roots
     }
+
+    fn byte_encoding(&self) -> ByteEncoding {
+        self.context.options().byte_encoding()
+    }
+}
+
+// COMMITTED ROWS
+// ================================================================================================
+
+/// Rows of an extended trace (or of extended extra polynomials) opened against a commitment;
+/// values in the rows are arranged according to `column_layout`, and are read into states
+/// starting at `first_register`.
+struct CommittedRows<'a> {
+    root: &'a [u8; 32],
+    proof: &'a BatchMerkleProof,
+    values: &'a [Bytes],
+    column_layout: &'a [usize],
+    first_register: usize,
+}
+
+/// Reasons for which committed rows could not be read.
+enum RowReadError {
+    CommitmentMismatch,
+    Deserialization,
+}
+
+impl CommittedRows<'_> {
+    /// Reads rows at the specified positions into the provided `states` slice, which must hold
+    /// one state per position, stored one after the other; values of each row are written in
+    /// the order of their indexes starting at `first_register` of the state. `leaf_positions` is
+    /// used as a buffer for indexes of the commitment leaves. This also checks if the rows are
+    /// valid against the commitment.
+    fn read_into<B: StarkField, H: Hasher>(
+        &self,
+        context: &ComputationContext,
+        positions: &[usize],
+        leaf_positions: &mut Vec<usize>,
+        states: &mut [B],
+    ) -> Result<(), RowReadError> {
+        // make sure the states included in the proof correspond to the commitment
+        let rows_per_leaf = context.options().trace_leaf_batching();
+        utils::map_trace_positions_to_leaves_into(positions, rows_per_leaf, leaf_positions);
+        if !MerkleTree::verify_batch(self.root, leaf_positions, self.proof, H::hash_fn()) {
+            return Err(RowReadError::CommitmentMismatch);
+        }
+
+        // make sure each leaf contains exactly the expected number of rows
+        let width = self.column_layout.len();
+        let encoding = context.options().byte_encoding();
+        let row_bytes = width * encoding.element_bytes::<B>();
+        if self.values.len() != leaf_positions.len()
+            || self
+                .values
+                .iter()
+                .any(|leaf| leaf.len() != row_bytes * rows_per_leaf)
+        {
+            return Err(RowReadError::Deserialization);
+        }
+
+        // convert query bytes into field elements of appropriate type
+        let state_width = states.len() / positions.len();
+        let mut row = vec![B::ZERO; width];
+        for (&position, state) in positions.iter().zip(states.chunks_mut(state_width)) {
+            let state = &mut state[self.first_register..];
+            let (leaf_idx, row_idx) =
+                utils::locate_in_leaves(position, leaf_positions, rows_per_leaf).unwrap();
+            let row_start = row_idx * row_bytes;
+            let state_bytes = &self.values[leaf_idx][row_start..(row_start + row_bytes)];
+            match encoding.decode_elements_into(state_bytes, &mut row) {
+                Ok(num_elements) if num_elements == width => (),
+                _ => return Err(RowReadError::Deserialization),
+            }
+            for (value, &column) in state.iter_mut().zip(self.column_layout.iter()) {
+                *value = row[column];
+            }
+        }
+
+        Ok(())
+    }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
-fn build_query_seed<H: Hasher>(
-    fri_roots: &[[u8; 32]],
-    nonce: u64,
-    options: &ProofOptions,
-) -> Result<[u8; 32], VerifierError> {
-    let hash_fn = H::hash_fn();
 
-    // combine roots of all FIR layers into a single array of bytes
-    let mut root_bytes: Vec<u8> = Vec::with_capacity(fri_roots.len() * 32);
-    for root in fri_roots.iter() {
-        root.iter().for_each(|&v| root_bytes.push(v));
+/// Reads a trace state of the specified width from `source` bytes into the `destination`
+/// vector; returns an error if the bytes do not encode exactly `width` field elements.
+fn read_state_into<E: FieldElement>(
+    source: &[u8],
+    destination: &mut Vec<E>,
+    width: usize,
+    encoding: ByteEncoding,
+) -> Result<(), ()> {
+    scratch::reset(destination, width);
+    match encoding.decode_elements_into(source, destination) {
+        Ok(num_elements) if num_elements == width => Ok(()),
+        _ => Err(()),
     }
+}
 
-    // hash the array of bytes into a single 32-byte value
-    let mut query_seed = [0u8; 32];
-    hash_fn(&root_bytes, &mut query_seed);
+/// Reads `width` elements of the out-of-domain frame from `source` in the same way as
+/// [read_state_into()]. When a field extension is used, but `source` contains exactly `width`
+/// base field elements, the frame was evaluated in the base field; such frames are reported via a
+/// dedicated error.
+fn read_ood_state_into<B: StarkField, E: FieldElement>(
+    source: &[u8],
+    destination: &mut Vec<E>,
+    width: usize,
+    encoding: ByteEncoding,
+) -> Result<(), VerifierError> {
+    read_state_into(source, destination, width, encoding).map_err(|_| {
+        let (base_bytes, element_bytes) =
+            (encoding.element_bytes::<B>(), encoding.element_bytes::<E>());
+        if element_bytes != base_bytes && source.len() == width * base_bytes {
+            VerifierError::OodFrameNotInExtensionField
+        } else {
+            VerifierError::OodFrameDeserializationFailed
+        }
+    })
+}
 
-    // verify proof of work
-    let mut input_bytes = [0; 64];
-    input_bytes[0..32].copy_from_slice(&query_seed);
-    input_bytes[56..].copy_from_slice(&nonce.to_le_bytes());
+/// Checks that the number of FRI layers, depths of FRI layer commitments, and the size of the
+/// FRI remainder included in the `proof` are consistent with the LDE domain of the computation
+/// described by the `context`.
+fn validate_fri_proof_shape<B: StarkField, E: FieldElement>(
+    proof: &StarkProof,
+    context: &ComputationContext,
+) -> Result<(), ProofShapeError> {
+    let lde_domain_size = context.lde_domain_size();
+    let fri_options = context.options().to_fri_options::<B>();
+    let num_fri_layers = fri_options.num_fri_layers(lde_domain_size);
+
+    // the last commitment is a commitment to the remainder
+    let num_fri_roots = proof.commitments.fri_roots.len();
+    if num_fri_roots != num_fri_layers + 1 {
+        return Err(ProofShapeError::FriCommitmentCountMismatch(
+            num_fri_roots,
+            num_fri_layers + 1,
+        ));
+    }
 
-    hash_fn(&input_bytes, &mut query_seed);
+    let fri_proof = &proof.fri_proof;
+    if fri_proof.layers.len() != num_fri_layers {
+        return Err(ProofShapeError::FriLayerCountMismatch(
+            fri_proof.layers.len(),
+            num_fri_layers,
+        ));
+    }
 
-    let seed_head = u64::from_le_bytes(query_seed[..8].try_into().unwrap());
-    if seed_head.trailing_zeros() < options.grinding_factor() {
-        return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
+    // every layer commits to rows of `folding_factor` evaluations of the layer domain, and each
+    // layer domain is smaller than the previous one by the folding factor
+    let mut domain_size = lde_domain_size;
+    for (i, layer) in fri_proof.layers.iter().enumerate() {
+        domain_size /= fri_options.folding_factor();
+        let expected_depth = domain_size.trailing_zeros() as u8;
+        if layer.depth != expected_depth {
+            return Err(ProofShapeError::FriLayerDepthMismatch(
+                i,
+                layer.depth,
+                expected_depth,
+            ));
+        }
+    }
+
+    let remainder_bytes = fri_options.fri_remainder_length(lde_domain_size)
+        * fri_options.byte_encoding().element_bytes::<E>();
+    if fri_proof.rem_values.len() != remainder_bytes {
+        return Err(ProofShapeError::FriRemainderSizeMismatch(
+            fri_proof.rem_values.len(),
+            remainder_bytes,
+        ));
     }
 
-    Ok(query_seed)
+    Ok(())
+}
+
+/// Builds the query seed from FRI layer commitments and the FRI remainder included in a proof,
+/// and verifies proof-of-work applied to the seed at the `difficulty` declared by the proof; the
+/// seed is derived using the transcript hash function `T`.
+pub(crate) fn build_query_seed<B: StarkField, T: Hasher>(
+    fri_roots: &[[u8; 32]],
+    fri_remainder: &[u8],
+    nonce: u64,
+    difficulty: u8,
+    context: &ComputationContext,
+    pow: &dyn ProofOfWork,
+) -> Result<[u8; 32], VerifierError> {
+    let hash_fn = T::hash_fn();
+
+    // the prover may exceed the grinding factor, but cannot fall short of it
+    let grinding_factor = context.options().grinding_factor();
+    if (difficulty as u32) < grinding_factor {
+        return Err(ProofShapeError::PowDifficultyTooLow(difficulty, grinding_factor).into());
+    }
+
+    // hash roots of all FRI layers together with the remainder into a single 32-byte value
+    let degree_bound = context.fri_remainder_degree_bound::<B>();
+    let encoding = context.options().byte_encoding();
+    let seed = build_fri_seed::<T>(fri_roots, fri_remainder, degree_bound, encoding);
+
+    // verify proof of work
+    pow.verify(&seed, nonce, difficulty as u32, hash_fn, encoding)
+        .ok_or(VerifierError::QuerySeedProofOfWorkVerificationFailed)
 }
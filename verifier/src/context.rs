@@ -0,0 +1,184 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::VerifierScratch;
+use common::{Air, ConstraintLayout, TransitionConstraintDegree};
+use core::marker::PhantomData;
+use crypto::{hash::Blake3_256, Hasher};
+use std::sync::Arc;
+
+// VERIFIER CONTEXT
+// ================================================================================================
+
+/// Data derived from an AIR which can be re-used across verifications of many proofs.
+///
+/// An instance of the AIR has to be created for every proof because the AIR depends on public
+/// inputs; but interpolating periodic columns into polynomials, and laying out constraints, does
+/// not depend on the proof. When many proofs of the same computation are verified with the same
+/// context via [verify_with_context()](crate::verify_with_context), this data is built for the
+/// first proof and is re-used for all subsequent proofs with the same trace length, constraint
+/// degrees, and periodic columns. A context also holds a [VerifierScratch], and thus, buffers
+/// for intermediate values are re-used as well.
+///
+/// The cached data consists of periodic column polynomials and of the [ConstraintLayout] (i.e.,
+/// evaluation degrees of transition constraints, assignment of transition constraints to groups,
+/// and the transition divisor). Periodic columns may depend on public inputs; cached
+/// polynomials are re-used only if the digest of the columns in the new AIR instance is the
+/// same as the digest of the columns from which the polynomials were built. Otherwise, the
+/// polynomials are rebuilt and replace the cached ones.
+///
+/// Coefficients of constraint groups are drawn for each proof, and boundary constraints are
+/// built from assertions which depend on public inputs; thus, these are still built anew for
+/// every proof. Using a context does not affect the outcome of verification.
+pub struct VerifierContext<A: Air> {
+    /// trace length and digest of periodic columns together with polynomials built from them
+    #[allow(clippy::type_complexity)]
+    periodic_column_polys: Option<(usize, [u8; 32], Arc<Vec<Vec<A::BaseElement>>>)>,
+    /// trace length and transition constraint degrees together with the layout built from them
+    #[allow(clippy::type_complexity)]
+    constraint_layout: Option<(
+        usize,
+        Vec<TransitionConstraintDegree>,
+        Arc<ConstraintLayout<A::BaseElement>>,
+    )>,
+    scratch: VerifierScratch<A::BaseElement>,
+    num_hits: usize,
+    num_misses: usize,
+    _air: PhantomData<fn() -> A>,
+}
+
+impl<A: Air> VerifierContext<A> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new empty context.
+    pub fn new() -> Self {
+        Self::with_scratch(VerifierScratch::new())
+    }
+
+    /// Returns a new empty context which takes buffers for intermediate values from the
+    /// specified `scratch` space.
+    pub fn with_scratch(scratch: VerifierScratch<A::BaseElement>) -> Self {
+        VerifierContext {
+            periodic_column_polys: None,
+            constraint_layout: None,
+            scratch,
+            num_hits: 0,
+            num_misses: 0,
+            _air: PhantomData,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of times all cached data was re-used.
+    pub fn num_hits(&self) -> usize {
+        self.num_hits
+    }
+
+    /// Returns the number of times some of the data had to be built because it was not in the
+    /// context.
+    pub fn num_misses(&self) -> usize {
+        self.num_misses
+    }
+
+    /// Returns true if no data has been cached yet.
+    pub fn is_empty(&self) -> bool {
+        self.periodic_column_polys.is_none() && self.constraint_layout.is_none()
+    }
+
+    // DATA MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Removes all cached data; hit and miss counters and scratch buffers are not affected.
+    pub fn clear(&mut self) {
+        self.periodic_column_polys = None;
+        self.constraint_layout = None;
+    }
+
+    /// Populates caches of the specified `air` instance with the data from this context; data
+    /// which is not in this context yet is built from the `air` and is added to the context.
+    ///
+    /// Returns the scratch space of this context.
+    pub(crate) fn prepare(&mut self, air: &A) -> &mut VerifierScratch<A::BaseElement> {
+        let polys_cached = self.prepare_periodic_column_polys(air);
+        let layout_cached = self.prepare_constraint_layout(air);
+        if polys_cached && layout_cached {
+            self.num_hits += 1;
+        } else {
+            self.num_misses += 1;
+        }
+
+        &mut self.scratch
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Places cached periodic column polynomials into the context of the `air` if they were
+    /// built from the same columns, and returns true; otherwise, builds the polynomials and
+    /// caches them, and returns false.
+    fn prepare_periodic_column_polys(&mut self, air: &A) -> bool {
+        let trace_length = air.trace_length();
+        let digest = air.get_periodic_columns().iter().fold(
+            Blake3_256::hash_elements::<A::BaseElement>(&[]),
+            |acc, column| Blake3_256::merge(&[acc, column.digest::<Blake3_256>()]),
+        );
+
+        match &self.periodic_column_polys {
+            Some((length, cached_digest, polys))
+                if *length == trace_length && *cached_digest == digest =>
+            {
+                air.context().set_periodic_column_polys(polys.clone());
+                true
+            }
+            _ => {
+                // the default implementation of get_periodic_column_polys() caches polynomials
+                // in the computation context; if the AIR overrides it, the polynomials are copied
+                let built_polys = air.get_periodic_column_polys();
+                let polys = air
+                    .context()
+                    .cached_periodic_column_polys()
+                    .unwrap_or_else(|| Arc::new(built_polys.to_vec()));
+                self.periodic_column_polys = Some((trace_length, digest, polys));
+                false
+            }
+        }
+    }
+
+    /// Places the cached constraint layout into the context of the `air` if it was built for
+    /// the same trace length and constraint degrees, and returns true; otherwise, builds the
+    /// layout and caches it, and returns false.
+    fn prepare_constraint_layout(&mut self, air: &A) -> bool {
+        let context = air.context();
+        match &self.constraint_layout {
+            Some((length, degrees, layout))
+                if *length == context.trace_length()
+                    && degrees[..] == *context.transition_constraint_degrees() =>
+            {
+                context.set_constraint_layout(layout.clone());
+                true
+            }
+            _ => {
+                context.constraint_layout::<A::BaseElement>();
+                let layout = context
+                    .cached_constraint_layout()
+                    .expect("constraint layout must be cached in the context");
+                self.constraint_layout = Some((
+                    context.trace_length(),
+                    context.transition_constraint_degrees().to_vec(),
+                    layout,
+                ));
+                false
+            }
+        }
+    }
+}
+
+impl<A: Air> Default for VerifierContext<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,14 +4,27 @@
 // LICENSE file in the root directory of this source tree.
 
 pub use common::{
-    errors::VerifierError, proof::StarkProof, Air, FieldExtension, HashFunction, TraceInfo,
+    errors::{ProofParsingError, ProofShapeError, VerifierError},
+    evaluate_constraints, limits,
+    proof::{
+        parse_proof, ParsingLimits, StarkProof, TraceChunkOpening, LEGACY_PROOF_VERSION,
+        PROOF_VERSION,
+    },
+    Air, ByteEncoding, ByteReader, ByteWriter, CoefficientScheme, ComputationContext,
+    Deserializable, DeserializationError, FieldExtension, HashFunction, HashProofOfWork,
+    HashedPublicInputs, NoProofOfWork, ProofOfWork, ProofOptions, Serializable, SliceReader,
+    TraceInfo, TraceRowHashing, VerificationCost,
 };
 
+#[cfg(feature = "encoding")]
+pub use common::{errors::EncodingError, TextEncoding};
+
 pub use crypto;
 use crypto::hash::{Blake3_256, Sha3_256};
 
+use common::{utils, ExtensionVisitor};
 pub use math;
-use math::field::QuadExtension;
+use math::field::{ExtensionOf, StarkField};
 
 mod channel;
 use channel::VerifierChannel;
@@ -20,48 +33,267 @@ mod verification;
 use verification::perform_verification;
 
 mod constraints;
-use constraints::{compose_constraints, evaluate_constraints};
+use constraints::compose_constraints;
+
+mod context;
+pub use context::VerifierContext;
+
+mod scratch;
+use scratch::ScratchBuffers;
+pub use scratch::VerifierScratch;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod mutation;
+
+mod opening;
+pub use opening::verify_trace_chunks;
+
+mod report;
+pub use report::VerificationReport;
+
+mod rejection;
+use rejection::ProofDetails;
+pub use rejection::{set_rejection_hook, statement_digest, take_rejection_hook, RejectionRecord};
+
+mod audit;
+pub use audit::{
+    derive_queries, export_transcript, extract_challenges, recompute_commitment_roots, Challenges,
+    CommitmentOpening, FriQueryRecord, QueryRecord, QueryTranscript, RecomputedRoots,
+    TranscriptStep, TranscriptVector,
+};
+
+#[cfg(test)]
+mod tests;
 
 // VERIFIER
 // ================================================================================================
 
 /// Verifies STARK `proof` attesting that the computation specified by `AIR` was executed correctly
 /// against the provided `pub_inputs`.
-#[rustfmt::skip]
 pub fn verify<AIR: Air>(
     proof: StarkProof,
     pub_inputs: AIR::PublicInputs,
 ) -> Result<(), VerifierError> {
+    verify_with_scratch::<AIR>(proof, pub_inputs, &mut VerifierScratch::new())
+}
+
+/// Verifies STARK `proof` in the same way as [verify()], and on success, returns a report with
+/// the security level, hash functions, and field parameters of the accepted proof.
+pub fn verify_with_report<AIR: Air>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<VerificationReport, VerifierError> {
+    let report = VerificationReport::new::<AIR::BaseElement>(&proof);
+    verify::<AIR>(proof, pub_inputs)?;
+    Ok(report)
+}
+
+/// Verifies STARK `proof` for a computation whose `AIR` is bound to a digest of its public
+/// inputs. The digest is recomputed from the full `inputs` using the hash function specified in
+/// the proof options, and the proof is then verified in the same way as in [verify()].
+pub fn verify_with_hashed_inputs<AIR, I>(proof: StarkProof, inputs: &I) -> Result<(), VerifierError>
+where
+    AIR: Air<PublicInputs = HashedPublicInputs>,
+    I: Serializable,
+{
+    let pub_inputs = HashedPublicInputs::new(proof.options().hash_fn(), inputs);
+    verify::<AIR>(proof, pub_inputs)
+}
+
+/// Verifies STARK `proof` in the same way as [verify()], but takes buffers for intermediate
+/// values from the provided `scratch` space. Reusing the same scratch space across many
+/// verifications avoids re-allocating these buffers for every proof.
+pub fn verify_with_scratch<AIR: Air>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    scratch: &mut VerifierScratch<AIR::BaseElement>,
+) -> Result<(), VerifierError> {
+    verify_with_pow::<AIR>(proof, pub_inputs, &HashProofOfWork, scratch)
+}
+
+/// Verifies STARK `proof` in the same way as [verify_with_scratch()], but checks proof-of-work
+/// applied to the query seed using the specified scheme instead of the default
+/// [HashProofOfWork]. This must be the same scheme which was used to generate the proof.
+pub fn verify_with_pow<AIR: Air>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    pow: &dyn ProofOfWork,
+    scratch: &mut VerifierScratch<AIR::BaseElement>,
+) -> Result<(), VerifierError> {
+    verify_and_report::<AIR, _>(proof, pub_inputs, |air, proof| {
+        run_verification(air, proof, pow, scratch)
+    })
+}
+
+/// Verifies STARK `proof` in the same way as [verify_with_scratch()], but takes data derived
+/// from the AIR, as well as buffers for intermediate values, from the specified `context`; data
+/// which is not in the `context` yet is added to it.
+///
+/// This is useful for verifying many proofs of the same computation: data such as periodic
+/// column polynomials is built only once for all proofs with the same trace length.
+pub fn verify_with_context<AIR: Air>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    context: &mut VerifierContext<AIR>,
+) -> Result<(), VerifierError> {
+    verify_and_report::<AIR, _>(proof, pub_inputs, |air, proof| {
+        let scratch = context.prepare(&air);
+        run_verification(air, proof, &HashProofOfWork, scratch)
+    })
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Instantiates the AIR for the `proof` and verifies the proof using the `verify` function; if
+/// the proof is rejected and a rejection hook is installed, the rejection is reported to the
+/// hook.
+fn verify_and_report<AIR, F>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    verify: F,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    F: FnOnce(AIR, StarkProof) -> Result<(), VerifierError>,
+{
+    let mut details = ProofDetails::capture(&proof);
+    let result = build_air::<AIR>(&proof, pub_inputs).and_then(|air| {
+        if let Some(details) = details.as_mut() {
+            details.set_statement(&air);
+        }
+        verify(air, proof)
+    });
+
+    if let (Err(error), Some(details)) = (&result, details) {
+        details.report(error);
+    }
+    result
+}
+
+/// Makes sure the layout and the domain of the `proof` are supported, and instantiates the AIR
+/// for the computation specified in the proof.
+#[rustfmt::skip]
+fn build_air<AIR: Air>(
+    proof: &StarkProof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<AIR, VerifierError> {
+    // ----- make sure the proof layout is supported ----------------------------------------------
+    // proofs in the legacy layout were parsed according to their version, and differ from proofs
+    // in the current layout only by the byte encoding which is always native for them; thus,
+    // they are verified in the same way. Proofs of all other versions must be generated again
+    match proof.version() {
+        PROOF_VERSION | LEGACY_PROOF_VERSION => (),
+        version => return Err(VerifierError::UnsupportedProofVersion(version)),
+    }
+
+    // ----- make sure the declared LDE domain is valid for the base field ------------------------
+    // trace length is derived from the LDE domain depth, and thus, the depth must be checked
+    // before the AIR is instantiated
+    let blowup_depth = proof.options().blowup_factor().trailing_zeros();
+    let min_depth = blowup_depth + ComputationContext::MIN_TRACE_LENGTH.trailing_zeros();
+    let max_depth = AIR::BaseElement::max_two_adicity();
+    let lde_domain_depth = proof.context.lde_domain_depth;
+    if !(min_depth..=max_depth).contains(&(lde_domain_depth as u32)) {
+        let error = ProofShapeError::LdeDomainDepthOutOfRange(lde_domain_depth, min_depth, max_depth);
+        return Err(error.into());
+    }
+
     // ----- create AIR instance for the computation specified in the proof -----------------------
     let trace_info = TraceInfo {
         length: proof.trace_length(),
         meta: vec![],
     };
     let air = AIR::new(trace_info, pub_inputs, proof.options().clone());
+    air.context()
+        .validate::<AIR::BaseElement>()
+        .map_err(VerifierError::InvalidParameters)?;
+
+    // ----- make sure registers of the main trace segment are arranged into commitment rows ------
+    // according to a permutation of their indexes
+    let main_trace_width = air.context().main_trace_width();
+    if !utils::is_valid_column_layout(&air.get_column_layout(), main_trace_width) {
+        return Err(VerifierError::InvalidColumnLayout(main_trace_width));
+    }
 
-    // ----- instantiate verifier channel and run the verification --------------------------------
+    // ----- make sure the proof commits to each blinded register ---------------------------------
+    // nothing in the proof relates these commitments to the values of the registers, so we can
+    // check only that the number of commitments matches the number of registers designated by
+    // the AIR
+    let num_aux_roots = proof.commitments.aux_roots.len();
+    let num_blinded_registers = air.get_blinded_registers().len();
+    if num_aux_roots != num_blinded_registers {
+        return Err(VerifierError::AuxCommitmentCountMismatch(num_aux_roots, num_blinded_registers));
+    }
+
+    Ok(air)
+}
+
+/// Runs the verification procedure for the `proof` against the specified `air` instance.
+fn run_verification<AIR: Air>(
+    air: AIR,
+    proof: StarkProof,
+    pow: &dyn ProofOfWork,
+    scratch: &mut VerifierScratch<AIR::BaseElement>,
+) -> Result<(), VerifierError> {
     // figure out which version of the generic proof verification procedure to run. this is a sort
-    // of static dispatch for selecting two generic parameter: extension field and hash function.
-    match air.context().options().field_extension() {
-        FieldExtension::None => match air.context().options().hash_fn() {
-            HashFunction::Blake3_256 => {
-                let channel = VerifierChannel::new(&air, proof)?;
-                perform_verification::<AIR, AIR::BaseElement, Blake3_256>(air, channel)
-            }
-            HashFunction::Sha3_256 => {
-                let channel = VerifierChannel::new(&air, proof)?;
-                perform_verification::<AIR, AIR::BaseElement, Sha3_256>(air, channel)
-            }
-        },
-        FieldExtension::Quadratic => match air.context().options().hash_fn() {
-            HashFunction::Blake3_256 => {
-                let channel = VerifierChannel::new(&air, proof)?;
-                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Blake3_256>(air, channel)
-            }
-            HashFunction::Sha3_256 => {
-                let channel = VerifierChannel::new(&air, proof)?;
-                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Sha3_256>(air, channel)
-            }
-        },
+    // of static dispatch for selecting generic parameters: extension field and hash functions.
+    let field_extension = air.context().options().field_extension();
+    field_extension.visit(ProofVerifier {
+        air,
+        proof,
+        pow,
+        scratch,
+    })
+}
+
+/// Arguments of the generic proof verification procedure; the procedure is run in the field
+/// selected by the field extension specified in proof options.
+struct ProofVerifier<'a, AIR: Air> {
+    air: AIR,
+    proof: StarkProof,
+    pow: &'a dyn ProofOfWork,
+    scratch: &'a mut VerifierScratch<AIR::BaseElement>,
+}
+
+impl<AIR: Air> ExtensionVisitor<AIR::BaseElement> for ProofVerifier<'_, AIR> {
+    type Output = Result<(), VerifierError>;
+
+    fn visit<E: ExtensionOf<AIR::BaseElement>>(self) -> Self::Output {
+        let scratch = self.scratch.buffers::<E>();
+        verify_with_hashes(self.air, self.proof, self.pow, scratch)
+    }
+}
+
+/// Selects hash functions for verifying commitments and for deriving the Fiat-Shamir transcript,
+/// and runs the generic proof verification procedure for the selected functions.
+fn verify_with_hashes<AIR, E>(
+    air: AIR,
+    proof: StarkProof,
+    pow: &dyn ProofOfWork,
+    scratch: &mut ScratchBuffers<AIR::BaseElement, E>,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    E: ExtensionOf<AIR::BaseElement>,
+{
+    let options = air.context().options();
+    match (options.hash_fn(), options.transcript_hash_fn()) {
+        (HashFunction::Blake3_256, HashFunction::Blake3_256) => {
+            let channel = VerifierChannel::<_, E, Blake3_256, Blake3_256>::new(&air, proof, pow)?;
+            perform_verification(air, channel, scratch)
+        }
+        (HashFunction::Blake3_256, HashFunction::Sha3_256) => {
+            let channel = VerifierChannel::<_, E, Blake3_256, Sha3_256>::new(&air, proof, pow)?;
+            perform_verification(air, channel, scratch)
+        }
+        (HashFunction::Sha3_256, HashFunction::Blake3_256) => {
+            let channel = VerifierChannel::<_, E, Sha3_256, Blake3_256>::new(&air, proof, pow)?;
+            perform_verification(air, channel, scratch)
+        }
+        (HashFunction::Sha3_256, HashFunction::Sha3_256) => {
+            let channel = VerifierChannel::<_, E, Sha3_256, Sha3_256>::new(&air, proof, pow)?;
+            perform_verification(air, channel, scratch)
+        }
     }
 }
@@ -0,0 +1,345 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Test support for checking that the verifier rejects corrupted proofs.
+//!
+//! This module is available only when the `testing` feature is enabled. It is intended for
+//! projects which modify the verifier: running the mutations against a valid proof of their
+//! computation checks that none of their changes silently dropped a check performed by the
+//! verifier.
+
+use crate::{verify, Air, StarkProof, VerifierError};
+use common::{errors::ProofShapeError, proof::Queries};
+use fri::VerifierError as FriVerifierError;
+use math::field::{ByteEncoding, EVM_WORD_BYTES};
+
+// PROOF MUTATION
+// ================================================================================================
+
+/// A corrupted copy of a valid proof together with the error the verifier is expected to return
+/// when verifying it.
+#[derive(Clone)]
+pub struct ProofMutation {
+    /// Description of how the proof was corrupted.
+    pub description: &'static str,
+    /// The corrupted proof.
+    pub proof: StarkProof,
+    /// Name of the verifier error expected for the corrupted proof.
+    pub expected_error: &'static str,
+    matches_error: fn(&VerifierError) -> bool,
+}
+
+impl ProofMutation {
+    /// Returns a new mutation; `matches_error` must return true for the errors which the
+    /// verifier is expected to return for the corrupted `proof`.
+    pub fn new(
+        description: &'static str,
+        proof: StarkProof,
+        expected_error: &'static str,
+        matches_error: fn(&VerifierError) -> bool,
+    ) -> Self {
+        ProofMutation {
+            description,
+            proof,
+            expected_error,
+            matches_error,
+        }
+    }
+
+    /// Returns true if the `error` is the error which the verifier is expected to return for
+    /// this mutation.
+    pub fn is_expected_error(&self, error: &VerifierError) -> bool {
+        (self.matches_error)(error)
+    }
+}
+
+// MUTATION BUILDER
+// ================================================================================================
+
+/// Returns a set of systematic mutations of the specified valid `proof`.
+///
+/// The following mutations are produced:
+/// * a flipped node of the Merkle authentication paths for trace queries, constraint queries,
+///   extra polynomial queries (if the proof has extra polynomials), and the first FRI layer (if
+///   the proof has FRI layers);
+/// * a truncated trace query value, a truncated constraint query value, and a truncated set of
+///   trace queries;
+/// * an altered and a truncated out-of-domain frame, and an altered evaluation of an extra
+///   polynomial at the out-of-domain point (if the proof has extra polynomials);
+/// * a flipped trace root and a flipped constraint root;
+/// * a wrong proof-of-work nonce, and a proof-of-work difficulty below the grinding factor.
+///
+/// Mutations which are not applicable to the `proof` (e.g., a flipped Merkle node when all
+/// authentication paths are empty) are omitted.
+pub fn build_mutations(proof: &StarkProof) -> Vec<ProofMutation> {
+    let mut result = Vec::new();
+
+    // --- Merkle authentication paths ------------------------------------------------------------
+    let mut mutated = proof.clone();
+    if flip_path_node(&mut mutated.trace_queries.paths) {
+        result.push(ProofMutation::new(
+            "flipped node of a trace query authentication path",
+            mutated,
+            "TraceQueryDoesNotMatchCommitment",
+            |e| matches!(e, VerifierError::TraceQueryDoesNotMatchCommitment),
+        ));
+    }
+
+    let mut mutated = proof.clone();
+    if flip_path_node(&mut mutated.constraint_queries.paths) {
+        result.push(ProofMutation::new(
+            "flipped node of a constraint query authentication path",
+            mutated,
+            "ConstraintQueryDoesNotMatchCommitment",
+            |e| matches!(e, VerifierError::ConstraintQueryDoesNotMatchCommitment),
+        ));
+    }
+
+    let mut mutated = proof.clone();
+    if let Some(extra_queries) = mutated.extra_queries.as_mut() {
+        if flip_path_node(&mut extra_queries.paths) {
+            result.push(ProofMutation::new(
+                "flipped node of an extra polynomial query authentication path",
+                mutated,
+                "ExtraQueryDoesNotMatchCommitment",
+                |e| matches!(e, VerifierError::ExtraQueryDoesNotMatchCommitment),
+            ));
+        }
+    }
+
+    let mut mutated = proof.clone();
+    if let Some(layer) = mutated.fri_proof.layers.first_mut() {
+        if flip_path_node(&mut layer.paths) {
+            result.push(ProofMutation::new(
+                "flipped node of a FRI layer authentication path",
+                mutated,
+                "FriVerificationFailed(LayerCommitmentMismatch(0))",
+                |e| {
+                    matches!(
+                        e,
+                        VerifierError::FriVerificationFailed(
+                            FriVerifierError::LayerCommitmentMismatch(0)
+                        )
+                    )
+                },
+            ));
+        }
+    }
+
+    // --- queried values -------------------------------------------------------------------------
+    // a truncated value of a trace query changes the leaf it hashes into; in compact encoding,
+    // truncated elements cannot be expanded back into the queried values
+    let mut mutated = proof.clone();
+    if truncate_value(&mut mutated.trace_queries) {
+        if mutated.trace_queries.compact_values.is_some() {
+            result.push(ProofMutation::new(
+                "truncated value of a trace query",
+                mutated,
+                "TraceQueryDeserializationFailed",
+                |e| matches!(e, VerifierError::TraceQueryDeserializationFailed),
+            ));
+        } else {
+            result.push(ProofMutation::new(
+                "truncated value of a trace query",
+                mutated,
+                "TraceQueryDoesNotMatchCommitment",
+                |e| matches!(e, VerifierError::TraceQueryDoesNotMatchCommitment),
+            ));
+        }
+    }
+
+    let mut mutated = proof.clone();
+    if truncate_value(&mut mutated.constraint_queries) {
+        result.push(ProofMutation::new(
+            "truncated value of a constraint query",
+            mutated,
+            "ConstraintQueryDoesNotMatchCommitment",
+            |e| matches!(e, VerifierError::ConstraintQueryDoesNotMatchCommitment),
+        ));
+    }
+
+    let mut mutated = proof.clone();
+    if mutated.trace_queries.compact_values.is_none() && mutated.trace_queries.values.len() > 1 {
+        mutated.trace_queries.values.pop();
+        result.push(ProofMutation::new(
+            "missing trace query",
+            mutated,
+            "TraceQueryDoesNotMatchCommitment",
+            |e| matches!(e, VerifierError::TraceQueryDoesNotMatchCommitment),
+        ));
+    }
+
+    // --- out-of-domain frame --------------------------------------------------------------------
+    // an altered OOD frame is detected only through the DEEP composition polynomial, which is
+    // checked by the FRI protocol
+    let encoding = proof.options().byte_encoding();
+    let mut mutated = proof.clone();
+    if flip_first_element(&mut mutated.ood_frame.trace_at_z1, encoding) {
+        result.push(ProofMutation::new(
+            "altered trace evaluation at the out-of-domain point",
+            mutated,
+            "FriVerificationFailed",
+            |e| matches!(e, VerifierError::FriVerificationFailed(_)),
+        ));
+    }
+
+    let mut mutated = proof.clone();
+    if flip_first_element(&mut mutated.ood_frame.extra_at_z, encoding) {
+        result.push(ProofMutation::new(
+            "altered extra polynomial evaluation at the out-of-domain point",
+            mutated,
+            "FriVerificationFailed",
+            |e| matches!(e, VerifierError::FriVerificationFailed(_)),
+        ));
+    }
+
+    let mut mutated = proof.clone();
+    if mutated.ood_frame.trace_at_z2.pop().is_some() {
+        result.push(ProofMutation::new(
+            "truncated out-of-domain frame",
+            mutated,
+            "OodFrameDeserializationFailed",
+            |e| matches!(e, VerifierError::OodFrameDeserializationFailed),
+        ));
+    }
+
+    // --- commitments ----------------------------------------------------------------------------
+    // commitments seed all challenges, but a changed commitment is caught already when queries
+    // are authenticated against it
+    let mut mutated = proof.clone();
+    mutated.commitments.trace_root[0] ^= 1;
+    result.push(ProofMutation::new(
+        "flipped trace root",
+        mutated,
+        "TraceQueryDoesNotMatchCommitment",
+        |e| matches!(e, VerifierError::TraceQueryDoesNotMatchCommitment),
+    ));
+
+    let mut mutated = proof.clone();
+    mutated.commitments.constraint_root[0] ^= 1;
+    result.push(ProofMutation::new(
+        "flipped constraint root",
+        mutated,
+        "ConstraintQueryDoesNotMatchCommitment",
+        |e| matches!(e, VerifierError::ConstraintQueryDoesNotMatchCommitment),
+    ));
+
+    // --- proof-of-work --------------------------------------------------------------------------
+    // a wrong nonce satisfies the grinding factor with probability 2^-grinding_factor; in such
+    // a case, it changes the query positions, and the queries in the proof no longer match the
+    // trace commitment
+    let mut mutated = proof.clone();
+    mutated.pow_nonce ^= 1;
+    result.push(ProofMutation::new(
+        "wrong proof-of-work nonce",
+        mutated,
+        "QuerySeedProofOfWorkVerificationFailed or TraceQueryDoesNotMatchCommitment",
+        |e| {
+            matches!(
+                e,
+                VerifierError::QuerySeedProofOfWorkVerificationFailed
+                    | VerifierError::TraceQueryDoesNotMatchCommitment
+            )
+        },
+    ));
+
+    if proof.options().grinding_factor() > 0 {
+        let mut mutated = proof.clone();
+        mutated.pow_difficulty = proof.options().grinding_factor() as u8 - 1;
+        result.push(ProofMutation::new(
+            "proof-of-work difficulty below the grinding factor",
+            mutated,
+            "InconsistentProof(PowDifficultyTooLow)",
+            |e| {
+                matches!(
+                    e,
+                    VerifierError::InconsistentProof(ProofShapeError::PowDifficultyTooLow(..))
+                )
+            },
+        ));
+    }
+
+    result
+}
+
+// MUTATION CHECKER
+// ================================================================================================
+
+/// Verifies every mutation of the specified valid `proof` of a computation described by `AIR`,
+/// and panics if any of the mutated proofs is accepted by the verifier, or is rejected with an
+/// unexpected error; `pub_inputs` must return public inputs against which the `proof` is valid.
+///
+/// Returns the number of checked mutations.
+///
+/// # Panics
+/// Panics if the `proof` itself does not verify, or if any mutation is not rejected with the
+/// expected error.
+pub fn check_mutations<AIR, F>(proof: &StarkProof, pub_inputs: F) -> usize
+where
+    AIR: Air,
+    F: Fn() -> AIR::PublicInputs,
+{
+    if let Err(error) = verify::<AIR>(proof.clone(), pub_inputs()) {
+        panic!("the original proof is not valid: {}", error);
+    }
+
+    let mutations = build_mutations(proof);
+    for mutation in mutations.iter() {
+        match verify::<AIR>(mutation.proof.clone(), pub_inputs()) {
+            Ok(()) => panic!("verifier accepted proof with {}", mutation.description),
+            Err(error) => assert!(
+                mutation.is_expected_error(&error),
+                "verifier rejected proof with {} with error {:?}, but {} was expected",
+                mutation.description,
+                error,
+                mutation.expected_error
+            ),
+        }
+    }
+    mutations.len()
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Flips a bit in the first node of the first non-empty authentication path; returns false if
+/// all paths are empty.
+fn flip_path_node(paths: &mut [Vec<[u8; 32]>]) -> bool {
+    match paths.iter_mut().find_map(|path| path.first_mut()) {
+        Some(node) => {
+            node[0] ^= 1;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Flips the least significant bit of the first element encoded in `bytes` according to the
+/// `encoding`; returns false if `bytes` are empty.
+fn flip_first_element(bytes: &mut [u8], encoding: ByteEncoding) -> bool {
+    let index = match encoding {
+        ByteEncoding::Native => 0,
+        ByteEncoding::EvmWords => EVM_WORD_BYTES - 1,
+    };
+    match bytes.get_mut(index) {
+        Some(byte) => {
+            *byte ^= 1;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Removes the last byte of the first queried value; returns false if there are no non-empty
+/// values.
+fn truncate_value(queries: &mut Queries) -> bool {
+    if let Some(compact_values) = queries.compact_values.as_mut() {
+        return compact_values.elements.pop().is_some() || compact_values.constants.pop().is_some();
+    }
+    match queries.values.iter_mut().find(|value| !value.is_empty()) {
+        Some(value) => value.pop().is_some(),
+        None => false,
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{
+    errors::VerifierError, proof::TraceChunkOpening, utils, HashFunction, ProofOptions,
+    TraceRowHashing,
+};
+use crypto::{
+    hash::{Blake3_256, Sha3_256},
+    BatchMerkleProof, Hasher, MerkleTree,
+};
+use math::field::{ByteEncoding, StarkField};
+
+// CHUNK OPENING VERIFICATION
+// ================================================================================================
+
+/// Checks that the `opening` of a trace commitment at the specified `chunks` of rows at the
+/// specified `positions` of the LDE domain is valid against the commitment `trace_root`, and
+/// returns the opened values.
+///
+/// The commitment must have been built with [TraceRowHashing::Chunked] row hashing using the
+/// `options`; `trace_width` and `trace_length` describe the execution trace before it was
+/// extended. Chunk indexes must be unique and must be listed in ascending order. For every
+/// position, the returned vector contains values of the requested chunks one after another;
+/// values are arranged according to the column layout of the commitment.
+///
+/// # Errors
+/// Returns an error if the commitment was not built with chunked row hashing, if the opening is
+/// malformed, or if the opened values do not match the commitment.
+///
+/// # Panics
+/// Panics if any of the chunk indexes is out of bounds or if chunk indexes are not sorted.
+pub fn verify_trace_chunks<B: StarkField>(
+    trace_root: &[u8; 32],
+    options: &ProofOptions,
+    trace_width: usize,
+    trace_length: usize,
+    positions: &[usize],
+    chunks: &[usize],
+    opening: &TraceChunkOpening,
+) -> Result<Vec<Vec<B>>, VerifierError> {
+    let chunk_width = match options.trace_row_hashing() {
+        TraceRowHashing::Chunked(chunk_width) => chunk_width as usize,
+        TraceRowHashing::Flat => return Err(VerifierError::TraceRowsNotChunked),
+    };
+    let num_chunks = utils::get_num_row_chunks(trace_width, chunk_width);
+    assert!(
+        chunks.windows(2).all(|w| w[0] < w[1]),
+        "chunk indexes must be unique and sorted in ascending order"
+    );
+    assert!(
+        chunks.last().is_some_and(|&chunk| chunk < num_chunks),
+        "chunk indexes must be smaller than the number of chunks in a row ({})",
+        num_chunks
+    );
+
+    let layout = ChunkLayout {
+        trace_width,
+        chunk_width,
+        rows_per_leaf: options.trace_leaf_batching(),
+        num_leaves: trace_length * options.blowup_factor() / options.trace_leaf_batching(),
+        encoding: options.byte_encoding(),
+    };
+    match options.hash_fn() {
+        HashFunction::Blake3_256 => {
+            verify_chunks::<B, Blake3_256>(trace_root, &layout, positions, chunks, opening)
+        }
+        HashFunction::Sha3_256 => {
+            verify_chunks::<B, Sha3_256>(trace_root, &layout, positions, chunks, opening)
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Shape of the trace commitment.
+struct ChunkLayout {
+    trace_width: usize,
+    chunk_width: usize,
+    rows_per_leaf: usize,
+    num_leaves: usize,
+    encoding: ByteEncoding,
+}
+
+fn verify_chunks<B: StarkField, H: Hasher>(
+    trace_root: &[u8; 32],
+    layout: &ChunkLayout,
+    positions: &[usize],
+    chunks: &[usize],
+    opening: &TraceChunkOpening,
+) -> Result<Vec<Vec<B>>, VerifierError> {
+    let rows_per_leaf = layout.rows_per_leaf;
+    let leaf_positions = utils::map_trace_positions_to_leaves(positions, rows_per_leaf);
+    if opening.leaves.values.len() != leaf_positions.len()
+        || opening.chunk_paths.len() != leaf_positions.len()
+    {
+        return Err(VerifierError::TraceQueryDeserializationFailed);
+    }
+
+    // determine sizes of the opened chunks; only the last chunk of a row may be narrower
+    let num_chunks = utils::get_num_row_chunks(layout.trace_width, layout.chunk_width);
+    let padded_chunks = utils::get_padded_chunks_per_row(num_chunks);
+    let chunk_sizes = chunks
+        .iter()
+        .map(|&chunk| {
+            let first_column = chunk * layout.chunk_width;
+            let num_columns = layout.chunk_width.min(layout.trace_width - first_column);
+            num_columns * layout.encoding.element_bytes::<B>()
+        })
+        .collect::<Vec<_>>();
+    let opened_row_bytes = chunk_sizes.iter().sum::<usize>();
+    let leaf_tree_depth = (rows_per_leaf * padded_chunks).max(2).trailing_zeros() as u8;
+
+    // recompute the opened leaves from digests of the opened chunks
+    let hash_fn = H::hash_fn();
+    let mut leaves = Vec::with_capacity(leaf_positions.len());
+    let mut leaf_rows = Vec::with_capacity(leaf_positions.len());
+    for ((&leaf, values), paths) in leaf_positions
+        .iter()
+        .zip(opening.leaves.values.iter())
+        .zip(opening.chunk_paths.iter())
+    {
+        let rows = utils::get_queried_rows(leaf, rows_per_leaf, positions);
+        if values.len() != rows.len() * opened_row_bytes {
+            return Err(VerifierError::TraceQueryDeserializationFailed);
+        }
+
+        let mut chunk_indexes = Vec::with_capacity(rows.len() * chunks.len());
+        let mut digests = Vec::with_capacity(rows.len() * chunks.len());
+        let mut chunk_values = values.as_slice();
+        for &row in rows.iter() {
+            for (&chunk, &chunk_size) in chunks.iter().zip(chunk_sizes.iter()) {
+                let mut digest = [0u8; 32];
+                hash_fn(&chunk_values[..chunk_size], &mut digest);
+                digests.push(digest);
+                chunk_indexes.push(row * padded_chunks + chunk);
+                chunk_values = &chunk_values[chunk_size..];
+            }
+        }
+
+        let chunk_proof = BatchMerkleProof {
+            values: digests,
+            nodes: paths.clone(),
+            depth: leaf_tree_depth,
+        };
+        let leaf_root = chunk_proof
+            .get_root(&chunk_indexes, hash_fn)
+            .ok_or(VerifierError::TraceQueryDoesNotMatchCommitment)?;
+        leaves.push(leaf_root);
+        leaf_rows.push(rows);
+    }
+
+    // make sure the recomputed leaves are consistent with the trace commitment
+    let trace_proof = BatchMerkleProof {
+        values: leaves,
+        nodes: opening.leaves.paths.clone(),
+        depth: layout.num_leaves.trailing_zeros() as u8,
+    };
+    if !MerkleTree::verify_batch(trace_root, &leaf_positions, &trace_proof, hash_fn) {
+        return Err(VerifierError::TraceQueryDoesNotMatchCommitment);
+    }
+
+    // read opened values at every position
+    let mut result = Vec::with_capacity(positions.len());
+    for &position in positions.iter() {
+        let (leaf_idx, row) =
+            utils::locate_in_leaves(position, &leaf_positions, rows_per_leaf).unwrap();
+        let row_idx = leaf_rows[leaf_idx].binary_search(&row).unwrap();
+        let start = row_idx * opened_row_bytes;
+        let row_bytes = &opening.leaves.values[leaf_idx][start..start + opened_row_bytes];
+        let values = layout
+            .encoding
+            .decode_elements(row_bytes)
+            .map_err(|_| VerifierError::TraceQueryDeserializationFailed)?;
+        result.push(values);
+    }
+
+    Ok(result)
+}
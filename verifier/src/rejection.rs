@@ -0,0 +1,192 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{
+    errors::VerifierError, proof::StarkProof, Air, ByteWriter, HashFunction, ProofOptions,
+    Serializable,
+};
+use crypto::hash;
+use math::field::FieldElement;
+use std::sync::RwLock;
+
+// CONSTANTS
+// ================================================================================================
+
+const DOMAIN_SEPARATOR: &[u8] = b"WINTERFELL_STATEMENT";
+
+/// Hook invoked for every rejected proof; set via [set_rejection_hook()].
+#[allow(clippy::type_complexity)]
+static REJECTION_HOOK: RwLock<Option<Box<dyn Fn(&RejectionRecord) + Send + Sync>>> =
+    RwLock::new(None);
+
+// REJECTION RECORD
+// ================================================================================================
+
+/// Description of a proof rejected by the verifier.
+///
+/// A record is passed to the hook installed via [set_rejection_hook()] every time a proof is
+/// rejected by one of the verification functions of this crate. Records are meant for
+/// operational monitoring: e.g., a gateway which accepts proofs from untrusted parties can log
+/// them to detect abuse, or to detect provers configured with options the gateway does not
+/// accept.
+pub struct RejectionRecord<'a> {
+    /// Digest of the statement the proof was verified against; see [statement_digest()]. This
+    /// is None if the proof was rejected before the AIR for the statement could be instantiated.
+    pub statement_digest: Option<[u8; 32]>,
+    /// The check which failed.
+    pub error: &'a VerifierError,
+    /// Size of the serialized proof in bytes.
+    pub proof_size: usize,
+    /// Version of the proof layout.
+    pub proof_version: u8,
+    /// Options declared in the proof.
+    pub options: &'a ProofOptions,
+    /// Length of the execution trace declared in the proof; this is None if the declared trace
+    /// length cannot be represented by `usize`.
+    pub trace_length: Option<usize>,
+}
+
+// REJECTION HOOK
+// ================================================================================================
+
+/// Installs a hook which is invoked with a [RejectionRecord] every time a proof is rejected,
+/// replacing the previously installed hook, if any.
+///
+/// The hook is global: it is invoked for proofs rejected by any of the verification functions
+/// in any thread, and thus, rejected proofs can be monitored without wrapping every call site.
+/// When no hook is installed, verification does not incur any additional work. Otherwise,
+/// every verified proof is measured and, once its AIR is instantiated, its statement digest is
+/// computed before verification starts.
+///
+/// The hook is invoked while a lock on it is held; thus, the hook must not install or remove
+/// hooks itself.
+pub fn set_rejection_hook<F>(hook: F)
+where
+    F: Fn(&RejectionRecord) + Send + Sync + 'static,
+{
+    *REJECTION_HOOK
+        .write()
+        .unwrap_or_else(|err| err.into_inner()) = Some(Box::new(hook));
+}
+
+/// Removes the hook installed via [set_rejection_hook()], and returns it.
+#[allow(clippy::type_complexity)]
+pub fn take_rejection_hook() -> Option<Box<dyn Fn(&RejectionRecord) + Send + Sync>> {
+    REJECTION_HOOK
+        .write()
+        .unwrap_or_else(|err| err.into_inner())
+        .take()
+}
+
+// STATEMENT DIGEST
+// ================================================================================================
+
+/// Returns a digest of the statement described by the specified instance of an AIR.
+///
+/// Public inputs of a computation are enforced via assertions against the execution trace;
+/// thus, the digest is computed from the identifier of the AIR (if set), dimensions of the
+/// execution trace, and all assertions of the AIR using the hash function specified in proof
+/// options. Proofs verified against the same AIR and the same public inputs have the same
+/// statement digest.
+pub fn statement_digest<A: Air>(air: &A) -> [u8; 32] {
+    let context = air.context();
+    let mut bytes = DOMAIN_SEPARATOR.to_vec();
+    match context.air_id() {
+        Some(air_id) => {
+            bytes.write_u8(1);
+            bytes.write_u8_slice(&air_id);
+        }
+        None => bytes.write_u8(0),
+    }
+    bytes.write_u64(context.trace_width() as u64);
+    bytes.write_u64(context.trace_length() as u64);
+
+    let assertions = air.get_assertions();
+    bytes.write_u64(assertions.len() as u64);
+    for assertion in assertions.iter() {
+        bytes.write_u64(assertion.register() as u64);
+        bytes.write_u64(assertion.first_step() as u64);
+        bytes.write_u64(assertion.stride() as u64);
+        bytes.write_u64(assertion.values().len() as u64);
+        bytes.write_u8_slice(A::BaseElement::elements_as_bytes(assertion.values()));
+    }
+
+    let mut digest = [0u8; 32];
+    match context.options().hash_fn() {
+        HashFunction::Blake3_256 => hash::blake3(&bytes, &mut digest),
+        HashFunction::Sha3_256 => hash::sha3(&bytes, &mut digest),
+    }
+    digest
+}
+
+// PROOF DETAILS
+// ================================================================================================
+
+/// Details of a proof captured before the proof is verified, so that the proof can be reported
+/// to the rejection hook if it is rejected.
+pub(crate) struct ProofDetails {
+    statement_digest: Option<[u8; 32]>,
+    proof_size: usize,
+    proof_version: u8,
+    options: ProofOptions,
+    trace_length: Option<usize>,
+}
+
+impl ProofDetails {
+    /// Returns details of the specified `proof`, or None if no rejection hook is installed.
+    pub fn capture(proof: &StarkProof) -> Option<Self> {
+        let hook = REJECTION_HOOK.read().unwrap_or_else(|err| err.into_inner());
+        hook.as_ref()?;
+
+        // the proof has not been validated yet, and thus, the declared LDE domain depth may be
+        // arbitrarily large
+        let trace_length = 1usize
+            .checked_shl(proof.context.lde_domain_depth as u32)
+            .map(|lde_domain_size| lde_domain_size / proof.options().blowup_factor());
+
+        let mut counter = ByteCounter(0);
+        proof.write_into(&mut counter);
+        Some(ProofDetails {
+            statement_digest: None,
+            proof_size: counter.0,
+            proof_version: proof.version(),
+            options: proof.options().clone(),
+            trace_length,
+        })
+    }
+
+    /// Records the digest of the statement described by the specified `air`.
+    pub fn set_statement<A: Air>(&mut self, air: &A) {
+        self.statement_digest = Some(statement_digest(air));
+    }
+
+    /// Passes a record of the rejected proof to the rejection hook, if one is still installed.
+    pub fn report(self, error: &VerifierError) {
+        let hook = REJECTION_HOOK.read().unwrap_or_else(|err| err.into_inner());
+        if let Some(hook) = hook.as_ref() {
+            hook(&RejectionRecord {
+                statement_digest: self.statement_digest,
+                error,
+                proof_size: self.proof_size,
+                proof_version: self.proof_version,
+                options: &self.options,
+                trace_length: self.trace_length,
+            });
+        }
+    }
+}
+
+/// A writer which counts the number of bytes written into it, and discards the bytes.
+struct ByteCounter(usize);
+
+impl ByteWriter for ByteCounter {
+    fn write_u8(&mut self, _value: u8) {
+        self.0 += 1;
+    }
+
+    fn write_u8_slice(&mut self, values: &[u8]) {
+        self.0 += values.len();
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{proof::StarkProof, FieldExtension, HashFunction};
+use math::field::StarkField;
+
+// VERIFICATION REPORT
+// ================================================================================================
+
+/// Parameters of a proof accepted by the verifier.
+///
+/// A report is returned by [verify_with_report()](crate::verify_with_report) only after the
+/// proof has been verified successfully, and thus, it describes what a relying party has actually
+/// accepted. This can be used to log accepted proofs, or to enforce policies such as a minimum
+/// security level, on top of the verification itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerificationReport {
+    /// Conjectured security level of the proof in bits.
+    pub conjectured_security: u32,
+    /// Proven security level of the proof in bits.
+    pub proven_security: u32,
+    /// Hash function used to build commitments.
+    pub hash_fn: HashFunction,
+    /// Hash function used to derive the Fiat-Shamir transcript.
+    pub transcript_hash_fn: HashFunction,
+    /// Modulus of the base field in little-endian byte order.
+    pub field_modulus: Vec<u8>,
+    /// Number of bits in the modulus of the base field.
+    pub field_modulus_bits: u32,
+    /// Extension of the base field in which the proof was generated.
+    pub field_extension: FieldExtension,
+    /// Length of the execution trace of the computation.
+    pub trace_length: usize,
+}
+
+impl VerificationReport {
+    /// Returns a report describing the specified `proof` generated over base field `B`.
+    pub(crate) fn new<B: StarkField>(proof: &StarkProof) -> Self {
+        let options = proof.options();
+        VerificationReport {
+            conjectured_security: proof.security_level(true),
+            proven_security: proof.security_level(false),
+            hash_fn: options.hash_fn(),
+            transcript_hash_fn: options.transcript_hash_fn(),
+            field_modulus: B::get_modulus_le_bytes(),
+            field_modulus_bits: B::MODULUS_BITS,
+            field_extension: options.field_extension(),
+            trace_length: proof.trace_length(),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::EvaluationFrame;
+use core::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+};
+use math::field::{ExtensionOf, FieldElement, StarkField};
+
+// VERIFIER SCRATCH SPACE
+// ================================================================================================
+
+/// Reusable buffers for intermediate values computed during proof verification.
+///
+/// Verifying a proof requires several vectors which grow with the number of queries and the
+/// width of the execution trace. When a scratch space is passed to
+/// [verify_with_scratch()](crate::verify_with_scratch), these vectors are taken from the scratch
+/// space rather than allocated anew; a buffer is re-allocated only if its capacity is not
+/// sufficient to hold the values for a given proof. Thus, once a scratch space has been used to
+/// verify a proof, verifying other proofs of the same (or smaller) shape does not allocate
+/// memory for these buffers.
+///
+/// Buffers for the base field and for each of the extension fields are kept separately because
+/// the field in which a proof is verified is determined by the proof options. Buffers for a given
+/// field are allocated when a proof is verified in this field for the first time.
+///
+/// Only the verifier's own per-query work is covered by the scratch space. Instantiating the
+/// AIR, building constraint groups, verifying Merkle authentication paths, and verifying the
+/// FRI proof still allocate memory on the heap; thus, verification with a scratch space is not
+/// allocation-free.
+pub struct VerifierScratch<B: StarkField> {
+    trace_width: usize,
+    num_transition_constraints: usize,
+    num_queries: usize,
+    buffers: Vec<(TypeId, Box<dyn Any + Send + Sync>)>,
+    _base: PhantomData<B>,
+}
+
+impl<B: StarkField> VerifierScratch<B> {
+    /// Returns a new scratch space with all buffers empty; the buffers will be sized during the
+    /// first verification performed with this scratch space.
+    pub fn new() -> Self {
+        Self::with_capacity(0, 0, 0)
+    }
+
+    /// Returns a new scratch space with buffers pre-allocated for verifying proofs of
+    /// computations with the specified trace width and number of transition constraints, with
+    /// the specified number of queries.
+    ///
+    /// Buffers are allocated with this capacity once the field in which a proof is verified
+    /// becomes known.
+    pub fn with_capacity(
+        trace_width: usize,
+        num_transition_constraints: usize,
+        num_queries: usize,
+    ) -> Self {
+        VerifierScratch {
+            trace_width,
+            num_transition_constraints,
+            num_queries,
+            buffers: Vec::new(),
+            _base: PhantomData,
+        }
+    }
+
+    /// Returns buffers for verifying proofs in field `E`, allocating them if this scratch space
+    /// has not been used for this field before.
+    pub(crate) fn buffers<E: ExtensionOf<B>>(&mut self) -> &mut ScratchBuffers<B, E> {
+        let type_id = TypeId::of::<E>();
+        let index = match self.buffers.iter().position(|(id, _)| *id == type_id) {
+            Some(index) => index,
+            None => {
+                let buffers = ScratchBuffers::<B, E>::new(
+                    self.trace_width,
+                    self.num_transition_constraints,
+                    self.num_queries,
+                );
+                self.buffers.push((type_id, Box::new(buffers)));
+                self.buffers.len() - 1
+            }
+        };
+        self.buffers[index]
+            .1
+            .downcast_mut()
+            .expect("scratch buffers stored under a wrong field type")
+    }
+}
+
+impl<B: StarkField> Default for VerifierScratch<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// SCRATCH BUFFERS
+// ================================================================================================
+
+/// Buffers used for verifying a proof in a specific field `E`.
+pub(crate) struct ScratchBuffers<B: StarkField, E: FieldElement + From<B>> {
+    /// Trace states at the queried positions; states are stored one after the other.
+    pub trace_states: Vec<B>,
+    /// Indexes of the trace commitment leaves which contain the queried states.
+    pub trace_positions: Vec<usize>,
+    /// Constraint evaluations at the queried positions.
+    pub constraint_evaluations: Vec<E>,
+    /// Indexes of the constraint commitment leaves which contain the queried evaluations.
+    pub constraint_positions: Vec<usize>,
+    /// Pseudo-random positions at which the proof was queried.
+    pub query_positions: Vec<usize>,
+    /// LDE domain coordinates of the queried positions.
+    pub x_coordinates: Vec<B>,
+    /// Trace states at the out-of-domain points.
+    pub ood_frame: EvaluationFrame<E>,
+    /// Conjugates of the trace state at the first out-of-domain point.
+    pub ood_conjugates: Vec<E>,
+    /// Transition constraint evaluations at the out-of-domain point.
+    pub t_evaluations: Vec<E>,
+    /// Values of periodic columns at the out-of-domain point.
+    pub periodic_values: Vec<E>,
+    /// DEEP composition polynomial evaluations at the queried positions.
+    pub evaluations: Vec<E>,
+    /// Evaluations of extra polynomials at the queried positions; evaluations are stored one
+    /// row after the other.
+    pub extra_states: Vec<B>,
+    /// Indexes of the extra polynomial commitment leaves which contain the queried evaluations.
+    pub extra_positions: Vec<usize>,
+    /// Evaluations of extra polynomials at the out-of-domain point.
+    pub ood_extra: Vec<E>,
+    /// Conjugates of evaluations of extra polynomials at the out-of-domain point.
+    pub ood_extra_conjugates: Vec<E>,
+}
+
+impl<B: StarkField, E: FieldElement + From<B>> ScratchBuffers<B, E> {
+    fn new(trace_width: usize, num_transition_constraints: usize, num_queries: usize) -> Self {
+        ScratchBuffers {
+            trace_states: Vec::with_capacity(trace_width * num_queries),
+            trace_positions: Vec::with_capacity(num_queries),
+            constraint_evaluations: Vec::with_capacity(num_queries),
+            constraint_positions: Vec::with_capacity(num_queries),
+            query_positions: Vec::with_capacity(num_queries),
+            x_coordinates: Vec::with_capacity(num_queries),
+            ood_frame: EvaluationFrame {
+                current: Vec::with_capacity(trace_width),
+                next: Vec::with_capacity(trace_width),
+            },
+            ood_conjugates: Vec::with_capacity(trace_width),
+            t_evaluations: Vec::with_capacity(num_transition_constraints),
+            periodic_values: Vec::new(),
+            evaluations: Vec::with_capacity(num_queries),
+            extra_states: Vec::new(),
+            extra_positions: Vec::new(),
+            ood_extra: Vec::new(),
+            ood_extra_conjugates: Vec::new(),
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Sets the length of the `buffer` to `len` and fills it with zeros; the buffer is re-allocated
+/// only if its capacity is smaller than `len`.
+pub(crate) fn reset<E: FieldElement>(buffer: &mut Vec<E>, len: usize) {
+    buffer.clear();
+    buffer.resize(len, E::ZERO);
+}
@@ -0,0 +1,247 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{build_fib_air, build_proof_options, prove_fib, RecordingObserver};
+use crate::{
+    derive_queries, export_transcript, extract_challenges, recompute_commitment_roots,
+    VerifierError,
+};
+use common::{Air, FieldExtension, HashFunction, ProofOptions};
+use math::field::{f128::BaseElement, FieldElement, QuadExtension};
+use prover::NullObserver;
+
+// QUERY DERIVATION
+// ================================================================================================
+
+#[test]
+fn derive_queries_from_proof() {
+    let mut observer = RecordingObserver::default();
+    let (proof, pub_inputs) = prove_fib(1024, build_proof_options(false), &mut observer);
+
+    // query positions derived from the proof must match the positions used by the prover
+    let air = build_fib_air(&proof, pub_inputs.clone());
+    let transcript = derive_queries::<BaseElement>(&proof, air.context()).unwrap();
+    assert_eq!(observer.query_seed, transcript.query_seed);
+    let positions = transcript
+        .queries
+        .iter()
+        .map(|q| q.position)
+        .collect::<Vec<_>>();
+    assert_eq!(observer.positions, positions);
+
+    // every query must point to leaves of the commitments included in the proof
+    // the last FRI root commits to the remainder, which is sent in full and is not queried
+    let num_fri_layers = proof.commitments.fri_roots.len() - 1;
+    assert!(num_fri_layers > 0);
+    for query in transcript.queries.iter() {
+        assert_eq!(proof.commitments.trace_root, query.trace.root);
+        assert_eq!(query.position, query.trace.leaf_index);
+        assert_eq!(proof.commitments.constraint_root, query.constraints.root);
+        assert_eq!(num_fri_layers, query.fri_layers.len());
+        for (opening, root) in query.fri_layers.iter().zip(&proof.commitments.fri_roots) {
+            assert_eq!(*root, opening.root);
+        }
+    }
+
+    // query positions must depend on the contents of the FRI remainder
+    let mut tampered_proof = proof.clone();
+    tampered_proof.fri_proof.rem_values[0] ^= 1;
+    let tampered_transcript =
+        derive_queries::<BaseElement>(&tampered_proof, air.context()).unwrap();
+    assert_ne!(transcript.query_seed, tampered_transcript.query_seed);
+
+    // the proof was generated without grinding, so its proof-of-work is not valid when the
+    // context requires grinding
+    let mut proof = proof;
+    proof.context.options =
+        ProofOptions::new(28, 16, 8, HashFunction::Blake3_256, FieldExtension::None);
+    let air = build_fib_air(&proof, pub_inputs);
+    assert!(derive_queries::<BaseElement>(&proof, air.context()).is_err());
+}
+
+#[test]
+fn derive_queries_with_extra_fri_queries() {
+    let mut observer = RecordingObserver::default();
+    let options = build_proof_options(false).with_num_fri_queries(40);
+    let (proof, pub_inputs) = prove_fib(1024, options, &mut observer);
+
+    // trace queries must be followed by FRI-only queries, and together they must match the
+    // positions used by the prover
+    let air = build_fib_air(&proof, pub_inputs);
+    let transcript = derive_queries::<BaseElement>(&proof, air.context()).unwrap();
+    assert_eq!(28, transcript.queries.len());
+    assert_eq!(12, transcript.fri_queries.len());
+    let positions = transcript
+        .queries
+        .iter()
+        .map(|q| q.position)
+        .chain(transcript.fri_queries.iter().map(|q| q.position))
+        .collect::<Vec<_>>();
+    assert_eq!(observer.positions, positions);
+
+    let num_fri_layers = proof.commitments.fri_roots.len() - 1;
+    for query in transcript.fri_queries.iter() {
+        assert_eq!(num_fri_layers, query.fri_layers.len());
+    }
+}
+
+// ROOT RECOMPUTATION
+// ================================================================================================
+
+#[test]
+fn recompute_roots_from_proof() {
+    let options = [
+        build_proof_options(false),
+        build_proof_options(true).with_trace_leaf_batching(8),
+    ];
+    for options in options.iter() {
+        let (proof, pub_inputs) = prove_fib(1024, options.clone(), &mut NullObserver);
+        let air = build_fib_air(&proof, pub_inputs);
+
+        // roots recomputed from the queried data must match the commitments in the proof
+        let roots = recompute_commitment_roots::<BaseElement>(&proof, air.context()).unwrap();
+        assert_eq!(proof.commitments.trace_root, roots.trace_root);
+        assert_eq!(proof.commitments.constraint_root, roots.constraint_root);
+
+        // tampering with a queried trace row changes only the recomputed trace root
+        let mut tampered_proof = proof.clone();
+        tampered_proof.trace_queries.values[0][0] ^= 1;
+        let tampered_roots =
+            recompute_commitment_roots::<BaseElement>(&tampered_proof, air.context()).unwrap();
+        assert_ne!(roots.trace_root, tampered_roots.trace_root);
+        assert_eq!(roots.constraint_root, tampered_roots.constraint_root);
+
+        // truncated authentication paths do not resolve to a root
+        let mut tampered_proof = proof.clone();
+        tampered_proof.constraint_queries.paths[0].clear();
+        assert!(matches!(
+            recompute_commitment_roots::<BaseElement>(&tampered_proof, air.context()),
+            Err(VerifierError::ConstraintQueryDeserializationFailed)
+        ));
+    }
+}
+
+// CHALLENGE EXTRACTION
+// ================================================================================================
+
+#[test]
+fn extract_challenges_from_proof() {
+    type E = QuadExtension<BaseElement>;
+
+    for &use_extension in [false, true].iter() {
+        let mut observer = RecordingObserver::default();
+        let (proof, pub_inputs) = prove_fib(64, build_proof_options(use_extension), &mut observer);
+        let air = build_fib_air(&proof, pub_inputs);
+
+        // challenges must be extracted in the field in which the proof was generated
+        let (z, query_seed, num_alphas) = if use_extension {
+            assert!(extract_challenges::<BaseElement, BaseElement>(&proof, air.context()).is_err());
+            let challenges = extract_challenges::<BaseElement, E>(&proof, air.context()).unwrap();
+            let z = E::elements_as_bytes(&[challenges.ood_point]).to_vec();
+            (z, challenges.query_seed, challenges.fri_alphas.len())
+        } else {
+            assert!(extract_challenges::<BaseElement, E>(&proof, air.context()).is_err());
+            let challenges =
+                extract_challenges::<BaseElement, BaseElement>(&proof, air.context()).unwrap();
+            let z = BaseElement::elements_as_bytes(&[challenges.ood_point]).to_vec();
+            (z, challenges.query_seed, challenges.fri_alphas.len())
+        };
+
+        // extracted challenges must match the values drawn by the prover
+        assert_eq!(observer.z, z);
+        assert_eq!(observer.query_seed, query_seed);
+        assert_eq!(proof.commitments.fri_roots.len() - 1, num_alphas);
+    }
+}
+
+// TRANSCRIPT EXPORT
+// ================================================================================================
+
+#[test]
+fn export_transcript_from_proof() {
+    let mut observer = RecordingObserver::default();
+    let options = build_proof_options(false).with_num_fri_queries(32);
+    let (proof, pub_inputs) = prove_fib(64, options, &mut observer);
+    let air = build_fib_air(&proof, pub_inputs);
+
+    let transcript = export_transcript(&proof, &air).unwrap();
+    let labels = transcript
+        .steps
+        .iter()
+        .map(|step| step.label.as_str())
+        .collect::<Vec<_>>();
+    let num_fri_layers = proof.commitments.fri_roots.len() - 1;
+    let mut expected_labels = vec![
+        "constraint_seed",
+        "transition_coefficients",
+        "boundary_coefficients",
+        "ood_point",
+        "composition_coefficients",
+    ];
+    let fri_labels = (0..num_fri_layers)
+        .map(|i| format!("fri_alpha_{}", i))
+        .collect::<Vec<_>>();
+    expected_labels.extend(fri_labels.iter().map(|label| label.as_str()));
+    expected_labels.extend_from_slice(&["fri_seed", "query_seed", "query_positions"]);
+    assert_eq!(expected_labels, labels);
+
+    // every step must record the commitments it absorbs, and the values the prover drew
+    let step = |label: &str| transcript.steps.iter().find(|s| s.label == label).unwrap();
+    assert_eq!(
+        proof.commitments.trace_root.to_vec(),
+        step("constraint_seed").absorbed
+    );
+    assert_eq!(2 * 2, step("transition_coefficients").squeezed.len());
+    assert_eq!(3 * 2, step("boundary_coefficients").squeezed.len());
+    assert_eq!(
+        proof.commitments.constraint_root.to_vec(),
+        step("ood_point").absorbed
+    );
+    assert_eq!(vec![observer.z.clone()], step("ood_point").squeezed);
+    assert_eq!(2 * 3 + 3, step("composition_coefficients").squeezed.len());
+    assert_eq!(
+        proof.commitments.fri_roots[0].to_vec(),
+        step("fri_alpha_0").absorbed
+    );
+    assert_eq!(
+        observer.pow_nonce.to_le_bytes().to_vec(),
+        step("query_seed").absorbed
+    );
+    assert_eq!(
+        vec![observer.query_seed.to_vec()],
+        step("query_seed").squeezed
+    );
+    let positions = observer
+        .positions
+        .iter()
+        .map(|&p| (p as u64).to_le_bytes().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(32, positions.len());
+    assert_eq!(positions, step("query_positions").squeezed);
+
+    // the transcript is deterministic, and is encoded as JSON with hex-encoded values
+    assert_eq!(transcript, export_transcript(&proof, &air).unwrap());
+    let constraint_root = proof
+        .commitments
+        .constraint_root
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    assert!(transcript.to_json().contains(&format!(
+        "\"label\": \"ood_point\", \"absorbed\": \"{}\"",
+        constraint_root
+    )));
+
+    // tampering with the FRI remainder changes the steps which depend on it
+    let mut tampered_proof = proof.clone();
+    tampered_proof.fri_proof.rem_values[0] ^= 1;
+    let tampered = export_transcript(&tampered_proof, &air).unwrap();
+    let num_unchanged = transcript.steps.len() - 3;
+    assert_eq!(
+        transcript.steps[..num_unchanged],
+        tampered.steps[..num_unchanged]
+    );
+    assert_ne!(step("fri_seed"), &tampered.steps[num_unchanged]);
+}
@@ -0,0 +1,179 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{
+    proof::StarkProof, Air, Assertion, ByteWriter, ComputationContext, EvaluationFrame,
+    FieldExtension, HashFunction, ProofOptions, Serializable, TraceInfo,
+    TransitionConstraintDegree,
+};
+use math::field::{f128::BaseElement, FieldElement};
+use prover::{ExecutionTrace, ProverObserver};
+
+mod audit;
+mod scratch;
+mod verification;
+
+// FIBONACCI TRACE BUILDER
+// ================================================================================================
+
+/// Builds a trace of a Fibonacci sequence of the specified length which starts with the specified
+/// terms; each row of the trace holds two consecutive terms of the sequence.
+pub fn build_fib_trace(
+    sequence_length: usize,
+    start: [BaseElement; 2],
+) -> ExecutionTrace<BaseElement> {
+    let mut trace = ExecutionTrace::new(2, sequence_length / 2);
+    trace.fill(
+        |state| {
+            state[0] = start[0];
+            state[1] = start[1];
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+    trace
+}
+
+/// Returns public inputs of [FibAir] for the specified trace built by [build_fib_trace()].
+pub fn build_fib_inputs(trace: &ExecutionTrace<BaseElement>) -> FibInputs {
+    FibInputs {
+        start: [trace.get(0, 0), trace.get(1, 0)],
+        result: trace.get(1, trace.len() - 1),
+    }
+}
+
+/// Generates a proof of computing a Fibonacci sequence of the specified length which starts with
+/// terms 1 and 1, and returns it together with the public inputs against which it verifies.
+pub fn prove_fib(
+    sequence_length: usize,
+    options: ProofOptions,
+    observer: &mut dyn ProverObserver,
+) -> (StarkProof, FibInputs) {
+    let trace = build_fib_trace(sequence_length, [BaseElement::ONE; 2]);
+    let pub_inputs = build_fib_inputs(&trace);
+    let proof = prover::Prover::<FibAir>::new(options)
+        .with_observer(observer)
+        .prove(trace, pub_inputs.clone())
+        .unwrap();
+    (proof, pub_inputs)
+}
+
+/// Instantiates [FibAir] for the specified proof.
+pub fn build_fib_air(proof: &StarkProof, pub_inputs: FibInputs) -> FibAir {
+    let trace_info = TraceInfo {
+        length: proof.trace_length(),
+        meta: vec![],
+    };
+    FibAir::new(trace_info, pub_inputs, proof.options().clone())
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// Public inputs of [FibAir]: the first two terms of the sequence and the expected value of the
+/// term at which the sequence terminates.
+#[derive(Clone)]
+pub struct FibInputs {
+    pub start: [BaseElement; 2],
+    pub result: BaseElement,
+}
+
+impl Serializable for FibInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        BaseElement::write_batch_into(&self.start, target);
+        target.write(&self.result);
+    }
+}
+
+/// AIR for the trace built by [build_fib_trace()].
+pub struct FibAir {
+    context: ComputationContext,
+    start: [BaseElement; 2],
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = FibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: FibInputs, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+        ];
+        FibAir {
+            context: ComputationContext::new(2, trace_info.length, degrees, options),
+            start: pub_inputs.start,
+            result: pub_inputs.result,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, self.start[0]),
+            Assertion::single(1, 0, self.start[1]),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+}
+
+// RECORDING OBSERVER
+// ================================================================================================
+
+/// Records values drawn by the prover from the transcript.
+#[derive(Default)]
+pub struct RecordingObserver {
+    pub z: Vec<u8>,
+    pub query_seed: [u8; 32],
+    pub pow_nonce: u64,
+    pub positions: Vec<usize>,
+}
+
+impl ProverObserver for RecordingObserver {
+    fn on_ood_point(&mut self, z: &[u8]) {
+        self.z = z.to_vec();
+    }
+
+    fn on_query_seed(&mut self, seed: &[u8; 32], pow_nonce: u64) {
+        self.query_seed = *seed;
+        self.pow_nonce = pow_nonce;
+    }
+
+    fn on_query_positions(&mut self, positions: &[usize]) {
+        self.positions = positions.to_vec();
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns options for proofs generated in end-to-end tests.
+pub fn build_proof_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 16, 0, HashFunction::Blake3_256, extension)
+}
@@ -0,0 +1,119 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{build_proof_options, prove_fib, FibAir, FibInputs};
+use crate::{StarkProof, VerifierScratch};
+use math::field::f128::BaseElement;
+use prover::NullObserver;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+// COUNTING ALLOCATOR
+// ================================================================================================
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+thread_local! {
+    /// Number of allocations made by the current thread; tests run concurrently, and thus,
+    /// allocations are counted per thread.
+    static NUM_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Allocator which counts heap allocations and re-allocations made by each thread.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn record_allocation() {
+    let _ = NUM_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn verify_with_warm_scratch_reuses_buffers() {
+    let (proof, pub_inputs) = prove_fib(64, build_proof_options(false), &mut NullObserver);
+
+    // verify the proof once with a throw-away scratch space so that one-time allocations made
+    // on the first verification in a thread (e.g., lazily initialized values) are not counted
+    count_verification_allocations(&proof, &pub_inputs, &mut VerifierScratch::new());
+
+    let mut scratch = VerifierScratch::<BaseElement>::new();
+
+    // the first verification sizes the buffers of the scratch space
+    let cold = count_verification_allocations(&proof, &pub_inputs, &mut scratch);
+    let buffers = scratch.buffers::<BaseElement>();
+    let pointers = [
+        buffers.trace_states.as_ptr() as usize,
+        buffers.constraint_evaluations.as_ptr() as usize,
+        buffers.query_positions.as_ptr() as usize,
+        buffers.x_coordinates.as_ptr() as usize,
+        buffers.evaluations.as_ptr() as usize,
+    ];
+
+    // verifying a proof of the same shape does not re-allocate any of these buffers
+    let warm = count_verification_allocations(&proof, &pub_inputs, &mut scratch);
+    let buffers = scratch.buffers::<BaseElement>();
+    assert_eq!(
+        pointers,
+        [
+            buffers.trace_states.as_ptr() as usize,
+            buffers.constraint_evaluations.as_ptr() as usize,
+            buffers.query_positions.as_ptr() as usize,
+            buffers.x_coordinates.as_ptr() as usize,
+            buffers.evaluations.as_ptr() as usize,
+        ]
+    );
+
+    // the remaining allocations are made outside of the scratch space (e.g., by the AIR, Merkle
+    // path and FRI verification), and do not depend on whether the scratch space is warm
+    assert!(warm < cold);
+    assert!(cold - warm >= pointers.len());
+    let fresh = count_verification_allocations(&proof, &pub_inputs, &mut VerifierScratch::new());
+    assert_eq!(cold, fresh);
+    assert_eq!(
+        warm,
+        count_verification_allocations(&proof, &pub_inputs, &mut scratch)
+    );
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Verifies the `proof` using the `scratch` space, and returns the number of allocations made
+/// during verification.
+fn count_verification_allocations(
+    proof: &StarkProof,
+    pub_inputs: &FibInputs,
+    scratch: &mut VerifierScratch<BaseElement>,
+) -> usize {
+    let (proof, pub_inputs) = (proof.clone(), pub_inputs.clone());
+    let before = NUM_ALLOCATIONS.with(|count| count.get());
+    crate::verify_with_scratch::<FibAir>(proof, pub_inputs, scratch).unwrap();
+    NUM_ALLOCATIONS.with(|count| count.get()) - before
+}
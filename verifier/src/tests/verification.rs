@@ -0,0 +1,396 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    build_fib_air, build_fib_inputs, build_fib_trace, build_proof_options, prove_fib, FibAir,
+    FibInputs,
+};
+use crate::{ProofShapeError, StarkProof, VerifierError, VerifierScratch};
+use common::{
+    Air, Assertion, ComputationContext, Deserializable, EvaluationFrame, FieldExtension,
+    HashFunction, ProofOptions, Serializable, TraceInfo, TraceRowHashing,
+};
+use math::field::{f128::BaseElement, from_evm_words, ByteEncoding, FieldElement, EVM_WORD_BYTES};
+use prover::NullObserver;
+use std::sync::{Arc, Mutex};
+
+// PROOF SHAPE
+// ================================================================================================
+
+#[test]
+fn verify_inconsistent_proof_shape() {
+    let (proof, pub_inputs) = prove_fib(64, build_proof_options(false), &mut NullObserver);
+    assert!(!proof.fri_proof.layers.is_empty());
+    let verify = |proof: StarkProof| crate::verify::<FibAir>(proof, pub_inputs.clone());
+    let assert_rejected = |proof: StarkProof, expected: ProofShapeError| match verify(proof) {
+        Err(VerifierError::InconsistentProof(error)) => assert_eq!(expected, error),
+        result => panic!("expected {:?}, but got {:?}", expected, result),
+    };
+    assert!(verify(proof.clone()).is_ok());
+
+    // LDE domain must be large enough for the blowup factor and must fit into the field
+    let mut bad_proof = proof.clone();
+    bad_proof.context.lde_domain_depth = 6;
+    assert_rejected(
+        bad_proof,
+        ProofShapeError::LdeDomainDepthOutOfRange(6, 7, 40),
+    );
+    let mut bad_proof = proof.clone();
+    bad_proof.context.lde_domain_depth = 200;
+    assert_rejected(
+        bad_proof,
+        ProofShapeError::LdeDomainDepthOutOfRange(200, 7, 40),
+    );
+
+    // declared CE blowup factor and field modulus must match the computation
+    let mut bad_proof = proof.clone();
+    bad_proof.context.ce_blowup_factor *= 2;
+    assert_rejected(bad_proof, ProofShapeError::CeBlowupFactorMismatch(4, 2));
+    let mut bad_proof = proof.clone();
+    bad_proof.context.field_modulus_bytes[0] ^= 1;
+    assert_rejected(bad_proof, ProofShapeError::FieldModulusMismatch);
+
+    // FRI commitments and queries must match the number of layers implied by the LDE domain
+    let num_fri_layers = proof.fri_proof.layers.len();
+    let mut bad_proof = proof.clone();
+    bad_proof.commitments.fri_roots.push([0; 32]);
+    let expected =
+        ProofShapeError::FriCommitmentCountMismatch(num_fri_layers + 2, num_fri_layers + 1);
+    assert_rejected(bad_proof, expected);
+    let mut bad_proof = proof.clone();
+    bad_proof.fri_proof.layers.pop();
+    let expected = ProofShapeError::FriLayerCountMismatch(num_fri_layers - 1, num_fri_layers);
+    assert_rejected(bad_proof, expected);
+    let mut bad_proof = proof.clone();
+    let depth = bad_proof.fri_proof.layers[0].depth;
+    bad_proof.fri_proof.layers[0].depth += 1;
+    let expected = ProofShapeError::FriLayerDepthMismatch(0, depth + 1, depth);
+    assert_rejected(bad_proof, expected);
+
+    // FRI remainder must contain exactly the number of elements implied by the LDE domain
+    let mut bad_proof = proof;
+    let remainder_bytes = bad_proof.fri_proof.rem_values.len();
+    bad_proof.fri_proof.rem_values.truncate(remainder_bytes / 2);
+    let expected = ProofShapeError::FriRemainderSizeMismatch(remainder_bytes / 2, remainder_bytes);
+    assert_rejected(bad_proof, expected);
+}
+
+#[test]
+fn verify_invalid_column_layout() {
+    /// [FibAir] which places both registers into the same column of commitment rows.
+    struct BadLayoutAir(FibAir);
+
+    impl Air for BadLayoutAir {
+        type BaseElement = BaseElement;
+        type PublicInputs = FibInputs;
+
+        fn new(trace_info: TraceInfo, pub_inputs: FibInputs, options: ProofOptions) -> Self {
+            BadLayoutAir(FibAir::new(trace_info, pub_inputs, options))
+        }
+
+        fn context(&self) -> &ComputationContext {
+            self.0.context()
+        }
+
+        fn get_assertions(&self) -> Vec<Assertion<BaseElement>> {
+            self.0.get_assertions()
+        }
+
+        fn evaluate_transition<E: FieldElement + From<BaseElement>>(
+            &self,
+            frame: &EvaluationFrame<E>,
+            periodic_values: &[E],
+            result: &mut [E],
+        ) {
+            self.0.evaluate_transition(frame, periodic_values, result)
+        }
+
+        fn get_column_layout(&self) -> Vec<usize> {
+            vec![0, 0]
+        }
+    }
+
+    // a malformed column layout is reported as an error rather than a panic
+    let (proof, pub_inputs) = prove_fib(16, build_proof_options(false), &mut NullObserver);
+    let result = crate::verify::<BadLayoutAir>(proof, pub_inputs);
+    assert!(matches!(result, Err(VerifierError::InvalidColumnLayout(2))));
+}
+
+#[test]
+fn verify_ood_frame_in_extension_field() {
+    let (mut proof, pub_inputs) = prove_fib(16, build_proof_options(true), &mut NullObserver);
+
+    // an out-of-domain frame evaluated in the base field is rejected when options specify an
+    // extension; elements of the quadratic extension take twice as many bytes
+    let num_base_bytes = proof.ood_frame.trace_at_z1.len() / 2;
+    proof.ood_frame.trace_at_z1.truncate(num_base_bytes);
+    let result = crate::verify::<FibAir>(proof, pub_inputs);
+    assert!(matches!(
+        result,
+        Err(VerifierError::OodFrameNotInExtensionField)
+    ));
+}
+
+#[test]
+fn verify_transcript_hash_fn_mismatch() {
+    let (proof, _) = prove_fib(64, build_proof_options(false), &mut NullObserver);
+    let options = build_proof_options(false).with_transcript_hash_fn(HashFunction::Sha3_256);
+    let (sha3_proof, pub_inputs) = prove_fib(64, options, &mut NullObserver);
+
+    // the transcript hash function does not affect commitments to the execution trace, but it
+    // does affect all values drawn from the transcript
+    assert_eq!(
+        proof.commitments.trace_root,
+        sha3_proof.commitments.trace_root
+    );
+    assert_ne!(
+        proof.commitments.constraint_root,
+        sha3_proof.commitments.constraint_root
+    );
+
+    // the transcript hash function is serialized in the proof
+    let proof_bytes = sha3_proof.to_bytes();
+    let sha3_proof = StarkProof::read_from_bytes(&proof_bytes).unwrap();
+    assert!(matches!(
+        sha3_proof.options().transcript_hash_fn(),
+        HashFunction::Sha3_256
+    ));
+    assert!(matches!(
+        sha3_proof.options().hash_fn(),
+        HashFunction::Blake3_256
+    ));
+
+    // a proof claiming a different transcript hash function than the one it was generated with
+    // does not verify
+    let mut proof = sha3_proof.clone();
+    proof.context.options = build_proof_options(false);
+    assert!(crate::verify::<FibAir>(proof, pub_inputs.clone()).is_err());
+    crate::verify::<FibAir>(sha3_proof, pub_inputs).unwrap();
+}
+
+// PROOF VERSION
+// ================================================================================================
+
+#[test]
+fn verify_unsupported_proof_version() {
+    let (proof, pub_inputs) = prove_fib(16, build_proof_options(false), &mut NullObserver);
+    assert_eq!(crate::PROOF_VERSION, proof.version());
+
+    // proofs of unsupported versions are rejected before anything else is checked
+    for version in [0, crate::LEGACY_PROOF_VERSION - 1, crate::PROOF_VERSION + 1] {
+        let mut bad_proof = proof.clone();
+        bad_proof.context.version = version;
+        let proof_bytes = bad_proof.to_bytes();
+        let bad_proof = StarkProof::read_from_bytes(&proof_bytes).unwrap();
+        assert!(matches!(
+            crate::verify::<FibAir>(bad_proof, pub_inputs.clone()),
+            Err(VerifierError::UnsupportedProofVersion(v)) if v == version
+        ));
+    }
+    crate::verify::<FibAir>(proof, pub_inputs).unwrap();
+}
+
+#[test]
+fn verify_legacy_proof_version() {
+    // the proof was generated by the previous revision of the prover for a sequence of 16 terms
+    // with 8 queries and blowup factor 8
+    let proof_bytes = include_bytes!("fixtures/fib_v16.bin");
+    let proof = StarkProof::read_from_bytes(proof_bytes).unwrap();
+    assert_eq!(crate::LEGACY_PROOF_VERSION, proof.version());
+    assert_eq!(ByteEncoding::Native, proof.options().byte_encoding());
+    assert_eq!(proof_bytes.to_vec(), proof.to_bytes());
+
+    let pub_inputs = build_fib_inputs(&build_fib_trace(16, [BaseElement::ONE; 2]));
+    crate::verify::<FibAir>(proof.clone(), pub_inputs.clone()).unwrap();
+
+    // the legacy proof is bound to its public inputs in the same way as current proofs
+    let mut bad_inputs = pub_inputs;
+    bad_inputs.result += BaseElement::ONE;
+    assert!(crate::verify::<FibAir>(proof, bad_inputs).is_err());
+}
+
+// BYTE ENCODING
+// ================================================================================================
+
+#[test]
+fn verify_evm_word_encoding() {
+    let base_options = build_proof_options(false).with_byte_encoding(ByteEncoding::EvmWords);
+    let option_sets = vec![
+        base_options.clone(),
+        build_proof_options(true).with_byte_encoding(ByteEncoding::EvmWords),
+        base_options.clone().with_compact_trace_queries(),
+        base_options.clone().with_trace_leaf_batching(4),
+        base_options.with_trace_row_hashing(TraceRowHashing::Chunked(1)),
+        ProofOptions::new(28, 16, 8, HashFunction::Blake3_256, FieldExtension::None)
+            .with_byte_encoding(ByteEncoding::EvmWords),
+    ];
+    for options in option_sets {
+        let (proof, pub_inputs) = prove_fib(64, options.clone(), &mut NullObserver);
+        let degree = options.field_extension().degree();
+
+        // the nonce is written as a 32-byte big-endian word followed by the difficulty byte
+        let proof_bytes = proof.to_bytes();
+        let nonce_start = proof_bytes.len() - EVM_WORD_BYTES - 1;
+        let nonce_word = &proof_bytes[nonce_start..proof_bytes.len() - 1];
+        assert_eq!([0u8; 24], nonce_word[..24]);
+        assert_eq!(proof.pow_nonce.to_be_bytes(), nonce_word[24..]);
+
+        // every coefficient of queried and out-of-domain values is encoded as an EVM word
+        let rows_per_leaf = options.trace_leaf_batching();
+        for leaf in proof.trace_queries.values.iter() {
+            assert_eq!(rows_per_leaf * 2 * EVM_WORD_BYTES, leaf.len());
+            assert!(from_evm_words::<BaseElement>(leaf).is_some());
+        }
+        assert_eq!(
+            2 * degree * EVM_WORD_BYTES,
+            proof.ood_frame.trace_at_z1.len()
+        );
+        assert!(from_evm_words::<BaseElement>(&proof.ood_frame.trace_at_z1).is_some());
+
+        // the proof survives serialization and verifies
+        let decoded = StarkProof::read_from_bytes(&proof_bytes).unwrap();
+        assert_eq!(proof_bytes, decoded.to_bytes());
+        crate::verify::<FibAir>(decoded, pub_inputs.clone()).unwrap();
+
+        // the encoding is bound to commitments, and thus, the same computation proven with the
+        // native encoding yields different commitments
+        let native_options = options.with_byte_encoding(ByteEncoding::Native);
+        let (native_proof, _) = prove_fib(64, native_options.clone(), &mut NullObserver);
+        assert_ne!(
+            native_proof.commitments.trace_root,
+            proof.commitments.trace_root
+        );
+        assert_ne!(
+            native_proof.commitments.constraint_root,
+            proof.commitments.constraint_root
+        );
+
+        // a proof claiming a different encoding than the one it was generated with does not
+        // verify
+        let mut bad_proof = proof.clone();
+        bad_proof.context.options = native_options;
+        assert!(crate::verify::<FibAir>(bad_proof, pub_inputs.clone()).is_err());
+
+        let num_mutations =
+            crate::mutation::check_mutations::<FibAir, _>(&proof, || pub_inputs.clone());
+        assert!(num_mutations >= 10);
+    }
+}
+
+// MUTATIONS
+// ================================================================================================
+
+#[test]
+fn verify_proof_mutations() {
+    let base_options = build_proof_options(false);
+    let option_sets = vec![
+        base_options.clone(),
+        build_proof_options(true),
+        base_options.clone().with_compact_trace_queries(),
+        base_options.with_trace_leaf_batching(4),
+        ProofOptions::new(28, 16, 8, HashFunction::Blake3_256, FieldExtension::None),
+    ];
+    for options in option_sets {
+        let (proof, pub_inputs) = prove_fib(64, options, &mut NullObserver);
+        let num_mutations =
+            crate::mutation::check_mutations::<FibAir, _>(&proof, || pub_inputs.clone());
+        assert!(num_mutations >= 10);
+    }
+}
+
+// SCRATCH SPACE
+// ================================================================================================
+
+#[test]
+fn verify_with_reused_scratch() {
+    // the same scratch space can be reused for proofs of different shapes and fields
+    let mut scratch = VerifierScratch::new();
+    for &(length, use_extension) in [(16, false), (64, true), (32, false), (16, true)].iter() {
+        let options = build_proof_options(use_extension);
+        let (proof, pub_inputs) = prove_fib(length, options, &mut NullObserver);
+        let result =
+            crate::verify_with_scratch::<FibAir>(proof.clone(), pub_inputs.clone(), &mut scratch);
+        assert!(result.is_ok());
+
+        let pub_inputs = FibInputs {
+            result: pub_inputs.result + BaseElement::ONE,
+            ..pub_inputs
+        };
+        let result = crate::verify_with_scratch::<FibAir>(proof, pub_inputs, &mut scratch);
+        assert!(result.is_err());
+    }
+}
+
+// REJECTION HOOK
+// ================================================================================================
+
+#[test]
+fn verify_with_rejection_hook() {
+    // starting terms are unique to this test so that proofs rejected by other tests running
+    // concurrently can be told apart by their statement digests
+    let start = [BaseElement::new(11), BaseElement::new(13)];
+    let trace = build_fib_trace(16, start);
+    let valid_inputs = build_fib_inputs(&trace);
+    let invalid_inputs = FibInputs {
+        result: valid_inputs.result + BaseElement::ONE,
+        ..valid_inputs.clone()
+    };
+    let proof =
+        prover::prove::<FibAir>(trace, valid_inputs.clone(), build_proof_options(false)).unwrap();
+    let valid_digest = crate::statement_digest(&build_fib_air(&proof, valid_inputs.clone()));
+    let invalid_digest = crate::statement_digest(&build_fib_air(&proof, invalid_inputs.clone()));
+    assert_ne!(valid_digest, invalid_digest);
+
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let hook_records = records.clone();
+    crate::set_rejection_hook(move |record| {
+        hook_records.lock().unwrap().push((
+            record.statement_digest,
+            record.error.to_string(),
+            record.proof_size,
+            record.options.hash_fn(),
+            record.trace_length,
+        ));
+    });
+    let count_records = |digest| {
+        let records = records.lock().unwrap();
+        records.iter().filter(|r| r.0 == digest).count()
+    };
+
+    // accepted proofs are not reported
+    assert!(crate::verify::<FibAir>(proof.clone(), valid_inputs.clone()).is_ok());
+    assert_eq!(0, count_records(Some(valid_digest)));
+
+    // rejected proofs are reported together with the statement they were verified against
+    let error = crate::verify::<FibAir>(proof.clone(), invalid_inputs.clone()).unwrap_err();
+    assert_eq!(1, count_records(Some(invalid_digest)));
+    {
+        let records = records.lock().unwrap();
+        let record = records
+            .iter()
+            .find(|r| r.0 == Some(invalid_digest))
+            .unwrap();
+        assert_eq!(error.to_string(), record.1);
+        assert_eq!(proof.to_bytes().len(), record.2);
+        assert_eq!(proof.options().hash_fn(), record.3);
+        assert_eq!(Some(proof.trace_length()), record.4);
+    }
+
+    // proofs rejected before the AIR is instantiated are reported without a statement digest
+    let mut malformed_proof = proof.clone();
+    malformed_proof.context.lde_domain_depth = 100;
+    let error = crate::verify::<FibAir>(malformed_proof, valid_inputs).unwrap_err();
+    assert!({
+        let records = records.lock().unwrap();
+        records
+            .iter()
+            .any(|r| r.0.is_none() && r.1 == error.to_string())
+    });
+
+    // once the hook is removed, rejected proofs are no longer reported
+    assert!(crate::take_rejection_hook().is_some());
+    assert!(crate::verify::<FibAir>(proof, invalid_inputs).is_err());
+    assert_eq!(1, count_records(Some(invalid_digest)));
+}
@@ -3,9 +3,14 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{compose_constraints, evaluate_constraints, VerifierChannel};
-use common::CompositionCoefficients;
-use common::{errors::VerifierError, Air, EvaluationFrame, PublicCoin};
+use super::{
+    compose_constraints,
+    scratch::{self, ScratchBuffers},
+    VerifierChannel,
+};
+use common::{
+    errors::VerifierError, evaluate_constraints, Air, DeepComposer, EvaluationFrame, PublicCoin,
+};
 use crypto::Hasher;
 use fri::VerifierChannel as FriVerifierChannel;
 use math::field::{FieldElement, StarkField};
@@ -13,36 +18,103 @@ use math::field::{FieldElement, StarkField};
 // VERIFICATION PROCEDURE
 // ================================================================================================
 
-pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>(
-    air: A,
-    channel: VerifierChannel<A::BaseElement, E, H>,
-) -> Result<(), VerifierError> {
+pub fn perform_verification<A, E, H, T>(
+    mut air: A,
+    channel: VerifierChannel<A::BaseElement, E, H, T>,
+    scratch: &mut ScratchBuffers<A::BaseElement, E>,
+) -> Result<(), VerifierError>
+where
+    A: Air,
+    E: FieldElement + From<A::BaseElement>,
+    H: Hasher,
+    T: Hasher,
+{
     // 1 ----- Compute constraint evaluations at OOD point z ----------------------------------
 
+    // pass random elements from which the auxiliary trace segment was built to the AIR; this
+    // must be done before any constraints are evaluated
+    if air.context().aux_segment_width() > 0 {
+        let rand_elements = channel.draw_aux_rand_elements::<A::BaseElement>();
+        air.set_aux_rand_elements(&rand_elements);
+    }
+
     // draw a pseudo-random out-of-domain point for DEEP composition
     let z = channel.draw_deep_point::<E>();
 
     // evaluate constraints at z
-    let ood_frame = channel.read_ood_frame()?;
-    let constraint_evaluation_at_z = evaluate_constraints(&air, &channel, &ood_frame, z);
+    channel.read_ood_frame_into(&mut scratch.ood_frame)?;
+    let constraint_evaluation_at_z = evaluate_constraints(
+        &air,
+        &channel,
+        &scratch.ood_frame,
+        z,
+        &mut scratch.periodic_values,
+        &mut scratch.t_evaluations,
+    );
 
     // 2 ----- Read queried trace states and constraint evaluations ---------------------------
 
-    // draw pseudo-random query positions
-    let query_positions = channel.draw_query_positions();
+    // draw pseudo-random query positions; FRI layers are queried at all of these positions,
+    // while trace and constraint commitments are queried only at the first num_queries of them
+    channel.draw_fri_query_positions_into(&mut scratch.query_positions);
+    let fri_query_positions = &scratch.query_positions;
+    let query_positions = &fri_query_positions[..air.context().options().num_queries()];
 
     // compute LDE domain coordinates for all query positions
     let g_lde = air.context().get_lde_domain_generator::<A::BaseElement>();
     let domain_offset = air.context().domain_offset::<A::BaseElement>();
-    let x_coordinates: Vec<A::BaseElement> = query_positions
-        .iter()
-        .map(|&p| g_lde.exp((p as u64).into()) * domain_offset)
-        .collect();
+    scratch.x_coordinates.clear();
+    scratch.x_coordinates.extend(
+        query_positions
+            .iter()
+            .map(|&p| g_lde.exp((p as u64).into()) * domain_offset),
+    );
 
     // read trace states and constraint evaluations at the queried positions; this also
     // checks that Merkle authentication paths for the states and evaluations are valid
-    let trace_states = channel.read_trace_states(&query_positions)?;
-    let constraint_evaluations = channel.read_constraint_evaluations(&query_positions)?;
+    #[cfg(not(feature = "concurrent"))]
+    {
+        channel.read_trace_states_into(
+            query_positions,
+            &mut scratch.trace_positions,
+            &mut scratch.trace_states,
+        )?;
+        channel.read_constraint_evaluations_into(
+            query_positions,
+            &mut scratch.constraint_positions,
+            &mut scratch.constraint_evaluations,
+        )?;
+    }
+
+    // when `concurrent` feature is enabled, trace and constraint commitments are checked in
+    // parallel; if both checks fail, the error for the trace commitment is returned
+    #[cfg(feature = "concurrent")]
+    {
+        let trace_positions = &mut scratch.trace_positions;
+        let trace_states = &mut scratch.trace_states;
+        let constraint_positions = &mut scratch.constraint_positions;
+        let constraint_evaluations = &mut scratch.constraint_evaluations;
+        let (trace_result, constraint_result) = rayon::join(
+            || channel.read_trace_states_into(query_positions, trace_positions, trace_states),
+            || {
+                channel.read_constraint_evaluations_into(
+                    query_positions,
+                    constraint_positions,
+                    constraint_evaluations,
+                )
+            },
+        );
+        trace_result.and(constraint_result)?;
+    }
+
+    // read evaluations of extra polynomials at z and at the queried positions; this is a no-op
+    // when the computation declares no extra polynomials
+    channel.read_ood_extra_into(&mut scratch.ood_extra)?;
+    channel.read_extra_states_into(
+        query_positions,
+        &mut scratch.extra_positions,
+        &mut scratch.extra_states,
+    )?;
 
     // 3 ----- Compute composition polynomial evaluations -------------------------------------
 
@@ -50,33 +122,40 @@ pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: H
     // polynomials; the result of this linear combination are evaluations of deep composition
     // polynomial
     let coefficients = channel.draw_composition_coefficients();
+    let composer = DeepComposer::new::<A::BaseElement>(air.context(), z, coefficients);
 
     // compute composition of trace registers
-    let t_composition = compose_registers(
-        &air,
-        &trace_states,
-        &x_coordinates,
-        &ood_frame,
-        z,
-        &coefficients,
+    scratch::reset(&mut scratch.evaluations, query_positions.len());
+    compose_registers(
+        &composer,
+        &scratch.trace_states,
+        &scratch.x_coordinates,
+        &scratch.ood_frame,
+        &mut scratch.ood_conjugates,
+        &mut scratch.evaluations,
     );
 
-    // compute composition of constraints
-    let c_composition = compose_constraints(
-        constraint_evaluations,
-        &x_coordinates,
-        z,
+    // compute composition of extra polynomials and add it to the composition of trace registers
+    if !scratch.ood_extra.is_empty() {
+        compose_extra_polys(
+            &composer,
+            &scratch.extra_states,
+            &scratch.x_coordinates,
+            &scratch.ood_extra,
+            &mut scratch.ood_extra_conjugates,
+            &mut scratch.evaluations,
+        );
+    }
+
+    // compute composition of constraints and add it to the composition of trace registers
+    compose_constraints(
+        &composer,
+        &scratch.constraint_evaluations,
+        &scratch.x_coordinates,
         constraint_evaluation_at_z,
-        &coefficients,
+        &mut scratch.evaluations,
     );
 
-    // add the two together
-    let evaluations = t_composition
-        .iter()
-        .zip(c_composition)
-        .map(|(&t, c)| t + c)
-        .collect::<Vec<_>>();
-
     // 4 ----- Verify low-degree proof -------------------------------------------------------------
     // make sure that evaluations we computed in the previous step are in fact evaluations
     // of a polynomial of degree equal to context.deep_composition_degree()
@@ -85,84 +164,62 @@ pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: H
         air.context().composition_degree(),
         channel.num_fri_partitions(),
         air.context().options().to_fri_options::<A::BaseElement>(),
-    );
-    fri::verify(&fri_context, &channel, &evaluations, &query_positions)
-        .map_err(VerifierError::FriVerificationFailed)
+    )
+    .map_err(VerifierError::FriVerificationFailed)?;
+    fri::verify(
+        &fri_context,
+        &channel,
+        &scratch.evaluations,
+        fri_query_positions,
+    )
+    .map_err(VerifierError::FriVerificationFailed)
 }
 
 // TRACE COMPOSITION
 // ================================================================================================
 
-/// TODO: add comments
-fn compose_registers<B: StarkField, E: FieldElement + From<B>, A: Air<BaseElement = B>>(
-    air: &A,
-    trace_states: &[Vec<B>],
+/// Computes a random linear combination of DEEP quotients of trace registers at the queried
+/// positions, and writes the result into the `result` slice; `trace_states` contains queried
+/// trace states stored one after the other, and `conjugates` is used as a buffer for conjugates
+/// of the OOD trace state when field extension is enabled.
+fn compose_registers<B: StarkField, E: FieldElement + From<B>>(
+    composer: &DeepComposer<E>,
+    trace_states: &[B],
     x_coordinates: &[B],
     ood_frame: &EvaluationFrame<E>,
-    z: E,
-    cc: &CompositionCoefficients<E>,
-) -> Vec<E> {
-    let next_z = z * E::from(air.trace_domain_generator());
-
-    let trace_at_z1 = &ood_frame.current;
-    let trace_at_z2 = &ood_frame.next;
-
-    // TODO: this is computed in several paces; consolidate
-    let composition_degree = air.context().deep_composition_degree();
-    let incremental_degree = (composition_degree - (air.trace_length() - 2)) as u32;
-
-    // when field extension is enabled, these will be set to conjugates of trace values at
-    // z as well as conjugate of z itself
-    let conjugate_values = get_conjugate_values(air, trace_at_z1, z);
-
-    let mut result = Vec::with_capacity(trace_states.len());
-    for (registers, &x) in trace_states.iter().zip(x_coordinates) {
-        let x = E::from(x);
-        let mut composition = E::ZERO;
-        for (i, &value) in registers.iter().enumerate() {
-            let value = E::from(value);
-            // compute T1(x) = (T(x) - T(z)) / (x - z)
-            let t1 = (value - trace_at_z1[i]) / (x - z);
-            // multiply it by a pseudo-random coefficient, and combine with result
-            composition += t1 * cc.trace[i].0;
-
-            // compute T2(x) = (T(x) - T(z * g)) / (x - z * g)
-            let t2 = (value - trace_at_z2[i]) / (x - next_z);
-            // multiply it by a pseudo-random coefficient, and combine with result
-            composition += t2 * cc.trace[i].1;
-
-            // compute T3(x) = (T(x) - T(z_conjugate)) / (x - z_conjugate)
-            // when extension field is enabled, this constraint is needed in order to verify
-            // that the trace is defined over the base field, rather than the extension field
-            if let Some((z_conjugate, ref trace_at_z1_conjugates)) = conjugate_values {
-                let t3 = (value - trace_at_z1_conjugates[i]) / (x - z_conjugate);
-                composition += t3 * cc.trace[i].2;
-            }
-        }
-
-        // raise the degree to match composition degree
-        let xp = x.exp(incremental_degree.into());
-        composition *= cc.trace_degree.0 + xp * cc.trace_degree.1;
-
-        result.push(composition);
+    conjugates: &mut Vec<E>,
+    result: &mut [E],
+) {
+    composer.conjugate_trace_state_into(&ood_frame.current, conjugates);
+
+    let trace_width = ood_frame.current.len();
+    for ((state, &x), result) in trace_states
+        .chunks(trace_width)
+        .zip(x_coordinates)
+        .zip(result.iter_mut())
+    {
+        *result = composer.compose_trace_state(state, E::from(x), ood_frame, conjugates);
     }
-
-    result
 }
 
-/// When field extension is used, returns conjugate values of the `trace_state` and `z`;
-/// otherwise, returns None.
-fn get_conjugate_values<A: Air, E: FieldElement + From<A::BaseElement>>(
-    air: &A,
-    trace_state: &[E],
-    z: E,
-) -> Option<(E, Vec<E>)> {
-    if air.context().options().field_extension().is_none() {
-        None
-    } else {
-        Some((
-            z.conjugate(),
-            trace_state.iter().map(|v| v.conjugate()).collect(),
-        ))
+/// Computes a random linear combination of DEEP quotients of extra polynomials at the queried
+/// positions, and adds the result to the values in the `result` slice; `extra_states` contains
+/// queried evaluations of extra polynomials stored one row after the other.
+fn compose_extra_polys<B: StarkField, E: FieldElement + From<B>>(
+    composer: &DeepComposer<E>,
+    extra_states: &[B],
+    x_coordinates: &[B],
+    extra_at_z: &[E],
+    conjugates: &mut Vec<E>,
+    result: &mut [E],
+) {
+    composer.conjugate_trace_state_into(extra_at_z, conjugates);
+
+    for ((state, &x), result) in extra_states
+        .chunks(extra_at_z.len())
+        .zip(x_coordinates)
+        .zip(result.iter_mut())
+    {
+        *result += composer.compose_extra_state(state, E::from(x), extra_at_z, conjugates);
     }
 }